@@ -0,0 +1,124 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use egui::{Color32, Stroke};
+use lazy_static::lazy_static;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "accessibility_options.json";
+pub const DEFAULT_MIN_FONT_SIZE: f32 = 12.0;
+// 設定頁面可調整的字體下限範圍
+pub const MIN_FONT_SIZE_RANGE: (f32, f32) = (10.0, 24.0);
+pub const DEFAULT_ANIMATION_MS: u32 = 200;
+pub const ANIMATION_MS_RANGE: (u32, u32) = (50, 600);
+
+fn default_min_font_size() -> f32 {
+    DEFAULT_MIN_FONT_SIZE
+}
+
+fn default_animation_ms() -> u32 {
+    DEFAULT_ANIMATION_MS
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AccessibilityOptions {
+    #[serde(default)]
+    pub high_contrast: bool,
+    // 所有文字樣式都不會小於此大小
+    #[serde(default = "default_min_font_size")]
+    pub min_font_size: f32,
+    // 關閉所有展開、側邊選單與滑過動畫，適合效能較低的電腦
+    #[serde(default)]
+    pub reduced_motion: bool,
+    #[serde(default = "default_animation_ms")]
+    pub animation_ms: u32,
+}
+
+impl Default for AccessibilityOptions {
+    fn default() -> Self {
+        Self {
+            high_contrast: false,
+            min_font_size: DEFAULT_MIN_FONT_SIZE,
+            reduced_motion: false,
+            animation_ms: DEFAULT_ANIMATION_MS,
+        }
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<AccessibilityOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> AccessibilityOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn accessibility_options() -> AccessibilityOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_accessibility_options(options: AccessibilityOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存無障礙選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 純色背景、粗邊框與不透明文字，方便低視力使用者辨識
+fn high_contrast_visuals(dark_mode: bool) -> egui::Visuals {
+    let (mut visuals, foreground, background) = if dark_mode {
+        (egui::Visuals::dark(), Color32::WHITE, Color32::BLACK)
+    } else {
+        (egui::Visuals::light(), Color32::BLACK, Color32::WHITE)
+    };
+    visuals.override_text_color = Some(foreground);
+    visuals.panel_fill = background;
+    visuals.window_fill = background;
+    visuals.extreme_bg_color = background;
+    visuals.faint_bg_color = background;
+    visuals.window_stroke = Stroke::new(2.0, foreground);
+    visuals.hyperlink_color = if dark_mode {
+        Color32::from_rgb(0x66, 0xCC, 0xFF)
+    } else {
+        Color32::from_rgb(0x00, 0x33, 0xCC)
+    };
+    visuals.selection.bg_fill = if dark_mode {
+        Color32::from_rgb(0x00, 0x46, 0xA0)
+    } else {
+        Color32::from_rgb(0xFF, 0xD7, 0x00)
+    };
+    visuals.selection.stroke = Stroke::new(2.0, foreground);
+    visuals.widgets.noninteractive.bg_fill = background;
+    for widget in [
+        &mut visuals.widgets.noninteractive,
+        &mut visuals.widgets.inactive,
+        &mut visuals.widgets.hovered,
+        &mut visuals.widgets.active,
+        &mut visuals.widgets.open,
+    ] {
+        widget.fg_stroke = Stroke::new(widget.fg_stroke.width.max(1.5), foreground);
+        widget.bg_stroke = Stroke::new(widget.bg_stroke.width.max(1.5), foreground);
+    }
+    visuals
+}
+
+// 依系統深淺色與高對比設定套用主題
+pub fn apply_theme(ctx: &egui::Context, options: &AccessibilityOptions) {
+    let dark_mode = dark_light::detect() == dark_light::Mode::Dark;
+    ctx.set_visuals(match (options.high_contrast, dark_mode) {
+        (true, _) => high_contrast_visuals(dark_mode),
+        (false, true) => egui::Visuals::dark(),
+        (false, false) => egui::Visuals::light(),
+    });
+}
+
+// 只有圖示的按鈕沒有文字，需另外提供螢幕閱讀器讀出的名稱
+pub fn label_icon_button(response: &egui::Response, label: &str) {
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, label));
+}
@@ -0,0 +1,48 @@
+// 本地模組導入
+use crate::accessibility::{accessibility_options, AccessibilityOptions};
+
+// 動畫結尾減速，比線性動畫自然
+pub fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t.clamp(0.0, 1.0)).powi(3)
+}
+
+fn animation_secs(options: &AccessibilityOptions) -> f32 {
+    options.animation_ms as f32 / 1000.0
+}
+
+// egui 內建的面板與折疊動畫也跟著設定調整
+pub fn apply_motion(ctx: &egui::Context, options: &AccessibilityOptions) {
+    let animation_time = if options.reduced_motion {
+        0.0
+    } else {
+        animation_secs(options)
+    };
+    ctx.style_mut(|style| style.animation_time = animation_time);
+}
+
+// 展開 / 收起的進度，0 為收起、1 為完全展開，動畫期間會自動要求重繪
+pub fn animate_open(ctx: &egui::Context, id: egui::Id, open: bool) -> f32 {
+    let options = accessibility_options();
+    if options.reduced_motion {
+        return if open { 1.0 } else { 0.0 };
+    }
+    ease_out_cubic(ctx.animate_bool_with_time(id, open, animation_secs(&options)))
+}
+
+// 滑過效果的進度，每個畫面依經過的時間往目標靠近，尚未到達時要求重繪
+pub fn step_hover(ui: &egui::Ui, progress: f32, hovered: bool) -> f32 {
+    let options = accessibility_options();
+    if options.reduced_motion {
+        return if hovered { 1.0 } else { 0.0 };
+    }
+    let step = ui.input(|i| i.unstable_dt) / animation_secs(&options);
+    let progress = if hovered {
+        (progress + step).min(1.0)
+    } else {
+        (progress - step).max(0.0)
+    };
+    if progress > 0.0 && progress < 1.0 {
+        ui.ctx().request_repaint();
+    }
+    progress
+}
@@ -0,0 +1,305 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex, RwLock};
+
+// 第三方庫導入
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use lazy_static::lazy_static;
+use log::{error, info};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+// 本地模組導入
+use crate::osu::{get_beatmapsets, get_osu_token};
+use crate::overlay::{overlay_html, OverlayMatcher};
+use crate::plugin_actions::beatmapset_payload;
+use crate::spotify::CurrentlyPlaying;
+use crate::updater::CURRENT_VERSION;
+use crate::DownloadStatus;
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "api_server.json";
+pub const DEFAULT_PORT: u16 = 47821;
+const TOKEN_LENGTH: usize = 32;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ApiServerOptions {
+    pub enabled: bool,
+    pub port: u16,
+    // 請求需要帶上 Authorization: Bearer <token> 或 ?token=
+    pub token: String,
+    // 監聽所有網路介面，讓同一個區網的手機也能連線
+    #[serde(default)]
+    pub allow_lan: bool,
+}
+
+impl Default for ApiServerOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: generate_token(),
+            allow_lan: false,
+        }
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<ApiServerOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> ApiServerOptions {
+    match load_config::<ApiServerOptions>(OPTIONS_FILE) {
+        Some(options) if !options.token.is_empty() => options,
+        loaded => {
+            // 第一次使用時產生 token 並保存，重新啟動後仍可使用同一個 token
+            let options = ApiServerOptions {
+                token: generate_token(),
+                ..loaded.unwrap_or_default()
+            };
+            if let Err(e) = save_config(OPTIONS_FILE, &options) {
+                error!("保存遠端控制選項失敗: {:?}", e);
+            }
+            options
+        }
+    }
+}
+
+pub fn api_server_options() -> ApiServerOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_api_server_options(options: ApiServerOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存遠端控制選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+// 從 HTTP 請求轉送給主畫面處理的操作
+#[derive(Clone, Debug, PartialEq)]
+pub enum RemoteCommand {
+    Search(String),
+    Download(i32),
+}
+
+#[derive(Clone)]
+struct ServerState {
+    token: Arc<str>,
+    sender: UnboundedSender<RemoteCommand>,
+    download_statuses: Arc<Mutex<HashMap<i32, DownloadStatus>>>,
+    currently_playing: Arc<Mutex<Option<CurrentlyPlaying>>>,
+    overlay: Arc<OverlayMatcher>,
+    client: Client,
+    ctx: egui::Context,
+}
+
+impl ServerState {
+    fn send(&self, command: RemoteCommand) {
+        if self.sender.send(command).is_ok() {
+            self.ctx.request_repaint();
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+fn status_label(status: DownloadStatus) -> &'static str {
+    match status {
+        DownloadStatus::NotStarted => "not_started",
+        DownloadStatus::Waiting => "waiting",
+        DownloadStatus::Downloading => "downloading",
+        DownloadStatus::Completed => "completed",
+    }
+}
+
+async fn require_token(State(state): State<ServerState>, request: Request, next: Next) -> Response {
+    let header_token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query_token = request.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    });
+    if header_token.or(query_token) == Some(&*state.token) {
+        next.run(request).await
+    } else {
+        error_response(StatusCode::UNAUTHORIZED, "token 錯誤")
+    }
+}
+
+// 在畫面上執行搜尋，同時回傳 osu! 搜尋結果給呼叫端
+async fn search(State(state): State<ServerState>, Query(params): Query<SearchParams>) -> Response {
+    let query = params.q.trim().to_string();
+    if query.is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "缺少 q 參數");
+    }
+    state.send(RemoteCommand::Search(query.clone()));
+
+    let result = match get_osu_token(&state.client, false).await {
+        Ok(token) => get_beatmapsets(&state.client, &token, &query, false).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(beatmapsets) => Json(json!({
+            "query": query,
+            "beatmapsets": beatmapsets.iter().map(beatmapset_payload).collect::<Vec<_>>(),
+        }))
+        .into_response(),
+        Err(e) => error_response(StatusCode::BAD_GATEWAY, &e.to_string()),
+    }
+}
+
+async fn download(State(state): State<ServerState>, Path(beatmapset_id): Path<i32>) -> Response {
+    if beatmapset_id <= 0 {
+        return error_response(StatusCode::BAD_REQUEST, "譜面集 ID 無效");
+    }
+    state.send(RemoteCommand::Download(beatmapset_id));
+    (
+        StatusCode::ACCEPTED,
+        Json(json!({ "beatmapset_id": beatmapset_id, "status": "queued" })),
+    )
+        .into_response()
+}
+
+async fn status(State(state): State<ServerState>) -> Response {
+    let downloads: Vec<_> = state
+        .download_statuses
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, status)| **status != DownloadStatus::NotStarted)
+        .map(|(id, status)| json!({ "beatmapset_id": id, "status": status_label(*status) }))
+        .collect();
+    Json(json!({
+        "version": CURRENT_VERSION,
+        "downloads": downloads,
+    }))
+    .into_response()
+}
+
+// 直播用的正在播放疊加層，作為 OBS 的瀏覽器來源
+async fn overlay() -> Html<String> {
+    Html(overlay_html())
+}
+
+async fn overlay_json(State(state): State<ServerState>) -> Response {
+    let playing = state.currently_playing.lock().unwrap().clone();
+    Json(state.overlay.payload(playing).await).into_response()
+}
+
+// 本機 HTTP 伺服器，讓 StreamDeck、手機等外部工具觸發搜尋與下載，釋放時停止
+pub struct ApiServer {
+    task: JoinHandle<()>,
+    receiver: UnboundedReceiver<RemoteCommand>,
+    address: SocketAddr,
+}
+
+impl ApiServer {
+    pub fn start(
+        ctx: &egui::Context,
+        download_statuses: Arc<Mutex<HashMap<i32, DownloadStatus>>>,
+        currently_playing: Arc<Mutex<Option<CurrentlyPlaying>>>,
+    ) -> Self {
+        let options = api_server_options();
+        let host = if options.allow_lan {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+        let address = SocketAddr::from((host, options.port));
+        let (sender, receiver) = unbounded_channel();
+        let state = ServerState {
+            token: options.token.into(),
+            sender,
+            download_statuses,
+            currently_playing,
+            overlay: Arc::new(OverlayMatcher::new()),
+            client: Client::new(),
+            ctx: ctx.clone(),
+        };
+
+        let app = Router::new()
+            .route("/search", get(search))
+            .route("/download/:beatmapset_id", get(download).post(download))
+            .route("/status", get(status))
+            .route("/overlay", get(overlay))
+            .route("/overlay.json", get(overlay_json))
+            .layer(middleware::from_fn_with_state(state.clone(), require_token))
+            .with_state(state);
+
+        let task = tokio::spawn(async move {
+            let listener = match TcpListener::bind(address).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("遠端控制伺服器無法監聽 {}: {:?}", address, e);
+                    return;
+                }
+            };
+            info!("遠端控制伺服器已啟動: http://{}", address);
+            if let Err(e) = axum::serve(listener, app).await {
+                error!("遠端控制伺服器錯誤: {:?}", e);
+            }
+        });
+
+        Self {
+            task,
+            receiver,
+            address,
+        }
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    // 監聽失敗或伺服器錯誤時任務會提早結束
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    pub fn poll(&mut self) -> Vec<RemoteCommand> {
+        let mut commands = Vec::new();
+        while let Ok(command) = self.receiver.try_recv() {
+            commands.push(command);
+        }
+        commands
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
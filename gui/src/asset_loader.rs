@@ -0,0 +1,135 @@
+// 標準庫導入
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+
+// 第三方庫導入
+use anyhow::Result;
+use egui::TextureHandle;
+use log::{error, info};
+
+// 圖示先載入，載入完成前顯示啟動畫面
+pub const ICON_PATHS: [&str; 15] = [
+    "spotify_icon_black.png",
+    "osu!logo.png",
+    "Spotify_Full_Logo_RGB_White.png",
+    "Spotify_Full_Logo_RGB_Black.png",
+    "osu!logo@2x.png",
+    "search.png",
+    "like.png",
+    "liked.png",
+    "expand_on.png",
+    "expand_off.png",
+    "play.png",
+    "pause.png",
+    "download.png",
+    "delete.png",
+    "downloading.png",
+];
+// 預設背景較大，放在圖示之後解碼，載入前以面板底色代替
+pub const BACKGROUND_PATHS: [&str; 2] = ["background1.jpg", "background_light2.jpg"];
+
+fn icon_bytes(icon_path: &str) -> Option<&'static [u8]> {
+    let bytes: &[u8] = match icon_path {
+        "spotify_icon_black.png" => include_bytes!("assets/spotify_icon_black.png"),
+        "osu!logo.png" => include_bytes!("assets/osu!logo.png"),
+        "Spotify_Full_Logo_RGB_White.png" => {
+            include_bytes!("assets/Spotify_Full_Logo_RGB_White.png")
+        }
+        "Spotify_Full_Logo_RGB_Black.png" => {
+            include_bytes!("assets/Spotify_Full_Logo_RGB_Black.png")
+        }
+        "osu!logo@2x.png" => include_bytes!("assets/osu!logo@2x.png"),
+        "search.png" => include_bytes!("assets/search.png"),
+        "like.png" => include_bytes!("assets/like.png"),
+        "liked.png" => include_bytes!("assets/liked.png"),
+        "expand_on.png" => include_bytes!("assets/expand_on.png"),
+        "expand_off.png" => include_bytes!("assets/expand_off.png"),
+        "play.png" => include_bytes!("assets/play.png"),
+        "pause.png" => include_bytes!("assets/pause.png"),
+        "download.png" => include_bytes!("assets/download.png"),
+        "delete.png" => include_bytes!("assets/delete.png"),
+        "downloading.png" => include_bytes!("assets/downloading.png"),
+        "background1.jpg" => include_bytes!("assets/background1.jpg"),
+        "background_light2.jpg" => include_bytes!("assets/background_light2.jpg"),
+        _ => {
+            error!("未知的圖標路徑: {}", icon_path);
+            return None;
+        }
+    };
+    Some(bytes)
+}
+
+fn to_texture(ctx: &egui::Context, name: &str, image: image::DynamicImage) -> TextureHandle {
+    let image = image.to_rgba8();
+    let size = [image.width() as _, image.height() as _];
+    let pixels = image.as_flat_samples();
+    let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
+    ctx.load_texture(name, color_image, egui::TextureOptions::default())
+}
+
+// 解碼內嵌的圖示並上傳為紋理
+pub fn load_icon(ctx: &egui::Context, icon_path: &str) -> Option<TextureHandle> {
+    let bytes = icon_bytes(icon_path)?;
+    match image::load_from_memory(bytes) {
+        Ok(image) => Some(to_texture(ctx, icon_path, image)),
+        Err(e) => {
+            error!("無法加載圖標 {}: {:?}", icon_path, e);
+            None
+        }
+    }
+}
+
+// 從檔案解碼圖片，用於自定義背景
+pub fn load_image_file(ctx: &egui::Context, name: &str, path: &Path) -> Result<TextureHandle> {
+    let image = image::ImageReader::open(path)?.decode()?;
+    Ok(to_texture(ctx, name, image))
+}
+
+// 在背景執行緒依序解碼內嵌圖示與預設背景，讓第一幀不必等待解碼
+pub struct IconLoader {
+    receiver: Receiver<(String, Option<TextureHandle>)>,
+    // 已處理（成功或失敗）的數量
+    loaded: usize,
+}
+
+impl IconLoader {
+    pub fn start(ctx: &egui::Context) -> Self {
+        let (sender, receiver) = channel();
+        let ctx = ctx.clone();
+        tokio::task::spawn_blocking(move || {
+            for path in ICON_PATHS.iter().chain(BACKGROUND_PATHS.iter()) {
+                let texture = load_icon(&ctx, path);
+                if sender.send((path.to_string(), texture)).is_err() {
+                    return;
+                }
+                ctx.request_repaint();
+            }
+            info!("圖示與背景載入完成");
+        });
+        Self {
+            receiver,
+            loaded: 0,
+        }
+    }
+
+    // 取出這段期間載入完成的紋理
+    pub fn poll(&mut self) -> Vec<(String, TextureHandle)> {
+        let mut textures = Vec::new();
+        for (path, texture) in self.receiver.try_iter() {
+            self.loaded += 1;
+            if let Some(texture) = texture {
+                textures.push((path, texture));
+            }
+        }
+        textures
+    }
+
+    pub fn icons_ready(&self) -> bool {
+        self.loaded >= ICON_PATHS.len()
+    }
+
+    // 啟動畫面的進度，只計算圖示
+    pub fn progress(&self) -> f32 {
+        self.loaded.min(ICON_PATHS.len()) as f32 / ICON_PATHS.len() as f32
+    }
+}
@@ -0,0 +1,311 @@
+// 標準庫導入
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+
+// 第三方庫導入
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use reqwest::Client;
+use rspotify::model::FullTrack;
+use rspotify::prelude::Id;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+// 本地模組導入
+use crate::download_options::low_disk_space;
+use crate::match_memory::{confirmed_beatmapset, is_rejected};
+use crate::matcher::{match_options, rank_beatmapsets};
+use crate::osu::{get_osu_token, is_beatmap_downloaded};
+use crate::query_normalizer::search_beatmapsets_normalized;
+use crate::review_queue::{add_review_item, ReviewCandidate, ReviewItem, ReviewTrack};
+use crate::spotify::PlaylistEntry;
+use crate::DownloadStatus;
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "auto_download.json";
+const ACTIVITY_FILE: &str = "auto_download_activity.json";
+// 保留最近的活動紀錄筆數
+const MAX_ACTIVITY: usize = 200;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AutoDownloadOptions {
+    // 標記為自動下載的播放清單 ID 與名稱
+    #[serde(default)]
+    pub playlists: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AutoDownloadActivity {
+    pub time: DateTime<Local>,
+    pub playlist: String,
+    pub message: String,
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<AutoDownloadOptions> = RwLock::new(load_options());
+    static ref ACTIVITY: Mutex<VecDeque<AutoDownloadActivity>> =
+        Mutex::new(load_config(ACTIVITY_FILE).unwrap_or_default());
+}
+
+fn load_options() -> AutoDownloadOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn auto_download_options() -> AutoDownloadOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_auto_download_options(options: AutoDownloadOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存自動下載選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+pub fn is_auto_download(playlist_id: &str) -> bool {
+    OPTIONS.read().unwrap().playlists.contains_key(playlist_id)
+}
+
+pub fn set_auto_download(playlist_id: &str, playlist_name: &str, enabled: bool) {
+    let mut options = auto_download_options();
+    if enabled {
+        options
+            .playlists
+            .insert(playlist_id.to_string(), playlist_name.to_string());
+    } else {
+        options.playlists.remove(playlist_id);
+    }
+    info!(
+        "播放清單 {} 自動下載: {}",
+        playlist_name,
+        if enabled { "開啟" } else { "關閉" }
+    );
+    set_auto_download_options(options);
+}
+
+fn log_activity(playlist: &str, message: String) {
+    info!("自動下載 [{}] {}", playlist, message);
+    let mut activity = ACTIVITY.lock().unwrap();
+    if activity.len() >= MAX_ACTIVITY {
+        activity.pop_front();
+    }
+    activity.push_back(AutoDownloadActivity {
+        time: Local::now(),
+        playlist: playlist.to_string(),
+        message,
+    });
+    if let Err(e) = save_config(ACTIVITY_FILE, &*activity) {
+        error!("保存自動下載紀錄失敗: {:?}", e);
+    }
+}
+
+// 由新到舊排列
+pub fn auto_download_activity() -> Vec<AutoDownloadActivity> {
+    ACTIVITY.lock().unwrap().iter().rev().cloned().collect()
+}
+
+pub fn clear_auto_download_activity() {
+    let mut activity = ACTIVITY.lock().unwrap();
+    activity.clear();
+    if let Err(e) = save_config(ACTIVITY_FILE, &*activity) {
+        error!("保存自動下載紀錄失敗: {:?}", e);
+    }
+}
+
+// 比對快取與最新的曲目，只回傳新加入的曲目，無法取得的項目不處理
+pub fn new_tracks(previous: &[PlaylistEntry], current: &[PlaylistEntry]) -> Vec<FullTrack> {
+    let known: HashSet<String> = previous
+        .iter()
+        .filter_map(PlaylistEntry::track)
+        .filter_map(|track| track.id.as_ref().map(|id| id.id().to_string()))
+        .collect();
+    current
+        .iter()
+        .filter_map(PlaylistEntry::track)
+        .filter(|track| match &track.id {
+            Some(id) => !known.contains(id.id()),
+            None => false,
+        })
+        .cloned()
+        .collect()
+}
+
+// 下載相關的共用狀態，與手動下載使用同一個隊列
+pub struct AutoDownloadTarget {
+    pub download_directory: PathBuf,
+    pub download_queue_sender: mpsc::UnboundedSender<i32>,
+    pub download_statuses: Arc<Mutex<HashMap<i32, DownloadStatus>>>,
+}
+
+// 為播放清單新加入的曲目配對譜面並送入下載隊列，沒有足夠可信的譜面時只記錄不下載
+pub async fn auto_download_new_tracks(
+    client: Client,
+    playlist_id: String,
+    tracks: Vec<FullTrack>,
+    target: AutoDownloadTarget,
+    debug_mode: bool,
+) {
+    let playlist = OPTIONS
+        .read()
+        .unwrap()
+        .playlists
+        .get(&playlist_id)
+        .cloned()
+        .unwrap_or_else(|| playlist_id.clone());
+    log_activity(&playlist, format!("偵測到 {} 首新曲目", tracks.len()));
+    download_tracks(client, playlist, tracks, target, debug_mode).await;
+}
+
+// 使用者在播放清單頁面手動下載尚未有譜面的曲目，流程與自動下載相同
+pub async fn download_playlist_tracks(
+    client: Client,
+    playlist_name: String,
+    tracks: Vec<FullTrack>,
+    target: AutoDownloadTarget,
+    debug_mode: bool,
+) {
+    log_activity(
+        &playlist_name,
+        format!("開始下載 {} 首尚未下載譜面的曲目", tracks.len()),
+    );
+    download_tracks(client, playlist_name, tracks, target, debug_mode).await;
+}
+
+async fn download_tracks(
+    client: Client,
+    playlist: String,
+    tracks: Vec<FullTrack>,
+    target: AutoDownloadTarget,
+    debug_mode: bool,
+) {
+    let osu_token = match get_osu_token(&client, debug_mode).await {
+        Ok(token) => token,
+        Err(e) => {
+            error!("自動下載無法取得 osu! token: {:?}", e);
+            log_activity(
+                &playlist,
+                "無法取得 osu! token，略過本次自動下載".to_string(),
+            );
+            return;
+        }
+    };
+
+    let min_score = match_options().auto_min_score;
+    let mut queued = 0;
+    for track in &tracks {
+        let artist = track
+            .artists
+            .iter()
+            .map(|artist| artist.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let label = format!("{} - {}", artist, track.name);
+
+        // 使用者確認過的配對直接使用
+        let remembered = track
+            .id
+            .as_ref()
+            .and_then(|id| confirmed_beatmapset(id.id()));
+        let beatmapset_id = match remembered {
+            Some(beatmapset_id) => beatmapset_id,
+            None => {
+                let beatmapsets = match search_beatmapsets_normalized(
+                    &client,
+                    &osu_token,
+                    &artist,
+                    &track.name,
+                    debug_mode,
+                )
+                .await
+                {
+                    Ok(beatmapsets) => beatmapsets,
+                    Err(e) => {
+                        error!("自動下載搜尋 {} 失敗: {:?}", label, e);
+                        log_activity(&playlist, format!("{}：搜尋失敗", label));
+                        continue;
+                    }
+                };
+                let review_track = ReviewTrack {
+                    spotify_track_id: track.id.as_ref().map(|id| id.id().to_string()),
+                    artist: artist.clone(),
+                    title: track.name.clone(),
+                    duration_ms: Some(track.duration.num_milliseconds().max(0) as u64),
+                    preview_url: track.preview_url.clone(),
+                };
+                let rejected_key = review_track.key();
+                // 使用者拒絕過的譜面不再列入候選
+                match rank_beatmapsets(&artist, &track.name, review_track.duration_ms, beatmapsets)
+                    .into_iter()
+                    .find(|scored| !is_rejected(&rejected_key, scored.beatmapset.id))
+                {
+                    Some(best) if best.score >= min_score => best.beatmapset.id,
+                    Some(best) => {
+                        // 低於門檻的配對不下載，交給使用者確認
+                        add_review_item(ReviewItem {
+                            track: review_track,
+                            candidate: ReviewCandidate::from(&best),
+                            score: best.score,
+                            source: playlist.clone(),
+                            added_at: Local::now(),
+                        });
+                        log_activity(
+                            &playlist,
+                            format!(
+                                "{}：最佳譜面 {} 只有 {:.0}%，已加入需要確認",
+                                label,
+                                best.beatmapset.id,
+                                best.score * 100.0
+                            ),
+                        );
+                        continue;
+                    }
+                    None => {
+                        log_activity(&playlist, format!("{}：找不到譜面", label));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if is_beatmap_downloaded(&target.download_directory, beatmapset_id) {
+            log_activity(
+                &playlist,
+                format!("{}：譜面 {} 已下載", label, beatmapset_id),
+            );
+            continue;
+        }
+        if let Some(available_mb) = low_disk_space(&target.download_directory) {
+            warn!("磁碟剩餘空間 {} MB 不足，停止自動下載", available_mb);
+            log_activity(&playlist, "磁碟空間不足，停止自動下載".to_string());
+            break;
+        }
+
+        target
+            .download_statuses
+            .lock()
+            .unwrap()
+            .insert(beatmapset_id, DownloadStatus::Waiting);
+        if let Err(e) = target.download_queue_sender.send(beatmapset_id) {
+            error!("無法將自動下載的譜面加入下載隊列: {:?}", e);
+            target
+                .download_statuses
+                .lock()
+                .unwrap()
+                .insert(beatmapset_id, DownloadStatus::NotStarted);
+            continue;
+        }
+        queued += 1;
+        log_activity(
+            &playlist,
+            format!("{}：已加入下載隊列 (#{})", label, beatmapset_id),
+        );
+    }
+
+    log_activity(
+        &playlist,
+        format!("完成，{} / {} 首已加入下載", queued, tracks.len()),
+    );
+}
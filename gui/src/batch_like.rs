@@ -0,0 +1,257 @@
+// 標準庫導入
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use log::{error, info, warn};
+use rspotify::AuthCodeSpotify;
+
+// 本地模組導入
+use crate::spotify::{set_tracks_liked, SAVED_TRACKS_CHUNK_SIZE};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LikeAction {
+    Like,
+    Unlike,
+}
+
+impl LikeAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LikeAction::Like => "加入喜歡",
+            LikeAction::Unlike => "移除喜歡",
+        }
+    }
+
+    fn reverse(&self) -> Self {
+        match self {
+            LikeAction::Like => LikeAction::Unlike,
+            LikeAction::Unlike => LikeAction::Like,
+        }
+    }
+}
+
+pub struct BatchLikeRequest {
+    pub action: LikeAction,
+    pub track_ids: Vec<String>,
+}
+
+enum BatchLikeState {
+    Idle,
+    Running {
+        action: LikeAction,
+        done: usize,
+        total: usize,
+    },
+    // applied 為已成功更新的曲目，部分失敗時可用來復原
+    Finished {
+        action: LikeAction,
+        applied: Vec<String>,
+        failed: usize,
+        synced: bool,
+    },
+}
+
+// 播放清單與搜尋結果的多選，批次加入或移除 Liked Songs
+pub struct BatchLike {
+    // 是否顯示勾選框與批次操作列
+    pub show: bool,
+    selected: HashSet<String>,
+    state: Arc<Mutex<BatchLikeState>>,
+}
+
+impl BatchLike {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            selected: HashSet::new(),
+            state: Arc::new(Mutex::new(BatchLikeState::Idle)),
+        }
+    }
+
+    pub fn is_selected(&self, track_id: &str) -> bool {
+        self.selected.contains(track_id)
+    }
+
+    pub fn set_selected(&mut self, track_id: &str, selected: bool) {
+        if selected {
+            self.selected.insert(track_id.to_string());
+        } else {
+            self.selected.remove(track_id);
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), BatchLikeState::Running { .. })
+    }
+
+    // 完成後回傳一次已更新的曲目，讓呼叫端同步畫面上的收藏狀態
+    pub fn take_applied(&self) -> Option<(LikeAction, Vec<String>)> {
+        match &mut *self.state.lock().unwrap() {
+            BatchLikeState::Finished {
+                action,
+                applied,
+                synced,
+                ..
+            } if !*synced => {
+                *synced = true;
+                Some((*action, applied.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    // 每批 SAVED_TRACKS_CHUNK_SIZE 首依序送出，遇到失敗就停止並記錄已更新的曲目
+    pub fn start(
+        &mut self,
+        spotify: AuthCodeSpotify,
+        request: BatchLikeRequest,
+        ctx: egui::Context,
+    ) {
+        let BatchLikeRequest { action, track_ids } = request;
+        let total = track_ids.len();
+        *self.state.lock().unwrap() = BatchLikeState::Running {
+            action,
+            done: 0,
+            total,
+        };
+        self.selected.clear();
+        info!("批次{} {} 首曲目", action.label(), total);
+
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            let mut applied: Vec<String> = Vec::new();
+            let mut failed = 0;
+            for chunk in track_ids.chunks(SAVED_TRACKS_CHUNK_SIZE) {
+                match set_tracks_liked(&spotify, chunk, action == LikeAction::Like).await {
+                    Ok(_) => applied.extend_from_slice(chunk),
+                    Err(e) => {
+                        error!("批次{}失敗: {:?}", action.label(), e);
+                        failed = total - applied.len();
+                        break;
+                    }
+                }
+                if let BatchLikeState::Running { done, .. } = &mut *state.lock().unwrap() {
+                    *done = applied.len();
+                }
+                ctx.request_repaint();
+            }
+
+            if failed > 0 && !applied.is_empty() {
+                warn!(
+                    "批次{}部分失敗，已更新 {} 首，復原需{}: {}",
+                    action.label(),
+                    applied.len(),
+                    action.reverse().label(),
+                    applied.join(",")
+                );
+            } else if failed == 0 {
+                info!("批次{}完成，共 {} 首", action.label(), applied.len());
+            }
+            *state.lock().unwrap() = BatchLikeState::Finished {
+                action,
+                applied,
+                failed,
+                synced: false,
+            };
+            ctx.request_repaint();
+        });
+    }
+
+    // visible_ids 為目前畫面上可選取的曲目，回傳使用者要執行的批次操作
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        visible_ids: &[String],
+    ) -> Option<BatchLikeRequest> {
+        let mut request = None;
+        let running = self.is_running();
+        ui.horizontal(|ui| {
+            let selected_count = visible_ids
+                .iter()
+                .filter(|id| self.selected.contains(*id))
+                .count();
+            ui.label(format!("已選取 {} 首", selected_count));
+            if ui.small_button("全選").clicked() {
+                self.selected.extend(visible_ids.iter().cloned());
+            }
+            if ui.small_button("取消選取").clicked() {
+                self.selected.clear();
+            }
+            ui.separator();
+
+            let track_ids: Vec<String> = visible_ids
+                .iter()
+                .filter(|id| self.selected.contains(*id))
+                .cloned()
+                .collect();
+            let enabled = !running && !track_ids.is_empty();
+            for action in [LikeAction::Like, LikeAction::Unlike] {
+                if ui
+                    .add_enabled(
+                        enabled,
+                        egui::Button::new(format!("全部{}", action.label())),
+                    )
+                    .clicked()
+                {
+                    request = Some(BatchLikeRequest {
+                        action,
+                        track_ids: track_ids.clone(),
+                    });
+                }
+            }
+        });
+
+        match &*self.state.lock().unwrap() {
+            BatchLikeState::Idle => {}
+            BatchLikeState::Running {
+                action,
+                done,
+                total,
+            } => {
+                ui.add(
+                    egui::ProgressBar::new(*done as f32 / (*total).max(1) as f32).text(format!(
+                        "正在{} {}/{}",
+                        action.label(),
+                        done,
+                        total
+                    )),
+                );
+            }
+            BatchLikeState::Finished {
+                action,
+                applied,
+                failed,
+                ..
+            } => {
+                ui.horizontal(|ui| {
+                    if *failed == 0 {
+                        ui.label(format!("已{} {} 首", action.label(), applied.len()));
+                        return;
+                    }
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!(
+                            "已{} {} 首，{} 首失敗（可能需要重新登入 Spotify）",
+                            action.label(),
+                            applied.len(),
+                            failed
+                        ),
+                    );
+                    if !applied.is_empty()
+                        && ui
+                            .small_button("復原")
+                            .on_hover_text(format!("將已更新的曲目{}", action.reverse().label()))
+                            .clicked()
+                    {
+                        request = Some(BatchLikeRequest {
+                            action: action.reverse(),
+                            track_ids: applied.clone(),
+                        });
+                    }
+                });
+            }
+        }
+        request
+    }
+}
@@ -0,0 +1,762 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use log::{error, info};
+use reqwest::Client;
+
+// 本地模組導入
+use crate::match_memory::{confirmed_beatmapset, remember_match, spotify_track_id};
+use crate::matcher::{
+    duration_mismatch, rank_beatmapsets, ScoredBeatmapset, CONFIDENT_MATCH_SCORE,
+};
+use crate::osu::{get_beatmapset_by_id, get_osu_token};
+use crate::query_normalizer::search_beatmapsets_normalized;
+use crate::report::{export_report, ReportEntry, ReportMatch, ReportTrack};
+use crate::spotify::{get_access_token, get_public_playlist_tracks, search_track, Track};
+use crate::DownloadStatus;
+
+// 每首歌保留的候選譜面數量
+const MAX_CANDIDATES: usize = 5;
+
+// 歌單檔中的一行
+#[derive(Clone, Debug)]
+pub struct SongQuery {
+    pub artist: String,
+    pub title: String,
+    pub raw: String,
+}
+
+impl SongQuery {
+    pub fn search_text(&self) -> String {
+        if self.artist.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{} {}", self.artist, self.title)
+        }
+    }
+}
+
+// 透過 Spotify 搜尋確認的正式曲目資訊
+#[derive(Clone, Debug)]
+pub struct ResolvedTrack {
+    pub name: String,
+    pub artists: String,
+    pub spotify_url: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EntryStatus {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchMatchEntry {
+    pub query: SongQuery,
+    pub spotify_track: Option<ResolvedTrack>,
+    pub candidates: Vec<ScoredBeatmapset>,
+    pub selected: usize,
+    pub confirmed: bool,
+    pub status: EntryStatus,
+    pub error: Option<String>,
+}
+
+impl BatchMatchEntry {
+    fn new(query: SongQuery) -> Self {
+        Self {
+            query,
+            spotify_track: None,
+            candidates: Vec::new(),
+            selected: 0,
+            confirmed: false,
+            status: EntryStatus::Pending,
+            error: None,
+        }
+    }
+
+    // 將使用者確認的配對記下，之後遇到同一首曲目直接使用
+    fn remember_selected(&self) {
+        let track = match &self.spotify_track {
+            Some(track) => track,
+            None => return,
+        };
+        let track_id = match track.spotify_url.as_deref().and_then(spotify_track_id) {
+            Some(track_id) => track_id,
+            None => return,
+        };
+        if let Some(candidate) = self.selected_candidate() {
+            remember_match(
+                &track_id,
+                &format!("{} - {}", track.artists, track.name),
+                &candidate.beatmapset,
+            );
+        }
+    }
+
+    pub fn selected_candidate(&self) -> Option<&ScoredBeatmapset> {
+        self.candidates.get(self.selected)
+    }
+}
+
+// 解析純文字（每行 "歌手 - 歌名"）或 CSV 歌單
+pub fn parse_song_list(content: &str, is_csv: bool) -> Vec<SongQuery> {
+    if is_csv {
+        parse_csv(content)
+    } else {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| match line.split_once(" - ") {
+                Some((artist, title)) => SongQuery {
+                    artist: artist.trim().to_string(),
+                    title: title.trim().to_string(),
+                    raw: line.to_string(),
+                },
+                None => SongQuery {
+                    artist: String::new(),
+                    title: line.to_string(),
+                    raw: line.to_string(),
+                },
+            })
+            .collect()
+    }
+}
+
+fn parse_csv(content: &str) -> Vec<SongQuery> {
+    let mut rows: Vec<Vec<String>> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(split_csv_line)
+        .collect();
+    if rows.is_empty() {
+        return Vec::new();
+    }
+
+    // 有標題列時依欄位名稱找出歌手與歌名，否則預設第一欄歌手、第二欄歌名
+    let header: Vec<String> = rows[0].iter().map(|c| c.trim().to_lowercase()).collect();
+    let find_column = |names: &[&str]| header.iter().position(|c| names.contains(&c.as_str()));
+    let artist_column = find_column(&["artist", "artists", "artist name(s)", "歌手"]);
+    let title_column = find_column(&["title", "name", "track name", "song", "歌名"]);
+    let (artist_column, title_column) = match (artist_column, title_column) {
+        (Some(artist), Some(title)) => {
+            rows.remove(0);
+            (Some(artist), title)
+        }
+        (None, Some(title)) => {
+            rows.remove(0);
+            (None, title)
+        }
+        _ => (Some(0), 1),
+    };
+
+    rows.into_iter()
+        .filter_map(|row| {
+            let raw = row.join(", ");
+            let (artist, title) = if row.len() > title_column {
+                let artist = artist_column
+                    .and_then(|column| row.get(column))
+                    .cloned()
+                    .unwrap_or_default();
+                (artist, row[title_column].clone())
+            } else {
+                // 只有一欄時視為 "歌手 - 歌名" 格式
+                let line = row.first()?.clone();
+                match line.split_once(" - ") {
+                    Some((artist, title)) => (artist.to_string(), title.to_string()),
+                    None => (String::new(), line),
+                }
+            };
+            let title = title.trim().to_string();
+            if title.is_empty() {
+                return None;
+            }
+            Some(SongQuery {
+                artist: artist.trim().to_string(),
+                title,
+                raw,
+            })
+        })
+        .collect()
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+// 歌單來源：本機歌單檔或 Spotify 播放清單網址
+#[derive(Clone, Debug)]
+enum ImportSource {
+    File(PathBuf),
+    SpotifyPlaylist(String),
+}
+
+pub struct BatchImport {
+    pub show: bool,
+    source: Option<ImportSource>,
+    entries: Arc<Mutex<Vec<BatchMatchEntry>>>,
+    processed: Arc<AtomicUsize>,
+    is_running: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl BatchImport {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            source: None,
+            entries: Arc::new(Mutex::new(Vec::new())),
+            processed: Arc::new(AtomicUsize::new(0)),
+            is_running: Arc::new(AtomicBool::new(false)),
+            error: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.is_running.load(Ordering::SeqCst)
+    }
+
+    // 所有候選譜面集 ID，供呼叫端查詢下載狀態
+    pub fn candidate_ids(&self) -> Vec<i32> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|entry| entry.candidates.iter().map(|c| c.beatmapset.id))
+            .collect()
+    }
+
+    // 轉換為匯出報告用的格式，只列出使用者選擇的譜面
+    fn report_entries(entries: &[BatchMatchEntry]) -> Vec<ReportEntry> {
+        entries
+            .iter()
+            .map(|entry| ReportEntry {
+                query: entry.query.raw.clone(),
+                track: entry.spotify_track.as_ref().map(|track| ReportTrack {
+                    name: track.name.clone(),
+                    artists: track.artists.clone(),
+                    album: None,
+                    url: track.spotify_url.clone(),
+                }),
+                matches: entry
+                    .selected_candidate()
+                    .map(|candidate| vec![ReportMatch::from(candidate)])
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn export(&mut self, entries: &[BatchMatchEntry]) {
+        let file_name = match &self.source {
+            Some(ImportSource::File(path)) => path
+                .file_stem()
+                .map(|stem| format!("{}_report.md", stem.to_string_lossy())),
+            Some(ImportSource::SpotifyPlaylist(id)) => Some(format!("spotify_{}_report.md", id)),
+            None => None,
+        }
+        .unwrap_or_else(|| "report.md".to_string());
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name(&file_name)
+            .add_filter("Markdown", &["md"])
+            .add_filter("HTML", &["html"])
+            .save_file()
+        {
+            let title = format!(
+                "歌單匹配報告 - {}",
+                file_name.trim_end_matches("_report.md")
+            );
+            match export_report(&path, &title, &Self::report_entries(entries)) {
+                Ok(_) => info!("已匯出匹配報告: {:?}", path),
+                Err(e) => {
+                    error!("匯出匹配報告失敗: {:?}", e);
+                    *self.error.lock().unwrap() = Some(format!("匯出失敗: {}", e));
+                }
+            }
+        }
+    }
+
+    // 讀取歌單檔並在背景逐首匹配
+    pub fn start(&mut self, path: &Path, ctx: egui::Context, debug_mode: bool) {
+        if self.is_running() {
+            return;
+        }
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                error!("讀取歌單檔失敗: {:?}", e);
+                *self.error.lock().unwrap() = Some(format!("讀取歌單檔失敗: {}", e));
+                return;
+            }
+        };
+        let is_csv = path
+            .extension()
+            .map_or(false, |ext| ext.eq_ignore_ascii_case("csv"));
+        let queries = parse_song_list(&content, is_csv);
+        info!("匯入歌單檔 {:?}：共 {} 首", path, queries.len());
+
+        self.run(
+            ImportSource::File(path.to_path_buf()),
+            queries,
+            ctx,
+            debug_mode,
+        );
+    }
+
+    // 以 Spotify 播放清單網址匯入，曲目在背景取得後再逐首匹配
+    pub fn start_spotify_playlist(
+        &mut self,
+        playlist_id: &str,
+        ctx: egui::Context,
+        debug_mode: bool,
+    ) {
+        if self.is_running() {
+            return;
+        }
+        info!("匯入 Spotify 播放清單: {}", playlist_id);
+        self.run(
+            ImportSource::SpotifyPlaylist(playlist_id.to_string()),
+            Vec::new(),
+            ctx,
+            debug_mode,
+        );
+    }
+
+    fn run(
+        &mut self,
+        source: ImportSource,
+        queries: Vec<SongQuery>,
+        ctx: egui::Context,
+        debug_mode: bool,
+    ) {
+        self.source = Some(source.clone());
+        *self.error.lock().unwrap() = None;
+        *self.entries.lock().unwrap() = queries.into_iter().map(BatchMatchEntry::new).collect();
+        self.processed.store(0, Ordering::SeqCst);
+        self.is_running.store(true, Ordering::SeqCst);
+
+        let entries = self.entries.clone();
+        let processed = self.processed.clone();
+        let is_running = self.is_running.clone();
+        let error_message = self.error.clone();
+
+        tokio::spawn(async move {
+            let client = Client::new();
+            let tokens = async {
+                let spotify_token = get_access_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let osu_token = get_osu_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok::<_, String>((spotify_token, osu_token))
+            }
+            .await;
+
+            let (spotify_token, osu_token) = match tokens {
+                Ok(tokens) => tokens,
+                Err(e) => {
+                    error!("批次匹配無法取得 token: {}", e);
+                    for entry in entries.lock().unwrap().iter_mut() {
+                        entry.status = EntryStatus::Failed;
+                        entry.error = Some(e.clone());
+                    }
+                    *error_message.lock().unwrap() = Some(format!("無法取得 token: {}", e));
+                    is_running.store(false, Ordering::SeqCst);
+                    ctx.request_repaint();
+                    return;
+                }
+            };
+
+            if let ImportSource::SpotifyPlaylist(playlist_id) = &source {
+                match get_public_playlist_tracks(&client, playlist_id, &spotify_token, debug_mode)
+                    .await
+                {
+                    Ok(tracks) => {
+                        info!("Spotify 播放清單 {}：共 {} 首", playlist_id, tracks.len());
+                        *entries.lock().unwrap() = tracks
+                            .iter()
+                            .map(song_query_from_track)
+                            .map(BatchMatchEntry::new)
+                            .collect();
+                        ctx.request_repaint();
+                    }
+                    Err(e) => {
+                        error!("讀取 Spotify 播放清單失敗: {:?}", e);
+                        *error_message.lock().unwrap() =
+                            Some(format!("讀取 Spotify 播放清單失敗: {}", e));
+                        is_running.store(false, Ordering::SeqCst);
+                        ctx.request_repaint();
+                        return;
+                    }
+                }
+            }
+
+            let total = entries.lock().unwrap().len();
+            for index in 0..total {
+                let query = entries.lock().unwrap()[index].query.clone();
+                let result =
+                    match_song(&client, &spotify_token, &osu_token, &query, debug_mode).await;
+
+                if let Some(entry) = entries.lock().unwrap().get_mut(index) {
+                    match result {
+                        Ok((spotify_track, candidates)) => {
+                            entry.confirmed = candidates
+                                .first()
+                                .map_or(false, |c| c.score >= CONFIDENT_MATCH_SCORE);
+                            entry.spotify_track = spotify_track;
+                            entry.candidates = candidates;
+                            entry.status = EntryStatus::Done;
+                        }
+                        Err(e) => {
+                            error!("匹配 {} 失敗: {}", query.raw, e);
+                            entry.status = EntryStatus::Failed;
+                            entry.error = Some(e);
+                        }
+                    }
+                }
+                processed.fetch_add(1, Ordering::SeqCst);
+                ctx.request_repaint();
+            }
+
+            info!("批次匹配完成：共 {} 首", total);
+            is_running.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    // 渲染匯入頁面，回傳使用者要求下載的譜面集 ID
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+    ) -> Vec<i32> {
+        let mut download_requests = Vec::new();
+        let mut export_clicked = false;
+        let is_running = self.is_running();
+
+        ui.heading("匯入歌單檔");
+        match &self.source {
+            Some(ImportSource::File(path)) => {
+                ui.label(format!("檔案: {}", path.to_string_lossy()));
+            }
+            Some(ImportSource::SpotifyPlaylist(id)) => {
+                ui.label(format!("Spotify 播放清單: {}", id));
+            }
+            None => {}
+        }
+        if let Some(error) = self.error.lock().unwrap().as_ref() {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let entries_arc = self.entries.clone();
+        let mut entries = entries_arc.lock().unwrap();
+        let total = entries.len();
+        let processed = self.processed.load(Ordering::SeqCst);
+
+        ui.horizontal(|ui| {
+            if is_running {
+                ui.add(egui::Spinner::new());
+            }
+            ui.label(format!("已處理 {} / {}", processed, total));
+            ui.add(
+                egui::ProgressBar::new(if total == 0 {
+                    0.0
+                } else {
+                    processed as f32 / total as f32
+                })
+                .desired_width(200.0),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("全選有結果的項目").clicked() {
+                for entry in entries.iter_mut() {
+                    entry.confirmed = !entry.candidates.is_empty();
+                }
+            }
+            if ui.button("全部取消").clicked() {
+                for entry in entries.iter_mut() {
+                    entry.confirmed = false;
+                }
+            }
+            let pending: Vec<i32> = entries
+                .iter()
+                .filter(|entry| entry.confirmed)
+                .filter_map(|entry| entry.selected_candidate().map(|c| c.beatmapset.id))
+                .filter(|id| {
+                    download_statuses
+                        .get(id)
+                        .copied()
+                        .unwrap_or(DownloadStatus::NotStarted)
+                        == DownloadStatus::NotStarted
+                })
+                .collect();
+            if ui
+                .add_enabled(
+                    !pending.is_empty(),
+                    egui::Button::new(format!("下載已確認 ({})", pending.len())),
+                )
+                .clicked()
+            {
+                for entry in entries.iter().filter(|entry| entry.confirmed) {
+                    entry.remember_selected();
+                }
+                download_requests.extend(pending);
+            }
+            if ui
+                .add_enabled(!is_running && total > 0, egui::Button::new("匯出結果"))
+                .clicked()
+            {
+                export_clicked = true;
+            }
+        });
+
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_source("batch_import_entries")
+            .show(ui, |ui| {
+                for (index, entry) in entries.iter_mut().enumerate() {
+                    Self::render_entry(ui, index, entry, download_statuses);
+                    ui.separator();
+                }
+            });
+
+        if export_clicked {
+            let snapshot = entries.clone();
+            drop(entries);
+            self.export(&snapshot);
+        }
+
+        download_requests
+    }
+
+    fn render_entry(
+        ui: &mut egui::Ui,
+        index: usize,
+        entry: &mut BatchMatchEntry,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.add_enabled(
+                !entry.candidates.is_empty(),
+                egui::Checkbox::new(&mut entry.confirmed, ""),
+            );
+            ui.vertical(|ui| {
+                ui.label(egui::RichText::new(&entry.query.raw).strong());
+                match entry.status {
+                    EntryStatus::Pending => {
+                        ui.label("等待匹配...");
+                        return;
+                    }
+                    EntryStatus::Failed => {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            entry.error.as_deref().unwrap_or("匹配失敗"),
+                        );
+                        return;
+                    }
+                    EntryStatus::Done => {}
+                }
+
+                match &entry.spotify_track {
+                    Some(track) => {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Spotify: {} - {}", track.artists, track.name));
+                            if let Some(url) = &track.spotify_url {
+                                if ui.small_button("開啟").clicked() {
+                                    if let Err(e) = open::that(url) {
+                                        error!("無法開啟 Spotify 連結: {:?}", e);
+                                    }
+                                }
+                            }
+                        });
+                    }
+                    None => {
+                        ui.label("Spotify: 找不到曲目");
+                    }
+                }
+
+                if entry.candidates.is_empty() {
+                    ui.label("osu!: 找不到譜面");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("osu!:");
+                    let selected_text = entry
+                        .selected_candidate()
+                        .map(candidate_label)
+                        .unwrap_or_default();
+                    egui::ComboBox::from_id_source(("batch_import_candidate", index))
+                        .selected_text(selected_text)
+                        .width(300.0)
+                        .show_ui(ui, |ui| {
+                            for (candidate_index, candidate) in entry.candidates.iter().enumerate()
+                            {
+                                ui.selectable_value(
+                                    &mut entry.selected,
+                                    candidate_index,
+                                    candidate_label(candidate),
+                                );
+                            }
+                        });
+
+                    if let Some(candidate) = entry.selected_candidate() {
+                        let duration_ms = entry
+                            .spotify_track
+                            .as_ref()
+                            .and_then(|track| track.duration_ms);
+                        if let Some(mismatch) =
+                            duration_mismatch(duration_ms, &candidate.beatmapset)
+                        {
+                            ui.colored_label(ui.visuals().warn_fg_color, mismatch.label())
+                                .on_hover_text(mismatch.detail());
+                        }
+                        let status = download_statuses
+                            .get(&candidate.beatmapset.id)
+                            .copied()
+                            .unwrap_or(DownloadStatus::NotStarted);
+                        match status {
+                            DownloadStatus::Completed => {
+                                ui.label("已下載");
+                            }
+                            DownloadStatus::Downloading => {
+                                ui.add(egui::Spinner::new());
+                            }
+                            DownloadStatus::Waiting => {
+                                ui.label("等待中");
+                            }
+                            DownloadStatus::NotStarted => {}
+                        }
+                    }
+                });
+            });
+        });
+    }
+}
+
+fn candidate_label(candidate: &ScoredBeatmapset) -> String {
+    format!(
+        "{} - {} (by {}) [{:.0}%]",
+        candidate.beatmapset.artist,
+        candidate.beatmapset.title,
+        candidate.beatmapset.creator,
+        candidate.score * 100.0
+    )
+}
+
+fn song_query_from_track(track: &Track) -> SongQuery {
+    let artist = track
+        .artists
+        .iter()
+        .map(|a| a.name.clone())
+        .collect::<Vec<_>>()
+        .join(", ");
+    SongQuery {
+        raw: format!("{} - {}", artist, track.name),
+        artist,
+        title: track.name.clone(),
+    }
+}
+
+// 先以 Spotify 確認正式曲名，再用正式曲名搜尋 osu! 譜面
+async fn match_song(
+    client: &Client,
+    spotify_token: &str,
+    osu_token: &str,
+    query: &SongQuery,
+    debug_mode: bool,
+) -> Result<(Option<ResolvedTrack>, Vec<ScoredBeatmapset>), String> {
+    let spotify_track = search_track(
+        client,
+        &query.search_text(),
+        spotify_token,
+        1,
+        0,
+        debug_mode,
+    )
+    .await
+    .map_err(|e| format!("Spotify 搜尋失敗: {}", e))?
+    .0
+    .into_iter()
+    .next()
+    .map(|track| ResolvedTrack {
+        name: track.name,
+        artists: track
+            .artists
+            .iter()
+            .map(|a| a.name.clone())
+            .collect::<Vec<_>>()
+            .join(", "),
+        spotify_url: track.external_urls.get("spotify").cloned(),
+        duration_ms: track.duration_ms,
+    });
+
+    // 使用者確認過的配對直接使用，不重新搜尋與評分
+    let remembered = spotify_track
+        .as_ref()
+        .and_then(|track| track.spotify_url.as_deref())
+        .and_then(spotify_track_id)
+        .and_then(|track_id| confirmed_beatmapset(&track_id));
+    if let Some(beatmapset_id) = remembered {
+        match get_beatmapset_by_id(client, osu_token, &beatmapset_id.to_string(), debug_mode).await
+        {
+            Ok(beatmapset) => {
+                return Ok((
+                    spotify_track,
+                    vec![ScoredBeatmapset {
+                        beatmapset,
+                        score: 1.0,
+                    }],
+                ));
+            }
+            Err(e) => error!(
+                "讀取已確認的譜面 {} 失敗，改為重新搜尋: {:?}",
+                beatmapset_id, e
+            ),
+        }
+    }
+
+    let (artist, title) = match &spotify_track {
+        Some(track) => (track.artists.clone(), track.name.clone()),
+        None => (query.artist.clone(), query.title.clone()),
+    };
+
+    let beatmapsets = search_beatmapsets_normalized(client, osu_token, &artist, &title, debug_mode)
+        .await
+        .map_err(|e| format!("osu! 搜尋失敗: {}", e))?;
+
+    let mut candidates = rank_beatmapsets(
+        &artist,
+        &title,
+        spotify_track.as_ref().and_then(|track| track.duration_ms),
+        beatmapsets,
+    );
+    candidates.truncate(MAX_CANDIDATES);
+    Ok((spotify_track, candidates))
+}
@@ -0,0 +1,450 @@
+// 第三方庫導入
+use egui::{Color32, RichText};
+
+// 縮排一層的寬度
+const INDENT_WIDTH: f32 = 16.0;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct SpanStyle {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+    heading: bool,
+    code: bool,
+    color: Option<Color32>,
+    link: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct Span {
+    text: String,
+    style: SpanStyle,
+}
+
+// 一行文字，清單項目與引用會縮排
+#[derive(Clone, Debug, Default)]
+struct Line {
+    indent: usize,
+    bullet: bool,
+    spans: Vec<Span>,
+}
+
+// 解析後的 BBCode，只保留粗體、連結、清單等常見格式，其餘標籤只顯示內容
+#[derive(Clone, Debug, Default)]
+pub struct BbcodeDocument {
+    lines: Vec<Line>,
+}
+
+struct Parser {
+    lines: Vec<Line>,
+    // 目前開啟的標籤與套用後的樣式
+    styles: Vec<(String, SpanStyle)>,
+    indent: usize,
+}
+
+impl Parser {
+    fn style(&self) -> SpanStyle {
+        self.styles
+            .last()
+            .map(|(_, style)| style.clone())
+            .unwrap_or_default()
+    }
+
+    fn current_line(&mut self) -> &mut Line {
+        if self.lines.is_empty() {
+            self.new_line();
+        }
+        self.lines.last_mut().unwrap()
+    }
+
+    fn new_line(&mut self) {
+        self.lines.push(Line {
+            indent: self.indent,
+            ..Line::default()
+        });
+    }
+
+    // 區塊標籤前後換行，已經在空行時不重複換行
+    fn break_line(&mut self) {
+        if self
+            .lines
+            .last()
+            .map_or(true, |line| !line.spans.is_empty() || line.bullet)
+        {
+            self.new_line();
+        } else if let Some(line) = self.lines.last_mut() {
+            line.indent = self.indent;
+        }
+    }
+
+    fn push_text(&mut self, text: &str, style: SpanStyle) {
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.new_line();
+            }
+            if part.is_empty() {
+                continue;
+            }
+            let line = self.current_line();
+            // 相同樣式的文字合併成一段，連結各自獨立
+            match line.spans.last_mut() {
+                Some(span) if style.link.is_none() && span.style == style => {
+                    span.text.push_str(part)
+                }
+                _ => line.spans.push(Span {
+                    text: part.to_string(),
+                    style: style.clone(),
+                }),
+            }
+        }
+    }
+
+    fn open(&mut self, name: &str, style: SpanStyle) {
+        self.styles.push((name.to_string(), style));
+    }
+
+    // 關閉最近一個同名標籤，沒有開啟過時忽略
+    fn close(&mut self, name: &str) {
+        if let Some(position) = self.styles.iter().rposition(|(open, _)| open == name) {
+            self.styles.truncate(position);
+        }
+    }
+}
+
+// 標籤名稱與參數，例如 url=https://osu.ppy.sh
+fn split_tag(tag: &str) -> (String, Option<String>) {
+    match tag.split_once('=') {
+        Some((name, value)) => (
+            name.trim().to_lowercase(),
+            Some(value.trim().trim_matches('"').to_string()),
+        ),
+        None => (tag.trim().to_lowercase(), None),
+    }
+}
+
+// 內容本身就是網址的標籤，直接取到結束標籤為止
+fn take_until_close<'a>(text: &'a str, name: &str) -> Option<(&'a str, usize)> {
+    let close = format!("[/{}]", name);
+    let end = text.to_ascii_lowercase().find(&close)?;
+    Some((&text[..end], end + close.len()))
+}
+
+impl BbcodeDocument {
+    pub fn parse(text: &str) -> Self {
+        let mut parser = Parser {
+            lines: Vec::new(),
+            styles: Vec::new(),
+            indent: 0,
+        };
+        let text = text.replace("\r\n", "\n");
+        let mut rest = text.as_str();
+
+        while !rest.is_empty() {
+            let start = match rest.find('[') {
+                Some(start) => start,
+                None => {
+                    parser.push_text(rest, parser.style());
+                    break;
+                }
+            };
+            parser.push_text(&rest[..start], parser.style());
+            rest = &rest[start..];
+            let end = match rest.find(']') {
+                Some(end) => end,
+                None => {
+                    parser.push_text(rest, parser.style());
+                    break;
+                }
+            };
+            let tag = &rest[1..end];
+            let after = &rest[end + 1..];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                let name = name.trim().to_lowercase();
+                match name.as_str() {
+                    "list" => {
+                        parser.indent = parser.indent.saturating_sub(1);
+                        parser.break_line();
+                    }
+                    "heading" | "box" | "spoilerbox" | "centre" | "center" | "notice" => {
+                        parser.close(&name);
+                        parser.break_line();
+                    }
+                    "quote" => {
+                        parser.indent = parser.indent.saturating_sub(1);
+                        parser.break_line();
+                    }
+                    _ => parser.close(&name),
+                }
+                rest = after;
+                continue;
+            }
+
+            let (name, value) = split_tag(tag);
+            let mut style = parser.style();
+            match name.as_str() {
+                "b" => style.bold = true,
+                "i" => style.italic = true,
+                "u" => style.underline = true,
+                "s" | "strike" => style.strikethrough = true,
+                "c" | "code" => style.code = true,
+                "color" => {
+                    style.color = value
+                        .as_deref()
+                        .and_then(|color| Color32::from_hex(color).ok())
+                }
+                "size" | "spoiler" | "centre" | "center" | "notice" | "profile" => {}
+                "heading" => {
+                    parser.break_line();
+                    style.heading = true;
+                    style.bold = true;
+                }
+                "box" | "spoilerbox" => {
+                    parser.break_line();
+                    if let Some(title) = &value {
+                        let mut title_style = style.clone();
+                        title_style.bold = true;
+                        parser.push_text(&format!("▸ {}", title), title_style);
+                        parser.break_line();
+                    }
+                }
+                "quote" => {
+                    parser.break_line();
+                    if let Some(author) = &value {
+                        let mut author_style = style.clone();
+                        author_style.italic = true;
+                        parser.push_text(&format!("{} 寫道:", author), author_style);
+                    }
+                    parser.indent += 1;
+                    parser.break_line();
+                    rest = after;
+                    continue;
+                }
+                "list" => {
+                    parser.indent += 1;
+                    parser.break_line();
+                    rest = after;
+                    continue;
+                }
+                "*" => {
+                    parser.break_line();
+                    parser.current_line().bullet = true;
+                    rest = after;
+                    continue;
+                }
+                "url" | "email" => {
+                    if value.is_some() {
+                        style.link = value;
+                    } else if let Some((url, consumed)) = take_until_close(after, &name) {
+                        style.link = Some(url.trim().to_string());
+                        parser.push_text(url.trim(), style);
+                        rest = &after[consumed..];
+                        continue;
+                    }
+                }
+                "img" | "youtube" | "audio" => {
+                    if let Some((content, consumed)) = take_until_close(after, &name) {
+                        let content = content.trim();
+                        let (label, url) = match name.as_str() {
+                            "img" => ("🖼 圖片", content.to_string()),
+                            "youtube" => (
+                                "▶ YouTube",
+                                format!("https://www.youtube.com/watch?v={}", content),
+                            ),
+                            _ => ("🔊 音訊", content.to_string()),
+                        };
+                        style.link = Some(url);
+                        parser.push_text(label, style);
+                        rest = &after[consumed..];
+                        continue;
+                    }
+                }
+                // 不認得的方括號當作一般文字
+                _ => {
+                    parser.push_text("[", parser.style());
+                    rest = &rest[1..];
+                    continue;
+                }
+            }
+            parser.open(&name, style);
+            rest = after;
+        }
+
+        let mut lines = parser.lines;
+        while lines.last().map_or(false, |line| line.spans.is_empty()) {
+            lines.pop();
+        }
+        let leading = lines
+            .iter()
+            .take_while(|line| line.spans.is_empty())
+            .count();
+        lines.drain(..leading);
+        Self { lines }
+    }
+
+    // osu! API 只提供轉換後的 HTML，先轉回 BBCode 再解析
+    pub fn from_html(html: &str) -> Self {
+        Self::parse(&html_to_bbcode(html))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn render(&self, ui: &mut egui::Ui, font_size: f32) {
+        for line in &self.lines {
+            if line.spans.is_empty() && !line.bullet {
+                ui.add_space(font_size * 0.5);
+                continue;
+            }
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing.x = 0.0;
+                ui.add_space(line.indent as f32 * INDENT_WIDTH);
+                if line.bullet {
+                    ui.label(RichText::new("• ").size(font_size));
+                }
+                for span in &line.spans {
+                    let text = span_text(span, font_size);
+                    match &span.style.link {
+                        Some(url) => {
+                            ui.hyperlink_to(text, url).on_hover_text(url);
+                        }
+                        None => {
+                            ui.label(text);
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+fn span_text(span: &Span, font_size: f32) -> RichText {
+    let style = &span.style;
+    let size = if style.heading {
+        font_size * 1.2
+    } else {
+        font_size
+    };
+    let mut text = RichText::new(&span.text).size(size);
+    if style.bold {
+        text = text.strong();
+    }
+    if style.italic {
+        text = text.italics();
+    }
+    if style.underline {
+        text = text.underline();
+    }
+    if style.strikethrough {
+        text = text.strikethrough();
+    }
+    if style.code {
+        text = text.code();
+    }
+    if let Some(color) = style.color {
+        text = text.color(color);
+    }
+    text
+}
+
+// 取出 HTML 標籤中的屬性值，例如 href 或 src
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!("{}=", name))? + name.len() + 1;
+    let value = &tag[start..];
+    let quote = value.chars().next()?;
+    if quote == '"' || quote == '\'' {
+        let value = &value[1..];
+        Some(value[..value.find(quote)?].to_string())
+    } else {
+        Some(
+            value
+                .split(|c: char| c.is_whitespace() || c == '>')
+                .next()?
+                .to_string(),
+        )
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#039;", "'")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+// 將 osu! 網站由 BBCode 產生的 HTML 轉回對應的 BBCode，無法對應的標籤只保留內容
+fn html_to_bbcode(html: &str) -> String {
+    let mut output = String::new();
+    // 每個開啟的 <a> 對應的結束標籤，折疊區塊的標題連結沒有實際網址
+    let mut anchors: Vec<&str> = Vec::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&decode_entities(&rest[..start].replace('\n', "")));
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        let mapped = match name.as_str() {
+            "strong" | "b" => Some("b"),
+            "em" | "i" => Some("i"),
+            "u" => Some("u"),
+            "del" | "s" => Some("s"),
+            "code" => Some("c"),
+            "h2" | "h3" => Some("heading"),
+            "blockquote" => Some("quote"),
+            "ol" | "ul" => Some("list"),
+            _ => None,
+        };
+        match (name.as_str(), closing) {
+            ("br", _) => output.push('\n'),
+            ("li", false) => output.push_str("[*]"),
+            ("img", false) => {
+                if let Some(src) = attribute(tag, "src") {
+                    output.push_str(&format!("[img]{}[/img]", src));
+                }
+            }
+            ("a", false) => match attribute(tag, "href").filter(|href| href != "#") {
+                Some(href) => {
+                    output.push_str(&format!("[url={}]", decode_entities(&href)));
+                    anchors.push("[/url]");
+                }
+                None => {
+                    output.push_str("[b]");
+                    anchors.push("[/b]\n");
+                }
+            },
+            ("a", true) => output.push_str(anchors.pop().unwrap_or_default()),
+            ("p" | "div", true) => output.push('\n'),
+            _ => {
+                if let Some(mapped) = mapped {
+                    if closing {
+                        output.push_str(&format!("[/{}]", mapped));
+                    } else {
+                        output.push_str(&format!("[{}]", mapped));
+                    }
+                }
+            }
+        }
+    }
+    output.push_str(&decode_entities(&rest.replace('\n', "")));
+    output
+}
@@ -0,0 +1,324 @@
+// 第三方庫導入
+use futures::future::BoxFuture;
+use log::{error, info};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::matcher::preferred_search_filters;
+use crate::osu::{
+    get_beatmapsets_page, get_osu_token, Beatmap, Beatmapset, BeatmapsetPage, BeatmapsetTag,
+    Covers, OsuError,
+};
+use lib::{load_config, save_config};
+
+const SOURCE_CONFIG_FILE: &str = "beatmap_source.json";
+const MIRROR_PAGE_SIZE: usize = 50;
+
+// 譜面搜尋來源，官方 API 以外也可以改用鏡像站的搜尋
+pub trait BeatmapSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // cursor 為上一頁回傳的 cursor_string，None 代表第一頁
+    fn search_page<'a>(
+        &'a self,
+        client: &'a Client,
+        query: &'a str,
+        cursor: Option<&'a str>,
+        debug_mode: bool,
+    ) -> BoxFuture<'a, Result<BeatmapsetPage, OsuError>>;
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BeatmapSourceKind {
+    #[default]
+    Official,
+    Nerinyan,
+    Mino,
+}
+
+impl BeatmapSourceKind {
+    pub const ALL: [BeatmapSourceKind; 3] = [
+        BeatmapSourceKind::Official,
+        BeatmapSourceKind::Nerinyan,
+        BeatmapSourceKind::Mino,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        self.source().name()
+    }
+
+    pub fn source(&self) -> &'static dyn BeatmapSource {
+        match self {
+            BeatmapSourceKind::Official => &OfficialSource,
+            BeatmapSourceKind::Nerinyan => &NerinyanSource,
+            BeatmapSourceKind::Mino => &MinoSource,
+        }
+    }
+
+    pub fn load() -> Self {
+        load_config(SOURCE_CONFIG_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = save_config(SOURCE_CONFIG_FILE, self);
+        match result {
+            Ok(_) => info!("已切換譜面來源: {}", self.label()),
+            Err(e) => error!("保存譜面來源失敗: {:?}", e),
+        }
+    }
+}
+
+// osu! 官方 API v2
+pub struct OfficialSource;
+
+impl BeatmapSource for OfficialSource {
+    fn name(&self) -> &'static str {
+        "osu! 官方"
+    }
+
+    fn search_page<'a>(
+        &'a self,
+        client: &'a Client,
+        query: &'a str,
+        cursor: Option<&'a str>,
+        debug_mode: bool,
+    ) -> BoxFuture<'a, Result<BeatmapsetPage, OsuError>> {
+        Box::pin(async move {
+            let token = get_osu_token(client, debug_mode).await?;
+            let filters = preferred_search_filters();
+            get_beatmapsets_page(client, &token, query, cursor, &filters, debug_mode).await
+        })
+    }
+}
+
+// Nerinyan 鏡像站（下載也是使用此站）
+pub struct NerinyanSource;
+
+impl BeatmapSource for NerinyanSource {
+    fn name(&self) -> &'static str {
+        "Nerinyan 鏡像"
+    }
+
+    fn search_page<'a>(
+        &'a self,
+        client: &'a Client,
+        query: &'a str,
+        cursor: Option<&'a str>,
+        debug_mode: bool,
+    ) -> BoxFuture<'a, Result<BeatmapsetPage, OsuError>> {
+        Box::pin(async move {
+            let page = parse_cursor(cursor)?;
+            let page_param = page.to_string();
+            let size_param = MIRROR_PAGE_SIZE.to_string();
+            let request = client.get("https://api.nerinyan.moe/search").query(&[
+                ("q", query),
+                ("p", page_param.as_str()),
+                ("ps", size_param.as_str()),
+            ]);
+            let beatmapsets = fetch_mirror_results(request, self.name(), debug_mode).await?;
+            Ok(mirror_page(beatmapsets, page + 1))
+        })
+    }
+}
+
+// Mino (catboy.best) 鏡像站，回應格式與官方 API v2 相近
+pub struct MinoSource;
+
+impl BeatmapSource for MinoSource {
+    fn name(&self) -> &'static str {
+        "Mino 鏡像"
+    }
+
+    fn search_page<'a>(
+        &'a self,
+        client: &'a Client,
+        query: &'a str,
+        cursor: Option<&'a str>,
+        debug_mode: bool,
+    ) -> BoxFuture<'a, Result<BeatmapsetPage, OsuError>> {
+        Box::pin(async move {
+            let page = parse_cursor(cursor)?;
+            let offset_param = (page * MIRROR_PAGE_SIZE).to_string();
+            let limit_param = MIRROR_PAGE_SIZE.to_string();
+            let request = client.get("https://catboy.best/api/v2/search").query(&[
+                ("query", query),
+                ("offset", offset_param.as_str()),
+                ("limit", limit_param.as_str()),
+            ]);
+            let beatmapsets = fetch_mirror_results(request, self.name(), debug_mode).await?;
+            Ok(mirror_page(beatmapsets, page + 1))
+        })
+    }
+}
+
+// 鏡像站的 cursor 為頁碼
+fn parse_cursor(cursor: Option<&str>) -> Result<usize, OsuError> {
+    match cursor {
+        Some(cursor) => cursor
+            .parse()
+            .map_err(|_| OsuError::Other(format!("無效的分頁參數: {}", cursor))),
+        None => Ok(0),
+    }
+}
+
+fn mirror_page(beatmapsets: Vec<Beatmapset>, next_page: usize) -> BeatmapsetPage {
+    // 結果少於一頁代表沒有下一頁
+    let cursor_string = if beatmapsets.len() >= MIRROR_PAGE_SIZE {
+        Some(next_page.to_string())
+    } else {
+        None
+    };
+    BeatmapsetPage {
+        beatmapsets,
+        cursor_string,
+        total: None,
+    }
+}
+
+async fn fetch_mirror_results(
+    request: reqwest::RequestBuilder,
+    source_name: &str,
+    debug_mode: bool,
+) -> Result<Vec<Beatmapset>, OsuError> {
+    let response = request.send().await.map_err(OsuError::RequestError)?;
+    if !response.status().is_success() {
+        return Err(OsuError::ApiError(format!(
+            "{} 搜尋失敗，狀態碼: {}",
+            source_name,
+            response.status()
+        )));
+    }
+
+    let response_text = response.text().await.map_err(OsuError::RequestError)?;
+    if debug_mode {
+        info!("{} 回應 JSON: {}", source_name, response_text);
+    }
+
+    let results: Vec<MirrorBeatmapset> =
+        serde_json::from_str(&response_text).map_err(OsuError::JsonError)?;
+    Ok(results
+        .into_iter()
+        .map(MirrorBeatmapset::into_beatmapset)
+        .collect())
+}
+
+// 鏡像站回傳的譜面集，欄位可能缺漏，統一轉換為 Beatmapset
+#[derive(Deserialize)]
+struct MirrorBeatmapset {
+    id: i32,
+    #[serde(default)]
+    artist: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    artist_unicode: Option<String>,
+    #[serde(default)]
+    title_unicode: Option<String>,
+    #[serde(default)]
+    creator: String,
+    #[serde(default)]
+    covers: Option<Covers>,
+    #[serde(default)]
+    preview_url: Option<String>,
+    #[serde(default)]
+    beatmaps: Vec<MirrorBeatmap>,
+    // 鏡像站沿用 API v1 的欄位，只有 ID
+    #[serde(default)]
+    genre_id: Option<u32>,
+    #[serde(default)]
+    language_id: Option<u32>,
+    #[serde(default)]
+    favourite_count: u32,
+    #[serde(default)]
+    play_count: u32,
+    #[serde(default)]
+    pack_tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct MirrorBeatmap {
+    id: i32,
+    #[serde(default)]
+    difficulty_rating: f32,
+    #[serde(default)]
+    mode: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    total_length: i32,
+    #[serde(default)]
+    user_id: i32,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    cs: f32,
+}
+
+impl MirrorBeatmapset {
+    fn into_beatmapset(self) -> Beatmapset {
+        let id = self.id;
+        Beatmapset {
+            id,
+            artist: self.artist,
+            title: self.title,
+            artist_unicode: self.artist_unicode,
+            title_unicode: self.title_unicode,
+            creator: self.creator,
+            covers: self.covers.unwrap_or_else(|| default_covers(id)),
+            // 部分鏡像回傳 "//b.ppy.sh/..." 形式的網址
+            preview_url: self.preview_url.map(|url| {
+                if url.starts_with("//") {
+                    format!("https:{}", url)
+                } else {
+                    url
+                }
+            }),
+            beatmaps: self
+                .beatmaps
+                .into_iter()
+                .map(|beatmap| Beatmap {
+                    difficulty_rating: beatmap.difficulty_rating,
+                    id: beatmap.id,
+                    mode: beatmap.mode,
+                    status: beatmap.status,
+                    total_length: beatmap.total_length,
+                    user_id: beatmap.user_id,
+                    version: beatmap.version,
+                    cs: beatmap.cs,
+                })
+                .collect(),
+            genre: self.genre_id.map(|id| BeatmapsetTag {
+                id,
+                name: String::new(),
+            }),
+            language: self.language_id.map(|id| BeatmapsetTag {
+                id,
+                name: String::new(),
+            }),
+            favourite_count: self.favourite_count,
+            play_count: self.play_count,
+            pack_tags: self.pack_tags,
+        }
+    }
+}
+
+// 鏡像沒有提供封面時，使用 osu! 資源站的固定路徑
+fn default_covers(beatmapset_id: i32) -> Covers {
+    let url = |name: &str| {
+        Some(format!(
+            "https://assets.ppy.sh/beatmaps/{}/covers/{}.jpg",
+            beatmapset_id, name
+        ))
+    };
+    Covers {
+        cover: url("cover"),
+        cover_2x: url("cover@2x"),
+        card: url("card"),
+        card_2x: url("card@2x"),
+        list: url("list"),
+        list_2x: url("list@2x"),
+        slimcover: url("slimcover"),
+        slimcover_2x: url("slimcover@2x"),
+    }
+}
@@ -0,0 +1,295 @@
+// 標準庫導入
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// 第三方庫導入
+use log::{debug, error, info};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// 本地模組導入
+use crate::download_history::{clear_history, history_size};
+use crate::session::AVATAR_DIR;
+use crate::storage::{storage, StorageError};
+use lib::{get_app_data_path, load_cache_ttl, save_cache_ttl};
+
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+// 超過此大小的快取改用串流解析，避免整個檔案與解析結果同時佔用記憶體
+const STREAMING_THRESHOLD_BYTES: u64 = 4 * 1024 * 1024;
+
+// 磁碟上的快取種類
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheKind {
+    PlaylistTracks,
+    LikedTracks,
+    Previews,
+    Avatars,
+    CrashReports,
+    DownloadHistory,
+}
+
+impl CacheKind {
+    pub const ALL: [CacheKind; 6] = [
+        CacheKind::PlaylistTracks,
+        CacheKind::LikedTracks,
+        CacheKind::Previews,
+        CacheKind::Avatars,
+        CacheKind::CrashReports,
+        CacheKind::DownloadHistory,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CacheKind::PlaylistTracks => "播放列表快取",
+            CacheKind::LikedTracks => "喜歡的曲目快取",
+            CacheKind::Previews => "譜面預覽音訊",
+            CacheKind::Avatars => "使用者頭像",
+            CacheKind::CrashReports => "崩潰報告",
+            CacheKind::DownloadHistory => "下載紀錄",
+        }
+    }
+
+    fn matches(&self, file_name: &str) -> bool {
+        match self {
+            CacheKind::PlaylistTracks => {
+                file_name.starts_with("playlist_") && file_name.ends_with("_cache.json")
+            }
+            CacheKind::LikedTracks => file_name == "liked_tracks_cache.json",
+            CacheKind::Previews => file_name.starts_with("preview_") && file_name.ends_with(".mp3"),
+            CacheKind::Avatars => file_name.ends_with(".jpg"),
+            CacheKind::CrashReports => true,
+            CacheKind::DownloadHistory => false,
+        }
+    }
+
+    // 曲目快取由 Storage 管理，不一定是檔案
+    fn in_storage(&self) -> bool {
+        matches!(self, CacheKind::PlaylistTracks | CacheKind::LikedTracks)
+    }
+}
+
+// 管理快取檔案的位置、大小與有效期
+pub struct CacheManager {
+    root: PathBuf,
+    ttl: Duration,
+}
+
+impl CacheManager {
+    pub fn new() -> Self {
+        let ttl_secs = load_cache_ttl().unwrap_or(DEFAULT_CACHE_TTL_SECS);
+        Self {
+            root: get_app_data_path(),
+            ttl: Duration::from_secs(ttl_secs),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+        if let Err(e) = save_cache_ttl(ttl.as_secs()) {
+            error!("保存快取有效期失敗: {:?}", e);
+        }
+    }
+
+    // 快取不存在或超過有效期時回傳 true
+    pub fn is_stale(&self, name: &str) -> bool {
+        storage()
+            .cache_modified(name)
+            .and_then(|modified| modified.elapsed().ok())
+            .map_or(true, |elapsed| elapsed > self.ttl)
+    }
+
+    fn storage_caches(&self, kind: CacheKind) -> Vec<(String, u64)> {
+        storage()
+            .caches()
+            .into_iter()
+            .filter(|(name, _)| kind.matches(name))
+            .collect()
+    }
+
+    pub fn files(&self, kind: CacheKind) -> Vec<PathBuf> {
+        match kind {
+            CacheKind::Avatars => {
+                list_files(&self.root.join(AVATAR_DIR), |name| kind.matches(name))
+            }
+            CacheKind::CrashReports => {
+                list_files(&self.root.join("crash_reports"), |name| kind.matches(name))
+            }
+            // 下載紀錄由 Storage 保存，不一定是檔案
+            CacheKind::DownloadHistory => Vec::new(),
+            _ => list_files(&self.root, |name| kind.matches(name)),
+        }
+    }
+
+    pub fn size(&self, kind: CacheKind) -> u64 {
+        if kind == CacheKind::DownloadHistory {
+            return history_size();
+        }
+        if kind.in_storage() {
+            return self.storage_caches(kind).iter().map(|(_, size)| size).sum();
+        }
+        self.files(kind)
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    pub fn clear(&self, kind: CacheKind) -> Result<usize, StorageError> {
+        if kind == CacheKind::DownloadHistory {
+            let count = clear_history()?;
+            info!("已清除 {}：{} 筆紀錄", kind.label(), count);
+            return Ok(count);
+        }
+        if kind.in_storage() {
+            let caches = self.storage_caches(kind);
+            for (name, _) in &caches {
+                storage().remove_cache(name)?;
+            }
+            info!("已清除 {}：{} 個快取", kind.label(), caches.len());
+            return Ok(caches.len());
+        }
+        let files = self.files(kind);
+        for path in &files {
+            fs::remove_file(path)?;
+        }
+        info!("已清除 {}：{} 個檔案", kind.label(), files.len());
+        Ok(files.len())
+    }
+}
+
+fn list_files(dir: &Path, filter: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter(|entry| entry.file_name().to_str().map_or(false, &filter))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+// 快取讀取的進度，背景執行緒更新、介面讀取
+#[derive(Default)]
+pub struct CacheProgress {
+    done: AtomicU64,
+    total: AtomicU64,
+}
+
+impl CacheProgress {
+    // 沒有進行中的讀取時回傳 None
+    pub fn fraction(&self) -> Option<f32> {
+        let total = self.total.load(Ordering::Relaxed);
+        if total == 0 {
+            return None;
+        }
+        let done = self.done.load(Ordering::Relaxed).min(total);
+        Some(done as f32 / total as f32)
+    }
+
+    fn start(&self, total: u64) {
+        self.done.store(0, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    fn finish(&self) {
+        self.total.store(0, Ordering::Relaxed);
+    }
+}
+
+// 讀取時累計已讀取的位元組數
+struct ProgressReader<R> {
+    inner: R,
+    progress: Arc<CacheProgress>,
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.progress.done.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+fn parse_json_cache<T: DeserializeOwned>(
+    name: &str,
+    progress: &Arc<CacheProgress>,
+) -> Result<Option<T>, StorageError> {
+    let (reader, total) = match storage().open_cache(name)? {
+        Some(cache) => cache,
+        None => return Ok(None),
+    };
+    progress.start(total);
+    let mut reader = ProgressReader {
+        inner: reader,
+        progress: progress.clone(),
+    };
+    if total > STREAMING_THRESHOLD_BYTES {
+        debug!("以串流方式解析快取 {} ({})", name, format_size(total));
+        return Ok(Some(serde_json::from_reader(BufReader::new(reader))?));
+    }
+    let mut content = Vec::with_capacity(total as usize);
+    reader.read_to_end(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+// 在阻塞執行緒上讀取並解析 JSON 快取，不存在或損毀時回傳 None
+pub async fn read_json_cache<T>(name: String, progress: Arc<CacheProgress>) -> Option<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let result = tokio::task::spawn_blocking(move || {
+        let result = parse_json_cache(&name, &progress);
+        progress.finish();
+        result.map_err(|e| (name, e))
+    })
+    .await;
+    match result {
+        Ok(Ok(value)) => value,
+        Ok(Err((name, e))) => {
+            error!("讀取快取 {} 失敗: {:?}", name, e);
+            None
+        }
+        Err(e) => {
+            error!("快取讀取工作失敗: {:?}", e);
+            None
+        }
+    }
+}
+
+// 在阻塞執行緒上序列化並寫入 JSON 快取
+pub async fn write_json_cache<T>(name: String, value: T) -> Result<(), StorageError>
+where
+    T: Serialize + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || {
+        let content = serde_json::to_vec(&value)?;
+        storage().save_cache(&name, &content)
+    })
+    .await
+    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+}
@@ -0,0 +1,193 @@
+// 標準庫導入
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "confirm_options.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ConfirmOptions {
+    // 勾選「不要再詢問」的對話框，鍵 -> 對話框標題（設定頁面顯示用）
+    #[serde(default)]
+    pub skipped: BTreeMap<String, String>,
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<ConfirmOptions> =
+        RwLock::new(load_config(OPTIONS_FILE).unwrap_or_default());
+}
+
+pub fn confirm_options() -> ConfirmOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_confirm_options(options: ConfirmOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存確認對話框選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+fn is_skipped(key: &str) -> bool {
+    OPTIONS.read().unwrap().skipped.contains_key(key)
+}
+
+// 一次需要確認的操作，action 為 None 時只顯示訊息與關閉按鈕
+pub struct ConfirmRequest<A> {
+    // 同一類操作共用的鍵，用來記住「不要再詢問」
+    key: &'static str,
+    title: String,
+    message: String,
+    details: Vec<String>,
+    confirm_label: String,
+    action: Option<A>,
+    // 為 false 時不提供「不要再詢問」，例如確認內容本身就是必須看到的摘要
+    allow_skip: bool,
+}
+
+impl<A> ConfirmRequest<A> {
+    pub fn new(key: &'static str, title: &str, message: impl Into<String>, action: A) -> Self {
+        Self {
+            key,
+            title: title.to_string(),
+            message: message.into(),
+            details: Vec::new(),
+            confirm_label: String::from("確定"),
+            action: Some(action),
+            allow_skip: true,
+        }
+    }
+
+    // 沒有可執行的操作，例如沒有符合條件的圖譜
+    pub fn notice(key: &'static str, title: &str, message: impl Into<String>) -> Self {
+        Self {
+            key,
+            title: title.to_string(),
+            message: message.into(),
+            details: Vec::new(),
+            confirm_label: String::new(),
+            action: None,
+            allow_skip: false,
+        }
+    }
+
+    // 對話框中列出的項目，例如要刪除的檔案
+    pub fn details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+
+    pub fn confirm_label(mut self, label: &str) -> Self {
+        self.confirm_label = label.to_string();
+        self
+    }
+
+    // 每次都顯示對話框，忽略先前的「不要再詢問」
+    pub fn always_ask(mut self) -> Self {
+        self.allow_skip = false;
+        self
+    }
+}
+
+// 破壞性操作前的確認對話框，確認後由呼叫端執行回傳的操作
+pub struct ConfirmDialog<A> {
+    pending: Option<ConfirmRequest<A>>,
+    dont_ask_again: bool,
+}
+
+impl<A> ConfirmDialog<A> {
+    pub fn new() -> Self {
+        Self {
+            pending: None,
+            dont_ask_again: false,
+        }
+    }
+
+    // 使用者先前選擇不要再詢問時直接回傳操作，否則等待確認
+    pub fn request(&mut self, request: ConfirmRequest<A>) -> Option<A> {
+        if request.action.is_some() && request.allow_skip && is_skipped(request.key) {
+            return request.action;
+        }
+        self.pending = Some(request);
+        self.dont_ask_again = false;
+        None
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<A> {
+        let pending = match &self.pending {
+            Some(pending) => pending,
+            None => return None,
+        };
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        egui::Window::new(&pending.title)
+            .id(egui::Id::new("confirm_dialog"))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(&pending.message);
+                if !pending.details.is_empty() {
+                    egui::ScrollArea::vertical()
+                        .max_height(250.0)
+                        .show(ui, |ui| {
+                            for detail in &pending.details {
+                                ui.label(detail);
+                            }
+                        });
+                }
+                ui.separator();
+                if pending.action.is_none() {
+                    if ui.button("關閉").clicked() {
+                        cancelled = true;
+                    }
+                    return;
+                }
+                if pending.allow_skip {
+                    ui.checkbox(&mut self.dont_ask_again, "不要再詢問");
+                }
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(
+                            egui::RichText::new(&pending.confirm_label)
+                                .color(ui.visuals().error_fg_color),
+                        )
+                        .clicked()
+                    {
+                        confirmed = true;
+                    }
+                    if ui.button("取消").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+
+        if confirmed {
+            let pending = self.pending.take()?;
+            if self.dont_ask_again {
+                let mut options = confirm_options();
+                options
+                    .skipped
+                    .insert(pending.key.to_string(), pending.title.clone());
+                set_confirm_options(options);
+            }
+            pending.action
+        } else {
+            if cancelled || !open {
+                self.pending = None;
+            }
+            None
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+// 第三方庫導入
+use egui::Color32;
+use image::DynamicImage;
+use lazy_static::lazy_static;
+
+// 取色前先縮小，16x16 已足夠判斷主色
+const SAMPLE_SIZE: u32 = 16;
+// 每個色版保留的位元數，相近的顏色歸到同一個色桶
+const BUCKET_BITS: u8 = 3;
+// 記住的封面數上限，超過時清空重新累積
+const MAX_ENTRIES: usize = 2000;
+
+lazy_static! {
+    // 封面網址 -> 主色
+    static ref ACCENTS: RwLock<HashMap<String, Color32>> = RwLock::new(HashMap::new());
+}
+
+// 將像素分到粗略的色桶，取權重最高的色桶的平均色。
+// 權重依飽和度計算，大片黑、白或灰色背景不會蓋過封面的主色
+fn dominant_color(image: &DynamicImage) -> Option<Color32> {
+    let sample = image.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_rgb8();
+    let mut buckets: HashMap<(u8, u8, u8), (f32, [f32; 3])> = HashMap::new();
+    for pixel in sample.pixels() {
+        let [r, g, b] = pixel.0;
+        let max = r.max(g).max(b) as f32;
+        let min = r.min(g).min(b) as f32;
+        // 加上少量基本權重，全灰階的封面仍能取到顏色
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+        let weight = saturation + 0.05;
+        let shift = 8 - BUCKET_BITS;
+        let bucket = buckets
+            .entry((r >> shift, g >> shift, b >> shift))
+            .or_insert((0.0, [0.0; 3]));
+        bucket.0 += weight;
+        bucket.1[0] += r as f32 * weight;
+        bucket.1[1] += g as f32 * weight;
+        bucket.1[2] += b as f32 * weight;
+    }
+    let (weight, sum) = buckets.into_values().max_by(|a, b| a.0.total_cmp(&b.0))?;
+    Some(Color32::from_rgb(
+        (sum[0] / weight) as u8,
+        (sum[1] / weight) as u8,
+        (sum[2] / weight) as u8,
+    ))
+}
+
+// 封面解碼後記錄主色，之後繪製時以網址查詢
+pub fn record_accent(url: &str, image: &DynamicImage) {
+    let color = match dominant_color(image) {
+        Some(color) => color,
+        None => return,
+    };
+    let mut accents = ACCENTS.write().unwrap();
+    if accents.len() >= MAX_ENTRIES {
+        accents.clear();
+    }
+    accents.insert(url.to_string(), color);
+}
+
+pub fn accent_color(url: &str) -> Option<Color32> {
+    ACCENTS.read().unwrap().get(url).copied()
+}
+
+// 往白色混合，amount 為保留的主色比例，讓上面的圖示仍然清楚
+pub fn tint(color: Color32, amount: f32) -> Color32 {
+    let mix = |channel: u8| (255.0 + (channel as f32 - 255.0) * amount).round() as u8;
+    Color32::from_rgb(mix(color.r()), mix(color.g()), mix(color.b()))
+}
@@ -0,0 +1,137 @@
+// 標準庫導入
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::sync::{Mutex, TryLockError};
+
+// 第三方庫導入
+use chrono::Local;
+use lazy_static::lazy_static;
+
+// 本地模組導入
+use crate::updater::CURRENT_VERSION;
+use lib::get_app_data_path;
+
+const LOG_FILE: &str = "output.log";
+const LOG_TAIL_LINES: usize = 50;
+const PENDING_REPORT_FILE: &str = "pending_crash_report.txt";
+
+type FlushHook = Box<dyn Fn() + Send + Sync>;
+
+lazy_static! {
+    static ref FLUSH_HOOKS: Mutex<Vec<(String, FlushHook)>> = Mutex::new(Vec::new());
+}
+
+// 註冊在 panic 時需要寫入磁碟的狀態
+pub fn register_flush_hook<F>(name: &str, hook: F)
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let mut hooks = match FLUSH_HOOKS.lock() {
+        Ok(hooks) => hooks,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    hooks.retain(|(existing, _)| existing != name);
+    hooks.push((name.to_string(), Box::new(hook)));
+}
+
+fn crash_report_dir() -> PathBuf {
+    get_app_data_path().join("crash_reports")
+}
+
+// 安裝 panic hook，寫入崩潰報告並保存狀態後再交給原本的 hook
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report_path = write_crash_report(info);
+        flush_state();
+        if let Some(path) = report_path {
+            eprintln!("崩潰報告已寫入: {:?}", path);
+        }
+        default_hook(info);
+    }));
+}
+
+// 讀取上次崩潰留下的報告路徑，讀取後即清除標記
+pub fn take_pending_crash_report() -> Option<PathBuf> {
+    let marker_path = get_app_data_path().join(PENDING_REPORT_FILE);
+    let content = fs::read_to_string(&marker_path).ok()?;
+    let _ = fs::remove_file(&marker_path);
+    let report_path = PathBuf::from(content.trim());
+    report_path.exists().then_some(report_path)
+}
+
+fn write_crash_report(info: &PanicHookInfo) -> Option<PathBuf> {
+    let report_dir = crash_report_dir();
+    fs::create_dir_all(&report_dir).ok()?;
+
+    let now = Local::now();
+    let report_path = report_dir.join(format!("crash_{}.txt", now.format("%Y%m%d_%H%M%S")));
+
+    let thread = std::thread::current();
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "未知".to_string());
+    let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "未知錯誤".to_string()
+    };
+
+    let report = format!(
+        "Search App 崩潰報告\n\
+         時間: {}\n\
+         版本: {}\n\
+         系統: {} {}\n\
+         執行緒: {}\n\
+         位置: {}\n\
+         訊息: {}\n\n\
+         ===== Backtrace =====\n{}\n\n\
+         ===== 最後 {} 行日誌 =====\n{}\n",
+        now.format("%Y-%m-%d %H:%M:%S"),
+        CURRENT_VERSION,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        thread.name().unwrap_or("unnamed"),
+        location,
+        message,
+        Backtrace::force_capture(),
+        LOG_TAIL_LINES,
+        read_log_tail(),
+    );
+
+    fs::write(&report_path, report).ok()?;
+    let _ = fs::write(
+        get_app_data_path().join(PENDING_REPORT_FILE),
+        report_path.to_string_lossy().as_bytes(),
+    );
+    Some(report_path)
+}
+
+fn read_log_tail() -> String {
+    match fs::read_to_string(LOG_FILE) {
+        Ok(content) => {
+            let lines: Vec<&str> = content.lines().collect();
+            let start = lines.len().saturating_sub(LOG_TAIL_LINES);
+            lines[start..].join("\n")
+        }
+        Err(e) => format!("無法讀取日誌: {}", e),
+    }
+}
+
+fn flush_state() {
+    // panic 可能發生在持有鎖的情況下，避免在 hook 中死鎖
+    let hooks = match FLUSH_HOOKS.try_lock() {
+        Ok(hooks) => hooks,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => return,
+    };
+    for (name, hook) in hooks.iter() {
+        eprintln!("保存狀態: {}", name);
+        hook();
+    }
+}
@@ -0,0 +1,662 @@
+// 標準庫導入
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// 第三方庫導入
+use chrono::{DateTime, Local};
+use futures::future::join_all;
+use lazy_static::lazy_static;
+use log::{error, info};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{field, info_span, Instrument, Metadata, Span, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Registry;
+
+// 本地模組導入
+use crate::cache::format_size;
+use crate::download_options::{available_space, low_disk_space};
+use crate::osu::get_osu_token;
+use crate::rate_limit::record_rate_limit;
+use crate::scheduler::format_local_time;
+use lib::{check_and_refresh_token, read_config, read_login_info};
+
+// 只收集這個 target 的 span，其他套件（hyper 等）的 span 直接略過
+const TARGET: &str = "diagnostics";
+// 保留最近的操作筆數
+const MAX_RECORDS: usize = 500;
+const SLOWEST_COUNT: usize = 20;
+// 檢查鏡像站時的逾時，避免啟動檢查卡住
+const MIRROR_TIMEOUT: Duration = Duration::from_secs(10);
+// 下載用的鏡像站，任何 HTTP 回應都視為可以連線
+const MIRRORS: [(&str, &str); 2] = [
+    ("nerinyan", "https://api.nerinyan.moe"),
+    ("mino", "https://catboy.best"),
+];
+const WRITE_TEST_FILE: &str = ".write_test";
+
+#[derive(Clone, Debug)]
+pub struct OperationRecord {
+    pub service: String,
+    pub operation: String,
+    // HTTP 狀態碼，或沒有回應時的錯誤類型
+    pub status: String,
+    pub duration: Duration,
+    pub finished_at: DateTime<Local>,
+}
+
+impl OperationRecord {
+    pub fn is_error(&self) -> bool {
+        match self.status.parse::<u16>() {
+            Ok(code) => code >= 400,
+            Err(_) => self.status != "成功",
+        }
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS.as_u16().to_string()
+    }
+}
+
+lazy_static! {
+    static ref RECORDS: Mutex<VecDeque<OperationRecord>> = Mutex::new(VecDeque::new());
+}
+
+fn push_record(record: OperationRecord) {
+    let mut records = RECORDS.lock().unwrap();
+    if records.len() >= MAX_RECORDS {
+        records.pop_front();
+    }
+    records.push_back(record);
+}
+
+pub fn recent_operations() -> Vec<OperationRecord> {
+    RECORDS.lock().unwrap().iter().cloned().collect()
+}
+
+pub fn clear_operations() {
+    RECORDS.lock().unwrap().clear();
+}
+
+// 建立一個會被診斷頁面記錄的操作 span，status 之後以 record_status 填入
+pub fn operation_span(service: &'static str, operation: &'static str) -> Span {
+    info_span!(
+        target: TARGET,
+        "operation",
+        service,
+        operation,
+        status = field::Empty
+    )
+}
+
+// 在目前的操作 span 記錄 HTTP 狀態碼
+pub fn record_status(status: StatusCode) {
+    Span::current().record("status", status.as_u16());
+}
+
+// 請求沒有成功送出或回應時記錄錯誤類型
+pub fn record_request_error(error: &reqwest::Error) {
+    match error.status() {
+        Some(status) => record_status(status),
+        None if error.is_timeout() => {
+            Span::current().record("status", "逾時");
+        }
+        None => {
+            Span::current().record("status", "連線失敗");
+        }
+    }
+}
+
+// 送出請求並記錄耗時與狀態碼
+pub async fn send_traced(
+    request: RequestBuilder,
+    service: &'static str,
+    operation: &'static str,
+) -> reqwest::Result<Response> {
+    async move {
+        let result = request.send().await;
+        match &result {
+            Ok(response) => {
+                record_status(response.status());
+                record_rate_limit(service, response.status(), response.headers());
+            }
+            Err(e) => record_request_error(e),
+        }
+        result
+    }
+    .instrument(operation_span(service, operation))
+    .await
+}
+
+// 記錄不經過 reqwest 的操作（例如 rspotify 的呼叫），只區分成功或失敗
+pub async fn traced<T, E>(
+    service: &'static str,
+    operation: &'static str,
+    future: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    async move {
+        let result = future.await;
+        Span::current().record("status", if result.is_ok() { "成功" } else { "失敗" });
+        result
+    }
+    .instrument(operation_span(service, operation))
+    .await
+}
+
+#[derive(Default)]
+struct OperationFields {
+    service: String,
+    operation: String,
+    status: Option<String>,
+}
+
+impl Visit for OperationFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "service" => self.service = value.to_string(),
+            "operation" => self.operation = value.to_string(),
+            "status" => self.status = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "status" {
+            self.status = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
+
+struct OperationTiming {
+    fields: OperationFields,
+    start: Instant,
+}
+
+// 在 span 關閉時計算耗時並存入最近操作列表
+struct DiagnosticsLayer;
+
+impl<S> Layer<S> for DiagnosticsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if metadata.target() == TARGET {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        metadata.target() == TARGET
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = OperationFields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(OperationTiming {
+                fields,
+                start: Instant::now(),
+            });
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<OperationTiming>() {
+                values.record(&mut timing.fields);
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let timing = match ctx.span(&id) {
+            Some(span) => span.extensions_mut().remove::<OperationTiming>(),
+            None => None,
+        };
+        if let Some(timing) = timing {
+            push_record(OperationRecord {
+                service: timing.fields.service,
+                operation: timing.fields.operation,
+                status: timing.fields.status.unwrap_or_else(|| "未完成".to_string()),
+                duration: timing.start.elapsed(),
+                finished_at: Local::now(),
+            });
+        }
+    }
+}
+
+pub fn init_diagnostics() {
+    let subscriber = Registry::default().with(DiagnosticsLayer);
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        error!("無法啟用操作記錄: {:?}", e);
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration.as_secs() >= 1 {
+        format!("{:.2} 秒", duration.as_secs_f32())
+    } else {
+        format!("{} 毫秒", duration.as_millis())
+    }
+}
+
+#[derive(Default)]
+struct ServiceSummary {
+    count: usize,
+    total: Duration,
+    slowest: Duration,
+    errors: usize,
+    rate_limited: usize,
+}
+
+// 啟動時執行的檢查項目，讓問題在開始操作前就顯示出來
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HealthCheck {
+    SpotifyToken,
+    OsuToken,
+    DownloadDirectory,
+    Mirrors,
+    DiskSpace,
+}
+
+impl HealthCheck {
+    pub const ALL: [HealthCheck; 5] = [
+        HealthCheck::SpotifyToken,
+        HealthCheck::OsuToken,
+        HealthCheck::DownloadDirectory,
+        HealthCheck::Mirrors,
+        HealthCheck::DiskSpace,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthCheck::SpotifyToken => "Spotify 登入",
+            HealthCheck::OsuToken => "osu! API",
+            HealthCheck::DownloadDirectory => "下載目錄",
+            HealthCheck::Mirrors => "鏡像站",
+            HealthCheck::DiskSpace => "磁碟空間",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum HealthStatus {
+    Checking,
+    Ok(String),
+    // 不影響使用，但部分功能可能無法運作
+    Warning(String),
+    Failed(String),
+}
+
+impl HealthStatus {
+    fn is_problem(&self) -> bool {
+        matches!(self, HealthStatus::Warning(_) | HealthStatus::Failed(_))
+    }
+}
+
+async fn check_spotify_token(client: &Client, debug_mode: bool) -> HealthStatus {
+    match read_login_info() {
+        Ok(login_infos) if !login_infos.contains_key("spotify") => {
+            return HealthStatus::Warning(String::from("尚未登入"));
+        }
+        Ok(_) => {}
+        Err(e) => return HealthStatus::Failed(e.to_string()),
+    }
+    let config = match read_config(debug_mode) {
+        Ok(config) => config,
+        Err(e) => return HealthStatus::Failed(e.to_string()),
+    };
+    match check_and_refresh_token(client, &config, "spotify").await {
+        Ok(login_info) => HealthStatus::Ok(format!(
+            "有效至 {}",
+            format_local_time(login_info.expiry_time.timestamp())
+        )),
+        Err(e) => HealthStatus::Failed(e.to_string()),
+    }
+}
+
+async fn check_osu_token(client: &Client, debug_mode: bool) -> HealthStatus {
+    match get_osu_token(client, debug_mode).await {
+        Ok(_) => HealthStatus::Ok(String::from("已取得 token")),
+        Err(e) => HealthStatus::Failed(e.to_string()),
+    }
+}
+
+// 實際寫入再刪除一個檔案，權限不足或唯讀的磁碟都能被發現
+fn check_download_directory(directory: &Path) -> HealthStatus {
+    let test_path = directory.join(WRITE_TEST_FILE);
+    let result = fs::create_dir_all(directory)
+        .and_then(|_| fs::write(&test_path, b"ok"))
+        .and_then(|_| fs::remove_file(&test_path));
+    match result {
+        Ok(()) => HealthStatus::Ok(directory.display().to_string()),
+        Err(e) => HealthStatus::Failed(format!("無法寫入 {}: {}", directory.display(), e)),
+    }
+}
+
+async fn check_mirrors(client: &Client) -> HealthStatus {
+    let results = join_all(MIRRORS.iter().map(|(service, url)| async move {
+        let request = client.head(*url).timeout(MIRROR_TIMEOUT);
+        (
+            *service,
+            send_traced(request, *service, "health_check").await,
+        )
+    }))
+    .await;
+    let unreachable: Vec<&str> = results
+        .iter()
+        .filter(|(_, result)| result.is_err())
+        .map(|(service, _)| *service)
+        .collect();
+    if unreachable.is_empty() {
+        HealthStatus::Ok(String::from("全部可以連線"))
+    } else if unreachable.len() == MIRRORS.len() {
+        HealthStatus::Failed(String::from("所有鏡像站都無法連線"))
+    } else {
+        HealthStatus::Warning(format!("無法連線: {}", unreachable.join(", ")))
+    }
+}
+
+fn check_disk_space(directory: &Path) -> HealthStatus {
+    let available = match available_space(directory) {
+        Some(available) => available,
+        None => return HealthStatus::Warning(String::from("無法取得可用空間")),
+    };
+    match low_disk_space(directory) {
+        Some(available_mb) => {
+            HealthStatus::Warning(format!("剩餘 {} MB，低於設定的下限", available_mb))
+        }
+        None => HealthStatus::Ok(format!("可用 {}", format_size(available))),
+    }
+}
+
+// 檔案系統的檢查可能很慢，不在 async 執行緒上進行
+async fn run_blocking(check: impl FnOnce() -> HealthStatus + Send + 'static) -> HealthStatus {
+    match tokio::task::spawn_blocking(check).await {
+        Ok(status) => status,
+        Err(e) => HealthStatus::Failed(format!("檢查中斷: {}", e)),
+    }
+}
+
+async fn run_check(
+    check: HealthCheck,
+    download_directory: PathBuf,
+    debug_mode: bool,
+) -> HealthStatus {
+    let client = Client::new();
+    match check {
+        HealthCheck::SpotifyToken => check_spotify_token(&client, debug_mode).await,
+        HealthCheck::OsuToken => check_osu_token(&client, debug_mode).await,
+        HealthCheck::Mirrors => check_mirrors(&client).await,
+        HealthCheck::DownloadDirectory => {
+            run_blocking(move || check_download_directory(&download_directory)).await
+        }
+        HealthCheck::DiskSpace => run_blocking(move || check_disk_space(&download_directory)).await,
+    }
+}
+
+// 各項檢查的最新結果，檢查在背景平行執行
+struct HealthChecks {
+    results: Arc<Mutex<HashMap<HealthCheck, HealthStatus>>>,
+    download_directory: PathBuf,
+    debug_mode: bool,
+}
+
+impl HealthChecks {
+    fn new() -> Self {
+        Self {
+            results: Arc::new(Mutex::new(HashMap::new())),
+            download_directory: PathBuf::new(),
+            debug_mode: false,
+        }
+    }
+
+    fn run(&self, ctx: &egui::Context, check: HealthCheck) {
+        self.results
+            .lock()
+            .unwrap()
+            .insert(check, HealthStatus::Checking);
+        let results = self.results.clone();
+        let download_directory = self.download_directory.clone();
+        let debug_mode = self.debug_mode;
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let status = run_check(check, download_directory, debug_mode).await;
+            if status.is_problem() {
+                info!("健康檢查 {}: {:?}", check.label(), status);
+            }
+            results.lock().unwrap().insert(check, status);
+            ctx.request_repaint();
+        });
+    }
+
+    fn status(&self, check: HealthCheck) -> HealthStatus {
+        self.results
+            .lock()
+            .unwrap()
+            .get(&check)
+            .cloned()
+            .unwrap_or(HealthStatus::Checking)
+    }
+
+    fn is_running(&self) -> bool {
+        HealthCheck::ALL
+            .iter()
+            .any(|check| matches!(self.status(*check), HealthStatus::Checking))
+    }
+
+    fn has_problems(&self) -> bool {
+        HealthCheck::ALL
+            .iter()
+            .any(|check| self.status(*check).is_problem())
+    }
+
+    // 每列一個檢查項目，有問題的項目可以單獨重試
+    fn render(&self, ui: &mut egui::Ui, id: &str) {
+        egui::Grid::new(id).num_columns(4).show(ui, |ui| {
+            for check in HealthCheck::ALL {
+                let status = self.status(check);
+                match &status {
+                    HealthStatus::Checking => {
+                        ui.spinner();
+                    }
+                    HealthStatus::Ok(_) => {
+                        ui.colored_label(egui::Color32::GREEN, "✔");
+                    }
+                    HealthStatus::Warning(_) => {
+                        ui.colored_label(ui.visuals().warn_fg_color, "⚠");
+                    }
+                    HealthStatus::Failed(_) => {
+                        ui.colored_label(ui.visuals().error_fg_color, "✖");
+                    }
+                }
+                ui.label(check.label());
+                match &status {
+                    HealthStatus::Checking => ui.weak("檢查中..."),
+                    HealthStatus::Ok(message)
+                    | HealthStatus::Warning(message)
+                    | HealthStatus::Failed(message) => ui.label(message),
+                };
+                if status.is_problem() && ui.small_button("重試").clicked() {
+                    self.run(ui.ctx(), check);
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
+
+// 顯示最近最慢的請求與下載，方便排查速率限制與緩慢的鏡像站
+pub struct DiagnosticsWindow {
+    pub show: bool,
+    health: HealthChecks,
+    // 啟動時的精簡狀態面板，全部通過後自動關閉
+    show_health_panel: bool,
+}
+
+impl DiagnosticsWindow {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            health: HealthChecks::new(),
+            show_health_panel: false,
+        }
+    }
+
+    // 平行執行所有檢查，啟動時與設定中的「診斷」共用
+    pub fn run_health_checks(
+        &mut self,
+        ctx: &egui::Context,
+        download_directory: &Path,
+        debug_mode: bool,
+    ) {
+        self.health.download_directory = download_directory.to_path_buf();
+        self.health.debug_mode = debug_mode;
+        for check in HealthCheck::ALL {
+            self.health.run(ctx, check);
+        }
+    }
+
+    pub fn show_health_panel(&mut self) {
+        self.show_health_panel = true;
+    }
+
+    pub fn render_health_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_health_panel {
+            return;
+        }
+        if !self.health.is_running() && !self.health.has_problems() {
+            self.show_health_panel = false;
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("啟動檢查")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+            .show(ctx, |ui| {
+                self.health.render(ui, "health_panel");
+            });
+        self.show_health_panel = open;
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) {
+        if !self.show {
+            return;
+        }
+        let records = recent_operations();
+        let mut open = self.show;
+        egui::Window::new("診斷")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong("健康檢查");
+                    if ui
+                        .add_enabled(!self.health.is_running(), egui::Button::new("重新檢查"))
+                        .clicked()
+                    {
+                        for check in HealthCheck::ALL {
+                            self.health.run(ui.ctx(), check);
+                        }
+                    }
+                });
+                self.health.render(ui, "diagnostics_health");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(format!("最近 {} 筆操作", records.len()));
+                    if ui.small_button("清除").clicked() {
+                        clear_operations();
+                    }
+                });
+                ui.separator();
+                Self::render_summary(ui, &records);
+                ui.separator();
+                Self::render_slowest(ui, &records);
+            });
+        self.show = open;
+    }
+
+    fn render_summary(ui: &mut egui::Ui, records: &[OperationRecord]) {
+        let mut summaries: HashMap<&str, ServiceSummary> = HashMap::new();
+        for record in records {
+            let summary = summaries.entry(record.service.as_str()).or_default();
+            summary.count += 1;
+            summary.total += record.duration;
+            summary.slowest = summary.slowest.max(record.duration);
+            if record.is_error() {
+                summary.errors += 1;
+            }
+            if record.is_rate_limited() {
+                summary.rate_limited += 1;
+            }
+        }
+        let mut services: Vec<_> = summaries.into_iter().collect();
+        services.sort_by(|a, b| a.0.cmp(b.0));
+
+        egui::Grid::new("diagnostics_summary")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["服務", "次數", "平均", "最慢", "錯誤", "429"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for (service, summary) in services {
+                    ui.label(service);
+                    ui.label(summary.count.to_string());
+                    ui.label(format_duration(summary.total / summary.count as u32));
+                    ui.label(format_duration(summary.slowest));
+                    ui.label(summary.errors.to_string());
+                    ui.label(summary.rate_limited.to_string());
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn render_slowest(ui: &mut egui::Ui, records: &[OperationRecord]) {
+        let mut slowest: Vec<&OperationRecord> = records.iter().collect();
+        slowest.sort_by(|a, b| b.duration.cmp(&a.duration));
+        slowest.truncate(SLOWEST_COUNT);
+
+        ui.strong("最慢的操作");
+        egui::ScrollArea::vertical()
+            .max_height(320.0)
+            .show(ui, |ui| {
+                egui::Grid::new("diagnostics_slowest")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["時間", "服務", "操作", "狀態", "耗時"] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for record in slowest {
+                            ui.label(record.finished_at.format("%H:%M:%S").to_string());
+                            ui.label(&record.service);
+                            ui.label(&record.operation);
+                            if record.is_error() {
+                                ui.colored_label(ui.visuals().error_fg_color, &record.status);
+                            } else {
+                                ui.label(&record.status);
+                            }
+                            ui.label(format_duration(record.duration));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
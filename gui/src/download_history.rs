@@ -0,0 +1,339 @@
+// 標準庫導入
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::map_index::{forget_maps, read_metadata};
+use crate::storage::{storage, StorageError};
+use crate::trash::{remove_entry, TrashedItem};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+// 一筆由本程式下載的譜面集紀錄
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DownloadRecord {
+    pub beatmapset_id: i32,
+    pub file_name: String,
+    // Unix 時間（秒）
+    pub downloaded_at: u64,
+}
+
+// 下載目錄中的一個項目，.osz 檔或 osu! 匯入後解壓出的資料夾
+#[derive(Clone, Debug)]
+pub struct DownloadedMap {
+    pub file_name: String,
+    pub beatmapset_id: Option<i32>,
+    pub downloaded_at: u64,
+    // osu! 匯入 .osz 後會解壓成同 ID 開頭的資料夾
+    pub imported: bool,
+}
+
+impl DownloadedMap {
+    pub fn age_days(&self) -> u64 {
+        now_secs().saturating_sub(self.downloaded_at) / SECS_PER_DAY
+    }
+}
+
+// 掃描下載資料夾時發現的問題
+#[derive(Clone, Debug)]
+pub enum IntegrityIssue {
+    // 檔案已不在下載資料夾中
+    Missing,
+    // 無法解壓或找不到 .osu 檔
+    Corrupt(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct IntegrityProblem {
+    pub beatmapset_id: i32,
+    pub file_name: String,
+    pub issue: IntegrityIssue,
+}
+
+impl IntegrityProblem {
+    pub fn describe(&self) -> String {
+        match &self.issue {
+            IntegrityIssue::Missing => format!("遺失: {}", self.file_name),
+            IntegrityIssue::Corrupt(reason) => format!("損毀: {} ({})", self.file_name, reason),
+        }
+    }
+}
+
+lazy_static! {
+    static ref HISTORY: RwLock<Vec<DownloadRecord>> = RwLock::new(load_history());
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn load_history() -> Vec<DownloadRecord> {
+    storage().load_history().unwrap_or_else(|e| {
+        error!("讀取下載紀錄失敗: {:?}", e);
+        Vec::new()
+    })
+}
+
+fn save_history(records: &[DownloadRecord]) {
+    let result = storage().save_history(records);
+    if let Err(e) = result {
+        error!("保存下載紀錄失敗: {:?}", e);
+    }
+}
+
+// 下載完成後記錄，同一個譜面集只保留最新的一筆
+pub fn record_download(beatmapset_id: i32, file_name: &str) {
+    let mut history = HISTORY.write().unwrap();
+    history.retain(|record| record.beatmapset_id != beatmapset_id);
+    history.push(DownloadRecord {
+        beatmapset_id,
+        file_name: file_name.to_string(),
+        downloaded_at: now_secs(),
+    });
+    save_history(&history);
+}
+
+pub fn has_download_record(beatmapset_id: i32) -> bool {
+    HISTORY
+        .read()
+        .unwrap()
+        .iter()
+        .any(|record| record.beatmapset_id == beatmapset_id)
+}
+
+fn leading_id(name: &str) -> Option<i32> {
+    name.split_whitespace().next()?.parse().ok()
+}
+
+// 列出下載目錄中的譜面，下載時間優先使用紀錄，沒有紀錄時使用檔案修改時間
+pub fn downloaded_maps(download_directory: &Path) -> Vec<DownloadedMap> {
+    let entries = match fs::read_dir(download_directory) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let entries: Vec<(String, bool, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+            Some((file_name, metadata.is_dir(), modified))
+        })
+        .collect();
+
+    let imported_ids: HashSet<i32> = entries
+        .iter()
+        .filter(|(_, is_dir, _)| *is_dir)
+        .filter_map(|(name, _, _)| leading_id(name))
+        .collect();
+    let history = HISTORY.read().unwrap();
+
+    entries
+        .into_iter()
+        .filter(|(name, is_dir, _)| {
+            if *is_dir {
+                leading_id(name).is_some()
+            } else {
+                name.ends_with(".osz")
+            }
+        })
+        .map(|(file_name, is_dir, modified)| {
+            let beatmapset_id = leading_id(&file_name);
+            let downloaded_at = history
+                .iter()
+                .find(|record| record.file_name == file_name)
+                .map(|record| record.downloaded_at)
+                .unwrap_or(modified);
+            DownloadedMap {
+                imported: is_dir || beatmapset_id.map_or(false, |id| imported_ids.contains(&id)),
+                file_name,
+                beatmapset_id,
+                downloaded_at,
+            }
+        })
+        .collect()
+}
+
+// 下載目錄中已下載的譜面集 ID，只讀取一次目錄
+// 檔名開頭不是 ID 時（自訂檔名範本）以下載紀錄中的檔名對應
+pub fn downloaded_ids(download_directory: &Path) -> HashSet<i32> {
+    let maps = downloaded_maps(download_directory);
+    let history = HISTORY.read().unwrap();
+    maps.into_iter()
+        .filter_map(|map| {
+            map.beatmapset_id.or_else(|| {
+                history
+                    .iter()
+                    .find(|record| record.file_name == map.file_name)
+                    .map(|record| record.beatmapset_id)
+            })
+        })
+        .collect()
+}
+
+// 大量搜尋結果共用的已下載譜面集，只在下載目錄變動或有下載狀態改變時重新讀取
+pub struct DownloadedIds {
+    ids: HashSet<i32>,
+    // 讀取時下載目錄的修改時間，新增、刪除或改名檔案時會改變
+    modified: Option<SystemTime>,
+    stale: bool,
+}
+
+impl DownloadedIds {
+    pub fn new() -> Self {
+        Self {
+            ids: HashSet::new(),
+            modified: None,
+            stale: true,
+        }
+    }
+
+    // 每一幀呼叫一次，只讀取目錄的修改時間
+    pub fn refresh(&mut self, download_directory: &Path) {
+        let modified = fs::metadata(download_directory)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if self.stale || modified != self.modified {
+            self.ids = downloaded_ids(download_directory);
+            self.modified = modified;
+            self.stale = false;
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    pub fn contains(&self, beatmapset_id: i32) -> bool {
+        self.ids.contains(&beatmapset_id)
+    }
+}
+
+// 下載紀錄以 JSON 保存時的大小，供快取管理顯示
+pub fn history_size() -> u64 {
+    serde_json::to_vec(&*HISTORY.read().unwrap()).map_or(0, |content| content.len() as u64)
+}
+
+// 清除所有下載紀錄（不刪除檔案），回傳清除的筆數
+pub fn clear_history() -> Result<usize, StorageError> {
+    let mut history = HISTORY.write().unwrap();
+    storage().save_history(&[])?;
+    let count = history.len();
+    history.clear();
+    Ok(count)
+}
+
+// 刪除檔案或資料夾並移除對應的下載紀錄，啟用資源回收筒時回傳可復原的項目
+pub fn delete_downloaded_maps(
+    download_directory: &Path,
+    file_names: &[String],
+) -> Vec<TrashedItem> {
+    let mut deleted = Vec::new();
+    let mut trashed = Vec::new();
+    for file_name in file_names {
+        match remove_entry(download_directory, file_name) {
+            Ok(item) => {
+                deleted.push(file_name.clone());
+                trashed.extend(item);
+            }
+            Err(e) => error!(
+                "刪除 {:?} 失敗: {:?}",
+                download_directory.join(file_name),
+                e
+            ),
+        }
+    }
+
+    let mut history = HISTORY.write().unwrap();
+    history.retain(|record| !deleted.contains(&record.file_name));
+    save_history(&history);
+    forget_maps(&deleted);
+    info!(
+        "已刪除 {} / {} 個已下載的圖譜",
+        deleted.len(),
+        file_names.len()
+    );
+    trashed
+}
+
+// 檢查每筆下載紀錄的檔案是否仍存在且可以讀取。
+// 已被 osu! 解壓成資料夾的紀錄改指向資料夾，遺失的紀錄直接移除
+pub fn scan_downloads(download_directory: &Path) -> Vec<IntegrityProblem> {
+    let folders: Vec<String> = match fs::read_dir(download_directory) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| entry.file_type().map_or(false, |kind| kind.is_dir()))
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect(),
+        Err(e) => {
+            error!("無法讀取下載資料夾 {:?}: {:?}", download_directory, e);
+            return Vec::new();
+        }
+    };
+    let records = HISTORY.read().unwrap().clone();
+    let scanned = records.len();
+
+    let mut problems = Vec::new();
+    let mut imported = Vec::new();
+    for record in records {
+        let path = download_directory.join(&record.file_name);
+        if path.exists() {
+            if let Err(e) = read_metadata(&path) {
+                problems.push(IntegrityProblem {
+                    beatmapset_id: record.beatmapset_id,
+                    file_name: record.file_name,
+                    issue: IntegrityIssue::Corrupt(e.to_string()),
+                });
+            }
+            continue;
+        }
+        match folders
+            .iter()
+            .find(|name| leading_id(name) == Some(record.beatmapset_id))
+        {
+            Some(folder) => imported.push((record.beatmapset_id, folder.clone())),
+            None => problems.push(IntegrityProblem {
+                beatmapset_id: record.beatmapset_id,
+                file_name: record.file_name,
+                issue: IntegrityIssue::Missing,
+            }),
+        }
+    }
+
+    let mut history = HISTORY.write().unwrap();
+    for record in history.iter_mut() {
+        if let Some((_, folder)) = imported
+            .iter()
+            .find(|(beatmapset_id, _)| *beatmapset_id == record.beatmapset_id)
+        {
+            record.file_name = folder.clone();
+        }
+    }
+    history.retain(|record| {
+        !problems.iter().any(|problem| {
+            matches!(problem.issue, IntegrityIssue::Missing)
+                && problem.file_name == record.file_name
+        })
+    });
+    if !imported.is_empty() || !problems.is_empty() {
+        save_history(&history);
+    }
+    info!("已掃描 {} 筆下載紀錄，{} 筆有問題", scanned, problems.len());
+    problems
+}
@@ -0,0 +1,367 @@
+// 標準庫導入
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "auto_pause_options.json";
+
+// 每隔多久取樣一次下載速度
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+// 新樣本的權重，數值越小速度顯示越平滑
+const SPEED_SMOOTHING: f64 = 0.3;
+// 用來估算等待中項目大小的最近完成下載數量
+const RECENT_SIZES: usize = 20;
+// 暫停期間檢查是否可以繼續的間隔
+const RESUME_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const BYTES_PER_KB: f64 = 1024.0;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AutoPauseOptions {
+    // 省流模式：手動暫停所有尚未開始的下載
+    pub data_saver: bool,
+    // 連線變慢時自動暫停
+    pub auto_pause: bool,
+    // 總下載速度低於此值（KB/s）視為連線變慢
+    pub min_speed_kbps: u64,
+    // 持續變慢多久才暫停
+    pub slow_seconds: u64,
+    // 自動暫停後多久繼續
+    pub resume_minutes: u64,
+}
+
+impl Default for AutoPauseOptions {
+    fn default() -> Self {
+        Self {
+            data_saver: false,
+            auto_pause: false,
+            min_speed_kbps: 50,
+            slow_seconds: 30,
+            resume_minutes: 10,
+        }
+    }
+}
+
+// 尚未開始的下載被暫停的原因
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PauseReason {
+    DataSaver,
+    SlowConnection { resume_at: Instant },
+}
+
+// download_beatmap 接收檔案內容時回報的進度事件
+#[derive(Clone, Copy, Debug)]
+pub enum ProgressEvent {
+    // 收到回應標頭，total 為伺服器提供的檔案大小
+    Started { total: Option<u64> },
+    // 目前已接收的位元組數
+    Received(u64),
+    Completed,
+    // 失敗、逾時或被取消
+    Stopped,
+}
+
+struct ActiveDownload {
+    total: Option<u64>,
+    downloaded: u64,
+    started_at: Instant,
+    sample_at: Instant,
+    sample_bytes: u64,
+    // 平滑後的速度（位元組/秒），取得第一個樣本前為 None
+    speed: Option<f64>,
+}
+
+impl ActiveDownload {
+    fn remaining(&self) -> Option<u64> {
+        self.total
+            .map(|total| total.saturating_sub(self.downloaded))
+    }
+}
+
+// 單一下載的進度，供下載管理頁面顯示
+#[derive(Clone, Copy, Debug)]
+pub struct ItemProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub eta: Option<Duration>,
+}
+
+impl ItemProgress {
+    pub fn fraction(&self) -> Option<f32> {
+        match self.total {
+            Some(total) if total > 0 => Some(self.downloaded as f32 / total as f32),
+            _ => None,
+        }
+    }
+}
+
+// 依進度事件測量下載速度，估算單一項目與整個隊列的剩餘時間
+#[derive(Default)]
+pub struct DownloadManager {
+    active: HashMap<i32, ActiveDownload>,
+    // 最近完成的檔案大小，用來估算尚未開始的項目
+    recent_sizes: VecDeque<u64>,
+    // 最近一次完成下載的平均速度，沒有進行中的下載時使用
+    last_speed: Option<f64>,
+    // 總速度開始低於門檻的時間
+    slow_since: Option<Instant>,
+    // 因連線變慢而自動暫停，到此時間後繼續
+    paused_until: Option<Instant>,
+}
+
+impl DownloadManager {
+    pub fn handle(&mut self, beatmapset_id: i32, event: ProgressEvent) {
+        let now = Instant::now();
+        match event {
+            ProgressEvent::Started { total } => {
+                self.active.insert(
+                    beatmapset_id,
+                    ActiveDownload {
+                        total,
+                        downloaded: 0,
+                        started_at: now,
+                        sample_at: now,
+                        sample_bytes: 0,
+                        speed: None,
+                    },
+                );
+            }
+            ProgressEvent::Received(downloaded) => {
+                let download = match self.active.get_mut(&beatmapset_id) {
+                    Some(download) => download,
+                    None => return,
+                };
+                download.downloaded = downloaded;
+                let elapsed = now.duration_since(download.sample_at);
+                if elapsed >= SAMPLE_INTERVAL {
+                    let rate = downloaded.saturating_sub(download.sample_bytes) as f64
+                        / elapsed.as_secs_f64();
+                    download.speed = Some(match download.speed {
+                        Some(speed) => speed + (rate - speed) * SPEED_SMOOTHING,
+                        None => rate,
+                    });
+                    download.sample_at = now;
+                    download.sample_bytes = downloaded;
+                    self.check_throughput(now);
+                }
+            }
+            ProgressEvent::Completed => {
+                if let Some(download) = self.active.remove(&beatmapset_id) {
+                    let elapsed = now.duration_since(download.started_at).as_secs_f64();
+                    if elapsed > 0.0 && download.downloaded > 0 {
+                        self.last_speed = Some(download.downloaded as f64 / elapsed);
+                    }
+                    if self.recent_sizes.len() >= RECENT_SIZES {
+                        self.recent_sizes.pop_front();
+                    }
+                    self.recent_sizes.push_back(download.downloaded);
+                }
+            }
+            ProgressEvent::Stopped => {
+                self.active.remove(&beatmapset_id);
+            }
+        }
+    }
+
+    // 總速度持續低於門檻時暫停尚未開始的下載，進行中的下載不受影響
+    fn check_throughput(&mut self, now: Instant) {
+        let options = auto_pause_options();
+        if !options.auto_pause || self.paused_until.is_some() {
+            self.slow_since = None;
+            return;
+        }
+        let speed: f64 = self
+            .active
+            .values()
+            .filter_map(|download| download.speed)
+            .sum();
+        if speed >= options.min_speed_kbps as f64 * BYTES_PER_KB {
+            self.slow_since = None;
+            return;
+        }
+        let slow_since = *self.slow_since.get_or_insert(now);
+        if now.duration_since(slow_since) >= Duration::from_secs(options.slow_seconds) {
+            warn!(
+                "下載速度 {:.0} KB/s 持續低於 {} KB/s，暫停 {} 分鐘",
+                speed / BYTES_PER_KB,
+                options.min_speed_kbps,
+                options.resume_minutes
+            );
+            self.paused_until = Some(now + Duration::from_secs(options.resume_minutes * 60));
+            self.slow_since = None;
+        }
+    }
+
+    pub fn pause_reason(&mut self) -> Option<PauseReason> {
+        if auto_pause_options().data_saver {
+            return Some(PauseReason::DataSaver);
+        }
+        match self.paused_until {
+            Some(resume_at) if resume_at > Instant::now() => {
+                Some(PauseReason::SlowConnection { resume_at })
+            }
+            Some(_) => {
+                info!("自動暫停時間已到，繼續下載");
+                self.paused_until = None;
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn item_progress(&self, beatmapset_id: i32) -> Option<ItemProgress> {
+        let download = self.active.get(&beatmapset_id)?;
+        let eta = match (download.remaining(), download.speed) {
+            (Some(remaining), Some(speed)) if speed > 0.0 => {
+                Some(Duration::from_secs_f64(remaining as f64 / speed))
+            }
+            _ => None,
+        };
+        Some(ItemProgress {
+            downloaded: download.downloaded,
+            total: download.total,
+            eta,
+        })
+    }
+
+    // 尚未知道大小的項目以最近完成的平均大小估算
+    fn average_size(&self) -> Option<u64> {
+        let sizes: Vec<u64> = if self.recent_sizes.is_empty() {
+            self.active
+                .values()
+                .filter_map(|download| download.total)
+                .collect()
+        } else {
+            self.recent_sizes.iter().copied().collect()
+        };
+        if sizes.is_empty() {
+            None
+        } else {
+            Some(sizes.iter().sum::<u64>() / sizes.len() as u64)
+        }
+    }
+
+    // 同時下載的項目共用頻寬，以目前的總速度估算整個隊列的剩餘時間
+    pub fn queue_eta(&self, downloading: &[i32], waiting: usize) -> Option<Duration> {
+        let average_size = self.average_size()?;
+        let remaining: u64 = downloading
+            .iter()
+            .map(|id| {
+                self.active
+                    .get(id)
+                    .and_then(ActiveDownload::remaining)
+                    .unwrap_or(average_size)
+            })
+            .sum::<u64>()
+            + average_size * waiting as u64;
+
+        let active_speed: f64 = self
+            .active
+            .values()
+            .filter_map(|download| download.speed)
+            .sum();
+        let speed = if active_speed > 0.0 {
+            active_speed
+        } else {
+            self.last_speed?
+        };
+        Some(Duration::from_secs_f64(remaining as f64 / speed))
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<AutoPauseOptions> =
+        RwLock::new(load_config(OPTIONS_FILE).unwrap_or_default());
+    static ref MANAGER: Mutex<DownloadManager> = Mutex::new(DownloadManager::default());
+}
+
+pub fn auto_pause_options() -> AutoPauseOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_auto_pause_options(options: AutoPauseOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存自動暫停選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+pub fn report_progress(beatmapset_id: i32, event: ProgressEvent) {
+    MANAGER.lock().unwrap().handle(beatmapset_id, event);
+}
+
+pub fn item_progress(beatmapset_id: i32) -> Option<ItemProgress> {
+    MANAGER.lock().unwrap().item_progress(beatmapset_id)
+}
+
+pub fn queue_eta(downloading: &[i32], waiting: usize) -> Option<Duration> {
+    MANAGER.lock().unwrap().queue_eta(downloading, waiting)
+}
+
+pub fn pause_reason() -> Option<PauseReason> {
+    MANAGER.lock().unwrap().pause_reason()
+}
+
+// 取消自動暫停，省流模式需要在設定中關閉
+pub fn resume_downloads() {
+    MANAGER.lock().unwrap().paused_until = None;
+}
+
+// 下載處理器在開始每個下載前呼叫，暫停期間等待
+pub async fn wait_until_resumed() {
+    while pause_reason().is_some() {
+        tokio::time::sleep(RESUME_POLL_INTERVAL).await;
+    }
+}
+
+// 下載期間持有，沒有呼叫 complete 就被丟棄（失敗或逾時取消）時移除進度
+pub struct ProgressTracker {
+    beatmapset_id: i32,
+    completed: bool,
+}
+
+impl ProgressTracker {
+    pub fn start(beatmapset_id: i32, total: Option<u64>) -> Self {
+        report_progress(beatmapset_id, ProgressEvent::Started { total });
+        Self {
+            beatmapset_id,
+            completed: false,
+        }
+    }
+
+    pub fn received(&self, downloaded: u64) {
+        report_progress(self.beatmapset_id, ProgressEvent::Received(downloaded));
+    }
+
+    pub fn complete(mut self) {
+        self.completed = true;
+        report_progress(self.beatmapset_id, ProgressEvent::Completed);
+    }
+}
+
+impl Drop for ProgressTracker {
+    fn drop(&mut self) {
+        if !self.completed {
+            report_progress(self.beatmapset_id, ProgressEvent::Stopped);
+        }
+    }
+}
+
+pub fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    if secs < 60 {
+        format!("{} 秒", secs.max(1))
+    } else if secs < 60 * 60 {
+        format!("{} 分鐘", (secs + 59) / 60)
+    } else {
+        format!("{} 小時 {} 分鐘", secs / 3600, secs % 3600 / 60)
+    }
+}
@@ -0,0 +1,130 @@
+// 標準庫導入
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// 第三方庫導入
+use log::{error, info};
+
+// 本地模組導入
+use crate::download_history::downloaded_maps;
+
+// 更換下載目錄時處理舊目錄中圖譜的方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MigrationMode {
+    Move,
+    // 保留舊目錄中的檔案
+    Copy,
+}
+
+impl MigrationMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MigrationMode::Move => "搬移",
+            MigrationMode::Copy => "複製",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MigrationProgress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+}
+
+impl MigrationProgress {
+    // 沒有進行中的搬移時回傳 None，否則回傳（已完成, 總數）
+    pub fn counts(&self) -> Option<(usize, usize)> {
+        let total = self.total.load(Ordering::Relaxed);
+        if total == 0 {
+            return None;
+        }
+        Some((self.done.load(Ordering::Relaxed).min(total), total))
+    }
+
+    fn start(&self, total: usize) {
+        self.done.store(0, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    fn finish(&self) {
+        self.total.store(0, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+pub struct MigrationReport {
+    pub mode: MigrationMode,
+    pub migrated: usize,
+    // 新目錄已有同名檔案，不覆蓋
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+// 舊目錄中要帶到新目錄的 .osz；osu! 解壓出的資料夾已匯入遊戲，不需要搬移
+pub fn migration_candidates(from: &Path) -> Vec<String> {
+    downloaded_maps(from)
+        .into_iter()
+        .map(|map| map.file_name)
+        .filter(|file_name| file_name.ends_with(".osz"))
+        .collect()
+}
+
+// 跨磁碟時無法直接改名，改為複製後刪除
+fn move_file(source: &Path, target: &Path) -> io::Result<()> {
+    match fs::rename(source, target) {
+        Ok(()) => Ok(()),
+        Err(_) => {
+            fs::copy(source, target)?;
+            fs::remove_file(source)
+        }
+    }
+}
+
+// 下載紀錄與圖譜索引都以檔名記錄，檔名不變，搬移後不需要另外更新
+pub fn migrate_downloads(
+    from: &Path,
+    to: &Path,
+    file_names: &[String],
+    mode: MigrationMode,
+    progress: &MigrationProgress,
+) -> MigrationReport {
+    progress.start(file_names.len());
+    let mut report = MigrationReport {
+        mode,
+        migrated: 0,
+        skipped: Vec::new(),
+        failed: Vec::new(),
+    };
+    for file_name in file_names {
+        let source = from.join(file_name);
+        let target = to.join(file_name);
+        if target.exists() {
+            report.skipped.push(file_name.clone());
+        } else {
+            let result = match mode {
+                MigrationMode::Move => move_file(&source, &target),
+                MigrationMode::Copy => fs::copy(&source, &target).map(|_| ()),
+            };
+            match result {
+                Ok(()) => report.migrated += 1,
+                Err(e) => {
+                    error!("{}圖譜 {:?} 失敗: {:?}", mode.label(), source, e);
+                    report.failed.push(file_name.clone());
+                }
+            }
+        }
+        progress.done.fetch_add(1, Ordering::Relaxed);
+    }
+    progress.finish();
+    info!(
+        "已從 {:?} {} {} / {} 個圖譜至 {:?}",
+        from,
+        mode.label(),
+        report.migrated,
+        file_names.len(),
+        to
+    );
+    report
+}
@@ -0,0 +1,82 @@
+// 標準庫導入
+use std::path::Path;
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::error;
+use serde::{Deserialize, Serialize};
+use sysinfo::Disks;
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+// 檔名範本與清理和 osu_spotify_core 的 DownloadJob 共用
+pub use osu_spotify_core::{
+    format_filename, unique_path, BeatmapsetNames, DEFAULT_FILENAME_TEMPLATE,
+};
+
+const OPTIONS_FILE: &str = "download_options.json";
+// 下載前目錄所在磁碟至少要保留的空間，譜面集通常在 50MB 以內
+pub const DEFAULT_MIN_FREE_SPACE_MB: u64 = 500;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DownloadOptions {
+    // 可使用 {id}、{artist}、{title}
+    pub filename_template: String,
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            min_free_space_mb: DEFAULT_MIN_FREE_SPACE_MB,
+        }
+    }
+}
+
+fn default_min_free_space_mb() -> u64 {
+    DEFAULT_MIN_FREE_SPACE_MB
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<DownloadOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> DownloadOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn download_options() -> DownloadOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_download_options(options: DownloadOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存下載選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 目錄所在磁碟的可用空間（位元組），找不到對應的磁碟時回傳 None
+pub fn available_space(directory: &Path) -> Option<u64> {
+    // 不使用 canonicalize，Windows 上會產生 \\?\ 前綴而無法與掛載點比對
+    let disks = Disks::new_with_refreshed_list();
+    // 掛載點可能互相包含，取最長的符合者
+    disks
+        .list()
+        .iter()
+        .filter(|disk| directory.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+// 可用空間低於設定的下限時回傳剩餘的 MB 數
+pub fn low_disk_space(directory: &Path) -> Option<u64> {
+    let available_mb = available_space(directory)? / BYTES_PER_MB;
+    (available_mb < download_options().min_free_space_mb).then_some(available_mb)
+}
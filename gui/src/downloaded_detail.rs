@@ -0,0 +1,353 @@
+// 標準庫導入
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// 第三方庫導入
+use egui::TextureHandle;
+use log::error;
+use reqwest::Client;
+
+// 本地模組導入
+use crate::cache::format_size;
+use crate::download_history::downloaded_maps;
+use crate::map_index::read_metadata;
+use crate::match_memory::source_track;
+use crate::osu::{get_beatmapset_by_id, get_osu_token, Beatmapset};
+use crate::osu_client::import_osz;
+use crate::scheduler::format_local_time;
+use crate::texturequeue::{downscale_image, fetch_cover_image, THUMBNAIL_SIZE};
+
+const COVER_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 需要主畫面處理的操作
+pub enum DetailAction {
+    // 刪除現有檔案後重新加入下載隊列
+    Redownload {
+        beatmapset_id: i32,
+        file_name: String,
+    },
+    // 以歌手與歌名搜尋其他譜面
+    FindSimilar(String),
+}
+
+// 檔案狀態，開啟詳細資料時檢查一次
+#[derive(Clone, Copy, PartialEq)]
+enum FileState {
+    Ok,
+    Missing,
+    Corrupt,
+}
+
+// 已下載圖譜的詳細資料視窗
+pub struct DownloadedMapDetail {
+    pub show: bool,
+    file_name: String,
+    path: PathBuf,
+    beatmapset_id: Option<i32>,
+    size: Option<u64>,
+    downloaded_at: Option<u64>,
+    file_state: FileState,
+    source_track: Option<String>,
+    // 依 ID 重新取得的譜面集，None 表示仍在載入
+    beatmapset: Arc<Mutex<Option<Result<Beatmapset, String>>>>,
+    cover: Arc<Mutex<Option<TextureHandle>>>,
+}
+
+impl DownloadedMapDetail {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            file_name: String::new(),
+            path: PathBuf::new(),
+            beatmapset_id: None,
+            size: None,
+            downloaded_at: None,
+            file_state: FileState::Ok,
+            source_track: None,
+            beatmapset: Arc::new(Mutex::new(None)),
+            cover: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn open(&mut self, ctx: &egui::Context, download_directory: &Path, file_name: &str) {
+        let path = download_directory.join(file_name);
+        let record = downloaded_maps(download_directory)
+            .into_iter()
+            .find(|map| map.file_name == file_name);
+        let file_state = if !path.exists() {
+            FileState::Missing
+        } else if read_metadata(&path).is_err() {
+            FileState::Corrupt
+        } else {
+            FileState::Ok
+        };
+
+        self.show = true;
+        self.file_name = file_name.to_string();
+        self.beatmapset_id = record
+            .as_ref()
+            .and_then(|map| map.beatmapset_id)
+            .or_else(|| file_name.split_whitespace().next()?.parse().ok());
+        self.size = entry_size(&path);
+        self.downloaded_at = record.map(|map| map.downloaded_at);
+        self.file_state = file_state;
+        self.source_track = self.beatmapset_id.and_then(source_track);
+        self.path = path;
+        self.beatmapset = Arc::new(Mutex::new(None));
+        self.cover = Arc::new(Mutex::new(None));
+
+        let beatmapset_id = match self.beatmapset_id {
+            Some(id) => id,
+            None => {
+                *self.beatmapset.lock().unwrap() = Some(Err(String::from("檔名中沒有譜面集 ID")));
+                return;
+            }
+        };
+        let beatmapset = self.beatmapset.clone();
+        let cover = self.cover.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let client = Client::new();
+            let result = match get_osu_token(&client, false).await {
+                Ok(token) => {
+                    get_beatmapset_by_id(&client, &token, &beatmapset_id.to_string(), false).await
+                }
+                Err(e) => Err(e),
+            };
+            let cover_url = match &result {
+                Ok(loaded) => loaded.covers.card_2x.clone().or(loaded.covers.card.clone()),
+                Err(_) => None,
+            };
+            *beatmapset.lock().unwrap() = Some(result.map_err(|e| e.to_string()));
+            ctx.request_repaint();
+
+            if let Some(url) = cover_url {
+                match fetch_cover_image(&client, &url, COVER_TIMEOUT).await {
+                    Ok(image) => {
+                        let image = downscale_image(image, THUMBNAIL_SIZE).to_rgba8();
+                        let color_image = egui::ColorImage::from_rgba_unmultiplied(
+                            [image.width() as usize, image.height() as usize],
+                            &image,
+                        );
+                        let texture = ctx.load_texture(
+                            format!("downloaded_detail_{}", beatmapset_id),
+                            color_image,
+                            Default::default(),
+                        );
+                        *cover.lock().unwrap() = Some(texture);
+                        ctx.request_repaint();
+                    }
+                    Err(e) => error!("載入譜面集 {} 的封面失敗: {:?}", beatmapset_id, e),
+                }
+            }
+        });
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<DetailAction> {
+        if !self.show {
+            return None;
+        }
+        let mut open = self.show;
+        let mut action = None;
+        let beatmapset = self.beatmapset.lock().unwrap().clone();
+        let cover = self.cover.lock().unwrap().clone();
+
+        egui::Window::new("圖譜詳細資料")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    match &cover {
+                        Some(texture) => {
+                            let size = texture.size_vec2();
+                            let height = 80.0;
+                            ui.image(egui::load::SizedTexture::new(
+                                texture.id(),
+                                egui::vec2(size.x * height / size.y, height),
+                            ));
+                        }
+                        None if beatmapset.is_none() => {
+                            ui.add_sized([80.0, 80.0], egui::Spinner::new());
+                        }
+                        // 沒有封面時不顯示
+                        None => {}
+                    }
+                    ui.vertical(|ui| match &beatmapset {
+                        Some(Ok(beatmapset)) => {
+                            ui.label(egui::RichText::new(&beatmapset.title).strong().size(16.0));
+                            ui.label(&beatmapset.artist);
+                            ui.label(
+                                egui::RichText::new(format!("by {}", beatmapset.creator)).small(),
+                            );
+                        }
+                        Some(Err(e)) => {
+                            ui.label(egui::RichText::new(&self.file_name).strong());
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                format!("無法取得譜面資料: {}", e),
+                            );
+                        }
+                        None => {
+                            ui.label(egui::RichText::new(&self.file_name).strong());
+                            ui.label("正在取得譜面資料...");
+                        }
+                    });
+                });
+                ui.separator();
+
+                egui::Grid::new("downloaded_detail_info")
+                    .num_columns(2)
+                    .spacing([12.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label("檔案");
+                        ui.label(&self.file_name);
+                        ui.end_row();
+
+                        ui.label("大小");
+                        ui.label(self.size.map_or(String::from("-"), format_size));
+                        ui.end_row();
+
+                        ui.label("下載時間");
+                        ui.label(
+                            self.downloaded_at
+                                .map_or(String::from("-"), format_timestamp),
+                        );
+                        ui.end_row();
+
+                        ui.label("來源曲目");
+                        ui.label(self.source_track.as_deref().unwrap_or("-"));
+                        ui.end_row();
+
+                        ui.label("狀態");
+                        match self.file_state {
+                            FileState::Ok => ui.label("正常"),
+                            FileState::Missing => {
+                                ui.colored_label(egui::Color32::RED, "檔案已不存在")
+                            }
+                            FileState::Corrupt => {
+                                ui.colored_label(egui::Color32::RED, "檔案已損毀，無法讀取")
+                            }
+                        };
+                        ui.end_row();
+                    });
+
+                if let Some(Ok(beatmapset)) = &beatmapset {
+                    ui.separator();
+                    ui.label(egui::RichText::new("難度").strong());
+                    let mut beatmaps = beatmapset.beatmaps.clone();
+                    beatmaps.sort_by(|a, b| a.difficulty_rating.total_cmp(&b.difficulty_rating));
+                    for beatmap in &beatmaps {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("★ {:.2}", beatmap.difficulty_rating));
+                            ui.label(&beatmap.version);
+                            ui.label(egui::RichText::new(&beatmap.mode).small().weak());
+                        });
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal_wrapped(|ui| {
+                    if ui
+                        .add_enabled(
+                            self.file_state != FileState::Missing,
+                            egui::Button::new("在 osu! 中開啟"),
+                        )
+                        .on_hover_text("尚未匯入的 .osz 會交給 osu! 匯入")
+                        .clicked()
+                    {
+                        self.open_in_osu();
+                    }
+                    if ui
+                        .add_enabled(
+                            self.file_state != FileState::Missing,
+                            egui::Button::new("在資料夾中顯示"),
+                        )
+                        .clicked()
+                    {
+                        reveal_in_folder(&self.path);
+                    }
+                    if let Some(beatmapset_id) = self.beatmapset_id {
+                        if ui
+                            .add_enabled(
+                                self.file_state != FileState::Ok,
+                                egui::Button::new("重新下載"),
+                            )
+                            .on_hover_text("檔案遺失或損毀時重新下載")
+                            .clicked()
+                        {
+                            action = Some(DetailAction::Redownload {
+                                beatmapset_id,
+                                file_name: self.file_name.clone(),
+                            });
+                        }
+                    }
+                    if let Some(Ok(beatmapset)) = &beatmapset {
+                        if ui.button("尋找相似譜面").clicked() {
+                            action = Some(DetailAction::FindSimilar(format!(
+                                "{} {}",
+                                beatmapset.artist, beatmapset.title
+                            )));
+                        }
+                    }
+                });
+            });
+
+        self.show = open && action.is_none();
+        action
+    }
+
+    // .osz 交給設定的 osu! 用戶端匯入，已匯入的資料夾改開啟譜面頁面
+    fn open_in_osu(&self) {
+        if !self.path.is_dir() {
+            if let Err(e) = import_osz(&self.path) {
+                error!("無法在 osu! 中開啟 {:?}: {}", self.path, e);
+            }
+            return;
+        }
+        let result = match self.beatmapset_id {
+            Some(id) => open::that(format!("https://osu.ppy.sh/beatmapsets/{}", id)),
+            None => open::that(&self.path),
+        };
+        if let Err(e) = result {
+            error!("無法在 osu! 中開啟 {:?}: {:?}", self.path, e);
+        }
+    }
+}
+
+// 資料夾計算內部檔案的總大小
+fn entry_size(path: &Path) -> Option<u64> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return Some(metadata.len());
+    }
+    let entries = fs::read_dir(path).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter_map(|entry| entry_size(&entry.path()))
+            .sum(),
+    )
+}
+
+// 開啟所在資料夾並選取項目，不支援選取的平台只開啟資料夾
+fn reveal_in_folder(path: &Path) {
+    let result = if cfg!(target_os = "windows") {
+        std::process::Command::new("explorer")
+            .arg(format!("/select,{}", path.display()))
+            .spawn()
+            .map(|_| ())
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+            .arg("-R")
+            .arg(path)
+            .spawn()
+            .map(|_| ())
+    } else {
+        open::that(path.parent().unwrap_or(path))
+    };
+    if let Err(e) = result {
+        error!("無法開啟 {:?} 所在的資料夾: {:?}", path, e);
+    }
+}
@@ -0,0 +1,171 @@
+// 標準庫導入
+use std::time::{Duration, Instant};
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use regex::Regex;
+
+// 速率限制訊息沒有附上等待時間時的預設值
+const DEFAULT_RATE_LIMIT_SECS: u64 = 30;
+
+lazy_static! {
+    static ref RETRY_AFTER: Regex = Regex::new(r"(?i)retry[- _]after\D{0,3}(\d+)").unwrap();
+}
+
+// 錯誤分類，決定橫幅上提供的操作
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    AuthExpired,
+    RateLimited { retry_after: Duration },
+    Network,
+    Other,
+}
+
+pub enum ErrorBannerAction {
+    Reauthorize,
+    Retry,
+    Dismiss,
+}
+
+// 錯誤多半已轉成字串，只能依訊息內容判斷類型
+pub fn classify_error(message: &str) -> ErrorKind {
+    let lower = message.to_lowercase();
+    let contains_any = |keywords: &[&str]| keywords.iter().any(|k| lower.contains(k));
+
+    if contains_any(&["429", "too many requests", "rate limit", "速率限制"]) {
+        let secs = RETRY_AFTER
+            .captures(message)
+            .and_then(|captures| captures[1].parse().ok())
+            .unwrap_or(DEFAULT_RATE_LIMIT_SECS);
+        return ErrorKind::RateLimited {
+            retry_after: Duration::from_secs(secs),
+        };
+    }
+    if contains_any(&[
+        "401",
+        "unauthorized",
+        "invalid_token",
+        "token expired",
+        "token 無效",
+        "需要重新授權",
+        "授權錯誤",
+    ]) {
+        return ErrorKind::AuthExpired;
+    }
+    if contains_any(&[
+        "error sending request",
+        "timed out",
+        "timeout",
+        "connection",
+        "dns",
+        "請求錯誤",
+        "請求失敗",
+        "網路",
+        "無法獲取 token",
+    ]) {
+        return ErrorKind::Network;
+    }
+    ErrorKind::Other
+}
+
+// 取代原本單行錯誤文字的錯誤橫幅
+pub struct ErrorBanner {
+    message: String,
+    kind: ErrorKind,
+    rate_limited_until: Option<Instant>,
+}
+
+impl ErrorBanner {
+    pub fn new() -> Self {
+        Self {
+            message: String::new(),
+            kind: ErrorKind::Other,
+            rate_limited_until: None,
+        }
+    }
+
+    // message 為目前的錯誤訊息，空字串代表沒有錯誤
+    pub fn render(&mut self, ui: &mut egui::Ui, message: &str) -> Option<ErrorBannerAction> {
+        if message.is_empty() {
+            self.message.clear();
+            self.rate_limited_until = None;
+            return None;
+        }
+        if message != self.message {
+            self.message = message.to_string();
+            self.kind = classify_error(message);
+            self.rate_limited_until = match &self.kind {
+                ErrorKind::RateLimited { retry_after } => Some(Instant::now() + *retry_after),
+                _ => None,
+            };
+        }
+
+        let mut action = None;
+        let (icon, title) = match &self.kind {
+            ErrorKind::AuthExpired => ("🔒", "授權已過期"),
+            ErrorKind::RateLimited { .. } => ("⏳", "請求過於頻繁"),
+            ErrorKind::Network => ("📡", "網路連線錯誤"),
+            ErrorKind::Other => ("⚠", "發生錯誤"),
+        };
+
+        egui::Frame::none()
+            .fill(ui.visuals().error_fg_color.linear_multiply(0.15))
+            .stroke(egui::Stroke::new(1.0, ui.visuals().error_fg_color))
+            .rounding(5.0)
+            .inner_margin(egui::Margin::same(8.0))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(icon).size(18.0));
+                    ui.vertical(|ui| {
+                        ui.label(
+                            egui::RichText::new(title)
+                                .strong()
+                                .color(ui.visuals().error_fg_color),
+                        );
+                        ui.label(&self.message);
+                    });
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✖").on_hover_text("關閉").clicked() {
+                            action = Some(ErrorBannerAction::Dismiss);
+                        }
+                        match &self.kind {
+                            ErrorKind::AuthExpired => {
+                                if ui.button("重新授權").clicked() {
+                                    action = Some(ErrorBannerAction::Reauthorize);
+                                }
+                            }
+                            ErrorKind::RateLimited { .. } => {
+                                let remaining = self
+                                    .rate_limited_until
+                                    .map(|until| until.saturating_duration_since(Instant::now()))
+                                    .unwrap_or_default();
+                                if remaining.is_zero() {
+                                    if ui.button("重試").clicked() {
+                                        action = Some(ErrorBannerAction::Retry);
+                                    }
+                                } else {
+                                    ui.add_enabled(
+                                        false,
+                                        egui::Button::new(format!(
+                                            "{} 秒後可重試",
+                                            remaining.as_secs() + 1
+                                        )),
+                                    );
+                                    ui.ctx().request_repaint_after(Duration::from_secs(1));
+                                }
+                            }
+                            ErrorKind::Network => {
+                                if ui.button("重試").clicked() {
+                                    action = Some(ErrorBannerAction::Retry);
+                                }
+                            }
+                            ErrorKind::Other => {}
+                        }
+                    });
+                });
+            });
+        ui.add_space(5.0);
+
+        action
+    }
+}
@@ -0,0 +1,367 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use log::{error, info};
+use rspotify::prelude::Id;
+use rspotify::AuthCodeSpotify;
+
+// 本地模組導入
+use crate::release_radar::{match_tracks, render_track, ReleaseTrack, ReleaseTracksState};
+use crate::spotify::{get_artist_top_tracks_for_user, get_followed_artists, set_artist_followed};
+use crate::DownloadStatus;
+
+// 快速搜尋時取熱門曲目的前幾首
+const TOP_TRACK_COUNT: usize = 5;
+
+#[derive(Clone, Debug)]
+pub struct FollowedArtist {
+    pub id: String,
+    pub name: String,
+    pub genres: Vec<String>,
+    pub followers: u32,
+    // 取消追蹤後仍留在列表中，方便復原
+    pub following: bool,
+    // 正在送出追蹤或取消追蹤的請求
+    pub updating: bool,
+    pub top_tracks: ReleaseTracksState,
+}
+
+// 追蹤的歌手側邊選單頁面
+pub struct FollowedArtists {
+    pub show: bool,
+    artists: Arc<Mutex<Vec<FollowedArtist>>>,
+    is_loading: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<String>>>,
+    filter: String,
+}
+
+enum ArtistAction {
+    SetFollowed(String, bool),
+    SearchTopTracks(String),
+}
+
+impl FollowedArtists {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            artists: Arc::new(Mutex::new(Vec::new())),
+            is_loading: Arc::new(AtomicBool::new(false)),
+            error: Arc::new(Mutex::new(None)),
+            filter: String::new(),
+        }
+    }
+
+    // 已配對的譜面集 ID，供呼叫端查詢下載狀態
+    pub fn matched_ids(&self) -> Vec<i32> {
+        self.artists
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|artist| match &artist.top_tracks {
+                ReleaseTracksState::Checked(tracks) => Some(tracks),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|track| track.best_match.as_ref())
+            .map(|scored| scored.beatmapset.id)
+            .collect()
+    }
+
+    // 打開頁面時呼叫，第一次會載入追蹤的歌手
+    pub fn open(&mut self, ctx: egui::Context, spotify: Option<AuthCodeSpotify>) {
+        self.show = true;
+        if self.artists.lock().unwrap().is_empty() {
+            self.refresh(ctx, spotify);
+        }
+    }
+
+    fn refresh(&mut self, ctx: egui::Context, spotify: Option<AuthCodeSpotify>) {
+        let spotify = match spotify {
+            Some(spotify) => spotify,
+            None => {
+                *self.error.lock().unwrap() = Some("請先登入 Spotify".to_string());
+                return;
+            }
+        };
+        if self.is_loading.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let artists = self.artists.clone();
+        let is_loading = self.is_loading.clone();
+        let error = self.error.clone();
+        *error.lock().unwrap() = None;
+
+        tokio::spawn(async move {
+            match get_followed_artists(&spotify).await {
+                Ok(followed) => {
+                    info!("取得追蹤的歌手: {} 位", followed.len());
+                    let mut followed: Vec<FollowedArtist> = followed
+                        .into_iter()
+                        .map(|artist| FollowedArtist {
+                            id: artist.id.id().to_string(),
+                            name: artist.name,
+                            genres: artist.genres,
+                            followers: artist.followers.total,
+                            following: true,
+                            updating: false,
+                            top_tracks: ReleaseTracksState::NotChecked,
+                        })
+                        .collect();
+                    followed.sort_by_key(|artist| artist.name.to_lowercase());
+                    *artists.lock().unwrap() = followed;
+                }
+                Err(e) => {
+                    error!("取得追蹤的歌手失敗: {:?}", e);
+                    // 舊的登入沒有 user-follow-read 權限，需要重新登入
+                    *error.lock().unwrap() =
+                        Some(format!("取得追蹤的歌手失敗，請嘗試重新登入 Spotify: {}", e));
+                }
+            }
+            is_loading.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    fn update_artist(
+        artists: &Mutex<Vec<FollowedArtist>>,
+        artist_id: &str,
+        update: impl FnOnce(&mut FollowedArtist),
+    ) {
+        if let Some(artist) = artists
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|artist| artist.id == artist_id)
+        {
+            update(artist);
+        }
+    }
+
+    fn set_followed(
+        &self,
+        artist_id: String,
+        follow: bool,
+        ctx: egui::Context,
+        spotify: AuthCodeSpotify,
+    ) {
+        let artists = self.artists.clone();
+        let error = self.error.clone();
+        Self::update_artist(&artists, &artist_id, |artist| artist.updating = true);
+
+        tokio::spawn(async move {
+            let result = set_artist_followed(&spotify, &artist_id, follow).await;
+            if let Err(e) = &result {
+                error!("更新歌手 {} 的追蹤狀態失敗: {:?}", artist_id, e);
+                // 舊的登入沒有 user-follow-modify 權限，需要重新登入
+                *error.lock().unwrap() =
+                    Some(format!("更新追蹤狀態失敗，請嘗試重新登入 Spotify: {}", e));
+            }
+            Self::update_artist(&artists, &artist_id, |artist| {
+                artist.updating = false;
+                if result.is_ok() {
+                    artist.following = follow;
+                    info!(
+                        "{} 歌手: {}",
+                        if follow { "追蹤" } else { "取消追蹤" },
+                        artist.name
+                    );
+                }
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    // 取得歌手的熱門曲目並為前幾首搜尋 osu! 譜面
+    fn search_top_tracks(
+        &self,
+        artist_id: String,
+        ctx: egui::Context,
+        spotify: AuthCodeSpotify,
+        debug_mode: bool,
+    ) {
+        let artists = self.artists.clone();
+        Self::update_artist(&artists, &artist_id, |artist| {
+            artist.top_tracks = ReleaseTracksState::Checking
+        });
+
+        tokio::spawn(async move {
+            let result = async {
+                let tracks = get_artist_top_tracks_for_user(&spotify, &artist_id)
+                    .await?
+                    .into_iter()
+                    .take(TOP_TRACK_COUNT)
+                    .map(|track| ReleaseTrack::new(track.name, &track.artists, track.duration))
+                    .collect();
+                match_tracks(tracks, debug_mode).await
+            }
+            .await;
+            let state = match result {
+                Ok(tracks) => ReleaseTracksState::Checked(tracks),
+                Err(e) => {
+                    error!("搜尋歌手 {} 熱門曲目的譜面失敗: {:?}", artist_id, e);
+                    ReleaseTracksState::Failed(e.to_string())
+                }
+            };
+            Self::update_artist(&artists, &artist_id, |artist| artist.top_tracks = state);
+            ctx.request_repaint();
+        });
+    }
+
+    // 渲染頁面，回傳使用者要求下載的譜面集 ID
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        spotify_client: &Arc<Mutex<Option<AuthCodeSpotify>>>,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        debug_mode: bool,
+    ) -> Vec<i32> {
+        let mut download_requests = Vec::new();
+        let ctx = ui.ctx().clone();
+        let is_loading = self.is_loading.load(Ordering::SeqCst);
+
+        ui.horizontal(|ui| {
+            if ui.button("< 返回").clicked() {
+                self.show = false;
+            }
+            ui.heading("追蹤的歌手");
+            if is_loading {
+                ui.spinner();
+            }
+        });
+        ui.add_space(10.0);
+
+        let error = self.error.lock().unwrap().clone();
+        if let Some(error) = error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let artists = self.artists.lock().unwrap().clone();
+        ui.horizontal(|ui| {
+            ui.label("搜尋:");
+            ui.text_edit_singleline(&mut self.filter);
+            if ui
+                .add_enabled(!is_loading, egui::Button::new("重新整理"))
+                .clicked()
+            {
+                let spotify = spotify_client.lock().unwrap().clone();
+                self.refresh(ctx.clone(), spotify);
+            }
+        });
+        ui.label(
+            egui::RichText::new(format!(
+                "追蹤中 {} 位",
+                artists.iter().filter(|artist| artist.following).count()
+            ))
+            .weak(),
+        );
+        ui.separator();
+
+        let filter = self.filter.to_lowercase();
+        let mut action = None;
+        egui::ScrollArea::vertical()
+            .id_source("followed_artists")
+            .show(ui, |ui| {
+                if artists.is_empty() && !is_loading {
+                    ui.label("沒有追蹤的歌手");
+                }
+                for artist in artists.iter().filter(|artist| {
+                    filter.is_empty() || artist.name.to_lowercase().contains(&filter)
+                }) {
+                    Self::render_artist(
+                        ui,
+                        artist,
+                        download_statuses,
+                        &mut action,
+                        &mut download_requests,
+                    );
+                    ui.separator();
+                }
+            });
+
+        let spotify = spotify_client.lock().unwrap().clone();
+        match (action, spotify) {
+            (Some(ArtistAction::SetFollowed(artist_id, follow)), Some(spotify)) => {
+                self.set_followed(artist_id, follow, ctx, spotify);
+            }
+            (Some(ArtistAction::SearchTopTracks(artist_id)), Some(spotify)) => {
+                self.search_top_tracks(artist_id, ctx, spotify, debug_mode);
+            }
+            (Some(_), None) => {
+                *self.error.lock().unwrap() = Some("請先登入 Spotify".to_string());
+            }
+            (None, _) => {}
+        }
+
+        download_requests
+    }
+
+    fn render_artist(
+        ui: &mut egui::Ui,
+        artist: &FollowedArtist,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        action: &mut Option<ArtistAction>,
+        download_requests: &mut Vec<i32>,
+    ) {
+        ui.horizontal_wrapped(|ui| {
+            let name = egui::RichText::new(&artist.name).strong();
+            if artist.following {
+                ui.label(name);
+            } else {
+                ui.label(name.weak());
+            }
+            ui.label(egui::RichText::new(format!("{} 位追蹤者", artist.followers)).weak());
+        });
+        if !artist.genres.is_empty() {
+            ui.label(egui::RichText::new(artist.genres.join(", ")).weak());
+        }
+
+        ui.horizontal(|ui| {
+            if artist.updating {
+                ui.spinner();
+            } else if artist.following {
+                if ui.small_button("取消追蹤").clicked() {
+                    *action = Some(ArtistAction::SetFollowed(artist.id.clone(), false));
+                }
+            } else if ui.small_button("追蹤").clicked() {
+                *action = Some(ArtistAction::SetFollowed(artist.id.clone(), true));
+            }
+            if ui.small_button("開啟").clicked() {
+                let url = format!("https://open.spotify.com/artist/{}", artist.id);
+                if let Err(e) = open::that(url) {
+                    error!("無法開啟 Spotify 頁面: {:?}", e);
+                }
+            }
+            if matches!(
+                artist.top_tracks,
+                ReleaseTracksState::NotChecked | ReleaseTracksState::Failed(_)
+            ) && ui
+                .small_button("搜尋熱門曲目譜面")
+                .on_hover_text(format!("為前 {} 首熱門曲目搜尋 osu! 譜面", TOP_TRACK_COUNT))
+                .clicked()
+            {
+                *action = Some(ArtistAction::SearchTopTracks(artist.id.clone()));
+            }
+        });
+
+        match &artist.top_tracks {
+            ReleaseTracksState::NotChecked => {}
+            ReleaseTracksState::Checking => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("搜尋譜面中...");
+                });
+            }
+            ReleaseTracksState::Checked(tracks) => {
+                for track in tracks {
+                    render_track(ui, track, download_statuses, download_requests);
+                }
+            }
+            ReleaseTracksState::Failed(error) => {
+                ui.colored_label(egui::Color32::RED, format!("搜尋失敗: {}", error));
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+// 簡單的模糊比對：查詢的字元依序出現在文字中即視為符合，不分大小寫
+pub fn fuzzy_contains(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars().flat_map(char::to_lowercase);
+    needle
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|c| haystack.any(|h| h == c))
+}
+
+// 查詢以空白分隔，每個詞都必須模糊符合其中一個欄位
+pub fn fuzzy_matches(query: &str, fields: &[&str]) -> bool {
+    query
+        .split_whitespace()
+        .all(|term| fields.iter().any(|field| fuzzy_contains(term, field)))
+}
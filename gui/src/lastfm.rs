@@ -0,0 +1,788 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use log::{debug, error, info};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// 本地模組導入
+use crate::matcher::{rank_beatmapsets, ScoredBeatmapset, CONFIDENT_MATCH_SCORE};
+use crate::osu::get_osu_token;
+use crate::query_normalizer::search_beatmapsets_normalized;
+use crate::DownloadStatus;
+use lib::{get_app_data_path, load_config, save_config};
+
+const API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+const CONFIG_FILE: &str = "lastfm_config.json";
+const TRACK_LIMIT: u32 = 50;
+// 「配對前幾首」一次處理的曲目數
+const BULK_MATCH_COUNT: usize = 10;
+
+#[derive(Error, Debug)]
+pub enum LastFmError {
+    #[error("請求錯誤: {0}")]
+    RequestError(#[from] reqwest::Error),
+    #[error("JSON 解析錯誤: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Last.fm API 錯誤: {0}")]
+    ApiError(String),
+}
+
+// 讀取公開的播放紀錄只需要 API key 與使用者名稱
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LastFmConfig {
+    pub api_key: String,
+    pub username: String,
+}
+
+impl LastFmConfig {
+    pub fn load() -> Option<Self> {
+        load_config(CONFIG_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        save_config(CONFIG_FILE, self)
+    }
+
+    pub fn remove() {
+        let path = get_app_data_path().join(CONFIG_FILE);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(path) {
+                error!("刪除 Last.fm 設定失敗: {:?}", e);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TopPeriod {
+    Overall,
+    SevenDays,
+    OneMonth,
+    ThreeMonths,
+    SixMonths,
+    TwelveMonths,
+}
+
+impl TopPeriod {
+    pub const ALL: [TopPeriod; 6] = [
+        TopPeriod::Overall,
+        TopPeriod::SevenDays,
+        TopPeriod::OneMonth,
+        TopPeriod::ThreeMonths,
+        TopPeriod::SixMonths,
+        TopPeriod::TwelveMonths,
+    ];
+
+    fn api_value(&self) -> &'static str {
+        match self {
+            TopPeriod::Overall => "overall",
+            TopPeriod::SevenDays => "7day",
+            TopPeriod::OneMonth => "1month",
+            TopPeriod::ThreeMonths => "3month",
+            TopPeriod::SixMonths => "6month",
+            TopPeriod::TwelveMonths => "12month",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TopPeriod::Overall => "全部時間",
+            TopPeriod::SevenDays => "最近 7 天",
+            TopPeriod::OneMonth => "最近 1 個月",
+            TopPeriod::ThreeMonths => "最近 3 個月",
+            TopPeriod::SixMonths => "最近 6 個月",
+            TopPeriod::TwelveMonths => "最近 12 個月",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LastFmView {
+    TopTracks,
+    RecentTracks,
+}
+
+#[derive(Clone, Debug)]
+pub struct LastFmTrack {
+    pub artist: String,
+    pub name: String,
+    pub playcount: Option<u32>,
+    pub now_playing: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct LastFmUser {
+    pub name: String,
+    pub playcount: u64,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorResponse {
+    error: i32,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct UserInfoResponse {
+    user: UserInfo,
+}
+
+// Last.fm 的數字欄位都以字串回傳
+#[derive(Deserialize)]
+struct UserInfo {
+    name: String,
+    #[serde(default)]
+    playcount: String,
+}
+
+#[derive(Deserialize)]
+struct TopTracksResponse {
+    toptracks: TopTracks,
+}
+
+#[derive(Deserialize)]
+struct TopTracks {
+    track: Vec<TopTrack>,
+}
+
+#[derive(Deserialize)]
+struct TopTrack {
+    name: String,
+    #[serde(default)]
+    playcount: String,
+    artist: NamedArtist,
+}
+
+#[derive(Deserialize)]
+struct NamedArtist {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct RecentTracksResponse {
+    recenttracks: RecentTracks,
+}
+
+#[derive(Deserialize)]
+struct RecentTracks {
+    track: Vec<RecentTrack>,
+}
+
+#[derive(Deserialize)]
+struct RecentTrack {
+    name: String,
+    artist: TextArtist,
+    #[serde(rename = "@attr", default)]
+    attr: Option<RecentTrackAttr>,
+}
+
+#[derive(Deserialize)]
+struct TextArtist {
+    #[serde(rename = "#text")]
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct RecentTrackAttr {
+    #[serde(default)]
+    nowplaying: Option<String>,
+}
+
+async fn call_api(
+    client: &Client,
+    config: &LastFmConfig,
+    method: &str,
+    params: &[(&str, &str)],
+    debug_mode: bool,
+) -> Result<String, LastFmError> {
+    let mut query = vec![
+        ("method", method),
+        ("user", config.username.as_str()),
+        ("api_key", config.api_key.as_str()),
+        ("format", "json"),
+    ];
+    query.extend_from_slice(params);
+
+    let response_text = client
+        .get(API_URL)
+        .query(&query)
+        .send()
+        .await?
+        .text()
+        .await?;
+    if debug_mode {
+        debug!("Last.fm {} 回應: {}", method, response_text);
+    }
+
+    if let Ok(api_error) = serde_json::from_str::<ApiErrorResponse>(&response_text) {
+        return Err(LastFmError::ApiError(format!(
+            "{} ({})",
+            api_error.message, api_error.error
+        )));
+    }
+    Ok(response_text)
+}
+
+// 以 user.getInfo 確認 API key 與使用者名稱有效
+pub async fn get_user_info(
+    client: &Client,
+    config: &LastFmConfig,
+    debug_mode: bool,
+) -> Result<LastFmUser, LastFmError> {
+    let response_text = call_api(client, config, "user.getinfo", &[], debug_mode).await?;
+    let response: UserInfoResponse = serde_json::from_str(&response_text)?;
+    Ok(LastFmUser {
+        name: response.user.name,
+        playcount: response.user.playcount.parse().unwrap_or(0),
+    })
+}
+
+pub async fn get_top_tracks(
+    client: &Client,
+    config: &LastFmConfig,
+    period: TopPeriod,
+    limit: u32,
+    debug_mode: bool,
+) -> Result<Vec<LastFmTrack>, LastFmError> {
+    let limit = limit.to_string();
+    let response_text = call_api(
+        client,
+        config,
+        "user.gettoptracks",
+        &[("period", period.api_value()), ("limit", limit.as_str())],
+        debug_mode,
+    )
+    .await?;
+    let response: TopTracksResponse = serde_json::from_str(&response_text)?;
+    Ok(response
+        .toptracks
+        .track
+        .into_iter()
+        .map(|track| LastFmTrack {
+            artist: track.artist.name,
+            name: track.name,
+            playcount: track.playcount.parse().ok(),
+            now_playing: false,
+        })
+        .collect())
+}
+
+pub async fn get_recent_tracks(
+    client: &Client,
+    config: &LastFmConfig,
+    debug_mode: bool,
+) -> Result<Vec<LastFmTrack>, LastFmError> {
+    let limit = TRACK_LIMIT.to_string();
+    let response_text = call_api(
+        client,
+        config,
+        "user.getrecenttracks",
+        &[("limit", limit.as_str())],
+        debug_mode,
+    )
+    .await?;
+    let response: RecentTracksResponse = serde_json::from_str(&response_text)?;
+    Ok(response
+        .recenttracks
+        .track
+        .into_iter()
+        .map(|track| LastFmTrack {
+            artist: track.artist.text,
+            name: track.name,
+            playcount: None,
+            now_playing: track
+                .attr
+                .and_then(|attr| attr.nowplaying)
+                .map_or(false, |value| value == "true"),
+        })
+        .collect())
+}
+
+#[derive(Clone, Debug)]
+pub enum MatchState {
+    NotMatched,
+    Matching,
+    Matched(Option<ScoredBeatmapset>),
+    Failed(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct LastFmEntry {
+    pub track: LastFmTrack,
+    pub match_state: MatchState,
+}
+
+// Last.fm 側邊選單頁面
+pub struct LastFmPanel {
+    pub show: bool,
+    config: Option<LastFmConfig>,
+    api_key_input: String,
+    username_input: String,
+    user: Arc<Mutex<Option<LastFmUser>>>,
+    view: LastFmView,
+    period: TopPeriod,
+    entries: Arc<Mutex<Vec<LastFmEntry>>>,
+    is_loading: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl LastFmPanel {
+    pub fn new() -> Self {
+        let config = LastFmConfig::load();
+        Self {
+            show: false,
+            api_key_input: config
+                .as_ref()
+                .map(|c| c.api_key.clone())
+                .unwrap_or_default(),
+            username_input: config
+                .as_ref()
+                .map(|c| c.username.clone())
+                .unwrap_or_default(),
+            config,
+            user: Arc::new(Mutex::new(None)),
+            view: LastFmView::TopTracks,
+            period: TopPeriod::Overall,
+            entries: Arc::new(Mutex::new(Vec::new())),
+            is_loading: Arc::new(AtomicBool::new(false)),
+            error: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // 已配對的譜面集 ID，供呼叫端查詢下載狀態
+    pub fn matched_ids(&self) -> Vec<i32> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|entry| match &entry.match_state {
+                MatchState::Matched(Some(scored)) => Some(scored.beatmapset.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // 打開頁面時呼叫，第一次會載入使用者資訊與曲目
+    pub fn open(&mut self, ctx: egui::Context, debug_mode: bool) {
+        self.show = true;
+        if self.config.is_some() && self.entries.lock().unwrap().is_empty() {
+            self.refresh(ctx, debug_mode);
+        }
+    }
+
+    fn connect(&mut self, ctx: egui::Context, debug_mode: bool) {
+        let config = LastFmConfig {
+            api_key: self.api_key_input.trim().to_string(),
+            username: self.username_input.trim().to_string(),
+        };
+        let user = self.user.clone();
+        let error = self.error.clone();
+        let is_loading = self.is_loading.clone();
+        is_loading.store(true, Ordering::SeqCst);
+        *error.lock().unwrap() = None;
+
+        // 先確認帳號有效才保存設定
+        self.config = Some(config.clone());
+        tokio::spawn(async move {
+            let client = Client::new();
+            match get_user_info(&client, &config, debug_mode).await {
+                Ok(info) => {
+                    info!("已連結 Last.fm 帳號: {}", info.name);
+                    if let Err(e) = config.save() {
+                        error!("保存 Last.fm 設定失敗: {:?}", e);
+                    }
+                    *user.lock().unwrap() = Some(info);
+                }
+                Err(e) => {
+                    error!("連結 Last.fm 失敗: {:?}", e);
+                    *error.lock().unwrap() = Some(format!("連結失敗: {}", e));
+                }
+            }
+            is_loading.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    fn disconnect(&mut self) {
+        LastFmConfig::remove();
+        self.config = None;
+        *self.user.lock().unwrap() = None;
+        self.entries.lock().unwrap().clear();
+        *self.error.lock().unwrap() = None;
+        info!("已中斷 Last.fm 連結");
+    }
+
+    fn refresh(&mut self, ctx: egui::Context, debug_mode: bool) {
+        let config = match &self.config {
+            Some(config) => config.clone(),
+            None => return,
+        };
+        let view = self.view;
+        let period = self.period;
+        let user = self.user.clone();
+        let entries = self.entries.clone();
+        let error = self.error.clone();
+        let is_loading = self.is_loading.clone();
+        is_loading.store(true, Ordering::SeqCst);
+        *error.lock().unwrap() = None;
+
+        tokio::spawn(async move {
+            let client = Client::new();
+            let needs_user_info = user.lock().unwrap().is_none();
+            if needs_user_info {
+                match get_user_info(&client, &config, debug_mode).await {
+                    Ok(info) => *user.lock().unwrap() = Some(info),
+                    Err(e) => error!("取得 Last.fm 使用者資訊失敗: {:?}", e),
+                }
+            }
+
+            let result = match view {
+                LastFmView::TopTracks => {
+                    get_top_tracks(&client, &config, period, TRACK_LIMIT, debug_mode).await
+                }
+                LastFmView::RecentTracks => get_recent_tracks(&client, &config, debug_mode).await,
+            };
+            match result {
+                Ok(tracks) => {
+                    info!("取得 Last.fm 曲目: {} 首", tracks.len());
+                    *entries.lock().unwrap() = tracks
+                        .into_iter()
+                        .map(|track| LastFmEntry {
+                            track,
+                            match_state: MatchState::NotMatched,
+                        })
+                        .collect();
+                }
+                Err(e) => {
+                    error!("取得 Last.fm 曲目失敗: {:?}", e);
+                    *error.lock().unwrap() = Some(e.to_string());
+                }
+            }
+            is_loading.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    // 依序為指定曲目搜尋 osu! 譜面並取最高分的結果
+    fn match_tracks(&self, indices: Vec<usize>, ctx: egui::Context, debug_mode: bool) {
+        let entries = self.entries.clone();
+        let tracks: Vec<(usize, LastFmTrack)> = {
+            let mut entries = entries.lock().unwrap();
+            indices
+                .into_iter()
+                .filter_map(|index| {
+                    let entry = entries.get_mut(index)?;
+                    if matches!(
+                        entry.match_state,
+                        MatchState::Matching | MatchState::Matched(_)
+                    ) {
+                        return None;
+                    }
+                    entry.match_state = MatchState::Matching;
+                    Some((index, entry.track.clone()))
+                })
+                .collect()
+        };
+        if tracks.is_empty() {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let client = Client::new();
+            let osu_token = match get_osu_token(&client, debug_mode).await {
+                Ok(token) => token,
+                Err(e) => {
+                    error!("Last.fm 配對無法取得 osu token: {:?}", e);
+                    let mut entries = entries.lock().unwrap();
+                    for (index, _) in &tracks {
+                        if let Some(entry) = entries.get_mut(*index) {
+                            entry.match_state = MatchState::Failed(e.to_string());
+                        }
+                    }
+                    ctx.request_repaint();
+                    return;
+                }
+            };
+
+            for (index, track) in tracks {
+                let osu_query = format!("{} {}", track.artist, track.name);
+                let state = match search_beatmapsets_normalized(
+                    &client,
+                    &osu_token,
+                    &track.artist,
+                    &track.name,
+                    debug_mode,
+                )
+                .await
+                {
+                    Ok(beatmapsets) => MatchState::Matched(
+                        rank_beatmapsets(&track.artist, &track.name, None, beatmapsets)
+                            .into_iter()
+                            .next(),
+                    ),
+                    Err(e) => {
+                        error!("Last.fm 曲目 {} 配對失敗: {:?}", osu_query, e);
+                        MatchState::Failed(e.to_string())
+                    }
+                };
+                // 重新整理後列表可能已經換掉，只更新同一首歌
+                if let Some(entry) = entries.lock().unwrap().get_mut(index) {
+                    if entry.track.name == track.name && entry.track.artist == track.artist {
+                        entry.match_state = state;
+                    }
+                }
+                ctx.request_repaint();
+            }
+        });
+    }
+
+    // 渲染頁面，回傳使用者要求下載的譜面集 ID
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        debug_mode: bool,
+    ) -> Vec<i32> {
+        let mut download_requests = Vec::new();
+        let ctx = ui.ctx().clone();
+        let is_loading = self.is_loading.load(Ordering::SeqCst);
+
+        ui.horizontal(|ui| {
+            if ui.button("< 返回").clicked() {
+                self.show = false;
+            }
+            ui.heading("Last.fm");
+            if is_loading {
+                ui.spinner();
+            }
+        });
+        ui.add_space(10.0);
+
+        let error = self.error.lock().unwrap().clone();
+        if let Some(error) = error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let connected_user = self.user.lock().unwrap().clone();
+        if self.config.is_none() || (connected_user.is_none() && !is_loading) {
+            self.render_connect_form(ui, ctx, debug_mode);
+            return download_requests;
+        }
+
+        if let Some(user) = &connected_user {
+            ui.horizontal(|ui| {
+                ui.label(format!("已連結: {} ({} 次播放)", user.name, user.playcount));
+                if ui.small_button("中斷連結").clicked() {
+                    self.disconnect();
+                }
+            });
+        }
+
+        let mut need_refresh = false;
+        ui.horizontal(|ui| {
+            need_refresh |= ui
+                .selectable_value(&mut self.view, LastFmView::TopTracks, "最常播放")
+                .changed();
+            need_refresh |= ui
+                .selectable_value(&mut self.view, LastFmView::RecentTracks, "最近播放")
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            if self.view == LastFmView::TopTracks {
+                let previous = self.period;
+                egui::ComboBox::from_id_source("lastfm_period")
+                    .selected_text(self.period.label())
+                    .show_ui(ui, |ui| {
+                        for period in TopPeriod::ALL {
+                            ui.selectable_value(&mut self.period, period, period.label());
+                        }
+                    });
+                need_refresh |= self.period != previous;
+            }
+            need_refresh |= ui
+                .add_enabled(!is_loading, egui::Button::new("重新整理"))
+                .clicked();
+        });
+        if need_refresh {
+            self.refresh(ctx.clone(), debug_mode);
+        }
+
+        let entries = self.entries.lock().unwrap().clone();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !entries.is_empty(),
+                    egui::Button::new(format!("配對前 {} 首", BULK_MATCH_COUNT)),
+                )
+                .clicked()
+            {
+                self.match_tracks(
+                    (0..BULK_MATCH_COUNT.min(entries.len())).collect(),
+                    ctx.clone(),
+                    debug_mode,
+                );
+            }
+            // 只下載分數夠高的配對結果
+            let confident: Vec<i32> = entries
+                .iter()
+                .filter_map(|entry| match &entry.match_state {
+                    MatchState::Matched(Some(scored)) if scored.score >= CONFIDENT_MATCH_SCORE => {
+                        Some(scored.beatmapset.id)
+                    }
+                    _ => None,
+                })
+                .filter(|id| {
+                    download_statuses
+                        .get(id)
+                        .copied()
+                        .unwrap_or(DownloadStatus::NotStarted)
+                        == DownloadStatus::NotStarted
+                })
+                .collect();
+            if ui
+                .add_enabled(
+                    !confident.is_empty(),
+                    egui::Button::new(format!("下載可信配對 ({})", confident.len())),
+                )
+                .clicked()
+            {
+                download_requests.extend(confident);
+            }
+        });
+
+        ui.separator();
+
+        let mut match_request = None;
+        egui::ScrollArea::vertical()
+            .id_source("lastfm_tracks")
+            .show(ui, |ui| {
+                if entries.is_empty() && !is_loading {
+                    ui.label("沒有播放紀錄");
+                }
+                for (index, entry) in entries.iter().enumerate() {
+                    Self::render_entry(
+                        ui,
+                        index,
+                        entry,
+                        download_statuses,
+                        &mut match_request,
+                        &mut download_requests,
+                    );
+                    ui.separator();
+                }
+            });
+        if let Some(index) = match_request {
+            self.match_tracks(vec![index], ctx, debug_mode);
+        }
+
+        download_requests
+    }
+
+    fn render_connect_form(&mut self, ui: &mut egui::Ui, ctx: egui::Context, debug_mode: bool) {
+        ui.label("輸入 Last.fm API key 與使用者名稱以讀取播放紀錄");
+        if ui.link("申請 API key").clicked() {
+            if let Err(e) = open::that("https://www.last.fm/api/account/create") {
+                error!("無法開啟 Last.fm 網頁: {:?}", e);
+            }
+        }
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("API key:");
+            ui.add(egui::TextEdit::singleline(&mut self.api_key_input).password(true));
+        });
+        ui.horizontal(|ui| {
+            ui.label("使用者:");
+            ui.text_edit_singleline(&mut self.username_input);
+        });
+        let can_connect = !self.api_key_input.trim().is_empty()
+            && !self.username_input.trim().is_empty()
+            && !self.is_loading.load(Ordering::SeqCst);
+        if ui
+            .add_enabled(can_connect, egui::Button::new("連結"))
+            .clicked()
+        {
+            self.connect(ctx, debug_mode);
+        }
+    }
+
+    fn render_entry(
+        ui: &mut egui::Ui,
+        index: usize,
+        entry: &LastFmEntry,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        match_request: &mut Option<usize>,
+        download_requests: &mut Vec<i32>,
+    ) {
+        let track = &entry.track;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(format!("{}.", index + 1));
+            ui.label(egui::RichText::new(&track.name).strong());
+            if track.now_playing {
+                ui.label(egui::RichText::new("正在播放").color(egui::Color32::GREEN));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(&track.artist).weak());
+            if let Some(playcount) = track.playcount {
+                ui.label(egui::RichText::new(format!("{} 次", playcount)).weak());
+            }
+        });
+
+        match &entry.match_state {
+            MatchState::NotMatched => {
+                if ui.small_button("配對 osu! 譜面").clicked() {
+                    *match_request = Some(index);
+                }
+            }
+            MatchState::Matching => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("配對中...");
+                });
+            }
+            MatchState::Matched(None) => {
+                ui.label("osu!: 找不到譜面");
+            }
+            MatchState::Matched(Some(scored)) => {
+                let beatmapset = &scored.beatmapset;
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(format!(
+                        "osu!: {} - {} [{:.0}%]",
+                        beatmapset.artist,
+                        beatmapset.title,
+                        scored.score * 100.0
+                    ));
+                    let status = download_statuses
+                        .get(&beatmapset.id)
+                        .copied()
+                        .unwrap_or(DownloadStatus::NotStarted);
+                    match status {
+                        DownloadStatus::NotStarted => {
+                            if ui.small_button("下載").clicked() {
+                                download_requests.push(beatmapset.id);
+                            }
+                        }
+                        DownloadStatus::Waiting => {
+                            ui.label("等待中");
+                        }
+                        DownloadStatus::Downloading => {
+                            ui.spinner();
+                        }
+                        DownloadStatus::Completed => {
+                            ui.label("已下載");
+                        }
+                    }
+                });
+            }
+            MatchState::Failed(error) => {
+                ui.colored_label(egui::Color32::RED, format!("配對失敗: {}", error));
+            }
+        }
+    }
+}
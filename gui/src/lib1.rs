@@ -1,10 +1,10 @@
 // 標準庫導入
 use std::fs::File;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 use std::process::Command;
 use std::sync::Mutex;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 
 // 第三方庫導入
@@ -15,8 +15,9 @@ use dirs;
 use dirs::home_dir;
 use reqwest::Client;
 use lazy_static::lazy_static;
-use log::{debug, error, LevelFilter};
+use log::{debug, error, warn, LevelFilter};
 use regex::Regex;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
@@ -211,31 +212,133 @@ pub fn get_app_data_path() -> PathBuf {
     path
 }
 
-pub fn save_login_info(login_info: &HashMap<String, LoginInfo>) -> Result<(), ConfigError> {
-    let app_data_path = get_app_data_path();
-    fs::create_dir_all(&app_data_path)
-        .map_err(|e| ConfigError::Other(format!("無法創建應用數據目錄: {}", e)))?;
+// 設定檔格式版本，格式變更時遞增並在 migrate_config 加上對應的升級
+pub const CONFIG_VERSION: u64 = 1;
 
-    let file_path = app_data_path.join("login_info.json");
-    let json = serde_json::to_string(login_info)
-        .map_err(|e| ConfigError::Other(format!("無法序列化登入信息: {}", e)))?;
-    
-    fs::write(&file_path, json)
-        .map_err(|e| ConfigError::FileOpenError(format!("無法保存登入信息: {}", e)))
+// 在檔名後加上副檔名，例如 login_info.json -> login_info.json.tmp
+fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(extension);
+    path.with_file_name(file_name)
 }
 
-pub fn read_login_info() -> Result<HashMap<String, LoginInfo>, ConfigError> {
-    let file_path = get_app_data_path().join("login_info.json");
-    
-    match fs::read_to_string(file_path) {
-        Ok(contents) => {
-            let login_info: HashMap<String, LoginInfo> = serde_json::from_str(&contents)
-                .map_err(|e| ConfigError::JsonParseError(format!("無法解析登入信息: {}", e)))?;
-            Ok(login_info)
+// 先寫入暫存檔再重新命名，避免寫到一半中斷導致檔案損毀
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = sibling_path(path, ".tmp");
+    let result = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(contents.as_ref())?;
+        file.sync_all()
+    });
+    if let Err(e) = result.and_then(|_| fs::rename(&temp_path, path)) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+// 以 {"version": N, "data": ...} 格式寫入應用數據目錄下的設定檔
+pub fn save_config<T: Serialize>(file_name: &str, value: &T) -> io::Result<()> {
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "version": CONFIG_VERSION,
+        "data": value,
+    }))?;
+    write_atomic(&get_app_data_path().join(file_name), content)
+}
+
+// 將舊版本的資料逐版升級，from 為資料目前的版本
+fn migrate_config(file_name: &str, from: u64, mut data: Value) -> Value {
+    for version in from..CONFIG_VERSION {
+        data = match (version, file_name) {
+            // 舊版登入信息沒有 platform 欄位，以外層的鍵補上
+            (0, "login_info.json") => {
+                if let Value::Object(accounts) = &mut data {
+                    for (platform, info) in accounts.iter_mut() {
+                        if let Value::Object(info) = info {
+                            info.entry("platform")
+                                .or_insert_with(|| Value::String(platform.clone()));
+                        }
+                    }
+                }
+                data
+            }
+            // 版本 0 為加入版本欄位前的檔案，內容即為資料本身
+            _ => data,
+        };
+    }
+    data
+}
+
+// 無法解析的檔案改名為 .bak 保留，讓程式以預設值繼續執行
+fn back_up_broken_config(path: &Path, reason: &str) {
+    let backup_path = sibling_path(path, ".bak");
+    error!(
+        "設定檔 {:?} 無法使用 ({})，已備份至 {:?}",
+        path, reason, backup_path
+    );
+    if let Err(e) = fs::rename(path, &backup_path) {
+        error!("備份設定檔失敗: {:?}", e);
+    }
+}
+
+// 讀取設定檔，舊格式會升級後寫回，檔案不存在或損毀時回傳 None
+pub fn load_config<T: DeserializeOwned>(file_name: &str) -> Option<T> {
+    let path = get_app_data_path().join(file_name);
+    let content = fs::read_to_string(&path).ok()?;
+    let value: Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            back_up_broken_config(&path, &e.to_string());
+            return None;
+        }
+    };
+
+    let (version, data) = match value {
+        Value::Object(mut object)
+            if object.len() == 2
+                && object.contains_key("version")
+                && object.contains_key("data") =>
+        {
+            let version = object["version"].as_u64().unwrap_or(0);
+            (version, object.remove("data").unwrap_or(Value::Null))
         }
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
-        Err(e) => Err(ConfigError::FileReadError(format!("無法讀取登入信息: {}", e))),
+        value => (0, value),
+    };
+    if version > CONFIG_VERSION {
+        warn!(
+            "設定檔 {} 的版本 {} 比目前支援的版本新，嘗試直接讀取",
+            file_name, version
+        );
     }
+    let data = migrate_config(file_name, version, data);
+
+    match serde_json::from_value::<T>(data) {
+        Ok(config) => {
+            if version < CONFIG_VERSION {
+                debug!("設定檔 {} 已從版本 {} 升級", file_name, version);
+                if let Err(e) = save_config(file_name, &config) {
+                    error!("寫回升級後的設定檔 {} 失敗: {:?}", file_name, e);
+                }
+            }
+            Some(config)
+        }
+        Err(e) => {
+            back_up_broken_config(&path, &e.to_string());
+            None
+        }
+    }
+}
+
+pub fn save_login_info(login_info: &HashMap<String, LoginInfo>) -> Result<(), ConfigError> {
+    save_config("login_info.json", login_info)
+        .map_err(|e| ConfigError::FileOpenError(format!("無法保存登入信息: {}", e)))
+}
+
+// 損毀的登入信息會被備份，視為尚未登入
+pub fn read_login_info() -> Result<HashMap<String, LoginInfo>, ConfigError> {
+    Ok(load_config("login_info.json").unwrap_or_default())
 }
 
 pub fn is_token_valid(login_info: &LoginInfo) -> bool {
@@ -331,59 +434,67 @@ pub fn load_download_directory() -> Option<PathBuf> {
 
 pub fn save_download_directory(download_directory: &PathBuf) -> Result<(), std::io::Error> {
     let path = get_app_data_path().join("download_directory.txt");
-    fs::create_dir_all(path.parent().unwrap())?;
-    fs::write(&path, download_directory.to_str().unwrap())?;
-    Ok(())
+    write_atomic(&path, download_directory.to_str().unwrap())
 }
 
 pub fn save_background_path(custom_background_path: &Option<PathBuf>) -> Result<(), std::io::Error> {
-    let app_data_path = get_app_data_path();
-    fs::create_dir_all(&app_data_path)?;
-    let config_path = app_data_path.join("background_config.json");
-    
     let config = serde_json::json!({
         "background_path": custom_background_path
     });
-    
-    fs::write(config_path, serde_json::to_string_pretty(&config)?)?;
-    Ok(())
+    save_config("background_config.json", &config)
 }
 
 pub fn load_background_path() -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
-    let config_path = get_app_data_path().join("background_config.json");
-    if config_path.exists() {
-        let content = fs::read_to_string(config_path)?;
-        let config: serde_json::Value = serde_json::from_str(&content)?;
-        if let Some(path) = config["background_path"].as_str() {
-            return Ok(Some(PathBuf::from(path)));
-        }
-    }
-    Ok(None)
+    let config: Option<Value> = load_config("background_config.json");
+    Ok(config.and_then(|config| config["background_path"].as_str().map(PathBuf::from)))
 }
 
 pub fn save_scale_factor(scale: f32) -> Result<(), std::io::Error> {
-    let app_data_path = get_app_data_path();
-    fs::create_dir_all(&app_data_path)?;
-    let config_path = app_data_path.join("scale_config.json");
-    
     let config = serde_json::json!({
         "scale_factor": scale
     });
-    
-    fs::write(config_path, serde_json::to_string_pretty(&config)?)?;
-    Ok(())
+    save_config("scale_config.json", &config)
 }
 
 pub fn load_scale_factor() -> Result<Option<f32>, Box<dyn std::error::Error>> {
-    let config_path = get_app_data_path().join("scale_config.json");
-    if config_path.exists() {
-        let content = fs::read_to_string(config_path)?;
-        let config: serde_json::Value = serde_json::from_str(&content)?;
-        if let Some(scale) = config["scale_factor"].as_f64() {
-            return Ok(Some(scale as f32));
+    let config: Option<Value> = load_config("scale_config.json");
+    Ok(config.and_then(|config| config["scale_factor"].as_f64().map(|scale| scale as f32)))
+}
+
+pub fn save_cache_ttl(ttl_secs: u64) -> Result<(), std::io::Error> {
+    let config = serde_json::json!({
+        "cache_ttl_secs": ttl_secs
+    });
+    save_config("cache_config.json", &config)
+}
+
+pub fn load_cache_ttl() -> Option<u64> {
+    let config: Value = load_config("cache_config.json")?;
+    config["cache_ttl_secs"].as_u64()
+}
+
+// 工作階段狀態，程式異常結束時也會寫入
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionState {
+    pub global_volume: f32,
+    pub search_query: String,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            global_volume: 0.3,
+            search_query: String::new(),
         }
     }
-    Ok(None)
+}
+
+pub fn save_session_state(state: &SessionState) -> Result<(), std::io::Error> {
+    save_config("session_state.json", state)
+}
+
+pub fn load_session_state() -> Option<SessionState> {
+    load_config("session_state.json")
 }
 
 // 新增一個函數來檢查是否需要選擇下載目錄
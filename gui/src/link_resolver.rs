@@ -0,0 +1,203 @@
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{debug, info};
+use regex::Regex;
+use reqwest::Client;
+use serde::Deserialize;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Error, Debug)]
+pub enum LinkResolveError {
+    #[error("請求錯誤: {0}")]
+    RequestError(#[from] reqwest::Error),
+    #[error("JSON 解析錯誤: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("找不到連結對應的曲目: {0}")]
+    NotFound(String),
+}
+
+// 可以反查歌手與歌名的音樂平台連結
+#[derive(Debug, Clone, PartialEq)]
+pub enum MusicLink {
+    YouTubeMusic { video_id: String },
+    AppleMusic { id: String, country: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedLink {
+    pub artist: String,
+    pub title: String,
+    pub source: &'static str,
+}
+
+impl ResolvedLink {
+    // 給 Spotify 與 osu! 搜尋使用的關鍵字
+    pub fn query(&self) -> String {
+        format!("{} {}", self.artist, self.title).trim().to_string()
+    }
+}
+
+lazy_static! {
+    // 影片標題常見的後綴，例如 (Official Video)、【MV】
+    static ref TITLE_NOISE: Regex = Regex::new(
+        r"(?i)\s*[\(\[【「][^\)\]】」]*(official|music video|mv|lyric|audio|video|歌詞|中字)[^\)\]】」]*[\)\]】」]"
+    )
+    .unwrap();
+}
+
+pub fn parse_music_link(input: &str) -> Option<MusicLink> {
+    let url = Url::parse(input.trim()).ok()?;
+    let host = url.host_str()?.trim_start_matches("www.");
+
+    match host {
+        "music.youtube.com" | "youtube.com" | "m.youtube.com" => url
+            .query_pairs()
+            .find(|(key, _)| key == "v")
+            .map(|(_, value)| MusicLink::YouTubeMusic {
+                video_id: value.into_owned(),
+            }),
+        "youtu.be" => url
+            .path_segments()?
+            .next()
+            .filter(|id| !id.is_empty())
+            .map(|id| MusicLink::YouTubeMusic {
+                video_id: id.to_string(),
+            }),
+        "music.apple.com" => {
+            let segments: Vec<&str> = url.path_segments()?.collect();
+            let country = segments.first()?.to_string();
+            // 專輯連結中的單曲以 ?i= 指定，否則使用路徑最後一段的 ID
+            let id = url
+                .query_pairs()
+                .find(|(key, _)| key == "i")
+                .map(|(_, value)| value.into_owned())
+                .or_else(|| segments.last().map(|s| s.to_string()))?;
+            if id.chars().all(|c| c.is_ascii_digit()) && !id.is_empty() {
+                Some(MusicLink::AppleMusic { id, country })
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+pub async fn resolve_music_link(
+    client: &Client,
+    link: &MusicLink,
+    debug_mode: bool,
+) -> Result<ResolvedLink, LinkResolveError> {
+    let resolved = match link {
+        MusicLink::YouTubeMusic { video_id } => {
+            resolve_youtube(client, video_id, debug_mode).await?
+        }
+        MusicLink::AppleMusic { id, country } => {
+            resolve_apple_music(client, id, country, debug_mode).await?
+        }
+    };
+    info!(
+        "{} 連結解析結果: {} - {}",
+        resolved.source, resolved.artist, resolved.title
+    );
+    Ok(resolved)
+}
+
+#[derive(Deserialize)]
+struct OEmbedResponse {
+    title: String,
+    author_name: String,
+}
+
+async fn resolve_youtube(
+    client: &Client,
+    video_id: &str,
+    debug_mode: bool,
+) -> Result<ResolvedLink, LinkResolveError> {
+    let video_url = format!("https://www.youtube.com/watch?v={}", video_id);
+    let response = client
+        .get("https://www.youtube.com/oembed")
+        .query(&[("url", video_url.as_str()), ("format", "json")])
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(LinkResolveError::NotFound(video_url));
+    }
+
+    let response_text = response.text().await?;
+    if debug_mode {
+        debug!("YouTube oEmbed 回應: {}", response_text);
+    }
+    let oembed: OEmbedResponse = serde_json::from_str(&response_text)?;
+
+    let title = TITLE_NOISE
+        .replace_all(&oembed.title, "")
+        .trim()
+        .to_string();
+    // YouTube Music 自動產生的頻道名稱為 "歌手 - Topic"，標題即為歌名
+    let (artist, title) = match oembed.author_name.strip_suffix(" - Topic") {
+        Some(artist) => (artist.to_string(), title),
+        None => match title.split_once(" - ") {
+            Some((artist, song)) => (artist.trim().to_string(), song.trim().to_string()),
+            None => (oembed.author_name.clone(), title),
+        },
+    };
+
+    Ok(ResolvedLink {
+        artist,
+        title,
+        source: "YouTube Music",
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ITunesLookupResult {
+    #[serde(default)]
+    artist_name: String,
+    #[serde(default)]
+    track_name: Option<String>,
+    #[serde(default)]
+    collection_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ITunesLookupResponse {
+    results: Vec<ITunesLookupResult>,
+}
+
+async fn resolve_apple_music(
+    client: &Client,
+    id: &str,
+    country: &str,
+    debug_mode: bool,
+) -> Result<ResolvedLink, LinkResolveError> {
+    let response_text = client
+        .get("https://itunes.apple.com/lookup")
+        .query(&[("id", id), ("country", country)])
+        .send()
+        .await?
+        .text()
+        .await?;
+    if debug_mode {
+        debug!("iTunes lookup 回應: {}", response_text);
+    }
+    let lookup: ITunesLookupResponse = serde_json::from_str(&response_text)?;
+
+    let result = lookup
+        .results
+        .into_iter()
+        .next()
+        .ok_or_else(|| LinkResolveError::NotFound(id.to_string()))?;
+    // 單曲使用歌名，專輯連結則使用專輯名稱
+    let title = result
+        .track_name
+        .or(result.collection_name)
+        .ok_or_else(|| LinkResolveError::NotFound(id.to_string()))?;
+
+    Ok(ResolvedLink {
+        artist: result.artist_name,
+        title,
+        source: "Apple Music",
+    })
+}
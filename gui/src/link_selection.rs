@@ -0,0 +1,89 @@
+// 第三方庫導入
+use clipboard::{ClipboardContext, ClipboardProvider};
+use log::{error, info};
+
+// Spotify 與 osu! 搜尋結果共用的多選，將選取的連結以換行分隔複製到剪貼簿
+pub struct LinkSelection {
+    // 是否顯示勾選框與操作列
+    pub show: bool,
+    // 保留選取的順序，複製時依序排列
+    links: Vec<String>,
+    // 上次複製的數量，選取變更後清除
+    copied: Option<usize>,
+}
+
+impl LinkSelection {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            links: Vec::new(),
+            copied: None,
+        }
+    }
+
+    pub fn is_selected(&self, link: &str) -> bool {
+        self.links.iter().any(|selected| selected == link)
+    }
+
+    pub fn set_selected(&mut self, link: &str, selected: bool) {
+        if selected && !self.is_selected(link) {
+            self.links.push(link.to_string());
+        } else if !selected {
+            self.links.retain(|selected| selected != link);
+        }
+        self.copied = None;
+    }
+
+    // 結果列表中每一筆前方的勾選框
+    pub fn checkbox(&mut self, ui: &mut egui::Ui, link: &str) {
+        let mut selected = self.is_selected(link);
+        if ui.checkbox(&mut selected, "").changed() {
+            self.set_selected(link, selected);
+        }
+    }
+
+    // 結果列表上方的開關與操作列，visible_links 為目前列表可全選的連結
+    pub fn render(&mut self, ui: &mut egui::Ui, visible_links: &[String]) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show, "選取連結");
+            if !self.show {
+                return;
+            }
+            ui.label(format!("已選取 {} 個", self.links.len()));
+            if ui.button("全選").clicked() {
+                for link in visible_links {
+                    self.set_selected(link, true);
+                }
+            }
+            if ui
+                .add_enabled(!self.links.is_empty(), egui::Button::new("取消選取"))
+                .clicked()
+            {
+                self.links.clear();
+                self.copied = None;
+            }
+            if ui
+                .add_enabled(!self.links.is_empty(), egui::Button::new("複製所有連結"))
+                .clicked()
+            {
+                self.copy_to_clipboard();
+            }
+            if let Some(count) = self.copied {
+                ui.label(format!("已複製 {} 個連結", count));
+            }
+        });
+    }
+
+    fn copy_to_clipboard(&mut self) {
+        let contents = self.links.join("\n");
+        let result = ClipboardProvider::new()
+            .and_then(|mut ctx: ClipboardContext| ctx.set_contents(contents));
+        match result {
+            Ok(_) => {
+                info!("已複製 {} 個連結到剪貼簿", self.links.len());
+                self.copied = Some(self.links.len());
+            }
+            Err(e) => error!("無法複製連結到剪貼簿: {:?}", e),
+        }
+    }
+}
@@ -1,11 +1,72 @@
 // 本地模組
+mod accessibility;
+mod animation;
+mod api_server;
+mod asset_loader;
+mod auto_download;
+mod batch_like;
+mod batchimport;
+mod bbcode;
+mod beatmapsource;
+mod cache;
+mod confirm_dialog;
+mod cover_palette;
+mod crash;
+mod diagnostics;
+mod download_history;
+mod download_manager;
+mod download_migration;
+mod download_options;
+mod downloaded_detail;
+mod errorbanner;
+mod followed_artists;
+mod fuzzy;
+mod lastfm;
+mod link_resolver;
+mod link_selection;
+mod map_index;
+mod mapper_lists;
+mod match_memory;
+mod matcher;
+mod media_keys;
+mod notify;
 mod osu;
+mod osu_client;
+mod osufavourites;
 mod osuhelper;
+mod overlay;
+mod pinned_playlists;
+mod playlist_folders;
+mod playlistbuilder;
+mod plugin_actions;
+mod preview_cache;
+mod preview_compare;
+mod preview_effects;
+mod preview_playlist;
+mod query_normalizer;
+mod quick_actions;
+mod rate_limit;
+mod recently_viewed;
+mod release_radar;
+mod report;
+mod review_queue;
+mod scheduler;
+mod scripts;
+mod search_suggestions;
+mod session;
+mod settings_bundle;
 mod spotify;
+#[cfg(feature = "sqlite")]
+mod sqlite_storage;
+mod storage;
+mod sync;
+mod texture_budget;
+mod texturequeue;
+mod trash;
+mod updater;
+mod watch_folder;
 
 // 標準庫導入
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::default::Default;
@@ -29,7 +90,7 @@ use egui::{
     FontData, FontDefinitions, FontFamily, TextureHandle, TextureWrapMode, ViewportBuilder,
 };
 
-use log::{debug, error, info, LevelFilter};
+use log::{debug, error, info, warn, LevelFilter};
 use parking_lot::Mutex as ParkingLotMutex;
 use reqwest::Client;
 use rodio::{OutputStream, OutputStreamHandle, Sink};
@@ -54,31 +115,175 @@ use tokio::{
 };
 
 // 本地模組導入
+use crate::download_history::{
+    delete_downloaded_maps, downloaded_maps, has_download_record, scan_downloads, DownloadedIds,
+    IntegrityIssue, IntegrityProblem,
+};
+use crate::download_manager::{
+    auto_pause_options, format_eta, item_progress, pause_reason, queue_eta, resume_downloads,
+    set_auto_pause_options, wait_until_resumed, AutoPauseOptions, PauseReason,
+};
+use crate::download_migration::{
+    migrate_downloads, migration_candidates, MigrationMode, MigrationProgress, MigrationReport,
+};
+use crate::download_options::{
+    available_space, download_options, format_filename, low_disk_space, set_download_options,
+    BeatmapsetNames, DownloadOptions, DEFAULT_FILENAME_TEMPLATE,
+};
+use crate::map_index::{forget_maps, index_downloaded_maps, map_matches, map_metadata};
+use crate::notify::{
+    notify_batch_completed, notify_options, send_batch_report, set_notify_options, BatchReport,
+    NotifyOptions,
+};
 use crate::osu::{
-    delete_beatmap, get_beatmapset_by_id, get_beatmapset_details, get_beatmapsets,
-    get_downloaded_beatmaps, get_osu_token, load_osu_covers, parse_osu_url, preview_beatmap,
-    print_beatmap_info_gui, Beatmapset,
+    delete_beatmap, get_beatmap_pack, get_beatmapset_by_id, get_beatmapset_description,
+    get_beatmapset_details, get_downloaded_beatmaps, get_osu_token, load_osu_covers, parse_osu_url,
+    preview_beatmap, print_beatmap_info_gui, BeatmapPack, Beatmapset, OsuError, COVER_MAX_RETRIES,
+    COVER_RETRY_BASE_DELAY_MS,
 };
 use crate::spotify::{
-    add_track_to_liked, authorize_spotify, get_access_token, get_playlist_tracks, get_track_info,
-    get_user_playlists, is_valid_spotify_url, load_spotify_icon, open_spotify_url,
-    remove_track_from_liked, search_track, update_currently_playing_wrapper, Album, AuthStatus,
-    CurrentlyPlaying, Image, SpotifyError, SpotifyUrlStatus, Track, TrackWithCover,
+    add_track_to_liked, add_tracks_to_playlist, authorize_spotify, create_playlist,
+    get_access_token, get_album, get_album_tracks, get_artist, get_artist_top_tracks, get_episode,
+    get_liked_tracks, get_playlist_tracks, get_show_episodes, get_track_info, get_user_playlists,
+    is_spotify_short_link, is_valid_spotify_url, normalize_spotify_url, open_spotify_url,
+    parse_spotify_url, remove_track_from_liked, resolve_spotify_short_link, search_album_by_name,
+    search_episodes, search_track, search_track_in_markets, skip_spotify_track,
+    toggle_spotify_playback, update_currently_playing_wrapper, Album, AuthStatus, CurrentlyPlaying,
+    PlaylistEntry, SpotifyError, SpotifyUrlKind, SpotifyUrlStatus, Track, TrackWithCover,
+    UnavailableReason,
 };
 use lib::{
     check_and_refresh_token, get_app_data_path, load_background_path, load_download_directory,
-    load_scale_factor, need_select_download_directory, read_config, read_login_info,
-    save_background_path, save_download_directory, save_scale_factor, set_log_level, ConfigError,
+    load_scale_factor, load_session_state, need_select_download_directory, read_config,
+    read_login_info, save_background_path, save_download_directory, save_scale_factor,
+    save_session_state, set_log_level, ConfigError, SessionState,
 };
 
+use accessibility::{
+    accessibility_options, apply_theme, label_icon_button, set_accessibility_options,
+    ANIMATION_MS_RANGE, MIN_FONT_SIZE_RANGE,
+};
+use animation::{animate_open, apply_motion, step_hover};
+use api_server::{
+    api_server_options, generate_token, set_api_server_options, ApiServer, RemoteCommand,
+};
+use asset_loader::{load_image_file, IconLoader};
+use auto_download::{
+    auto_download_activity, auto_download_new_tracks, clear_auto_download_activity,
+    download_playlist_tracks, is_auto_download, new_tracks, set_auto_download, AutoDownloadTarget,
+};
+use batch_like::{BatchLike, BatchLikeRequest, LikeAction};
+use batchimport::BatchImport;
+use bbcode::BbcodeDocument;
+use beatmapsource::BeatmapSourceKind;
+use cache::{
+    format_size, read_json_cache, write_json_cache, CacheKind, CacheManager, CacheProgress,
+};
+use confirm_dialog::{confirm_options, set_confirm_options, ConfirmDialog, ConfirmRequest};
+use cover_palette::{accent_color, record_accent, tint};
+use diagnostics::{init_diagnostics, traced, DiagnosticsWindow};
+use downloaded_detail::{DetailAction, DownloadedMapDetail};
+use errorbanner::{ErrorBanner, ErrorBannerAction};
+use followed_artists::FollowedArtists;
+use fuzzy::fuzzy_matches;
+use lastfm::LastFmPanel;
+use link_resolver::{parse_music_link, resolve_music_link};
+use link_selection::LinkSelection;
+use mapper_lists::{
+    is_hidden_mapper, mapper_lists, mapper_preference, set_mapper_lists, set_mapper_preference,
+    MapperPreference,
+};
+use match_memory::{
+    confirmed_beatmapset, forget_match, is_reported_wrong, reject_match, remember_match,
+    remember_match_id, report_wrong_match, spotify_track_id, MatchMemoryEditor,
+};
+use matcher::{
+    duration_mismatch, match_options, rank_beatmapsets, set_match_options,
+    version_preference_label, ScoredBeatmapset, AUTO_MIN_SCORE_RANGE, MAP_GENRES, MAP_LANGUAGES,
+    VERSION_PREFERENCES,
+};
+use media_keys::{media_key_options, set_media_key_options, MediaKeyAction, MediaKeyListener};
+use osu_client::{installed_clients, osu_client_options, set_osu_client_options, OsuClient};
+use osufavourites::{FavouritesAction, OsuFavourites};
 use osuhelper::OsuHelper;
+use pinned_playlists::{
+    is_pinned, pinned_playlists, refresh_pinned_names, sort_pinned_first, toggle_pinned,
+};
+use playlist_folders::{
+    add_folder, folder_index_of, move_to_folder, playlist_folders, remove_folder, rename_folder,
+};
+use playlistbuilder::{
+    parse_downloaded_file_name, PlaylistBuilder, PlaylistBuilderAction, PlaylistTarget,
+};
+use plugin_actions::{
+    actions_for, beatmapset_payload, spawn_action, track_payload, ActionTarget, PluginAction,
+    PluginActionEditor,
+};
+use preview_cache::{preview_cache_options, set_preview_cache_options};
+use preview_compare::{osu_preview_url, CompareAction, CompareSources, PreviewCompare};
+use preview_effects::{
+    BufferedPreview, EqPreset, MAX_PREVIEW_SPEED, MIN_PREVIEW_SPEED, SPEED_PRESETS,
+};
+use preview_playlist::{export_preview_playlist, PreviewSource};
+use query_normalizer::{
+    duplicate_key, query_options, query_variants, search_beatmapsets_normalized, set_query_options,
+    SEARCH_MARKETS,
+};
+use quick_actions::{
+    action_label, help_pressed, pressed_action, quick_actions, ResultKind, HELP_TEXT,
+};
+use rate_limit::render_rate_limit_indicator;
+use recently_viewed::{
+    clear_recently_viewed, recently_viewed, record_view, remove_viewed, ViewedItem,
+};
+use release_radar::ReleaseRadar;
+use report::{export_report, ReportEntry, ReportMatch, ReportTrack};
+use review_queue::{remove_review_item, review_count, review_items, ReviewItem};
+use scheduler::{
+    format_local_time, parse_local_time, DownloadScheduler, ScheduleTrigger, DEFAULT_IDLE_MINUTES,
+};
+use scripts::ScriptsPage;
+use search_suggestions::SearchSuggestions;
+use session::{avatar_path, migrate_legacy_avatar, LogoutMode, SessionManager, SpotifySession};
+use settings_bundle::{apply_pending_import, bundle_contents, export_bundle, import_bundle};
+use storage::{set_storage_options, storage_options, StorageBackend};
+use texture_budget::{
+    set_texture_budget_options, texture_budget_options, texture_bytes, TextureBudget,
+    TEXTURE_BUDGET_MB_RANGE,
+};
+use texturequeue::{
+    downscale_image, fetch_cover_image, TextureLoadQueue, COVER_FETCH_CONCURRENCY, PREFETCH_MARGIN,
+    THUMBNAIL_SIZE,
+};
+use trash::{
+    empty_trash, purge_expired_trash, purge_items, restore_items, set_trash_options, trash_items,
+    trash_options, trash_size, TrashedItem, UndoToast,
+};
+use updater::{
+    check_latest_release, download_release, ReleaseInfo, UpdateDownloadStatus, CURRENT_VERSION,
+};
+use watch_folder::{set_watch_folder_options, watch_folder_options, ImportMode, WatchFolder};
 
 const BASE_SIDE_MENU_WIDTH: f32 = 300.0;
 const MIN_SIDE_MENU_WIDTH: f32 = 200.0;
 const MAX_SIDE_MENU_WIDTH: f32 = 500.0;
 const BUTTON_SIZE: f32 = 40.0;
-const ANIMATION_SPEED: f32 = 4.0;
 const SEARCH_BAR_WIDTH_RATIO: f32 = 0.6;
+// 滑鼠滾輪每格調整的音量
+const VOLUME_SCROLL_STEP: f32 = 0.05;
+// 沒有進行中的工作時，輪詢背景狀態（目前播放、設定錯誤等）的間隔
+const IDLE_REPAINT_INTERVAL: Duration = Duration::from_secs(1);
+// 搜尋、下載或預覽播放時的重繪間隔
+const ACTIVE_REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+// Spotify 搜尋每次請求的曲目數與 API 允許的最大 offset（含 limit）
+const SPOTIFY_SEARCH_PAGE_SIZE: u32 = 50;
+const SPOTIFY_SEARCH_MAX_OFFSET: u32 = 1000;
+// 待確認頁面的 A/B 比較不屬於任何搜尋結果，以此為基準加上列表索引避免與曲目索引重疊
+const REVIEW_COMPARE_INDEX_BASE: usize = usize::MAX / 2;
+// 曲目數達到此數量才顯示「跳至」字母列
+const JUMP_BAR_MIN_TRACKS: usize = 100;
+// 搜尋結果中至少有這麼多譜面屬於同一個譜面包才提示下載整個譜面包
+const PACK_HINT_MIN_MATCHES: usize = 2;
 
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -110,13 +315,162 @@ pub enum DownloadStatus {
     Downloading,
     Completed,
 }
+// 播放清單曲目旁的配對狀態標記
+#[derive(Clone, Copy, PartialEq)]
+enum TrackMatchBadge {
+    Matched,
+    Downloaded,
+}
+// osu! 搜尋結果的排序方式，相關度即 API 回傳的順序
+#[derive(Clone, Copy, PartialEq)]
+enum OsuResultSort {
+    Relevance,
+    Favourites,
+    PlayCount,
+}
+
+impl OsuResultSort {
+    const ALL: [OsuResultSort; 3] = [
+        OsuResultSort::Relevance,
+        OsuResultSort::Favourites,
+        OsuResultSort::PlayCount,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            OsuResultSort::Relevance => "相關度",
+            OsuResultSort::Favourites => "收藏數",
+            OsuResultSort::PlayCount => "遊玩次數",
+        }
+    }
+}
+// 定義 SearchMode 列舉，用於切換 Spotify 搜尋曲目、專輯或 Podcast 節目
+#[derive(Clone, Copy, PartialEq)]
+pub enum SearchMode {
+    Track,
+    Album,
+    Episode,
+}
+// 專輯內單首曲目對應到的 osu! 譜面
+#[derive(Clone)]
+struct AlbumTrackMatches {
+    track_name: String,
+    beatmapsets: Vec<Beatmapset>,
+}
+#[derive(Clone)]
+enum AlbumMatchState {
+    Loading,
+    Loaded(Vec<AlbumTrackMatches>),
+    Failed(String),
+}
+// 單首 Spotify 曲目的 osu! 反向搜尋結果
+#[derive(Clone)]
+enum TrackMatchState {
+    Loading,
+    Loaded(Vec<ScoredBeatmapset>),
+    Failed(String),
+}
+// 封面載入失敗記錄的索引，Spotify 以網址、osu! 以搜尋結果索引區分
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum CoverKey {
+    Spotify(String),
+    Osu(usize),
+}
+// 需要先經過確認對話框的操作，多數為破壞性操作
+enum ConfirmAction {
+    DeleteMaps(Vec<String>),
+    DeleteBeatmapset(i32),
+    ClearCache(CacheKind),
+    EmptyTrash,
+    PurgeTrashItems(Vec<TrashedItem>),
+    DownloadPlaylistTracks {
+        playlist_name: String,
+        tracks: Vec<FullTrack>,
+    },
+    ImportSettings {
+        path: PathBuf,
+        include_credentials: bool,
+    },
+    // 譜面包中尚未下載的譜面集
+    DownloadBeatmapPack(Vec<i32>),
+    // 掃描下載資料夾時發現遺失或損毀的譜面
+    RedownloadMaps(Vec<IntegrityProblem>),
+    // 更換下載目錄後，把舊目錄的圖譜帶到新目錄
+    MigrateDownloads {
+        from: PathBuf,
+        file_names: Vec<String>,
+        mode: MigrationMode,
+    },
+}
+// Spotify 搜尋結果的一筆，alternates 為同一首歌在其他專輯（單曲、合輯等）的版本
+struct SpotifyResultGroup {
+    track: Track,
+    alternates: Vec<Track>,
+}
+
+// 關鍵字搜尋的分頁狀態，只有一般曲目搜尋可以向後翻頁
+struct SpotifySearchPaging {
+    query: String,
+    // 翻頁時沿用第一頁的搜尋地區
+    markets: Vec<String>,
+    next_offset: u32,
+    total: u32,
+}
+
+impl SpotifySearchPaging {
+    fn has_more(&self) -> bool {
+        self.next_offset < self.total.min(SPOTIFY_SEARCH_MAX_OFFSET)
+    }
+}
+
 // 定義 PlaylistCache 結構，用於緩存播放列表曲目
 #[derive(Serialize, Deserialize)]
 struct PlaylistCache {
-    tracks: Vec<FullTrack>,
+    tracks: Vec<PlaylistEntry>,
+    last_updated: SystemTime,
+}
+
+impl PlaylistCache {
+    fn summary(&self) -> PlaylistSummary {
+        PlaylistSummary {
+            track_count: self.tracks.len(),
+            total_duration: self
+                .tracks
+                .iter()
+                .filter_map(PlaylistEntry::track)
+                .map(|track| track.duration.to_std().unwrap_or_default())
+                .sum(),
+            last_updated: self.last_updated,
+        }
+    }
+}
+
+// 播放清單列表顯示的曲目數、總長度與快取更新時間
+#[derive(Clone, Copy)]
+struct PlaylistSummary {
+    track_count: usize,
+    total_duration: Duration,
     last_updated: SystemTime,
 }
 
+impl PlaylistSummary {
+    fn describe(&self) -> String {
+        let minutes = (self.total_duration.as_secs() + 30) / 60;
+        let duration = if minutes >= 60 {
+            format!("{} 小時 {} 分鐘", minutes / 60, minutes % 60)
+        } else {
+            format!("{} 分鐘", minutes)
+        };
+        let updated: DateTime<chrono::Local> = self.last_updated.into();
+        format!(
+            "{} 首 · {} · 更新於 {}",
+            self.track_count,
+            duration,
+            updated.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
+
 // 定義 AuthManager 結構，儲存授權狀態和錯誤記錄
 pub struct AuthManager {
     status: ParkingLotMutex<HashMap<AuthPlatform, AuthStatus>>,
@@ -185,18 +539,63 @@ struct SearchApp {
 
     // 搜索相關
     search_query: String,
+    search_suggestions: SearchSuggestions,
+    search_mode: SearchMode,
+    // 短網址解析完成後的實際網址，由 update 取出並重新搜尋
+    resolved_short_link: Arc<Mutex<Option<String>>>,
+    album_search_results: Arc<tokio::sync::Mutex<Vec<Album>>>,
+    expanded_album_id: Option<String>,
+    album_osu_matches: Arc<Mutex<HashMap<String, AlbumMatchState>>>,
+    track_osu_matches: Arc<Mutex<HashMap<usize, TrackMatchState>>>,
     is_searching: Arc<AtomicBool>,
     search_results: Arc<tokio::sync::Mutex<Vec<Track>>>,
     osu_search_results: Arc<tokio::sync::Mutex<Vec<Beatmapset>>>,
+    osu_search_query: Arc<Mutex<String>>,
+    osu_search_cursor: Arc<Mutex<Option<String>>>,
+    osu_search_source: Arc<Mutex<BeatmapSourceKind>>,
+    beatmap_source: BeatmapSourceKind,
+    osu_total_results: Arc<Mutex<Option<u32>>>,
+    is_loading_more_osu: Arc<AtomicBool>,
+    spotify_search_paging: Arc<Mutex<Option<SpotifySearchPaging>>>,
+    is_loading_more_spotify: Arc<AtomicBool>,
     displayed_spotify_results: usize,
     displayed_osu_results: usize,
     downloaded_maps_search: String,
+    // 已載入結果的本地篩選
+    osu_results_filter: String,
+    spotify_results_filter: String,
+    osu_result_sort: OsuResultSort,
+    // 只顯示含有指定鍵數難度的 mania 譜面
+    osu_key_filter: Option<u32>,
+    // 正在讀取內容的譜面包，讀取完成後顯示確認對話框
+    loading_beatmap_pack: Option<String>,
+    fetched_beatmap_pack: Arc<Mutex<Option<Result<BeatmapPack, String>>>>,
+    scanning_downloads: bool,
+    download_scan_result: Arc<Mutex<Option<Vec<IntegrityProblem>>>>,
+    // 更換下載目錄時複製而不是搬移舊目錄的圖譜
+    migrate_by_copy: bool,
+    migration_progress: Arc<MigrationProgress>,
+    migration_result: Arc<Mutex<Option<MigrationReport>>>,
+    // 上次為排序後的顯示範圍補載封面時的 (排序, 顯示數, 結果數)
+    sorted_covers_loaded_for: Option<(OsuResultSort, Option<u32>, usize, usize)>,
     playlist_search_query: String,
+    // 正在輸入的新資料夾名稱
+    new_folder_name: Option<String>,
+    // 正在重新命名的資料夾索引與名稱
+    renaming_folder: Option<(usize, String)>,
     tracks_search_query: String,
+    // 「跳至」選擇的列，下一次繪製曲目列表時捲動到該列
+    jump_to_row: Option<usize>,
+    // 只列出還沒有下載過譜面的曲目，方便逐步收集
+    only_undownloaded_tracks: bool,
+    // 設定頁面新增譜面作者的輸入框
+    new_mapper_name: String,
+    // 匯出／匯入設定時是否包含登入 token 與 API 金鑰
+    bundle_include_credentials: bool,
 
     // 播放列表和曲目
     spotify_user_playlists: Arc<Mutex<Vec<SimplifiedPlaylist>>>,
-    spotify_playlist_tracks: Arc<Mutex<Vec<FullTrack>>>,
+    spotify_playlist_tracks: Arc<Mutex<Vec<PlaylistEntry>>>,
     spotify_liked_tracks: Arc<Mutex<Vec<FullTrack>>>,
     selected_playlist: Option<SimplifiedPlaylist>,
     currently_playing: Arc<Mutex<Option<CurrentlyPlaying>>>,
@@ -213,10 +612,31 @@ struct SearchApp {
     global_font_size: f32,
     search_bar_expanded: bool,
     is_beatmap_playing: bool,
+    preview_speed: f32,
+    preview_eq: EqPreset,
+    // Spotify 試聽與 osu! 預覽的 A/B 比較
+    preview_compare: Option<PreviewCompare>,
+    media_keys: Option<MediaKeyListener>,
+    // 遠端控制 HTTP 伺服器，未啟用時為 None
+    api_server: Option<ApiServer>,
+    match_memory_editor: MatchMemoryEditor,
+    downloaded_detail: DownloadedMapDetail,
+    // 搜尋結果的多選複製連結
+    link_selection: LinkSelection,
+    diagnostics_window: DiagnosticsWindow,
     scale_factor: f32,
     is_first_update: bool,
     show_downloaded_maps: bool,
+    show_download_manager: bool,
+    show_review_queue: bool,
+    show_recently_viewed: bool,
     expanded_map_indices: HashSet<String>,
+    selected_downloaded_maps: HashSet<String>,
+    cleanup_days: u64,
+    confirm_dialog: ConfirmDialog<ConfirmAction>,
+    session_manager: SessionManager,
+    // 刪除後可以復原的提示
+    undo_toast: Option<UndoToast>,
     show_osu_search_bar: bool,
     show_playlist_search_bar: bool,
     show_tracks_search_bar: bool,
@@ -226,10 +646,17 @@ struct SearchApp {
     avatar_load_handle: Option<tokio::task::JoinHandle<()>>,
     cover_textures: Arc<RwLock<HashMap<usize, Option<(Arc<TextureHandle>, (f32, f32))>>>>,
     playlist_cover_textures: Arc<Mutex<HashMap<String, Option<TextureHandle>>>>,
+    // 以播放清單 ID 為鍵，None 表示正在讀取
+    playlist_summaries: Arc<Mutex<HashMap<String, Option<PlaylistSummary>>>>,
     default_avatar_texture: Option<egui::TextureHandle>,
-    spotify_icon: Option<egui::TextureHandle>,
     texture_cache: Arc<RwLock<HashMap<String, Arc<TextureHandle>>>>,
+    cover_load_errors: Arc<Mutex<HashMap<CoverKey, String>>>,
+    // cover_textures 與 texture_cache 共用的記憶體上限
+    texture_budget: Arc<Mutex<TextureBudget<CoverKey>>>,
+    // 被淘汰的 osu! 封面索引，捲回畫面時重新載入
+    evicted_osu_covers: HashSet<usize>,
     preloaded_icons: HashMap<String, egui::TextureHandle>,
+    icon_loader: IconLoader,
 
     // 網絡和客戶端
     client: Arc<tokio::sync::Mutex<Client>>,
@@ -237,6 +664,7 @@ struct SearchApp {
 
     // 錯誤處理
     err_msg: Arc<tokio::sync::Mutex<String>>,
+    error_banner: ErrorBanner,
     error_message: Arc<tokio::sync::Mutex<String>>,
     config_errors: Arc<Mutex<Vec<String>>>,
 
@@ -257,44 +685,89 @@ struct SearchApp {
     global_volume: f32,
     expanded_track_index: Option<usize>,
     expanded_beatmapset_index: Option<usize>,
+    // 最後展開操作列的結果種類，快捷鍵作用在它上面
+    focused_result: Option<ResultKind>,
+    show_shortcut_help: bool,
 
     // 其他功能
     debug_mode: bool,
     ctx: egui::Context,
     selected_beatmapset: Option<usize>,
+    // 詳細資訊頁的原尺寸封面 (譜面集 ID, 紋理)，離開頁面即釋放
+    detail_cover: Arc<Mutex<Option<(i32, Option<TextureHandle>)>>>,
+    // 詳細資訊頁的譜面說明 (譜面集 ID, 解析後的說明或錯誤訊息)
+    detail_description: Arc<Mutex<Option<(i32, Option<Result<BbcodeDocument, String>>)>>>,
     should_detect_now_playing: Arc<AtomicBool>,
     spotify_track_liked_status: Arc<Mutex<HashMap<String, bool>>>,
     osu_download_statuses: HashMap<usize, DownloadStatus>,
     osu_helper: OsuHelper,
+    batch_import: BatchImport,
+    batch_like: BatchLike,
+    lastfm: LastFmPanel,
+    release_radar: ReleaseRadar,
+    followed_artists: FollowedArtists,
+    scripts: ScriptsPage,
+    osu_favourites: OsuFavourites,
+    playlist_builder: PlaylistBuilder,
 
     // 快取
     liked_songs_cache: Arc<Mutex<Option<PlaylistCache>>>,
-    cache_ttl: Duration,
-    texture_load_queue: Arc<Mutex<BinaryHeap<Reverse<(usize, String)>>>>,
+    cache_manager: CacheManager,
+    // 播放列表快取的讀取進度
+    cache_progress: Arc<CacheProgress>,
+    cache_sizes: Option<Vec<(CacheKind, u64)>>,
+    texture_load_queue: Arc<Mutex<TextureLoadQueue>>,
 
     // 更新檢查
     update_check_result: Arc<Mutex<Option<bool>>>,
     update_check_sender: Sender<bool>,
     update_check_receiver: Receiver<bool>,
+    app_update_release: Arc<Mutex<Option<ReleaseInfo>>>,
+    app_update_download_status: Arc<Mutex<UpdateDownloadStatus>>,
+    app_update_message: Arc<Mutex<Option<String>>>,
+    show_app_update_dialog: Arc<AtomicBool>,
     last_background_key: String,
 
     // 下載相關
     download_directory: PathBuf,
     status_sender: tokio::sync::mpsc::Sender<(i32, DownloadStatus)>,
     status_receiver: tokio::sync::mpsc::Receiver<(i32, DownloadStatus)>,
-    download_queue_sender: mpsc::Sender<i32>,
-    download_queue_receiver: Arc<Mutex<Option<mpsc::Receiver<i32>>>>,
+    download_queue_sender: mpsc::UnboundedSender<i32>,
+    download_queue_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<i32>>>>,
     download_semaphore: Arc<Semaphore>,
     current_downloads: Arc<AtomicUsize>,
+    download_scheduler: DownloadScheduler,
+    schedule_time_inputs: HashMap<i32, String>,
+    filename_template_input: String,
+    // 因磁碟空間不足而暫停的下載
+    blocked_downloads: Vec<i32>,
+    // 搜尋列直接下載的譜面集 ID 與是否已下載，避免每一幀都讀取下載目錄
+    direct_download_downloaded: Option<(i32, bool)>,
+    // 列出大量譜面的頁面共用，判斷下載狀態時不必逐一讀取下載目錄
+    downloaded_ids: DownloadedIds,
+    // 目前這一批下載中各譜面的最後狀態，全部結束後發送通知
+    download_batch: HashMap<i32, DownloadStatus>,
+    webhook_url_input: String,
+    plugin_editor: PluginActionEditor,
+    watch_folder: WatchFolder,
 
     // 預覽播放
     audio_output: Option<(OutputStream, OutputStreamHandle)>,
     current_previews: Arc<TokioMutex<HashMap<i32, Sink>>>,
+    // 已緩衝的預覽音訊，進度條跳轉時使用
+    preview_buffers: Arc<TokioMutex<HashMap<i32, BufferedPreview>>>,
+    // 拖曳進度條時顯示的位置（秒），放開後才跳轉
+    preview_seek_drag: Option<(i32, f32)>,
 
     // 自定義背景
     custom_background_path: Option<PathBuf>,
     custom_background: Option<egui::TextureHandle>,
+    background_load_handle: Option<JoinHandle<Result<egui::TextureHandle>>>,
     need_load_background: bool,
+
+    // 崩潰復原
+    session_state: Arc<Mutex<SessionState>>,
+    pending_crash_report: Option<PathBuf>,
 }
 
 impl eframe::App for SearchApp {
@@ -302,28 +775,44 @@ impl eframe::App for SearchApp {
         if !self.initialized {
             self.initialize(ctx);
         }
-        if self.need_load_background {
-            self.load_background(ctx);
-            self.need_load_background = false;
-        }
         if self.is_first_update {
             ctx.set_pixels_per_point(self.scale_factor);
             self.is_first_update = false;
+        } else if self.need_load_background {
+            // 第一幀畫出後才開始解碼自定義背景
+            self.load_background(ctx);
+            self.need_load_background = false;
         }
+        self.receive_loaded_textures();
 
+        self.texture_load_queue.lock().unwrap().begin_frame();
+        self.texture_budget.lock().unwrap().begin_frame();
         self.handle_avatar_loading(ctx);
         self.check_auth_status();
         self.handle_config_errors(ctx);
+        self.downloaded_ids.refresh(&self.download_directory);
         self.update_ui(ctx);
+        self.evict_textures();
         self.handle_debug_mode();
         self.update_current_playing(ctx);
         self.handle_download_status_updates();
+        self.poll_blocked_schedules();
+        self.poll_download_migration();
+        self.handle_resolved_short_link();
+        self.sync_batch_like_results();
+        self.handle_media_keys();
+        self.handle_shortcut_help(ctx);
+        self.handle_remote_commands();
         self.check_and_update_avatar(ctx);
+        self.sync_session_state();
 
-        ctx.request_repaint();
+        self.schedule_repaint(ctx);
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(e) = save_session_state(&self.session_state.lock().unwrap()) {
+            error!("保存工作階段狀態失敗: {:?}", e);
+        }
         self.clean_up_resources();
     }
 }
@@ -331,12 +820,160 @@ impl eframe::App for SearchApp {
 impl SearchApp {
     fn initialize(&mut self, ctx: &egui::Context) {
         self.spawn_osu_cover_loader(ctx);
-        self.spawn_texture_receiver();
+        self.spawn_texture_receiver(ctx);
         self.spawn_access_token_fetcher();
         self.spawn_error_message_handler(ctx);
+        self.check_app_update(ctx, false);
+        self.diagnostics_window
+            .run_health_checks(ctx, &self.download_directory, self.debug_mode);
+        self.diagnostics_window.show_health_panel();
         self.initialized = true;
     }
 
+    // 檢查應用程式更新，manual 為 true 時即使沒有新版本也會提示使用者
+    fn check_app_update(&self, ctx: &egui::Context, manual: bool) {
+        let app_update_release = self.app_update_release.clone();
+        let app_update_message = self.app_update_message.clone();
+        let show_app_update_dialog = self.show_app_update_dialog.clone();
+        let debug_mode = self.debug_mode;
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            let client = Client::new();
+            match check_latest_release(&client, debug_mode).await {
+                Ok(Some(release)) => {
+                    *app_update_release.lock().unwrap() = Some(release);
+                    *app_update_message.lock().unwrap() = None;
+                    show_app_update_dialog.store(true, Ordering::SeqCst);
+                }
+                Ok(None) => {
+                    if manual {
+                        *app_update_message.lock().unwrap() =
+                            Some(format!("目前已是最新版本 ({})", CURRENT_VERSION));
+                    }
+                }
+                Err(e) => {
+                    error!("檢查應用程式更新失敗: {:?}", e);
+                    if manual {
+                        *app_update_message.lock().unwrap() = Some(format!("檢查更新失敗: {}", e));
+                    }
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn download_app_update(&self, ctx: &egui::Context) {
+        let release = match self.app_update_release.lock().unwrap().clone() {
+            Some(release) => release,
+            None => return,
+        };
+        let status = self.app_update_download_status.clone();
+        let download_directory = self.download_directory.clone();
+        let ctx = ctx.clone();
+
+        *status.lock().unwrap() = UpdateDownloadStatus::Downloading;
+        tokio::spawn(async move {
+            let client = Client::new();
+            let result = download_release(&client, &release, &download_directory).await;
+            *status.lock().unwrap() = match result {
+                Ok(path) => UpdateDownloadStatus::Completed(path),
+                Err(e) => {
+                    error!("下載應用程式更新失敗: {:?}", e);
+                    UpdateDownloadStatus::Failed(e.to_string())
+                }
+            };
+            ctx.request_repaint();
+        });
+    }
+
+    fn render_app_update_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_app_update_dialog.load(Ordering::SeqCst) {
+            return;
+        }
+        let release = match self.app_update_release.lock().unwrap().clone() {
+            Some(release) => release,
+            None => return,
+        };
+        let download_status = self.app_update_download_status.lock().unwrap().clone();
+        let mut open = true;
+
+        egui::Window::new("發現新版本")
+            .collapsible(false)
+            .resizable(true)
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "目前版本: {}    最新版本: {}",
+                    CURRENT_VERSION,
+                    release.version()
+                ));
+                if let Some(name) = &release.name {
+                    ui.heading(name);
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        ui.label(release.body.as_deref().unwrap_or("沒有更新說明"));
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    match &download_status {
+                        UpdateDownloadStatus::Idle | UpdateDownloadStatus::Failed(_) => {
+                            if let Some(asset) = release.preferred_asset() {
+                                let label = format!(
+                                    "下載更新 ({:.1} MB)",
+                                    asset.size as f64 / 1024.0 / 1024.0
+                                );
+                                if ui.button(label).clicked() {
+                                    self.download_app_update(ctx);
+                                }
+                            }
+                        }
+                        UpdateDownloadStatus::Downloading => {
+                            ui.add(egui::Spinner::new());
+                            ui.label("下載中...");
+                        }
+                        UpdateDownloadStatus::Completed(path) => {
+                            if ui.button("開啟所在資料夾").clicked() {
+                                if let Some(parent) = path.parent() {
+                                    if let Err(e) = open::that(parent) {
+                                        error!("無法開啟資料夾: {:?}", e);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if ui.button("開啟發布頁面").clicked() {
+                        if let Err(e) = open::that(&release.html_url) {
+                            error!("無法開啟發布頁面: {:?}", e);
+                        }
+                    }
+                    if ui.button("稍後").clicked() {
+                        self.show_app_update_dialog.store(false, Ordering::SeqCst);
+                    }
+                });
+
+                match &download_status {
+                    UpdateDownloadStatus::Completed(path) => {
+                        ui.label(format!("已下載至: {}", path.to_string_lossy()));
+                    }
+                    UpdateDownloadStatus::Failed(e) => {
+                        ui.colored_label(egui::Color32::RED, format!("下載失敗: {}", e));
+                    }
+                    _ => {}
+                }
+            });
+
+        if !open {
+            self.show_app_update_dialog.store(false, Ordering::SeqCst);
+        }
+    }
+
     fn spawn_osu_cover_loader(&self, ctx: &egui::Context) {
         let sender = self.sender.clone();
         let ctx = ctx.clone();
@@ -353,10 +990,12 @@ impl SearchApp {
         match load_background_path() {
             Ok(Some(path)) => {
                 self.custom_background_path = Some(path.clone());
-                if let Err(e) = self.load_custom_background(ctx) {
-                    error!("加載自定義背景失敗: {:?}", e);
-                    self.custom_background_path = None;
-                }
+                let ctx = ctx.clone();
+                self.background_load_handle = Some(tokio::task::spawn_blocking(move || {
+                    let result = load_image_file(&ctx, "custom_background", &path);
+                    ctx.request_repaint();
+                    result
+                }));
             }
             Ok(None) => {
                 // 沒有保存的背景路徑，使用默認背景
@@ -367,6 +1006,44 @@ impl SearchApp {
         }
     }
 
+    // 收下背景執行緒解碼完成的圖示與自定義背景
+    fn receive_loaded_textures(&mut self) {
+        for (path, texture) in self.icon_loader.poll() {
+            self.preloaded_icons.insert(path, texture);
+        }
+
+        let finished = self
+            .background_load_handle
+            .as_ref()
+            .map_or(false, |handle| handle.is_finished());
+        if !finished {
+            return;
+        }
+        if let Some(handle) = self.background_load_handle.take() {
+            match futures::executor::block_on(handle) {
+                Ok(Ok(texture)) => self.custom_background = Some(texture),
+                Ok(Err(e)) => {
+                    error!("加載自定義背景失敗: {:?}", e);
+                    self.custom_background_path = None;
+                }
+                Err(e) => error!("自定義背景載入任務失敗: {:?}", e),
+            }
+        }
+    }
+
+    // 記錄 load_osu_covers 回傳的失敗索引，讓結果區顯示錯誤佔位圖
+    fn record_osu_cover_errors(
+        cover_load_errors: &Mutex<HashMap<CoverKey, String>>,
+        error: &OsuError,
+    ) {
+        if let OsuError::CoverLoadFailed(failed) = error {
+            let mut errors = cover_load_errors.lock().unwrap();
+            for (index, message) in failed {
+                errors.insert(CoverKey::Osu(*index), message.clone());
+            }
+        }
+    }
+
     fn handle_osu_cover_load_error(e: impl std::fmt::Debug, debug_mode: bool, ctx: &egui::Context) {
         error!("初始化時載入 osu 封面發生錯誤: {:?}", e);
         if debug_mode {
@@ -377,13 +1054,14 @@ impl SearchApp {
         }
     }
 
-    fn spawn_texture_receiver(&mut self) {
+    fn spawn_texture_receiver(&mut self, ctx: &egui::Context) {
         let receiver = self.receiver.take().expect("Receiver already taken");
         let cover_textures = Arc::downgrade(&self.cover_textures);
-        let need_repaint = Arc::downgrade(&self.need_repaint);
+        let texture_budget = self.texture_budget.clone();
+        let ctx = ctx.clone();
 
         tokio::spawn(async move {
-            Self::process_texture_updates(receiver, cover_textures, need_repaint).await;
+            Self::process_texture_updates(receiver, cover_textures, texture_budget, ctx).await;
         });
     }
 
@@ -392,23 +1070,22 @@ impl SearchApp {
         cover_textures: std::sync::Weak<
             RwLock<HashMap<usize, Option<(Arc<TextureHandle>, (f32, f32))>>>,
         >,
-        need_repaint: std::sync::Weak<AtomicBool>,
+        texture_budget: Arc<Mutex<TextureBudget<CoverKey>>>,
+        ctx: egui::Context,
     ) {
         while let Some((id, texture, dimensions)) = receiver.recv().await {
-            if let (Some(cover_textures), Some(need_repaint)) =
-                (cover_textures.upgrade(), need_repaint.upgrade())
-            {
-                let mut textures = cover_textures.write().await;
-                textures.insert(id, Some((texture, dimensions)));
-
-                // 實現緩存淘汰策略
-                if textures.len() > 1000 {
-                    // 設置最大容量限制
-                    let oldest_id = *textures.keys().next().unwrap();
-                    textures.remove(&oldest_id);
-                }
+            if let Some(cover_textures) = cover_textures.upgrade() {
+                texture_budget
+                    .lock()
+                    .unwrap()
+                    .insert(CoverKey::Osu(id), texture_bytes(&texture));
+                cover_textures
+                    .write()
+                    .await
+                    .insert(id, Some((texture, dimensions)));
 
-                need_repaint.store(true, Ordering::SeqCst);
+                // 畫面閒置時不會自動重繪，封面到達後主動喚醒
+                ctx.request_repaint();
             } else {
                 break;
             }
@@ -643,6 +1320,43 @@ impl SearchApp {
             });
     }
 
+    // 閒置時只以較長間隔輪詢背景狀態，有搜尋、下載或預覽播放時才提高重繪頻率
+    fn schedule_repaint(&self, ctx: &egui::Context) {
+        let interval = if self.has_active_work() {
+            ACTIVE_REPAINT_INTERVAL
+        } else {
+            IDLE_REPAINT_INTERVAL
+        };
+        ctx.request_repaint_after(interval);
+    }
+
+    fn has_active_work(&self) -> bool {
+        if self.is_searching.load(Ordering::SeqCst)
+            || self.auth_in_progress.load(Ordering::SeqCst)
+            || self.batch_like.is_running()
+        {
+            return true;
+        }
+        let downloading = self
+            .beatmapset_download_statuses
+            .lock()
+            .unwrap()
+            .values()
+            .any(|status| {
+                matches!(
+                    status,
+                    DownloadStatus::Waiting | DownloadStatus::Downloading
+                )
+            });
+        // 預覽播放結束時要更新播放按鈕
+        downloading
+            || self.current_previews.try_lock().map_or(false, |previews| {
+                previews
+                    .values()
+                    .any(|sink| !sink.empty() && !sink.is_paused())
+            })
+    }
+
     fn update_ui(&mut self, ctx: &egui::Context) {
         if self
             .need_repaint
@@ -652,66 +1366,407 @@ impl SearchApp {
             ctx.request_repaint();
         }
 
+        if !self.icon_loader.icons_ready() {
+            self.render_splash(ctx);
+            return;
+        }
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             self.render_top_panel(ui);
         });
 
         self.render_side_menu(ctx);
         self.render_central_panel(ctx);
+        self.render_app_update_dialog(ctx);
+        self.render_crash_report_dialog(ctx);
+        self.match_memory_editor.render(ctx);
+        self.render_downloaded_detail(ctx);
+        self.render_undo_toast(ctx);
+        self.render_confirm_dialog(ctx);
+        if let Some(mode) = self.session_manager.render_logout_dialog(ctx) {
+            self.logout_spotify(mode);
+        }
+        self.diagnostics_window.render(ctx);
+        self.diagnostics_window.render_health_panel(ctx);
     }
 
-    fn handle_debug_mode(&mut self) {
-        if self.search_query.trim().to_lowercase() == "debug" {
-            self.debug_mode = !self.debug_mode;
-            set_log_level(self.debug_mode);
-            self.search_query.clear();
-            info!("Debug mode: {}", self.debug_mode);
+    // 使用者選擇不要再詢問時直接執行
+    fn request_confirmation(&mut self, request: ConfirmRequest<ConfirmAction>) {
+        if let Some(action) = self.confirm_dialog.request(request) {
+            self.run_confirmed_action(action);
         }
     }
 
-    fn update_current_playing(&self, ctx: &egui::Context) {
-        if self.should_update_current_playing()
-            && self.should_detect_now_playing.load(Ordering::SeqCst)
-        {
-            let spotify_client = Arc::downgrade(&self.spotify_client);
-            let currently_playing = Arc::downgrade(&self.currently_playing);
-            let debug_mode = self.debug_mode;
-            let ctx = ctx.clone();
-            let spotify_authorized = Arc::downgrade(&self.spotify_authorized);
-            let should_detect_now_playing = Arc::downgrade(&self.should_detect_now_playing);
+    fn render_confirm_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(action) = self.confirm_dialog.render(ctx) {
+            self.run_confirmed_action(action);
+        }
+    }
 
-            tokio::spawn(async move {
-                if let (
-                    Some(spotify_client),
-                    Some(currently_playing),
-                    Some(spotify_authorized),
-                    Some(should_detect_now_playing),
-                ) = (
-                    spotify_client.upgrade(),
-                    currently_playing.upgrade(),
-                    spotify_authorized.upgrade(),
-                    should_detect_now_playing.upgrade(),
-                ) {
-                    Self::update_and_handle_current_playing(
-                        spotify_client,
-                        currently_playing,
-                        debug_mode,
-                        ctx,
-                        spotify_authorized,
-                        should_detect_now_playing,
-                    )
-                    .await;
+    fn run_confirmed_action(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::DeleteMaps(file_names) => {
+                let trashed = delete_downloaded_maps(&self.download_directory, &file_names);
+                self.show_undo_toast(trashed);
+                for file_name in &file_names {
+                    self.selected_downloaded_maps.remove(file_name);
+                    self.expanded_map_indices.remove(file_name);
                 }
-            });
+            }
+            ConfirmAction::DeleteBeatmapset(beatmapset_id) => {
+                match delete_beatmap(&self.download_directory, beatmapset_id) {
+                    Ok(trashed) => {
+                        info!("成功刪除譜面 {}", beatmapset_id);
+                        self.show_undo_toast(trashed);
+                        self.beatmapset_download_statuses
+                            .lock()
+                            .unwrap()
+                            .insert(beatmapset_id, DownloadStatus::NotStarted);
+                    }
+                    Err(e) => {
+                        error!("無法刪除譜面 {}: {:?}", beatmapset_id, e);
+                    }
+                }
+            }
+            ConfirmAction::ClearCache(kind) => {
+                if let Err(e) = self.cache_manager.clear(kind) {
+                    error!("清除{}失敗: {:?}", kind.label(), e);
+                }
+                self.cache_sizes = None;
+                // 自訂檔名的譜面靠下載紀錄判斷是否已下載
+                self.downloaded_ids.invalidate();
+            }
+            ConfirmAction::EmptyTrash => empty_trash(),
+            ConfirmAction::PurgeTrashItems(items) => purge_items(&items),
+            ConfirmAction::DownloadPlaylistTracks {
+                playlist_name,
+                tracks,
+            } => {
+                let target = AutoDownloadTarget {
+                    download_directory: self.download_directory.clone(),
+                    download_queue_sender: self.download_queue_sender.clone(),
+                    download_statuses: self.beatmapset_download_statuses.clone(),
+                };
+                let client = self.client.clone();
+                let debug_mode = self.debug_mode;
+                tokio::spawn(async move {
+                    let client = client.lock().await.clone();
+                    download_playlist_tracks(client, playlist_name, tracks, target, debug_mode)
+                        .await;
+                });
+            }
+            ConfirmAction::ImportSettings {
+                path,
+                include_credentials,
+            } => {
+                let message = match import_bundle(&path, include_credentials) {
+                    Ok(count) => format!(
+                        "已匯入 {} 個設定檔，將在下次啟動時套用，請重新啟動程式",
+                        count
+                    ),
+                    Err(e) => {
+                        error!("匯入設定失敗: {:?}", e);
+                        format!("匯入設定失敗: {}", e)
+                    }
+                };
+                self.request_confirmation(ConfirmRequest::notice(
+                    "import_settings",
+                    "匯入設定",
+                    message,
+                ));
+            }
+            ConfirmAction::DownloadBeatmapPack(beatmapset_ids) => {
+                self.enqueue_beatmap_downloads("下載譜面包", beatmapset_ids);
+            }
+            ConfirmAction::RedownloadMaps(problems) => {
+                // 損毀的檔案會被新下載的檔案覆蓋，先移除舊的索引讓它重新讀取
+                let corrupt: Vec<String> = problems
+                    .iter()
+                    .filter(|problem| matches!(problem.issue, IntegrityIssue::Corrupt(_)))
+                    .map(|problem| problem.file_name.clone())
+                    .collect();
+                forget_maps(&corrupt);
+                let beatmapset_ids = problems
+                    .iter()
+                    .map(|problem| problem.beatmapset_id)
+                    .collect();
+                self.enqueue_beatmap_downloads("重新下載圖譜", beatmapset_ids);
+            }
+            ConfirmAction::MigrateDownloads {
+                from,
+                file_names,
+                mode,
+            } => self.start_download_migration(from, file_names, mode),
         }
     }
 
-    async fn update_and_handle_current_playing(
-        spotify_client: Arc<Mutex<Option<AuthCodeSpotify>>>,
-        currently_playing: Arc<Mutex<Option<CurrentlyPlaying>>>,
-        debug_mode: bool,
-        ctx: egui::Context,
-        spotify_authorized: Arc<AtomicBool>,
+    // 列出目前播放清單中還沒有下載過譜面的曲目，確認後配對並送入下載隊列
+    fn confirm_download_missing_tracks(&mut self) {
+        let (playlist_name, tracks) = if self.show_liked_tracks {
+            (
+                "Liked Songs".to_string(),
+                self.spotify_liked_tracks.lock().unwrap().clone(),
+            )
+        } else {
+            match &self.selected_playlist {
+                Some(playlist) => {
+                    let entries = self.spotify_playlist_tracks.lock().unwrap();
+                    let tracks = entries
+                        .iter()
+                        .filter_map(PlaylistEntry::track)
+                        .cloned()
+                        .collect();
+                    (playlist.name.clone(), tracks)
+                }
+                None => return,
+            }
+        };
+
+        let mut remembered = 0;
+        let mut details = Vec::new();
+        let mut missing = Vec::new();
+        for track in tracks {
+            let badge = self.track_match_badge(&track);
+            if badge == Some(TrackMatchBadge::Downloaded) {
+                continue;
+            }
+            let artists = track
+                .artists
+                .iter()
+                .map(|artist| artist.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let icon = if badge == Some(TrackMatchBadge::Matched) {
+                remembered += 1;
+                "🔗"
+            } else {
+                "🔍"
+            };
+            details.push(format!("{} {} - {}", icon, artists, track.name));
+            missing.push(track);
+        }
+
+        if missing.is_empty() {
+            self.request_confirmation(ConfirmRequest::notice(
+                "download_missing_tracks",
+                "下載缺少的譜面",
+                format!("「{}」的曲目都已下載過譜面", playlist_name),
+            ));
+            return;
+        }
+        let message = format!(
+            "將為「{}」中 {} 首尚未下載的曲目下載譜面：{} 首使用記住的配對，{} 首需要搜尋，分數不足的會加入需要確認",
+            playlist_name,
+            missing.len(),
+            remembered,
+            missing.len() - remembered
+        );
+        self.request_confirmation(
+            ConfirmRequest::new(
+                "download_missing_tracks",
+                "下載缺少的譜面",
+                message,
+                ConfirmAction::DownloadPlaylistTracks {
+                    playlist_name,
+                    tracks: missing,
+                },
+            )
+            .details(details)
+            .confirm_label("開始下載")
+            .always_ask(),
+        );
+    }
+
+    // 刪除已下載的圖譜，沒有符合條件的項目時只顯示提示
+    fn confirm_delete_maps(&mut self, title: &str, file_names: Vec<String>) {
+        if file_names.is_empty() {
+            self.request_confirmation(ConfirmRequest::notice(
+                "delete_maps",
+                title,
+                "沒有符合條件的圖譜",
+            ));
+            return;
+        }
+        let message = if trash_options().enabled {
+            format!("將以下 {} 個項目移到資源回收筒：", file_names.len())
+        } else {
+            format!(
+                "將永久刪除以下 {} 個項目，此操作無法復原：",
+                file_names.len()
+            )
+        };
+        self.request_confirmation(
+            ConfirmRequest::new(
+                "delete_maps",
+                "刪除圖譜",
+                message,
+                ConfirmAction::DeleteMaps(file_names.clone()),
+            )
+            .details(file_names)
+            .confirm_label("刪除"),
+        );
+    }
+
+    fn render_undo_toast(&mut self, ctx: &egui::Context) {
+        let toast = match &self.undo_toast {
+            Some(toast) => toast,
+            None => return,
+        };
+        if toast.render(ctx) {
+            restore_items(toast.items());
+            self.undo_toast = None;
+        } else if toast.is_expired() {
+            self.undo_toast = None;
+        }
+    }
+
+    // 刪除後顯示復原提示，未啟用資源回收筒時沒有可復原的項目
+    fn show_undo_toast(&mut self, items: Vec<TrashedItem>) {
+        self.undo_toast = if items.is_empty() {
+            None
+        } else {
+            Some(UndoToast::new(items))
+        };
+    }
+
+    fn render_downloaded_detail(&mut self, ctx: &egui::Context) {
+        match self.downloaded_detail.render(ctx) {
+            Some(DetailAction::Redownload {
+                beatmapset_id,
+                file_name,
+            }) => {
+                // 損毀的檔案仍在時先刪除，否則會被視為已下載
+                if self.download_directory.join(&file_name).exists() {
+                    let trashed = delete_downloaded_maps(&self.download_directory, &[file_name]);
+                    self.show_undo_toast(trashed);
+                }
+                self.enqueue_beatmap_download(beatmapset_id);
+            }
+            Some(DetailAction::FindSimilar(query)) => {
+                self.search_query = query;
+                self.perform_search(ctx.clone());
+            }
+            None => {}
+        }
+    }
+
+    // 圖示解碼完成前的啟動畫面
+    fn render_splash(&self, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 3.0);
+                ui.spinner();
+                ui.add_space(10.0);
+                ui.label("正在載入...");
+                ui.add(egui::ProgressBar::new(self.icon_loader.progress()).desired_width(240.0));
+            });
+        });
+    }
+
+    // 將需要在崩潰時保存的狀態同步到共享的 SessionState
+    fn sync_session_state(&self) {
+        let mut state = self.session_state.lock().unwrap();
+        if state.global_volume != self.global_volume {
+            state.global_volume = self.global_volume;
+        }
+        if state.search_query != self.search_query {
+            state.search_query = self.search_query.clone();
+        }
+    }
+
+    fn render_crash_report_dialog(&mut self, ctx: &egui::Context) {
+        let report_path = match &self.pending_crash_report {
+            Some(path) => path.clone(),
+            None => return,
+        };
+        let mut close = false;
+
+        egui::Window::new("程式上次異常結束")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("程式上次執行時發生錯誤並意外關閉，已保存崩潰報告:");
+                ui.label(report_path.to_string_lossy());
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("開啟報告").clicked() {
+                        if let Err(e) = open::that(&report_path) {
+                            error!("無法開啟崩潰報告: {:?}", e);
+                        }
+                        close = true;
+                    }
+                    if ui.button("開啟所在資料夾").clicked() {
+                        if let Some(parent) = report_path.parent() {
+                            if let Err(e) = open::that(parent) {
+                                error!("無法開啟資料夾: {:?}", e);
+                            }
+                        }
+                        close = true;
+                    }
+                    if ui.button("忽略").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if close {
+            self.pending_crash_report = None;
+        }
+    }
+
+    fn handle_debug_mode(&mut self) {
+        if self.search_query.trim().to_lowercase() == "debug" {
+            self.debug_mode = !self.debug_mode;
+            set_log_level(self.debug_mode);
+            self.search_query.clear();
+            info!("Debug mode: {}", self.debug_mode);
+        }
+    }
+
+    fn update_current_playing(&self, ctx: &egui::Context) {
+        if self.should_update_current_playing()
+            && self.should_detect_now_playing.load(Ordering::SeqCst)
+        {
+            let spotify_client = Arc::downgrade(&self.spotify_client);
+            let currently_playing = Arc::downgrade(&self.currently_playing);
+            let debug_mode = self.debug_mode;
+            let ctx = ctx.clone();
+            let spotify_authorized = Arc::downgrade(&self.spotify_authorized);
+            let should_detect_now_playing = Arc::downgrade(&self.should_detect_now_playing);
+
+            tokio::spawn(async move {
+                if let (
+                    Some(spotify_client),
+                    Some(currently_playing),
+                    Some(spotify_authorized),
+                    Some(should_detect_now_playing),
+                ) = (
+                    spotify_client.upgrade(),
+                    currently_playing.upgrade(),
+                    spotify_authorized.upgrade(),
+                    should_detect_now_playing.upgrade(),
+                ) {
+                    Self::update_and_handle_current_playing(
+                        spotify_client,
+                        currently_playing,
+                        debug_mode,
+                        ctx,
+                        spotify_authorized,
+                        should_detect_now_playing,
+                    )
+                    .await;
+                }
+            });
+        }
+    }
+
+    async fn update_and_handle_current_playing(
+        spotify_client: Arc<Mutex<Option<AuthCodeSpotify>>>,
+        currently_playing: Arc<Mutex<Option<CurrentlyPlaying>>>,
+        debug_mode: bool,
+        ctx: egui::Context,
+        spotify_authorized: Arc<AtomicBool>,
         should_detect_now_playing: Arc<AtomicBool>,
     ) {
         match update_currently_playing_wrapper(spotify_client, currently_playing, debug_mode).await
@@ -750,10 +1805,54 @@ impl SearchApp {
         }
 
         if !status_updates.is_empty() {
+            // 下載結束後重新檢查直接下載的譜面與已下載的譜面集
+            self.direct_download_downloaded = None;
+            self.downloaded_ids.invalidate();
+            self.track_download_batch(&status_updates);
             self.ctx.request_repaint();
         }
     }
 
+    // 下載隊列清空時，將這一批下載的結果送到 webhook
+    fn track_download_batch(&mut self, status_updates: &[(i32, DownloadStatus)]) {
+        for &(beatmapset_id, status) in status_updates {
+            self.download_batch.insert(beatmapset_id, status);
+        }
+        let queue_active = self
+            .beatmapset_download_statuses
+            .lock()
+            .unwrap()
+            .values()
+            .any(|status| {
+                matches!(
+                    status,
+                    DownloadStatus::Waiting | DownloadStatus::Downloading
+                )
+            });
+        if queue_active || self.download_batch.is_empty() {
+            return;
+        }
+
+        let batch = std::mem::take(&mut self.download_batch);
+        let succeeded = batch
+            .values()
+            .filter(|status| **status == DownloadStatus::Completed)
+            .count();
+        let mut failures: Vec<String> = batch
+            .iter()
+            .filter(|(_, status)| **status == DownloadStatus::NotStarted)
+            .map(|(beatmapset_id, _)| format!("譜面 #{}", beatmapset_id))
+            .collect();
+        failures.sort();
+        info!("本批下載結束：成功 {}，失敗 {}", succeeded, failures.len());
+        tokio::spawn(notify_batch_completed(BatchReport {
+            title: "譜面下載完成".to_string(),
+            succeeded,
+            failures,
+            fields: Vec::new(),
+        }));
+    }
+
     fn collect_status_updates(&mut self) -> Vec<(i32, DownloadStatus)> {
         let mut status_updates = Vec::new();
         while let Ok(update) = self.status_receiver.try_recv() {
@@ -800,7 +1899,7 @@ impl SearchApp {
     fn start_waiting_download(&mut self, waiting_index: usize, waiting_beatmapset: i32) {
         self.osu_download_statuses
             .insert(waiting_index, DownloadStatus::Downloading);
-        if let Err(e) = self.download_queue_sender.try_send(waiting_beatmapset) {
+        if let Err(e) = self.download_queue_sender.send(waiting_beatmapset) {
             error!("無法將等待中的圖譜加入下載隊列: {:?}", e);
             self.osu_download_statuses
                 .insert(waiting_index, DownloadStatus::Waiting);
@@ -822,6 +1921,9 @@ impl SearchApp {
             let mut textures = futures::executor::block_on(self.cover_textures.write());
             textures.clear();
         });
+        self.texture_budget.lock().unwrap().clear();
+        self.cover_load_errors.lock().unwrap().clear();
+        self.texture_load_queue.lock().unwrap().clear();
     }
 }
 
@@ -867,15 +1969,19 @@ impl SearchApp {
     ) -> Result<Self, AppError> {
         let texture_cache: Arc<RwLock<HashMap<String, Arc<TextureHandle>>>> =
             Arc::new(RwLock::new(HashMap::new()));
-        let texture_load_queue: Arc<Mutex<BinaryHeap<Reverse<(usize, String)>>>> =
-            Arc::new(Mutex::new(BinaryHeap::new()));
+        let texture_load_queue = Arc::new(Mutex::new(TextureLoadQueue::new()));
+        let texture_budget = Arc::new(Mutex::new(TextureBudget::new()));
+
+        let cover_load_errors: Arc<Mutex<HashMap<CoverKey, String>>> =
+            Arc::new(Mutex::new(HashMap::new()));
 
         let texture_cache_clone = Arc::clone(&texture_cache);
         let texture_load_queue_clone = Arc::clone(&texture_load_queue);
+        let cover_load_errors_clone = Arc::clone(&cover_load_errors);
+        let texture_budget_clone = Arc::clone(&texture_budget);
         let need_repaint_clone = Arc::clone(&need_repaint);
         let ctx_clone = ctx.clone();
 
-        let spotify_icon = load_spotify_icon(&ctx);
         let config = read_config(debug_mode)?;
 
         let (update_check_sender, update_check_receiver) = tokio::sync::mpsc::channel(100); // 設置適當的緩衝區大小
@@ -901,8 +2007,24 @@ impl SearchApp {
 
         let download_directory = load_download_directory().unwrap_or_else(|| PathBuf::from("."));
 
+        // 載入上次的工作階段狀態，並在崩潰時寫回磁碟
+        let session_state = load_session_state().unwrap_or_default();
+        let session_state_for_hook = Arc::new(Mutex::new(session_state.clone()));
+        let session_state_clone = session_state_for_hook.clone();
+        crash::register_flush_hook("session_state", move || {
+            let state = match session_state_clone.try_lock() {
+                Ok(state) => state.clone(),
+                Err(std::sync::TryLockError::Poisoned(poisoned)) => poisoned.into_inner().clone(),
+                Err(std::sync::TryLockError::WouldBlock) => return,
+            };
+            if let Err(e) = save_session_state(&state) {
+                eprintln!("保存工作階段狀態失敗: {:?}", e);
+            }
+        });
+
         let (status_sender, status_receiver) = tokio::sync::mpsc::channel(100);
-        let (download_queue_sender, download_queue_receiver) = mpsc::channel(100);
+        // 批次下載一次可能送入上百個譜面，使用不限容量的隊列，同時下載數由 download_semaphore 控制
+        let (download_queue_sender, download_queue_receiver) = mpsc::unbounded_channel();
 
         let audio_output = OutputStream::try_default().ok();
 
@@ -992,60 +2114,60 @@ impl SearchApp {
 
         ctx.set_fonts(fonts);
 
-        let mut preloaded_icons = HashMap::new();
-        let icon_paths = vec![
-            "spotify_icon_black.png",
-            "osu!logo.png",
-            "Spotify_Full_Logo_RGB_White.png",
-            "Spotify_Full_Logo_RGB_Black.png",
-            "osu!logo@2x.png",
-            "search.png",
-            "like.png",
-            "liked.png",
-            "expand_on.png",
-            "expand_off.png",
-            "play.png",
-            "pause.png",
-            "download.png",
-            "delete.png",
-            "downloading.png",
-            "background1.jpg",
-            "background_light2.jpg",
-        ];
-        for path in icon_paths {
-            if let Some(texture) = Self::load_icon(&ctx, path) {
-                preloaded_icons.insert(path.to_string(), texture);
-            }
-        }
+        // 圖示在背景解碼，載入完成前顯示啟動畫面
+        let icon_loader = IconLoader::start(&ctx);
 
-        // 啟動異步加載任務
+        // 啟動異步加載任務，同時最多 COVER_FETCH_CONCURRENCY 個封面在載入中
+        // 有空位時才從佇列取出，讓之後登記的可見封面仍能優先
+        let notify = texture_load_queue.lock().unwrap().notifier();
+        let slots = Arc::new(Semaphore::new(COVER_FETCH_CONCURRENCY));
         tokio::spawn(async move {
             loop {
-                let item = {
-                    let mut queue = texture_load_queue_clone.lock().unwrap();
-                    queue.pop()
+                let slot = match slots.clone().acquire_owned().await {
+                    Ok(slot) => slot,
+                    Err(_) => break,
+                };
+                let item = texture_load_queue_clone.lock().unwrap().pop();
+                let url = match item {
+                    Some(url) => url,
+                    None => {
+                        drop(slot);
+                        notify.notified().await;
+                        continue;
+                    }
                 };
 
-                if let Some(Reverse((_, url))) = item {
-                    if !texture_cache_clone.read().await.contains_key(&url) {
-                        match Self::load_texture_async(&ctx_clone, &url, Duration::from_secs(30))
-                            .await
-                        {
+                let texture_cache = texture_cache_clone.clone();
+                let texture_load_queue = texture_load_queue_clone.clone();
+                let cover_load_errors = cover_load_errors_clone.clone();
+                let texture_budget = texture_budget_clone.clone();
+                let need_repaint = need_repaint_clone.clone();
+                let ctx = ctx_clone.clone();
+                tokio::spawn(async move {
+                    let key = CoverKey::Spotify(url.clone());
+                    let already_failed = cover_load_errors.lock().unwrap().contains_key(&key);
+                    if !already_failed && !texture_cache.read().await.contains_key(&url) {
+                        match Self::load_texture_with_retry(&ctx, &url).await {
                             Ok(texture) => {
-                                texture_cache_clone
+                                texture_budget
+                                    .lock()
+                                    .unwrap()
+                                    .insert(key, texture_bytes(&texture));
+                                texture_cache
                                     .write()
                                     .await
                                     .insert(url.clone(), Arc::new(texture));
-                                need_repaint_clone.store(true, Ordering::SeqCst);
                             }
                             Err(e) => {
                                 error!("載入紋理失敗: {:?}", e);
+                                cover_load_errors.lock().unwrap().insert(key, e.to_string());
                             }
                         }
+                        need_repaint.store(true, Ordering::SeqCst);
                     }
-                }
-
-                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                    texture_load_queue.lock().unwrap().finish(&url);
+                    drop(slot);
+                });
             }
         });
 
@@ -1053,6 +2175,7 @@ impl SearchApp {
             // 自定義背景
             custom_background_path: None,
             custom_background: None,
+            background_load_handle: None,
             // 認證相關
             access_token: Arc::new(tokio::sync::Mutex::new(String::new())),
             auth_in_progress: Arc::new(AtomicBool::new(false)),
@@ -1067,15 +2190,48 @@ impl SearchApp {
             spotify_user_name,
 
             // 搜索相關
-            search_query: String::new(),
+            search_query: session_state.search_query.clone(),
+            search_suggestions: SearchSuggestions::new(),
+            search_mode: SearchMode::Track,
+            resolved_short_link: Arc::new(Mutex::new(None)),
+            album_search_results: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            expanded_album_id: None,
+            album_osu_matches: Arc::new(Mutex::new(HashMap::new())),
+            track_osu_matches: Arc::new(Mutex::new(HashMap::new())),
             is_searching: Arc::new(AtomicBool::new(false)),
             search_results: Arc::new(tokio::sync::Mutex::new(Vec::new())),
             osu_search_results: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            osu_search_query: Arc::new(Mutex::new(String::new())),
+            osu_search_cursor: Arc::new(Mutex::new(None)),
+            osu_search_source: Arc::new(Mutex::new(BeatmapSourceKind::default())),
+            beatmap_source: BeatmapSourceKind::load(),
+            osu_total_results: Arc::new(Mutex::new(None)),
+            is_loading_more_osu: Arc::new(AtomicBool::new(false)),
+            spotify_search_paging: Arc::new(Mutex::new(None)),
+            is_loading_more_spotify: Arc::new(AtomicBool::new(false)),
             displayed_spotify_results: 10,
             displayed_osu_results: 10,
             downloaded_maps_search: String::new(),
+            osu_results_filter: String::new(),
+            spotify_results_filter: String::new(),
+            osu_result_sort: OsuResultSort::Relevance,
+            osu_key_filter: None,
+            loading_beatmap_pack: None,
+            fetched_beatmap_pack: Arc::new(Mutex::new(None)),
+            scanning_downloads: false,
+            download_scan_result: Arc::new(Mutex::new(None)),
+            migrate_by_copy: false,
+            migration_progress: Arc::new(MigrationProgress::default()),
+            migration_result: Arc::new(Mutex::new(None)),
+            sorted_covers_loaded_for: None,
             playlist_search_query: String::new(),
+            new_folder_name: None,
+            renaming_folder: None,
             tracks_search_query: String::new(),
+            jump_to_row: None,
+            only_undownloaded_tracks: false,
+            new_mapper_name: String::new(),
+            bundle_include_credentials: false,
             // 播放列表和曲目
             spotify_user_playlists: Arc::new(Mutex::new(Vec::new())),
             spotify_playlist_tracks: Arc::new(Mutex::new(Vec::new())),
@@ -1094,14 +2250,37 @@ impl SearchApp {
             osu_scroll_to_top: false,
             global_font_size: 16.0,
             search_bar_expanded: false,
-            global_volume: 0.3,
+            global_volume: session_state.global_volume,
             expanded_track_index: None,
             expanded_beatmapset_index: None,
+            focused_result: None,
+            show_shortcut_help: false,
             is_beatmap_playing: false,
+            preview_speed: 1.0,
+            preview_eq: EqPreset::default(),
+            preview_compare: None,
+            media_keys: if media_key_options().enabled {
+                MediaKeyListener::start(&ctx)
+            } else {
+                None
+            },
+            api_server: None,
+            match_memory_editor: MatchMemoryEditor::new(),
+            downloaded_detail: DownloadedMapDetail::new(),
+            link_selection: LinkSelection::new(),
+            diagnostics_window: DiagnosticsWindow::new(),
             scale_factor,
             is_first_update: true,
             show_downloaded_maps: false,
+            show_download_manager: false,
+            show_review_queue: false,
+            show_recently_viewed: false,
             expanded_map_indices: HashSet::new(),
+            selected_downloaded_maps: HashSet::new(),
+            cleanup_days: 30,
+            confirm_dialog: ConfirmDialog::new(),
+            session_manager: SessionManager::new(),
+            undo_toast: None,
             show_osu_search_bar: false,
             show_playlist_search_bar: false,
             show_tracks_search_bar: false,
@@ -1110,10 +2289,14 @@ impl SearchApp {
             avatar_load_handle: None,
             cover_textures,
             playlist_cover_textures: Arc::new(Mutex::new(HashMap::new())),
+            playlist_summaries: Arc::new(Mutex::new(HashMap::new())),
             default_avatar_texture: None,
-            spotify_icon,
             texture_cache,
-            preloaded_icons,
+            cover_load_errors,
+            texture_budget,
+            evicted_osu_covers: HashSet::new(),
+            preloaded_icons: HashMap::new(),
+            icon_loader,
 
             // 網絡和客戶端
             client,
@@ -1121,6 +2304,7 @@ impl SearchApp {
 
             // 錯誤處理
             err_msg: Arc::new(tokio::sync::Mutex::new(String::new())),
+            error_banner: ErrorBanner::new(),
             error_message: Arc::new(tokio::sync::Mutex::new(String::new())),
             config_errors,
 
@@ -1143,20 +2327,36 @@ impl SearchApp {
             debug_mode,
             ctx,
             selected_beatmapset: None,
+            detail_cover: Arc::new(Mutex::new(None)),
+            detail_description: Arc::new(Mutex::new(None)),
             should_detect_now_playing: Arc::new(AtomicBool::new(false)),
             spotify_track_liked_status: Arc::new(Mutex::new(HashMap::new())),
             osu_download_statuses: HashMap::new(),
             osu_helper: OsuHelper::new(),
+            batch_import: BatchImport::new(),
+            batch_like: BatchLike::new(),
+            lastfm: LastFmPanel::new(),
+            release_radar: ReleaseRadar::new(),
+            followed_artists: FollowedArtists::new(),
+            scripts: ScriptsPage::new(),
+            osu_favourites: OsuFavourites::new(),
+            playlist_builder: PlaylistBuilder::new(),
 
             // 快取
             liked_songs_cache: Arc::new(Mutex::new(None)),
-            cache_ttl: Duration::from_secs(300), // 5 分鐘的緩存有效期
+            cache_manager: CacheManager::new(),
+            cache_progress: Arc::new(CacheProgress::default()),
+            cache_sizes: None,
             texture_load_queue,
 
             // 更新檢查
             update_check_result: Arc::new(Mutex::new(None)),
             update_check_sender,
             update_check_receiver,
+            app_update_release: Arc::new(Mutex::new(None)),
+            app_update_download_status: Arc::new(Mutex::new(UpdateDownloadStatus::Idle)),
+            app_update_message: Arc::new(Mutex::new(None)),
+            show_app_update_dialog: Arc::new(AtomicBool::new(false)),
             last_background_key: String::new(),
 
             // 下載相關
@@ -1167,14 +2367,31 @@ impl SearchApp {
             download_queue_receiver: Arc::new(Mutex::new(Some(download_queue_receiver))),
             download_semaphore: Arc::new(Semaphore::new(3)), // 允許3個同時下載
             current_downloads: Arc::new(AtomicUsize::new(0)),
+            download_scheduler: DownloadScheduler::new(),
+            schedule_time_inputs: HashMap::new(),
+            filename_template_input: download_options().filename_template,
+            blocked_downloads: Vec::new(),
+            direct_download_downloaded: None,
+            downloaded_ids: DownloadedIds::new(),
+            download_batch: HashMap::new(),
+            webhook_url_input: notify_options().webhook_url,
+            plugin_editor: PluginActionEditor::default(),
+            watch_folder: WatchFolder::new(),
 
             // 音頻播放
             audio_output,
             current_previews: Arc::new(TokioMutex::new(HashMap::new())),
+            preview_buffers: Arc::new(TokioMutex::new(HashMap::new())),
+            preview_seek_drag: None,
             need_load_background: true,
+
+            // 崩潰復原
+            session_state: session_state_for_hook,
+            pending_crash_report: crash::take_pending_crash_report(),
         };
         // 檢查並加載本地頭像
         if let Some(user_name) = app.spotify_user_name.lock().unwrap().clone() {
+            migrate_legacy_avatar(&user_name);
             let avatar_path = Self::get_avatar_path(&user_name);
             if let Ok(Some(texture)) = Self::load_local_avatar(&app.ctx, &avatar_path) {
                 *app.spotify_user_avatar.lock().unwrap() = Some(texture);
@@ -1184,10 +2401,129 @@ impl SearchApp {
 
         app.load_default_avatar();
         app.start_download_processor();
+        app.download_scheduler.start(
+            app.download_directory.clone(),
+            app.download_queue_sender.clone(),
+            app.beatmapset_download_statuses.clone(),
+            app.ctx.clone(),
+        );
+        app.restart_watch_folder();
+        app.restart_api_server();
+        purge_expired_trash();
 
         Ok(app)
     }
 
+    // 舊目錄中還有圖譜時詢問是否一併帶到新目錄，避免看起來像是下載紀錄消失
+    fn confirm_migrate_downloads(&mut self, from: PathBuf) {
+        if from == self.download_directory {
+            return;
+        }
+        let file_names = migration_candidates(&from);
+        if file_names.is_empty() {
+            return;
+        }
+        let mode = if self.migrate_by_copy {
+            MigrationMode::Copy
+        } else {
+            MigrationMode::Move
+        };
+        self.request_confirmation(
+            ConfirmRequest::new(
+                "migrate_downloads",
+                "轉移已下載的圖譜",
+                format!(
+                    "舊的下載目錄 {} 中有 {} 個圖譜，要{}到新的下載目錄嗎？",
+                    from.display(),
+                    file_names.len(),
+                    mode.label()
+                ),
+                ConfirmAction::MigrateDownloads {
+                    from,
+                    file_names: file_names.clone(),
+                    mode,
+                },
+            )
+            .details(file_names)
+            .confirm_label(mode.label()),
+        );
+    }
+
+    fn start_download_migration(
+        &mut self,
+        from: PathBuf,
+        file_names: Vec<String>,
+        mode: MigrationMode,
+    ) {
+        let to = self.download_directory.clone();
+        let progress = self.migration_progress.clone();
+        let migration_result = self.migration_result.clone();
+        let ctx = self.ctx.clone();
+        tokio::task::spawn_blocking(move || {
+            let report = migrate_downloads(&from, &to, &file_names, mode, &progress);
+            *migration_result.lock().unwrap() = Some(report);
+            ctx.request_repaint();
+        });
+    }
+
+    // 排程到期時磁碟空間不足的譜面，重新檢查後加入隊列或在下載管理中暫停
+    fn poll_blocked_schedules(&mut self) {
+        for beatmapset_id in self.download_scheduler.take_blocked() {
+            self.enqueue_beatmap_download(beatmapset_id);
+        }
+    }
+
+    fn poll_download_migration(&mut self) {
+        let report = match self.migration_result.lock().unwrap().take() {
+            Some(report) => report,
+            None => return,
+        };
+        let mut details: Vec<String> = report
+            .skipped
+            .iter()
+            .map(|file_name| format!("略過（新目錄已有同名檔案）: {}", file_name))
+            .collect();
+        details.extend(
+            report
+                .failed
+                .iter()
+                .map(|file_name| format!("失敗: {}", file_name)),
+        );
+        self.request_confirmation(
+            ConfirmRequest::notice(
+                "migrate_downloads",
+                "轉移已下載的圖譜",
+                format!(
+                    "已{} {} 個圖譜到新的下載目錄",
+                    report.mode.label(),
+                    report.migrated
+                ),
+            )
+            .details(details),
+        );
+    }
+
+    fn restart_watch_folder(&mut self) {
+        if let Err(e) = self
+            .watch_folder
+            .restart(&self.ctx, &self.download_directory)
+        {
+            error!("無法監看資料夾: {:?}", e);
+        }
+    }
+
+    // 先停止舊的伺服器釋放連接埠，再依設定重新啟動
+    fn restart_api_server(&mut self) {
+        self.api_server = None;
+        if api_server_options().enabled {
+            self.api_server = Some(ApiServer::start(
+                &self.ctx,
+                self.beatmapset_download_statuses.clone(),
+                self.currently_playing.clone(),
+            ));
+        }
+    }
+
     fn cancel_authorization(&mut self) {
         self.auth_manager.reset(&AuthPlatform::Spotify);
         self.auth_start_time = None;
@@ -1352,18 +2688,45 @@ impl SearchApp {
         });
     }
 
+    // 載入失敗時依 COVER_MAX_RETRIES 自動重試，每次等待時間加倍
+    async fn load_texture_with_retry(
+        ctx: &egui::Context,
+        url: &str,
+    ) -> Result<TextureHandle, anyhow::Error> {
+        let mut attempt = 0;
+        loop {
+            match Self::load_texture_async(ctx, url, Duration::from_secs(30), Some(THUMBNAIL_SIZE))
+                .await
+            {
+                Ok(texture) => return Ok(texture),
+                Err(e) if attempt < COVER_MAX_RETRIES => {
+                    let delay = COVER_RETRY_BASE_DELAY_MS * 2u64.pow(attempt);
+                    attempt += 1;
+                    debug!(
+                        "載入紋理失敗，{} ms 後第 {} 次重試: {} ({:?})",
+                        delay, attempt, url, e
+                    );
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // max_height 為 None 時保留原始大小，只用於詳細資訊頁的大圖
     async fn load_texture_async(
         ctx: &egui::Context,
         url: &str,
         timeout: Duration,
+        max_height: Option<u32>,
     ) -> Result<TextureHandle, anyhow::Error> {
         let client = reqwest::Client::new();
-        let bytes = tokio::time::timeout(timeout, client.get(url).send())
-            .await??
-            .bytes()
-            .await?;
-
-        let image = image::load_from_memory(&bytes)?;
+        let image = fetch_cover_image(&client, url, timeout).await?;
+        let image = match max_height {
+            Some(max_height) => downscale_image(image, max_height),
+            None => image,
+        };
+        record_accent(url, &image);
         let size = [image.width() as _, image.height() as _];
         let image_buffer = image.to_rgba8();
         let pixels = image_buffer.as_flat_samples();
@@ -1381,6 +2744,41 @@ impl SearchApp {
     //處理搜尋
     fn perform_search(&mut self, ctx: egui::Context) -> JoinHandle<Result<()>> {
         set_log_level(self.debug_mode); // 設置日誌級別
+        self.download_scheduler.mark_activity();
+        self.osu_results_filter.clear();
+        self.spotify_results_filter.clear();
+        self.sorted_covers_loaded_for = None;
+
+        if is_spotify_short_link(&self.search_query) {
+            return self.resolve_short_link(ctx);
+        }
+        self.search_query = normalize_spotify_url(&self.search_query);
+
+        // 專輯網址開啟專輯詳細頁，播放清單網址交給歌單匯入逐首匹配
+        match parse_spotify_url(&self.search_query) {
+            Some(SpotifyUrlKind::Album(album_id)) => return self.open_spotify_album(ctx, album_id),
+            Some(SpotifyUrlKind::Playlist(playlist_id)) => {
+                info!("使用者匯入 Spotify 播放清單: {}", playlist_id);
+                self.batch_import
+                    .start_spotify_playlist(&playlist_id, ctx, self.debug_mode);
+                self.batch_import.show = true;
+                self.osu_helper.show = false;
+                return tokio::spawn(async { Ok(()) });
+            }
+            _ => {}
+        }
+
+        // 專輯模式下，網址仍然使用原本的搜尋流程
+        if self.search_mode == SearchMode::Album
+            && parse_osu_url(&self.search_query).is_none()
+            && parse_music_link(&self.search_query).is_none()
+            && matches!(
+                is_valid_spotify_url(&self.search_query),
+                Ok(SpotifyUrlStatus::NotSpotify)
+            )
+        {
+            return self.perform_album_search(ctx);
+        }
 
         let client = self.client.clone();
         let debug_mode = self.debug_mode;
@@ -1391,9 +2789,22 @@ impl SearchApp {
         let need_repaint = self.need_repaint.clone();
         let err_msg = self.err_msg.clone();
         let sender = self.sender.clone();
+        let cover_load_errors = self.cover_load_errors.clone();
         let spotify_client = self.spotify_client.clone(); // 添加這行
+        let osu_search_query = self.osu_search_query.clone();
+        let osu_search_cursor = self.osu_search_cursor.clone();
+        let osu_search_source = self.osu_search_source.clone();
+        let osu_total_results = self.osu_total_results.clone();
+        let spotify_search_paging = self.spotify_search_paging.clone();
+        let beatmap_source = self.beatmap_source;
+        let search_mode = self.search_mode;
+        let search_markets = query_options().markets();
         let ctx_clone = ctx.clone(); // 在這裡克隆 ctx
         self.displayed_osu_results = 10;
+        self.track_osu_matches.lock().unwrap().clear();
+        *self.spotify_search_paging.lock().unwrap() = None;
+        *self.osu_search_cursor.lock().unwrap() = None;
+        *self.osu_total_results.lock().unwrap() = None;
         self.clear_cover_textures();
         self.expanded_beatmapset_index = None;
 
@@ -1426,6 +2837,23 @@ impl SearchApp {
                         anyhow!("Osu 錯誤：無法獲取 token")
                     })?;
 
+                // YouTube Music / Apple Music 連結先解析出歌手與歌名，再以關鍵字搜尋
+                let resolved_link = match parse_music_link(&query) {
+                    Some(link) => Some(
+                        resolve_music_link(&*client.lock().await, &link, debug_mode)
+                            .await
+                            .map_err(|e| {
+                                error!("解析音樂連結錯誤: {:?}", e);
+                                anyhow!("連結解析錯誤：{}", e)
+                            })?,
+                    ),
+                    None => None,
+                };
+                let query = match &resolved_link {
+                    Some(resolved) => resolved.query(),
+                    None => query,
+                };
+
                 if let Some((beatmapset_id, _)) = parse_osu_url(&query) {
                     info!("Osu 搜尋: {}", query);
 
@@ -1465,33 +2893,7 @@ impl SearchApp {
                     let mut search_results = search_results.lock().await;
                     *search_results = tracks_with_cover
                         .iter()
-                        .map(|twc| Track {
-                            name: twc.name.clone(),
-                            artists: twc.artists.clone(),
-                            album: Album {
-                                name: twc.album_name.clone(),
-                                album_type: String::new(),
-                                artists: Vec::new(),
-                                external_urls: HashMap::new(),
-                                images: twc
-                                    .cover_url
-                                    .as_ref()
-                                    .map(|url| {
-                                        vec![Image {
-                                            url: url.clone(),
-                                            width: 0,
-                                            height: 0,
-                                        }]
-                                    })
-                                    .unwrap_or_default(),
-                                id: String::new(),
-                                release_date: String::new(),
-                                total_tracks: 0,
-                            },
-                            external_urls: twc.external_urls.clone(),
-                            index: twc.index,
-                            is_liked: None, // 添加缺失的 is_liked 字段
-                        })
+                        .map(TrackWithCover::to_track)
                         .collect();
 
                     // 獲取 osu! beatmapset
@@ -1520,6 +2922,7 @@ impl SearchApp {
                         load_osu_covers(osu_covers, ctx_clone.clone(), sender.clone()).await
                     {
                         error!("載入 osu 封面時發生錯誤: {:?}", e);
+                        Self::record_osu_cover_errors(&cover_load_errors, &e);
                         if debug_mode {
                             ctx_clone.request_repaint();
                             egui::Window::new("Error").show(&ctx_clone, |ui| {
@@ -1530,38 +2933,81 @@ impl SearchApp {
                     }
                 } else {
                     // 如果不是 osu! URL，執行原有的搜索邏輯
+                    let url_kind = parse_spotify_url(&query);
+                    let mut artist_name = None;
                     let spotify_result: Result<Vec<TrackWithCover>> =
                         match is_valid_spotify_url(&query) {
                             Ok(status) => match status {
                                 SpotifyUrlStatus::Valid => {
                                     info!("Spotify 查詢 (URL): {}", query);
-                                    let track_id = query
-                                        .split('/')
-                                        .last()
-                                        .unwrap_or("")
-                                        .split('?')
-                                        .next()
-                                        .unwrap_or("");
-                                    let track = get_track_info(
-                                        &*client.lock().await,
-                                        track_id,
-                                        &spotify_token,
-                                    )
-                                    .await
-                                    .map_err(|e| anyhow!("獲取曲目資訊錯誤: {:?}", e))?;
-
-                                    Ok(vec![TrackWithCover {
-                                        name: track.name.clone(),
-                                        artists: track.artists.clone(),
-                                        external_urls: track.external_urls.clone(),
-                                        album_name: track.album.name.clone(),
-                                        cover_url: track
-                                            .album
-                                            .images
-                                            .first()
-                                            .map(|img| img.url.clone()),
-                                        index: 0, // 添加這行，給予一個固定的索引
-                                    }])
+                                    match &url_kind {
+                                        Some(SpotifyUrlKind::Track(track_id)) => {
+                                            let track = get_track_info(
+                                                &*client.lock().await,
+                                                track_id,
+                                                &spotify_token,
+                                            )
+                                            .await
+                                            .map_err(|e| anyhow!("獲取曲目資訊錯誤: {:?}", e))?;
+
+                                            Ok(vec![TrackWithCover::from_track(&track, 0)])
+                                        }
+                                        Some(SpotifyUrlKind::Artist(artist_id)) => {
+                                            let client = client.lock().await.clone();
+                                            let artist =
+                                                get_artist(&client, artist_id, &spotify_token)
+                                                    .await
+                                                    .map_err(|e| {
+                                                        anyhow!("獲取歌手資訊錯誤: {}", e)
+                                                    })?;
+                                            let tracks = get_artist_top_tracks(
+                                                &client,
+                                                artist_id,
+                                                &spotify_token,
+                                                debug_mode,
+                                            )
+                                            .await
+                                            .map_err(|e| anyhow!("獲取歌手熱門曲目錯誤: {}", e))?;
+                                            info!("Spotify 歌手頁面: {}", artist.name);
+                                            artist_name = Some(artist.name);
+                                            Ok(tracks
+                                                .iter()
+                                                .enumerate()
+                                                .map(|(index, track)| {
+                                                    TrackWithCover::from_track(track, index)
+                                                })
+                                                .collect())
+                                        }
+                                        Some(SpotifyUrlKind::Episode(episode_id)) => {
+                                            let episode = get_episode(
+                                                &*client.lock().await,
+                                                episode_id,
+                                                &spotify_token,
+                                            )
+                                            .await
+                                            .map_err(|e| anyhow!("獲取 Podcast 單集錯誤: {}", e))?;
+                                            Ok(vec![TrackWithCover::from_episode(episode, 0)])
+                                        }
+                                        Some(SpotifyUrlKind::Show(show_id)) => {
+                                            let (show, episodes) = get_show_episodes(
+                                                &*client.lock().await,
+                                                show_id,
+                                                &spotify_token,
+                                            )
+                                            .await
+                                            .map_err(|e| anyhow!("獲取 Podcast 節目錯誤: {}", e))?;
+                                            info!("Spotify 節目頁面: {}", show.name);
+                                            Ok(episodes
+                                                .into_iter()
+                                                .enumerate()
+                                                .map(|(index, episode)| {
+                                                    TrackWithCover::from_episode(episode, index)
+                                                })
+                                                .collect())
+                                        }
+                                        // 專輯與播放清單網址在 perform_search 開頭就已分派
+                                        _ => Err(anyhow!("無法解析 Spotify URL")),
+                                    }
                                 }
                                 SpotifyUrlStatus::Incomplete => {
                                     *error = "Spotify URL 不完整，請輸入完整的 URL".to_string();
@@ -1573,20 +3019,48 @@ impl SearchApp {
                                 }
                                 SpotifyUrlStatus::NotSpotify => {
                                     // 執行普通搜索
-                                    if !query.is_empty() {
+                                    if !query.is_empty() && search_mode == SearchMode::Episode {
+                                        info!("Spotify 查詢 (節目): {}", query);
+                                        search_episodes(
+                                            &*client.lock().await,
+                                            &query,
+                                            &spotify_token,
+                                            50,
+                                            debug_mode,
+                                        )
+                                        .await
+                                        .map(|episodes| {
+                                            episodes
+                                                .into_iter()
+                                                .enumerate()
+                                                .map(|(index, episode)| {
+                                                    TrackWithCover::from_episode(episode, index)
+                                                })
+                                                .collect()
+                                        })
+                                        .map_err(|e| anyhow!("Spotify 節目搜索錯誤: {}", e))
+                                    } else if !query.is_empty() {
                                         info!("Spotify 查詢 (關鍵字): {}", query);
-                                        let limit = 50;
-                                        let offset = 0;
-                                        search_track(
+                                        search_track_in_markets(
                                             &*client.lock().await,
                                             &query,
                                             &spotify_token,
-                                            limit,
-                                            offset,
+                                            SPOTIFY_SEARCH_PAGE_SIZE,
+                                            0,
+                                            &search_markets,
                                             debug_mode,
                                         )
                                         .await
-                                        .map(|(tracks_with_cover, _)| tracks_with_cover)
+                                        .map(|(tracks_with_cover, total)| {
+                                            *spotify_search_paging.lock().unwrap() =
+                                                Some(SpotifySearchPaging {
+                                                    query: query.clone(),
+                                                    markets: search_markets.clone(),
+                                                    next_offset: SPOTIFY_SEARCH_PAGE_SIZE,
+                                                    total,
+                                                });
+                                            tracks_with_cover
+                                        })
                                         .map_err(|e| anyhow!("Spotify 搜索錯誤: {}", e))
                                     } else {
                                         Ok(Vec::new())
@@ -1599,97 +3073,62 @@ impl SearchApp {
                             }
                         };
 
-                    let osu_query = match spotify_result {
+                    let (osu_query, fallback_query) = match spotify_result {
                         Ok(ref tracks_with_cover) => {
                             info!("Spotify 搜索結果: {} 首曲目", tracks_with_cover.len());
                             let mut search_results = search_results.lock().await;
                             *search_results = tracks_with_cover
                                 .iter()
-                                .map(|twc| Track {
-                                    name: twc.name.clone(),
-                                    artists: twc.artists.clone(),
-                                    album: Album {
-                                        name: twc.album_name.clone(),
-                                        album_type: String::new(),
-                                        artists: Vec::new(),
-                                        external_urls: HashMap::new(),
-                                        images: twc
-                                            .cover_url
-                                            .as_ref()
-                                            .map(|url| {
-                                                vec![Image {
-                                                    url: url.clone(),
-                                                    width: 0,
-                                                    height: 0,
-                                                }]
-                                            })
-                                            .unwrap_or_default(),
-                                        id: String::new(),
-                                        release_date: String::new(),
-                                        total_tracks: 0,
-                                    },
-                                    external_urls: twc.external_urls.clone(),
-                                    index: twc.index,
-                                    is_liked: None, // 初始化為 None
-                                })
+                                .map(TrackWithCover::to_track)
                                 .collect();
 
+                            if tracks_with_cover.iter().any(|twc| twc.is_episode) {
+                                // Podcast 單集沒有對應的譜面也無法收藏，只顯示 Spotify 結果
+                                osu_search_results.lock().await.clear();
+                                return Ok(());
+                            }
+
                             // 檢查前十首歌曲的喜歡狀態
-                            if !search_results.is_empty() {
-                                let track_ids: Vec<TrackId> = search_results
-                                    .iter()
-                                    .take(10)
-                                    .filter_map(|track| {
-                                        track
-                                            .external_urls
-                                            .get("spotify")
-                                            .and_then(|url| url.split('/').last())
-                                            .and_then(|id| TrackId::from_id(id).ok())
-                                    })
-                                    .collect();
-
-                                let spotify_option = {
-                                    let spotify_guard = spotify_client.lock().unwrap();
-                                    spotify_guard.as_ref().cloned()
-                                };
+                            let checked = search_results.len().min(10);
+                            Self::check_liked_statuses(
+                                &spotify_client,
+                                &mut search_results[..checked],
+                            )
+                            .await;
 
-                                if let Some(spotify) = spotify_option {
-                                    match spotify
-                                        .current_user_saved_tracks_contains(track_ids)
-                                        .await
-                                    {
-                                        Ok(statuses) => {
-                                            for (track, &is_liked) in
-                                                search_results.iter_mut().zip(statuses.iter())
-                                            {
-                                                track.is_liked = Some(is_liked);
-                                            }
-                                        }
-                                        Err(e) => {
-                                            error!("無法檢查歌曲喜歡狀態: {:?}", e);
-                                        }
-                                    }
-                                }
-                            }
-
-                            if matches!(is_valid_spotify_url(&query), Ok(SpotifyUrlStatus::Valid))
+                            if let Some(artist_name) = artist_name {
+                                // 歌手頁面直接以歌手名稱搜尋 osu! 譜面
+                                info!("Osu 查詢 (歌手): {}", artist_name);
+                                (artist_name, None)
+                            } else if matches!(url_kind, Some(SpotifyUrlKind::Track(_)))
                                 && !tracks_with_cover.is_empty()
                             {
-                                let osu_query = format!(
-                                    "{} {}",
-                                    tracks_with_cover[0]
-                                        .artists
-                                        .iter()
-                                        .map(|a| a.name.clone())
-                                        .collect::<Vec<_>>()
-                                        .join(", "),
-                                    tracks_with_cover[0].name
-                                );
+                                let artists = tracks_with_cover[0]
+                                    .artists
+                                    .iter()
+                                    .map(|a| a.name.clone())
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                let osu_query =
+                                    format!("{} {}", artists, tracks_with_cover[0].name);
                                 info!("Osu 查詢 (從 Spotify): {}", osu_query);
-                                osu_query
+                                // 原始查詢找不到時改用去除 feat.、remaster 等後綴的查詢
+                                let fallback_query =
+                                    query_variants(&artists, &tracks_with_cover[0].name)
+                                        .into_iter()
+                                        .nth(1);
+                                (osu_query, fallback_query)
                             } else {
                                 info!("Osu 查詢 (關鍵字): {}", query);
-                                query.clone()
+                                let fallback_query = match &resolved_link {
+                                    Some(resolved) => {
+                                        query_variants(&resolved.artist, &resolved.title)
+                                    }
+                                    None => query_variants("", &query),
+                                }
+                                .into_iter()
+                                .nth(1);
+                                (query.clone(), fallback_query)
                             }
                         }
                         Err(e) => {
@@ -1697,13 +3136,51 @@ impl SearchApp {
                             return Err(anyhow!("Spotify 錯誤：搜索失敗"));
                         }
                     };
-                    let results =
-                        get_beatmapsets(&*client.lock().await, &osu_token, &osu_query, debug_mode)
-                            .await
-                            .map_err(|e| {
-                                error!("Osu 搜索錯誤: {:?}", e);
-                                anyhow!("Osu 錯誤：搜索失敗")
-                            })?;
+                    let mut osu_query = osu_query;
+                    let mut page = beatmap_source
+                        .source()
+                        .search_page(&*client.lock().await, &osu_query, None, debug_mode)
+                        .await
+                        .map_err(|e| {
+                            error!("Osu 搜索錯誤 ({}): {:?}", beatmap_source.label(), e);
+                            anyhow!("Osu 錯誤：搜索失敗")
+                        })?;
+                    if let Some(fallback_query) = fallback_query {
+                        if page.beatmapsets.is_empty() {
+                            info!("Osu 查詢無結果，改用清理後的查詢: {}", fallback_query);
+                            match beatmap_source
+                                .source()
+                                .search_page(
+                                    &*client.lock().await,
+                                    &fallback_query,
+                                    None,
+                                    debug_mode,
+                                )
+                                .await
+                            {
+                                Ok(fallback_page) => {
+                                    page = fallback_page;
+                                    osu_query = fallback_query;
+                                }
+                                Err(e) => {
+                                    error!("Osu 清理後查詢搜索錯誤: {:?}", e);
+                                }
+                            }
+                        }
+                    }
+                    let mut results = page.beatmapsets;
+                    // 由連結解析出的曲目，依匹配分數重新排序譜面
+                    if let Some(resolved) = &resolved_link {
+                        results =
+                            rank_beatmapsets(&resolved.artist, &resolved.title, None, results)
+                                .into_iter()
+                                .map(|scored| scored.beatmapset)
+                                .collect();
+                    }
+                    *osu_search_query.lock().unwrap() = osu_query.clone();
+                    *osu_search_source.lock().unwrap() = beatmap_source;
+                    *osu_search_cursor.lock().unwrap() = page.cursor_string;
+                    *osu_total_results.lock().unwrap() = page.total;
 
                     info!("Osu 搜索結果: {} 個 beatmapsets", results.len());
                     if debug_mode {
@@ -1723,6 +3200,7 @@ impl SearchApp {
                         load_osu_covers(osu_covers, ctx_clone.clone(), sender.clone()).await
                     {
                         error!("載入 osu 封面時發生錯誤: {:?}", e);
+                        Self::record_osu_cover_errors(&cover_load_errors, &e);
                         if debug_mode {
                             ctx_clone.request_repaint();
                             egui::Window::new("Error").show(&ctx_clone, |ui| {
@@ -1750,8 +3228,436 @@ impl SearchApp {
         })
     }
 
+    fn perform_album_search(&mut self, ctx: egui::Context) -> JoinHandle<Result<()>> {
+        let client = self.client.clone();
+        let debug_mode = self.debug_mode;
+        let query = self.search_query.clone();
+        let album_search_results = self.album_search_results.clone();
+        let is_searching = self.is_searching.clone();
+        let err_msg = self.err_msg.clone();
+        self.expanded_album_id = None;
+        self.album_osu_matches.lock().unwrap().clear();
+
+        info!("使用者搜尋專輯: {}", query);
+        is_searching.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            let result: Result<()> = async {
+                err_msg.lock().await.clear();
+                if query.trim().is_empty() {
+                    album_search_results.lock().await.clear();
+                    return Ok(());
+                }
+
+                let spotify_token = get_access_token(&*client.lock().await, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Spotify 錯誤：無法獲取 token: {}", e))?;
+
+                let (albums, _) = search_album_by_name(
+                    &*client.lock().await,
+                    &query,
+                    &spotify_token,
+                    50,
+                    0,
+                    debug_mode,
+                )
+                .await
+                .map_err(|e| {
+                    error!("Spotify 專輯搜索錯誤: {:?}", e);
+                    anyhow!("Spotify 錯誤：專輯搜索失敗")
+                })?;
+
+                info!("Spotify 專輯搜索結果: {} 張專輯", albums.len());
+                *album_search_results.lock().await = albums;
+                Ok(())
+            }
+            .await;
+
+            if let Err(e) = &result {
+                *err_msg.lock().await = e.to_string();
+            }
+            is_searching.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+            result
+        })
+    }
+
+    // 手機分享的 spotify.link 短網址需先轉址，解析完成後由 update 以實際網址重新搜尋
+    fn resolve_short_link(&mut self, ctx: egui::Context) -> JoinHandle<Result<()>> {
+        let client = self.client.clone();
+        let url = self.search_query.clone();
+        let resolved_short_link = self.resolved_short_link.clone();
+        let is_searching = self.is_searching.clone();
+        let err_msg = self.err_msg.clone();
+
+        info!("解析 Spotify 短網址: {}", url);
+        is_searching.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            let client = client.lock().await.clone();
+            let result = resolve_spotify_short_link(&client, &url)
+                .await
+                .map_err(|e| {
+                    error!("解析 Spotify 短網址失敗: {:?}", e);
+                    anyhow!("Spotify 短網址解析失敗：{}", e)
+                });
+
+            match &result {
+                Ok(resolved) => *resolved_short_link.lock().unwrap() = Some(resolved.clone()),
+                Err(e) => *err_msg.lock().await = e.to_string(),
+            }
+            is_searching.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+            result.map(|_| ())
+        })
+    }
+
+    fn handle_resolved_short_link(&mut self) {
+        let resolved = self.resolved_short_link.lock().unwrap().take();
+        if let Some(url) = resolved {
+            self.search_query = url;
+            self.perform_search(self.ctx.clone());
+        }
+    }
+
+    // 以專輯網址開啟：切換到專輯模式並直接展開該專輯
+    fn open_spotify_album(
+        &mut self,
+        ctx: egui::Context,
+        album_id: String,
+    ) -> JoinHandle<Result<()>> {
+        let client = self.client.clone();
+        let debug_mode = self.debug_mode;
+        let album_search_results = self.album_search_results.clone();
+        let is_searching = self.is_searching.clone();
+        let err_msg = self.err_msg.clone();
+        self.search_mode = SearchMode::Album;
+        self.expanded_album_id = Some(album_id.clone());
+
+        info!("使用者開啟 Spotify 專輯: {}", album_id);
+        is_searching.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            let result: Result<()> = async {
+                err_msg.lock().await.clear();
+                album_search_results.lock().await.clear();
+
+                let client = client.lock().await.clone();
+                let spotify_token = get_access_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Spotify 錯誤：無法獲取 token: {}", e))?;
+                let album = get_album(&client, &album_id, &spotify_token, debug_mode)
+                    .await
+                    .map_err(|e| {
+                        error!("獲取 Spotify 專輯錯誤: {:?}", e);
+                        anyhow!("Spotify 錯誤：獲取專輯失敗")
+                    })?;
+
+                *album_search_results.lock().await = vec![album];
+                Ok(())
+            }
+            .await;
+
+            if let Err(e) = &result {
+                *err_msg.lock().await = e.to_string();
+            }
+            is_searching.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+            result
+        })
+    }
+
+    // 展開專輯時，逐首搜尋 osu! 並彙整找到的譜面
+    fn load_album_osu_matches(&self, album: &Album) {
+        let client = self.client.clone();
+        let debug_mode = self.debug_mode;
+        let album_osu_matches = self.album_osu_matches.clone();
+        let album_id = album.id.clone();
+        let ctx = self.ctx.clone();
+
+        album_osu_matches
+            .lock()
+            .unwrap()
+            .insert(album_id.clone(), AlbumMatchState::Loading);
+
+        tokio::spawn(async move {
+            let result: Result<Vec<AlbumTrackMatches>> = async {
+                let client = client.lock().await.clone();
+                let spotify_token = get_access_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Spotify 錯誤：無法獲取 token: {}", e))?;
+                let osu_token = get_osu_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Osu 錯誤：無法獲取 token: {}", e))?;
+                let tracks = get_album_tracks(&client, &album_id, &spotify_token, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Spotify 錯誤：獲取專輯曲目失敗: {}", e))?;
+
+                let mut seen = HashSet::new();
+                let mut matches = Vec::new();
+                for track in tracks {
+                    let artist = track
+                        .artists
+                        .first()
+                        .map(|a| a.name.clone())
+                        .unwrap_or_default();
+                    let beatmapsets = match search_beatmapsets_normalized(
+                        &client,
+                        &osu_token,
+                        &artist,
+                        &track.name,
+                        debug_mode,
+                    )
+                    .await
+                    {
+                        Ok(results) => results
+                            .into_iter()
+                            .take(3)
+                            .filter(|b| seen.insert(b.id))
+                            .collect(),
+                        Err(e) => {
+                            error!("專輯曲目 {} 搜尋 osu 失敗: {:?}", track.name, e);
+                            Vec::new()
+                        }
+                    };
+                    matches.push(AlbumTrackMatches {
+                        track_name: track.name,
+                        beatmapsets,
+                    });
+                    ctx.request_repaint();
+                }
+                Ok(matches)
+            }
+            .await;
+
+            let state = match result {
+                Ok(matches) => {
+                    info!(
+                        "專輯 {} 共找到 {} 個譜面",
+                        album_id,
+                        matches.iter().map(|m| m.beatmapsets.len()).sum::<usize>()
+                    );
+                    AlbumMatchState::Loaded(matches)
+                }
+                Err(e) => {
+                    error!("載入專輯 {} 的 osu 譜面失敗: {:?}", album_id, e);
+                    AlbumMatchState::Failed(e.to_string())
+                }
+            };
+            album_osu_matches.lock().unwrap().insert(album_id, state);
+            ctx.request_repaint();
+        });
+    }
+
+    fn display_spotify_album_results(&mut self, ui: &mut egui::Ui) {
+        let albums = self
+            .album_search_results
+            .try_lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default();
+        let total_results = albums.len();
+        let displayed_results = self.displayed_spotify_results.min(total_results);
+
+        self.display_spotify_header(ui, total_results, displayed_results);
+        Self::display_results_filter(
+            ui,
+            &mut self.spotify_results_filter,
+            "依專輯名稱 / 歌手篩選已載入的專輯",
+        );
+        let filter = self.spotify_results_filter.trim().to_string();
+
+        if !filter.is_empty() {
+            let matched: Vec<(usize, &Album)> = albums
+                .iter()
+                .enumerate()
+                .filter(|(_, album)| {
+                    let artists = album
+                        .artists
+                        .iter()
+                        .map(|artist| artist.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    fuzzy_matches(&filter, &[album.name.as_str(), &artists])
+                })
+                .collect();
+            ui.label(format!(
+                "符合 {} / {} 張已載入的專輯",
+                matched.len(),
+                total_results
+            ));
+            for (index, album) in matched {
+                self.display_spotify_album(ui, album, index);
+            }
+            return;
+        }
+
+        for (index, album) in albums.iter().take(displayed_results).enumerate() {
+            self.display_spotify_album(ui, album, index);
+        }
+        if !albums.is_empty() {
+            self.display_spotify_footer(ui, displayed_results, total_results);
+        }
+    }
+
+    fn display_spotify_album(&mut self, ui: &mut egui::Ui, album: &Album, index: usize) {
+        let is_expanded = self.expanded_album_id.as_deref() == Some(album.id.as_str());
+        // 由專輯網址開啟時專輯已展開，但尚未開始搜尋譜面
+        let matches_started = self
+            .album_osu_matches
+            .lock()
+            .unwrap()
+            .contains_key(&album.id);
+        if is_expanded && !matches_started {
+            self.load_album_osu_matches(album);
+        }
+
+        ui.horizontal(|ui| {
+            if let Some(cover_url) = album.images.first().map(|img| &img.url) {
+                let texture = self
+                    .texture_cache
+                    .try_read()
+                    .ok()
+                    .and_then(|cache| cache.get(cover_url).cloned());
+                let key = CoverKey::Spotify(cover_url.clone());
+                match (texture, self.cover_load_error(&key)) {
+                    (Some(texture), _) => {
+                        self.mark_cover_visible(ui, &key);
+                        ui.add(egui::Image::new(egui::load::SizedTexture::new(
+                            texture.id(),
+                            egui::Vec2::new(100.0, 100.0),
+                        )));
+                    }
+                    (None, Some(error)) => {
+                        if self.display_cover_error(ui, &error) {
+                            self.retry_cover_load(key);
+                        }
+                    }
+                    (None, None) => {
+                        let visible = Self::is_cover_visible(ui, egui::vec2(100.0, 100.0));
+                        self.queue_texture_load(index, cover_url, visible);
+                        ui.add_sized([100.0, 100.0], egui::Spinner::new().size(32.0));
+                    }
+                }
+            }
+            ui.add_space(10.0);
+            ui.vertical(|ui| {
+                ui.label(
+                    egui::RichText::new(&album.name)
+                        .font(egui::FontId::proportional(self.global_font_size))
+                        .strong(),
+                );
+                ui.label(
+                    egui::RichText::new(
+                        album
+                            .artists
+                            .iter()
+                            .map(|a| a.name.clone())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    )
+                    .font(egui::FontId::proportional(self.global_font_size * 0.9)),
+                );
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} · {} 首曲目",
+                        album.release_date, album.total_tracks
+                    ))
+                    .font(egui::FontId::proportional(self.global_font_size * 0.7)),
+                );
+                ui.horizontal(|ui| {
+                    let expand_text = if is_expanded {
+                        "收起"
+                    } else {
+                        "搜尋 osu! 譜面"
+                    };
+                    if ui.button(expand_text).clicked() {
+                        if is_expanded {
+                            self.expanded_album_id = None;
+                        } else {
+                            self.expanded_album_id = Some(album.id.clone());
+                            let already_loaded = matches!(
+                                self.album_osu_matches.lock().unwrap().get(&album.id),
+                                Some(AlbumMatchState::Loaded(_)) | Some(AlbumMatchState::Loading)
+                            );
+                            if !already_loaded {
+                                self.load_album_osu_matches(album);
+                            }
+                        }
+                    }
+                    if let Some(url) = album.external_urls.get("spotify") {
+                        if ui.button("在 Spotify 開啟").clicked() {
+                            if let Err(e) = open_spotify_url(url) {
+                                error!("無法開啟 Spotify 專輯: {:?}", e);
+                            }
+                        }
+                    }
+                });
+            });
+        });
+
+        if is_expanded {
+            // 展開的專輯以封面主色作為背景，與封面保持視覺上的連續
+            let fill = album
+                .images
+                .first()
+                .and_then(|image| accent_color(&image.url))
+                .map_or(egui::Color32::TRANSPARENT, |accent| {
+                    egui::Color32::from_rgba_unmultiplied(accent.r(), accent.g(), accent.b(), 40)
+                });
+            egui::Frame::none()
+                .fill(fill)
+                .rounding(egui::Rounding::same(8.0))
+                .inner_margin(egui::Margin::same(8.0))
+                .show(ui, |ui| {
+                    self.display_album_osu_matches(ui, &album.id);
+                });
+        }
+
+        ui.add_space(5.0);
+        ui.separator();
+    }
+
+    fn display_album_osu_matches(&mut self, ui: &mut egui::Ui, album_id: &str) {
+        let state = self
+            .album_osu_matches
+            .lock()
+            .unwrap()
+            .get(album_id)
+            .cloned();
+        ui.indent(album_id, |ui| match state {
+            Some(AlbumMatchState::Loading) | None => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::Spinner::new());
+                    ui.label("正在搜尋專輯曲目的 osu! 譜面...");
+                });
+            }
+            Some(AlbumMatchState::Failed(e)) => {
+                ui.colored_label(egui::Color32::RED, format!("搜尋失敗: {}", e));
+            }
+            Some(AlbumMatchState::Loaded(matches)) => {
+                let total: usize = matches.iter().map(|m| m.beatmapsets.len()).sum();
+                ui.label(format!("共找到 {} 個譜面", total));
+                for track_matches in &matches {
+                    ui.label(egui::RichText::new(&track_matches.track_name).strong());
+                    if track_matches.beatmapsets.is_empty() {
+                        ui.label("  沒有找到譜面");
+                    }
+                    for beatmapset in &track_matches.beatmapsets {
+                        let status = self.cached_download_status(beatmapset.id);
+                        self.display_compact_beatmapset_row(ui, beatmapset, None, status);
+                    }
+                }
+            }
+        });
+    }
+
     //顯示Spotify搜索結果
     fn display_spotify_results(&mut self, ui: &mut egui::Ui, window_size: egui::Vec2) {
+        self.display_cover_error_summary(ui, false);
+        if self.search_mode == SearchMode::Album {
+            self.display_spotify_album_results(ui);
+            return;
+        }
         // 獲取排序後的搜索結果
         let sorted_results = self.get_sorted_spotify_results();
         let total_results = sorted_results.len();
@@ -1760,11 +3666,62 @@ impl SearchApp {
 
         // 顯示 Spotify 搜索結果的標題和統計信息
         self.display_spotify_header(ui, total_results, displayed_results);
+        Self::display_results_filter(
+            ui,
+            &mut self.spotify_results_filter,
+            "依歌名 / 歌手 / 專輯篩選已載入的曲目",
+        );
+        let filter = self.spotify_results_filter.trim().to_string();
 
+        if self.search_mode == SearchMode::Track && !sorted_results.is_empty() {
+            let visible_ids: Vec<String> = sorted_results
+                .iter()
+                .filter(|group| !group.track.is_episode)
+                .filter_map(|group| {
+                    group
+                        .track
+                        .external_urls
+                        .get("spotify")
+                        .and_then(|url| spotify_track_id(url))
+                })
+                .collect();
+            self.display_batch_like_bar(ui, &visible_ids);
+        }
         if !sorted_results.is_empty() {
+            let visible_links: Vec<String> = sorted_results
+                .iter()
+                .filter_map(|group| group.track.external_urls.get("spotify").cloned())
+                .collect();
+            self.link_selection.render(ui, &visible_links);
+        }
+
+        if !sorted_results.is_empty() && !filter.is_empty() {
+            let matched: Vec<(usize, &SpotifyResultGroup)> = sorted_results
+                .iter()
+                .enumerate()
+                .filter(|(_, group)| {
+                    let track = &group.track;
+                    let artists = track
+                        .artists
+                        .iter()
+                        .map(|artist| artist.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    fuzzy_matches(&filter, &[track.name.as_str(), &artists, &track.album.name])
+                })
+                .collect();
+            ui.label(format!(
+                "符合 {} / {} 首已載入的曲目",
+                matched.len(),
+                total_results
+            ));
+            for (index, group) in matched {
+                self.display_spotify_track(ui, &group.track, &group.alternates, index);
+            }
+        } else if !sorted_results.is_empty() {
             // 遍歷並顯示每個搜索結果
-            for (index, track) in sorted_results.iter().take(displayed_results).enumerate() {
-                self.display_spotify_track(ui, track, index);
+            for (index, group) in sorted_results.iter().take(displayed_results).enumerate() {
+                self.display_spotify_track(ui, &group.track, &group.alternates, index);
             }
             // 顯示底部的控制元素（如"顯示更多"按鈕）
             self.display_spotify_footer(ui, displayed_results, total_results);
@@ -1773,15 +3730,63 @@ impl SearchApp {
         };
     }
 
-    fn get_sorted_spotify_results(&self) -> Vec<Track> {
-        self.search_results
+    // 已載入結果上方的篩選欄，回傳內容是否變更
+    fn display_results_filter(ui: &mut egui::Ui, filter: &mut String, hint: &str) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("篩選:");
+            changed = ui
+                .add(
+                    egui::TextEdit::singleline(filter)
+                        .hint_text(hint)
+                        .desired_width(250.0),
+                )
+                .changed();
+            if !filter.is_empty() && ui.small_button("清除").clicked() {
+                filter.clear();
+                changed = true;
+            }
+        });
+        ui.add_space(5.0);
+        changed
+    }
+
+    // 依原始順序排列，啟用去除重複時將同一首歌的其他版本併入最前面的一筆
+    fn get_sorted_spotify_results(&self) -> Vec<SpotifyResultGroup> {
+        let mut results = self
+            .search_results
             .try_lock()
-            .map(|guard| {
-                let mut results = guard.clone();
-                results.sort_by_key(|track| track.index);
-                results
-            })
-            .unwrap_or_default()
+            .map(|guard| guard.clone())
+            .unwrap_or_default();
+        results.sort_by_key(|track| track.index);
+
+        let options = query_options();
+        if options.hide_unplayable {
+            results.retain(|track| !track.is_unplayable());
+        }
+        let dedup = options.dedup_results;
+        let mut groups: Vec<SpotifyResultGroup> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for track in results {
+            if dedup {
+                let artist = track
+                    .artists
+                    .first()
+                    .map(|artist| artist.name.as_str())
+                    .unwrap_or_default();
+                let key = duplicate_key(artist, &track.name);
+                if let Some(&position) = positions.get(&key) {
+                    groups[position].alternates.push(track);
+                    continue;
+                }
+                positions.insert(key, groups.len());
+            }
+            groups.push(SpotifyResultGroup {
+                track,
+                alternates: Vec::new(),
+            });
+        }
+        groups
     }
 
     fn display_spotify_header(
@@ -1799,8 +3804,14 @@ impl SearchApp {
                 } else {
                     egui::Color32::from_hex("#121212").unwrap_or(egui::Color32::BLACK)
                 };
+                let total_text = match self.spotify_search_paging.lock().unwrap().as_ref() {
+                    Some(paging) if paging.total as usize > total_results => {
+                        format!("總結果數: {} (已載入 {})", paging.total, total_results)
+                    }
+                    _ => format!("總結果數: {}", total_results),
+                };
                 ui.label(
-                    egui::RichText::new(format!("總結果數: {}", total_results))
+                    egui::RichText::new(total_text)
                         .size(self.global_font_size)
                         .color(text_color),
                 );
@@ -1809,6 +3820,25 @@ impl SearchApp {
                         .size(self.global_font_size)
                         .color(text_color),
                 );
+                if self.search_mode == SearchMode::Track
+                    && total_results > 0
+                    && ui.button("匯出結果").clicked()
+                {
+                    self.export_search_results();
+                }
+                if self.search_mode == SearchMode::Track {
+                    let mut query_opts = query_options();
+                    let mut changed = ui
+                        .checkbox(&mut query_opts.dedup_results, "合併重複曲目")
+                        .on_hover_text("同一首歌出現在單曲、專輯或合輯時只顯示一筆")
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut query_opts.hide_unplayable, "隱藏無法播放的曲目")
+                        .changed();
+                    if changed {
+                        set_query_options(query_opts);
+                    }
+                }
             });
 
             // 右側：Spotify logo
@@ -1837,7 +3867,69 @@ impl SearchApp {
         ui.add_space(10.0);
     }
 
-    fn display_spotify_footer(
+    // 將目前的 Spotify 結果與 osu! 結果配對後匯出成報告
+    fn export_search_results(&self) {
+        let tracks: Vec<Track> = self
+            .get_sorted_spotify_results()
+            .into_iter()
+            .map(|group| group.track)
+            .collect();
+        let beatmapsets = match self.osu_search_results.try_lock() {
+            Ok(results) => results.clone(),
+            Err(_) => {
+                error!("osu 搜尋結果忙碌中，無法匯出");
+                return;
+            }
+        };
+
+        let entries: Vec<ReportEntry> = tracks
+            .iter()
+            .map(|track| {
+                let artists = track
+                    .artists
+                    .iter()
+                    .map(|a| a.name.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let matches = rank_beatmapsets(
+                    &artists,
+                    &track.name,
+                    track.duration_ms,
+                    beatmapsets.clone(),
+                )
+                .iter()
+                .filter(|scored| scored.score >= 0.3)
+                .take(3)
+                .map(ReportMatch::from)
+                .collect();
+                ReportEntry {
+                    query: format!("{} - {}", artists, track.name),
+                    track: Some(ReportTrack {
+                        name: track.name.clone(),
+                        artists,
+                        album: Some(track.album.name.clone()),
+                        url: track.external_urls.get("spotify").cloned(),
+                    }),
+                    matches,
+                }
+            })
+            .collect();
+
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name("search_report.md")
+            .add_filter("Markdown", &["md"])
+            .add_filter("HTML", &["html"])
+            .save_file()
+        {
+            let title = format!("搜尋結果 - {}", self.search_query);
+            match export_report(&path, &title, &entries) {
+                Ok(_) => info!("已匯出搜尋結果: {:?}", path),
+                Err(e) => error!("匯出搜尋結果失敗: {:?}", e),
+            }
+        }
+    }
+
+    fn display_spotify_footer(
         &mut self,
         ui: &mut egui::Ui,
         displayed_results: usize,
@@ -1856,6 +3948,26 @@ impl SearchApp {
                     self.displayed_spotify_results =
                         (self.displayed_spotify_results + 10).min(total_results);
                 }
+            } else if self.is_loading_more_spotify.load(Ordering::SeqCst) {
+                ui.add_sized([150.0, 40.0], egui::Spinner::new());
+            } else if self
+                .spotify_search_paging
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map_or(false, |paging| paging.has_more())
+            {
+                // 已載入的結果都顯示完畢，向 API 要求下一頁
+                if ui
+                    .add_sized(
+                        [150.0, 40.0],
+                        egui::Button::new(egui::RichText::new("顯示更多").size(18.0)),
+                    )
+                    .clicked()
+                {
+                    self.displayed_spotify_results = displayed_results + 10;
+                    self.fetch_next_spotify_page();
+                }
             } else {
                 ui.label(egui::RichText::new("已顯示所有結果").size(18.0));
             }
@@ -1874,7 +3986,13 @@ impl SearchApp {
         ui.add_space(50.0);
     }
 
-    fn display_spotify_track(&mut self, ui: &mut egui::Ui, track: &Track, index: usize) {
+    fn display_spotify_track(
+        &mut self,
+        ui: &mut egui::Ui,
+        track: &Track,
+        alternates: &[Track],
+        index: usize,
+    ) {
         let response = ui.add(
             egui::Button::new("")
                 .frame(false)
@@ -1883,6 +4001,20 @@ impl SearchApp {
 
         ui.allocate_ui_at_rect(response.rect, |ui| {
             ui.horizontal(|ui| {
+                if self.batch_like.show && !track.is_episode {
+                    if let Some(track_id) = track
+                        .external_urls
+                        .get("spotify")
+                        .and_then(|url| spotify_track_id(url))
+                    {
+                        self.display_batch_like_checkbox(ui, &track_id);
+                    }
+                }
+                if self.link_selection.show {
+                    if let Some(url) = track.external_urls.get("spotify") {
+                        self.link_selection.checkbox(ui, url);
+                    }
+                }
                 self.display_album_cover(ui, track);
                 ui.add_space(10.0);
                 self.display_track_info(ui, track);
@@ -1893,20 +4025,62 @@ impl SearchApp {
 
         response.context_menu(|ui| self.create_track_context_menu(ui, track));
 
+        if !track.is_episode {
+            self.display_track_osu_matches(ui, track);
+        }
+
+        if !alternates.is_empty() {
+            Self::display_alternate_versions(ui, track, alternates);
+        }
+
         ui.add_space(5.0);
         ui.separator();
     }
 
+    // 去除重複後被併入的其他版本
+    fn display_alternate_versions(ui: &mut egui::Ui, track: &Track, alternates: &[Track]) {
+        egui::CollapsingHeader::new(format!("其他版本 ({})", alternates.len()))
+            .id_source(("spotify_alternates", track.index))
+            .show(ui, |ui| {
+                for alternate in alternates {
+                    ui.horizontal(|ui| {
+                        let artists = alternate
+                            .artists
+                            .iter()
+                            .map(|artist| artist.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        ui.label(format!("{} - {}", artists, alternate.name));
+                        ui.label(egui::RichText::new(&alternate.album.name).small().weak());
+                        if let Some(url) = alternate.external_urls.get("spotify") {
+                            if ui.small_button("開啟").clicked() {
+                                if let Err(e) = open::that(url) {
+                                    error!("無法開啟 Spotify 連結: {:?}", e);
+                                }
+                            }
+                        }
+                    });
+                }
+            });
+    }
+
     fn display_album_cover(&self, ui: &mut egui::Ui, track: &Track) {
         if let Some(cover_url) = track.album.images.first().map(|img| &img.url) {
             if let Ok(cache) = self.texture_cache.try_read() {
+                let key = CoverKey::Spotify(cover_url.clone());
                 if let Some(texture) = cache.get(cover_url) {
+                    self.mark_cover_visible(ui, &key);
                     ui.add(egui::Image::new(egui::load::SizedTexture::new(
                         texture.id(),
                         egui::Vec2::new(100.0, 100.0),
                     )));
+                } else if let Some(error) = self.cover_load_error(&key) {
+                    if self.display_cover_error(ui, &error) {
+                        self.retry_cover_load(key);
+                    }
                 } else {
-                    self.queue_texture_load(track.index, cover_url);
+                    let visible = Self::is_cover_visible(ui, egui::vec2(100.0, 100.0));
+                    self.queue_texture_load(track.index, cover_url, visible);
                     ui.add_sized([100.0, 100.0], egui::Spinner::new().size(32.0));
                 }
             } else {
@@ -1915,21 +4089,111 @@ impl SearchApp {
         }
     }
 
-    fn queue_texture_load(&self, index: usize, cover_url: &str) {
-        if let Ok(mut queue) = self.texture_load_queue.lock() {
-            if !queue.iter().any(|Reverse((_, url))| url == cover_url) {
-                queue.push(Reverse((index, cover_url.to_string())));
+    fn cover_load_error(&self, key: &CoverKey) -> Option<String> {
+        self.cover_load_errors.lock().unwrap().get(key).cloned()
+    }
+
+    // 清除失敗記錄並重新載入封面
+    fn retry_cover_load(&self, key: CoverKey) {
+        self.cover_load_errors.lock().unwrap().remove(&key);
+        match key {
+            CoverKey::Spotify(url) => self.queue_texture_load(0, &url, true),
+            CoverKey::Osu(index) => self.load_more_osu_covers(index, index + 1),
+        }
+    }
+
+    // 封面載入失敗的佔位圖，回傳是否按下重試
+    fn display_cover_error(&self, ui: &mut egui::Ui, error: &str) -> bool {
+        let mut retry = false;
+        ui.allocate_ui(egui::vec2(100.0, 100.0), |ui| {
+            ui.set_min_size(egui::vec2(100.0, 100.0));
+            ui.vertical_centered(|ui| {
+                ui.add_space(10.0);
+                ui.label(egui::RichText::new("🖼").size(32.0).weak())
+                    .on_hover_text(error);
+                ui.label(
+                    egui::RichText::new("封面載入失敗")
+                        .small()
+                        .color(egui::Color32::RED),
+                );
+                retry = ui.small_button("重試").clicked();
+            });
+        });
+        retry
+    }
+
+    // 顯示結果區的封面載入失敗數量，並提供全部重試
+    fn display_cover_error_summary(&self, ui: &mut egui::Ui, osu: bool) {
+        let failed: Vec<CoverKey> = self
+            .cover_load_errors
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|key| matches!(key, CoverKey::Osu(_)) == osu)
+            .cloned()
+            .collect();
+        if failed.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                egui::Color32::RED,
+                format!("{} 個封面載入失敗", failed.len()),
+            );
+            if ui.small_button("全部重試").clicked() {
+                for key in failed {
+                    self.retry_cover_load(key);
+                }
             }
+        });
+    }
+
+    // 登記封面載入，畫面外的請求會被取消，捲回畫面時再重新登記
+    fn queue_texture_load(&self, index: usize, cover_url: &str, visible: bool) {
+        if let Ok(mut queue) = self.texture_load_queue.lock() {
+            queue.request(index, cover_url, visible);
+        }
+    }
+
+    // 封面位置（含預先載入範圍）是否在可見區域內
+    fn is_cover_visible(ui: &egui::Ui, size: egui::Vec2) -> bool {
+        let rect = egui::Rect::from_min_size(ui.cursor().min, size);
+        ui.is_rect_visible(rect.expand2(egui::vec2(0.0, PREFETCH_MARGIN)))
+    }
+
+    // 在封面畫出前呼叫，記錄可見的封面讓記憶體淘汰略過它
+    fn mark_cover_visible(&self, ui: &egui::Ui, key: &CoverKey) {
+        if Self::is_cover_visible(ui, egui::vec2(100.0, 100.0)) {
+            self.texture_budget.lock().unwrap().touch(key);
         }
     }
 
     fn display_track_info(&mut self, ui: &mut egui::Ui, track: &Track) {
         ui.vertical(|ui| {
-            ui.label(
-                egui::RichText::new(&track.name)
-                    .font(egui::FontId::proportional(self.global_font_size * 1.0))
-                    .strong(),
-            );
+            ui.horizontal(|ui| {
+                if track.explicit {
+                    ui.label(
+                        egui::RichText::new(" E ")
+                            .small()
+                            .strong()
+                            .color(ui.visuals().strong_text_color())
+                            .background_color(ui.visuals().widgets.inactive.bg_fill),
+                    )
+                    .on_hover_text("含有露骨內容");
+                }
+                ui.label(
+                    egui::RichText::new(&track.name)
+                        .font(egui::FontId::proportional(self.global_font_size * 1.0))
+                        .strong(),
+                );
+            });
+            if track.is_unplayable() {
+                ui.label(
+                    egui::RichText::new("無法在你的地區播放")
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                );
+            }
 
             let artist_names = track
                 .artists
@@ -1956,9 +4220,33 @@ impl SearchApp {
                 egui::RichText::new(&track.album.name)
                     .font(egui::FontId::proportional(self.global_font_size * 0.7)),
             );
+            if track.is_episode {
+                Self::display_episode_details(ui, track);
+            }
         });
     }
 
+    // Podcast 單集的長度與發行日期
+    fn display_episode_details(ui: &mut egui::Ui, track: &Track) {
+        let mut details = vec!["Podcast".to_string()];
+        if let Some(duration_ms) = track.duration_ms {
+            let total_seconds = duration_ms / 1000;
+            details.push(match total_seconds / 3600 {
+                0 => format!("{}:{:02}", total_seconds / 60, total_seconds % 60),
+                hours => format!(
+                    "{}:{:02}:{:02}",
+                    hours,
+                    total_seconds / 60 % 60,
+                    total_seconds % 60
+                ),
+            });
+        }
+        if !track.album.release_date.is_empty() {
+            details.push(track.album.release_date.clone());
+        }
+        ui.label(egui::RichText::new(details.join(" · ")).small().weak());
+    }
+
     fn draw_spotify_circular_buttons(
         &mut self,
         ui: &mut egui::Ui,
@@ -1967,7 +4255,7 @@ impl SearchApp {
         center: egui::Pos2,
     ) {
         let button_size = egui::vec2(30.0, 30.0);
-        let container_width = 180.0;
+        let container_width = 220.0;
         let container_height = 30.0;
 
         let container_pos = egui::pos2(
@@ -1981,43 +4269,72 @@ impl SearchApp {
             button_size,
         );
 
-        if self.expanded_track_index == Some(index) {
-        } else {
+        let expanded = self.expanded_track_index == Some(index);
+        // 收起時容器仍會播放收合動畫，結束後才顯示展開按鈕
+        let animation_progress = animate_open(
+            ui.ctx(),
+            egui::Id::new(("spotify_buttons", index)),
+            expanded,
+        );
+
+        if !expanded && animation_progress == 0.0 {
             // 如果當前軌道未展開，顯示展開按鈕
-            if ui.put(expand_button_rect, egui::Button::new("▶")).clicked() {
+            let response = ui.put(expand_button_rect, egui::Button::new("▶"));
+            label_icon_button(&response, "展開曲目操作");
+            if response.clicked() {
                 self.expanded_track_index = Some(index);
+                self.focused_result = Some(ResultKind::Track);
+                if let Some(item) = ViewedItem::from_track(track) {
+                    record_view(item);
+                }
+            }
+        }
+        if expanded && self.focused_result == Some(ResultKind::Track) {
+            if let Some(button) = pressed_action(ui.ctx(), ResultKind::Track) {
+                // Podcast 單集不提供收藏與 osu! 搜尋
+                if !(track.is_episode && matches!(button, 2 | 3)) {
+                    self.handle_button_click(button, track, index, ui.ctx().clone());
+                }
             }
         }
 
-        if self.expanded_track_index == Some(index) {
-            // 計算動畫進度
-            let animation_progress = 1.0; // 暫時移除動畫，使用固定值
-
-            // 計算動畫中的容器寬度
+        if animation_progress > 0.0 {
+            // 容器從展開按鈕的位置向左展開
             let animated_width = container_width * animation_progress;
             let animated_container_rect = egui::Rect::from_min_size(
-                container_pos,
+                container_pos + egui::vec2(container_width - animated_width, 0.0),
                 egui::vec2(animated_width, container_height),
             );
 
-            // 如果當前軌道被展開，繪製完整的按鈕列表
+            // 如果當前軌道被展開，繪製完整的按鈕列表，容器帶有專輯封面的主色
+            let container_color = track
+                .album
+                .images
+                .first()
+                .and_then(|image| accent_color(&image.url))
+                .map_or(egui::Color32::WHITE, |accent| tint(accent, 0.4));
             ui.painter().rect(
                 animated_container_rect,
                 egui::Rounding::same(10.0),
-                egui::Color32::from_hex("#FFFFFF").unwrap_or(egui::Color32::WHITE),
+                container_color,
                 egui::Stroke::NONE,
             );
 
-            let total_buttons = 4; // 減少為4個按鈕
-            let spacing = animated_width / (total_buttons as f32 + 1.0);
+            let total_buttons = 5;
+            let spacing = container_width / (total_buttons as f32 + 1.0);
 
             for i in 0..total_buttons {
                 let button_center =
                     container_pos + egui::vec2((i as f32 + 1.0) * spacing, container_height / 2.0);
                 let rect = egui::Rect::from_center_size(button_center, button_size);
 
+                // Podcast 單集不提供收藏與 osu! 搜尋
+                if track.is_episode && matches!(i, 2 | 3) {
+                    continue;
+                }
+
                 // 只有當按鈕完全顯示時才繪製和處理
-                if rect.right() <= animated_container_rect.right() {
+                if animated_container_rect.contains_rect(rect) {
                     ui.painter().circle(
                         rect.center(),
                         button_size.x / 2.0,
@@ -2027,8 +4344,10 @@ impl SearchApp {
 
                     self.draw_button_icon(ui, rect, i, track);
 
+                    let label = action_label(ResultKind::Track, i, track.is_liked.unwrap_or(false));
                     let response = ui.allocate_rect(rect, egui::Sense::click());
-                    if response.clicked() {
+                    label_icon_button(&response, label);
+                    if expanded && response.clicked() {
                         self.handle_button_click(i, track, index, ui.ctx().clone());
                     }
                     if response.hovered() {
@@ -2038,20 +4357,7 @@ impl SearchApp {
                             egui::Color32::from_white_alpha(200),
                             egui::Stroke::NONE,
                         );
-                        let hover_text = match i {
-                            0 => "開啟",
-                            1 => "搜尋",
-                            2 => {
-                                if track.is_liked.unwrap_or(false) {
-                                    "取消收藏"
-                                } else {
-                                    "收藏"
-                                }
-                            }
-                            3 => "收起",
-                            _ => "",
-                        };
-                        response.on_hover_text(hover_text);
+                        response.on_hover_text(label);
                     }
                 }
             }
@@ -2076,9 +4382,6 @@ impl SearchApp {
                 );
             }
         }
-
-        // 請求重繪以實現動畫效果
-        ui.ctx().request_repaint();
     }
 
     fn draw_button_icon(&self, ui: &mut egui::Ui, rect: egui::Rect, index: usize, track: &Track) {
@@ -2122,6 +4425,16 @@ impl SearchApp {
                 }
             }
             3 => {
+                if let Some(texture) = self.preloaded_icons.get("osu!logo.png") {
+                    ui.painter().image(
+                        texture.id(),
+                        icon_rect,
+                        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                        egui::Color32::WHITE,
+                    );
+                }
+            }
+            4 => {
                 if let Some(texture) = self.preloaded_icons.get("expand_off.png") {
                     ui.painter().image(
                         texture.id(),
@@ -2146,71 +4459,484 @@ impl SearchApp {
             0 => self.handle_search_click(track),
             1 => self.handle_open_click(track),
             2 => self.handle_like_click(track, track_index, ctx),
-            3 => self.expanded_track_index = None, // 收起按鈕的處理邏輯
+            3 => self.handle_osu_only_search_click(track),
+            4 => self.expanded_track_index = None, // 收起按鈕的處理邏輯
             _ => {}
         }
     }
 
-    fn handle_search_click(&mut self, track: &Track) {
-        self.search_query = track
+    // 只針對這首曲目搜尋 osu!，結果顯示在曲目下方，不影響目前的搜尋結果
+    fn handle_osu_only_search_click(&mut self, track: &Track) {
+        let track_index = track.index;
+        let artists = track
+            .artists
+            .iter()
+            .map(|a| a.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let title = track.name.clone();
+        let duration_ms = track.duration_ms;
+        let remembered = track
             .external_urls
             .get("spotify")
-            .cloned()
-            .unwrap_or_else(|| {
-                format!(
-                    "{} {}",
-                    track.name,
-                    track
-                        .artists
-                        .iter()
-                        .map(|a| a.name.as_str())
-                        .collect::<Vec<_>>()
-                        .join(" ")
+            .and_then(|url| spotify_track_id(url))
+            .and_then(|track_id| confirmed_beatmapset(&track_id));
+        let client = self.client.clone();
+        let track_osu_matches = self.track_osu_matches.clone();
+        let ctx = self.ctx.clone();
+        let debug_mode = self.debug_mode;
+
+        track_osu_matches
+            .lock()
+            .unwrap()
+            .insert(track_index, TrackMatchState::Loading);
+        info!("僅搜尋 osu!: {} - {}", artists, title);
+
+        tokio::spawn(async move {
+            let result: Result<Vec<ScoredBeatmapset>> = async {
+                let client = client.lock().await.clone();
+                let osu_token = get_osu_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Osu 錯誤：無法獲取 token: {}", e))?;
+                // 使用者確認過的配對直接使用，不重新搜尋與評分
+                if let Some(beatmapset_id) = remembered {
+                    let beatmapset = get_beatmapset_by_id(
+                        &client,
+                        &osu_token,
+                        &beatmapset_id.to_string(),
+                        debug_mode,
+                    )
+                    .await
+                    .map_err(|e| anyhow!("Osu 錯誤：讀取已確認的譜面失敗: {}", e))?;
+                    return Ok(vec![ScoredBeatmapset {
+                        beatmapset,
+                        score: 1.0,
+                    }]);
+                }
+                let beatmapsets = search_beatmapsets_normalized(
+                    &client, &osu_token, &artists, &title, debug_mode,
                 )
+                .await
+                .map_err(|e| anyhow!("Osu 錯誤：搜索失敗: {}", e))?;
+                let mut ranked = rank_beatmapsets(&artists, &title, duration_ms, beatmapsets);
+                ranked.truncate(5);
+                Ok(ranked)
+            }
+            .await;
+
+            let state = match result {
+                Ok(matches) => TrackMatchState::Loaded(matches),
+                Err(e) => {
+                    error!("曲目 osu! 搜尋失敗: {:?}", e);
+                    TrackMatchState::Failed(e.to_string())
+                }
+            };
+            track_osu_matches.lock().unwrap().insert(track_index, state);
+            ctx.request_repaint();
+        });
+    }
+
+    fn display_track_osu_matches(&mut self, ui: &mut egui::Ui, track: &Track) {
+        let track_index = track.index;
+        let state = match self.track_osu_matches.lock().unwrap().get(&track_index) {
+            Some(state) => state.clone(),
+            None => return,
+        };
+
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("osu! 譜面").strong());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("✖").clicked() {
+                        self.track_osu_matches.lock().unwrap().remove(&track_index);
+                    }
+                });
             });
-        self.perform_search(self.ctx.clone());
+            match state {
+                TrackMatchState::Loading => {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Spinner::new());
+                        ui.label("搜尋中...");
+                    });
+                }
+                TrackMatchState::Failed(e) => {
+                    ui.colored_label(egui::Color32::RED, format!("搜尋失敗: {}", e));
+                }
+                TrackMatchState::Loaded(matches) => {
+                    if matches.is_empty() {
+                        ui.label("沒有找到譜面");
+                    } else if ui
+                        .small_button("匯出試聽清單")
+                        .on_hover_text("將 Spotify 試聽與候選譜面的預覽匯出為 m3u/xspf 播放清單")
+                        .clicked()
+                    {
+                        self.export_match_previews(track, &matches);
+                    }
+                    for scored in &matches {
+                        let status = self.cached_download_status(scored.beatmapset.id);
+                        ui.horizontal(|ui| {
+                            self.display_compact_beatmapset_row(
+                                ui,
+                                &scored.beatmapset,
+                                Some(scored.score),
+                                status,
+                            );
+                            if let Some(mismatch) =
+                                duration_mismatch(track.duration_ms, &scored.beatmapset)
+                            {
+                                ui.colored_label(ui.visuals().warn_fg_color, mismatch.label())
+                                    .on_hover_text(mismatch.detail());
+                            }
+                            self.render_match_memory_button(ui, track, &scored.beatmapset);
+                            self.render_report_wrong_match_button(ui, track, scored);
+                            let can_compare = track.preview_url.is_some()
+                                && scored.beatmapset.preview_url.is_some()
+                                && self.audio_output.is_some();
+                            if can_compare
+                                && ui
+                                    .small_button("A/B")
+                                    .on_hover_text("比較 Spotify 試聽與 osu! 預覽")
+                                    .clicked()
+                            {
+                                self.start_preview_compare(track, &scored.beatmapset);
+                            }
+                        });
+                        self.display_preview_compare(ui, track_index, scored.beatmapset.id);
+                    }
+                }
+            }
+        });
     }
 
-    fn handle_open_click(&self, track: &Track) {
-        if let Some(url) = track.external_urls.get("spotify") {
-            if let Err(e) = open_spotify_url(url) {
-                log::error!("無法開啟 URL: {}", e);
+    // 以預覽快取產生播放清單，下載譜面前先在外部播放器試聽候選歌曲
+    fn export_match_previews(&self, track: &Track, matches: &[ScoredBeatmapset]) {
+        let artists = track
+            .artists
+            .iter()
+            .map(|a| a.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut sources = Vec::new();
+        let spotify_id = track
+            .external_urls
+            .get("spotify")
+            .and_then(|url| spotify_track_id(url));
+        if let (Some(track_id), Some(url)) = (spotify_id, &track.preview_url) {
+            sources.push(PreviewSource {
+                key: format!("spotify_{}", track_id),
+                url: url.clone(),
+                title: format!("[Spotify] {} - {}", artists, track.name),
+            });
+        }
+        for scored in matches {
+            let beatmapset = &scored.beatmapset;
+            if let Some(url) = &beatmapset.preview_url {
+                sources.push(PreviewSource {
+                    key: beatmapset.id.to_string(),
+                    url: osu_preview_url(url),
+                    title: format!(
+                        "[osu! {:.0}%] {} - {} (by {})",
+                        scored.score * 100.0,
+                        beatmapset.artist,
+                        beatmapset.title,
+                        beatmapset.creator
+                    ),
+                });
             }
         }
+        if sources.is_empty() {
+            warn!("{} - {} 沒有可匯出的預覽", artists, track.name);
+            return;
+        }
+
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name("previews.m3u")
+            .add_filter("M3U", &["m3u", "m3u8"])
+            .add_filter("XSPF", &["xspf"])
+            .save_file()
+        {
+            let title = format!("{} - {}", artists, track.name);
+            tokio::spawn(async move {
+                if let Err(e) = export_preview_playlist(path, title, sources).await {
+                    error!("匯出試聽清單失敗: {:?}", e);
+                }
+            });
+        }
     }
 
-    fn handle_like_click(&mut self, track: &Track, index: usize, ctx: egui::Context) {
-        if self.spotify_authorized.load(Ordering::SeqCst)
-            && self.spotify_client.lock().unwrap().is_some()
+    // 回報後這組配對分數歸零，之後的搜尋、同步與自動下載都不再選用
+    fn render_report_wrong_match_button(
+        &self,
+        ui: &mut egui::Ui,
+        track: &Track,
+        scored: &ScoredBeatmapset,
+    ) {
+        let artists = track
+            .artists
+            .iter()
+            .map(|a| a.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if is_reported_wrong(&artists, &track.name, scored.beatmapset.id) {
+            ui.label(egui::RichText::new("已回報錯誤").small().weak());
+            return;
+        }
+        if ui
+            .small_button("回報錯誤配對")
+            .on_hover_text("這個譜面不是這首歌，之後不再推薦")
+            .clicked()
         {
-            let track_id = track
+            let track_key = track
                 .external_urls
                 .get("spotify")
-                .and_then(|url| url.split('/').last())
-                .unwrap_or("");
-            let is_liked = track.is_liked.unwrap_or(false);
-            self.toggle_track_like_status(track_id, is_liked, index, ctx);
+                .and_then(|url| spotify_track_id(url))
+                .unwrap_or_else(|| format!("{} - {}", artists, track.name));
+            report_wrong_match(&track_key, &artists, &track.name, scored);
         }
     }
 
-    fn toggle_track_like_status(
+    // 確認配對後，之後的搜尋與同步都直接使用這個譜面
+    fn render_match_memory_button(
         &self,
-        track_id: &str,
-        is_liked: bool,
-        index: usize,
-        ctx: egui::Context,
+        ui: &mut egui::Ui,
+        track: &Track,
+        beatmapset: &Beatmapset,
     ) {
-        let track_id = track_id.to_string();
-        let spotify_client = self.spotify_client.clone();
-        let search_results = self.search_results.clone();
-
-        tokio::spawn(async move {
-            let spotify_option = {
-                let spotify_guard = spotify_client.lock().unwrap();
-                spotify_guard.as_ref().cloned()
-            };
-
-            if let Some(spotify) = spotify_option {
+        let track_id = match track
+            .external_urls
+            .get("spotify")
+            .and_then(|url| spotify_track_id(url))
+        {
+            Some(track_id) => track_id,
+            None => return,
+        };
+        if confirmed_beatmapset(&track_id) == Some(beatmapset.id) {
+            ui.label("✔ 已確認");
+            if ui
+                .small_button("忘記")
+                .on_hover_text("移除這個配對，下次重新搜尋")
+                .clicked()
+            {
+                forget_match(&track_id);
+            }
+        } else if ui
+            .small_button("確認")
+            .on_hover_text("記住這首曲目對應此譜面")
+            .clicked()
+        {
+            let artists = track
+                .artists
+                .iter()
+                .map(|a| a.name.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+            remember_match(
+                &track_id,
+                &format!("{} - {}", artists, track.name),
+                beatmapset,
+            );
+        }
+    }
+
+    fn start_preview_compare(&mut self, track: &Track, beatmapset: &Beatmapset) {
+        let stream_handle = match self.audio_output.as_ref() {
+            Some((_, handle)) => handle.clone(),
+            None => return,
+        };
+        // 先關閉上一組比較，停止它的播放
+        self.preview_compare = None;
+        self.preview_compare = PreviewCompare::start(
+            &self.ctx,
+            stream_handle,
+            track,
+            beatmapset,
+            self.global_volume,
+            self.preview_speed,
+            self.preview_eq,
+        );
+    }
+
+    fn display_preview_compare(
+        &mut self,
+        ui: &mut egui::Ui,
+        track_index: usize,
+        beatmapset_id: i32,
+    ) {
+        let compare = match self.preview_compare.as_mut() {
+            Some(compare)
+                if compare.track_index == track_index && compare.beatmapset_id == beatmapset_id =>
+            {
+                compare
+            }
+            _ => return,
+        };
+        if let Some(CompareAction::Close) = compare.render(ui) {
+            self.preview_compare = None;
+        }
+    }
+
+    // 精簡的譜面列：名稱、匹配度、下載狀態與開啟按鈕
+    fn display_compact_beatmapset_row(
+        &mut self,
+        ui: &mut egui::Ui,
+        beatmapset: &Beatmapset,
+        score: Option<f32>,
+        status: DownloadStatus,
+    ) {
+        ui.horizontal(|ui| {
+            let mut text = format!(
+                "{} - {} (by {})",
+                beatmapset.artist, beatmapset.title, beatmapset.creator
+            );
+            if let Some(score) = score {
+                text.push_str(&format!(" [{:.0}%]", score * 100.0));
+            }
+            ui.label(text);
+            match status {
+                DownloadStatus::Completed => {
+                    ui.label("已下載");
+                }
+                DownloadStatus::Downloading => {
+                    ui.add(egui::Spinner::new());
+                }
+                DownloadStatus::Waiting => {
+                    ui.label("等待中");
+                }
+                DownloadStatus::NotStarted => {
+                    if ui.small_button("下載").clicked() {
+                        self.enqueue_beatmap_download(beatmapset.id);
+                    }
+                }
+            }
+            if ui.small_button("開啟").clicked() {
+                let url = format!("https://osu.ppy.sh/beatmapsets/{}", beatmapset.id);
+                if let Err(e) = open::that(url) {
+                    error!("無法開啟譜面頁面: {:?}", e);
+                }
+            }
+        });
+    }
+
+    fn handle_search_click(&mut self, track: &Track) {
+        self.search_query = track
+            .external_urls
+            .get("spotify")
+            .cloned()
+            .unwrap_or_else(|| {
+                format!(
+                    "{} {}",
+                    track.name,
+                    track
+                        .artists
+                        .iter()
+                        .map(|a| a.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )
+            });
+        self.perform_search(self.ctx.clone());
+    }
+
+    fn handle_open_click(&self, track: &Track) {
+        if let Some(url) = track.external_urls.get("spotify") {
+            if let Err(e) = open_spotify_url(url) {
+                log::error!("無法開啟 URL: {}", e);
+            }
+        }
+    }
+
+    fn handle_like_click(&mut self, track: &Track, index: usize, ctx: egui::Context) {
+        if self.spotify_authorized.load(Ordering::SeqCst)
+            && self.spotify_client.lock().unwrap().is_some()
+        {
+            let track_id = track
+                .external_urls
+                .get("spotify")
+                .and_then(|url| url.split('/').last())
+                .unwrap_or("");
+            let is_liked = track.is_liked.unwrap_or(false);
+            self.toggle_track_like_status(track_id, is_liked, index, ctx);
+        }
+    }
+
+    // 多選開關與批次加入 / 移除喜歡的操作列，visible_ids 為可選取的曲目
+    fn display_batch_like_bar(&mut self, ui: &mut egui::Ui, visible_ids: &[String]) {
+        let authorized = self.spotify_authorized.load(Ordering::SeqCst);
+        ui.add_enabled(
+            authorized,
+            egui::Checkbox::new(&mut self.batch_like.show, "多選"),
+        )
+        .on_disabled_hover_text("需要登入 Spotify");
+        if !authorized || !self.batch_like.show {
+            return;
+        }
+        if let Some(request) = self.batch_like.render(ui, visible_ids) {
+            self.start_batch_like(request, ui.ctx().clone());
+        }
+        ui.add_space(5.0);
+    }
+
+    fn display_batch_like_checkbox(&mut self, ui: &mut egui::Ui, track_id: &str) {
+        let mut selected = self.batch_like.is_selected(track_id);
+        if ui.checkbox(&mut selected, "").changed() {
+            self.batch_like.set_selected(track_id, selected);
+        }
+    }
+
+    fn start_batch_like(&mut self, request: BatchLikeRequest, ctx: egui::Context) {
+        let spotify = self.spotify_client.lock().unwrap().clone();
+        match spotify {
+            Some(spotify) => self.batch_like.start(spotify, request, ctx),
+            None => error!("無法獲取 Spotify 客戶端"),
+        }
+    }
+
+    // 批次操作完成後同步搜尋結果與 Liked Songs 列表
+    fn sync_batch_like_results(&mut self) {
+        let (action, track_ids) = match self.batch_like.take_applied() {
+            Some(applied) => applied,
+            None => return,
+        };
+        let track_ids: HashSet<String> = track_ids.into_iter().collect();
+        if let Ok(mut results) = self.search_results.try_lock() {
+            for track in results.iter_mut() {
+                let track_id = track
+                    .external_urls
+                    .get("spotify")
+                    .and_then(|url| spotify_track_id(url));
+                if track_id.map_or(false, |id| track_ids.contains(&id)) {
+                    track.is_liked = Some(action == LikeAction::Like);
+                }
+            }
+        }
+        if action == LikeAction::Unlike {
+            self.spotify_liked_tracks.lock().unwrap().retain(|track| {
+                track
+                    .id
+                    .as_ref()
+                    .map_or(true, |id| !track_ids.contains(id.id()))
+            });
+        }
+    }
+
+    fn toggle_track_like_status(
+        &self,
+        track_id: &str,
+        is_liked: bool,
+        index: usize,
+        ctx: egui::Context,
+    ) {
+        let track_id = track_id.to_string();
+        let spotify_client = self.spotify_client.clone();
+        let search_results = self.search_results.clone();
+
+        tokio::spawn(async move {
+            let spotify_option = {
+                let spotify_guard = spotify_client.lock().unwrap();
+                spotify_guard.as_ref().cloned()
+            };
+
+            if let Some(spotify) = spotify_option {
                 let result = if is_liked {
                     remove_track_from_liked(&spotify, &track_id).await
                 } else {
@@ -2236,6 +4962,8 @@ impl SearchApp {
     }
 
     fn create_track_context_menu(&self, ui: &mut egui::Ui, track: &Track) {
+        let plugin_actions = actions_for(ActionTarget::Track);
+        let payload = track_payload(track);
         self.create_context_menu(ui, |add_button| {
             if let Some(url) = track.external_urls.get("spotify") {
                 add_button(
@@ -2254,6 +4982,97 @@ impl SearchApp {
                     }),
                 );
             }
+            Self::add_plugin_buttons(add_button, plugin_actions, payload);
+        });
+    }
+    // 將使用者設定的自訂動作加入右鍵選單
+    fn add_plugin_buttons(
+        add_button: &mut dyn FnMut(&str, Box<dyn FnOnce() + '_>),
+        actions: Vec<PluginAction>,
+        payload: serde_json::Value,
+    ) {
+        for action in actions {
+            let name = action.name.clone();
+            let payload = payload.clone();
+            add_button(&name, Box::new(move || spawn_action(action, payload)));
+        }
+    }
+    fn create_beatmapset_context_menu(&self, ui: &mut egui::Ui, beatmapset: &Beatmapset) {
+        let beatmapset_id = beatmapset.id;
+        let label = format!("{} - {}", beatmapset.artist, beatmapset.title);
+        let url = format!("https://osu.ppy.sh/beatmapsets/{}", beatmapset_id);
+        let scheduler = self.download_scheduler.clone();
+        let already_scheduled = scheduler.contains(beatmapset_id);
+        let is_downloaded = self.get_download_status(beatmapset_id) == DownloadStatus::Completed;
+        let playlist_builder = &self.playlist_builder;
+        let in_builder = playlist_builder.contains(beatmapset_id);
+        let builder_beatmapset = beatmapset.clone();
+        let plugin_actions = actions_for(ActionTarget::Beatmapset);
+        let payload = beatmapset_payload(beatmapset);
+        let creator = beatmapset.creator.clone();
+        let mapper = mapper_preference(&creator);
+
+        self.create_context_menu(ui, |add_button| {
+            if !in_builder {
+                add_button(
+                    "加入歌單建立器",
+                    Box::new(move || {
+                        playlist_builder.add_beatmapset(&builder_beatmapset);
+                    }),
+                );
+            }
+            add_button(
+                "複製連結",
+                Box::new(move || {
+                    let mut ctx: ClipboardContext = ClipboardProvider::new().unwrap();
+                    ctx.set_contents(url).unwrap();
+                }),
+            );
+            if already_scheduled {
+                add_button(
+                    "取消稍後下載",
+                    Box::new(move || scheduler.remove(beatmapset_id)),
+                );
+            } else if !is_downloaded {
+                add_button(
+                    "稍後下載",
+                    Box::new(move || {
+                        scheduler.add(
+                            beatmapset_id,
+                            label,
+                            ScheduleTrigger::Idle {
+                                minutes: DEFAULT_IDLE_MINUTES,
+                            },
+                        )
+                    }),
+                );
+            }
+            // 喜愛與封鎖的譜面作者
+            if mapper.is_some() {
+                let creator = creator.clone();
+                add_button(
+                    "取消作者標記",
+                    Box::new(move || set_mapper_preference(&creator, None)),
+                );
+            }
+            if mapper != Some(MapperPreference::Favorite) {
+                let creator = creator.clone();
+                add_button(
+                    "喜愛此作者",
+                    Box::new(move || {
+                        set_mapper_preference(&creator, Some(MapperPreference::Favorite))
+                    }),
+                );
+            }
+            if mapper != Some(MapperPreference::Blocked) {
+                add_button(
+                    "封鎖此作者",
+                    Box::new(move || {
+                        set_mapper_preference(&creator, Some(MapperPreference::Blocked))
+                    }),
+                );
+            }
+            Self::add_plugin_buttons(add_button, plugin_actions, payload);
         });
     }
     //顯示osu搜索結果
@@ -2264,10 +5083,73 @@ impl SearchApp {
         // 計算實際顯示的結果數量
         let displayed_results = self.displayed_osu_results.min(total_results);
 
+        // 結果中出現過的 mania 鍵數，供鍵數篩選使用
+        let mut available_keys: Vec<u32> = sorted_results
+            .iter()
+            .flat_map(|beatmapset| beatmapset.key_counts())
+            .collect();
+        available_keys.sort_unstable();
+        available_keys.dedup();
+
         // 顯示 osu 搜索結果的標題和統計信息
-        self.display_osu_header(ui, total_results, displayed_results);
+        self.display_osu_header(ui, total_results, displayed_results, &available_keys);
+        self.display_cover_error_summary(ui, true);
+        self.display_beatmap_pack_hints(ui, &sorted_results);
+        let filter_changed = Self::display_results_filter(
+            ui,
+            &mut self.osu_results_filter,
+            "依標題 / 歌手 / 作者篩選已載入的譜面",
+        );
+        let filter = self.osu_results_filter.trim().to_string();
+        // 排序只改變顯示順序，索引仍對應封面
+        let order = self.osu_result_order(&sorted_results);
+        if !sorted_results.is_empty() && self.selected_beatmapset.is_none() {
+            let visible_links: Vec<String> = sorted_results
+                .iter()
+                .map(|beatmapset| format!("https://osu.ppy.sh/beatmapsets/{}", beatmapset.id))
+                .collect();
+            self.link_selection.render(ui, &visible_links);
+        }
 
-        if !sorted_results.is_empty() {
+        if !sorted_results.is_empty() && self.selected_beatmapset.is_none() && !filter.is_empty() {
+            let matched: Vec<usize> = order
+                .iter()
+                .map(|&index| (index, &sorted_results[index]))
+                .filter(|(_, beatmapset)| !is_hidden_mapper(&beatmapset.creator))
+                .filter(|(_, beatmapset)| {
+                    fuzzy_matches(
+                        &filter,
+                        &[
+                            beatmapset.title.as_str(),
+                            beatmapset.artist.as_str(),
+                            beatmapset.creator.as_str(),
+                            beatmapset.title_unicode.as_deref().unwrap_or_default(),
+                            beatmapset.artist_unicode.as_deref().unwrap_or_default(),
+                        ],
+                    )
+                })
+                .map(|(index, _)| index)
+                .collect();
+            // 篩選出的譜面可能在尚未顯示的範圍，補載它們的封面
+            if filter_changed {
+                let missing_covers: Vec<usize> = matched
+                    .iter()
+                    .copied()
+                    .filter(|&index| index >= displayed_results)
+                    .collect();
+                if !missing_covers.is_empty() {
+                    self.load_osu_covers_at(missing_covers);
+                }
+            }
+            ui.label(format!(
+                "符合 {} / {} 個已載入的譜面",
+                matched.len(),
+                total_results
+            ));
+            for index in matched {
+                self.display_beatmapset(ui, &sorted_results[index], index);
+            }
+        } else if !sorted_results.is_empty() {
             // 檢查是否有選中的譜面集
             if let Some(selected_index) = self.selected_beatmapset {
                 if let Some(selected_beatmapset) = sorted_results.get(selected_index) {
@@ -2276,12 +5158,19 @@ impl SearchApp {
                 } else {
                     // 如果選中的索引無效，重置選擇
                     self.selected_beatmapset = None;
+                    *self.detail_cover.lock().unwrap() = None;
+                    *self.detail_description.lock().unwrap() = None;
                 }
             } else {
                 // 遍歷並顯示每個搜索結果
-                for (index, beatmapset) in sorted_results.iter().take(displayed_results).enumerate()
-                {
-                    self.display_beatmapset(ui, beatmapset, index);
+                // 封鎖的作者設為隱藏時略過，索引仍需對應封面
+                let visible: Vec<usize> = order.into_iter().take(displayed_results).collect();
+                self.load_sorted_osu_covers(&visible, displayed_results, total_results);
+                for index in visible {
+                    let beatmapset = &sorted_results[index];
+                    if !is_hidden_mapper(&beatmapset.creator) {
+                        self.display_beatmapset(ui, beatmapset, index);
+                    }
                 }
                 // 顯示底部的控制元素（如"顯示更多"按鈕）
                 self.display_osu_footer(ui, displayed_results, total_results);
@@ -2292,19 +5181,151 @@ impl SearchApp {
         }
     }
 
+    // 已載入的結果中有多個譜面屬於同一個官方譜面包時，提示改為下載整個譜面包
+    fn display_beatmap_pack_hints(&mut self, ui: &mut egui::Ui, results: &[Beatmapset]) {
+        self.poll_fetched_beatmap_pack();
+        let mut packs: Vec<(String, usize)> = Vec::new();
+        for beatmapset in results
+            .iter()
+            .filter(|beatmapset| !is_hidden_mapper(&beatmapset.creator))
+        {
+            for tag in &beatmapset.pack_tags {
+                match packs.iter_mut().find(|(pack_tag, _)| pack_tag == tag) {
+                    Some((_, count)) => *count += 1,
+                    None => packs.push((tag.clone(), 1)),
+                }
+            }
+        }
+        for (tag, count) in packs
+            .into_iter()
+            .filter(|(_, count)| *count >= PACK_HINT_MIN_MATCHES)
+        {
+            ui.horizontal(|ui| {
+                ui.label(format!("📦 {} 個搜尋結果屬於譜面包 {}", count, tag));
+                if self.loading_beatmap_pack.as_deref() == Some(tag.as_str()) {
+                    ui.add(egui::Spinner::new());
+                } else if ui
+                    .add_enabled(
+                        self.loading_beatmap_pack.is_none(),
+                        egui::Button::new("下載整個譜面包"),
+                    )
+                    .clicked()
+                {
+                    self.fetch_beatmap_pack(tag.clone());
+                }
+            });
+        }
+    }
+
+    fn fetch_beatmap_pack(&mut self, tag: String) {
+        self.loading_beatmap_pack = Some(tag.clone());
+        let client = self.client.clone();
+        let fetched_beatmap_pack = self.fetched_beatmap_pack.clone();
+        let ctx = self.ctx.clone();
+        let debug_mode = self.debug_mode;
+        info!("讀取譜面包: {}", tag);
+
+        tokio::spawn(async move {
+            let result: Result<BeatmapPack> = async {
+                let client = client.lock().await.clone();
+                let osu_token = get_osu_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Osu 錯誤：無法獲取 token: {}", e))?;
+                get_beatmap_pack(&client, &osu_token, &tag, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Osu 錯誤：讀取譜面包失敗: {}", e))
+            }
+            .await;
+
+            if let Err(e) = &result {
+                error!("讀取譜面包 {} 失敗: {:?}", tag, e);
+            }
+            *fetched_beatmap_pack.lock().unwrap() = Some(result.map_err(|e| e.to_string()));
+            ctx.request_repaint();
+        });
+    }
+
+    // 列出譜面包的內容，確認後只下載尚未下載的譜面
+    fn poll_fetched_beatmap_pack(&mut self) {
+        let fetched = self.fetched_beatmap_pack.lock().unwrap().take();
+        let fetched = match fetched {
+            Some(fetched) => fetched,
+            None => return,
+        };
+        self.loading_beatmap_pack = None;
+        let pack = match fetched {
+            Ok(pack) => pack,
+            Err(e) => {
+                self.request_confirmation(ConfirmRequest::notice(
+                    "download_beatmap_pack",
+                    "下載譜面包",
+                    format!("無法讀取譜面包: {}", e),
+                ));
+                return;
+            }
+        };
+
+        let mut missing = Vec::new();
+        let details: Vec<String> = pack
+            .beatmapsets
+            .iter()
+            .map(|beatmapset| {
+                let downloaded = self.is_beatmap_downloaded(beatmapset.id);
+                if !downloaded {
+                    missing.push(beatmapset.id);
+                }
+                let mark = if downloaded { "✔" } else { "⬇" };
+                format!("{} {} - {}", mark, beatmapset.artist, beatmapset.title)
+            })
+            .collect();
+        if missing.is_empty() {
+            self.request_confirmation(
+                ConfirmRequest::notice(
+                    "download_beatmap_pack",
+                    "下載譜面包",
+                    format!("「{}」中的譜面都已下載", pack.name),
+                )
+                .details(details),
+            );
+            return;
+        }
+        self.request_confirmation(
+            ConfirmRequest::new(
+                "download_beatmap_pack",
+                "下載譜面包",
+                format!(
+                    "「{}」共 {} 個譜面，將下載其中 {} 個尚未下載的譜面",
+                    pack.name,
+                    pack.beatmapsets.len(),
+                    missing.len()
+                ),
+                ConfirmAction::DownloadBeatmapPack(missing),
+            )
+            .details(details)
+            .confirm_label("下載"),
+        );
+    }
+
     //顯示osu搜索結果的標題和統計信息
     fn display_osu_header(
-        &self,
+        &mut self,
         ui: &mut egui::Ui,
         total_results: usize,
         displayed_results: usize,
+        available_keys: &[u32],
     ) {
         ui.horizontal(|ui| {
             // 左側：結果統計和總結果數
             ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
                 ui.add_space(20.0);
+                let total_text = match *self.osu_total_results.lock().unwrap() {
+                    Some(total) if total as usize > total_results => {
+                        format!("總結果數: {} (已載入 {})", total, total_results)
+                    }
+                    _ => format!("總結果數: {}", total_results),
+                };
                 ui.label(
-                    egui::RichText::new(format!("總結果數: {}", total_results))
+                    egui::RichText::new(total_text)
                         .size(self.global_font_size)
                         .color(egui::Color32::from_hex("#FF66AA").unwrap_or(egui::Color32::WHITE)),
                 );
@@ -2313,6 +5334,35 @@ impl SearchApp {
                         .size(self.global_font_size)
                         .color(egui::Color32::from_hex("#FF66AA").unwrap_or(egui::Color32::WHITE)),
                 );
+                ui.horizontal(|ui| {
+                    ui.label("排序:");
+                    egui::ComboBox::from_id_source("osu_result_sort")
+                        .selected_text(self.osu_result_sort.label())
+                        .show_ui(ui, |ui| {
+                            for sort in OsuResultSort::ALL {
+                                ui.selectable_value(&mut self.osu_result_sort, sort, sort.label());
+                            }
+                        });
+                    if !available_keys.is_empty() || self.osu_key_filter.is_some() {
+                        ui.label("鍵數:");
+                        let selected = match self.osu_key_filter {
+                            Some(keys) => format!("{}K", keys),
+                            None => "全部".to_string(),
+                        };
+                        egui::ComboBox::from_id_source("osu_key_filter")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.osu_key_filter, None, "全部");
+                                for &keys in available_keys {
+                                    ui.selectable_value(
+                                        &mut self.osu_key_filter,
+                                        Some(keys),
+                                        format!("{}K", keys),
+                                    );
+                                }
+                            });
+                    }
+                });
             });
 
             // 右側：osu! logo
@@ -2354,6 +5404,20 @@ impl SearchApp {
                     self.displayed_osu_results = new_displayed_results;
                     self.load_more_osu_covers(displayed_results, new_displayed_results);
                 }
+            } else if self.is_loading_more_osu.load(Ordering::SeqCst) {
+                ui.add_sized([150.0, 40.0], egui::Spinner::new());
+            } else if self.osu_search_cursor.lock().unwrap().is_some() {
+                // 已取得的結果都顯示完畢，向 API 要求下一頁
+                if ui
+                    .add_sized(
+                        [150.0, 40.0],
+                        egui::Button::new(egui::RichText::new("顯示更多").size(18.0)),
+                    )
+                    .clicked()
+                {
+                    self.displayed_osu_results = displayed_results + 10;
+                    self.fetch_next_osu_page();
+                }
             } else {
                 ui.label(egui::RichText::new("已顯示所有結果").size(18.0));
             }
@@ -2374,8 +5438,60 @@ impl SearchApp {
     }
 
     //獲取排序後的osu搜索結果
-    fn get_sorted_osu_results(&self) -> Vec<Beatmapset> {
-        if let Ok(osu_search_results_guard) = self.osu_search_results.try_lock() {
+    // 依目前的排序方式回傳結果的索引順序，同分時維持 API 回傳的順序
+    fn osu_result_order(&self, results: &[Beatmapset]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..results.len())
+            .filter(|&index| match self.osu_key_filter {
+                Some(keys) => results[index].key_counts().contains(&keys),
+                None => true,
+            })
+            .collect();
+        match self.osu_result_sort {
+            OsuResultSort::Relevance => {}
+            OsuResultSort::Favourites => {
+                order.sort_by_key(|&index| std::cmp::Reverse(results[index].favourite_count))
+            }
+            OsuResultSort::PlayCount => {
+                order.sort_by_key(|&index| std::cmp::Reverse(results[index].play_count))
+            }
+        }
+        order
+    }
+
+    // 排序後顯示的譜面可能在原本載入封面的範圍之外，補載它們的封面
+    fn load_sorted_osu_covers(
+        &mut self,
+        visible: &[usize],
+        displayed_results: usize,
+        total_results: usize,
+    ) {
+        let key = (
+            self.osu_result_sort,
+            self.osu_key_filter,
+            displayed_results,
+            total_results,
+        );
+        if (self.osu_result_sort == OsuResultSort::Relevance && self.osu_key_filter.is_none())
+            || self.sorted_covers_loaded_for == Some(key)
+        {
+            return;
+        }
+        let missing: Vec<usize> = match self.cover_textures.try_read() {
+            Ok(textures) => visible
+                .iter()
+                .copied()
+                .filter(|index| *index >= displayed_results && !textures.contains_key(index))
+                .collect(),
+            Err(_) => return,
+        };
+        self.sorted_covers_loaded_for = Some(key);
+        if !missing.is_empty() {
+            self.load_osu_covers_at(missing);
+        }
+    }
+
+    fn get_sorted_osu_results(&self) -> Vec<Beatmapset> {
+        if let Ok(osu_search_results_guard) = self.osu_search_results.try_lock() {
             let results = osu_search_results_guard.clone();
             results
         } else {
@@ -2384,27 +5500,253 @@ impl SearchApp {
         }
     }
 
-    //加載更多osu封面
-    fn load_more_osu_covers(&self, start: usize, end: usize) {
-        if let Ok(osu_search_results_guard) = self.osu_search_results.try_lock() {
-            let mut osu_covers = Vec::new();
-            for (index, beatmapset) in osu_search_results_guard
+    // 查詢曲目是否已加入使用者的喜歡清單，未登入時略過
+    async fn check_liked_statuses(
+        spotify_client: &Arc<Mutex<Option<AuthCodeSpotify>>>,
+        tracks: &mut [Track],
+    ) {
+        let spotify = match spotify_client.lock().unwrap().as_ref().cloned() {
+            Some(spotify) => spotify,
+            None => return,
+        };
+        // API 每次最多查詢 50 首
+        for chunk in tracks.chunks_mut(50) {
+            let (positions, track_ids): (Vec<usize>, Vec<TrackId>) = chunk
                 .iter()
                 .enumerate()
-                .skip(start)
-                .take(end - start)
+                .filter_map(|(position, track)| {
+                    track
+                        .external_urls
+                        .get("spotify")
+                        .and_then(|url| url.split('/').last())
+                        .and_then(|id| TrackId::from_id(id.to_string()).ok())
+                        .map(|id| (position, id))
+                })
+                .unzip();
+            if track_ids.is_empty() {
+                continue;
+            }
+            match traced(
+                "Spotify",
+                "current_user_saved_tracks_contains",
+                spotify.current_user_saved_tracks_contains(track_ids),
+            )
+            .await
             {
-                osu_covers.push((index, beatmapset.covers.clone()));
+                Ok(statuses) => {
+                    for (position, is_liked) in positions.into_iter().zip(statuses) {
+                        chunk[position].is_liked = Some(is_liked);
+                    }
+                }
+                Err(e) => {
+                    error!("無法檢查歌曲喜歡狀態: {:?}", e);
+                }
+            }
+        }
+    }
+
+    // 以 offset 向 Spotify 取得下一頁搜尋結果，依 API 順序附加到目前的列表
+    fn fetch_next_spotify_page(&self) {
+        let (query, markets, offset) = match self.spotify_search_paging.lock().unwrap().as_ref() {
+            Some(paging) if paging.has_more() => (
+                paging.query.clone(),
+                paging.markets.clone(),
+                paging.next_offset,
+            ),
+            _ => return,
+        };
+        if self.is_loading_more_spotify.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let limit = SPOTIFY_SEARCH_PAGE_SIZE.min(SPOTIFY_SEARCH_MAX_OFFSET - offset);
+        let client = self.client.clone();
+        let search_results = self.search_results.clone();
+        let spotify_search_paging = self.spotify_search_paging.clone();
+        let is_loading_more_spotify = self.is_loading_more_spotify.clone();
+        let spotify_client = self.spotify_client.clone();
+        let err_msg = self.err_msg.clone();
+        let ctx = self.ctx.clone();
+        let debug_mode = self.debug_mode;
+
+        info!("載入 Spotify 下一頁結果: {} (offset {})", query, offset);
+
+        tokio::spawn(async move {
+            let result: Result<()> = async {
+                let client = client.lock().await.clone();
+                let spotify_token = get_access_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Spotify 錯誤：無法獲取 token: {}", e))?;
+                let (tracks_with_cover, total) = search_track_in_markets(
+                    &client,
+                    &query,
+                    &spotify_token,
+                    limit,
+                    offset,
+                    &markets,
+                    debug_mode,
+                )
+                .await
+                .map_err(|e| anyhow!("Spotify 錯誤：載入下一頁失敗: {}", e))?;
+                info!("取得 Spotify 下一頁: {} 首曲目", tracks_with_cover.len());
+
+                // 翻頁期間搜尋結果可能變動，略過已載入的曲目
+                let mut new_tracks: Vec<Track> = {
+                    let loaded = search_results.lock().await;
+                    let loaded_urls: HashSet<&String> = loaded
+                        .iter()
+                        .filter_map(|track| track.external_urls.get("spotify"))
+                        .collect();
+                    tracks_with_cover
+                        .iter()
+                        .filter(|twc| {
+                            twc.external_urls
+                                .get("spotify")
+                                .map_or(true, |url| !loaded_urls.contains(url))
+                        })
+                        .map(TrackWithCover::to_track)
+                        .collect()
+                };
+                Self::check_liked_statuses(&spotify_client, &mut new_tracks).await;
+
+                // 載入期間使用者可能已開始新的搜尋
+                let still_current = match spotify_search_paging.lock().unwrap().as_mut() {
+                    Some(paging) if paging.query == query && paging.next_offset == offset => {
+                        paging.next_offset = offset + limit;
+                        // 空白頁代表 API 已沒有更多結果，即使 total 仍較大
+                        paging.total = if tracks_with_cover.is_empty() {
+                            offset
+                        } else {
+                            total
+                        };
+                        true
+                    }
+                    _ => false,
+                };
+                if still_current {
+                    search_results.lock().await.extend(new_tracks);
+                } else {
+                    info!("搜尋已變更，捨棄 Spotify 下一頁結果");
+                }
+                Ok(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                error!("載入 Spotify 下一頁時發生錯誤: {:?}", e);
+                *err_msg.lock().await = e.to_string();
+            }
+            is_loading_more_spotify.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    // 以 cursor_string 取得下一頁結果並附加到目前的列表
+    fn fetch_next_osu_page(&self) {
+        let cursor = match self.osu_search_cursor.lock().unwrap().clone() {
+            Some(cursor) => cursor,
+            None => return,
+        };
+        let query = self.osu_search_query.lock().unwrap().clone();
+        let beatmap_source = *self.osu_search_source.lock().unwrap();
+        let client = self.client.clone();
+        let osu_search_results = self.osu_search_results.clone();
+        let osu_search_query = self.osu_search_query.clone();
+        let osu_search_cursor = self.osu_search_cursor.clone();
+        let is_loading_more_osu = self.is_loading_more_osu.clone();
+        let err_msg = self.err_msg.clone();
+        let sender = self.sender.clone();
+        let cover_load_errors = self.cover_load_errors.clone();
+        let ctx = self.ctx.clone();
+        let debug_mode = self.debug_mode;
+
+        is_loading_more_osu.store(true, Ordering::SeqCst);
+        info!("載入 osu 下一頁結果: {}", query);
+
+        tokio::spawn(async move {
+            let result: Result<()> = async {
+                let client = client.lock().await.clone();
+                let page = beatmap_source
+                    .source()
+                    .search_page(&client, &query, Some(&cursor), debug_mode)
+                    .await
+                    .map_err(|e| anyhow!("Osu 錯誤：載入下一頁失敗: {}", e))?;
+
+                info!("取得 osu 下一頁: {} 個 beatmapsets", page.beatmapsets.len());
+
+                // 載入期間使用者可能已開始新的搜尋，或同一頁已由其他請求載入
+                let still_current = {
+                    let mut current_cursor = osu_search_cursor.lock().unwrap();
+                    if current_cursor.as_deref() == Some(cursor.as_str())
+                        && *osu_search_query.lock().unwrap() == query
+                    {
+                        *current_cursor = page.cursor_string;
+                        true
+                    } else {
+                        false
+                    }
+                };
+                if !still_current {
+                    info!("搜尋已變更，捨棄 osu 下一頁結果");
+                    return Ok(());
+                }
+
+                let osu_covers: Vec<_> = {
+                    let mut results = osu_search_results.lock().await;
+                    let start_index = results.len();
+                    let osu_covers = page
+                        .beatmapsets
+                        .iter()
+                        .enumerate()
+                        .take(10)
+                        .map(|(offset, beatmapset)| {
+                            (start_index + offset, beatmapset.covers.clone())
+                        })
+                        .collect();
+                    results.extend(page.beatmapsets);
+                    osu_covers
+                };
+
+                load_osu_covers(osu_covers, ctx.clone(), sender)
+                    .await
+                    .map_err(|e| {
+                        Self::record_osu_cover_errors(&cover_load_errors, &e);
+                        anyhow!("載入 osu 封面失敗: {:?}", e)
+                    })?;
+                Ok(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                error!("載入 osu 下一頁時發生錯誤: {:?}", e);
+                *err_msg.lock().await = e.to_string();
             }
+            is_loading_more_osu.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    //加載更多osu封面
+    fn load_more_osu_covers(&self, start: usize, end: usize) {
+        self.load_osu_covers_at((start..end).collect());
+    }
+
+    fn load_osu_covers_at(&self, indices: Vec<usize>) {
+        if let Ok(osu_search_results_guard) = self.osu_search_results.try_lock() {
+            let osu_covers: Vec<_> = indices
+                .iter()
+                .filter_map(|&index| {
+                    osu_search_results_guard
+                        .get(index)
+                        .map(|beatmapset| (index, beatmapset.covers.clone()))
+                })
+                .collect();
 
             // 新增：記錄本次加載的封面數量
             let loaded_covers_count = osu_covers.len();
-            info!(
-                "正在加載更多 osu 封面：從 {} 到 {}，共 {} 個",
-                start, end, loaded_covers_count
-            );
+            info!("正在加載更多 osu 封面：共 {} 個", loaded_covers_count);
 
             let sender_clone = self.sender.clone();
+            let cover_load_errors = self.cover_load_errors.clone();
             let debug_mode = self.debug_mode;
             let need_repaint = self.need_repaint.clone();
             let ctx = self.ctx.clone();
@@ -2412,6 +5754,7 @@ impl SearchApp {
             tokio::spawn(async move {
                 if let Err(e) = load_osu_covers(osu_covers, ctx.clone(), sender_clone).await {
                     error!("載入更多 osu 封面時發生錯誤: {:?}", e);
+                    Self::record_osu_cover_errors(&cover_load_errors, &e);
                     if debug_mode {
                         error!("載入更多 osu 封面錯誤: {:?}", e);
                     }
@@ -2434,10 +5777,19 @@ impl SearchApp {
 
         if response.clicked() {
             self.selected_beatmapset = Some(index);
+            record_view(ViewedItem::from(beatmapset));
         }
+        response.context_menu(|ui| self.create_beatmapset_context_menu(ui, beatmapset));
+        let best_difficulty =
+            self.osu_helper
+                .best_difficulty(&self.ctx, beatmapset, self.debug_mode);
 
         ui.allocate_ui_at_rect(response.rect, |ui| {
             ui.horizontal(|ui| {
+                if self.link_selection.show {
+                    let url = format!("https://osu.ppy.sh/beatmapsets/{}", beatmapset.id);
+                    self.link_selection.checkbox(ui, &url);
+                }
                 if !self.show_side_menu {
                     ui.vertical(|ui| {
                         let is_image_loaded = if let Ok(textures) = self.cover_textures.try_read() {
@@ -2449,6 +5801,7 @@ impl SearchApp {
                         if is_image_loaded {
                             if let Ok(textures) = self.cover_textures.try_read() {
                                 if let Some(Some((texture, size))) = textures.get(&index) {
+                                    self.mark_cover_visible(ui, &CoverKey::Osu(index));
                                     let max_height = 100.0;
                                     let aspect_ratio = size.0 / size.1;
                                     let image_size =
@@ -2459,10 +5812,20 @@ impl SearchApp {
                                     );
                                     if image_response.clicked() {
                                         self.selected_beatmapset = Some(index);
+                                        record_view(ViewedItem::from(beatmapset));
                                     }
                                 }
                             }
+                        } else if let Some(error) = self.cover_load_error(&CoverKey::Osu(index)) {
+                            if self.display_cover_error(ui, &error) {
+                                self.retry_cover_load(CoverKey::Osu(index));
+                            }
                         } else {
+                            if Self::is_cover_visible(ui, egui::vec2(100.0, 100.0))
+                                && self.evicted_osu_covers.remove(&index)
+                            {
+                                self.load_more_osu_covers(index, index + 1);
+                            }
                             ui.add_sized([100.0, 100.0], egui::Spinner::new().size(32.0));
                         }
                     });
@@ -2489,14 +5852,63 @@ impl SearchApp {
                         self.search_query = beatmapset.artist.clone();
                         self.perform_search(self.ctx.clone());
                     }
-                    ui.label(
-                        egui::RichText::new(format!("by {}", beatmapset.creator))
-                            .font(egui::FontId::proportional(self.global_font_size * 0.7)),
-                    );
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("by {}", beatmapset.creator))
+                                .font(egui::FontId::proportional(self.global_font_size * 0.7)),
+                        );
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "❤ {}  ▶ {}",
+                                beatmapset.favourite_count, beatmapset.play_count
+                            ))
+                            .small()
+                            .weak(),
+                        )
+                        .on_hover_text("收藏數 / 遊玩次數");
+                        match mapper_preference(&beatmapset.creator) {
+                            Some(MapperPreference::Favorite) => {
+                                ui.label(
+                                    egui::RichText::new("⭐ 喜愛的作者")
+                                        .small()
+                                        .color(egui::Color32::GOLD),
+                                );
+                            }
+                            Some(MapperPreference::Blocked) => {
+                                ui.label(
+                                    egui::RichText::new("🚫 封鎖的作者")
+                                        .small()
+                                        .color(ui.visuals().error_fg_color),
+                                );
+                            }
+                            None => {}
+                        }
+                    });
+                    if let Some(beatmap) = best_difficulty {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "🎯 [{}] {:.2}★",
+                                beatmap.version, beatmap.difficulty_rating
+                            ))
+                            .small()
+                            .color(egui::Color32::LIGHT_GREEN),
+                        )
+                        .on_hover_text("最符合你技術範圍的難度");
+                    }
+                    let key_counts = beatmapset.key_counts();
+                    if !key_counts.is_empty() {
+                        let keys: Vec<String> =
+                            key_counts.iter().map(|keys| format!("{}K", keys)).collect();
+                        ui.label(egui::RichText::new(format!("🎹 {}", keys.join(" / "))).small())
+                            .on_hover_text("mania 難度的鍵數");
+                    }
                 });
             });
         });
         self.draw_osu_circular_buttons(ui, beatmapset, index, response.rect.center());
+        if self.expanded_beatmapset_index == Some(index) {
+            self.render_preview_seek_bar(ui, beatmapset.id);
+        }
 
         ui.add_space(5.0);
         ui.separator();
@@ -2525,22 +5937,32 @@ impl SearchApp {
             button_size,
         );
 
-        if self.expanded_beatmapset_index == Some(index) {
-        } else {
+        let expanded = self.expanded_beatmapset_index == Some(index);
+        // 收起時容器仍會播放收合動畫，結束後才顯示展開按鈕
+        let animation_progress =
+            animate_open(ui.ctx(), egui::Id::new(("osu_buttons", index)), expanded);
+
+        if !expanded && animation_progress == 0.0 {
             // 如果當前譜面集未展開，顯示展開按鈕
-            if ui.put(expand_button_rect, egui::Button::new("▶")).clicked() {
+            let response = ui.put(expand_button_rect, egui::Button::new("▶"));
+            label_icon_button(&response, "展開譜面操作");
+            if response.clicked() {
                 self.expanded_beatmapset_index = Some(index);
+                self.focused_result = Some(ResultKind::Beatmapset);
+                record_view(ViewedItem::from(beatmapset));
+            }
+        }
+        if expanded && self.focused_result == Some(ResultKind::Beatmapset) {
+            if let Some(button) = pressed_action(ui.ctx(), ResultKind::Beatmapset) {
+                self.handle_osu_button_click(button, beatmapset, ui.ctx().clone());
             }
         }
 
-        if self.expanded_beatmapset_index == Some(index) {
-            // 計算動畫進度
-            let animation_progress = 1.0; // 暫時移除動畫，使用固定值
-
-            // 計算動畫中的容器寬度
+        if animation_progress > 0.0 {
+            // 容器從展開按鈕的位置向左展開
             let animated_width = container_width * animation_progress;
             let animated_container_rect = egui::Rect::from_min_size(
-                container_pos,
+                container_pos + egui::vec2(container_width - animated_width, 0.0),
                 egui::vec2(animated_width, container_height),
             );
 
@@ -2553,7 +5975,7 @@ impl SearchApp {
             );
 
             let total_buttons = 5; // 增加到5個按鈕
-            let spacing = animated_width / (total_buttons as f32 + 1.0);
+            let spacing = container_width / (total_buttons as f32 + 1.0);
 
             for i in 0..total_buttons {
                 let button_center =
@@ -2561,7 +5983,7 @@ impl SearchApp {
                 let rect = egui::Rect::from_center_size(button_center, button_size);
 
                 // 只有當按鈕完全顯示時才繪製和處理
-                if rect.right() <= animated_container_rect.right() {
+                if animated_container_rect.contains_rect(rect) {
                     ui.painter().circle(
                         rect.center(),
                         button_size.x / 2.0,
@@ -2571,8 +5993,14 @@ impl SearchApp {
 
                     self.draw_osu_button_icon(ui, rect, i, beatmapset);
 
+                    let label = action_label(
+                        ResultKind::Beatmapset,
+                        i,
+                        self.is_beatmap_downloaded(beatmapset.id),
+                    );
                     let response = ui.allocate_rect(rect, egui::Sense::click());
-                    if response.clicked() {
+                    label_icon_button(&response, label);
+                    if expanded && response.clicked() {
                         self.handle_osu_button_click(i, beatmapset, ui.ctx().clone());
                     }
                     if response.hovered() {
@@ -2582,21 +6010,7 @@ impl SearchApp {
                             egui::Color32::from_rgb(255, 204, 221), // 淺粉色
                             egui::Stroke::NONE,
                         );
-                        let hover_text = match i {
-                            0 => "播放預覽",
-                            1 => "在osu!中打開",
-                            2 => {
-                                if self.is_beatmap_downloaded(beatmapset.id) {
-                                    "刪除"
-                                } else {
-                                    "下載"
-                                }
-                            }
-                            3 => "以此尋找",
-                            4 => "收起",
-                            _ => "",
-                        };
-                        response.on_hover_text(hover_text);
+                        response.on_hover_text(label);
                     }
                 }
             }
@@ -2621,9 +6035,6 @@ impl SearchApp {
                 );
             }
         }
-
-        // 請求重繪以實現動畫效果
-        ui.ctx().request_repaint();
     }
 
     fn draw_osu_button_icon(
@@ -2734,7 +6145,10 @@ impl SearchApp {
         if let Some(stream_handle) = self.audio_output.as_ref().map(|(_, handle)| handle.clone()) {
             let beatmapset_id = beatmapset.id;
             let volume = self.global_volume;
+            let speed = self.preview_speed;
+            let eq = self.preview_eq;
             let current_previews = self.current_previews.clone();
+            let preview_buffers = self.preview_buffers.clone();
             let is_playing = self.is_beatmap_playing;
 
             tokio::spawn(async move {
@@ -2745,8 +6159,9 @@ impl SearchApp {
                     }
                 } else {
                     // 如果沒有播放，則開始播放
-                    match preview_beatmap(beatmapset_id, &stream_handle, volume).await {
-                        Ok(sink) => {
+                    match preview_beatmap(beatmapset_id, &stream_handle, volume, speed, eq).await {
+                        Ok((sink, buffer)) => {
+                            preview_buffers.lock().await.insert(beatmapset_id, buffer);
                             let mut previews = current_previews.lock().await;
                             if let Some(old_sink) = previews.insert(beatmapset_id, sink) {
                                 old_sink.stop();
@@ -2775,42 +6190,91 @@ impl SearchApp {
         let beatmapset_id = beatmapset.id;
         if self.is_beatmap_downloaded(beatmapset_id) {
             // 如果已下載,則刪除
-            match delete_beatmap(&self.download_directory, beatmapset_id) {
-                Ok(_) => {
-                    info!("成功刪除譜面 {}", beatmapset_id);
-                    self.beatmapset_download_statuses
-                        .lock()
-                        .unwrap()
-                        .insert(beatmapset_id, DownloadStatus::NotStarted);
-                }
-                Err(e) => {
-                    error!("無法刪除譜面 {}: {:?}", beatmapset_id, e);
-                }
-            }
+            self.request_confirmation(
+                ConfirmRequest::new(
+                    "delete_beatmapset",
+                    "刪除譜面",
+                    format!(
+                        "要刪除已下載的 {} - {} 嗎？",
+                        beatmapset.artist, beatmapset.title
+                    ),
+                    ConfirmAction::DeleteBeatmapset(beatmapset_id),
+                )
+                .confirm_label("刪除"),
+            );
         } else {
             // 如果未下載,則開始下載
-            info!("將譜面 {} 加入下載隊列", beatmapset_id);
-            let current_downloads = self.current_downloads.load(Ordering::SeqCst);
-            if current_downloads < 3 {
-                self.beatmapset_download_statuses
-                    .lock()
-                    .unwrap()
-                    .insert(beatmapset_id, DownloadStatus::Downloading);
-            } else {
-                self.beatmapset_download_statuses
-                    .lock()
-                    .unwrap()
-                    .insert(beatmapset_id, DownloadStatus::Waiting);
+            self.enqueue_beatmap_download(beatmapset_id);
+        }
+        ctx.request_repaint();
+    }
+
+    // 將譜面集加入下載隊列，磁碟空間不足時先暫停並在下載管理中顯示，回傳是否已加入隊列
+    fn enqueue_beatmap_download(&mut self, beatmapset_id: i32) -> bool {
+        if let Some(available_mb) = low_disk_space(&self.download_directory) {
+            warn!(
+                "磁碟剩餘空間 {} MB 低於下限，暫停下載譜面 {}",
+                available_mb, beatmapset_id
+            );
+            if self.blocked_downloads.is_empty() {
+                self.show_downloaded_maps = false;
+                self.show_download_manager = true;
+                self.show_side_menu = true;
             }
-            if let Err(e) = self.download_queue_sender.try_send(beatmapset_id) {
+            if !self.blocked_downloads.contains(&beatmapset_id) {
+                self.blocked_downloads.push(beatmapset_id);
+            }
+            return false;
+        }
+        self.send_to_download_queue(beatmapset_id)
+    }
+
+    // 一次下載多個譜面，有譜面沒有加入隊列時提示實際加入的數量
+    fn enqueue_beatmap_downloads(&mut self, source: &str, beatmapset_ids: Vec<i32>) {
+        let total = beatmapset_ids.len();
+        let queued = beatmapset_ids
+            .into_iter()
+            .filter(|&beatmapset_id| self.enqueue_beatmap_download(beatmapset_id))
+            .count();
+        info!("{}：已將 {} / {} 個譜面加入下載隊列", source, queued, total);
+        if queued < total {
+            self.request_confirmation(ConfirmRequest::notice(
+                "download_queue_report",
+                source,
+                format!(
+                    "已將 {} / {} 個譜面加入下載隊列，其餘譜面因磁碟空間不足暫停或無法加入隊列，請查看下載管理",
+                    queued, total
+                ),
+            ));
+        }
+    }
+
+    // 將譜面集加入下載隊列並更新下載狀態
+    fn send_to_download_queue(&mut self, beatmapset_id: i32) -> bool {
+        info!("將譜面 {} 加入下載隊列", beatmapset_id);
+        let current_downloads = self.current_downloads.load(Ordering::SeqCst);
+        if current_downloads < 3 && pause_reason().is_none() {
+            self.beatmapset_download_statuses
+                .lock()
+                .unwrap()
+                .insert(beatmapset_id, DownloadStatus::Downloading);
+        } else {
+            self.beatmapset_download_statuses
+                .lock()
+                .unwrap()
+                .insert(beatmapset_id, DownloadStatus::Waiting);
+        }
+        match self.download_queue_sender.send(beatmapset_id) {
+            Ok(()) => true,
+            Err(e) => {
                 error!("無法將譜面加入下載隊列: {:?}", e);
                 self.beatmapset_download_statuses
                     .lock()
                     .unwrap()
                     .insert(beatmapset_id, DownloadStatus::NotStarted);
+                false
             }
         }
-        ctx.request_repaint();
     }
 
     fn is_beatmap_downloaded(&self, beatmapset_id: i32) -> bool {
@@ -2830,6 +6294,26 @@ impl SearchApp {
         }
     }
 
+    // 與 get_download_status 相同，但已下載的判斷使用每一幀更新一次的快取
+    fn cached_download_status(&self, beatmapset_id: i32) -> DownloadStatus {
+        if self.downloaded_ids.contains(beatmapset_id) {
+            return DownloadStatus::Completed;
+        }
+        self.beatmapset_download_statuses
+            .lock()
+            .unwrap()
+            .get(&beatmapset_id)
+            .cloned()
+            .unwrap_or(DownloadStatus::NotStarted)
+    }
+
+    fn cached_download_statuses(&self, beatmapset_ids: Vec<i32>) -> HashMap<i32, DownloadStatus> {
+        beatmapset_ids
+            .into_iter()
+            .map(|beatmapset_id| (beatmapset_id, self.cached_download_status(beatmapset_id)))
+            .collect()
+    }
+
     fn start_download_processor(&self) {
         let download_queue_receiver = self.download_queue_receiver.clone();
         let download_directory = self.download_directory.clone();
@@ -2849,6 +6333,8 @@ impl SearchApp {
             };
 
             while let Some(beatmapset_id) = receiver.recv().await {
+                // 省流模式或連線變慢時，尚未開始的下載在此等待
+                wait_until_resumed().await;
                 let permit = match semaphore.clone().acquire_owned().await {
                     Ok(p) => p,
                     Err(e) => {
@@ -2972,13 +6458,32 @@ impl SearchApp {
                 .font(egui::FontId::proportional(self.global_font_size * 0.9)),
         );
         ui.add_space(10.0);
+        self.display_detail_cover(ui, beatmapset);
+        ui.add_space(10.0);
+        egui::CollapsingHeader::new("譜面說明")
+            .default_open(true)
+            .show(ui, |ui| {
+                self.display_detail_description(ui, beatmapset.id);
+            });
 
-        for beatmap_info in beatmap_info.beatmaps {
+        let best_difficulty_id = self
+            .osu_helper
+            .best_difficulty(&self.ctx, beatmapset, self.debug_mode)
+            .map(|beatmap| beatmap.id);
+        // format_info 的難度順序與 beatmaps 相同
+        for (beatmap, beatmap_info) in beatmapset.beatmaps.iter().zip(beatmap_info.beatmaps) {
             ui.add_space(10.0);
-            ui.label(
-                egui::RichText::new(beatmap_info)
-                    .font(egui::FontId::proportional(self.global_font_size * 1.0)),
-            );
+            let text = egui::RichText::new(beatmap_info)
+                .font(egui::FontId::proportional(self.global_font_size * 1.0));
+            if best_difficulty_id == Some(beatmap.id) {
+                ui.label(
+                    egui::RichText::new("🎯 最符合你技術範圍的難度")
+                        .color(egui::Color32::LIGHT_GREEN),
+                );
+                ui.label(text.color(egui::Color32::LIGHT_GREEN));
+            } else {
+                ui.label(text);
+            }
             ui.add_space(10.0);
             ui.separator();
         }
@@ -2993,53 +6498,187 @@ impl SearchApp {
             .clicked()
         {
             self.selected_beatmapset = None;
+            *self.detail_cover.lock().unwrap() = None;
+            *self.detail_description.lock().unwrap() = None;
         }
     }
 
-    //清除封面紋理
-    fn clear_cover_textures(&self) {
-        if let Ok(mut textures) = self.cover_textures.try_write() {
-            textures.clear();
+    // 進入詳細資訊頁時才向 osu! API 讀取說明，搜尋結果不包含說明
+    fn display_detail_description(&self, ui: &mut egui::Ui, beatmapset_id: i32) {
+        let mut detail_description = self.detail_description.lock().unwrap();
+        let is_current = matches!(&*detail_description, Some((id, _)) if *id == beatmapset_id);
+        if !is_current {
+            *detail_description = Some((beatmapset_id, None));
+            let detail_description = self.detail_description.clone();
+            let client = self.client.clone();
+            let ctx = ui.ctx().clone();
+            let debug_mode = self.debug_mode;
+            tokio::spawn(async move {
+                let result: Result<BbcodeDocument> = async {
+                    let client = client.lock().await.clone();
+                    let osu_token = get_osu_token(&client, debug_mode)
+                        .await
+                        .map_err(|e| anyhow!("Osu 錯誤：無法獲取 token: {}", e))?;
+                    let description =
+                        get_beatmapset_description(&client, &osu_token, beatmapset_id, debug_mode)
+                            .await
+                            .map_err(|e| anyhow!("Osu 錯誤：讀取譜面說明失敗: {}", e))?;
+                    Ok(match description.bbcode {
+                        Some(bbcode) => BbcodeDocument::parse(&bbcode),
+                        None => BbcodeDocument::from_html(&description.description),
+                    })
+                }
+                .await;
+
+                if let Err(e) = &result {
+                    error!("讀取譜面集 {} 的說明失敗: {:?}", beatmapset_id, e);
+                }
+                let mut detail_description = detail_description.lock().unwrap();
+                // 載入期間可能已切換到其他譜面集
+                if matches!(&*detail_description, Some((id, _)) if *id == beatmapset_id) {
+                    *detail_description =
+                        Some((beatmapset_id, Some(result.map_err(|e| e.to_string()))));
+                }
+                ctx.request_repaint();
+            });
         }
-    }
 
-    //加載默認頭像
-    fn load_default_avatar(&mut self) {
-        let default_avatar_bytes = include_bytes!("assets/login.png");
-        let default_avatar_image = image::load_from_memory(default_avatar_bytes).unwrap();
-        let default_avatar_size = [
-            default_avatar_image.width() as _,
-            default_avatar_image.height() as _,
-        ];
-        let default_avatar_pixels = default_avatar_image.to_rgba8();
-        self.default_avatar_texture = Some(self.ctx.load_texture(
-            "default_avatar",
-            egui::ColorImage::from_rgba_unmultiplied(
-                default_avatar_size,
-                default_avatar_pixels.as_flat_samples().as_slice(),
-            ),
-            egui::TextureOptions::default(),
-        ));
+        match &*detail_description {
+            Some((_, Some(Ok(document)))) if document.is_empty() => {
+                ui.label(egui::RichText::new("沒有說明").weak());
+            }
+            Some((_, Some(Ok(document)))) => document.render(ui, self.global_font_size * 0.9),
+            Some((_, Some(Err(e)))) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            _ => {
+                ui.spinner();
+            }
+        }
     }
-    //渲染頂部面板
-    fn render_top_panel(&mut self, ui: &mut egui::Ui) {
-        ui.horizontal(|ui| {
-            ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                // 渲染側邊選單按鈕
-                let (rect, response) = ui.allocate_exact_size(
+
+    // 只有詳細資訊頁會載入原尺寸的封面
+    fn display_detail_cover(&self, ui: &mut egui::Ui, beatmapset: &Beatmapset) {
+        let cover_url = match beatmapset
+            .covers
+            .cover_2x
+            .clone()
+            .or_else(|| beatmapset.covers.cover.clone())
+        {
+            Some(url) => url,
+            None => return,
+        };
+
+        let mut detail_cover = self.detail_cover.lock().unwrap();
+        let is_current = matches!(&*detail_cover, Some((id, _)) if *id == beatmapset.id);
+        if !is_current {
+            *detail_cover = Some((beatmapset.id, None));
+            let detail_cover = self.detail_cover.clone();
+            let ctx = ui.ctx().clone();
+            let beatmapset_id = beatmapset.id;
+            tokio::spawn(async move {
+                match Self::load_texture_async(&ctx, &cover_url, Duration::from_secs(30), None)
+                    .await
+                {
+                    Ok(texture) => {
+                        let mut detail_cover = detail_cover.lock().unwrap();
+                        // 載入期間可能已切換到其他譜面集
+                        if matches!(&*detail_cover, Some((id, _)) if *id == beatmapset_id) {
+                            *detail_cover = Some((beatmapset_id, Some(texture)));
+                        }
+                    }
+                    Err(e) => error!("載入原尺寸封面失敗: {:?}", e),
+                }
+                ctx.request_repaint();
+            });
+        }
+
+        match &*detail_cover {
+            Some((_, Some(texture))) => {
+                let [width, height] = texture.size();
+                let display_width = ui.available_width().min(width as f32);
+                let display_height = display_width * height as f32 / width as f32;
+                ui.add(egui::Image::new((
+                    texture.id(),
+                    egui::vec2(display_width, display_height),
+                )));
+            }
+            _ => {
+                ui.spinner();
+            }
+        }
+    }
+
+    //清除封面紋理
+    fn clear_cover_textures(&mut self) {
+        self.evicted_osu_covers.clear();
+        if let Ok(mut textures) = self.cover_textures.try_write() {
+            textures.clear();
+            self.texture_budget
+                .lock()
+                .unwrap()
+                .retain(|key| !matches!(key, CoverKey::Osu(_)));
+        }
+        self.cover_load_errors
+            .lock()
+            .unwrap()
+            .retain(|key, _| !matches!(key, CoverKey::Osu(_)));
+    }
+
+    // 封面紋理超過記憶體上限時，釋放最久沒顯示的封面，之後捲回來會重新載入
+    fn evict_textures(&mut self) {
+        let limit_bytes = texture_budget_options().budget_mb as usize * 1024 * 1024;
+        let (mut covers, mut cache) = match (
+            self.cover_textures.try_write(),
+            self.texture_cache.try_write(),
+        ) {
+            (Ok(covers), Ok(cache)) => (covers, cache),
+            _ => return,
+        };
+        for key in self.texture_budget.lock().unwrap().evict(limit_bytes) {
+            match key {
+                CoverKey::Osu(index) => {
+                    covers.remove(&index);
+                    self.evicted_osu_covers.insert(index);
+                }
+                CoverKey::Spotify(url) => {
+                    cache.remove(&url);
+                }
+            }
+        }
+    }
+
+    //加載默認頭像
+    fn load_default_avatar(&mut self) {
+        let default_avatar_bytes = include_bytes!("assets/login.png");
+        let default_avatar_image = image::load_from_memory(default_avatar_bytes).unwrap();
+        let default_avatar_size = [
+            default_avatar_image.width() as _,
+            default_avatar_image.height() as _,
+        ];
+        let default_avatar_pixels = default_avatar_image.to_rgba8();
+        self.default_avatar_texture = Some(self.ctx.load_texture(
+            "default_avatar",
+            egui::ColorImage::from_rgba_unmultiplied(
+                default_avatar_size,
+                default_avatar_pixels.as_flat_samples().as_slice(),
+            ),
+            egui::TextureOptions::default(),
+        ));
+    }
+    //渲染頂部面板
+    fn render_top_panel(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                // 渲染側邊選單按鈕
+                let (rect, response) = ui.allocate_exact_size(
                     egui::vec2(BUTTON_SIZE, BUTTON_SIZE),
                     egui::Sense::click(),
                 );
                 if ui.is_rect_visible(rect) {
                     let visuals = ui.style().interact(&response);
                     let animation_progress = self.side_menu_animation.entry(ui.id()).or_insert(0.0);
-                    *animation_progress = if response.hovered() {
-                        (*animation_progress + ui.input(|i| i.unstable_dt) * ANIMATION_SPEED)
-                            .min(1.0)
-                    } else {
-                        (*animation_progress - ui.input(|i| i.unstable_dt) * ANIMATION_SPEED)
-                            .max(0.0)
-                    };
+                    *animation_progress = step_hover(ui, *animation_progress, response.hovered());
                     let color = egui::Color32::from_rgba_unmultiplied(
                         255,
                         255,
@@ -3112,6 +6751,7 @@ impl SearchApp {
                             } else {
                                 self.render_guest_user(ui);
                             }
+                            render_rate_limit_indicator(ui);
                         });
                     },
                 );
@@ -3148,6 +6788,22 @@ impl SearchApp {
     fn render_side_menu_content(&mut self, ui: &mut egui::Ui) {
         if self.show_downloaded_maps {
             self.render_downloaded_maps_list(ui);
+        } else if self.show_download_manager {
+            self.render_download_manager(ui);
+        } else if self.show_review_queue {
+            self.render_review_queue(ui);
+        } else if self.show_recently_viewed {
+            self.render_recently_viewed(ui);
+        } else if self.lastfm.show {
+            self.render_lastfm_page(ui);
+        } else if self.release_radar.show {
+            self.render_release_radar_page(ui);
+        } else if self.followed_artists.show {
+            self.render_followed_artists_page(ui);
+        } else if self.scripts.show {
+            self.render_scripts_page(ui);
+        } else if self.osu_favourites.show {
+            self.render_osu_favourites_page(ui);
         } else if self.show_liked_tracks || self.selected_playlist.is_some() {
             self.render_playlist_content(ui);
         } else if self.show_playlists {
@@ -3166,14 +6822,7 @@ impl SearchApp {
                 if ui.is_rect_visible(rect) {
                     let visuals = ui.style().interact(&response);
                     let animation_progress = self.side_menu_animation.entry(ui.id()).or_insert(0.0);
-
-                    if response.hovered() {
-                        *animation_progress =
-                            (*animation_progress + ui.input(|i| i.unstable_dt) * 4.0).min(1.0);
-                    } else {
-                        *animation_progress =
-                            (*animation_progress - ui.input(|i| i.unstable_dt) * 4.0).max(0.0);
-                    }
+                    *animation_progress = step_hover(ui, *animation_progress, response.hovered());
 
                     let color = egui::Color32::from_rgba_unmultiplied(
                         255,
@@ -3218,6 +6867,7 @@ impl SearchApp {
                     info!("點擊了: Spotify 搜尋");
                     self.show_side_menu = false;
                     self.osu_helper.show = false;
+                    self.batch_import.show = false;
                 }
                 if self
                     .create_auth_button(ui, "Playlists", "spotify_icon_black.png")
@@ -3228,6 +6878,50 @@ impl SearchApp {
                     self.load_user_playlists();
                     self.osu_helper.show = false;
                 }
+                self.render_pinned_playlists_strip(ui);
+                if self
+                    .create_auth_button(ui, "匯入歌單檔", "spotify_icon_black.png")
+                    .clicked()
+                {
+                    info!("點擊了: 匯入歌單檔");
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("歌單檔", &["txt", "csv"])
+                        .pick_file()
+                    {
+                        self.batch_import
+                            .start(&path, ui.ctx().clone(), self.debug_mode);
+                        self.batch_import.show = true;
+                        self.osu_helper.show = false;
+                        self.show_side_menu = false;
+                    }
+                }
+                if self
+                    .create_auth_button(ui, "新發行", "spotify_icon_black.png")
+                    .clicked()
+                {
+                    info!("點擊了: 新發行");
+                    let spotify = self.spotify_client.lock().unwrap().clone();
+                    self.release_radar.open(ui.ctx().clone(), spotify);
+                }
+                if self
+                    .create_auth_button(ui, "追蹤的歌手", "spotify_icon_black.png")
+                    .clicked()
+                {
+                    info!("點擊了: 追蹤的歌手");
+                    let spotify = self.spotify_client.lock().unwrap().clone();
+                    self.followed_artists.open(ui.ctx().clone(), spotify);
+                }
+                let builder_label = format!("從譜面建立歌單 ({})", self.playlist_builder.len());
+                if self
+                    .create_auth_button(ui, &builder_label, "spotify_icon_black.png")
+                    .clicked()
+                {
+                    info!("點擊了: 從譜面建立歌單");
+                    self.playlist_builder.show = true;
+                    self.batch_import.show = false;
+                    self.osu_helper.show = false;
+                    self.show_side_menu = false;
+                }
             });
 
         // Osu 折疊式視窗
@@ -3241,6 +6935,7 @@ impl SearchApp {
                 {
                     info!("點擊了: Osu Helper");
                     self.osu_helper.show = true;
+                    self.batch_import.show = false;
                     self.show_side_menu = false;
                 }
 
@@ -3252,6 +6947,67 @@ impl SearchApp {
                     info!("點擊了: 已下載圖譜");
                     self.show_downloaded_maps = true;
                 }
+
+                ui.add_space(5.0);
+                if self
+                    .create_auth_button(ui, "最愛 / 常玩譜面", "osu!logo.png")
+                    .clicked()
+                {
+                    info!("點擊了: 最愛 / 常玩譜面");
+                    let username = self.osu_helper.username().to_string();
+                    self.osu_favourites.open(&username);
+                }
+
+                ui.add_space(5.0);
+                if self
+                    .create_auth_button(ui, "下載管理", "osu!logo.png")
+                    .clicked()
+                {
+                    info!("點擊了: 下載管理");
+                    self.show_download_manager = true;
+                }
+
+                ui.add_space(5.0);
+                let review_label = format!("需要確認 ({})", review_count());
+                if self
+                    .create_auth_button(ui, &review_label, "osu!logo.png")
+                    .clicked()
+                {
+                    info!("點擊了: 需要確認");
+                    self.show_review_queue = true;
+                }
+
+                ui.add_space(5.0);
+                if self
+                    .create_auth_button(ui, "最近瀏覽", "osu!logo.png")
+                    .clicked()
+                {
+                    info!("點擊了: 最近瀏覽");
+                    self.show_recently_viewed = true;
+                }
+
+                ui.add_space(5.0);
+                if self
+                    .create_auth_button(ui, "腳本", "osu!logo.png")
+                    .clicked()
+                {
+                    info!("點擊了: 腳本");
+                    self.scripts.open();
+                }
+            });
+
+        // Last.fm 折疊式視窗
+        egui::CollapsingHeader::new(egui::RichText::new("📻 Last.fm").size(20.0))
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.add_space(5.0);
+                if self
+                    .create_auth_button(ui, "播放紀錄", "search.png")
+                    .clicked()
+                {
+                    info!("點擊了: Last.fm 播放紀錄");
+                    self.lastfm.open(ui.ctx().clone(), self.debug_mode);
+                }
             });
 
         // Settings 折疊式視窗
@@ -3285,13 +7041,70 @@ impl SearchApp {
                 // 音量控制
                 ui.horizontal(|ui| {
                     ui.label("音量:");
-                    if ui
+                    let response = ui
                         .add(egui::Slider::new(&mut self.global_volume, 0.01..=1.0))
-                        .changed()
-                    {
+                        .on_hover_text("可用滑鼠滾輪調整");
+                    if response.changed() || self.handle_volume_scroll(ui, &response) {
                         self.update_all_sinks_volume();
                     }
                 });
+                ui.horizontal(|ui| {
+                    let mut options = media_key_options();
+                    let mut changed = ui
+                        .checkbox(&mut options.enabled, "媒體鍵控制預覽")
+                        .on_hover_text("啟用後其他程式將收不到播放 / 暫停等媒體鍵")
+                        .changed();
+                    changed |= ui
+                        .add_enabled(
+                            options.enabled,
+                            egui::Checkbox::new(&mut options.control_spotify, "同時控制 Spotify"),
+                        )
+                        .on_hover_text("沒有預覽播放時控制 Spotify 的播放裝置，需要 Premium")
+                        .changed();
+                    if changed {
+                        // 先釋放舊的監聽，避免覆蓋新註冊的事件處理
+                        self.media_keys = None;
+                        if options.enabled {
+                            self.media_keys = MediaKeyListener::start(ui.ctx());
+                        }
+                        set_media_key_options(options);
+                    }
+                });
+
+                // 預覽播放速度與等化器
+                ui.horizontal(|ui| {
+                    ui.label("預覽速度:");
+                    let mut speed_changed = ui
+                        .add(
+                            egui::Slider::new(
+                                &mut self.preview_speed,
+                                MIN_PREVIEW_SPEED..=MAX_PREVIEW_SPEED,
+                            )
+                            .step_by(0.05)
+                            .suffix("x"),
+                        )
+                        .changed();
+                    for (label, speed) in SPEED_PRESETS {
+                        if ui
+                            .selectable_label(self.preview_speed == speed, label)
+                            .clicked()
+                        {
+                            self.preview_speed = speed;
+                            speed_changed = true;
+                        }
+                    }
+                    if speed_changed {
+                        self.update_all_sinks_speed();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("預覽等化器:");
+                    for preset in EqPreset::ALL {
+                        ui.selectable_value(&mut self.preview_eq, preset, preset.label());
+                    }
+                })
+                .response
+                .on_hover_text("等化器在下次播放預覽時套用");
 
                 ui.add_space(10.0);
 
@@ -3309,16 +7122,33 @@ impl SearchApp {
                 // 下載目錄設置
                 ui.horizontal(|ui| {
                     ui.label("圖譜下載目錄:");
-                    if ui.button("更改").clicked() {
+                    let migrating = self.migration_progress.counts().is_some();
+                    if ui
+                        .add_enabled(!migrating, egui::Button::new("更改"))
+                        .clicked()
+                    {
                         if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                            self.download_directory = path;
+                            let previous = std::mem::replace(&mut self.download_directory, path);
                             if let Err(e) = save_download_directory(&self.download_directory) {
                                 error!("保存下載目錄失敗: {:?}", e);
                             }
                             info!("下載目錄已更改為: {:?}", self.download_directory);
+                            self.restart_watch_folder();
+                            self.confirm_migrate_downloads(previous);
                         }
                     }
                 });
+                ui.checkbox(
+                    &mut self.migrate_by_copy,
+                    "更換目錄時複製圖譜，保留舊目錄的檔案",
+                );
+                if let Some((done, total)) = self.migration_progress.counts() {
+                    ui.add(
+                        egui::ProgressBar::new(done as f32 / total as f32)
+                            .text(format!("正在轉移圖譜 {} / {}", done, total)),
+                    );
+                    ui.ctx().request_repaint();
+                }
                 ui.add_space(5.0);
                 ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
                     let path_str = self.download_directory.to_string_lossy().to_string();
@@ -3351,69 +7181,1664 @@ impl SearchApp {
                         lines.push(current_line);
                     }
 
-                    for line in lines {
-                        ui.label(line);
+                    for line in lines {
+                        ui.label(line);
+                    }
+                });
+
+                ui.add_space(10.0);
+
+                // 自定義背景設置
+                ui.horizontal(|ui| {
+                    ui.label("背景圖片:");
+                    if ui.button("選擇背景").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("圖片", &["png", "jpg", "jpeg"])
+                            .pick_file()
+                        {
+                            self.custom_background_path = Some(path.clone());
+                            if let Err(e) = self.load_custom_background(ui.ctx()) {
+                                error!("加載背景失敗: {:?}", e);
+                                self.custom_background_path = None;
+
+                                // 顯示錯誤視窗
+                                let error_window = egui::Window::new("錯誤")
+                                    .collapsible(false)
+                                    .resizable(false);
+                                error_window.show(ui.ctx(), |ui| {
+                                    ui.label("無法讀取自定義背景,已恢復使用預設背景。");
+                                    if ui.button("確認").clicked() {
+                                        ui.close_menu();
+                                    }
+                                });
+                            } else {
+                                info!("自定義背景已設置: {:?}", path);
+                                if let Err(e) = save_background_path(&self.custom_background_path) {
+                                    error!("保存背景位置失敗: {:?}", e);
+                                }
+                            }
+                        }
+                    }
+                    if ui.button("恢復預設背景").clicked() {
+                        self.custom_background_path = None;
+                        self.custom_background = None;
+                        if let Err(e) = save_background_path(&None) {
+                            error!("保存背景位置失敗: {:?}", e);
+                        }
+                        info!("已恢復使用預設背景");
+                    }
+                });
+                if let Some(path) = &self.custom_background_path {
+                    ui.label(format!("當前背景: {}", path.to_string_lossy()));
+                } else {
+                    ui.label("當前使用預設背景");
+                }
+
+                ui.add_space(10.0);
+
+                // 譜面搜尋來源
+                ui.horizontal(|ui| {
+                    ui.label("譜面來源:");
+                    let previous_source = self.beatmap_source;
+                    egui::ComboBox::from_id_source("beatmap_source")
+                        .selected_text(self.beatmap_source.label())
+                        .show_ui(ui, |ui| {
+                            for kind in BeatmapSourceKind::ALL {
+                                ui.selectable_value(&mut self.beatmap_source, kind, kind.label());
+                            }
+                        });
+                    if self.beatmap_source != previous_source {
+                        self.beatmap_source.save();
+                    }
+                });
+
+                // osu! 反向搜尋查詢清理
+                let mut query_opts = query_options();
+                let mut query_opts_changed = ui
+                    .checkbox(&mut query_opts.romanize_kana, "搜尋時將假名轉為羅馬拼音")
+                    .changed();
+
+                // Spotify 關鍵字搜尋的地區
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Spotify 搜尋地區:")
+                        .on_hover_text("部分曲目只在特定地區上架，未選擇時不指定地區");
+                    query_opts_changed |= ui
+                        .checkbox(&mut query_opts.search_all_markets, "搜尋所有地區")
+                        .changed();
+                    ui.add_enabled_ui(!query_opts.search_all_markets, |ui| {
+                        for (code, name) in SEARCH_MARKETS {
+                            let mut selected = query_opts.search_markets.iter().any(|m| m == code);
+                            if ui.checkbox(&mut selected, name).changed() {
+                                query_opts.search_markets.retain(|m| m != code);
+                                if selected {
+                                    query_opts.search_markets.push(code.to_string());
+                                }
+                                query_opts_changed = true;
+                            }
+                        }
+                    });
+                });
+                if query_opts_changed {
+                    set_query_options(query_opts);
+                }
+
+                // 完整版與 TV Size 譜面的排序偏好
+                let mut match_opts = match_options();
+                ui.horizontal(|ui| {
+                    ui.label("譜面版本:");
+                    let mut changed = false;
+                    for preference in VERSION_PREFERENCES {
+                        changed |= ui
+                            .radio_value(
+                                &mut match_opts.version_preference,
+                                preference,
+                                version_preference_label(preference),
+                            )
+                            .changed();
+                    }
+                    if changed {
+                        set_match_options(match_opts);
+                    }
+                })
+                .response
+                .on_hover_text("依標題（TV Size、Short Ver.、Cut Ver.）與長度判斷剪輯版本");
+
+                // 自動下載與同步模式的信心門檻
+                let mut match_opts = match_options();
+                ui.horizontal(|ui| {
+                    ui.label("自動下載門檻:");
+                    let mut percent = match_opts.auto_min_score * 100.0;
+                    if ui
+                        .add(
+                            egui::Slider::new(
+                                &mut percent,
+                                AUTO_MIN_SCORE_RANGE.0 * 100.0..=AUTO_MIN_SCORE_RANGE.1 * 100.0,
+                            )
+                            .step_by(1.0)
+                            .suffix("%"),
+                        )
+                        .on_hover_text("低於門檻的配對不會自動下載，改為加入「需要確認」")
+                        .changed()
+                    {
+                        match_opts.auto_min_score = percent / 100.0;
+                        set_match_options(match_opts);
+                    }
+                });
+
+                // 偏好的譜面曲風與語言
+                let mut match_opts = match_options();
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label("偏好曲風:");
+                    changed |= Self::render_map_tag_combo(
+                        ui,
+                        "preferred_genre",
+                        &mut match_opts.preferred_genre,
+                        &MAP_GENRES,
+                    );
+                    ui.label("偏好語言:");
+                    changed |= Self::render_map_tag_combo(
+                        ui,
+                        "preferred_language",
+                        &mut match_opts.preferred_language,
+                        &MAP_LANGUAGES,
+                    );
+                })
+                .response
+                .on_hover_text("符合偏好的譜面匹配分數較高，排序時優先");
+                changed |= ui
+                    .checkbox(
+                        &mut match_opts.filter_search,
+                        "搜尋 osu! 時只顯示偏好的曲風與語言",
+                    )
+                    .on_hover_text("只適用於 osu! 官方搜尋來源")
+                    .changed();
+                if changed {
+                    set_match_options(match_opts);
+                }
+
+                ui.add_space(10.0);
+
+                // 下載檔名範本
+                ui.horizontal(|ui| {
+                    ui.label("下載檔名範本:");
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.filename_template_input)
+                            .hint_text(DEFAULT_FILENAME_TEMPLATE)
+                            .desired_width(250.0),
+                    );
+                    if response.lost_focus() {
+                        let template = self.filename_template_input.trim();
+                        let template = if template.is_empty() {
+                            DEFAULT_FILENAME_TEMPLATE
+                        } else {
+                            template
+                        };
+                        self.filename_template_input = template.to_string();
+                        set_download_options(DownloadOptions {
+                            filename_template: template.to_string(),
+                            ..download_options()
+                        });
+                    }
+                    if ui.button("重設").clicked() {
+                        self.filename_template_input = DEFAULT_FILENAME_TEMPLATE.to_string();
+                        set_download_options(DownloadOptions {
+                            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+                            ..download_options()
+                        });
+                    }
+                });
+                let preview = format_filename(
+                    &self.filename_template_input,
+                    123456,
+                    &BeatmapsetNames {
+                        artist: "Artist".to_string(),
+                        title: "Title".to_string(),
+                    },
+                );
+                ui.label(
+                    egui::RichText::new(format!(
+                        "可使用 {{id}}、{{artist}}、{{title}}，預覽: {}",
+                        preview
+                    ))
+                    .small()
+                    .weak(),
+                );
+
+                // 批次完成通知
+                let mut notify_opts = notify_options();
+                ui.horizontal(|ui| {
+                    if ui
+                        .checkbox(
+                            &mut notify_opts.enabled,
+                            "下載 / 同步完成時發送 Webhook 通知",
+                        )
+                        .changed()
+                    {
+                        set_notify_options(notify_opts.clone());
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Webhook 網址:");
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.webhook_url_input)
+                            .hint_text("https://discord.com/api/webhooks/...")
+                            .desired_width(250.0),
+                    );
+                    if response.lost_focus() {
+                        self.webhook_url_input = self.webhook_url_input.trim().to_string();
+                        set_notify_options(NotifyOptions {
+                            webhook_url: self.webhook_url_input.clone(),
+                            ..notify_options()
+                        });
+                    }
+                    if ui
+                        .add_enabled(
+                            !self.webhook_url_input.trim().is_empty(),
+                            egui::Button::new("測試"),
+                        )
+                        .clicked()
+                    {
+                        let webhook_url = self.webhook_url_input.trim().to_string();
+                        tokio::spawn(async move {
+                            let report = BatchReport {
+                                title: "測試通知".to_string(),
+                                succeeded: 1,
+                                failures: Vec::new(),
+                                fields: Vec::new(),
+                            };
+                            if let Err(e) =
+                                send_batch_report(&Client::new(), &webhook_url, &report).await
+                            {
+                                error!("測試通知發送失敗: {:?}", e);
+                            }
+                        });
+                    }
+                });
+
+                // 下載前檢查的磁碟剩餘空間下限
+                let mut download_opts = download_options();
+                ui.horizontal(|ui| {
+                    ui.label("下載前保留的磁碟空間 (MB):");
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut download_opts.min_free_space_mb)
+                                .clamp_range(0..=100_000)
+                                .speed(10),
+                        )
+                        .changed()
+                    {
+                        set_download_options(download_opts);
+                    }
+                });
+
+                ui.add_space(10.0);
+
+                // 省流模式與自動暫停
+                egui::CollapsingHeader::new("省流模式")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.render_auto_pause_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 監看資料夾
+                egui::CollapsingHeader::new("監看資料夾")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.render_watch_folder_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 匯入譜面的 osu! 用戶端
+                egui::CollapsingHeader::new("osu! 用戶端")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        Self::render_osu_client_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 快取管理
+                egui::CollapsingHeader::new("快取管理")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.render_cache_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 喜愛與封鎖的譜面作者
+                egui::CollapsingHeader::new("譜面作者")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.render_mapper_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 已刪除的圖譜
+                egui::CollapsingHeader::new("資源回收筒")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.render_trash_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 已選擇不要再詢問的確認對話框
+                egui::CollapsingHeader::new("確認對話框")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.render_confirm_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 自訂右鍵選單動作
+                egui::CollapsingHeader::new("自訂動作")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.plugin_editor.render(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 遠端控制 API
+                egui::CollapsingHeader::new("遠端控制")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.render_api_server_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 無障礙
+                egui::CollapsingHeader::new("無障礙")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        Self::render_accessibility_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 匯出與匯入設定
+                egui::CollapsingHeader::new("設定備份")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.render_bundle_settings(ui);
+                    });
+
+                ui.add_space(10.0);
+
+                // 應用程式更新
+                ui.horizontal(|ui| {
+                    ui.label(format!("版本: {}", CURRENT_VERSION));
+                    if ui.button("檢查更新").clicked() {
+                        self.check_app_update(ui.ctx(), true);
+                    }
+                    if self.app_update_release.lock().unwrap().is_some()
+                        && ui.button("查看新版本").clicked()
+                    {
+                        self.show_app_update_dialog.store(true, Ordering::SeqCst);
+                    }
+                });
+                if let Some(message) = self.app_update_message.lock().unwrap().as_ref() {
+                    ui.label(message);
+                }
+
+                if ui.button("配對記憶").clicked() {
+                    self.match_memory_editor.show = true;
+                }
+
+                if ui.button("診斷").clicked() {
+                    self.diagnostics_window.run_health_checks(
+                        ui.ctx(),
+                        &self.download_directory,
+                        self.debug_mode,
+                    );
+                    self.diagnostics_window.show = true;
+                }
+
+                if ui.button("About").clicked() {
+                    info!("點擊了: 關於");
+                    self.show_side_menu = false;
+                    self.osu_helper.show = false;
+                }
+            });
+    }
+
+    fn render_osu_client_settings(ui: &mut egui::Ui) {
+        let installed = installed_clients();
+        let installed_text = if installed.is_empty() {
+            "未在預設位置找到 osu!".to_string()
+        } else {
+            let labels: Vec<&str> = installed.iter().map(|client| client.label()).collect();
+            format!("已安裝: {}", labels.join("、"))
+        };
+        ui.label(egui::RichText::new(installed_text).weak());
+
+        let mut options = osu_client_options();
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("匯入至:");
+            for client in OsuClient::ALL {
+                let available = client == OsuClient::System || installed.contains(&client);
+                ui.add_enabled_ui(available, |ui| {
+                    changed |= ui
+                        .radio_value(&mut options.client, client, client.label())
+                        .on_disabled_hover_text("未在預設位置找到此用戶端")
+                        .changed();
+                });
+            }
+        });
+        changed |= ui
+            .checkbox(&mut options.auto_import, "下載完成後自動匯入")
+            .on_hover_text("osu!lazer 不會掃描 Songs 資料夾，需要匯入才會出現在遊戲中")
+            .changed();
+        if changed {
+            set_osu_client_options(options);
+        }
+    }
+
+    fn render_watch_folder_settings(&mut self, ui: &mut egui::Ui) {
+        let mut options = watch_folder_options();
+        let mut changed = false;
+
+        changed |= ui
+            .checkbox(&mut options.enabled, "自動匯入監看資料夾中新出現的 .osz 檔")
+            .changed();
+        ui.horizontal(|ui| {
+            ui.label("監看資料夾:");
+            match &options.folder {
+                Some(folder) => ui.label(folder.to_string_lossy()),
+                None => ui.label(egui::RichText::new("未設定").weak()),
+            };
+            if ui.button("選擇").clicked() {
+                if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                    options.folder = Some(path);
+                    changed = true;
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("匯入到:");
+            match &options.target_folder {
+                Some(folder) => ui.label(folder.to_string_lossy()),
+                None => ui.label("圖譜下載目錄"),
+            };
+            if ui
+                .button("選擇")
+                .on_hover_text("例如 osu! 的 Songs 資料夾")
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                    options.target_folder = Some(path);
+                    changed = true;
+                }
+            }
+            if options.target_folder.is_some() && ui.button("使用下載目錄").clicked() {
+                options.target_folder = None;
+                changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            changed |= ui
+                .radio_value(&mut options.mode, ImportMode::Move, "搬移")
+                .changed();
+            changed |= ui
+                .radio_value(&mut options.mode, ImportMode::Copy, "複製")
+                .changed();
+        });
+
+        if changed {
+            set_watch_folder_options(options);
+            self.restart_watch_folder();
+        }
+        if let Some(folder) = self.watch_folder.watching() {
+            ui.label(
+                egui::RichText::new(format!("正在監看: {}", folder.to_string_lossy()))
+                    .small()
+                    .weak(),
+            );
+        }
+    }
+
+    fn render_api_server_settings(&mut self, ui: &mut egui::Ui) {
+        let mut options = api_server_options();
+        let mut changed = ui
+            .checkbox(&mut options.enabled, "啟用本機 HTTP API")
+            .on_hover_text("讓 StreamDeck、手機等外部工具觸發搜尋與下載")
+            .changed();
+        ui.horizontal(|ui| {
+            ui.label("連接埠:");
+            let response =
+                ui.add(egui::DragValue::new(&mut options.port).clamp_range(1024..=65535));
+            if response.changed() {
+                set_api_server_options(options.clone());
+            }
+            // 拖曳或輸入完成後才重新啟動，避免每個數值都重新監聽
+            changed |= response.drag_released() || response.lost_focus();
+        });
+        changed |= ui
+            .checkbox(&mut options.allow_lan, "允許區網連線")
+            .on_hover_text("監聽所有網路介面，同一個網路中的裝置都能連線")
+            .changed();
+        ui.horizontal(|ui| {
+            ui.label("Token:");
+            ui.label(egui::RichText::new(&options.token).monospace());
+            if ui.small_button("複製").clicked() {
+                ui.output_mut(|o| o.copied_text = options.token.clone());
+            }
+            if ui.small_button("重新產生").clicked() {
+                options.token = generate_token();
+                changed = true;
+            }
+        });
+
+        if changed {
+            set_api_server_options(options.clone());
+            self.restart_api_server();
+        }
+        match &self.api_server {
+            Some(server) if server.is_running() => {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "GET http://{}/search?q=...、/download/{{id}}、/status，帶上 ?token= 或 Authorization: Bearer",
+                        server.address()
+                    ))
+                    .small()
+                    .weak(),
+                );
+                // OBS 瀏覽器來源使用的網址，需要開啟正在播放偵測
+                let overlay_url = format!(
+                    "http://127.0.0.1:{}/overlay?token={}",
+                    server.address().port(),
+                    options.token
+                );
+                ui.horizontal(|ui| {
+                    ui.label("直播疊加層:");
+                    if ui.small_button("複製網址").clicked() {
+                        ui.output_mut(|o| o.copied_text = overlay_url);
+                    }
+                });
+            }
+            Some(_) => {
+                ui.colored_label(egui::Color32::RED, "伺服器未執行，連接埠可能已被使用");
+            }
+            None => {}
+        }
+    }
+
+    fn render_accessibility_settings(ui: &mut egui::Ui) {
+        let mut options = accessibility_options();
+        let mut changed = ui
+            .checkbox(&mut options.high_contrast, "高對比模式")
+            .on_hover_text("使用純色背景與粗邊框，方便辨識文字與按鈕")
+            .changed();
+        ui.horizontal(|ui| {
+            ui.label("最小字體大小:");
+            changed |= ui
+                .add(
+                    egui::Slider::new(
+                        &mut options.min_font_size,
+                        MIN_FONT_SIZE_RANGE.0..=MIN_FONT_SIZE_RANGE.1,
+                    )
+                    .step_by(1.0),
+                )
+                .changed();
+        });
+        changed |= ui
+            .checkbox(&mut options.reduced_motion, "減少動畫")
+            .on_hover_text("關閉展開、側邊選單與滑過動畫，適合效能較低的電腦")
+            .changed();
+        ui.add_enabled_ui(!options.reduced_motion, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("動畫時間:");
+                changed |= ui
+                    .add(
+                        egui::Slider::new(
+                            &mut options.animation_ms,
+                            ANIMATION_MS_RANGE.0..=ANIMATION_MS_RANGE.1,
+                        )
+                        .suffix(" ms"),
+                    )
+                    .changed();
+            });
+        });
+        if changed {
+            apply_theme(ui.ctx(), &options);
+            apply_motion(ui.ctx(), &options);
+            set_accessibility_options(options);
+        }
+    }
+
+    // 曲風或語言的下拉選單，None 表示不偏好
+    fn render_map_tag_combo(
+        ui: &mut egui::Ui,
+        id: &str,
+        selected: &mut Option<u32>,
+        options: &[(u32, &str)],
+    ) -> bool {
+        let selected_label = selected
+            .and_then(|id| options.iter().find(|(option, _)| *option == id))
+            .map_or("不偏好", |(_, label)| *label);
+        let mut changed = false;
+        egui::ComboBox::from_id_source(id)
+            .selected_text(selected_label)
+            .show_ui(ui, |ui| {
+                changed |= ui.selectable_value(selected, None, "不偏好").changed();
+                for (option, label) in options {
+                    changed |= ui
+                        .selectable_value(selected, Some(*option), *label)
+                        .changed();
+                }
+            });
+        changed
+    }
+
+    fn render_auto_pause_settings(&mut self, ui: &mut egui::Ui) {
+        let mut options = auto_pause_options();
+        let mut changed = ui
+            .checkbox(&mut options.data_saver, "省流模式")
+            .on_hover_text("暫停所有尚未開始的下載，進行中的下載會繼續完成")
+            .changed();
+        changed |= ui
+            .checkbox(&mut options.auto_pause, "連線變慢時自動暫停下載")
+            .changed();
+        ui.add_enabled_ui(options.auto_pause, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("速度低於 (KB/s):");
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut options.min_speed_kbps)
+                            .clamp_range(1..=100_000)
+                            .speed(5),
+                    )
+                    .changed();
+            });
+            ui.horizontal(|ui| {
+                ui.label("持續秒數:");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut options.slow_seconds).clamp_range(5..=600))
+                    .changed();
+            });
+            ui.horizontal(|ui| {
+                ui.label("暫停分鐘:");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut options.resume_minutes).clamp_range(1..=240))
+                    .on_hover_text("暫停時間到後自動繼續，也可以在下載管理中立即繼續")
+                    .changed();
+            });
+        });
+        if changed {
+            set_auto_pause_options(options);
+        }
+    }
+
+    fn render_trash_settings(&mut self, ui: &mut egui::Ui) {
+        let mut options = trash_options();
+        let mut changed = ui
+            .checkbox(&mut options.enabled, "刪除圖譜時先移到資源回收筒")
+            .changed();
+        ui.add_enabled_ui(options.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("保留天數:");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut options.retention_days).clamp_range(0..=365))
+                    .on_hover_text("超過天數的項目在啟動時永久刪除，0 表示不自動清除")
+                    .changed();
+            });
+        });
+        if changed {
+            set_trash_options(options);
+        }
+
+        let items = trash_items();
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "共 {} 個項目，{}",
+                items.len(),
+                format_size(trash_size())
+            ));
+            if ui
+                .add_enabled(!items.is_empty(), egui::Button::new("全部復原"))
+                .clicked()
+            {
+                restore_items(&items);
+            }
+            if ui
+                .add_enabled(!items.is_empty(), egui::Button::new("清空"))
+                .clicked()
+            {
+                self.request_confirmation(
+                    ConfirmRequest::new(
+                        "empty_trash",
+                        "清空資源回收筒",
+                        format!("將永久刪除 {} 個項目，此操作無法復原", items.len()),
+                        ConfirmAction::EmptyTrash,
+                    )
+                    .confirm_label("清空"),
+                );
+            }
+        });
+
+        let mut purged = None;
+        egui::ScrollArea::vertical()
+            .id_source("trash_items")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for item in &items {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("復原").clicked() {
+                            restore_items(std::slice::from_ref(item));
+                        }
+                        if ui.small_button("永久刪除").clicked() {
+                            purged = Some(item.clone());
+                        }
+                        ui.label(&item.file_name).on_hover_text(format!(
+                            "{}\n刪除於 {}",
+                            item.download_directory.display(),
+                            format_local_time(item.deleted_at as i64)
+                        ));
+                    });
+                }
+            });
+        if let Some(item) = purged {
+            self.request_confirmation(
+                ConfirmRequest::new(
+                    "purge_trash_item",
+                    "永久刪除回收筒項目",
+                    format!("將永久刪除 {}，此操作無法復原", item.file_name),
+                    ConfirmAction::PurgeTrashItems(vec![item]),
+                )
+                .confirm_label("刪除"),
+            );
+        }
+    }
+
+    fn render_mapper_settings(&mut self, ui: &mut egui::Ui) {
+        let mut lists = mapper_lists();
+        if ui
+            .checkbox(&mut lists.hide_blocked, "隱藏封鎖作者的譜面")
+            .on_hover_text("不勾選時只標示並在配對時排到最後")
+            .changed()
+        {
+            set_mapper_lists(lists.clone());
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_mapper_name)
+                    .hint_text("作者名稱")
+                    .desired_width(150.0),
+            );
+            let name = self.new_mapper_name.trim().to_string();
+            let mut added = None;
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("⭐ 喜愛"))
+                .clicked()
+            {
+                added = Some(MapperPreference::Favorite);
+            }
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("🚫 封鎖"))
+                .clicked()
+            {
+                added = Some(MapperPreference::Blocked);
+            }
+            if added.is_some() {
+                set_mapper_preference(&name, added);
+                self.new_mapper_name.clear();
+            }
+        });
+
+        let mut removed = None;
+        for (title, names) in [
+            ("喜愛的作者", &lists.favorites),
+            ("封鎖的作者", &lists.blocked),
+        ] {
+            ui.label(format!("{} ({})", title, names.len()));
+            if names.is_empty() {
+                ui.label(egui::RichText::new("無").weak());
+            }
+            ui.horizontal_wrapped(|ui| {
+                for name in names {
+                    if ui
+                        .small_button(format!("{} ✖", name))
+                        .on_hover_text("移除")
+                        .clicked()
+                    {
+                        removed = Some(name.clone());
+                    }
+                }
+            });
+        }
+        if let Some(name) = removed {
+            set_mapper_preference(&name, None);
+        }
+    }
+
+    fn render_bundle_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("將設定、釘選的播放清單、配對記憶與下載紀錄打包成 zip，方便搬到新電腦");
+        ui.checkbox(
+            &mut self.bundle_include_credentials,
+            "包含登入資訊與 API 金鑰",
+        )
+        .on_hover_text("壓縮檔中的 token 與金鑰沒有加密，請勿分享給他人");
+        ui.horizontal(|ui| {
+            if ui.button("匯出設定").clicked() {
+                let file_name = format!("settings_{}.zip", chrono::Local::now().format("%Y%m%d"));
+                if let Some(path) = rfd::FileDialog::new()
+                    .set_file_name(file_name)
+                    .add_filter("ZIP", &["zip"])
+                    .save_file()
+                {
+                    let message = match export_bundle(&path, self.bundle_include_credentials) {
+                        Ok(count) => format!("已匯出 {} 個設定檔", count),
+                        Err(e) => {
+                            error!("匯出設定失敗: {:?}", e);
+                            format!("匯出設定失敗: {}", e)
+                        }
+                    };
+                    self.request_confirmation(ConfirmRequest::notice(
+                        "export_settings",
+                        "匯出設定",
+                        message,
+                    ));
+                }
+            }
+            if ui.button("匯入設定").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("ZIP", &["zip"])
+                    .pick_file()
+                {
+                    self.confirm_import_settings(path);
+                }
+            }
+        });
+    }
+
+    // 列出壓縮檔中會覆蓋的設定檔，確認後才匯入
+    fn confirm_import_settings(&mut self, path: PathBuf) {
+        let include_credentials = self.bundle_include_credentials;
+        match bundle_contents(&path, include_credentials) {
+            Ok(file_names) => self.request_confirmation(
+                ConfirmRequest::new(
+                    "import_settings",
+                    "匯入設定",
+                    format!("將以壓縮檔中的 {} 個檔案覆蓋目前的設定", file_names.len()),
+                    ConfirmAction::ImportSettings {
+                        path,
+                        include_credentials,
+                    },
+                )
+                .details(file_names)
+                .confirm_label("匯入"),
+            ),
+            Err(e) => {
+                error!("讀取設定壓縮檔失敗: {:?}", e);
+                self.request_confirmation(ConfirmRequest::notice(
+                    "import_settings",
+                    "匯入設定",
+                    format!("無法讀取壓縮檔: {}", e),
+                ));
+            }
+        }
+    }
+
+    fn render_confirm_settings(&mut self, ui: &mut egui::Ui) {
+        let mut options = confirm_options();
+        if options.skipped.is_empty() {
+            ui.label(egui::RichText::new("所有破壞性操作都會先詢問").weak());
+            return;
+        }
+        ui.label("以下操作不會再詢問：");
+        let mut restored = None;
+        for (key, title) in &options.skipped {
+            ui.horizontal(|ui| {
+                ui.label(title);
+                if ui.small_button("恢復詢問").clicked() {
+                    restored = Some(key.clone());
+                }
+            });
+        }
+        if ui.button("全部恢復詢問").clicked() {
+            options.skipped.clear();
+            set_confirm_options(options);
+        } else if let Some(key) = restored {
+            options.skipped.remove(&key);
+            set_confirm_options(options);
+        }
+    }
+
+    fn render_cache_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("快取有效期:");
+            let mut ttl_secs = self.cache_manager.ttl().as_secs();
+            if ui
+                .add(
+                    egui::DragValue::new(&mut ttl_secs)
+                        .clamp_range(0..=86400)
+                        .suffix(" 秒"),
+                )
+                .changed()
+            {
+                self.cache_manager.set_ttl(Duration::from_secs(ttl_secs));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("預覽音訊快取上限:");
+            let mut preview_opts = preview_cache_options();
+            if ui
+                .add(
+                    egui::DragValue::new(&mut preview_opts.max_size_mb)
+                        .clamp_range(10..=10_000)
+                        .suffix(" MB"),
+                )
+                .changed()
+            {
+                set_preview_cache_options(preview_opts);
+                self.cache_sizes = None;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("儲存方式:");
+            let mut options = storage_options();
+            let mut changed = false;
+            for backend in StorageBackend::ALL {
+                ui.add_enabled_ui(backend.available(), |ui| {
+                    changed |= ui
+                        .radio_value(&mut options.backend, backend, backend.label())
+                        .on_disabled_hover_text("此版本未啟用 sqlite 功能")
+                        .changed();
+                });
+            }
+            if changed {
+                set_storage_options(options);
+            }
+        });
+        ui.label(
+            egui::RichText::new("播放列表快取、下載紀錄與配對記憶的儲存方式，重新啟動後生效")
+                .small()
+                .weak(),
+        );
+
+        ui.add_space(5.0);
+
+        if self.cache_sizes.is_none() {
+            self.cache_sizes = Some(
+                CacheKind::ALL
+                    .iter()
+                    .map(|kind| (*kind, self.cache_manager.size(*kind)))
+                    .collect(),
+            );
+        }
+
+        let mut clear_kind = None;
+        if let Some(cache_sizes) = &self.cache_sizes {
+            for (kind, size) in cache_sizes {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}: {}", kind.label(), format_size(*size)));
+                    if ui.small_button("清除").clicked() {
+                        clear_kind = Some(*kind);
+                    }
+                });
+            }
+        }
+        if let Some(kind) = clear_kind {
+            self.request_confirmation(
+                ConfirmRequest::new(
+                    "clear_cache",
+                    "清除快取",
+                    format!("要清除{}嗎？", kind.label()),
+                    ConfirmAction::ClearCache(kind),
+                )
+                .confirm_label("清除"),
+            );
+        }
+
+        // 封面紋理只存在記憶體中
+        let mut budget_options = texture_budget_options();
+        ui.horizontal(|ui| {
+            let cover_count = self.texture_cache.try_read().map_or(0, |cache| cache.len())
+                + self
+                    .cover_textures
+                    .try_read()
+                    .map_or(0, |covers| covers.len());
+            let used_bytes = self.texture_budget.lock().unwrap().used_bytes();
+            ui.label(format!(
+                "封面快取: {} 張，約 {} / {}",
+                cover_count,
+                format_size(used_bytes as u64),
+                format_size(budget_options.budget_mb as u64 * 1024 * 1024)
+            ));
+            if ui.small_button("清除").clicked() {
+                self.clear_cover_textures();
+                if let Ok(mut cache) = self.texture_cache.try_write() {
+                    cache.clear();
+                    self.texture_budget
+                        .lock()
+                        .unwrap()
+                        .retain(|key| !matches!(key, CoverKey::Spotify(_)));
+                }
+                info!("已清除封面快取");
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("封面記憶體上限:");
+            if ui
+                .add(
+                    egui::Slider::new(
+                        &mut budget_options.budget_mb,
+                        TEXTURE_BUDGET_MB_RANGE.0..=TEXTURE_BUDGET_MB_RANGE.1,
+                    )
+                    .suffix(" MB")
+                    .logarithmic(true),
+                )
+                .changed()
+            {
+                set_texture_budget_options(budget_options);
+            }
+        });
+
+        if ui.button("重新計算").clicked() {
+            self.cache_sizes = None;
+        }
+    }
+
+    fn render_download_manager(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.set_width(BASE_SIDE_MENU_WIDTH);
+
+            // 頂部標題列
+            ui.horizontal(|ui| {
+                if ui.button("< 返回").clicked() {
+                    self.show_download_manager = false;
+                    self.show_side_menu = true;
+                }
+                ui.heading("下載管理");
+            });
+
+            ui.add_space(10.0);
+
+            if !self.blocked_downloads.is_empty() {
+                self.render_blocked_downloads(ui);
+                ui.separator();
+            }
+
+            if let Some(reason) = pause_reason() {
+                Self::render_paused_downloads(ui, reason);
+                ui.separator();
+            }
+
+            // 進行中的下載
+            let mut active: Vec<(i32, DownloadStatus)> = self
+                .beatmapset_download_statuses
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(_, status)| {
+                    matches!(
+                        status,
+                        DownloadStatus::Waiting | DownloadStatus::Downloading
+                    )
+                })
+                .map(|(id, status)| (*id, *status))
+                .collect();
+            active.sort_by_key(|(id, _)| *id);
+
+            ui.label(egui::RichText::new(format!("進行中 ({})", active.len())).strong());
+            if active.is_empty() {
+                ui.label("目前沒有進行中的下載");
+            } else {
+                let downloading: Vec<i32> = active
+                    .iter()
+                    .filter(|(_, status)| *status == DownloadStatus::Downloading)
+                    .map(|(id, _)| *id)
+                    .collect();
+                let waiting = active.len() - downloading.len();
+                let eta = match queue_eta(&downloading, waiting) {
+                    Some(eta) => format!("預估 {}", format_eta(eta)),
+                    None => String::from("預估時間計算中"),
+                };
+                ui.label(format!(
+                    "{} 下載中 / {} 等待中 / {}",
+                    downloading.len(),
+                    waiting,
+                    eta
+                ));
+            }
+            for (beatmapset_id, status) in active {
+                ui.horizontal(|ui| {
+                    ui.label(format!("#{}", beatmapset_id));
+                    match status {
+                        DownloadStatus::Downloading => match item_progress(beatmapset_id) {
+                            Some(progress) => {
+                                let mut text = match progress.total {
+                                    Some(total) => format!(
+                                        "{} / {}",
+                                        format_size(progress.downloaded),
+                                        format_size(total)
+                                    ),
+                                    None => format_size(progress.downloaded),
+                                };
+                                if let Some(eta) = progress.eta {
+                                    text.push_str(&format!("，剩餘 {}", format_eta(eta)));
+                                }
+                                match progress.fraction() {
+                                    Some(fraction) => {
+                                        ui.add(egui::ProgressBar::new(fraction).text(text));
+                                    }
+                                    None => {
+                                        ui.spinner();
+                                        ui.label(text);
+                                    }
+                                }
+                            }
+                            None => {
+                                ui.spinner();
+                                ui.label("下載中...");
+                            }
+                        },
+                        _ => {
+                            ui.label("等待中");
+                        }
+                    }
+                });
+            }
+
+            ui.separator();
+
+            // 稍後下載排程
+            let items = self.download_scheduler.items();
+            ui.label(egui::RichText::new(format!("稍後下載 ({})", items.len())).strong());
+            ui.label(
+                egui::RichText::new(format!(
+                    "已閒置 {} 分鐘",
+                    self.download_scheduler.idle_for().as_secs() / 60
+                ))
+                .small()
+                .weak(),
+            );
+            if items.is_empty() {
+                ui.label("沒有排程的下載，可在 osu! 搜尋結果上按右鍵加入");
+            }
+
+            let mut download_now = None;
+            egui::ScrollArea::vertical()
+                .id_source("download_schedule")
+                .show(ui, |ui| {
+                    for item in items {
+                        let beatmapset_id = item.beatmapset_id;
+                        ui.group(|ui| {
+                            ui.set_width(ui.available_width());
+                            ui.label(egui::RichText::new(&item.label).strong());
+                            ui.label(item.trigger.describe());
+
+                            // 觸發條件編輯
+                            let mut is_idle = matches!(item.trigger, ScheduleTrigger::Idle { .. });
+                            ui.horizontal(|ui| {
+                                if ui.radio_value(&mut is_idle, true, "閒置時").changed() {
+                                    self.download_scheduler.set_trigger(
+                                        beatmapset_id,
+                                        ScheduleTrigger::Idle {
+                                            minutes: DEFAULT_IDLE_MINUTES,
+                                        },
+                                    );
+                                }
+                                if ui.radio_value(&mut is_idle, false, "指定時間").changed() {
+                                    let timestamp =
+                                        (chrono::Local::now() + TimeDelta::hours(1)).timestamp();
+                                    self.schedule_time_inputs
+                                        .insert(beatmapset_id, format_local_time(timestamp));
+                                    self.download_scheduler.set_trigger(
+                                        beatmapset_id,
+                                        ScheduleTrigger::At { timestamp },
+                                    );
+                                }
+                            });
+
+                            match item.trigger {
+                                ScheduleTrigger::Idle { minutes } => {
+                                    let mut minutes = minutes;
+                                    ui.horizontal(|ui| {
+                                        ui.label("閒置分鐘:");
+                                        if ui
+                                            .add(
+                                                egui::DragValue::new(&mut minutes)
+                                                    .clamp_range(1..=720),
+                                            )
+                                            .changed()
+                                        {
+                                            self.download_scheduler.set_trigger(
+                                                beatmapset_id,
+                                                ScheduleTrigger::Idle { minutes },
+                                            );
+                                        }
+                                    });
+                                }
+                                ScheduleTrigger::At { timestamp } => {
+                                    let input = self
+                                        .schedule_time_inputs
+                                        .entry(beatmapset_id)
+                                        .or_insert_with(|| format_local_time(timestamp));
+                                    let parsed = parse_local_time(input);
+                                    ui.horizontal(|ui| {
+                                        ui.add(
+                                            egui::TextEdit::singleline(input)
+                                                .desired_width(130.0)
+                                                .hint_text("YYYY-MM-DD HH:MM"),
+                                        );
+                                        if ui
+                                            .add_enabled(
+                                                parsed.is_some(),
+                                                egui::Button::new("套用"),
+                                            )
+                                            .clicked()
+                                        {
+                                            if let Some(timestamp) = parsed {
+                                                self.download_scheduler.set_trigger(
+                                                    beatmapset_id,
+                                                    ScheduleTrigger::At { timestamp },
+                                                );
+                                            }
+                                        }
+                                    });
+                                    if parsed.is_none() {
+                                        ui.colored_label(
+                                            egui::Color32::RED,
+                                            "格式: YYYY-MM-DD HH:MM",
+                                        );
+                                    }
+                                }
+                            }
+
+                            ui.horizontal(|ui| {
+                                if ui.button("立即下載").clicked() {
+                                    download_now = Some(beatmapset_id);
+                                }
+                                if ui.button("移除").clicked() {
+                                    self.download_scheduler.remove(beatmapset_id);
+                                    self.schedule_time_inputs.remove(&beatmapset_id);
+                                }
+                            });
+                        });
+                    }
+                });
+
+            if let Some(beatmapset_id) = download_now {
+                self.download_scheduler.remove(beatmapset_id);
+                self.schedule_time_inputs.remove(&beatmapset_id);
+                self.enqueue_beatmap_download(beatmapset_id);
+            }
+
+            ui.separator();
+            Self::render_auto_download_activity(ui);
+        });
+    }
+
+    // 播放清單自動下載的活動紀錄
+    fn render_auto_download_activity(ui: &mut egui::Ui) {
+        let activity = auto_download_activity();
+        egui::CollapsingHeader::new(format!("自動下載紀錄 ({})", activity.len()))
+            .id_source("auto_download_activity")
+            .show(ui, |ui| {
+                if activity.is_empty() {
+                    ui.label("在播放清單中開啟「自動下載」後，新加入的曲目會自動配對並下載");
+                    return;
+                }
+                if ui.small_button("清除紀錄").clicked() {
+                    clear_auto_download_activity();
+                }
+                egui::ScrollArea::vertical()
+                    .id_source("auto_download_activity_scroll")
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        for entry in &activity {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{} [{}]",
+                                    entry.time.format("%m-%d %H:%M"),
+                                    entry.playlist
+                                ))
+                                .weak(),
+                            );
+                            ui.label(&entry.message);
+                        }
+                    });
+            });
+    }
+
+    // 展開或開啟過詳細資料的曲目與譜面集，點擊後以網址重新搜尋
+    fn render_recently_viewed(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.set_width(BASE_SIDE_MENU_WIDTH);
+
+            ui.horizontal(|ui| {
+                if ui.button("< 返回").clicked() {
+                    self.show_recently_viewed = false;
+                    self.show_side_menu = true;
+                }
+                ui.heading("最近瀏覽");
+            });
+            ui.add_space(10.0);
+
+            let entries = recently_viewed();
+            if entries.is_empty() {
+                ui.label("展開或查看詳細資料的曲目與譜面會顯示在這裡");
+                return;
+            }
+            if ui.button("清除紀錄").clicked() {
+                clear_recently_viewed();
+                return;
+            }
+
+            let mut open_item = None;
+            let mut removed_item = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for entry in &entries {
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        ui.horizontal(|ui| {
+                            let source = match entry.item {
+                                ViewedItem::SpotifyTrack { .. } => "Spotify",
+                                ViewedItem::Beatmapset { .. } => "osu!",
+                            };
+                            ui.label(egui::RichText::new(source).small().weak());
+                            if ui
+                                .link(egui::RichText::new(entry.item.title()).strong())
+                                .clicked()
+                            {
+                                open_item = Some(entry.item.clone());
+                            }
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.small_button("✖").on_hover_text("移除").clicked() {
+                                        removed_item = Some(entry.item.clone());
+                                    }
+                                },
+                            );
+                        });
+                        ui.label(entry.item.subtitle());
+                        ui.label(
+                            egui::RichText::new(entry.viewed_at.format("%m-%d %H:%M").to_string())
+                                .small()
+                                .weak(),
+                        );
+                    });
+                }
+            });
+
+            if let Some(item) = removed_item {
+                remove_viewed(&item);
+            }
+            if let Some(item) = open_item {
+                info!("重新開啟最近瀏覽: {}", item.title());
+                self.search_query = item.search_query();
+                self.show_recently_viewed = false;
+                self.perform_search(ui.ctx().clone());
+            }
+        });
+    }
+
+    // 自動下載與同步模式中分數低於門檻的配對
+    fn render_review_queue(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.set_width(BASE_SIDE_MENU_WIDTH);
+
+            ui.horizontal(|ui| {
+                if ui.button("< 返回").clicked() {
+                    self.show_review_queue = false;
+                    self.show_side_menu = true;
+                }
+                ui.heading("需要確認");
+            });
+            ui.label(egui::RichText::new("分數低於自動下載門檻的配對，核准後加入下載隊列").weak());
+            ui.add_space(10.0);
+
+            let items = review_items();
+            if items.is_empty() {
+                ui.label("目前沒有需要確認的配對");
+                return;
+            }
+            let mut decision = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, item) in items.iter().enumerate() {
+                    ui.group(|ui| {
+                        if let Some(approved) = self.render_review_item(ui, index, item) {
+                            decision = Some((index, approved));
+                        }
+                    });
+                }
+            });
+            if let Some((index, approved)) = decision {
+                self.resolve_review_item(index, approved);
+            }
+        });
+    }
+
+    // 左右並列 Spotify 曲目與候選譜面，回傳 Some(true) 為核准、Some(false) 為拒絕
+    fn render_review_item(
+        &mut self,
+        ui: &mut egui::Ui,
+        index: usize,
+        item: &ReviewItem,
+    ) -> Option<bool> {
+        let format_length = |seconds: u64| format!("{}:{:02}", seconds / 60, seconds % 60);
+        ui.columns(2, |columns| {
+            columns[0].label(egui::RichText::new("Spotify").strong());
+            columns[0].label(&item.track.title);
+            columns[0].label(&item.track.artist);
+            if let Some(duration_ms) = item.track.duration_ms {
+                columns[0].label(format_length(duration_ms / 1000));
+            }
+
+            columns[1].label(egui::RichText::new("osu!").strong());
+            columns[1].label(&item.candidate.title);
+            columns[1].label(&item.candidate.artist);
+            columns[1].label(format!("by {}", item.candidate.creator));
+            if let Some(length_secs) = item.candidate.length_secs {
+                columns[1].label(format_length(length_secs.max(0) as u64));
+            }
+        });
+        ui.label(
+            egui::RichText::new(format!(
+                "匹配度 {:.0}% · {} · {}",
+                item.score * 100.0,
+                item.source,
+                item.added_at.format("%m-%d %H:%M")
+            ))
+            .weak(),
+        );
+
+        let compare_index = REVIEW_COMPARE_INDEX_BASE + index;
+        let mut decision = None;
+        ui.horizontal(|ui| {
+            let can_compare = item.track.spotify_track_id.is_some()
+                && item.track.preview_url.is_some()
+                && item.candidate.preview_url.is_some()
+                && self.audio_output.is_some();
+            if can_compare
+                && ui
+                    .small_button("A/B")
+                    .on_hover_text("比較 Spotify 試聽與 osu! 預覽")
+                    .clicked()
+            {
+                self.start_review_compare(compare_index, item);
+            }
+            if let Some(track_id) = &item.track.spotify_track_id {
+                if ui.small_button("Spotify").clicked() {
+                    let url = format!("https://open.spotify.com/track/{}", track_id);
+                    if let Err(e) = open::that(url) {
+                        error!("無法開啟 Spotify 頁面: {:?}", e);
                     }
-                });
+                }
+            }
+            if ui.small_button("osu!").clicked() {
+                let url = format!(
+                    "https://osu.ppy.sh/beatmapsets/{}",
+                    item.candidate.beatmapset_id
+                );
+                if let Err(e) = open::that(url) {
+                    error!("無法開啟譜面頁面: {:?}", e);
+                }
+            }
+            ui.separator();
+            if ui
+                .button("核准並下載")
+                .on_hover_text("記住這個配對並加入下載隊列")
+                .clicked()
+            {
+                decision = Some(true);
+            }
+            if ui
+                .button("拒絕")
+                .on_hover_text("之後自動配對不再選用這個譜面")
+                .clicked()
+            {
+                decision = Some(false);
+            }
+        });
+        self.display_preview_compare(ui, compare_index, item.candidate.beatmapset_id);
+        decision
+    }
 
-                ui.add_space(10.0);
+    fn start_review_compare(&mut self, compare_index: usize, item: &ReviewItem) {
+        let stream_handle = match self.audio_output.as_ref() {
+            Some((_, handle)) => handle.clone(),
+            None => return,
+        };
+        let (track_id, spotify_url, osu_url) = match (
+            &item.track.spotify_track_id,
+            &item.track.preview_url,
+            &item.candidate.preview_url,
+        ) {
+            (Some(track_id), Some(spotify_url), Some(osu_url)) => {
+                (track_id, spotify_url.clone(), osu_preview_url(osu_url))
+            }
+            _ => return,
+        };
+        self.preview_compare = None;
+        self.preview_compare = Some(PreviewCompare::from_sources(
+            &self.ctx,
+            stream_handle,
+            CompareSources {
+                track_index: compare_index,
+                spotify_key: format!("spotify_{}", track_id),
+                spotify_url,
+                beatmapset_id: item.candidate.beatmapset_id,
+                osu_url,
+            },
+            self.global_volume,
+            self.preview_speed,
+            self.preview_eq,
+        ));
+    }
 
-                // 自定義背景設置
-                ui.horizontal(|ui| {
-                    ui.label("背景圖片:");
-                    if ui.button("選擇背景").clicked() {
-                        if let Some(path) = rfd::FileDialog::new()
-                            .add_filter("圖片", &["png", "jpg", "jpeg"])
-                            .pick_file()
-                        {
-                            self.custom_background_path = Some(path.clone());
-                            if let Err(e) = self.load_custom_background(ui.ctx()) {
-                                error!("加載背景失敗: {:?}", e);
-                                self.custom_background_path = None;
+    // 核准時記住配對並下載，拒絕時記錄到配對記憶讓自動配對略過此譜面
+    fn resolve_review_item(&mut self, index: usize, approved: bool) {
+        let item = match remove_review_item(index) {
+            Some(item) => item,
+            None => return,
+        };
+        // 列表索引已改變，關閉待確認頁面上的比較
+        if self.preview_compare.as_ref().map_or(false, |compare| {
+            compare.track_index >= REVIEW_COMPARE_INDEX_BASE
+        }) {
+            self.preview_compare = None;
+        }
+        let beatmapset_id = item.candidate.beatmapset_id;
+        if approved {
+            if let Some(track_id) = &item.track.spotify_track_id {
+                remember_match_id(
+                    track_id,
+                    &format!("{} - {}", item.track.artist, item.track.title),
+                    beatmapset_id,
+                    &format!("{} - {}", item.candidate.artist, item.candidate.title),
+                );
+            }
+            info!("核准待確認配對，下載譜面 {}", beatmapset_id);
+            self.enqueue_beatmap_download(beatmapset_id);
+        } else {
+            reject_match(&item.track.key(), beatmapset_id);
+        }
+    }
 
-                                // 顯示錯誤視窗
-                                let error_window = egui::Window::new("錯誤")
-                                    .collapsible(false)
-                                    .resizable(false);
-                                error_window.show(ui.ctx(), |ui| {
-                                    ui.label("無法讀取自定義背景,已恢復使用預設背景。");
-                                    if ui.button("確認").clicked() {
-                                        ui.close_menu();
-                                    }
-                                });
-                            } else {
-                                info!("自定義背景已設置: {:?}", path);
-                                if let Err(e) = save_background_path(&self.custom_background_path) {
-                                    error!("保存背景位置失敗: {:?}", e);
-                                }
-                            }
-                        }
-                    }
-                    if ui.button("恢復預設背景").clicked() {
-                        self.custom_background_path = None;
-                        self.custom_background = None;
-                        if let Err(e) = save_background_path(&None) {
-                            error!("保存背景位置失敗: {:?}", e);
-                        }
-                        info!("已恢復使用預設背景");
-                    }
-                });
-                if let Some(path) = &self.custom_background_path {
-                    ui.label(format!("當前背景: {}", path.to_string_lossy()));
-                } else {
-                    ui.label("當前使用預設背景");
+    fn render_paused_downloads(ui: &mut egui::Ui, reason: PauseReason) {
+        match reason {
+            PauseReason::DataSaver => {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "⏸ 省流模式已開啟，尚未開始的下載已暫停",
+                );
+                if ui.button("關閉省流模式").clicked() {
+                    set_auto_pause_options(AutoPauseOptions {
+                        data_saver: false,
+                        ..auto_pause_options()
+                    });
                 }
+            }
+            PauseReason::SlowConnection { resume_at } => {
+                let remaining = resume_at.saturating_duration_since(Instant::now());
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "⏸ 連線速度過慢，尚未開始的下載將在 {} 後繼續",
+                        format_eta(remaining)
+                    ),
+                );
+                if ui.button("立即繼續").clicked() {
+                    resume_downloads();
+                }
+            }
+        }
+    }
 
-                if ui.button("About").clicked() {
-                    info!("點擊了: 關於");
-                    self.show_side_menu = false;
-                    self.osu_helper.show = false;
+    fn render_blocked_downloads(&mut self, ui: &mut egui::Ui) {
+        let available = available_space(&self.download_directory)
+            .map(|bytes| format!("{} MB", bytes / (1024 * 1024)))
+            .unwrap_or_else(|| "未知".to_string());
+        ui.colored_label(
+            ui.visuals().error_fg_color,
+            format!(
+                "⚠ 磁碟空間不足：剩餘 {}，低於設定的 {} MB",
+                available,
+                download_options().min_free_space_mb
+            ),
+        );
+        ui.label(format!(
+            "已暫停 {} 個下載: {}",
+            self.blocked_downloads.len(),
+            self.blocked_downloads
+                .iter()
+                .map(|id| format!("#{}", id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        ui.horizontal(|ui| {
+            if ui.button("重新檢查").clicked() {
+                for beatmapset_id in std::mem::take(&mut self.blocked_downloads) {
+                    self.enqueue_beatmap_download(beatmapset_id);
                 }
-            });
+            }
+            if ui.button("仍要下載").clicked() {
+                for beatmapset_id in std::mem::take(&mut self.blocked_downloads) {
+                    self.send_to_download_queue(beatmapset_id);
+                }
+            }
+            if ui.button("取消").clicked() {
+                self.blocked_downloads.clear();
+            }
+        });
     }
 
     fn render_downloaded_maps_list(&mut self, ui: &mut egui::Ui) {
         let fixed_width = BASE_SIDE_MENU_WIDTH;
+        self.poll_download_scan();
 
         ui.vertical(|ui| {
             ui.set_width(fixed_width);
@@ -3465,26 +8890,53 @@ impl SearchApp {
                 ui.add_space(10.0);
             }
 
+            egui::CollapsingHeader::new("整理")
+                .default_open(false)
+                .show(ui, |ui| {
+                    self.render_download_cleanup_tools(ui);
+                });
+            ui.add_space(5.0);
+
             // 圖譜列表
             egui::ScrollArea::vertical().show(ui, |ui| {
                 let downloaded = get_downloaded_beatmaps(&self.download_directory);
                 if downloaded.is_empty() {
                     ui.label("尚未下載任何圖譜");
                 } else {
+                    // 讀取 .osu 的歌手、歌名、作者與標籤，讓搜尋不只比對檔名
+                    index_downloaded_maps(ui.ctx(), &self.download_directory, &downloaded);
+
                     // 先收集所有符合搜尋條件的檔案
-                    let search_term = self.downloaded_maps_search.to_lowercase();
+                    let search_term = self.downloaded_maps_search.trim();
                     let filtered_maps: Vec<_> = downloaded
                         .into_iter()
-                        .filter(|file_name| {
-                            search_term.is_empty()
-                                || file_name.to_lowercase().contains(&search_term)
-                        })
+                        .filter(|file_name| map_matches(search_term, file_name))
                         .collect();
 
+                    if ui.small_button("全部加入 Spotify 歌單").clicked() {
+                        let added = filtered_maps
+                            .iter()
+                            .filter_map(|file_name| parse_downloaded_file_name(file_name))
+                            .filter(|(id, artist, title)| {
+                                self.playlist_builder.add(*id, artist, title)
+                            })
+                            .count();
+                        info!("已將 {} 個已下載圖譜加入歌單建立器", added);
+                    }
+
                     for file_name in filtered_maps {
                         ui.horizontal(|ui| {
                             let is_expanded = self.expanded_map_indices.contains(&file_name);
 
+                            let mut selected = self.selected_downloaded_maps.contains(&file_name);
+                            if ui.checkbox(&mut selected, "").changed() {
+                                if selected {
+                                    self.selected_downloaded_maps.insert(file_name.clone());
+                                } else {
+                                    self.selected_downloaded_maps.remove(&file_name);
+                                }
+                            }
+
                             // 展開/收起按鈕
                             if let Some(icon) = self.preloaded_icons.get(if is_expanded {
                                 "expand_off.png"
@@ -3506,14 +8958,64 @@ impl SearchApp {
                                 }
                             }
 
-                            // 檔案名稱顯示
+                            // 顯示歌名、歌手與作者，無法解析時顯示檔案名稱
                             let available_width = fixed_width - 50.0;
-                            let text = egui::RichText::new(&file_name).size(14.0);
 
-                            egui::Frame::none().show(ui, |ui| {
+                            let frame = egui::Frame::none().show(ui, |ui| {
                                 ui.set_max_width(available_width);
-                                ui.label(text).on_hover_text(&file_name);
+                                match map_metadata(&file_name) {
+                                    Some(metadata) => {
+                                        let mut hover_text = file_name.clone();
+                                        if !metadata.tags.is_empty() {
+                                            hover_text.push_str(&format!(
+                                                "\n標籤: {}",
+                                                metadata.tags.join(" ")
+                                            ));
+                                        }
+                                        ui.vertical(|ui| {
+                                            ui.label(
+                                                egui::RichText::new(&metadata.title)
+                                                    .size(14.0)
+                                                    .strong(),
+                                            );
+                                            ui.label(
+                                                egui::RichText::new(&metadata.artist).size(12.0),
+                                            );
+                                            if !metadata.creator.is_empty() {
+                                                ui.label(
+                                                    egui::RichText::new(format!(
+                                                        "by {}",
+                                                        metadata.creator
+                                                    ))
+                                                    .small()
+                                                    .weak(),
+                                                );
+                                            }
+                                        })
+                                        .response
+                                        .on_hover_text(hover_text);
+                                    }
+                                    None => {
+                                        ui.label(egui::RichText::new(&file_name).size(14.0))
+                                            .on_hover_text(&file_name);
+                                    }
+                                }
                             });
+                            // 點擊開啟詳細資料
+                            if ui
+                                .interact(
+                                    frame.response.rect,
+                                    ui.id().with(("downloaded_map", &file_name)),
+                                    egui::Sense::click(),
+                                )
+                                .clicked()
+                            {
+                                self.downloaded_detail.open(
+                                    ui.ctx(),
+                                    &self.download_directory,
+                                    &file_name,
+                                );
+                            }
                         });
 
                         // 如果展開，顯示操作按鈕
@@ -3531,11 +9033,10 @@ impl SearchApp {
                                         )))
                                         .clicked()
                                     {
-                                        if let Err(e) = fs::remove_file(
-                                            self.download_directory.join(&file_name),
-                                        ) {
-                                            error!("刪除檔案失敗: {}", e);
-                                        }
+                                        self.confirm_delete_maps(
+                                            "刪除圖譜",
+                                            vec![file_name.clone()],
+                                        );
                                     }
                                 }
 
@@ -3556,6 +9057,22 @@ impl SearchApp {
                                         }
                                     }
                                 }
+
+                                // 加入歌單建立器
+                                if let Some((id, artist, title)) =
+                                    parse_downloaded_file_name(&file_name_clone)
+                                {
+                                    let in_builder = self.playlist_builder.contains(id);
+                                    if ui
+                                        .add_enabled(
+                                            !in_builder,
+                                            egui::Button::new("加入歌單").small(),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.playlist_builder.add(id, &artist, &title);
+                                    }
+                                }
                             });
                         }
                         ui.separator();
@@ -3565,6 +9082,119 @@ impl SearchApp {
         });
     }
 
+    // 在背景讀取每個下載的檔案，避免大量 .osz 卡住畫面
+    fn scan_download_directory(&mut self) {
+        self.scanning_downloads = true;
+        let download_directory = self.download_directory.clone();
+        let download_scan_result = self.download_scan_result.clone();
+        let ctx = self.ctx.clone();
+        tokio::task::spawn_blocking(move || {
+            let problems = scan_downloads(&download_directory);
+            *download_scan_result.lock().unwrap() = Some(problems);
+            ctx.request_repaint();
+        });
+    }
+
+    // 列出遺失或損毀的譜面，確認後重新下載
+    fn poll_download_scan(&mut self) {
+        let problems = match self.download_scan_result.lock().unwrap().take() {
+            Some(problems) => problems,
+            None => return,
+        };
+        self.scanning_downloads = false;
+        if problems.is_empty() {
+            self.request_confirmation(ConfirmRequest::notice(
+                "scan_downloads",
+                "掃描下載資料夾",
+                "所有下載紀錄的檔案都完整",
+            ));
+            return;
+        }
+        let details: Vec<String> = problems.iter().map(IntegrityProblem::describe).collect();
+        self.request_confirmation(
+            ConfirmRequest::new(
+                "scan_downloads",
+                "掃描下載資料夾",
+                format!(
+                    "發現 {} 個遺失或損毀的譜面，遺失的下載紀錄已移除。要重新下載嗎？",
+                    problems.len()
+                ),
+                ConfirmAction::RedownloadMaps(problems),
+            )
+            .details(details)
+            .confirm_label("重新下載"),
+        );
+    }
+
+    // 依條件挑出要刪除的已下載圖譜，實際刪除前一律經過確認對話框
+    fn render_download_cleanup_tools(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("刪除超過");
+            ui.add(egui::DragValue::new(&mut self.cleanup_days).clamp_range(1..=3650));
+            ui.label("天的圖譜");
+            if ui.button("刪除").clicked() {
+                let days = self.cleanup_days;
+                let file_names: Vec<String> = downloaded_maps(&self.download_directory)
+                    .into_iter()
+                    .filter(|map| map.age_days() >= days)
+                    .map(|map| map.file_name)
+                    .collect();
+                self.confirm_delete_maps(&format!("刪除下載超過 {} 天的圖譜", days), file_names);
+            }
+        });
+
+        if ui
+            .button("刪除尚未匯入 osu! 的圖譜")
+            .on_hover_text("osu! 匯入後會將 .osz 解壓成資料夾，仍是 .osz 檔的圖譜視為尚未匯入")
+            .clicked()
+        {
+            let file_names: Vec<String> = downloaded_maps(&self.download_directory)
+                .into_iter()
+                .filter(|map| !map.imported)
+                .map(|map| map.file_name)
+                .collect();
+            self.confirm_delete_maps("刪除尚未匯入 osu! 的圖譜", file_names);
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.scanning_downloads,
+                    egui::Button::new("掃描下載資料夾"),
+                )
+                .on_hover_text("檢查下載紀錄中的檔案是否仍存在且可以解壓")
+                .clicked()
+            {
+                self.scan_download_directory();
+            }
+            if self.scanning_downloads {
+                ui.spinner();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let selected_count = self.selected_downloaded_maps.len();
+            if ui
+                .add_enabled(
+                    selected_count > 0,
+                    egui::Button::new(format!("刪除選取的圖譜 ({})", selected_count)),
+                )
+                .clicked()
+            {
+                let mut file_names: Vec<String> =
+                    self.selected_downloaded_maps.iter().cloned().collect();
+                file_names.sort();
+                self.confirm_delete_maps("刪除選取的圖譜", file_names);
+            }
+            if ui
+                .add_enabled(selected_count > 0, egui::Button::new("取消選取"))
+                .clicked()
+            {
+                self.selected_downloaded_maps.clear();
+            }
+        });
+    }
+
     // 新增一個輔助函數來從檔名提取 beatmap ID
     fn extract_beatmap_id(file_name: &str) -> Option<&str> {
         file_name.split(' ').find(|s| s.parse::<u32>().is_ok())
@@ -3575,16 +9205,7 @@ impl SearchApp {
         ctx: &egui::Context,
     ) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(path) = &self.custom_background_path {
-            let image = image::ImageReader::open(path)?.decode()?;
-            let size = [image.width() as _, image.height() as _];
-            let image_buffer = image.to_rgba8();
-            let pixels = image_buffer.as_flat_samples();
-            let texture = ctx.load_texture(
-                "custom_background",
-                egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice()),
-                egui::TextureOptions::default(),
-            );
-            self.custom_background = Some(texture);
+            self.custom_background = Some(load_image_file(ctx, "custom_background", path)?);
             Ok(())
         } else {
             Err("No custom background path set".into())
@@ -3645,15 +9266,20 @@ impl SearchApp {
                 ui.separator();
     
                 // 過濾播放清單
-                let playlists_clone = {
+                let mut playlists_clone = {
                     if let Ok(playlists) = self.spotify_user_playlists.lock() {
                         playlists.clone()
                     } else {
                         Vec::new()
                     }
                 };
+                sort_pinned_first(&mut playlists_clone);
     
                 let search_term = self.playlist_search_query.to_lowercase();
+                if search_term.is_empty() {
+                    self.render_grouped_playlists(ui, &playlists_clone);
+                    return;
+                }
                 let filtered_playlists = playlists_clone.into_iter().filter(|playlist| {
                     search_term.is_empty() || 
                     playlist.name.to_lowercase().contains(&search_term)
@@ -3666,6 +9292,137 @@ impl SearchApp {
         });
     }
 
+    // 釘選的播放清單在最上方，其次是各資料夾，最後是未分類的播放清單
+    fn render_grouped_playlists(&mut self, ui: &mut egui::Ui, playlists: &[SimplifiedPlaylist]) {
+        let (pinned, rest): (Vec<&SimplifiedPlaylist>, Vec<&SimplifiedPlaylist>) = playlists
+            .iter()
+            .partition(|playlist| is_pinned(playlist.id.id()));
+        for playlist in pinned {
+            self.render_playlist_item(ui, playlist);
+        }
+
+        ui.add_space(5.0);
+        match &mut self.new_folder_name {
+            Some(name) => {
+                let mut done = false;
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(name)
+                            .hint_text("資料夾名稱")
+                            .desired_width(150.0),
+                    );
+                    response.request_focus();
+                    let submitted =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if submitted || ui.button("建立").clicked() {
+                        done = add_folder(name);
+                    }
+                    if ui.button("取消").clicked() {
+                        done = true;
+                    }
+                });
+                if done {
+                    self.new_folder_name = None;
+                }
+            }
+            None => {
+                if ui
+                    .small_button("＋ 新資料夾")
+                    .on_hover_text("可將播放清單拖曳到資料夾，或在播放清單上按右鍵")
+                    .clicked()
+                {
+                    self.new_folder_name = Some(String::new());
+                }
+            }
+        }
+
+        let folders = playlist_folders();
+        let mut dropped: Option<(String, Option<usize>)> = None;
+        for (index, folder) in folders.iter().enumerate() {
+            let members: Vec<&SimplifiedPlaylist> = rest
+                .iter()
+                .copied()
+                .filter(|playlist| folder.playlist_ids.iter().any(|id| id == playlist.id.id()))
+                .collect();
+
+            if let Some((renaming_index, name)) = &mut self.renaming_folder {
+                if *renaming_index == index {
+                    let mut done = false;
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(name).desired_width(150.0));
+                        if ui.button("確定").clicked() {
+                            done = rename_folder(index, name);
+                        }
+                        if ui.button("取消").clicked() {
+                            done = true;
+                        }
+                    });
+                    if done {
+                        self.renaming_folder = None;
+                    }
+                    continue;
+                }
+            }
+
+            let collapsing =
+                egui::CollapsingHeader::new(format!("📁 {} ({})", folder.name, members.len()))
+                    .id_source(("playlist_folder", index))
+                    .show(ui, |ui| {
+                        if members.is_empty() {
+                            ui.label(egui::RichText::new("拖曳播放清單到這裡").weak());
+                        }
+                        for playlist in &members {
+                            self.render_playlist_item(ui, playlist);
+                        }
+                    });
+            let header = collapsing.header_response;
+            if header.dnd_hover_payload::<String>().is_some() {
+                ui.painter()
+                    .rect_stroke(header.rect, 2.0, ui.visuals().selection.stroke);
+            }
+            if let Some(playlist_id) = header.dnd_release_payload::<String>() {
+                dropped = Some(((*playlist_id).clone(), Some(index)));
+            }
+            header.context_menu(|ui| {
+                if ui.button("重新命名").clicked() {
+                    self.renaming_folder = Some((index, folder.name.clone()));
+                    ui.close_menu();
+                }
+                if ui.button("刪除資料夾").clicked() {
+                    remove_folder(index);
+                    ui.close_menu();
+                }
+            });
+        }
+
+        let ungrouped: Vec<&SimplifiedPlaylist> = rest
+            .iter()
+            .copied()
+            .filter(|playlist| folder_index_of(playlist.id.id()).is_none())
+            .collect();
+        if !folders.is_empty() {
+            // 拖曳到此處可移出資料夾
+            let response = ui.add(
+                egui::Label::new(egui::RichText::new("未分類").strong())
+                    .sense(egui::Sense::hover()),
+            );
+            if response.dnd_hover_payload::<String>().is_some() {
+                ui.painter()
+                    .rect_stroke(response.rect, 2.0, ui.visuals().selection.stroke);
+            }
+            if let Some(playlist_id) = response.dnd_release_payload::<String>() {
+                dropped = Some(((*playlist_id).clone(), None));
+            }
+        }
+        for playlist in ungrouped {
+            self.render_playlist_item(ui, playlist);
+        }
+
+        if let Some((playlist_id, folder)) = dropped {
+            move_to_folder(&playlist_id, folder);
+        }
+    }
+
     fn render_liked_songs_item(&mut self, ui: &mut egui::Ui) {
         ui.add_space(5.0);
         let (rect, response) =
@@ -3724,8 +9481,34 @@ impl SearchApp {
     fn render_playlist_item(&mut self, ui: &mut egui::Ui, playlist: &SimplifiedPlaylist) {
         ui.add_space(5.0);
 
-        let (rect, response) =
-            ui.allocate_exact_size(egui::vec2(ui.available_width(), 70.0), egui::Sense::click());
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), 70.0),
+            egui::Sense::click_and_drag(),
+        );
+        // 拖曳到資料夾標題即可分類
+        response.dnd_set_drag_payload(playlist.id.id().to_string());
+        response.context_menu(|ui| {
+            let current = folder_index_of(playlist.id.id());
+            ui.menu_button("移到資料夾", |ui| {
+                let folders = playlist_folders();
+                if folders.is_empty() {
+                    ui.label("尚未建立資料夾");
+                }
+                for (index, folder) in folders.iter().enumerate() {
+                    if ui
+                        .add_enabled(current != Some(index), egui::Button::new(&folder.name))
+                        .clicked()
+                    {
+                        move_to_folder(playlist.id.id(), Some(index));
+                        ui.close_menu();
+                    }
+                }
+            });
+            if current.is_some() && ui.button("移出資料夾").clicked() {
+                move_to_folder(playlist.id.id(), None);
+                ui.close_menu();
+            }
+        });
 
         if ui.is_rect_visible(rect) {
             ui.painter()
@@ -3735,7 +9518,7 @@ impl SearchApp {
             let text_rect = rect.shrink2(egui::vec2(cover_size.x + 30.0, 0.0));
 
             ui.painter().text(
-                text_rect.left_center() + egui::vec2(0.0, -10.0),
+                text_rect.left_center() + egui::vec2(0.0, -15.0),
                 egui::Align2::LEFT_CENTER,
                 &playlist.name,
                 egui::FontId::proportional(18.0),
@@ -3744,7 +9527,7 @@ impl SearchApp {
 
             if let Some(owner) = &playlist.owner.display_name {
                 ui.painter().text(
-                    text_rect.left_center() + egui::vec2(0.0, 15.0),
+                    text_rect.left_center() + egui::vec2(0.0, 7.0),
                     egui::Align2::LEFT_CENTER,
                     owner,
                     egui::FontId::proportional(14.0),
@@ -3752,6 +9535,19 @@ impl SearchApp {
                 );
             }
 
+            // 總長度讀取前先顯示 Spotify 提供的曲目數
+            let details = match self.playlist_summary(ui.ctx(), playlist.id.id()) {
+                Some(summary) => summary.describe(),
+                None => format!("{} 首", playlist.tracks.total),
+            };
+            ui.painter().text(
+                text_rect.left_center() + egui::vec2(0.0, 25.0),
+                egui::Align2::LEFT_CENTER,
+                details,
+                egui::FontId::proportional(12.0),
+                ui.visuals().weak_text_color(),
+            );
+
             let image_rect = egui::Rect::from_min_size(
                 rect.left_center() - egui::vec2(0.0, cover_size.y / 2.0),
                 cover_size,
@@ -3766,8 +9562,13 @@ impl SearchApp {
                         let url = cover_url.clone();
                         let textures_clone = self.playlist_cover_textures.clone();
                         tokio::spawn(async move {
-                            if let Ok(texture) =
-                                Self::load_texture_async(&ctx, &url, Duration::from_secs(30)).await
+                            if let Ok(texture) = Self::load_texture_async(
+                                &ctx,
+                                &url,
+                                Duration::from_secs(30),
+                                Some(THUMBNAIL_SIZE),
+                            )
+                            .await
                             {
                                 let mut textures = textures_clone.lock().unwrap();
                                 textures.insert(url, Some(texture));
@@ -3809,13 +9610,80 @@ impl SearchApp {
             }
         }
 
+        // 釘選按鈕疊在列的右側，已釘選時一直顯示，否則滑過時才顯示
+        let pinned = is_pinned(playlist.id.id());
+        if pinned || ui.rect_contains_pointer(rect) {
+            let pin_rect = egui::Rect::from_center_size(
+                rect.right_center() - egui::vec2(20.0, 0.0),
+                egui::vec2(28.0, 28.0),
+            );
+            let pin_text = egui::RichText::new("📌").color(if pinned {
+                ui.visuals().selection.bg_fill
+            } else {
+                ui.visuals().weak_text_color()
+            });
+            let hover_text = if pinned {
+                "取消釘選"
+            } else {
+                "釘選到最上方"
+            };
+            if ui
+                .put(pin_rect, egui::Button::new(pin_text).frame(false))
+                .on_hover_text(hover_text)
+                .clicked()
+            {
+                toggle_pinned(playlist);
+                return;
+            }
+        }
+
         if response.clicked() {
-            self.selected_playlist = Some(playlist.clone());
-            self.load_playlist_tracks(playlist.id.clone());
-            self.show_liked_tracks = false;
-            self.show_playlists = false; // 確保關閉播放清單列表視圖
-            info!("正在加載播放清單: {}", playlist.name);
+            self.open_playlist(playlist);
+        }
+    }
+
+    fn open_playlist(&mut self, playlist: &SimplifiedPlaylist) {
+        self.selected_playlist = Some(playlist.clone());
+        self.load_playlist_tracks(playlist.id.clone());
+        self.show_liked_tracks = false;
+        self.show_playlists = false; // 確保關閉播放清單列表視圖
+        info!("正在加載播放清單: {}", playlist.name);
+    }
+
+    // 主選單中的釘選播放清單捷徑，尚未載入播放清單時先開啟列表
+    fn render_pinned_playlists_strip(&mut self, ui: &mut egui::Ui) {
+        let pinned = pinned_playlists();
+        if pinned.is_empty() {
+            return;
+        }
+        let mut clicked = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(egui::RichText::new("📌").weak());
+            for entry in &pinned {
+                if ui.small_button(&entry.name).clicked() {
+                    clicked = Some(entry.id.clone());
+                }
+            }
+        });
+        let playlist_id = match clicked {
+            Some(playlist_id) => playlist_id,
+            None => return,
+        };
+        let playlist = self
+            .spotify_user_playlists
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|playlist| playlist.id.id() == playlist_id)
+            .cloned();
+        match playlist {
+            Some(playlist) => self.open_playlist(&playlist),
+            None => {
+                self.show_playlists = true;
+                self.load_user_playlists();
+            }
         }
+        self.osu_helper.show = false;
     }
     fn render_playlist_content(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
@@ -3889,9 +9757,7 @@ impl SearchApp {
                             if let Some(spotify) = spotify {
                                 let cache_path = {
                                     let cache = liked_songs_cache.lock().unwrap();
-                                    cache
-                                        .as_ref()
-                                        .map(|c| PathBuf::from(&format!("{:?}", c.last_updated)))
+                                    cache.as_ref().map(|c| format!("{:?}", c.last_updated))
                                 };
 
                                 if let Some(path) = cache_path {
@@ -3912,6 +9778,32 @@ impl SearchApp {
                         });
                     }
 
+                    if ui
+                        .button("⬇ 下載缺少的譜面")
+                        .on_hover_text("為還沒有下載過譜面的曲目配對並下載")
+                        .clicked()
+                    {
+                        self.confirm_download_missing_tracks();
+                    }
+
+                    // 新加入的曲目自動配對並下載
+                    if !self.show_liked_tracks {
+                        if let Some(playlist) = &self.selected_playlist {
+                            let playlist_id = playlist.id.id();
+                            let mut enabled = is_auto_download(playlist_id);
+                            if ui
+                                .checkbox(&mut enabled, "自動下載")
+                                .on_hover_text("播放清單有新曲目時自動配對譜面並加入下載隊列")
+                                .changed()
+                            {
+                                set_auto_download(playlist_id, &playlist.name, enabled);
+                            }
+                        }
+                    }
+
+                    ui.checkbox(&mut self.only_undownloaded_tracks, "只顯示尚未下載的歌曲")
+                        .on_hover_text("隱藏已配對且下載過譜面的曲目");
+
                     // 搜尋按鈕
                     if let Some(search_icon) = self.preloaded_icons.get("search.png") {
                         if ui.add(egui::ImageButton::new(
@@ -3968,7 +9860,12 @@ impl SearchApp {
 
             let is_loading = self.is_searching.load(Ordering::SeqCst);
             let tracks = if self.show_liked_tracks {
-                self.spotify_liked_tracks.lock().unwrap().clone()
+                let liked_tracks = self.spotify_liked_tracks.lock().unwrap();
+                liked_tracks
+                    .iter()
+                    .cloned()
+                    .map(PlaylistEntry::Track)
+                    .collect()
             } else {
                 self.spotify_playlist_tracks.lock().unwrap().clone()
             };
@@ -3976,7 +9873,19 @@ impl SearchApp {
             if is_loading {
                 ui.add_space(20.0);
                 ui.add(egui::Spinner::new().size(32.0));
-                ui.label("正在加載...");
+                match self.cache_progress.fraction() {
+                    Some(fraction) => {
+                        ui.add(
+                            egui::ProgressBar::new(fraction)
+                                .desired_width(200.0)
+                                .show_percentage(),
+                        );
+                        ui.label("正在讀取快取...");
+                    }
+                    None => {
+                        ui.label("正在加載...");
+                    }
+                }
             } else if tracks.is_empty() {
                 ui.add_space(20.0);
                 ui.label("沒有找到曲目");
@@ -3986,34 +9895,151 @@ impl SearchApp {
                 let filtered_tracks: Vec<_> = tracks
                     .iter()
                     .enumerate()
-                    .filter(|(_, track)| {
-                        search_term.is_empty() ||
-                        track.name.to_lowercase().contains(&search_term) ||
-                        track.artists.iter().any(|artist| 
-                            artist.name.to_lowercase().contains(&search_term)
-                        )
+                    .filter(|(_, entry)| {
+                        !self.only_undownloaded_tracks
+                            || entry.track().map_or(false, |track| {
+                                self.track_match_badge(track) != Some(TrackMatchBadge::Downloaded)
+                            })
+                    })
+                    .filter(|(_, entry)| {
+                        search_term.is_empty()
+                            || entry.name().to_lowercase().contains(&search_term)
+                            || entry.track().map_or(false, |track| {
+                                track
+                                    .artists
+                                    .iter()
+                                    .any(|artist| artist.name.to_lowercase().contains(&search_term))
+                            })
                     })
                     .collect();
 
-                egui::ScrollArea::vertical().show_rows(
-                    ui,
-                    40.0,
-                    filtered_tracks.len(),
-                    |ui, row_range| {
-                        for i in row_range {
-                            if let Some((original_index, track)) = filtered_tracks.get(i) {
-                                self.render_track_item(ui, track, *original_index);
+                let visible_ids: Vec<String> = filtered_tracks
+                    .iter()
+                    .filter_map(|(_, entry)| entry.track())
+                    .filter_map(|track| track.id.as_ref().map(|id| id.id().to_string()))
+                    .collect();
+                self.display_batch_like_bar(ui, &visible_ids);
+                if filtered_tracks.len() >= JUMP_BAR_MIN_TRACKS {
+                    let names: Vec<&str> = filtered_tracks
+                        .iter()
+                        .map(|(_, entry)| entry.name())
+                        .collect();
+                    self.render_jump_to_letter(ui, &names);
+                }
+
+                let row_height = 40.0;
+                let mut scroll_area = egui::ScrollArea::vertical();
+                if let Some(row) = self.jump_to_row.take() {
+                    // 與 show_rows 相同的列高計算，讓該列出現在最上方
+                    let offset = row as f32 * (row_height + ui.spacing().item_spacing.y);
+                    scroll_area = scroll_area.vertical_scroll_offset(offset);
+                }
+                scroll_area.show_rows(ui, row_height, filtered_tracks.len(), |ui, row_range| {
+                    for i in row_range {
+                        match filtered_tracks.get(i) {
+                            Some((original_index, PlaylistEntry::Track(track))) => {
+                                self.render_track_item(ui, track, *original_index)
                             }
+                            Some((original_index, PlaylistEntry::Unavailable { name, reason })) => {
+                                Self::render_unavailable_item(
+                                    ui,
+                                    name.as_deref(),
+                                    *reason,
+                                    *original_index,
+                                )
+                            }
+                            None => {}
                         }
-                    },
+                    }
+                });
+            }
+        });
+    }
+
+    // 曲目依名稱的第一個字母分組，非英文字母的名稱歸在 #
+    fn render_jump_to_letter(&mut self, ui: &mut egui::Ui, names: &[&str]) {
+        let index_key = |name: &str| match name.trim_start().chars().next() {
+            Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase(),
+            _ => '#',
+        };
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.0;
+            ui.label("跳至:");
+            for key in std::iter::once('#').chain('A'..='Z') {
+                let first_row = names.iter().position(|name| index_key(name) == key);
+                let response = ui.add_enabled(
+                    first_row.is_some(),
+                    egui::Button::new(key.to_string()).small(),
                 );
+                if let (true, Some(row)) = (response.clicked(), first_row) {
+                    self.jump_to_row = Some(row);
+                }
             }
         });
+        ui.add_space(5.0);
+    }
+
+    // 本機檔案、已下架的曲目與 Podcast 單集以灰色顯示，沒有搜尋按鈕
+    fn render_unavailable_item(
+        ui: &mut egui::Ui,
+        name: Option<&str>,
+        reason: UnavailableReason,
+        index: usize,
+    ) {
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            let color = ui.visuals().weak_text_color();
+            ui.add(
+                egui::Label::new(
+                    egui::RichText::new(format!("{}.", index + 1))
+                        .size(18.0)
+                        .color(color),
+                )
+                .wrap(false),
+            );
+            ui.add_space(10.0);
+            ui.vertical(|ui| {
+                ui.label(
+                    egui::RichText::new(name.unwrap_or("無法取得的項目"))
+                        .size(18.0)
+                        .color(color),
+                );
+                ui.label(
+                    egui::RichText::new(format!("無法取得的項目 · {}", reason.label()))
+                        .size(16.0)
+                        .italics()
+                        .color(color),
+                );
+            });
+        });
+    }
+
+    // 依配對記憶與下載紀錄判斷曲目是否已經收集過譜面
+    fn track_match_badge(&self, track: &FullTrack) -> Option<TrackMatchBadge> {
+        let track_id = track.id.as_ref()?;
+        let beatmapset_id = confirmed_beatmapset(track_id.id())?;
+        let completed = matches!(
+            self.beatmapset_download_statuses
+                .lock()
+                .unwrap()
+                .get(&beatmapset_id),
+            Some(DownloadStatus::Completed)
+        );
+        if completed || has_download_record(beatmapset_id) {
+            Some(TrackMatchBadge::Downloaded)
+        } else {
+            Some(TrackMatchBadge::Matched)
+        }
     }
 
     fn render_track_item(&mut self, ui: &mut egui::Ui, track: &FullTrack, index: usize) {
         ui.add_space(5.0);
         ui.horizontal(|ui| {
+            if self.batch_like.show {
+                if let Some(track_id) = &track.id {
+                    self.display_batch_like_checkbox(ui, track_id.id());
+                }
+            }
             ui.add(
                 egui::Label::new(egui::RichText::new(format!("{}.", index + 1)).size(18.0))
                     .wrap(false),
@@ -4036,7 +10062,23 @@ impl SearchApp {
                     .map(|a| a.name.clone())
                     .collect::<Vec<_>>()
                     .join(", ");
-                ui.label(egui::RichText::new(artists).size(16.0).weak());
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(artists).size(16.0).weak());
+                    match self.track_match_badge(track) {
+                        Some(TrackMatchBadge::Downloaded) => {
+                            ui.label(
+                                egui::RichText::new("✔ 已下載")
+                                    .small()
+                                    .color(egui::Color32::from_rgb(100, 200, 100)),
+                            );
+                        }
+                        Some(TrackMatchBadge::Matched) => {
+                            ui.label(egui::RichText::new("🔗 已配對").small().weak())
+                                .on_hover_text("已記住配對的譜面，但尚未下載");
+                        }
+                        None => {}
+                    }
+                });
             });
     
             // 搜尋按鈕
@@ -4078,15 +10120,14 @@ impl SearchApp {
         let spotify_client = self.spotify_client.clone();
         let user_playlists = self.spotify_user_playlists.clone();
         let ctx = self.ctx.clone();
-        let cache_path = get_app_data_path().join("playlists_cache.json");
-
         tokio::spawn(async move {
             match get_user_playlists(spotify_client).await {
                 Ok(playlists) => {
+                    refresh_pinned_names(&playlists);
                     *user_playlists.lock().unwrap() = playlists.clone();
                     // 將播放列表緩存保存到文件
                     if let Err(e) =
-                        fs::write(&cache_path, serde_json::to_string(&playlists).unwrap())
+                        write_json_cache("playlists_cache.json".to_string(), playlists).await
                     {
                         error!("保存播放列表緩存失敗: {:?}", e);
                     }
@@ -4099,31 +10140,75 @@ impl SearchApp {
         });
     }
 
+    // 優先使用播放清單快取，沒有快取時才取得曲目並寫入快取，開啟播放清單時可直接使用
+    fn playlist_summary(&self, ctx: &egui::Context, playlist_id: &str) -> Option<PlaylistSummary> {
+        let mut summaries = self.playlist_summaries.lock().unwrap();
+        if let Some(summary) = summaries.get(playlist_id) {
+            return *summary;
+        }
+        summaries.insert(playlist_id.to_string(), None);
+
+        let summaries = self.playlist_summaries.clone();
+        let spotify_client = self.spotify_client.clone();
+        let playlist_id = playlist_id.to_string();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let cache_name = format!("playlist_{}_cache.json", playlist_id);
+            let cached = read_json_cache::<PlaylistCache>(cache_name.clone(), Arc::default()).await;
+            let summary = match cached {
+                Some(cache) => cache.summary(),
+                None => match get_playlist_tracks(spotify_client, playlist_id.clone()).await {
+                    Ok(tracks) => {
+                        let cache = PlaylistCache {
+                            tracks,
+                            last_updated: SystemTime::now(),
+                        };
+                        let summary = cache.summary();
+                        if let Err(e) = write_json_cache(cache_name, cache).await {
+                            error!("保存播放列表緩存失敗: {:?}", e);
+                        }
+                        summary
+                    }
+                    Err(e) => {
+                        warn!("取得播放清單 {} 的總長度失敗: {:?}", playlist_id, e);
+                        return;
+                    }
+                },
+            };
+            summaries.lock().unwrap().insert(playlist_id, Some(summary));
+            ctx.request_repaint();
+        });
+        None
+    }
+
     fn load_playlist_tracks(&self, playlist_id: PlaylistId) {
         let spotify_client = self.spotify_client.clone();
         let playlist_tracks = self.spotify_playlist_tracks.clone();
         let ctx = self.ctx.clone();
         let is_searching = self.is_searching.clone();
         let playlist_id_string = playlist_id.id().to_string();
-        let cache_ttl = self.cache_ttl;
         let update_check_result = self.update_check_result.clone();
-        let cache_path =
-            get_app_data_path().join(format!("playlist_{}_cache.json", playlist_id_string));
+        let cache_name = format!("playlist_{}_cache.json", playlist_id_string);
+        let cache_progress = self.cache_progress.clone();
+        let playlist_summaries = self.playlist_summaries.clone();
+        let client = self.client.clone();
+        let auto_download_target = AutoDownloadTarget {
+            download_directory: self.download_directory.clone(),
+            download_queue_sender: self.download_queue_sender.clone(),
+            download_statuses: self.beatmapset_download_statuses.clone(),
+        };
+        let debug_mode = self.debug_mode;
+
+        let should_update = self.cache_manager.is_stale(&cache_name);
 
         tokio::spawn(async move {
             is_searching.store(true, Ordering::SeqCst);
 
-            let should_update = if let Ok(metadata) = fs::metadata(&cache_path) {
-                metadata.modified().unwrap().elapsed().unwrap() > cache_ttl
-            } else {
-                true
-            };
-
             // 檢查是否有更新
             let has_updates = {
                 let spotify_option = spotify_client.lock().unwrap().clone();
                 if let Some(spotify) = spotify_option {
-                    match Self::check_for_updates(&spotify, &cache_path).await {
+                    match Self::check_for_updates(&spotify, &cache_name).await {
                         Ok(updates) => updates,
                         Err(e) => {
                             error!("檢查更新時發生錯誤: {:?}", e);
@@ -4137,19 +10222,42 @@ impl SearchApp {
 
             if should_update || has_updates {
                 info!("正在更新播放列表 {} 的緩存", playlist_id_string);
+                // 自動下載只處理與上次快取相比新加入的曲目，沒有快取時不處理
+                let previous_tracks = if is_auto_download(&playlist_id_string) {
+                    read_json_cache::<PlaylistCache>(cache_name.clone(), Arc::default())
+                        .await
+                        .map(|cached| cached.tracks)
+                } else {
+                    None
+                };
 
                 match get_playlist_tracks(spotify_client.clone(), playlist_id_string.clone()).await
                 {
                     Ok(tracks) => {
+                        if let Some(previous_tracks) = previous_tracks {
+                            let added = new_tracks(&previous_tracks, &tracks);
+                            if !added.is_empty() {
+                                let client = client.lock().await.clone();
+                                tokio::spawn(auto_download_new_tracks(
+                                    client,
+                                    playlist_id_string.clone(),
+                                    added,
+                                    auto_download_target,
+                                    debug_mode,
+                                ));
+                            }
+                        }
                         let tracks_len = tracks.len();
                         *playlist_tracks.lock().unwrap() = tracks.clone();
                         let cache = PlaylistCache {
                             tracks,
                             last_updated: SystemTime::now(),
                         };
-                        if let Err(e) =
-                            fs::write(&cache_path, serde_json::to_string(&cache).unwrap())
-                        {
+                        playlist_summaries
+                            .lock()
+                            .unwrap()
+                            .insert(playlist_id_string.clone(), Some(cache.summary()));
+                        if let Err(e) = write_json_cache(cache_name.clone(), cache).await {
                             error!("保存播放列表緩存失敗: {:?}", e);
                         }
                         info!(
@@ -4162,15 +10270,15 @@ impl SearchApp {
                     }
                 }
             } else {
-                if let Ok(cached_data) = fs::read_to_string(&cache_path) {
-                    if let Ok(cached) = serde_json::from_str::<PlaylistCache>(&cached_data) {
-                        *playlist_tracks.lock().unwrap() = cached.tracks;
-                        info!(
-                            "使用緩存的播放列表曲目，播放列表 ID: {}, 曲目數量: {}",
-                            playlist_id_string,
-                            playlist_tracks.lock().unwrap().len()
-                        );
-                    }
+                if let Some(cached) =
+                    read_json_cache::<PlaylistCache>(cache_name.clone(), cache_progress).await
+                {
+                    *playlist_tracks.lock().unwrap() = cached.tracks;
+                    info!(
+                        "使用緩存的播放列表曲目，播放列表 ID: {}, 曲目數量: {}",
+                        playlist_id_string,
+                        playlist_tracks.lock().unwrap().len()
+                    );
                 }
             }
 
@@ -4185,24 +10293,20 @@ impl SearchApp {
         let liked_tracks = self.spotify_liked_tracks.clone();
         let is_searching = self.is_searching.clone();
         let ctx = self.ctx.clone();
-        let cache_ttl = self.cache_ttl;
         let update_check_result = self.update_check_result.clone();
-        let cache_path = get_app_data_path().join("liked_tracks_cache.json");
+        let cache_name = "liked_tracks_cache.json".to_string();
+        let cache_progress = self.cache_progress.clone();
+
+        let should_update = self.cache_manager.is_stale(&cache_name);
 
         tokio::spawn(async move {
             is_searching.store(true, Ordering::SeqCst);
 
-            let should_update = if let Ok(metadata) = fs::metadata(&cache_path) {
-                metadata.modified().unwrap().elapsed().unwrap() > cache_ttl
-            } else {
-                true
-            };
-
             // 檢查是否有更新
             let has_updates = {
                 let spotify_option = spotify_client.lock().unwrap().clone();
                 if let Some(spotify) = spotify_option {
-                    match Self::check_for_updates(&spotify, &cache_path).await {
+                    match Self::check_for_updates(&spotify, &cache_name).await {
                         Ok(updates) => updates,
                         Err(e) => {
                             error!("檢查更新時發生錯誤: {:?}", e);
@@ -4216,56 +10320,48 @@ impl SearchApp {
 
             if should_update || has_updates {
                 info!("正在更新喜歡的曲目緩存");
-                let mut all_tracks = Vec::new();
                 let spotify_option = spotify_client.lock().unwrap().clone();
 
                 if let Some(spotify) = spotify_option {
-                    let mut offset = 0;
-                    loop {
-                        match spotify
-                            .current_user_saved_tracks_manual(None, Some(50), Some(offset))
-                            .await
-                        {
-                            Ok(page) => {
-                                let page_items_len = page.items.len();
-                                all_tracks.extend(
-                                    page.items.into_iter().map(|saved_track| saved_track.track),
-                                );
-
-                                if page.next.is_none() {
-                                    break;
-                                }
-                                offset += page_items_len as u32;
-                            }
-                            Err(e) => {
-                                error!("獲取用戶喜歡的曲目失敗: {:?}", e);
-                                break;
+                    // 任一分頁失敗時保留原本的快取，不寫入不完整的列表
+                    match get_liked_tracks(&spotify).await {
+                        Ok(all_tracks) => {
+                            *liked_tracks.lock().unwrap() = all_tracks.clone();
+                            let cache = PlaylistCache {
+                                tracks: all_tracks
+                                    .iter()
+                                    .cloned()
+                                    .map(PlaylistEntry::Track)
+                                    .collect(),
+                                last_updated: SystemTime::now(),
+                            };
+                            if let Err(e) = write_json_cache(cache_name.clone(), cache).await {
+                                error!("保存喜歡的曲目緩存失敗: {:?}", e);
                             }
-                        }
-                    }
 
-                    *liked_tracks.lock().unwrap() = all_tracks.clone();
-                    let cache = PlaylistCache {
-                        tracks: all_tracks.clone(),
-                        last_updated: SystemTime::now(),
-                    };
-                    if let Err(e) = fs::write(&cache_path, serde_json::to_string(&cache).unwrap()) {
-                        error!("保存喜歡的曲目緩存失敗: {:?}", e);
+                            info!("成功更新緩存並加載 {} 首喜歡的曲目", all_tracks.len());
+                        }
+                        Err(e) => {
+                            error!("獲取用戶喜歡的曲目失敗: {:?}", e);
+                        }
                     }
-
-                    info!("成功更新緩存並加載 {} 首喜歡的曲目", all_tracks.len());
                 } else {
                     error!("Spotify 客戶端未初始化");
                 }
             } else {
-                if let Ok(cached_data) = fs::read_to_string(&cache_path) {
-                    if let Ok(cached) = serde_json::from_str::<PlaylistCache>(&cached_data) {
-                        *liked_tracks.lock().unwrap() = cached.tracks;
-                        info!(
-                            "使用緩存的喜歡的曲目，曲目數量: {}",
-                            liked_tracks.lock().unwrap().len()
-                        );
-                    }
+                if let Some(cached) =
+                    read_json_cache::<PlaylistCache>(cache_name.clone(), cache_progress).await
+                {
+                    *liked_tracks.lock().unwrap() = cached
+                        .tracks
+                        .iter()
+                        .filter_map(PlaylistEntry::track)
+                        .cloned()
+                        .collect();
+                    info!(
+                        "使用緩存的喜歡的曲目，曲目數量: {}",
+                        liked_tracks.lock().unwrap().len()
+                    );
                 }
             }
 
@@ -4277,31 +10373,34 @@ impl SearchApp {
 
     async fn check_for_updates(
         spotify: &AuthCodeSpotify,
-        cache_path: &PathBuf,
+        cache_name: &str,
     ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
         let mut has_updates = false;
 
-        if cache_path.file_name().unwrap() == "liked_tracks_cache.json" {
+        if cache_name == "liked_tracks_cache.json" {
             // 檢查 Liked Songs 是否有更新
-            let liked_songs = spotify
-                .current_user_saved_tracks_manual(None, Some(1), Some(0))
-                .await?;
-            if let Ok(cached_data) = fs::read_to_string(cache_path) {
-                if let Ok(cached) = serde_json::from_str::<PlaylistCache>(&cached_data) {
-                    if liked_songs.total != cached.tracks.len() as u32 {
-                        has_updates = true;
-                        info!(
-                            "Liked Songs 有更新: API 返回 {} 首歌曲，緩存中有 {} 首歌曲",
-                            liked_songs.total,
-                            cached.tracks.len()
-                        );
-                    } else {
-                        info!(
-                            "Liked Songs 沒有更新: API 返回 {} 首歌曲，緩存中有 {} 首歌曲",
-                            liked_songs.total,
-                            cached.tracks.len()
-                        );
-                    }
+            let liked_songs = traced(
+                "Spotify",
+                "current_user_saved_tracks_manual",
+                spotify.current_user_saved_tracks_manual(None, Some(1), Some(0)),
+            )
+            .await?;
+            if let Some(cached) =
+                read_json_cache::<PlaylistCache>(cache_name.to_string(), Arc::default()).await
+            {
+                if liked_songs.total != cached.tracks.len() as u32 {
+                    has_updates = true;
+                    info!(
+                        "Liked Songs 有更新: API 返回 {} 首歌曲，緩存中有 {} 首歌曲",
+                        liked_songs.total,
+                        cached.tracks.len()
+                    );
+                } else {
+                    info!(
+                        "Liked Songs 沒有更新: API 返回 {} 首歌曲，緩存中有 {} 首歌曲",
+                        liked_songs.total,
+                        cached.tracks.len()
+                    );
                 }
             } else {
                 info!("Liked Songs 緩存不存在");
@@ -4309,34 +10408,33 @@ impl SearchApp {
             }
         } else {
             // 檢查播放列表是否有更新
-            let playlist_id = cache_path
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .replace("playlist_", "")
-                .replace("_cache", "");
-            let playlist = spotify
-                .playlist(PlaylistId::from_id(&playlist_id).unwrap(), None, None)
-                .await?;
-            if let Ok(cached_data) = fs::read_to_string(cache_path) {
-                if let Ok(cached) = serde_json::from_str::<PlaylistCache>(&cached_data) {
-                    if playlist.tracks.total != cached.tracks.len() as u32 {
-                        has_updates = true;
-                        info!(
-                            "播放列表 {} 有更新: API 返回 {} 首歌曲，緩存中有 {} 首歌曲",
-                            playlist.name,
-                            playlist.tracks.total,
-                            cached.tracks.len()
-                        );
-                    } else {
-                        info!(
-                            "播放列表 {} 沒有更新: API 返回 {} 首歌曲，緩存中有 {} 首歌曲",
-                            playlist.name,
-                            playlist.tracks.total,
-                            cached.tracks.len()
-                        );
-                    }
+            let playlist_id = cache_name
+                .trim_start_matches("playlist_")
+                .trim_end_matches("_cache.json");
+            let playlist = traced(
+                "Spotify",
+                "playlist",
+                spotify.playlist(PlaylistId::from_id(playlist_id).unwrap(), None, None),
+            )
+            .await?;
+            if let Some(cached) =
+                read_json_cache::<PlaylistCache>(cache_name.to_string(), Arc::default()).await
+            {
+                if playlist.tracks.total != cached.tracks.len() as u32 {
+                    has_updates = true;
+                    info!(
+                        "播放列表 {} 有更新: API 返回 {} 首歌曲，緩存中有 {} 首歌曲",
+                        playlist.name,
+                        playlist.tracks.total,
+                        cached.tracks.len()
+                    );
+                } else {
+                    info!(
+                        "播放列表 {} 沒有更新: API 返回 {} 首歌曲，緩存中有 {} 首歌曲",
+                        playlist.name,
+                        playlist.tracks.total,
+                        cached.tracks.len()
+                    );
                 }
             } else {
                 info!("播放列表 {} 緩存不存在", playlist.name);
@@ -4370,7 +10468,9 @@ impl SearchApp {
             match current_playing {
                 Some(current_playing) => {
                     ui.horizontal(|ui| {
-                        if let Some(spotify_icon) = &self.spotify_icon {
+                        if let Some(spotify_icon) =
+                            self.preloaded_icons.get("spotify_icon_black.png")
+                        {
                             let size = egui::vec2(24.0, 24.0);
                             ui.add(egui::Image::new(egui::load::SizedTexture::new(
                                 spotify_icon.id(),
@@ -4470,7 +10570,7 @@ impl SearchApp {
                     .create_auth_button(ui, &button_text, "spotify_icon_black.png")
                     .clicked()
                 {
-                    self.logout_spotify();
+                    self.session_manager.request_logout();
                     ui.close_menu();
                 }
             } else {
@@ -4550,11 +10650,21 @@ impl SearchApp {
         });
     }
 
-    fn logout_spotify(&mut self) {
+    fn logout_spotify(&mut self, mode: LogoutMode) {
         info!("用戶登出 Spotify");
+        // 先取得帳號資料，之後才重置介面狀態
+        let session = SpotifySession {
+            client: self.spotify_client.lock().unwrap().take(),
+            user_name: self.spotify_user_name.lock().unwrap().take(),
+        };
+        self.session_manager
+            .end_spotify_session(session, mode, &self.cache_manager);
+        if mode == LogoutMode::ClearAll {
+            self.cache_sizes = None;
+        }
+
         self.spotify_authorized.store(false, Ordering::SeqCst);
         *self.spotify_user_avatar.lock().unwrap() = None;
-        *self.spotify_user_name.lock().unwrap() = None;
         *self.spotify_user_avatar_url.lock().unwrap() = None;
         self.need_reload_avatar.store(true, Ordering::SeqCst);
         self.show_spotify_now_playing = false;
@@ -4563,29 +10673,11 @@ impl SearchApp {
         *self.currently_playing.lock().unwrap() = None;
         self.spotify_track_liked_status.lock().unwrap().clear();
 
-        // 重置 Spotify 客戶端
-        if let Ok(mut spotify_client) = self.spotify_client.try_lock() {
-            *spotify_client = None;
-        }
-
         // 重置授權管理器
         self.auth_manager.reset(&AuthPlatform::Spotify);
         self.auth_start_time = None;
         self.auth_in_progress.store(false, Ordering::SeqCst);
         self.show_auth_progress = false;
-
-        // 刪除 login_info.json 文件
-        let file_path = get_app_data_path().join("login_info.json");
-        if let Err(e) = std::fs::remove_file(file_path) {
-            error!("刪除 login_info.json 失敗: {}", e);
-        }
-        // 刪除使用者頭像
-        if let Some(user_name) = self.spotify_user_name.lock().unwrap().as_ref() {
-            let avatar_path = Self::get_avatar_path(user_name);
-            if let Err(e) = std::fs::remove_file(avatar_path) {
-                error!("刪除使用者頭像失敗: {}", e);
-            }
-        }
     }
 
     fn render_guest_user(&mut self, ui: &mut egui::Ui) {
@@ -4664,117 +10756,24 @@ impl SearchApp {
                     egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
                     egui::Color32::WHITE, // 使用白色以確保圖標可見
                 );
-                content_rect.min.x += icon_size.x + spacing;
-            }
-
-            // 繪製文字
-            let galley = ui.painter().layout_no_wrap(
-                text.to_owned(),
-                egui::FontId::new(16.0, egui::FontFamily::Proportional),
-                visuals.text_color(),
-            );
-            let text_pos = content_rect.left_center() - egui::vec2(0.0, galley.size().y / 2.0);
-            ui.painter().galley(text_pos, galley, visuals.text_color());
-
-            // 為了調試，繪製邊框
-            ui.painter()
-                .rect_stroke(rect, visuals.rounding, visuals.fg_stroke);
-        }
-
-        response
-    }
-
-    fn load_icon(ctx: &egui::Context, icon_path: &str) -> Option<egui::TextureHandle> {
-        let icon_bytes: &[u8] = match icon_path {
-            "spotify_icon_black.png" => {
-                info!("嘗試加載 Spotify 圖標");
-                include_bytes!("assets/spotify_icon_black.png")
-            }
-            "osu!logo.png" => {
-                info!("嘗試加載 Osu 圖標");
-                include_bytes!("assets/osu!logo.png")
-            }
-            "Spotify_Full_Logo_RGB_White.png" => {
-                info!("嘗試加載 Spotify 完整 White logo");
-                include_bytes!("assets/Spotify_Full_Logo_RGB_White.png")
-            }
-            "Spotify_Full_Logo_RGB_Black.png" => {
-                info!("嘗試加載 Spotify 完整 Black logo");
-                include_bytes!("assets/Spotify_Full_Logo_RGB_Black.png")
-            }
-            "osu!logo@2x.png" => {
-                info!("嘗試加載 osu!logo@2x.png");
-                include_bytes!("assets/osu!logo@2x.png")
-            }
-            "search.png" => {
-                info!("嘗試加載 search.png");
-                include_bytes!("assets/search.png")
-            }
-            "like.png" => {
-                info!("嘗試加載 like.png");
-                include_bytes!("assets/like.png")
-            }
-            "liked.png" => {
-                info!("嘗試加載 liked.png");
-                include_bytes!("assets/liked.png")
-            }
-            "expand_on.png" => {
-                info!("嘗試加載 expand_on.png");
-                include_bytes!("assets/expand_on.png")
-            }
-            "expand_off.png" => {
-                info!("嘗試加載 expand_off.png");
-                include_bytes!("assets/expand_off.png")
-            }
-            "play.png" => {
-                info!("嘗試加載 play.png");
-                include_bytes!("assets/play.png")
-            }
-            "pause.png" => {
-                info!("嘗試加載 pause.png");
-                include_bytes!("assets/pause.png")
-            }
-            "download.png" => {
-                info!("嘗試加載 download.png");
-                include_bytes!("assets/download.png")
-            }
-            "delete.png" => {
-                info!("嘗試加載 delete.png");
-                include_bytes!("assets/delete.png")
-            }
-            "downloading.png" => {
-                info!("嘗試加載 downloading.png");
-                include_bytes!("assets/downloading.png")
-            }
-            "background1.jpg" => {
-                info!("嘗試加載 background.jpg");
-                include_bytes!("assets/background1.jpg")
-            }
-            "background_light2.jpg" => {
-                info!("嘗試加載 background_light2.jpg");
-                include_bytes!("assets/background_light2.jpg")
-            }
-            _ => {
-                error!("未知的圖標路徑: {}", icon_path);
-                return None;
+                content_rect.min.x += icon_size.x + spacing;
             }
-        };
 
-        match image::load_from_memory(icon_bytes) {
-            Ok(image) => {
-                let image = image.to_rgba8();
-                let size = [image.width() as _, image.height() as _];
-                let pixels = image.as_flat_samples();
-                let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
+            // 繪製文字
+            let galley = ui.painter().layout_no_wrap(
+                text.to_owned(),
+                egui::FontId::new(16.0, egui::FontFamily::Proportional),
+                visuals.text_color(),
+            );
+            let text_pos = content_rect.left_center() - egui::vec2(0.0, galley.size().y / 2.0);
+            ui.painter().galley(text_pos, galley, visuals.text_color());
 
-                info!("成功加載圖標: {}", icon_path);
-                Some(ctx.load_texture(icon_path, color_image, egui::TextureOptions::default()))
-            }
-            Err(e) => {
-                error!("無法加載圖標 {}: {:?}", icon_path, e);
-                None
-            }
+            // 為了調試，繪製邊框
+            ui.painter()
+                .rect_stroke(rect, visuals.rounding, visuals.fg_stroke);
         }
+
+        response
     }
 
     // 渲染中央面板
@@ -4784,7 +10783,7 @@ impl SearchApp {
 
             // 選擇背景圖片
             let background_image = if let Some(custom_bg) = &self.custom_background {
-                custom_bg.clone()
+                Some(custom_bg.clone())
             } else {
                 // 使用預設背景的邏輯保持不變
                 if ui.visuals().dark_mode {
@@ -4793,28 +10792,19 @@ impl SearchApp {
                     self.last_background_key = "background_light2.jpg".to_string();
                 }
 
-                self.preloaded_icons
-                    .get(&self.last_background_key)
-                    .cloned()
-                    .unwrap_or_else(|| {
-                        Self::load_icon(ctx, &self.last_background_key).unwrap_or_else(|| {
-                            error!("無法加載背景圖片");
-                            ctx.load_texture(
-                                "background_fallback",
-                                egui::ColorImage::example(),
-                                Default::default(),
-                            )
-                        })
-                    })
+                // 背景仍在背景執行緒解碼時，先只顯示面板底色
+                self.preloaded_icons.get(&self.last_background_key).cloned()
             };
 
             // 渲染背景圖片
-            ui.painter().image(
-                background_image.id(),
-                available_rect,
-                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                egui::Color32::from_rgba_unmultiplied(255, 255, 255, 180),
-            );
+            if let Some(background_image) = background_image {
+                ui.painter().image(
+                    background_image.id(),
+                    available_rect,
+                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    egui::Color32::from_rgba_unmultiplied(255, 255, 255, 180),
+                );
+            }
 
             // 根據主題選擇遮罩顏色
             let mask_color = if ui.visuals().dark_mode {
@@ -4837,7 +10827,13 @@ impl SearchApp {
                     self.display_error_message(ui);
 
                     // 根據視窗大小決定佈局
-                    if window_size.x >= 1000.0 {
+                    if self.osu_helper.show {
+                        self.render_osu_helper(ui);
+                    } else if self.batch_import.show {
+                        self.render_batch_import(ui);
+                    } else if self.playlist_builder.show {
+                        self.render_playlist_builder(ui);
+                    } else if window_size.x >= 1000.0 {
                         self.render_large_window_layout(ui, window_size);
                     } else {
                         self.render_small_window_layout(ui, window_size);
@@ -4846,6 +10842,224 @@ impl SearchApp {
         });
     }
 
+    fn render_batch_import(&mut self, ui: &mut egui::Ui) {
+        if ui.button("← 返回搜尋").clicked() {
+            self.batch_import.show = false;
+            return;
+        }
+
+        let download_statuses = self.cached_download_statuses(self.batch_import.candidate_ids());
+
+        let download_requests = self.batch_import.render(ui, &download_statuses);
+        if !download_requests.is_empty() {
+            self.enqueue_beatmap_downloads("批次下載", download_requests);
+            ui.ctx().request_repaint();
+        }
+    }
+
+    fn render_lastfm_page(&mut self, ui: &mut egui::Ui) {
+        let download_statuses = self.cached_download_statuses(self.lastfm.matched_ids());
+
+        ui.vertical(|ui| {
+            ui.set_width(BASE_SIDE_MENU_WIDTH);
+            let download_requests = self.lastfm.render(ui, &download_statuses, self.debug_mode);
+            if !download_requests.is_empty() {
+                self.enqueue_beatmap_downloads("Last.fm 下載", download_requests);
+                ui.ctx().request_repaint();
+            }
+        });
+    }
+
+    fn render_release_radar_page(&mut self, ui: &mut egui::Ui) {
+        let download_statuses = self.cached_download_statuses(self.release_radar.matched_ids());
+
+        ui.vertical(|ui| {
+            ui.set_width(BASE_SIDE_MENU_WIDTH);
+            let download_requests = self.release_radar.render(
+                ui,
+                &self.spotify_client,
+                &download_statuses,
+                self.debug_mode,
+            );
+            if !download_requests.is_empty() {
+                self.enqueue_beatmap_downloads("新發行下載", download_requests);
+                ui.ctx().request_repaint();
+            }
+        });
+    }
+
+    fn render_followed_artists_page(&mut self, ui: &mut egui::Ui) {
+        let download_statuses = self.cached_download_statuses(self.followed_artists.matched_ids());
+
+        ui.vertical(|ui| {
+            ui.set_width(BASE_SIDE_MENU_WIDTH);
+            let download_requests = self.followed_artists.render(
+                ui,
+                &self.spotify_client,
+                &download_statuses,
+                self.debug_mode,
+            );
+            if !download_requests.is_empty() {
+                self.enqueue_beatmap_downloads("追蹤歌手下載", download_requests);
+                ui.ctx().request_repaint();
+            }
+        });
+    }
+
+    fn render_scripts_page(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.set_width(BASE_SIDE_MENU_WIDTH);
+            let download_requests =
+                self.scripts
+                    .render(ui, &self.download_directory, self.debug_mode);
+            if !download_requests.is_empty() {
+                self.enqueue_beatmap_downloads("腳本下載", download_requests);
+                ui.ctx().request_repaint();
+            }
+        });
+    }
+
+    fn render_osu_favourites_page(&mut self, ui: &mut egui::Ui) {
+        let download_statuses = self.cached_download_statuses(self.osu_favourites.beatmapset_ids());
+        let playlists: Vec<(String, String)> = self
+            .spotify_user_playlists
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|playlist| (playlist.id.id().to_string(), playlist.name.clone()))
+            .collect();
+
+        let actions = ui
+            .vertical(|ui| {
+                ui.set_width(BASE_SIDE_MENU_WIDTH);
+                self.osu_favourites
+                    .render(ui, &download_statuses, &playlists, self.debug_mode)
+            })
+            .inner;
+
+        for action in actions {
+            match action {
+                FavouritesAction::Download(beatmapset_id) => {
+                    self.enqueue_beatmap_download(beatmapset_id);
+                }
+                FavouritesAction::LoadPlaylists => {
+                    self.load_user_playlists();
+                }
+                FavouritesAction::AddToPlaylist {
+                    playlist_id,
+                    track_ids,
+                } => {
+                    let spotify_client = self.spotify_client.clone();
+                    let message = self.osu_favourites.message_handle();
+                    let ctx = ui.ctx().clone();
+                    *message.lock().unwrap() = Some("正在加入播放清單...".to_string());
+                    tokio::spawn(async move {
+                        let result =
+                            add_tracks_to_playlist(spotify_client, playlist_id, track_ids).await;
+                        *message.lock().unwrap() = Some(match result {
+                            Ok(count) => {
+                                info!("已將 {} 首曲目加入播放清單", count);
+                                format!("已加入 {} 首曲目", count)
+                            }
+                            Err(e) => {
+                                error!("加入播放清單失敗: {:?}", e);
+                                format!("加入失敗（可能需要重新登入 Spotify）: {}", e)
+                            }
+                        });
+                        ctx.request_repaint();
+                    });
+                }
+            }
+        }
+    }
+
+    fn render_playlist_builder(&mut self, ui: &mut egui::Ui) {
+        if ui.button("← 返回搜尋").clicked() {
+            self.playlist_builder.show = false;
+            return;
+        }
+
+        let playlists: Vec<(String, String)> = self
+            .spotify_user_playlists
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|playlist| (playlist.id.id().to_string(), playlist.name.clone()))
+            .collect();
+
+        for action in self
+            .playlist_builder
+            .render(ui, &playlists, self.debug_mode)
+        {
+            match action {
+                PlaylistBuilderAction::LoadPlaylists => self.load_user_playlists(),
+                PlaylistBuilderAction::Submit { target, track_ids } => {
+                    let spotify_client = self.spotify_client.clone();
+                    let user_playlists = self.spotify_user_playlists.clone();
+                    let message = self.playlist_builder.message_handle();
+                    let is_submitting = self.playlist_builder.submitting_handle();
+                    let ctx = ui.ctx().clone();
+
+                    tokio::spawn(async move {
+                        let result: Result<usize> = async {
+                            let playlist_id = match target {
+                                PlaylistTarget::New { name } => {
+                                    create_playlist(
+                                        spotify_client.clone(),
+                                        name,
+                                        Some("由 osu! 譜面建立".to_string()),
+                                    )
+                                    .await?
+                                }
+                                PlaylistTarget::Existing { playlist_id } => playlist_id,
+                            };
+                            add_tracks_to_playlist(spotify_client.clone(), playlist_id, track_ids)
+                                .await
+                        }
+                        .await;
+
+                        let text = match result {
+                            Ok(count) => {
+                                info!("已將 {} 首曲目加入播放清單", count);
+                                // 重新載入播放清單，讓新建立的清單出現在列表中
+                                if let Ok(playlists) = get_user_playlists(spotify_client).await {
+                                    *user_playlists.lock().unwrap() = playlists;
+                                }
+                                format!("完成：已加入 {} 首曲目", count)
+                            }
+                            Err(e) => {
+                                error!("建立播放清單失敗: {:?}", e);
+                                format!("失敗（可能需要重新登入 Spotify）: {}", e)
+                            }
+                        };
+                        *message.lock().unwrap() = Some(text);
+                        is_submitting.store(false, Ordering::SeqCst);
+                        ctx.request_repaint();
+                    });
+                }
+            }
+        }
+    }
+
+    fn render_osu_helper(&mut self, ui: &mut egui::Ui) {
+        let download_statuses: HashMap<i32, DownloadStatus> = self
+            .osu_helper
+            .recommended_ids()
+            .into_iter()
+            .map(|id| (id, self.get_download_status(id)))
+            .collect();
+
+        if let Some(beatmapset_id) = self.osu_helper.render(
+            ui,
+            &download_statuses,
+            &self.download_directory,
+            self.debug_mode,
+        ) {
+            self.enqueue_beatmap_download(beatmapset_id);
+            ui.ctx().request_repaint();
+        }
+    }
+
     fn render_large_window_layout(&mut self, ui: &mut egui::Ui, window_size: egui::Vec2) {
         ui.horizontal(|ui| {
             ui.add_space(25.0); // 左側增加25間距
@@ -4949,7 +11163,16 @@ impl SearchApp {
         let available_width = ui.available_width();
         let button_width = 30.0;
         let spacing = 5.0;
-        let text_edit_width = available_width - 2.0 * button_width - 2.0 * spacing;
+        let mode_button_width = 50.0;
+        let direct_download_width = 80.0;
+        // 查詢為譜面集網址時，在搜尋按鈕旁提供直接下載
+        let direct_download_id = parse_osu_url(self.search_query.trim())
+            .and_then(|(beatmapset_id, _)| beatmapset_id.parse::<i32>().ok());
+        let mut text_edit_width =
+            available_width - 2.0 * button_width - mode_button_width - 3.0 * spacing;
+        if direct_download_id.is_some() {
+            text_edit_width -= direct_download_width + spacing;
+        }
         let text_edit_height = 32.0;
 
         let search_bar_id = egui::Id::new("search_bar");
@@ -4963,6 +11186,27 @@ impl SearchApp {
             ui.style_mut().spacing.item_spacing.x = spacing;
 
             ui.horizontal(|ui| {
+                // 切換曲目 / 專輯 / 節目搜尋
+                let mode_text = match self.search_mode {
+                    SearchMode::Track => "曲目",
+                    SearchMode::Album => "專輯",
+                    SearchMode::Episode => "節目",
+                };
+                if ui
+                    .add_sized(
+                        [mode_button_width, text_edit_height],
+                        egui::Button::new(mode_text),
+                    )
+                    .on_hover_text("切換 Spotify 搜尋模式")
+                    .clicked()
+                {
+                    self.search_mode = match self.search_mode {
+                        SearchMode::Track => SearchMode::Album,
+                        SearchMode::Album => SearchMode::Episode,
+                        SearchMode::Episode => SearchMode::Track,
+                    };
+                }
+
                 let text_edit = egui::TextEdit::singleline(&mut self.search_query)
                     .id(search_bar_id)
                     .font(egui::FontId::proportional(16.0))
@@ -4975,10 +11219,20 @@ impl SearchApp {
                     ui.add_sized(egui::vec2(text_edit_width, text_edit_height), text_edit);
 
                 if response.changed() {
+                    self.search_suggestions.on_edit(&self.search_query);
                     ctx.request_repaint();
                 }
+                self.search_suggestions.poll(ctx, self.debug_mode);
+                let suggestion = self
+                    .search_suggestions
+                    .render(ui, &response, &self.search_query);
+                if let Some(query) = suggestion {
+                    self.search_query = query;
+                    self.perform_search(ctx.clone());
+                }
 
                 if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    self.search_suggestions.dismiss();
                     self.perform_search(ctx.clone());
                 }
 
@@ -4998,18 +11252,77 @@ impl SearchApp {
                 {
                     self.perform_search(ctx.clone());
                 }
+
+                if let Some(beatmapset_id) = direct_download_id {
+                    let status = self.direct_download_status(beatmapset_id);
+                    let hover_text = match status {
+                        DownloadStatus::Completed => "此譜面已下載",
+                        DownloadStatus::Downloading | DownloadStatus::Waiting => "已在下載隊列中",
+                        DownloadStatus::NotStarted => "不經過搜尋結果，直接將譜面加入下載隊列",
+                    };
+                    if ui
+                        .add_enabled(
+                            status == DownloadStatus::NotStarted,
+                            egui::Button::new("直接下載")
+                                .min_size(egui::vec2(direct_download_width, text_edit_height)),
+                        )
+                        .on_hover_text(hover_text)
+                        .on_disabled_hover_text(hover_text)
+                        .clicked()
+                    {
+                        self.direct_download(beatmapset_id);
+                    }
+                }
             });
         });
     }
 
+    // 只在網址換成其他譜面集或有下載結束時才重新讀取下載目錄
+    fn direct_download_status(&mut self, beatmapset_id: i32) -> DownloadStatus {
+        let downloaded = match self.direct_download_downloaded {
+            Some((cached_id, downloaded)) if cached_id == beatmapset_id => downloaded,
+            _ => {
+                let downloaded = self.is_beatmap_downloaded(beatmapset_id);
+                self.direct_download_downloaded = Some((beatmapset_id, downloaded));
+                downloaded
+            }
+        };
+        if downloaded {
+            return DownloadStatus::Completed;
+        }
+        self.beatmapset_download_statuses
+            .lock()
+            .unwrap()
+            .get(&beatmapset_id)
+            .cloned()
+            .unwrap_or(DownloadStatus::NotStarted)
+    }
+
+    // 直接下載網址中的譜面集，並開啟下載管理顯示進度
+    fn direct_download(&mut self, beatmapset_id: i32) {
+        info!("直接下載譜面: {}", beatmapset_id);
+        self.download_scheduler.mark_activity();
+        self.enqueue_beatmap_download(beatmapset_id);
+        self.show_downloaded_maps = false;
+        self.show_download_manager = true;
+        self.show_side_menu = true;
+    }
+
     fn update_font_size(&mut self, ui: &mut egui::Ui) {
+        // 無障礙設定的字體下限，同時套用到全域字體大小與所有文字樣式
+        let min_font_size = accessibility_options().min_font_size;
+        self.global_font_size = self.global_font_size.max(min_font_size);
+        let font_sizes = (self.global_font_size, min_font_size);
         if ui
-            .memory_mut(|mem| mem.data.get_temp::<f32>(egui::Id::new("global_font_size")))
-            .map_or(true, |old_size| old_size != self.global_font_size)
+            .memory_mut(|mem| {
+                mem.data
+                    .get_temp::<(f32, f32)>(egui::Id::new("global_font_size"))
+            })
+            .map_or(true, |old_sizes| old_sizes != font_sizes)
         {
             ui.memory_mut(|mem| {
                 mem.data
-                    .insert_temp(egui::Id::new("global_font_size"), self.global_font_size)
+                    .insert_temp(egui::Id::new("global_font_size"), font_sizes)
             });
 
             let text_style = egui::TextStyle::Body.resolve(ui.style());
@@ -5018,11 +11331,257 @@ impl SearchApp {
             ui.style_mut()
                 .text_styles
                 .insert(egui::TextStyle::Body, new_text_style);
+            // 以預設大小為基準，調低下限時可以恢復原本的大小
+            let default_styles = egui::Style::default().text_styles;
+            ui.ctx().style_mut(|style| {
+                for (text_style, font_id) in style.text_styles.iter_mut() {
+                    let default_size = default_styles
+                        .get(text_style)
+                        .map_or(font_id.size, |default| default.size);
+                    font_id.size = default_size.max(min_font_size);
+                }
+            });
+        }
+    }
+
+    // 滑鼠停在音量控制上滾動時調整音量，回傳音量是否改變
+    fn handle_volume_scroll(&mut self, ui: &egui::Ui, response: &egui::Response) -> bool {
+        if !response.hovered() {
+            return false;
+        }
+        let scroll = ui.input(|i| i.raw_scroll_delta.y);
+        if scroll == 0.0 {
+            return false;
+        }
+        // 避免外層的捲動區域同時捲動
+        ui.ctx()
+            .input_mut(|i| i.smooth_scroll_delta = egui::Vec2::ZERO);
+        self.global_volume =
+            (self.global_volume + VOLUME_SCROLL_STEP * scroll.signum()).clamp(0.01, 1.0);
+        true
+    }
+
+    fn handle_remote_commands(&mut self) {
+        let commands = match &mut self.api_server {
+            Some(server) => server.poll(),
+            None => return,
+        };
+        for command in commands {
+            match command {
+                RemoteCommand::Search(query) => {
+                    info!("遠端搜尋: {}", query);
+                    self.search_query = query;
+                    self.perform_search(self.ctx.clone());
+                }
+                RemoteCommand::Download(beatmapset_id) => {
+                    info!("遠端下載譜面: {}", beatmapset_id);
+                    self.enqueue_beatmap_download(beatmapset_id);
+                }
+            }
+        }
+    }
+
+    fn handle_media_keys(&mut self) {
+        let actions = match &self.media_keys {
+            Some(listener) => listener.poll(),
+            None => return,
+        };
+        let control_spotify = media_key_options().control_spotify;
+        for action in actions {
+            let has_preview = self.current_previews.try_lock().map_or(false, |previews| {
+                previews.values().any(|sink| !sink.empty())
+            });
+            match action {
+                MediaKeyAction::PlayPause if has_preview || !control_spotify => {
+                    self.toggle_previews()
+                }
+                MediaKeyAction::Stop => self.stop_previews(),
+                _ if control_spotify => self.control_spotify_playback(action),
+                _ => {}
+            }
+        }
+    }
+
+    // 按下 ? 開關快捷鍵說明，列出目前展開的結果可用的操作
+    fn handle_shortcut_help(&mut self, ctx: &egui::Context) {
+        if help_pressed(ctx) {
+            self.show_shortcut_help = !self.show_shortcut_help;
+        }
+        let focused = match self.focused_result {
+            Some(ResultKind::Track) if self.expanded_track_index.is_some() => {
+                Some(ResultKind::Track)
+            }
+            Some(ResultKind::Beatmapset) if self.expanded_beatmapset_index.is_some() => {
+                Some(ResultKind::Beatmapset)
+            }
+            _ => None,
+        };
+        let kinds = match focused {
+            Some(kind) => vec![kind],
+            None => ResultKind::ALL.to_vec(),
+        };
+
+        egui::Window::new("快捷鍵")
+            .open(&mut self.show_shortcut_help)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{}  顯示或隱藏此說明", HELP_TEXT));
+                for kind in kinds {
+                    ui.separator();
+                    ui.label(egui::RichText::new(kind.label()).strong());
+                    egui::Grid::new(("shortcut_help", kind.label()))
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for action in quick_actions(kind) {
+                                ui.label(egui::RichText::new(action.key.name()).code());
+                                ui.label(match action.toggled_label {
+                                    Some(toggled) => format!("{} / {}", action.label, toggled),
+                                    None => action.label.to_string(),
+                                });
+                                ui.end_row();
+                            }
+                        });
+                }
+                if focused.is_none() {
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new("展開搜尋結果的操作列後即可使用以上快捷鍵").weak(),
+                    );
+                }
+            });
+    }
+
+    // 全部暫停時繼續播放，否則暫停所有預覽
+    fn toggle_previews(&mut self) {
+        if let Ok(previews) = self.current_previews.try_lock() {
+            let paused = previews.values().all(|sink| sink.is_paused());
+            for sink in previews.values() {
+                if paused {
+                    sink.play();
+                } else {
+                    sink.pause();
+                }
+            }
+            self.is_beatmap_playing = paused && !previews.is_empty();
         }
     }
 
+    fn stop_previews(&mut self) {
+        if let Ok(mut previews) = self.current_previews.try_lock() {
+            for (_, sink) in previews.drain() {
+                sink.stop();
+            }
+            self.is_beatmap_playing = false;
+        }
+        if let Ok(mut buffers) = self.preview_buffers.try_lock() {
+            buffers.clear();
+        }
+    }
+
+    // 從緩衝的音訊重新建立 Sink，保留原本的暫停狀態
+    fn seek_preview(&self, beatmapset_id: i32, offset: Duration) {
+        let stream_handle = match self.audio_output.as_ref() {
+            Some((_, handle)) => handle.clone(),
+            None => return,
+        };
+        let volume = self.global_volume;
+        let speed = self.preview_speed;
+        let eq = self.preview_eq;
+        let current_previews = self.current_previews.clone();
+        let preview_buffers = self.preview_buffers.clone();
+
+        tokio::spawn(async move {
+            let mut buffers = preview_buffers.lock().await;
+            let buffer = match buffers.get_mut(&beatmapset_id) {
+                Some(buffer) => buffer,
+                None => return,
+            };
+            let mut previews = current_previews.lock().await;
+            let paused = previews
+                .get(&beatmapset_id)
+                .map_or(false, |sink| sink.is_paused());
+            match buffer.build_sink_at(offset, &stream_handle, volume, speed, eq) {
+                Ok(sink) => {
+                    if paused {
+                        sink.pause();
+                    }
+                    if let Some(old_sink) = previews.insert(beatmapset_id, sink) {
+                        old_sink.stop();
+                    }
+                }
+                Err(e) => error!("預覽跳轉失敗: {:?}", e),
+            }
+        });
+    }
+
+    // 展開的譜面集正在預覽時，在按鈕列下方顯示播放進度
+    fn render_preview_seek_bar(&mut self, ui: &mut egui::Ui, beatmapset_id: i32) {
+        let (elapsed, total) = {
+            let previews = match self.current_previews.try_lock() {
+                Ok(previews) => previews,
+                Err(_) => return,
+            };
+            let buffers = match self.preview_buffers.try_lock() {
+                Ok(buffers) => buffers,
+                Err(_) => return,
+            };
+            match (previews.get(&beatmapset_id), buffers.get(&beatmapset_id)) {
+                (Some(sink), Some(buffer)) if !sink.empty() => {
+                    (buffer.elapsed(sink), buffer.total_duration())
+                }
+                _ => return,
+            }
+        };
+
+        let mut position = match self.preview_seek_drag {
+            Some((id, position)) if id == beatmapset_id => position,
+            _ => elapsed.as_secs_f32(),
+        };
+        let format_time = |seconds: f32| {
+            let seconds = seconds as u64;
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(format_time(position));
+            let response = ui
+                .add(egui::Slider::new(&mut position, 0.0..=total.as_secs_f32()).show_value(false));
+            ui.label(format_time(total.as_secs_f32()));
+
+            if response.dragged() {
+                self.preview_seek_drag = Some((beatmapset_id, position));
+            }
+            // 點擊進度條直接跳轉，拖曳時等放開才重新建立來源
+            if response.drag_released() || (response.changed() && !response.dragged()) {
+                self.preview_seek_drag = None;
+                self.seek_preview(beatmapset_id, Duration::from_secs_f32(position));
+            }
+        });
+    }
+
+    fn control_spotify_playback(&self, action: MediaKeyAction) {
+        let spotify = match self.spotify_client.lock().unwrap().clone() {
+            Some(spotify) => spotify,
+            None => return,
+        };
+        tokio::spawn(async move {
+            let result = match action {
+                MediaKeyAction::Next => skip_spotify_track(&spotify, true).await,
+                MediaKeyAction::Previous => skip_spotify_track(&spotify, false).await,
+                _ => toggle_spotify_playback(&spotify).await,
+            };
+            if let Err(e) = result {
+                error!("媒體鍵控制 Spotify 失敗: {:?}", e);
+            }
+        });
+    }
+
     fn update_all_sinks_volume(&self) {
         let volume = self.global_volume;
+        if let Some(compare) = &self.preview_compare {
+            compare.set_volume(volume);
+        }
         let current_previews = self.current_previews.clone();
 
         tokio::spawn(async move {
@@ -5033,11 +11592,43 @@ impl SearchApp {
         });
     }
 
-    fn display_error_message(&self, ui: &mut egui::Ui) {
-        if let Ok(err_msg_guard) = self.err_msg.try_lock() {
-            if !err_msg_guard.is_empty() {
-                ui.label(format!("{}", *err_msg_guard));
+    fn update_all_sinks_speed(&self) {
+        let speed = self.preview_speed;
+        let current_previews = self.current_previews.clone();
+
+        tokio::spawn(async move {
+            let previews = current_previews.lock().await;
+            for (_, sink) in previews.iter() {
+                sink.set_speed(speed);
+            }
+        });
+    }
+
+    fn display_error_message(&mut self, ui: &mut egui::Ui) {
+        let message = match self.err_msg.try_lock() {
+            Ok(err_msg_guard) => err_msg_guard.clone(),
+            Err(_) => return,
+        };
+        let action = match self.error_banner.render(ui, &message) {
+            Some(action) => action,
+            None => return,
+        };
+
+        if let Ok(mut err_msg_guard) = self.err_msg.try_lock() {
+            err_msg_guard.clear();
+        }
+        match action {
+            ErrorBannerAction::Reauthorize => {
+                info!("從錯誤橫幅重新授權 Spotify");
+                self.start_spotify_authorization(ui.ctx().clone());
             }
+            ErrorBannerAction::Retry => {
+                if !self.search_query.trim().is_empty() {
+                    info!("從錯誤橫幅重試搜尋: {}", self.search_query);
+                    self.perform_search(ui.ctx().clone());
+                }
+            }
+            ErrorBannerAction::Dismiss => {}
         }
     }
 
@@ -5128,12 +11719,17 @@ impl SearchApp {
     }
 
     fn get_avatar_path(username: &str) -> PathBuf {
-        get_app_data_path().join(format!("{}.jpg", username))
+        avatar_path(username)
     }
 
     async fn download_and_save_avatar(url: &str, path: &PathBuf) -> Result<(), anyhow::Error> {
         let response = reqwest::get(url).await.context("下載頭像失敗")?;
         let bytes = response.bytes().await.context("讀取頭像數據失敗")?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .context("建立頭像資料夾失敗")?;
+        }
         tokio::fs::write(path, &bytes)
             .await
             .context("保存頭像失敗")?;
@@ -5235,9 +11831,21 @@ async fn main() -> Result<(), AppError> {
         log_file,
     )
     .context("Failed to initialize logger")?;
+    // 必須在任何設定被讀取之前套用，否則會被記憶體中的舊設定寫回
+    apply_pending_import();
+    crash::install_panic_hook();
+    init_diagnostics();
 
     info!("Welcome");
 
+    // 無圖形介面的播放清單同步模式
+    if env::args().any(|arg| arg == "--sync") {
+        let download_dir = load_download_directory()
+            .ok_or_else(|| AppError::Other("尚未設定下載目錄，請先在圖形介面中選擇".to_string()))?;
+        sync::run_sync_daemon(download_dir, debug_mode).await?;
+        return Ok(());
+    }
+
     // 讀取配置
     let config_errors = Arc::new(Mutex::new(Vec::new()));
 
@@ -5288,11 +11896,9 @@ async fn main() -> Result<(), AppError> {
         native_options,
         Box::new(move |cc| {
             let ctx = cc.egui_ctx.clone();
-            ctx.set_visuals(if dark_light::detect() == dark_light::Mode::Dark {
-                egui::Visuals::dark()
-            } else {
-                egui::Visuals::light()
-            });
+            let options = accessibility_options();
+            apply_theme(&ctx, &options);
+            apply_motion(&ctx, &options);
             ctx.set_pixels_per_point(1.0);
 
             match SearchApp::new(
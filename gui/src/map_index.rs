@@ -0,0 +1,227 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zip::ZipArchive;
+
+// 本地模組導入
+use crate::fuzzy::fuzzy_matches;
+use crate::playlistbuilder::parse_downloaded_file_name;
+use lib::{load_config, save_config};
+
+const INDEX_FILE: &str = "downloaded_map_index.json";
+
+#[derive(Error, Debug)]
+pub enum MapIndexError {
+    #[error("IO 錯誤: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("無法讀取 .osz: {0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("找不到 .osu 檔案")]
+    MissingOsuFile,
+}
+
+// 從譜面 .osu 檔的 [Metadata] 區段讀出的資料
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MapMetadata {
+    pub artist: String,
+    pub title: String,
+    #[serde(default)]
+    pub artist_unicode: String,
+    #[serde(default)]
+    pub title_unicode: String,
+    pub creator: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl MapMetadata {
+    // 尚未建立索引或讀取失敗時，從「ID 歌手 - 歌名」格式的檔名推測
+    fn from_file_name(file_name: &str) -> Option<Self> {
+        let (_, artist, title) = parse_downloaded_file_name(file_name)?;
+        Some(Self {
+            artist,
+            title,
+            ..Self::default()
+        })
+    }
+
+    fn matches(&self, query: &str, file_name: &str) -> bool {
+        let mut fields = vec![
+            file_name,
+            self.artist.as_str(),
+            self.title.as_str(),
+            self.artist_unicode.as_str(),
+            self.title_unicode.as_str(),
+            self.creator.as_str(),
+        ];
+        fields.extend(self.tags.iter().map(String::as_str));
+        fuzzy_matches(query, &fields)
+    }
+}
+
+// 以檔名為鍵，讀取失敗的項目存為 None，避免每次都重新解壓
+#[derive(Serialize, Deserialize, Default)]
+struct MapIndex {
+    entries: HashMap<String, Option<MapMetadata>>,
+}
+
+lazy_static! {
+    static ref INDEX: RwLock<MapIndex> = RwLock::new(load_config(INDEX_FILE).unwrap_or_default());
+}
+
+static IS_INDEXING: AtomicBool = AtomicBool::new(false);
+
+fn parse_osu_metadata(content: &str) -> MapMetadata {
+    let mut metadata = MapMetadata::default();
+    let mut in_metadata = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            // [Metadata] 之後的區段與搜尋無關
+            if in_metadata {
+                break;
+            }
+            in_metadata = line == "[Metadata]";
+            continue;
+        }
+        if !in_metadata {
+            continue;
+        }
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim().to_string()),
+            None => continue,
+        };
+        match key {
+            "Artist" => metadata.artist = value,
+            "Title" => metadata.title = value,
+            "ArtistUnicode" => metadata.artist_unicode = value,
+            "TitleUnicode" => metadata.title_unicode = value,
+            "Creator" => metadata.creator = value,
+            "Tags" => metadata.tags = value.split_whitespace().map(str::to_string).collect(),
+            _ => {}
+        }
+    }
+    metadata
+}
+
+// 同一個譜面集內的難度共用歌曲資料，讀第一個 .osu 就足夠
+fn read_osz_metadata(path: &Path) -> Result<MapMetadata, MapIndexError> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.name().ends_with(".osu") {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            return Ok(parse_osu_metadata(&content));
+        }
+    }
+    Err(MapIndexError::MissingOsuFile)
+}
+
+// osu! 匯入後解壓出的資料夾
+fn read_folder_metadata(path: &Path) -> Result<MapMetadata, MapIndexError> {
+    for entry in fs::read_dir(path)?.flatten() {
+        let entry_path = entry.path();
+        if entry_path
+            .extension()
+            .map_or(false, |extension| extension == "osu")
+        {
+            return Ok(parse_osu_metadata(&fs::read_to_string(entry_path)?));
+        }
+    }
+    Err(MapIndexError::MissingOsuFile)
+}
+
+// 同時用來檢查檔案是否損毀
+pub fn read_metadata(path: &Path) -> Result<MapMetadata, MapIndexError> {
+    if path.is_dir() {
+        read_folder_metadata(path)
+    } else {
+        read_osz_metadata(path)
+    }
+}
+
+// 已建立索引的資料，沒有時改用檔名推測
+pub fn map_metadata(file_name: &str) -> Option<MapMetadata> {
+    match INDEX.read().unwrap().entries.get(file_name) {
+        Some(Some(metadata)) => Some(metadata.clone()),
+        _ => MapMetadata::from_file_name(file_name),
+    }
+}
+
+// 檔名、歌手、歌名、作者與標籤任一符合即可
+pub fn map_matches(query: &str, file_name: &str) -> bool {
+    match map_metadata(file_name) {
+        Some(metadata) => metadata.matches(query, file_name),
+        None => fuzzy_matches(query, &[file_name]),
+    }
+}
+
+// 在背景為尚未建立索引的項目讀取 .osu 資料，完成後通知重繪
+pub fn index_downloaded_maps(
+    ctx: &egui::Context,
+    download_directory: &Path,
+    file_names: &[String],
+) {
+    let missing: Vec<String> = {
+        let index = INDEX.read().unwrap();
+        file_names
+            .iter()
+            .filter(|file_name| !index.entries.contains_key(*file_name))
+            .cloned()
+            .collect()
+    };
+    if missing.is_empty() || IS_INDEXING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let ctx = ctx.clone();
+    let download_directory: PathBuf = download_directory.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut parsed = Vec::with_capacity(missing.len());
+        for file_name in missing {
+            let metadata = match read_metadata(&download_directory.join(&file_name)) {
+                Ok(metadata) => Some(metadata),
+                Err(e) => {
+                    warn!("無法讀取 {} 的譜面資料: {}", file_name, e);
+                    None
+                }
+            };
+            parsed.push((file_name, metadata));
+        }
+        info!("已為 {} 個已下載圖譜建立索引", parsed.len());
+
+        let mut index = INDEX.write().unwrap();
+        index.entries.extend(parsed);
+        if let Err(e) = save_config(INDEX_FILE, &*index) {
+            error!("保存已下載圖譜索引失敗: {:?}", e);
+        }
+        drop(index);
+        IS_INDEXING.store(false, Ordering::SeqCst);
+        ctx.request_repaint();
+    });
+}
+
+// 刪除的檔案不需要保留索引
+pub fn forget_maps(file_names: &[String]) {
+    let mut index = INDEX.write().unwrap();
+    let before = index.entries.len();
+    for file_name in file_names {
+        index.entries.remove(file_name);
+    }
+    if index.entries.len() != before {
+        if let Err(e) = save_config(INDEX_FILE, &*index) {
+            error!("保存已下載圖譜索引失敗: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const LISTS_FILE: &str = "mapper_lists.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MapperPreference {
+    // 配對時加分，搜尋結果中標示
+    Favorite,
+    // 配對時排到最後，不會被自動下載選用
+    Blocked,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MapperLists {
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    #[serde(default)]
+    pub blocked: Vec<String>,
+    // 封鎖的作者從搜尋結果與配對候選中移除，而不只是排到最後
+    #[serde(default)]
+    pub hide_blocked: bool,
+}
+
+impl MapperLists {
+    // osu! 使用者名稱不分大小寫
+    fn preference(&self, creator: &str) -> Option<MapperPreference> {
+        let creator = creator.trim();
+        let contains = |list: &[String]| list.iter().any(|name| name.eq_ignore_ascii_case(creator));
+        if contains(&self.favorites) {
+            Some(MapperPreference::Favorite)
+        } else if contains(&self.blocked) {
+            Some(MapperPreference::Blocked)
+        } else {
+            None
+        }
+    }
+}
+
+lazy_static! {
+    static ref LISTS: RwLock<MapperLists> =
+        RwLock::new(load_config(LISTS_FILE).unwrap_or_default());
+}
+
+pub fn mapper_lists() -> MapperLists {
+    LISTS.read().unwrap().clone()
+}
+
+pub fn set_mapper_lists(lists: MapperLists) {
+    let result = save_config(LISTS_FILE, &lists);
+    if let Err(e) = result {
+        error!("保存譜面作者清單失敗: {:?}", e);
+    }
+    *LISTS.write().unwrap() = lists;
+}
+
+pub fn mapper_preference(creator: &str) -> Option<MapperPreference> {
+    LISTS.read().unwrap().preference(creator)
+}
+
+// 開啟隱藏時，封鎖作者的譜面不顯示也不參與配對
+pub fn is_hidden_mapper(creator: &str) -> bool {
+    let lists = LISTS.read().unwrap();
+    lists.hide_blocked && lists.preference(creator) == Some(MapperPreference::Blocked)
+}
+
+// 同一位作者只會在其中一個清單，None 表示從兩個清單移除
+pub fn set_mapper_preference(creator: &str, preference: Option<MapperPreference>) {
+    let creator = creator.trim();
+    if creator.is_empty() {
+        return;
+    }
+    let mut lists = mapper_lists();
+    lists
+        .favorites
+        .retain(|name| !name.eq_ignore_ascii_case(creator));
+    lists
+        .blocked
+        .retain(|name| !name.eq_ignore_ascii_case(creator));
+    match preference {
+        Some(MapperPreference::Favorite) => lists.favorites.push(creator.to_string()),
+        Some(MapperPreference::Blocked) => lists.blocked.push(creator.to_string()),
+        None => {}
+    }
+    info!("譜面作者 {} 設為 {:?}", creator, preference);
+    set_mapper_lists(lists);
+}
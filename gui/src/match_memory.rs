@@ -0,0 +1,378 @@
+// 標準庫導入
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::RwLock;
+
+// 第三方庫導入
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+// 本地模組導入
+use crate::matcher::{normalize, ScoredBeatmapset};
+use crate::osu::Beatmapset;
+use crate::spotify::{parse_spotify_url, SpotifyUrlKind};
+use crate::storage::storage;
+use crate::updater::CURRENT_VERSION;
+use lib::{load_config, save_config};
+
+const REJECTIONS_FILE: &str = "rejected_matches.json";
+const WRONG_MATCHES_FILE: &str = "wrong_matches.json";
+
+// 使用者確認過的 Spotify 曲目與譜面集配對
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConfirmedMatch {
+    pub beatmapset_id: i32,
+    // 以下僅供編輯器顯示
+    pub track: String,
+    pub beatmapset: String,
+    pub confirmed_at: DateTime<Local>,
+}
+
+// 使用者回報的錯誤配對，之後排序時降到最後
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WrongMatch {
+    pub track_artist: String,
+    pub track_title: String,
+    pub beatmapset_id: i32,
+    pub beatmapset_artist: String,
+    pub beatmapset_title: String,
+    // 回報當時的匹配分數
+    pub score: f32,
+    pub reported_at: DateTime<Local>,
+}
+
+impl WrongMatch {
+    fn matches(&self, artist: &str, title: &str, beatmapset_id: i32) -> bool {
+        self.beatmapset_id == beatmapset_id
+            && normalize(&self.track_artist) == normalize(artist)
+            && normalize(&self.track_title) == normalize(title)
+    }
+}
+
+lazy_static! {
+    // Spotify 曲目 ID -> 確認的配對
+    static ref MEMORY: RwLock<HashMap<String, ConfirmedMatch>> = RwLock::new(load_memory());
+    // 曲目鍵 -> 使用者拒絕的譜面集，自動配對時不再選用
+    static ref REJECTIONS: RwLock<HashMap<String, HashSet<i32>>> =
+        RwLock::new(load_config(REJECTIONS_FILE).unwrap_or_default());
+    static ref WRONG_MATCHES: RwLock<Vec<WrongMatch>> =
+        RwLock::new(load_config(WRONG_MATCHES_FILE).unwrap_or_default());
+}
+
+fn load_memory() -> HashMap<String, ConfirmedMatch> {
+    storage().load_match_memory().unwrap_or_else(|e| {
+        error!("讀取配對記憶失敗: {:?}", e);
+        HashMap::new()
+    })
+}
+
+fn save_memory(memory: &HashMap<String, ConfirmedMatch>) {
+    let result = storage().save_match_memory(memory);
+    if let Err(e) = result {
+        error!("保存配對記憶失敗: {:?}", e);
+    }
+}
+
+// 從 Spotify 曲目網址取出曲目 ID
+pub fn spotify_track_id(url: &str) -> Option<String> {
+    match parse_spotify_url(url) {
+        Some(SpotifyUrlKind::Track(id)) => Some(id),
+        _ => None,
+    }
+}
+
+pub fn confirmed_beatmapset(track_id: &str) -> Option<i32> {
+    MEMORY
+        .read()
+        .unwrap()
+        .get(track_id)
+        .map(|confirmed| confirmed.beatmapset_id)
+}
+
+pub fn remember_match(track_id: &str, track: &str, beatmapset: &Beatmapset) {
+    remember_match_id(
+        track_id,
+        track,
+        beatmapset.id,
+        &format!("{} - {}", beatmapset.artist, beatmapset.title),
+    );
+}
+
+// 只有譜面集 ID 與名稱時使用，例如從待確認配對核准
+pub fn remember_match_id(track_id: &str, track: &str, beatmapset_id: i32, beatmapset: &str) {
+    let mut memory = MEMORY.write().unwrap();
+    if memory
+        .get(track_id)
+        .map_or(false, |confirmed| confirmed.beatmapset_id == beatmapset_id)
+    {
+        return;
+    }
+    memory.insert(
+        track_id.to_string(),
+        ConfirmedMatch {
+            beatmapset_id,
+            track: track.to_string(),
+            beatmapset: beatmapset.to_string(),
+            confirmed_at: Local::now(),
+        },
+    );
+    save_memory(&memory);
+    info!("已記住配對: {} -> {}", track, beatmapset_id);
+}
+
+pub fn is_rejected(track_key: &str, beatmapset_id: i32) -> bool {
+    REJECTIONS
+        .read()
+        .unwrap()
+        .get(track_key)
+        .map_or(false, |rejected| rejected.contains(&beatmapset_id))
+}
+
+// track_key 為 Spotify 曲目 ID，沒有 ID 時為「演出者 - 曲名」
+pub fn reject_match(track_key: &str, beatmapset_id: i32) {
+    let mut rejections = REJECTIONS.write().unwrap();
+    if !rejections
+        .entry(track_key.to_string())
+        .or_default()
+        .insert(beatmapset_id)
+    {
+        return;
+    }
+    if let Err(e) = save_config(REJECTIONS_FILE, &*rejections) {
+        error!("保存拒絕的配對失敗: {:?}", e);
+    }
+    info!("已拒絕配對: {} -> {}", track_key, beatmapset_id);
+}
+
+// 以歌手與歌名比對，沒有 Spotify 曲目 ID 的來源（Last.fm 等）也適用
+pub fn is_reported_wrong(artist: &str, title: &str, beatmapset_id: i32) -> bool {
+    WRONG_MATCHES
+        .read()
+        .unwrap()
+        .iter()
+        .any(|wrong| wrong.matches(artist, title, beatmapset_id))
+}
+
+// 回報錯誤配對：同時加入拒絕紀錄，自動配對與待確認佇列都不再選用
+pub fn report_wrong_match(track_key: &str, artist: &str, title: &str, scored: &ScoredBeatmapset) {
+    let beatmapset = &scored.beatmapset;
+    reject_match(track_key, beatmapset.id);
+    let mut wrong_matches = WRONG_MATCHES.write().unwrap();
+    if wrong_matches
+        .iter()
+        .any(|wrong| wrong.matches(artist, title, beatmapset.id))
+    {
+        return;
+    }
+    wrong_matches.push(WrongMatch {
+        track_artist: artist.to_string(),
+        track_title: title.to_string(),
+        beatmapset_id: beatmapset.id,
+        beatmapset_artist: beatmapset.artist.clone(),
+        beatmapset_title: beatmapset.title.clone(),
+        score: scored.score,
+        reported_at: Local::now(),
+    });
+    if let Err(e) = save_config(WRONG_MATCHES_FILE, &*wrong_matches) {
+        error!("保存錯誤配對回報失敗: {:?}", e);
+    }
+    info!(
+        "已回報錯誤配對: {} - {} -> {}",
+        artist, title, beatmapset.id
+    );
+}
+
+pub fn wrong_match_count() -> usize {
+    WRONG_MATCHES.read().unwrap().len()
+}
+
+// 匯出時不包含回報時間與 Spotify 曲目 ID，只留下調整配對規則需要的名稱與分數
+pub fn export_wrong_matches(path: &Path) -> io::Result<usize> {
+    let wrong_matches = WRONG_MATCHES.read().unwrap().clone();
+    let corrections: Vec<_> = wrong_matches
+        .iter()
+        .map(|wrong| {
+            json!({
+                "track_artist": wrong.track_artist,
+                "track_title": wrong.track_title,
+                "beatmapset_id": wrong.beatmapset_id,
+                "beatmapset_artist": wrong.beatmapset_artist,
+                "beatmapset_title": wrong.beatmapset_title,
+                "score": wrong.score,
+            })
+        })
+        .collect();
+    let content = serde_json::to_string_pretty(&json!({
+        "app_version": CURRENT_VERSION,
+        "corrections": corrections,
+    }))?;
+    fs::write(path, content)?;
+    Ok(corrections.len())
+}
+
+// 編輯器手動修改譜面集 ID，原本的譜面名稱已不適用
+pub fn update_match(track_id: &str, beatmapset_id: i32) {
+    let mut memory = MEMORY.write().unwrap();
+    if let Some(confirmed) = memory.get_mut(track_id) {
+        confirmed.beatmapset_id = beatmapset_id;
+        confirmed.beatmapset = format!("#{}", beatmapset_id);
+        confirmed.confirmed_at = Local::now();
+        save_memory(&memory);
+    }
+}
+
+pub fn forget_match(track_id: &str) {
+    let mut memory = MEMORY.write().unwrap();
+    if memory.remove(track_id).is_some() {
+        save_memory(&memory);
+        info!("已移除配對記憶: {}", track_id);
+    }
+}
+
+// 下載過這個譜面集的 Spotify 曲目，顯示用
+pub fn source_track(beatmapset_id: i32) -> Option<String> {
+    MEMORY
+        .read()
+        .unwrap()
+        .values()
+        .filter(|confirmed| confirmed.beatmapset_id == beatmapset_id)
+        .max_by_key(|confirmed| confirmed.confirmed_at)
+        .map(|confirmed| confirmed.track.clone())
+}
+
+// 依確認時間由新到舊排序
+pub fn all_matches() -> Vec<(String, ConfirmedMatch)> {
+    let mut matches: Vec<(String, ConfirmedMatch)> = MEMORY
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(track_id, confirmed)| (track_id.clone(), confirmed.clone()))
+        .collect();
+    matches.sort_by(|a, b| b.1.confirmed_at.cmp(&a.1.confirmed_at));
+    matches
+}
+
+// 修正過時配對的編輯視窗
+pub struct MatchMemoryEditor {
+    pub show: bool,
+    filter: String,
+    // 正在編輯的譜面集 ID 輸入框
+    inputs: HashMap<String, String>,
+}
+
+impl MatchMemoryEditor {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            filter: String::new(),
+            inputs: HashMap::new(),
+        }
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) {
+        if !self.show {
+            return;
+        }
+        let mut open = self.show;
+        egui::Window::new("配對記憶")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("搜尋:");
+                    ui.text_edit_singleline(&mut self.filter);
+                });
+                ui.separator();
+
+                let filter = self.filter.to_lowercase();
+                let matches: Vec<(String, ConfirmedMatch)> = all_matches()
+                    .into_iter()
+                    .filter(|(_, confirmed)| {
+                        filter.is_empty()
+                            || confirmed.track.to_lowercase().contains(&filter)
+                            || confirmed.beatmapset.to_lowercase().contains(&filter)
+                    })
+                    .collect();
+                if matches.is_empty() {
+                    ui.label("沒有已確認的配對");
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for (track_id, confirmed) in matches {
+                            self.render_row(ui, &track_id, &confirmed);
+                            ui.separator();
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    let count = wrong_match_count();
+                    ui.label(format!("已回報的錯誤配對: {}", count));
+                    if ui
+                        .add_enabled(count > 0, egui::Button::new("匯出匿名資料"))
+                        .on_hover_text("不含回報時間與 Spotify 曲目 ID，可提供給開發者調整配對規則")
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_file_name("wrong_matches.json")
+                            .add_filter("JSON", &["json"])
+                            .save_file()
+                        {
+                            match export_wrong_matches(&path) {
+                                Ok(count) => info!("已匯出 {} 筆錯誤配對", count),
+                                Err(e) => error!("匯出錯誤配對失敗: {:?}", e),
+                            }
+                        }
+                    }
+                });
+            });
+        self.show = open;
+    }
+
+    fn render_row(&mut self, ui: &mut egui::Ui, track_id: &str, confirmed: &ConfirmedMatch) {
+        ui.label(egui::RichText::new(&confirmed.track).strong());
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "→ {} ({})",
+                confirmed.beatmapset,
+                confirmed.confirmed_at.format("%Y-%m-%d")
+            ));
+            if ui.small_button("開啟").clicked() {
+                let url = format!("https://osu.ppy.sh/beatmapsets/{}", confirmed.beatmapset_id);
+                if let Err(e) = open::that(url) {
+                    error!("無法開啟譜面頁面: {:?}", e);
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            let input = self
+                .inputs
+                .entry(track_id.to_string())
+                .or_insert_with(|| confirmed.beatmapset_id.to_string());
+            ui.label("譜面集 ID:");
+            ui.add(egui::TextEdit::singleline(input).desired_width(100.0));
+            let parsed = input.trim().parse::<i32>().ok();
+            if ui
+                .add_enabled(
+                    parsed.map_or(false, |id| id != confirmed.beatmapset_id),
+                    egui::Button::new("更新"),
+                )
+                .clicked()
+            {
+                if let Some(beatmapset_id) = parsed {
+                    update_match(track_id, beatmapset_id);
+                }
+            }
+            if ui.button("刪除").clicked() {
+                forget_match(track_id);
+                self.inputs.remove(track_id);
+            }
+        });
+    }
+}
@@ -0,0 +1,352 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::error;
+use osu_spotify_core::matching::{
+    duration_difference, rank, score_names, Candidate, SearchRequest,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::mapper_lists::{is_hidden_mapper, mapper_preference, MapperPreference};
+use crate::match_memory::is_reported_wrong;
+use crate::osu::{Beatmapset, BeatmapsetTag};
+use crate::spotify::search_track;
+use lib::{load_config, save_config};
+
+// 配對邏輯在 osu_spotify_core，這裡只保留設定與介面需要的部分
+pub use osu_spotify_core::matching::CONFIDENT_MATCH_SCORE;
+pub use osu_spotify_core::text::normalize;
+pub use osu_spotify_core::VersionPreference;
+
+// 設定頁面可調整的自動下載門檻範圍
+pub const AUTO_MIN_SCORE_RANGE: (f32, f32) = (0.5, 1.0);
+// 反向搜尋 Spotify 時比較的候選曲目數
+const SPOTIFY_CANDIDATES: u32 = 5;
+const OPTIONS_FILE: &str = "match_options.json";
+// 曲風或語言符合偏好時各加的分數，喜愛的譜面作者也加相同的分數
+const PREFERENCE_BONUS: f32 = 0.05;
+
+// osu! 的曲風 ID 與設定頁面的名稱，搜尋篩選的 g 參數使用相同的 ID
+pub const MAP_GENRES: [(u32, &str); 12] = [
+    (2, "電玩"),
+    (3, "動畫"),
+    (4, "搖滾"),
+    (5, "流行"),
+    (6, "其他"),
+    (7, "新奇"),
+    (9, "嘻哈"),
+    (10, "電子"),
+    (11, "金屬"),
+    (12, "古典"),
+    (13, "民謠"),
+    (14, "爵士"),
+];
+
+// osu! 的語言 ID，搜尋篩選的 l 參數使用相同的 ID
+pub const MAP_LANGUAGES: [(u32, &str); 13] = [
+    (2, "英文"),
+    (3, "日文"),
+    (4, "中文"),
+    (5, "純音樂"),
+    (6, "韓文"),
+    (7, "法文"),
+    (8, "德文"),
+    (9, "瑞典文"),
+    (10, "西班牙文"),
+    (11, "義大利文"),
+    (12, "俄文"),
+    (13, "波蘭文"),
+    (14, "其他"),
+];
+
+// 設定頁面依序列出的譜面版本偏好
+pub const VERSION_PREFERENCES: [VersionPreference; 3] = [
+    VersionPreference::NoPreference,
+    VersionPreference::FullVersion,
+    VersionPreference::TvSize,
+];
+
+pub fn version_preference_label(preference: VersionPreference) -> &'static str {
+    match preference {
+        VersionPreference::NoPreference => "不偏好",
+        VersionPreference::FullVersion => "偏好完整版",
+        VersionPreference::TvSize => "偏好 TV size",
+    }
+}
+
+fn default_auto_min_score() -> f32 {
+    CONFIDENT_MATCH_SCORE
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MatchOptions {
+    #[serde(default)]
+    pub version_preference: VersionPreference,
+    // 自動下載與同步模式的信心門檻，低於此分數的配對改為加入待確認列表
+    #[serde(default = "default_auto_min_score")]
+    pub auto_min_score: f32,
+    // 偏好的譜面曲風與語言，符合時提高匹配分數
+    #[serde(default)]
+    pub preferred_genre: Option<u32>,
+    #[serde(default)]
+    pub preferred_language: Option<u32>,
+    // 手動搜尋 osu! 官方 API 時預設只列出偏好的曲風與語言
+    #[serde(default)]
+    pub filter_search: bool,
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self {
+            version_preference: VersionPreference::default(),
+            auto_min_score: CONFIDENT_MATCH_SCORE,
+            preferred_genre: None,
+            preferred_language: None,
+            filter_search: false,
+        }
+    }
+}
+
+impl MatchOptions {
+    fn preference_bonus(&self, beatmapset: &Beatmapset) -> f32 {
+        let matches = |preferred: Option<u32>, tag: &Option<BeatmapsetTag>| {
+            preferred.is_some() && tag.as_ref().map(|tag| tag.id) == preferred
+        };
+        let mut bonus = 0.0;
+        if matches(self.preferred_genre, &beatmapset.genre) {
+            bonus += PREFERENCE_BONUS;
+        }
+        if matches(self.preferred_language, &beatmapset.language) {
+            bonus += PREFERENCE_BONUS;
+        }
+        if mapper_preference(&beatmapset.creator) == Some(MapperPreference::Favorite) {
+            bonus += PREFERENCE_BONUS;
+        }
+        bonus
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<MatchOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> MatchOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn match_options() -> MatchOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_match_options(options: MatchOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存匹配選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 手動搜尋時套用的 osu! 搜尋參數，沒有開啟篩選時為空
+pub fn preferred_search_filters() -> Vec<(&'static str, String)> {
+    let options = match_options();
+    if !options.filter_search {
+        return Vec::new();
+    }
+    let mut filters = Vec::new();
+    if let Some(genre) = options.preferred_genre {
+        filters.push(("g", genre.to_string()));
+    }
+    if let Some(language) = options.preferred_language {
+        filters.push(("l", language.to_string()));
+    }
+    filters
+}
+
+#[derive(Clone, Debug)]
+pub struct ScoredBeatmapset {
+    pub beatmapset: Beatmapset,
+    pub score: f32,
+}
+
+impl Candidate for Beatmapset {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name_variants(&self) -> Vec<(String, String)> {
+        Beatmapset::name_variants(self)
+    }
+
+    fn length_secs(&self) -> Option<i32> {
+        Beatmapset::length_secs(self)
+    }
+}
+
+// 依匹配分數由高到低排序，並套用完整版 / TV Size 的偏好
+// duration_ms 為 Spotify 曲目長度，沒有時只以標題判斷剪輯版本
+pub fn rank_beatmapsets(
+    artist: &str,
+    title: &str,
+    duration_ms: Option<u64>,
+    beatmapsets: Vec<Beatmapset>,
+) -> Vec<ScoredBeatmapset> {
+    let options = match_options();
+    let request = SearchRequest {
+        artist: artist.to_string(),
+        title: title.to_string(),
+        duration_ms,
+        version_preference: options.version_preference,
+        ..Default::default()
+    };
+    // 使用者回報過的錯誤配對與封鎖作者的譜面分數歸零並排到最後，不會被自動下載選用
+    let (reported, mut ranked): (Vec<_>, Vec<_>) = rank(&request, beatmapsets)
+        .into_iter()
+        .filter(|result| !is_hidden_mapper(&result.candidate.creator))
+        .map(|result| ScoredBeatmapset {
+            score: (result.score + options.preference_bonus(&result.candidate)).min(1.0),
+            beatmapset: result.candidate,
+        })
+        .partition(|scored| {
+            is_reported_wrong(artist, title, scored.beatmapset.id)
+                || mapper_preference(&scored.beatmapset.creator) == Some(MapperPreference::Blocked)
+        });
+    // 加上曲風與語言的偏好後重新排序，分數相同時維持原本的順序
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked.extend(reported.into_iter().map(|scored| ScoredBeatmapset {
+        score: 0.0,
+        ..scored
+    }));
+    ranked
+}
+
+// Spotify 曲目與譜面的長度差異
+#[derive(Clone, Copy, Debug)]
+pub struct DurationMismatch {
+    pub spotify_secs: i64,
+    pub beatmap_secs: i64,
+}
+
+impl DurationMismatch {
+    pub fn label(&self) -> String {
+        let diff = self.beatmap_secs - self.spotify_secs;
+        if diff < 0 {
+            format!("⏱ 短 {} 秒", -diff)
+        } else {
+            format!("⏱ 長 {} 秒", diff)
+        }
+    }
+
+    pub fn detail(&self) -> String {
+        format!(
+            "Spotify {} / 譜面 {}，可能是 TV Size 或剪輯版本",
+            format_secs(self.spotify_secs),
+            format_secs(self.beatmap_secs)
+        )
+    }
+}
+
+fn format_secs(secs: i64) -> String {
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+// 長度相差超過門檻時回傳差異，任一方沒有長度資料時視為無法判斷
+pub fn duration_mismatch(
+    duration_ms: Option<u64>,
+    beatmapset: &Beatmapset,
+) -> Option<DurationMismatch> {
+    let (spotify_secs, beatmap_secs) = duration_difference(duration_ms, beatmapset)?;
+    Some(DurationMismatch {
+        spotify_secs,
+        beatmap_secs,
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct SpotifyMatch {
+    pub track_id: String,
+    pub name: String,
+    pub artists: String,
+    pub url: String,
+    pub score: f32,
+}
+
+#[derive(Clone, Debug)]
+pub enum SpotifyMatchState {
+    Pending,
+    Matched(SpotifyMatch),
+    NotFound,
+    Failed(String),
+}
+
+// 以譜面的歌手與歌名搜尋 Spotify，取匹配分數最高的曲目
+// names 為譜面的各種寫法，羅馬拼音找不到可信的結果時改用原文搜尋
+pub async fn match_spotify_track(
+    client: &Client,
+    spotify_token: &str,
+    names: &[(String, String)],
+    debug_mode: bool,
+) -> SpotifyMatchState {
+    let mut best: Option<SpotifyMatch> = None;
+    let mut last_error = None;
+
+    for (artist, title) in names {
+        let query = format!("{} {}", artist, title);
+        let tracks = match search_track(
+            client,
+            &query,
+            spotify_token,
+            SPOTIFY_CANDIDATES,
+            0,
+            debug_mode,
+        )
+        .await
+        {
+            Ok((tracks, _)) => tracks,
+            Err(e) => {
+                error!("反向搜尋 Spotify 失敗 ({}): {:?}", query, e);
+                last_error = Some(e.to_string());
+                continue;
+            }
+        };
+
+        let candidates = tracks.into_iter().filter_map(|track| {
+            let url = track.external_urls.get("spotify")?.clone();
+            let track_id = url.split('/').last()?.split('?').next()?.to_string();
+            let artists = track
+                .artists
+                .iter()
+                .map(|a| a.name.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+            Some(SpotifyMatch {
+                score: score_names(&artists, &track.name, names),
+                track_id,
+                name: track.name,
+                artists,
+                url,
+            })
+        });
+        for candidate in candidates {
+            if best.as_ref().map_or(true, |b| candidate.score > b.score) {
+                best = Some(candidate);
+            }
+        }
+
+        if best
+            .as_ref()
+            .map_or(false, |b| b.score >= CONFIDENT_MATCH_SCORE)
+        {
+            break;
+        }
+    }
+
+    match (best, last_error) {
+        (Some(best), _) => SpotifyMatchState::Matched(best),
+        (None, Some(e)) => SpotifyMatchState::Failed(e),
+        (None, None) => SpotifyMatchState::NotFound,
+    }
+}
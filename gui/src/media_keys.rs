@@ -0,0 +1,127 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::RwLock;
+
+// 第三方庫導入
+use global_hotkey::hotkey::{Code, HotKey};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "media_key_options.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MediaKeyOptions {
+    // 註冊後其他程式就收不到媒體鍵，預設關閉
+    pub enabled: bool,
+    // 沒有預覽播放時改為控制 Spotify 的播放裝置
+    pub control_spotify: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MediaKeyAction {
+    PlayPause,
+    Next,
+    Previous,
+    Stop,
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<MediaKeyOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> MediaKeyOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn media_key_options() -> MediaKeyOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_media_key_options(options: MediaKeyOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存媒體鍵選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 系統層級的媒體鍵監聽，需在主執行緒建立，釋放時取消註冊
+pub struct MediaKeyListener {
+    manager: GlobalHotKeyManager,
+    hotkeys: Vec<HotKey>,
+    receiver: Receiver<MediaKeyAction>,
+}
+
+impl MediaKeyListener {
+    // 建立失敗時回傳 None，個別按鍵被其他程式佔用時略過該按鍵
+    pub fn start(ctx: &egui::Context) -> Option<Self> {
+        let manager = match GlobalHotKeyManager::new() {
+            Ok(manager) => manager,
+            Err(e) => {
+                error!("無法建立媒體鍵監聽: {:?}", e);
+                return None;
+            }
+        };
+
+        let mut hotkeys = Vec::new();
+        let mut actions = HashMap::new();
+        for (code, action) in [
+            (Code::MediaPlayPause, MediaKeyAction::PlayPause),
+            (Code::MediaTrackNext, MediaKeyAction::Next),
+            (Code::MediaTrackPrevious, MediaKeyAction::Previous),
+            (Code::MediaStop, MediaKeyAction::Stop),
+        ] {
+            let hotkey = HotKey::new(None, code);
+            match manager.register(hotkey) {
+                Ok(_) => {
+                    actions.insert(hotkey.id(), action);
+                    hotkeys.push(hotkey);
+                }
+                Err(e) => warn!("無法註冊媒體鍵 {:?}: {:?}", code, e),
+            }
+        }
+        if hotkeys.is_empty() {
+            return None;
+        }
+
+        // 事件在背景執行緒觸發，轉送後喚醒畫面處理
+        let (sender, receiver) = channel();
+        let ctx = ctx.clone();
+        GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+            if event.state != HotKeyState::Pressed {
+                return;
+            }
+            if let Some(action) = actions.get(&event.id) {
+                if sender.send(*action).is_ok() {
+                    ctx.request_repaint();
+                }
+            }
+        }));
+        info!("已註冊 {} 個媒體鍵", hotkeys.len());
+
+        Some(Self {
+            manager,
+            hotkeys,
+            receiver,
+        })
+    }
+
+    pub fn poll(&self) -> Vec<MediaKeyAction> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for MediaKeyListener {
+    fn drop(&mut self) {
+        GlobalHotKeyEvent::set_event_handler(None::<fn(GlobalHotKeyEvent)>);
+        if let Err(e) = self.manager.unregister_all(&self.hotkeys) {
+            warn!("取消註冊媒體鍵失敗: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::{error, info};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "notify_options.json";
+// Discord embed 的顏色
+const COLOR_SUCCESS: u32 = 0x1DB954;
+const COLOR_PARTIAL: u32 = 0xF0A500;
+const COLOR_FAILED: u32 = 0xE03C3C;
+// Discord 單一欄位最多 1024 字，只列出前幾筆失敗
+const MAX_LISTED_FAILURES: usize = 10;
+
+#[derive(Error, Debug)]
+pub enum NotifyError {
+    #[error("請求錯誤: {0}")]
+    RequestError(#[from] reqwest::Error),
+    #[error("Webhook 回應錯誤，狀態碼: {0}")]
+    StatusError(reqwest::StatusCode),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NotifyOptions {
+    pub enabled: bool,
+    // Discord webhook 或其他接受相同格式的網址
+    pub webhook_url: String,
+}
+
+// 一批工作完成後的統計
+#[derive(Clone, Debug)]
+pub struct BatchReport {
+    pub title: String,
+    pub succeeded: usize,
+    pub failures: Vec<String>,
+    // 額外顯示的欄位，例如新曲目數量
+    pub fields: Vec<(String, String)>,
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<NotifyOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> NotifyOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn notify_options() -> NotifyOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_notify_options(options: NotifyOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存通知選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+fn build_payload(report: &BatchReport) -> serde_json::Value {
+    let color = match (report.succeeded, report.failures.len()) {
+        (_, 0) => COLOR_SUCCESS,
+        (0, _) => COLOR_FAILED,
+        _ => COLOR_PARTIAL,
+    };
+    let mut fields = vec![
+        json!({ "name": "成功", "value": report.succeeded.to_string(), "inline": true }),
+        json!({ "name": "失敗", "value": report.failures.len().to_string(), "inline": true }),
+    ];
+    fields.extend(
+        report
+            .fields
+            .iter()
+            .map(|(name, value)| json!({ "name": name, "value": value, "inline": true })),
+    );
+    if !report.failures.is_empty() {
+        let mut listed: Vec<String> = report
+            .failures
+            .iter()
+            .take(MAX_LISTED_FAILURES)
+            .map(|failure| format!("• {}", failure))
+            .collect();
+        if report.failures.len() > MAX_LISTED_FAILURES {
+            listed.push(format!(
+                "…以及其他 {} 個",
+                report.failures.len() - MAX_LISTED_FAILURES
+            ));
+        }
+        fields.push(json!({ "name": "失敗項目", "value": listed.join("\n"), "inline": false }));
+    }
+
+    json!({
+        "username": "osu! Search App",
+        "embeds": [{
+            "title": report.title,
+            "color": color,
+            "fields": fields,
+            "timestamp": Utc::now().to_rfc3339(),
+        }],
+    })
+}
+
+pub async fn send_batch_report(
+    client: &Client,
+    webhook_url: &str,
+    report: &BatchReport,
+) -> Result<(), NotifyError> {
+    let response = client
+        .post(webhook_url)
+        .json(&build_payload(report))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(NotifyError::StatusError(response.status()));
+    }
+    info!("已發送通知: {}", report.title);
+    Ok(())
+}
+
+// 啟用通知且設定了網址時才發送，失敗只記錄不影響原本的流程
+pub async fn notify_batch_completed(report: BatchReport) {
+    let options = notify_options();
+    if !options.enabled || options.webhook_url.trim().is_empty() {
+        return;
+    }
+    if let Err(e) = send_batch_report(&Client::new(), options.webhook_url.trim(), &report).await {
+        error!("發送通知失敗: {:?}", e);
+    }
+}
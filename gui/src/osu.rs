@@ -2,31 +2,44 @@
 use std::sync::Arc;
 use std::path::Path;
 use std::fs;
-use std::io::{copy,Cursor};
-use std::fs::File;
+use std::time::Duration;
 
 
 
 // 第三方庫導入
 use anyhow::Result;
 use egui::{ColorImage, TextureHandle};
-use image::load_from_memory;
+use futures::stream::{self, StreamExt};
 use log::{debug, error, info};
+use osu_spotify_core::{CoreError, DownloadJob};
 use regex::Regex;
 use reqwest::Client;
 use serde::Deserialize;
 
 use thiserror::Error;
 
-use tokio::{sync::mpsc::Sender, try_join,task};
+use tokio::{sync::mpsc::Sender, try_join};
+use tracing::Instrument;
 
-use rodio::{Decoder, Sink, OutputStreamHandle};
+use rodio::{Sink, OutputStreamHandle};
 
 
 
 // 本地模組導入
 
+use crate::diagnostics::{operation_span, record_request_error, record_status, send_traced};
+use crate::download_history::record_download;
+use crate::download_manager::ProgressTracker;
+use crate::download_options::download_options;
+use crate::osu_client::{import_osz, osu_client_options};
+use crate::preview_cache::{cached_preview, store_preview};
+use crate::preview_effects::{BufferedPreview, EqPreset};
+use crate::rate_limit::record_rate_limit;
 use crate::read_config;
+use crate::texturequeue::{
+    downscale_image, fetch_cover_image, COVER_FETCH_CONCURRENCY, THUMBNAIL_SIZE,
+};
+use crate::trash::{remove_entry, TrashedItem};
 use crate::DownloadStatus;
 
 
@@ -43,13 +56,62 @@ pub struct Covers {
 }
 #[derive(Debug, Deserialize, Clone)] // 添加 Clone
 pub struct Beatmapset {
+    // 使用者的最常遊玩列表只回傳精簡的譜面集，沒有 beatmaps 欄位
+    #[serde(default)]
     pub beatmaps: Vec<Beatmap>,
     pub id: i32,
     pub artist: String,
     pub title: String,
+    // 原文（日文、韓文等）歌手與歌名，artist / title 通常是羅馬拼音
+    #[serde(default)]
+    pub artist_unicode: Option<String>,
+    #[serde(default)]
+    pub title_unicode: Option<String>,
     pub creator: String,
     pub covers: Covers,
     pub preview_url: Option<String>,
+    // 曲風與語言只有部分 API 回應附帶
+    #[serde(default)]
+    pub genre: Option<BeatmapsetTag>,
+    #[serde(default)]
+    pub language: Option<BeatmapsetTag>,
+    // 收藏數與遊玩次數，精簡的譜面集沒有時為 0
+    #[serde(default)]
+    pub favourite_count: u32,
+    #[serde(default)]
+    pub play_count: u32,
+    // 所屬的官方譜面包，例如 S1234
+    #[serde(default)]
+    pub pack_tags: Vec<String>,
+}
+// osu! 的曲風或語言，id 與搜尋篩選的 g / l 參數相同
+#[derive(Debug, Deserialize, Clone)]
+pub struct BeatmapsetTag {
+    pub id: u32,
+    #[serde(default)]
+    pub name: String,
+}
+// 官方譜面包，beatmapsets 只有查詢單一譜面包時才會附帶
+#[derive(Debug, Deserialize, Clone)]
+pub struct BeatmapPack {
+    pub tag: String,
+    pub name: String,
+    #[serde(default)]
+    pub beatmapsets: Vec<Beatmapset>,
+}
+// 譜面集說明，只有查詢單一譜面集時才會附帶
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct BeatmapsetDescription {
+    // 網站由 BBCode 轉換出的 HTML
+    #[serde(default)]
+    pub description: String,
+    // 只有譜面作者本人的 token 才會取得原始 BBCode
+    pub bbcode: Option<String>,
+}
+#[derive(Deserialize)]
+struct BeatmapsetDescriptionResponse {
+    #[serde(default)]
+    description: BeatmapsetDescription,
 }
 #[derive(Deserialize)]
 pub struct TokenResponse {
@@ -59,6 +121,18 @@ pub struct TokenResponse {
 #[derive(Debug, Deserialize)]
 pub struct SearchResponse {
     beatmapsets: Vec<Beatmapset>,
+    #[serde(default)]
+    cursor_string: Option<String>,
+    #[serde(default)]
+    total: Option<u32>,
+}
+
+// 一頁搜尋結果，cursor_string 為 None 代表沒有下一頁
+#[derive(Debug, Clone)]
+pub struct BeatmapsetPage {
+    pub beatmapsets: Vec<Beatmapset>,
+    pub cursor_string: Option<String>,
+    pub total: Option<u32>,
 }
 #[derive(Debug, Deserialize, Clone)]
 pub struct Beatmap {
@@ -69,6 +143,47 @@ pub struct Beatmap {
     pub total_length: i32,
     pub user_id: i32,
     pub version: String,
+    // 圓圈大小，mania 模式代表鍵數
+    #[serde(default)]
+    pub cs: f32,
+}
+#[derive(Debug, Deserialize, Clone)]
+pub struct OsuUserStatistics {
+    pub pp: f32,
+    pub global_rank: Option<u32>,
+}
+#[derive(Debug, Deserialize, Clone)]
+pub struct OsuUser {
+    pub id: i32,
+    pub username: String,
+    pub statistics: Option<OsuUserStatistics>,
+}
+// 使用者個人頁面上的譜面集列表
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UserBeatmapsetKind {
+    Favourite,
+    MostPlayed,
+}
+
+impl UserBeatmapsetKind {
+    fn api_value(&self) -> &'static str {
+        match self {
+            UserBeatmapsetKind::Favourite => "favourite",
+            UserBeatmapsetKind::MostPlayed => "most_played",
+        }
+    }
+}
+
+// play_count 只有最常遊玩列表才有
+#[derive(Debug, Clone)]
+pub struct UserBeatmapset {
+    pub beatmapset: Beatmapset,
+    pub play_count: Option<u32>,
+}
+#[derive(Debug, Deserialize)]
+struct MostPlayedItem {
+    count: u32,
+    beatmapset: Beatmapset,
 }
 pub struct BeatmapInfo {
     pub title: String,
@@ -97,8 +212,17 @@ pub enum OsuError {
     ReqwestError(reqwest::Error),
     #[error("其他錯誤: {0}")]
     Other(String),
+    #[error("下載錯誤: {0}")]
+    DownloadError(#[from] CoreError),
+    #[error("{} 個封面載入失敗", .0.len())]
+    CoverLoadFailed(Vec<(usize, String)>),
 }
 
+// 封面載入失敗時的自動重試次數與初始等待時間，每次重試等待時間加倍
+pub const COVER_MAX_RETRIES: u32 = 3;
+pub const COVER_RETRY_BASE_DELAY_MS: u64 = 500;
+const COVER_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
 
 
 
@@ -108,13 +232,71 @@ pub async fn get_beatmapsets(
     song_name: &str,
     debug_mode: bool,
 ) -> Result<Vec<Beatmapset>, OsuError> {
-    let response = client
-        .get("https://osu.ppy.sh/api/v2/beatmapsets/search")
-        .query(&[("query", song_name)])
-        .bearer_auth(access_token)
-        .send()
+    get_beatmapsets_page(client, access_token, song_name, None, &[], debug_mode)
         .await
-        .map_err(OsuError::RequestError)?;
+        .map(|page| page.beatmapsets)
+}
+
+// 以 cursor_string 取得指定頁的搜尋結果，cursor 為 None 時取得第一頁
+// filters 為額外的搜尋參數，例如曲風 g 與語言 l
+pub async fn get_beatmapsets_page(
+    client: &Client,
+    access_token: &str,
+    song_name: &str,
+    cursor: Option<&str>,
+    filters: &[(&str, String)],
+    debug_mode: bool,
+) -> Result<BeatmapsetPage, OsuError> {
+    let mut query = vec![("query", song_name)];
+    if let Some(cursor) = cursor {
+        query.push(("cursor_string", cursor));
+    }
+    query.extend(filters.iter().map(|(key, value)| (*key, value.as_str())));
+
+    let response = send_traced(
+        client
+            .get("https://osu.ppy.sh/api/v2/beatmapsets/search")
+            .query(&query)
+            .bearer_auth(access_token),
+        "osu!",
+        "get_beatmapsets_page",
+    )
+    .await
+    .map_err(OsuError::RequestError)?;
+
+    let response_text = response.text().await.map_err(OsuError::RequestError)?;
+
+    if debug_mode {
+        info!("Osu API 回應 JSON: {}", response_text);
+    }
+
+    let search_response: SearchResponse =
+        serde_json::from_str(&response_text).map_err(OsuError::JsonError)?;
+
+    Ok(BeatmapsetPage {
+        beatmapsets: search_response.beatmapsets,
+        cursor_string: search_response.cursor_string,
+        total: search_response.total,
+    })
+}
+
+// 獲取最近 ranked 的譜面集，mode: 0=osu, 1=taiko, 2=fruits, 3=mania
+pub async fn get_recently_ranked_beatmapsets(
+    client: &Client,
+    access_token: &str,
+    mode: &str,
+    debug_mode: bool,
+) -> Result<Vec<Beatmapset>, OsuError> {
+    let response = send_traced(
+        client
+            .get("https://osu.ppy.sh/api/v2/beatmapsets/search")
+            .query(&[("s", "ranked"), ("sort", "ranked_desc"), ("m", mode)])
+            .bearer_auth(access_token),
+        "osu!",
+        "get_recently_ranked_beatmapsets",
+    )
+    .await
+    .map_err(OsuError::RequestError)?;
 
     let response_text = response.text().await.map_err(OsuError::RequestError)?;
 
@@ -128,6 +310,122 @@ pub async fn get_beatmapsets(
     Ok(search_response.beatmapsets)
 }
 
+// 取得使用者的最愛或最常遊玩譜面集，最常遊玩會依譜面集合併各難度的遊玩次數
+pub async fn get_user_beatmapsets(
+    client: &Client,
+    access_token: &str,
+    user_id: i32,
+    kind: UserBeatmapsetKind,
+    limit: u32,
+    debug_mode: bool,
+) -> Result<Vec<UserBeatmapset>, OsuError> {
+    let url = format!(
+        "https://osu.ppy.sh/api/v2/users/{}/beatmapsets/{}",
+        user_id,
+        kind.api_value()
+    );
+
+    let response = send_traced(
+        client
+            .get(&url)
+            .query(&[("limit", limit)])
+            .bearer_auth(access_token),
+        "osu!",
+        "get_user_beatmapsets",
+    )
+    .await
+    .map_err(OsuError::RequestError)?;
+
+    if !response.status().is_success() {
+        return Err(OsuError::ApiError(format!(
+            "無法取得使用者譜面列表 (狀態碼: {})",
+            response.status()
+        )));
+    }
+
+    let response_text = response.text().await.map_err(OsuError::RequestError)?;
+
+    if debug_mode {
+        info!("Osu API 回應 JSON: {}", response_text);
+    }
+
+    match kind {
+        UserBeatmapsetKind::Favourite => {
+            let beatmapsets: Vec<Beatmapset> =
+                serde_json::from_str(&response_text).map_err(OsuError::JsonError)?;
+            Ok(beatmapsets
+                .into_iter()
+                .map(|beatmapset| UserBeatmapset {
+                    beatmapset,
+                    play_count: None,
+                })
+                .collect())
+        }
+        UserBeatmapsetKind::MostPlayed => {
+            let items: Vec<MostPlayedItem> =
+                serde_json::from_str(&response_text).map_err(OsuError::JsonError)?;
+            let mut merged: Vec<UserBeatmapset> = Vec::new();
+            for item in items {
+                match merged
+                    .iter_mut()
+                    .find(|existing| existing.beatmapset.id == item.beatmapset.id)
+                {
+                    Some(existing) => {
+                        existing.play_count = Some(existing.play_count.unwrap_or(0) + item.count);
+                    }
+                    None => merged.push(UserBeatmapset {
+                        beatmapset: item.beatmapset,
+                        play_count: Some(item.count),
+                    }),
+                }
+            }
+            merged.sort_by(|a, b| b.play_count.cmp(&a.play_count));
+            Ok(merged)
+        }
+    }
+}
+
+pub async fn get_user(
+    client: &Client,
+    access_token: &str,
+    username: &str,
+    debug_mode: bool,
+) -> Result<OsuUser, OsuError> {
+    let url = format!(
+        "https://osu.ppy.sh/api/v2/users/{}/osu",
+        urlencoding::encode(username)
+    );
+
+    let response = send_traced(
+        client
+            .get(&url)
+            .query(&[("key", "username")])
+            .bearer_auth(access_token),
+        "osu!",
+        "get_user",
+    )
+    .await
+    .map_err(OsuError::RequestError)?;
+
+    if !response.status().is_success() {
+        return Err(OsuError::ApiError(format!(
+            "找不到 osu! 使用者 {} (狀態碼: {})",
+            username,
+            response.status()
+        )));
+    }
+
+    let response_text = response.text().await.map_err(OsuError::RequestError)?;
+
+    if debug_mode {
+        info!("Osu API 回應 JSON: {}", response_text);
+    }
+
+    let user: OsuUser = serde_json::from_str(&response_text).map_err(OsuError::JsonError)?;
+
+    Ok(user)
+}
+
 pub async fn get_beatmapset_by_id(
     client: &Client,
     access_token: &str,
@@ -136,12 +434,13 @@ pub async fn get_beatmapset_by_id(
 ) -> Result<Beatmapset, OsuError> {
     let url = format!("https://osu.ppy.sh/api/v2/beatmapsets/{}", beatmapset_id);
 
-    let response = client
-        .get(&url)
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(OsuError::RequestError)?;
+    let response = send_traced(
+        client.get(&url).bearer_auth(access_token),
+        "osu!",
+        "get_beatmapset_by_id",
+    )
+    .await
+    .map_err(OsuError::RequestError)?;
 
     let response_text = response.text().await.map_err(OsuError::RequestError)?;
 
@@ -155,6 +454,73 @@ pub async fn get_beatmapset_by_id(
     Ok(beatmapset)
 }
 
+pub async fn get_beatmapset_description(
+    client: &Client,
+    access_token: &str,
+    beatmapset_id: i32,
+    debug_mode: bool,
+) -> Result<BeatmapsetDescription, OsuError> {
+    let url = format!("https://osu.ppy.sh/api/v2/beatmapsets/{}", beatmapset_id);
+
+    let response = send_traced(
+        client.get(&url).bearer_auth(access_token),
+        "osu!",
+        "get_beatmapset_description",
+    )
+    .await
+    .map_err(OsuError::RequestError)?;
+
+    if !response.status().is_success() {
+        return Err(OsuError::ApiError(format!(
+            "無法取得譜面集 {} 的說明 (狀態碼: {})",
+            beatmapset_id,
+            response.status()
+        )));
+    }
+
+    let response_text = response.text().await.map_err(OsuError::RequestError)?;
+
+    if debug_mode {
+        info!("Osu API 回應 JSON: {}", response_text);
+    }
+
+    let response: BeatmapsetDescriptionResponse =
+        serde_json::from_str(&response_text).map_err(OsuError::JsonError)?;
+    Ok(response.description)
+}
+
+pub async fn get_beatmap_pack(
+    client: &Client,
+    access_token: &str,
+    tag: &str,
+    debug_mode: bool,
+) -> Result<BeatmapPack, OsuError> {
+    let url = format!("https://osu.ppy.sh/api/v2/beatmaps/packs/{}", tag);
+
+    let response = send_traced(
+        client.get(&url).bearer_auth(access_token),
+        "osu!",
+        "get_beatmap_pack",
+    )
+    .await
+    .map_err(OsuError::RequestError)?;
+
+    if !response.status().is_success() {
+        return Err(OsuError::ApiError(format!(
+            "無法取得譜面包 {} (狀態碼: {})",
+            tag,
+            response.status()
+        )));
+    }
+
+    let response_text = response.text().await.map_err(OsuError::RequestError)?;
+
+    if debug_mode {
+        info!("Osu API 回應 JSON: {}", response_text);
+    }
+
+    serde_json::from_str(&response_text).map_err(OsuError::JsonError)
+}
 
 pub async fn get_beatmapset_details(
     client: &Client,
@@ -164,12 +530,13 @@ pub async fn get_beatmapset_details(
 ) -> Result<(String, String), OsuError> {
     let url = format!("https://osu.ppy.sh/api/v2/beatmapsets/{}", beatmapset_id);
 
-    let response = client
-        .get(&url)
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(OsuError::RequestError)?;
+    let response = send_traced(
+        client.get(&url).bearer_auth(access_token),
+        "osu!",
+        "get_beatmapset_details",
+    )
+    .await
+    .map_err(OsuError::RequestError)?;
 
     let beatmapset: serde_json::Value = response.json().await.map_err(OsuError::RequestError)?;
 
@@ -227,10 +594,12 @@ pub async fn get_osu_token(client: &Client, debug_mode: bool) -> Result<String,
         debug!("準備發送 Osu token 請求");
     }
 
-    let response = client.post(url).form(&params).send().await.map_err(|e| {
-        error!("發送 Osu token 請求時出錯: {}", e);
-        OsuError::RequestError(e)
-    })?;
+    let response = send_traced(client.post(url).form(&params), "osu!", "get_osu_token")
+        .await
+        .map_err(|e| {
+            error!("發送 Osu token 請求時出錯: {}", e);
+            OsuError::RequestError(e)
+        })?;
 
     let token_response: TokenResponse = response.json().await.map_err(|e| {
         error!("解析 Osu token 回應時出錯: {}", e);
@@ -245,6 +614,45 @@ pub async fn get_osu_token(client: &Client, debug_mode: bool) -> Result<String,
 }
 
 impl Beatmapset {
+    // 回傳 (歌手, 歌名) 的所有寫法，第一個為羅馬拼音，之後為原文
+    pub fn name_variants(&self) -> Vec<(String, String)> {
+        let mut variants = vec![(self.artist.clone(), self.title.clone())];
+        let unicode = (
+            self.artist_unicode
+                .clone()
+                .filter(|artist| !artist.trim().is_empty())
+                .unwrap_or_else(|| self.artist.clone()),
+            self.title_unicode
+                .clone()
+                .filter(|title| !title.trim().is_empty())
+                .unwrap_or_else(|| self.title.clone()),
+        );
+        if unicode != variants[0] {
+            variants.push(unicode);
+        }
+        variants
+    }
+
+    // 歌曲長度（秒），取最長的難度；精簡的譜面集沒有難度資料時回傳 None
+    pub fn length_secs(&self) -> Option<i32> {
+        self.beatmaps
+            .iter()
+            .map(|beatmap| beatmap.total_length)
+            .max()
+    }
+
+    // mania 難度的鍵數，由小到大且不重複
+    pub fn key_counts(&self) -> Vec<u32> {
+        let mut key_counts: Vec<u32> = self
+            .beatmaps
+            .iter()
+            .filter_map(Beatmap::key_count)
+            .collect();
+        key_counts.sort_unstable();
+        key_counts.dedup();
+        key_counts
+    }
+
     pub fn format_info(&self) -> BeatmapInfo {
         let beatmaps = self.beatmaps.iter().map(|b| b.format_info()).collect();
         BeatmapInfo {
@@ -257,11 +665,29 @@ impl Beatmapset {
 }
 
 impl Beatmap {
+    // 只有 mania 的 cs 代表鍵數，其他模式為圓圈或水果大小
+    pub fn key_count(&self) -> Option<u32> {
+        (self.mode == "mania").then(|| self.cs.round() as u32)
+    }
+
+    // cs 在 osu! 與 catch 為圓圈／水果大小，在 mania 為鍵數，taiko 不使用
+    fn cs_info(&self) -> Option<String> {
+        match self.mode.as_str() {
+            "osu" | "fruits" => Some(format!("CS {:.1}", self.cs)),
+            "mania" => self.key_count().map(|keys| format!("{}K", keys)),
+            _ => None,
+        }
+    }
+
     pub fn format_info(&self) -> String {
+        let mode = match self.cs_info() {
+            Some(cs_info) => format!("{} ({})", self.mode, cs_info),
+            None => self.mode.clone(),
+        };
         format!(
             "Difficulty: {:.2} | Mode: {} | Status: {}\nLength: {} min {}s | Version: {}",
             self.difficulty_rating,
-            self.mode,
+            mode,
             self.status,
             self.total_length / 60,
             self.total_length % 60,
@@ -291,78 +717,93 @@ pub async fn load_osu_covers(
     sender: Sender<(usize, Arc<TextureHandle>, (f32, f32))>,
 ) -> Result<(), OsuError> {
     let client = Client::new();
-    let mut errors = Vec::new();
-
-    for (index, covers) in beatmapsets {
-        let urls = [
-            covers.cover,
-            covers.cover_2x,
-            covers.card,
-            covers.card_2x,
-            covers.list,
-            covers.list_2x,
-            covers.slimcover,
-            covers.slimcover_2x,
-        ];
-
-        let mut success = false;
+
+    // 多個譜面集的封面同時載入，實際的下載數由 fetch_cover_image 共用的上限控制
+    let errors: Vec<(usize, String)> = stream::iter(beatmapsets)
+        .map(|(index, covers)| load_osu_cover(&client, &ctx, &sender, index, covers))
+        .buffer_unordered(COVER_FETCH_CONCURRENCY)
+        .filter_map(|result| async move { result.err() })
+        .collect()
+        .await;
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(OsuError::CoverLoadFailed(errors))
+    }
+}
+
+async fn load_osu_cover(
+    client: &Client,
+    ctx: &egui::Context,
+    sender: &Sender<(usize, Arc<TextureHandle>, (f32, f32))>,
+    index: usize,
+    covers: Covers,
+) -> Result<(), (usize, String)> {
+    let urls = [
+        covers.cover,
+        covers.cover_2x,
+        covers.card,
+        covers.card_2x,
+        covers.list,
+        covers.list_2x,
+        covers.slimcover,
+        covers.slimcover_2x,
+    ];
+    if urls.iter().all(|url| url.is_none()) {
+        return Err((index, String::from("沒有可用的封面網址")));
+    }
+
+    let mut last_error = String::new();
+
+    // 所有網址都失敗時，等待一段時間後重試
+    for attempt in 0..=COVER_MAX_RETRIES {
+        if attempt > 0 {
+            let delay = COVER_RETRY_BASE_DELAY_MS * 2u64.pow(attempt - 1);
+            debug!(
+                "索引 {} 的封面第 {} 次重試，等待 {} ms",
+                index, attempt, delay
+            );
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
 
         for url in urls.iter().flatten() {
             debug!("正在嘗試載入封面，URL: {}", url);
-            match client.get(url).send().await {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        match response.bytes().await {
-                            Ok(bytes) => match load_from_memory(&bytes) {
-                                Ok(image) => {
-                                    debug!("成功從記憶體載入圖片，URL: {}", url);
-                                    let color_image = ColorImage::from_rgba_unmultiplied(
-                                        [image.width() as usize, image.height() as usize],
-                                        &image.to_rgba8(),
-                                    );
-                                    let texture = ctx.load_texture(
-                                        format!("cover_{}", index),
-                                        color_image,
-                                        Default::default(),
-                                    );
-                                    let texture = Arc::new(texture);
-                                    let size = (image.width() as f32, image.height() as f32);
-                                    if let Err(e) = sender.send((index, texture, size)).await {
-                                        error!("發送紋理失敗，URL: {}, 錯誤: {:?}", url, e);
-                                    } else {
-                                        debug!("成功發送紋理，URL: {}", url);
-                                        success = true;
-                                        break;  // 成功載入後跳出循環
-                                    }
-                                }
-                                Err(e) => {
-                                    error!("從記憶體載入圖片失敗，URL: {}, 錯誤: {:?}", url, e);
-                                }
-                            },
-                            Err(e) => {
-                                error!("從回應獲取位元組失敗，URL: {}, 錯誤: {:?}", url, e);
-                            }
+            match fetch_cover_image(client, url, COVER_FETCH_TIMEOUT).await {
+                Ok(image) => {
+                    debug!("成功從記憶體載入圖片，URL: {}", url);
+                    let image = downscale_image(image, THUMBNAIL_SIZE);
+                    let color_image = ColorImage::from_rgba_unmultiplied(
+                        [image.width() as usize, image.height() as usize],
+                        &image.to_rgba8(),
+                    );
+                    let texture = ctx.load_texture(
+                        format!("cover_{}", index),
+                        color_image,
+                        Default::default(),
+                    );
+                    let texture = Arc::new(texture);
+                    let size = (image.width() as f32, image.height() as f32);
+                    match sender.send((index, texture, size)).await {
+                        Ok(()) => {
+                            debug!("成功發送紋理，URL: {}", url);
+                            return Ok(());
+                        }
+                        Err(e) => {
+                            error!("發送紋理失敗，URL: {}, 錯誤: {:?}", url, e);
+                            last_error = format!("發送紋理失敗: {}", e);
                         }
-                    } else {
-                        error!("載入封面失敗，URL: {}, 狀態碼: {}", url, response.status());
                     }
                 }
                 Err(e) => {
-                    error!("發送請求失敗，URL: {}, 錯誤: {:?}", url, e);
+                    error!("載入封面失敗，URL: {}, 錯誤: {}", url, e);
+                    last_error = e.to_string();
                 }
             }
         }
-
-        if !success {
-            errors.push(format!("無法載入索引 {} 的任何封面", index));
-        }
     }
 
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        Err(OsuError::Other(errors.join("\n")))
-    }
+    Err((index, last_error))
 }
 
 pub fn is_beatmap_downloaded(download_directory: &Path, beatmapset_id: i32) -> bool {
@@ -414,12 +855,25 @@ pub fn get_downloaded_beatmaps(download_directory: &Path) -> Vec<String> {
     downloaded.into_iter().map(|(name, _)| name).collect()
 }
 
+// 整個下載（含接收檔案內容）記錄為一個操作，診斷頁面才看得出鏡像站的速度
 pub async fn download_beatmap(
+    beatmapset_id: i32,
+    download_directory: &Path,
+    update_status: impl FnMut(DownloadStatus) + Send + 'static,
+) -> Result<(), OsuError> {
+    download_beatmap_inner(beatmapset_id, download_directory, update_status)
+        .instrument(operation_span("nerinyan", "download_beatmap"))
+        .await
+}
+
+async fn download_beatmap_inner(
     beatmapset_id: i32,
     download_directory: &Path,
     mut update_status: impl FnMut(DownloadStatus) + Send + 'static,
 ) -> Result<(), OsuError> {  // 改用 OsuError
-    let url = format!("https://api.nerinyan.moe/d/{}", beatmapset_id);
+    // 檔名範本與鏡像站請求由 osu_spotify_core 的 DownloadJob 處理
+    let job = DownloadJob::new(beatmapset_id, download_directory)
+        .with_filename_template(download_options().filename_template);
 
     update_status(DownloadStatus::Downloading);
 
@@ -428,37 +882,37 @@ pub async fn download_beatmap(
         .build()
         .map_err(|e| OsuError::RequestError(e))?;
 
-    let response = client.get(&url)
-        .header("Accept", "application/x-osu-beatmap-archive")
+    let response = job.request(&client)
         .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
         .header("Origin", "https://osu.ppy.sh")
         .send()
         .await
-        .map_err(|e| OsuError::RequestError(e))?;
+        .map_err(|e| {
+            record_request_error(&e);
+            OsuError::RequestError(e)
+        })?;
+    record_status(response.status());
+    record_rate_limit("nerinyan", response.status(), response.headers());
 
     if response.status().is_success() {
-        let filename = response.headers()
-            .get("content-disposition")
-            .and_then(|cd| cd.to_str().ok())
-            .and_then(|cd| cd.split("filename=\"").nth(1))
-            .and_then(|s| s.strip_suffix("\""))
-            .unwrap_or(&format!("{}.osz", beatmapset_id))
-            .to_string();
-
-        let content = response.bytes().await.map_err(|e| OsuError::RequestError(e))?;
-
-        let download_path = download_directory.join(&filename);
-        task::spawn_blocking(move || -> Result<(), OsuError> {
-            let mut dest = File::create(&download_path)
-                .map_err(|e| OsuError::IoError(e.to_string()))?;
-            copy(&mut content.as_ref(), &mut dest)
-                .map_err(|e| OsuError::IoError(e.to_string()))?;
-            Ok(())
-        })
-        .await
-        .map_err(|e| OsuError::Other(e.to_string()))??;
+        // 分段接收以回報進度，下載管理頁面依此估算剩餘時間
+        let progress = ProgressTracker::start(beatmapset_id, response.content_length());
+        let saved_path = job
+            .save(response, |received| progress.received(received))
+            .await?;
+        let filename = saved_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("{}.osz", beatmapset_id));
 
         info!("Beatmap {} downloaded successfully as: {}", beatmapset_id, filename);
+        progress.complete();
+        record_download(beatmapset_id, &filename);
+        if osu_client_options().auto_import {
+            if let Err(e) = import_osz(&saved_path) {
+                error!("自動匯入譜面 {} 失敗: {}", beatmapset_id, e);
+            }
+        }
         update_status(DownloadStatus::Completed);
         Ok(())
     } else {
@@ -473,8 +927,13 @@ pub async fn download_beatmap(
     }
 }
 
-pub fn delete_beatmap(download_directory: &Path, beatmapset_id: i32) -> std::io::Result<()> {
+// 啟用資源回收筒時回傳可復原的項目
+pub fn delete_beatmap(
+    download_directory: &Path,
+    beatmapset_id: i32,
+) -> std::io::Result<Vec<TrashedItem>> {
     let mut deleted = false;
+    let mut trashed = Vec::new();
 
     // 尋找並刪除含有 beatmapset_id 的 .osz 文件
     let osz_pattern = format!("*{}*", beatmapset_id);
@@ -486,7 +945,7 @@ pub fn delete_beatmap(download_directory: &Path, beatmapset_id: i32) -> std::io:
                 let file_name_str = file_name.to_string_lossy();
                 if file_name_str.contains(&beatmapset_id.to_string()) || 
                    file_name_str.to_lowercase().contains(&osz_pattern.to_lowercase()) {
-                    fs::remove_file(&path)?;
+                    trashed.extend(remove_entry(download_directory, &file_name_str)?);
                     info!("已刪除 .osz 文件: {:?}", path);
                     deleted = true;
                 }
@@ -500,8 +959,14 @@ pub fn delete_beatmap(download_directory: &Path, beatmapset_id: i32) -> std::io:
         let path = entry.path();
         if path.is_dir() {
             if let Some(dir_name) = path.file_name() {
-                if dir_name.to_string_lossy().contains(&beatmapset_id.to_string()) {
-                    fs::remove_dir_all(&path)?;
+                if dir_name
+                    .to_string_lossy()
+                    .contains(&beatmapset_id.to_string())
+                {
+                    trashed.extend(remove_entry(
+                        download_directory,
+                        &dir_name.to_string_lossy(),
+                    )?);
                     info!("已刪除資料夾: {:?}", path);
                     deleted = true;
                 }
@@ -510,13 +975,15 @@ pub fn delete_beatmap(download_directory: &Path, beatmapset_id: i32) -> std::io:
     }
 
     if deleted {
-        Ok(())
+        Ok(trashed)
     } else {
         error!("未找到與 beatmapset_id {} 相關的文件或資料夾", beatmapset_id);
         Err(std::io::Error::new(std::io::ErrorKind::NotFound, "未找到相關文件或資料夾"))
     }
 }
-pub async fn preview_beatmap(beatmapset_id: i32, stream_handle: &OutputStreamHandle, volume: f32) -> Result<Sink, Box<dyn std::error::Error + Send + Sync>> {
+async fn fetch_preview_audio(
+    beatmapset_id: i32,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
     // 首先建立 reqwest Client
     let client = Client::new();
     
@@ -526,10 +993,12 @@ pub async fn preview_beatmap(beatmapset_id: i32, stream_handle: &OutputStreamHan
     let url = format!("https://osu.ppy.sh/api/v2/beatmapsets/{}", beatmapset_id);
     
     // 發送請求獲取譜面集信息，包含授權
-    let response = client.get(&url)
-        .bearer_auth(&access_token)
-        .send()
-        .await?;
+    let response = send_traced(
+        client.get(&url).bearer_auth(&access_token),
+        "osu!",
+        "fetch_preview_audio",
+    )
+    .await?;
 
     // 檢查響應狀態
     if !response.status().is_success() {
@@ -552,36 +1021,35 @@ pub async fn preview_beatmap(beatmapset_id: i32, stream_handle: &OutputStreamHan
         format!("https:{}", preview_url)
     };
     
-    info!("正在預覽 beatmapset ID: {}, URL: {}", beatmapset_id, full_preview_url);
-    
-    // 創建緩存目錄
-    let cache_dir = dirs::home_dir()
-        .ok_or("無法獲取用戶主目錄")?
-        .join("AppData")
-        .join("Local")
-        .join("SongSearch");
-    fs::create_dir_all(&cache_dir)?;
-    
-    // 生成緩存文件名
-    let cache_file = cache_dir.join(format!("preview_{}.mp3", beatmapset_id));
-    
-    let audio_bytes = if cache_file.exists() {
-        info!("使用緩存的音頻文件: {:?}", cache_file);
-        fs::read(&cache_file)?
-    } else {
-        info!("下載音頻文件: {}", full_preview_url);
-        let audio_bytes = client.get(&full_preview_url).send().await?.bytes().await?;
-        fs::write(&cache_file, &audio_bytes)?;
-        info!("音頻文件已緩存: {:?}", cache_file);
-        audio_bytes.to_vec()
+    info!("下載預覽音頻 beatmapset ID: {}, URL: {}", beatmapset_id, full_preview_url);
+    let audio_bytes = send_traced(client.get(&full_preview_url), "osu!", "fetch_preview_audio")
+        .await?
+        .bytes()
+        .await?;
+    Ok(audio_bytes.to_vec())
+}
+
+pub async fn preview_beatmap(
+    beatmapset_id: i32,
+    stream_handle: &OutputStreamHandle,
+    volume: f32,
+    speed: f32,
+    eq: EqPreset,
+) -> Result<(Sink, BufferedPreview), Box<dyn std::error::Error + Send + Sync>> {
+    // 已快取時直接播放，不需要再查詢 API
+    let cache_key = beatmapset_id.to_string();
+    let audio_bytes = match cached_preview(&cache_key) {
+        Some(audio_bytes) => audio_bytes,
+        None => {
+            let audio_bytes = fetch_preview_audio(beatmapset_id).await?;
+            store_preview(&cache_key, &audio_bytes);
+            audio_bytes
+        }
     };
     info!("音頻數據大小: {} 字節", audio_bytes.len());
-    
-    let sink = Sink::try_new(stream_handle)?;
-    let cursor = Cursor::new(audio_bytes);
-    let source = Decoder::new(cursor)?;
-    sink.set_volume(volume);
-    sink.append(source);
-    
-    Ok(sink)
+
+    // 保留音訊供跳轉時重新建立來源
+    let mut preview = BufferedPreview::new(audio_bytes)?;
+    let sink = preview.build_sink_at(Duration::ZERO, stream_handle, volume, speed, eq)?;
+    Ok((sink, preview))
 }
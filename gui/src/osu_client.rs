@@ -0,0 +1,109 @@
+// 標準庫導入
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "osu_client.json";
+
+#[derive(Error, Debug)]
+pub enum OsuClientError {
+    #[error("找不到 {0}，請確認是否已安裝")]
+    NotInstalled(&'static str),
+    #[error("IO 錯誤: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+// 匯入 .osz 的 osu! 用戶端
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum OsuClient {
+    // 以系統關聯 .osz 的程式開啟
+    #[default]
+    System,
+    Stable,
+    Lazer,
+}
+
+impl OsuClient {
+    pub const ALL: [OsuClient; 3] = [OsuClient::System, OsuClient::Stable, OsuClient::Lazer];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OsuClient::System => "系統預設",
+            OsuClient::Stable => "osu!stable",
+            OsuClient::Lazer => "osu!lazer",
+        }
+    }
+
+    // 預設安裝位置的執行檔，System 沒有固定的執行檔
+    pub fn executable(&self) -> Option<PathBuf> {
+        let local_data = dirs::data_local_dir()?;
+        let candidates = match self {
+            OsuClient::System => return None,
+            OsuClient::Stable => vec![local_data.join("osu!").join("osu!.exe")],
+            // 新版安裝程式放在 current 資料夾，舊版直接放在安裝目錄
+            OsuClient::Lazer => vec![
+                local_data.join("osulazer").join("current").join("osu!.exe"),
+                local_data.join("osulazer").join("osu!.exe"),
+            ],
+        };
+        candidates.into_iter().find(|path| path.is_file())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct OsuClientOptions {
+    pub client: OsuClient,
+    // 下載完成後立即交給 osu! 匯入，lazer 不會自動掃描下載目錄
+    #[serde(default)]
+    pub auto_import: bool,
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<OsuClientOptions> =
+        RwLock::new(load_config(OPTIONS_FILE).unwrap_or_default());
+}
+
+pub fn osu_client_options() -> OsuClientOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_osu_client_options(options: OsuClientOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存 osu! 用戶端選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 在預設位置找到的 osu! 用戶端
+pub fn installed_clients() -> Vec<OsuClient> {
+    [OsuClient::Stable, OsuClient::Lazer]
+        .into_iter()
+        .filter(|client| client.executable().is_some())
+        .collect()
+}
+
+// 以設定的用戶端開啟 .osz，兩種用戶端都會把檔案當成匯入處理
+pub fn import_osz(path: &Path) -> Result<(), OsuClientError> {
+    let client = osu_client_options().client;
+    match client {
+        OsuClient::System => open::that(path)?,
+        OsuClient::Stable | OsuClient::Lazer => {
+            let executable = client
+                .executable()
+                .ok_or(OsuClientError::NotInstalled(client.label()))?;
+            Command::new(executable).arg(path).spawn()?;
+        }
+    }
+    info!("已交給 {} 匯入: {:?}", client.label(), path);
+    Ok(())
+}
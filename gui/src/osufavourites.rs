@@ -0,0 +1,378 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use log::{error, info};
+use reqwest::Client;
+
+// 本地模組導入
+use crate::matcher::{match_spotify_track, SpotifyMatchState, CONFIDENT_MATCH_SCORE};
+use crate::osu::{
+    get_osu_token, get_user, get_user_beatmapsets, UserBeatmapset, UserBeatmapsetKind,
+};
+use crate::spotify::get_access_token;
+use crate::DownloadStatus;
+
+const LIST_LIMIT: u32 = 50;
+
+#[derive(Clone, Debug)]
+pub struct FavouriteEntry {
+    pub item: UserBeatmapset,
+    pub spotify: SpotifyMatchState,
+    pub selected: bool,
+}
+
+pub enum FavouritesAction {
+    Download(i32),
+    LoadPlaylists,
+    AddToPlaylist {
+        playlist_id: String,
+        track_ids: Vec<String>,
+    },
+}
+
+// osu! 使用者最愛 / 最常遊玩譜面的側邊選單頁面
+pub struct OsuFavourites {
+    pub show: bool,
+    username_input: String,
+    kind: UserBeatmapsetKind,
+    entries: Arc<Mutex<Vec<FavouriteEntry>>>,
+    is_loading: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<String>>>,
+    message: Arc<Mutex<Option<String>>>,
+    selected_playlist: Option<String>,
+}
+
+impl OsuFavourites {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            username_input: String::new(),
+            kind: UserBeatmapsetKind::Favourite,
+            entries: Arc::new(Mutex::new(Vec::new())),
+            is_loading: Arc::new(AtomicBool::new(false)),
+            error: Arc::new(Mutex::new(None)),
+            message: Arc::new(Mutex::new(None)),
+            selected_playlist: None,
+        }
+    }
+
+    // 打開頁面，沒有輸入過使用者時帶入預設名稱
+    pub fn open(&mut self, default_username: &str) {
+        self.show = true;
+        if self.username_input.is_empty() {
+            self.username_input = default_username.to_string();
+        }
+    }
+
+    // 加入播放清單等背景工作的結果訊息
+    pub fn message_handle(&self) -> Arc<Mutex<Option<String>>> {
+        self.message.clone()
+    }
+
+    pub fn beatmapset_ids(&self) -> Vec<i32> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.item.beatmapset.id)
+            .collect()
+    }
+
+    fn load(&mut self, ctx: egui::Context, debug_mode: bool) {
+        let username = self.username_input.trim().to_string();
+        if username.is_empty() || self.is_loading.load(Ordering::SeqCst) {
+            return;
+        }
+        let kind = self.kind;
+        let entries = self.entries.clone();
+        let is_loading = self.is_loading.clone();
+        let error = self.error.clone();
+        is_loading.store(true, Ordering::SeqCst);
+        *error.lock().unwrap() = None;
+        *self.message.lock().unwrap() = None;
+        entries.lock().unwrap().clear();
+
+        tokio::spawn(async move {
+            let client = Client::new();
+            let result = async {
+                let osu_token = get_osu_token(&client, debug_mode)
+                    .await
+                    .map_err(|e| format!("無法取得 osu! token: {}", e))?;
+                let user = get_user(&client, &osu_token, &username, debug_mode)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                get_user_beatmapsets(&client, &osu_token, user.id, kind, LIST_LIMIT, debug_mode)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            .await;
+
+            let items = match result {
+                Ok(items) => items,
+                Err(e) => {
+                    error!("載入 osu! 使用者譜面失敗: {}", e);
+                    *error.lock().unwrap() = Some(e);
+                    is_loading.store(false, Ordering::SeqCst);
+                    ctx.request_repaint();
+                    return;
+                }
+            };
+            info!("載入 {} 的 osu! 譜面: {} 個", username, items.len());
+            *entries.lock().unwrap() = items
+                .into_iter()
+                .map(|item| FavouriteEntry {
+                    item,
+                    spotify: SpotifyMatchState::Pending,
+                    selected: false,
+                })
+                .collect();
+            ctx.request_repaint();
+
+            // 逐一反向搜尋 Spotify 曲目
+            let spotify_token = match get_access_token(&client, debug_mode).await {
+                Ok(token) => token,
+                Err(e) => {
+                    error!("無法取得 Spotify token: {:?}", e);
+                    for entry in entries.lock().unwrap().iter_mut() {
+                        entry.spotify = SpotifyMatchState::Failed(e.to_string());
+                    }
+                    is_loading.store(false, Ordering::SeqCst);
+                    ctx.request_repaint();
+                    return;
+                }
+            };
+
+            let total = entries.lock().unwrap().len();
+            for index in 0..total {
+                let names = entries.lock().unwrap()[index]
+                    .item
+                    .beatmapset
+                    .name_variants();
+                let state = match_spotify_track(&client, &spotify_token, &names, debug_mode).await;
+                if let Some(entry) = entries.lock().unwrap().get_mut(index) {
+                    entry.selected = matches!(
+                        &state,
+                        SpotifyMatchState::Matched(m) if m.score >= CONFIDENT_MATCH_SCORE
+                    );
+                    entry.spotify = state;
+                }
+                ctx.request_repaint();
+            }
+
+            is_loading.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    // playlists 為 (播放清單 ID, 名稱)
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        playlists: &[(String, String)],
+        debug_mode: bool,
+    ) -> Vec<FavouritesAction> {
+        let mut actions = Vec::new();
+        let is_loading = self.is_loading.load(Ordering::SeqCst);
+
+        ui.horizontal(|ui| {
+            if ui.button("< 返回").clicked() {
+                self.show = false;
+            }
+            ui.heading("osu! 譜面列表");
+            if is_loading {
+                ui.spinner();
+            }
+        });
+        ui.add_space(10.0);
+
+        ui.horizontal(|ui| {
+            ui.label("osu! 使用者:");
+            ui.add(egui::TextEdit::singleline(&mut self.username_input).desired_width(140.0));
+        });
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.kind, UserBeatmapsetKind::Favourite, "最愛");
+            ui.selectable_value(&mut self.kind, UserBeatmapsetKind::MostPlayed, "最常遊玩");
+            if ui
+                .add_enabled(
+                    !is_loading && !self.username_input.trim().is_empty(),
+                    egui::Button::new("載入"),
+                )
+                .clicked()
+            {
+                self.load(ui.ctx().clone(), debug_mode);
+            }
+        });
+
+        let error = self.error.lock().unwrap().clone();
+        if let Some(error) = error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.is_empty() {
+            if !is_loading {
+                ui.label("輸入 osu! 使用者名稱後載入最愛或最常遊玩的譜面");
+            }
+            return actions;
+        }
+
+        ui.separator();
+
+        // 加入 Spotify 播放清單
+        if playlists.is_empty() {
+            if ui.button("載入 Spotify 播放清單").clicked() {
+                actions.push(FavouritesAction::LoadPlaylists);
+            }
+        } else {
+            let selected_name = self
+                .selected_playlist
+                .as_ref()
+                .and_then(|id| playlists.iter().find(|(pid, _)| pid == id))
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| "選擇播放清單".to_string());
+            egui::ComboBox::from_id_source("osu_favourites_playlist")
+                .selected_text(selected_name)
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for (id, name) in playlists {
+                        ui.selectable_value(&mut self.selected_playlist, Some(id.clone()), name);
+                    }
+                });
+        }
+
+        let track_ids: Vec<String> = entries
+            .iter()
+            .filter(|entry| entry.selected)
+            .filter_map(|entry| match &entry.spotify {
+                SpotifyMatchState::Matched(m) => Some(m.track_id.clone()),
+                _ => None,
+            })
+            .collect();
+        ui.horizontal(|ui| {
+            if ui.small_button("全選").clicked() {
+                for entry in entries.iter_mut() {
+                    entry.selected = matches!(entry.spotify, SpotifyMatchState::Matched(_));
+                }
+            }
+            if ui.small_button("全部取消").clicked() {
+                for entry in entries.iter_mut() {
+                    entry.selected = false;
+                }
+            }
+            if let Some(playlist_id) = &self.selected_playlist {
+                if ui
+                    .add_enabled(
+                        !track_ids.is_empty(),
+                        egui::Button::new(format!("加入播放清單 ({})", track_ids.len())),
+                    )
+                    .clicked()
+                {
+                    actions.push(FavouritesAction::AddToPlaylist {
+                        playlist_id: playlist_id.clone(),
+                        track_ids: track_ids.clone(),
+                    });
+                }
+            }
+        });
+
+        let message = self.message.lock().unwrap().clone();
+        if let Some(message) = message {
+            ui.label(message);
+        }
+
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_source("osu_favourites_entries")
+            .show(ui, |ui| {
+                for entry in entries.iter_mut() {
+                    Self::render_entry(ui, entry, download_statuses, &mut actions);
+                    ui.separator();
+                }
+            });
+
+        actions
+    }
+
+    fn render_entry(
+        ui: &mut egui::Ui,
+        entry: &mut FavouriteEntry,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        actions: &mut Vec<FavouritesAction>,
+    ) {
+        let beatmapset = &entry.item.beatmapset;
+        ui.horizontal(|ui| {
+            ui.add_enabled(
+                matches!(entry.spotify, SpotifyMatchState::Matched(_)),
+                egui::Checkbox::new(&mut entry.selected, ""),
+            );
+            ui.vertical(|ui| {
+                ui.label(egui::RichText::new(&beatmapset.title).strong());
+                if let Some(title_unicode) = beatmapset
+                    .title_unicode
+                    .as_ref()
+                    .filter(|title| *title != &beatmapset.title)
+                {
+                    ui.label(egui::RichText::new(title_unicode).small());
+                }
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(&beatmapset.artist).weak());
+                    if let Some(play_count) = entry.item.play_count {
+                        ui.label(egui::RichText::new(format!("{} 次", play_count)).weak());
+                    }
+                    let status = download_statuses
+                        .get(&beatmapset.id)
+                        .copied()
+                        .unwrap_or(DownloadStatus::NotStarted);
+                    match status {
+                        DownloadStatus::NotStarted => {
+                            if ui.small_button("下載").clicked() {
+                                actions.push(FavouritesAction::Download(beatmapset.id));
+                            }
+                        }
+                        DownloadStatus::Waiting => {
+                            ui.label("等待中");
+                        }
+                        DownloadStatus::Downloading => {
+                            ui.spinner();
+                        }
+                        DownloadStatus::Completed => {
+                            ui.label("已下載");
+                        }
+                    }
+                });
+
+                match &entry.spotify {
+                    SpotifyMatchState::Pending => {
+                        ui.label("Spotify: 搜尋中...");
+                    }
+                    SpotifyMatchState::Matched(m) => {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(format!(
+                                "Spotify: {} - {} [{:.0}%]",
+                                m.artists,
+                                m.name,
+                                m.score * 100.0
+                            ));
+                            if ui.small_button("開啟").clicked() {
+                                if let Err(e) = open::that(&m.url) {
+                                    error!("無法開啟 Spotify 連結: {:?}", e);
+                                }
+                            }
+                        });
+                    }
+                    SpotifyMatchState::NotFound => {
+                        ui.label("Spotify: 找不到曲目");
+                    }
+                    SpotifyMatchState::Failed(e) => {
+                        ui.colored_label(egui::Color32::RED, format!("Spotify: {}", e));
+                    }
+                }
+            });
+        });
+    }
+}
@@ -1,26 +1,557 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
+// 第三方庫導入
+use chrono::Local;
+use log::{error, info};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::osu::{
+    get_osu_token, get_recently_ranked_beatmapsets, get_user, is_beatmap_downloaded, Beatmap,
+    Beatmapset, OsuError, OsuUser,
+};
+use crate::DownloadStatus;
+use lib::{load_config, save_config};
+
+const FEED_SIZE: usize = 10;
+
+// 偏好的 Mod，影響實際體感難度
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum PreferredMod {
+    NoMod,
+    HardRock,
+    DoubleTime,
+}
+
+impl PreferredMod {
+    // 粗略估計 Mod 對星數的影響倍率
+    fn star_multiplier(&self) -> f32 {
+        match self {
+            PreferredMod::NoMod => 1.0,
+            PreferredMod::HardRock => 1.1,
+            PreferredMod::DoubleTime => 1.4,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            PreferredMod::NoMod => "NoMod",
+            PreferredMod::HardRock => "HR",
+            PreferredMod::DoubleTime => "DT",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OsuHelperSettings {
+    pub username: String,
+    pub use_manual_range: bool,
+    pub min_stars: f32,
+    pub max_stars: f32,
+    pub preferred_mod: PreferredMod,
+}
+
+impl Default for OsuHelperSettings {
+    fn default() -> Self {
+        Self {
+            username: String::new(),
+            use_manual_range: false,
+            min_stars: 3.0,
+            max_stars: 4.5,
+            preferred_mod: PreferredMod::NoMod,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Recommendation {
+    pub beatmapset_id: i32,
+    pub artist: String,
+    pub title: String,
+    pub creator: String,
+    pub version: String,
+    pub difficulty_rating: f32,
+    pub score: f32,
+}
+
+// 每日推薦，以日期區分，同一天內重複開啟不會重新產生
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DailyFeed {
+    pub date: String,
+    pub star_range: (f32, f32),
+    pub recommendations: Vec<Recommendation>,
+}
 
 pub struct OsuHelper {
     pub show: bool,
+    settings: OsuHelperSettings,
+    profile: Arc<Mutex<Option<OsuUser>>>,
+    feed: Arc<Mutex<Option<DailyFeed>>>,
+    is_loading: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<String>>>,
+    // 搜尋結果需要技術範圍時只自動讀取一次使用者資料
+    profile_requested: bool,
 }
 
 impl OsuHelper {
     pub fn new() -> Self {
+        let settings = load_settings().unwrap_or_default();
+        let feed = load_daily_feed().filter(|feed| feed.date == today());
         Self {
             show: false,
+            settings,
+            profile: Arc::new(Mutex::new(None)),
+            feed: Arc::new(Mutex::new(feed)),
+            is_loading: Arc::new(AtomicBool::new(false)),
+            error: Arc::new(Mutex::new(None)),
+            profile_requested: false,
         }
     }
 
+    // 設定中的 osu! 使用者名稱
+    pub fn username(&self) -> &str {
+        self.settings.username.trim()
+    }
+
+    // 目前推薦清單中的譜面集 ID，供呼叫端查詢下載狀態
+    pub fn recommended_ids(&self) -> Vec<i32> {
+        self.feed
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|feed| {
+                feed.recommendations
+                    .iter()
+                    .map(|r| r.beatmapset_id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // 根據 pp 估計適合的星數區間
+    fn star_range(&self) -> (f32, f32) {
+        self.skill_range()
+            .unwrap_or((self.settings.min_stars, self.settings.max_stars))
+    }
 
+    // 手動設定或已讀取 pp 時才有範圍，未設定時不套用預設值
+    fn skill_range(&self) -> Option<(f32, f32)> {
+        if self.settings.use_manual_range {
+            return Some((self.settings.min_stars, self.settings.max_stars));
+        }
+        let pp = self
+            .profile
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|user| user.statistics.as_ref().map(|s| s.pp))
+            .unwrap_or(0.0);
+        if pp <= 0.0 {
+            return None;
+        }
+        let center = estimate_stars_from_pp(pp);
+        Some((center - 0.5, center + 0.5))
+    }
+
+    // 譜面集中最符合使用者技術範圍的難度，沒有落在範圍內的難度時回傳 None
+    pub fn best_difficulty<'a>(
+        &mut self,
+        ctx: &egui::Context,
+        beatmapset: &'a Beatmapset,
+        debug_mode: bool,
+    ) -> Option<&'a Beatmap> {
+        let star_range = match self.skill_range() {
+            Some(star_range) => star_range,
+            None => {
+                if !self.profile_requested && !self.username().is_empty() {
+                    self.profile_requested = true;
+                    self.load_profile(ctx.clone(), debug_mode);
+                }
+                return None;
+            }
+        };
+        best_fit(beatmapset, star_range, self.settings.preferred_mod).map(|(beatmap, _)| beatmap)
+    }
 
-    pub fn render(&mut self, ctx: &egui::Context) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Osu! Helper");
-            ui.label("建置中...");
+    fn load_profile(&self, ctx: egui::Context, debug_mode: bool) {
+        let username = self.settings.username.trim().to_string();
+        if username.is_empty() {
+            return;
+        }
+        let profile = self.profile.clone();
+        let is_loading = self.is_loading.clone();
+        let error = self.error.clone();
+
+        is_loading.store(true, Ordering::SeqCst);
+        tokio::spawn(async move {
+            let client = Client::new();
+            let result: Result<OsuUser, OsuError> = async {
+                let token = get_osu_token(&client, debug_mode).await?;
+                get_user(&client, &token, &username, debug_mode).await
+            }
+            .await;
+
+            match result {
+                Ok(user) => {
+                    info!("成功讀取 osu! 使用者資料: {}", user.username);
+                    *profile.lock().unwrap() = Some(user);
+                    *error.lock().unwrap() = None;
+                }
+                Err(e) => {
+                    error!("讀取 osu! 使用者資料失敗: {:?}", e);
+                    *error.lock().unwrap() = Some(e.to_string());
+                }
+            }
+            is_loading.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    fn generate_feed(&self, ctx: egui::Context, download_directory: PathBuf, debug_mode: bool) {
+        let star_range = self.star_range();
+        let preferred_mod = self.settings.preferred_mod;
+        let feed = self.feed.clone();
+        let is_loading = self.is_loading.clone();
+        let error = self.error.clone();
+
+        is_loading.store(true, Ordering::SeqCst);
+        tokio::spawn(async move {
+            let client = Client::new();
+            let result: Result<Vec<Beatmapset>, OsuError> = async {
+                let token = get_osu_token(&client, debug_mode).await?;
+                get_recently_ranked_beatmapsets(&client, &token, "0", debug_mode).await
+            }
+            .await;
+
+            match result {
+                Ok(candidates) => {
+                    let candidates: Vec<Beatmapset> = candidates
+                        .into_iter()
+                        .filter(|b| !is_beatmap_downloaded(&download_directory, b.id))
+                        .collect();
+                    let recommendations = rank_candidates(&candidates, star_range, preferred_mod);
+                    info!(
+                        "產生每日推薦：{} 個候選，{} 個推薦",
+                        candidates.len(),
+                        recommendations.len()
+                    );
+                    let new_feed = DailyFeed {
+                        date: today(),
+                        star_range,
+                        recommendations,
+                    };
+                    if let Err(e) = save_daily_feed(&new_feed) {
+                        error!("保存每日推薦失敗: {:?}", e);
+                    }
+                    *feed.lock().unwrap() = Some(new_feed);
+                    *error.lock().unwrap() = None;
+                }
+                Err(e) => {
+                    error!("產生每日推薦失敗: {:?}", e);
+                    *error.lock().unwrap() = Some(e.to_string());
+                }
+            }
+            is_loading.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
         });
     }
 
+    // 渲染 Osu Helper 頁面，回傳使用者要求下載的譜面集 ID
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        download_directory: &Path,
+        debug_mode: bool,
+    ) -> Option<i32> {
+        let mut download_request = None;
+        let mut settings_changed = false;
+        let is_loading = self.is_loading.load(Ordering::SeqCst);
 
-    // 其他方法...
+        ui.heading("Osu! Helper");
+        ui.add_space(10.0);
+
+        ui.horizontal(|ui| {
+            ui.label("osu! 使用者名稱:");
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.settings.username)
+                        .hint_text("輸入使用者名稱...")
+                        .desired_width(200.0),
+                )
+                .lost_focus()
+            {
+                settings_changed = true;
+            }
+            if ui
+                .add_enabled(!is_loading, egui::Button::new("讀取資料"))
+                .clicked()
+            {
+                settings_changed = true;
+                self.load_profile(ui.ctx().clone(), debug_mode);
+            }
+        });
+
+        if let Some(user) = self.profile.lock().unwrap().as_ref() {
+            if let Some(statistics) = &user.statistics {
+                ui.label(format!(
+                    "{} | {:.0}pp | 全球排名: {}",
+                    user.username,
+                    statistics.pp,
+                    statistics
+                        .global_rank
+                        .map(|rank| format!("#{}", rank))
+                        .unwrap_or_else(|| "-".to_string())
+                ));
+            }
+        }
+
+        ui.add_space(10.0);
+
+        if ui
+            .checkbox(&mut self.settings.use_manual_range, "手動設定難度範圍")
+            .changed()
+        {
+            settings_changed = true;
+        }
+        if self.settings.use_manual_range {
+            ui.horizontal(|ui| {
+                ui.label("星數:");
+                settings_changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut self.settings.min_stars)
+                            .speed(0.1)
+                            .clamp_range(0.0..=self.settings.max_stars),
+                    )
+                    .changed();
+                ui.label("~");
+                settings_changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut self.settings.max_stars)
+                            .speed(0.1)
+                            .clamp_range(self.settings.min_stars..=15.0),
+                    )
+                    .changed();
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("偏好 Mod:");
+            for preferred_mod in [
+                PreferredMod::NoMod,
+                PreferredMod::HardRock,
+                PreferredMod::DoubleTime,
+            ] {
+                settings_changed |= ui
+                    .selectable_value(
+                        &mut self.settings.preferred_mod,
+                        preferred_mod,
+                        preferred_mod.label(),
+                    )
+                    .changed();
+            }
+        });
+
+        let (min_stars, max_stars) = self.star_range();
+        ui.label(format!("推薦難度: {:.2}★ ~ {:.2}★", min_stars, max_stars));
+
+        if settings_changed {
+            if let Err(e) = save_settings(&self.settings) {
+                error!("保存 Osu Helper 設定失敗: {:?}", e);
+            }
+        }
+
+        ui.add_space(10.0);
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!is_loading, egui::Button::new("重新產生推薦"))
+                .clicked()
+            {
+                self.generate_feed(
+                    ui.ctx().clone(),
+                    download_directory.to_path_buf(),
+                    debug_mode,
+                );
+            }
+            if is_loading {
+                ui.add(egui::Spinner::new());
+            }
+        });
+
+        if let Some(error) = self.error.lock().unwrap().as_ref() {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        ui.separator();
+
+        let feed = self.feed.lock().unwrap().clone();
+        match feed {
+            Some(feed) => {
+                ui.label(format!("{} 的推薦", feed.date));
+                ui.add_space(5.0);
+                if feed.recommendations.is_empty() {
+                    ui.label("沒有符合條件的譜面");
+                }
+                egui::ScrollArea::vertical()
+                    .id_source("osu_helper_feed")
+                    .show(ui, |ui| {
+                        for recommendation in &feed.recommendations {
+                            let status = download_statuses
+                                .get(&recommendation.beatmapset_id)
+                                .copied()
+                                .unwrap_or(DownloadStatus::NotStarted);
+                            if Self::render_recommendation(ui, recommendation, status) {
+                                download_request = Some(recommendation.beatmapset_id);
+                            }
+                            ui.separator();
+                        }
+                    });
+            }
+            None => {
+                ui.label("今天還沒有推薦，點擊「重新產生推薦」開始");
+            }
+        }
+
+        download_request
+    }
+
+    fn render_recommendation(
+        ui: &mut egui::Ui,
+        recommendation: &Recommendation,
+        status: DownloadStatus,
+    ) -> bool {
+        let mut clicked = false;
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} - {}",
+                        recommendation.artist, recommendation.title
+                    ))
+                    .strong(),
+                );
+                ui.label(format!(
+                    "[{}] {:.2}★ | by {}",
+                    recommendation.version,
+                    recommendation.difficulty_rating,
+                    recommendation.creator
+                ));
+            });
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                match status {
+                    DownloadStatus::Completed => {
+                        ui.label("已下載");
+                    }
+                    DownloadStatus::Downloading => {
+                        ui.add(egui::Spinner::new());
+                    }
+                    DownloadStatus::Waiting => {
+                        ui.label("等待中");
+                    }
+                    DownloadStatus::NotStarted => {
+                        if ui.button("下載").clicked() {
+                            clicked = true;
+                        }
+                    }
+                }
+                if ui.button("開啟").clicked() {
+                    let url = format!(
+                        "https://osu.ppy.sh/beatmapsets/{}",
+                        recommendation.beatmapset_id
+                    );
+                    if let Err(e) = open::that(url) {
+                        error!("無法開啟譜面頁面: {:?}", e);
+                    }
+                }
+            });
+        });
+        clicked
+    }
+}
+
+// 經驗公式：pp 越高，可遊玩的星數越高
+fn estimate_stars_from_pp(pp: f32) -> f32 {
+    (pp.powf(0.4) * 0.195).clamp(1.0, 10.0)
+}
+
+// 挑選譜面集中在星數範圍內、最接近範圍中心的 difficulty 與其分數
+fn best_fit(
+    beatmapset: &Beatmapset,
+    star_range: (f32, f32),
+    preferred_mod: PreferredMod,
+) -> Option<(&Beatmap, f32)> {
+    let (min_stars, max_stars) = star_range;
+    let center = (min_stars + max_stars) / 2.0;
+    let half_width = ((max_stars - min_stars) / 2.0).max(0.1);
+    let multiplier = preferred_mod.star_multiplier();
+
+    beatmapset
+        .beatmaps
+        .iter()
+        .filter(|beatmap| beatmap.mode == "osu")
+        .filter_map(|beatmap| {
+            let effective = beatmap.difficulty_rating * multiplier;
+            if effective < min_stars || effective > max_stars {
+                return None;
+            }
+            let score = 1.0 - (effective - center).abs() / half_width;
+            Some((beatmap, score))
+        })
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+// 為每個譜面集挑選最接近目標難度的 difficulty 並排序
+fn rank_candidates(
+    candidates: &[Beatmapset],
+    star_range: (f32, f32),
+    preferred_mod: PreferredMod,
+) -> Vec<Recommendation> {
+    let mut recommendations: Vec<Recommendation> = candidates
+        .iter()
+        .filter_map(|beatmapset| {
+            best_fit(beatmapset, star_range, preferred_mod).map(|(beatmap, score)| Recommendation {
+                beatmapset_id: beatmapset.id,
+                artist: beatmapset.artist.clone(),
+                title: beatmapset.title.clone(),
+                creator: beatmapset.creator.clone(),
+                version: beatmap.version.clone(),
+                difficulty_rating: beatmap.difficulty_rating,
+                score,
+            })
+        })
+        .collect();
+
+    recommendations.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    recommendations.truncate(FEED_SIZE);
+    recommendations
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn save_settings(settings: &OsuHelperSettings) -> Result<(), std::io::Error> {
+    save_config("osu_helper_config.json", settings)
 }
 
+fn load_settings() -> Option<OsuHelperSettings> {
+    load_config("osu_helper_config.json")
+}
+
+fn save_daily_feed(feed: &DailyFeed) -> Result<(), std::io::Error> {
+    save_config("osu_helper_daily.json", feed)
+}
+
+fn load_daily_feed() -> Option<DailyFeed> {
+    load_config("osu_helper_daily.json")
+}
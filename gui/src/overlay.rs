@@ -0,0 +1,165 @@
+// 第三方庫導入
+use log::error;
+use reqwest::Client;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+// 本地模組導入
+use crate::matcher::{rank_beatmapsets, ScoredBeatmapset};
+use crate::osu::get_osu_token;
+use crate::query_normalizer::search_beatmapsets_normalized;
+use crate::spotify::CurrentlyPlaying;
+
+// 瀏覽器來源重新讀取 /overlay.json 的間隔
+const POLL_INTERVAL_MS: u32 = 3000;
+
+// OBS 瀏覽器來源使用的頁面，透明背景，token 沿用網址上的 ?token=
+pub fn overlay_html() -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Now Playing</title>
+<style>
+  body {{ margin: 0; background: transparent; font-family: sans-serif; color: #fff; }}
+  #overlay {{ display: none; align-items: center; gap: 12px; padding: 10px 14px;
+             background: rgba(0, 0, 0, 0.6); border-radius: 10px; width: max-content; }}
+  #cover {{ width: 96px; height: 54px; object-fit: cover; border-radius: 6px; }}
+  #track {{ font-size: 20px; font-weight: bold; }}
+  #artists {{ font-size: 15px; opacity: 0.85; }}
+  #map {{ font-size: 13px; color: #ff66aa; margin-top: 4px; }}
+</style>
+</head>
+<body>
+<div id="overlay">
+  <img id="cover" alt="">
+  <div>
+    <div id="track"></div>
+    <div id="artists"></div>
+    <div id="map"></div>
+  </div>
+</div>
+<script>
+  const query = new URLSearchParams(location.search);
+  const url = "/overlay.json?token=" + encodeURIComponent(query.get("token") || "");
+  const overlay = document.getElementById("overlay");
+  const cover = document.getElementById("cover");
+  async function refresh() {{
+    try {{
+      const data = await (await fetch(url)).json();
+      if (!data.playing) {{
+        overlay.style.display = "none";
+        return;
+      }}
+      overlay.style.display = "flex";
+      document.getElementById("track").textContent = data.track.name;
+      document.getElementById("artists").textContent = data.track.artists;
+      const map = data.match;
+      document.getElementById("map").textContent = map
+        ? "osu! " + map.artist + " - " + map.title + " (" + map.creator + ") · " + map.url
+        : "沒有找到 osu! 譜面";
+      cover.style.display = map && map.cover ? "block" : "none";
+      if (map && map.cover && cover.src !== map.cover) {{
+        cover.src = map.cover;
+      }}
+    }} catch (e) {{
+      overlay.style.display = "none";
+    }}
+  }}
+  refresh();
+  setInterval(refresh, {interval});
+</script>
+</body>
+</html>
+"#,
+        interval = POLL_INTERVAL_MS
+    )
+}
+
+// 記住目前曲目的最佳譜面，換歌時才重新搜尋
+pub struct OverlayMatcher {
+    client: Client,
+    cache: Mutex<Option<(String, Option<ScoredBeatmapset>)>>,
+}
+
+impl OverlayMatcher {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    async fn best_match(&self, playing: &CurrentlyPlaying) -> Option<ScoredBeatmapset> {
+        let track = &playing.track_info;
+        let key = playing
+            .spotify_url
+            .clone()
+            .unwrap_or_else(|| format!("{} - {}", track.artists, track.name));
+
+        // 搜尋期間持有鎖，同時進來的請求等待同一次搜尋的結果
+        let mut cache = self.cache.lock().await;
+        if let Some((cached_key, cached)) = cache.as_ref() {
+            if *cached_key == key {
+                return cached.clone();
+            }
+        }
+
+        let result = match get_osu_token(&self.client, false).await {
+            Ok(token) => {
+                search_beatmapsets_normalized(
+                    &self.client,
+                    &token,
+                    &track.artists,
+                    &track.name,
+                    false,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        let best = match result {
+            Ok(beatmapsets) => rank_beatmapsets(&track.artists, &track.name, None, beatmapsets)
+                .into_iter()
+                .next(),
+            Err(e) => {
+                // 失敗時不快取，下次請求再試
+                error!("搜尋直播疊加層的譜面失敗: {:?}", e);
+                return None;
+            }
+        };
+        *cache = Some((key, best.clone()));
+        best
+    }
+
+    pub async fn payload(&self, playing: Option<CurrentlyPlaying>) -> Value {
+        let playing = match playing {
+            Some(playing) => playing,
+            None => return json!({ "playing": false }),
+        };
+        let best = self.best_match(&playing).await;
+        let track = &playing.track_info;
+        json!({
+            "playing": true,
+            "track": {
+                "name": track.name,
+                "artists": track.artists,
+                "album": track.album,
+                "url": playing.spotify_url,
+            },
+            "match": best.map(|scored| {
+                let beatmapset = scored.beatmapset;
+                json!({
+                    "id": beatmapset.id,
+                    "artist": beatmapset.artist,
+                    "title": beatmapset.title,
+                    "creator": beatmapset.creator,
+                    "score": scored.score,
+                    "url": format!("https://osu.ppy.sh/beatmapsets/{}", beatmapset.id),
+                    "cover": beatmapset.covers.card,
+                })
+            }),
+        })
+    }
+}
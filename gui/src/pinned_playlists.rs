@@ -0,0 +1,91 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info};
+use rspotify::model::SimplifiedPlaylist;
+use rspotify::prelude::Id;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const PINNED_FILE: &str = "pinned_playlists.json";
+
+// 保存名稱，播放清單尚未載入時主選單也能顯示
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PinnedPlaylist {
+    pub id: String,
+    pub name: String,
+}
+
+lazy_static! {
+    static ref PINNED: RwLock<Vec<PinnedPlaylist>> =
+        RwLock::new(load_config(PINNED_FILE).unwrap_or_default());
+}
+
+fn save_pinned(pinned: &[PinnedPlaylist]) {
+    if let Err(e) = save_config(PINNED_FILE, &pinned) {
+        error!("保存釘選的播放清單失敗: {:?}", e);
+    }
+}
+
+// 依釘選的先後順序
+pub fn pinned_playlists() -> Vec<PinnedPlaylist> {
+    PINNED.read().unwrap().clone()
+}
+
+pub fn is_pinned(playlist_id: &str) -> bool {
+    PINNED
+        .read()
+        .unwrap()
+        .iter()
+        .any(|pinned| pinned.id == playlist_id)
+}
+
+pub fn toggle_pinned(playlist: &SimplifiedPlaylist) {
+    let playlist_id = playlist.id.id();
+    let mut pinned = PINNED.write().unwrap();
+    if pinned.iter().any(|entry| entry.id == playlist_id) {
+        pinned.retain(|entry| entry.id != playlist_id);
+        info!("取消釘選播放清單: {}", playlist.name);
+    } else {
+        pinned.push(PinnedPlaylist {
+            id: playlist_id.to_string(),
+            name: playlist.name.clone(),
+        });
+        info!("釘選播放清單: {}", playlist.name);
+    }
+    save_pinned(&pinned);
+}
+
+// 釘選的播放清單依釘選順序排在最前面，其餘維持原本的順序
+pub fn sort_pinned_first(playlists: &mut [SimplifiedPlaylist]) {
+    let pinned = PINNED.read().unwrap();
+    playlists.sort_by_key(|playlist| {
+        pinned
+            .iter()
+            .position(|entry| entry.id == playlist.id.id())
+            .unwrap_or(usize::MAX)
+    });
+}
+
+// 播放清單改名後更新保存的名稱
+pub fn refresh_pinned_names(playlists: &[SimplifiedPlaylist]) {
+    let mut pinned = PINNED.write().unwrap();
+    let mut changed = false;
+    for entry in pinned.iter_mut() {
+        let name = playlists
+            .iter()
+            .find(|playlist| playlist.id.id() == entry.id)
+            .map(|playlist| &playlist.name);
+        if let Some(name) = name.filter(|name| **name != entry.name) {
+            entry.name = name.clone();
+            changed = true;
+        }
+    }
+    if changed {
+        save_pinned(&pinned);
+    }
+}
@@ -0,0 +1,99 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const FOLDERS_FILE: &str = "playlist_folders.json";
+
+// 只存在本機的播放清單資料夾，Spotify 的 API 不提供資料夾
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlaylistFolder {
+    pub name: String,
+    pub playlist_ids: Vec<String>,
+}
+
+lazy_static! {
+    static ref FOLDERS: RwLock<Vec<PlaylistFolder>> =
+        RwLock::new(load_config(FOLDERS_FILE).unwrap_or_default());
+}
+
+fn save_folders(folders: &[PlaylistFolder]) {
+    if let Err(e) = save_config(FOLDERS_FILE, &folders) {
+        error!("保存播放清單資料夾失敗: {:?}", e);
+    }
+}
+
+pub fn playlist_folders() -> Vec<PlaylistFolder> {
+    FOLDERS.read().unwrap().clone()
+}
+
+// 名稱為空或已存在時回傳 false
+pub fn add_folder(name: &str) -> bool {
+    let name = name.trim();
+    let mut folders = FOLDERS.write().unwrap();
+    if name.is_empty() || folders.iter().any(|folder| folder.name == name) {
+        return false;
+    }
+    folders.push(PlaylistFolder {
+        name: name.to_string(),
+        playlist_ids: Vec::new(),
+    });
+    save_folders(&folders);
+    info!("新增播放清單資料夾: {}", name);
+    true
+}
+
+pub fn rename_folder(index: usize, name: &str) -> bool {
+    let name = name.trim();
+    let mut folders = FOLDERS.write().unwrap();
+    if name.is_empty()
+        || folders
+            .iter()
+            .enumerate()
+            .any(|(i, folder)| i != index && folder.name == name)
+    {
+        return false;
+    }
+    match folders.get_mut(index) {
+        Some(folder) => folder.name = name.to_string(),
+        None => return false,
+    }
+    save_folders(&folders);
+    true
+}
+
+// 刪除資料夾，其中的播放清單回到未分類
+pub fn remove_folder(index: usize) {
+    let mut folders = FOLDERS.write().unwrap();
+    if index < folders.len() {
+        let folder = folders.remove(index);
+        info!("刪除播放清單資料夾: {}", folder.name);
+        save_folders(&folders);
+    }
+}
+
+// 每個播放清單最多屬於一個資料夾，None 表示移出資料夾
+pub fn move_to_folder(playlist_id: &str, folder: Option<usize>) {
+    let mut folders = FOLDERS.write().unwrap();
+    for existing in folders.iter_mut() {
+        existing.playlist_ids.retain(|id| id != playlist_id);
+    }
+    if let Some(target) = folder.and_then(|index| folders.get_mut(index)) {
+        target.playlist_ids.push(playlist_id.to_string());
+    }
+    save_folders(&folders);
+}
+
+pub fn folder_index_of(playlist_id: &str) -> Option<usize> {
+    FOLDERS
+        .read()
+        .unwrap()
+        .iter()
+        .position(|folder| folder.playlist_ids.iter().any(|id| id == playlist_id))
+}
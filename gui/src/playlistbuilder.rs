@@ -0,0 +1,377 @@
+// 標準庫導入
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use log::{error, info};
+use reqwest::Client;
+
+// 本地模組導入
+use crate::matcher::{match_spotify_track, SpotifyMatchState, CONFIDENT_MATCH_SCORE};
+use crate::osu::Beatmapset;
+use crate::spotify::get_access_token;
+
+#[derive(Clone, Debug)]
+pub struct BuilderItem {
+    pub beatmapset_id: i32,
+    pub artist: String,
+    pub title: String,
+    // 比對 Spotify 時使用的 (歌手, 歌名) 寫法，包含羅馬拼音與原文
+    pub names: Vec<(String, String)>,
+    pub spotify: SpotifyMatchState,
+    pub include: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum PlaylistTarget {
+    New { name: String },
+    Existing { playlist_id: String },
+}
+
+pub enum PlaylistBuilderAction {
+    LoadPlaylists,
+    Submit {
+        target: PlaylistTarget,
+        track_ids: Vec<String>,
+    },
+}
+
+// 從已下載的檔名 "12345 Artist - Title.osz" 取出譜面集 ID、歌手與歌名
+pub fn parse_downloaded_file_name(file_name: &str) -> Option<(i32, String, String)> {
+    let stem = file_name.strip_suffix(".osz").unwrap_or(file_name);
+    let (id, rest) = stem.split_once(' ')?;
+    let beatmapset_id = id.parse().ok()?;
+    let (artist, title) = rest.split_once(" - ")?;
+    Some((
+        beatmapset_id,
+        artist.trim().to_string(),
+        title.trim().to_string(),
+    ))
+}
+
+// 從多個 osu! 譜面集反向比對 Spotify 曲目並建立播放清單
+pub struct PlaylistBuilder {
+    pub show: bool,
+    items: Arc<Mutex<Vec<BuilderItem>>>,
+    is_resolving: Arc<AtomicBool>,
+    is_submitting: Arc<AtomicBool>,
+    message: Arc<Mutex<Option<String>>>,
+    create_new: bool,
+    playlist_name: String,
+    existing_playlist: Option<String>,
+}
+
+impl PlaylistBuilder {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            items: Arc::new(Mutex::new(Vec::new())),
+            is_resolving: Arc::new(AtomicBool::new(false)),
+            is_submitting: Arc::new(AtomicBool::new(false)),
+            message: Arc::new(Mutex::new(None)),
+            create_new: true,
+            playlist_name: String::new(),
+            existing_playlist: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn contains(&self, beatmapset_id: i32) -> bool {
+        self.items
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|item| item.beatmapset_id == beatmapset_id)
+    }
+
+    // 加入譜面集，已存在時回傳 false
+    pub fn add(&self, beatmapset_id: i32, artist: &str, title: &str) -> bool {
+        self.add_with_names(beatmapset_id, vec![(artist.to_string(), title.to_string())])
+    }
+
+    // 從搜尋結果加入，會一併使用 title_unicode 等原文名稱比對
+    pub fn add_beatmapset(&self, beatmapset: &Beatmapset) -> bool {
+        self.add_with_names(beatmapset.id, beatmapset.name_variants())
+    }
+
+    fn add_with_names(&self, beatmapset_id: i32, names: Vec<(String, String)>) -> bool {
+        let mut items = self.items.lock().unwrap();
+        if items.iter().any(|item| item.beatmapset_id == beatmapset_id) {
+            return false;
+        }
+        let (artist, title) = names[0].clone();
+        items.push(BuilderItem {
+            beatmapset_id,
+            artist,
+            title,
+            names,
+            spotify: SpotifyMatchState::Pending,
+            include: false,
+        });
+        true
+    }
+
+    pub fn message_handle(&self) -> Arc<Mutex<Option<String>>> {
+        self.message.clone()
+    }
+
+    pub fn submitting_handle(&self) -> Arc<AtomicBool> {
+        self.is_submitting.clone()
+    }
+
+    // 為尚未比對的譜面搜尋 Spotify 曲目
+    fn resolve(&self, ctx: egui::Context, debug_mode: bool) {
+        if self.is_resolving.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let items = self.items.clone();
+        let is_resolving = self.is_resolving.clone();
+        *self.message.lock().unwrap() = None;
+
+        tokio::spawn(async move {
+            let client = Client::new();
+            let spotify_token = match get_access_token(&client, debug_mode).await {
+                Ok(token) => token,
+                Err(e) => {
+                    error!("無法取得 Spotify token: {:?}", e);
+                    for item in items.lock().unwrap().iter_mut() {
+                        if matches!(item.spotify, SpotifyMatchState::Pending) {
+                            item.spotify = SpotifyMatchState::Failed(e.to_string());
+                        }
+                    }
+                    is_resolving.store(false, Ordering::SeqCst);
+                    ctx.request_repaint();
+                    return;
+                }
+            };
+
+            loop {
+                // 比對期間使用者可能繼續加入或移除項目，每次重新找下一個待比對的
+                let next = items
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find(|item| matches!(item.spotify, SpotifyMatchState::Pending))
+                    .map(|item| (item.beatmapset_id, item.names.clone()));
+                let (beatmapset_id, names) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+
+                let state = match_spotify_track(&client, &spotify_token, &names, debug_mode).await;
+                if let Some(item) = items
+                    .lock()
+                    .unwrap()
+                    .iter_mut()
+                    .find(|item| item.beatmapset_id == beatmapset_id)
+                {
+                    item.include = matches!(
+                        &state,
+                        SpotifyMatchState::Matched(m) if m.score >= CONFIDENT_MATCH_SCORE
+                    );
+                    item.spotify = state;
+                }
+                ctx.request_repaint();
+            }
+
+            info!("歌單建立器比對完成");
+            is_resolving.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    // playlists 為 (播放清單 ID, 名稱)
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        playlists: &[(String, String)],
+        debug_mode: bool,
+    ) -> Vec<PlaylistBuilderAction> {
+        let mut actions = Vec::new();
+        let is_resolving = self.is_resolving.load(Ordering::SeqCst);
+        let is_submitting = self.is_submitting.load(Ordering::SeqCst);
+
+        ui.heading("從譜面建立 Spotify 播放清單");
+        ui.label("在 osu! 搜尋結果上按右鍵，或在已下載圖譜中選擇「加入歌單」來加入譜面");
+        ui.add_space(5.0);
+
+        let items_arc = self.items.clone();
+        let mut items = items_arc.lock().unwrap();
+        let pending = items
+            .iter()
+            .filter(|item| matches!(item.spotify, SpotifyMatchState::Pending))
+            .count();
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    pending > 0 && !is_resolving,
+                    egui::Button::new(format!("比對 Spotify 曲目 ({})", pending)),
+                )
+                .clicked()
+            {
+                self.resolve(ui.ctx().clone(), debug_mode);
+            }
+            if is_resolving {
+                ui.spinner();
+                ui.label(format!("比對中，剩餘 {} 首", pending));
+            }
+            if ui
+                .add_enabled(
+                    !items.is_empty() && !is_resolving,
+                    egui::Button::new("清除全部"),
+                )
+                .clicked()
+            {
+                items.clear();
+            }
+        });
+
+        ui.separator();
+
+        if items.is_empty() {
+            ui.label("尚未加入任何譜面");
+            return actions;
+        }
+
+        let mut remove = None;
+        egui::ScrollArea::vertical()
+            .id_source("playlist_builder_items")
+            .max_height(ui.available_height() - 150.0)
+            .show(ui, |ui| {
+                for item in items.iter_mut() {
+                    ui.horizontal(|ui| {
+                        ui.add_enabled(
+                            matches!(item.spotify, SpotifyMatchState::Matched(_)),
+                            egui::Checkbox::new(&mut item.include, ""),
+                        );
+                        ui.vertical(|ui| {
+                            ui.label(
+                                egui::RichText::new(format!("{} - {}", item.artist, item.title))
+                                    .strong(),
+                            );
+                            match &item.spotify {
+                                SpotifyMatchState::Pending => {
+                                    ui.label("Spotify: 尚未比對");
+                                }
+                                SpotifyMatchState::Matched(m) => {
+                                    let color = if m.score >= CONFIDENT_MATCH_SCORE {
+                                        egui::Color32::GREEN
+                                    } else {
+                                        egui::Color32::YELLOW
+                                    };
+                                    ui.colored_label(
+                                        color,
+                                        format!(
+                                            "Spotify: {} - {} [{:.0}%]",
+                                            m.artists,
+                                            m.name,
+                                            m.score * 100.0
+                                        ),
+                                    );
+                                }
+                                SpotifyMatchState::NotFound => {
+                                    ui.label("Spotify: 找不到曲目");
+                                }
+                                SpotifyMatchState::Failed(e) => {
+                                    ui.colored_label(egui::Color32::RED, format!("Spotify: {}", e));
+                                }
+                            }
+                        });
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui
+                                .add_enabled(!is_resolving, egui::Button::new("移除"))
+                                .clicked()
+                            {
+                                remove = Some(item.beatmapset_id);
+                            }
+                        });
+                    });
+                    ui.separator();
+                }
+            });
+        if let Some(beatmapset_id) = remove {
+            items.retain(|item| item.beatmapset_id != beatmapset_id);
+        }
+
+        // 確認步驟：選擇目標播放清單並檢查要加入的曲目
+        let track_ids: Vec<String> = items
+            .iter()
+            .filter(|item| item.include)
+            .filter_map(|item| match &item.spotify {
+                SpotifyMatchState::Matched(m) => Some(m.track_id.clone()),
+                _ => None,
+            })
+            .collect();
+        drop(items);
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.create_new, true, "建立新的播放清單");
+            ui.radio_value(&mut self.create_new, false, "加入現有播放清單");
+        });
+        let target = if self.create_new {
+            ui.horizontal(|ui| {
+                ui.label("名稱:");
+                ui.text_edit_singleline(&mut self.playlist_name);
+            });
+            let name = self.playlist_name.trim();
+            (!name.is_empty()).then(|| PlaylistTarget::New {
+                name: name.to_string(),
+            })
+        } else if playlists.is_empty() {
+            if ui.button("載入 Spotify 播放清單").clicked() {
+                actions.push(PlaylistBuilderAction::LoadPlaylists);
+            }
+            None
+        } else {
+            let selected_name = self
+                .existing_playlist
+                .as_ref()
+                .and_then(|id| playlists.iter().find(|(pid, _)| pid == id))
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| "選擇播放清單".to_string());
+            egui::ComboBox::from_id_source("playlist_builder_target")
+                .selected_text(selected_name)
+                .width(250.0)
+                .show_ui(ui, |ui| {
+                    for (id, name) in playlists {
+                        ui.selectable_value(&mut self.existing_playlist, Some(id.clone()), name);
+                    }
+                });
+            self.existing_playlist
+                .clone()
+                .map(|playlist_id| PlaylistTarget::Existing { playlist_id })
+        };
+
+        ui.horizontal(|ui| {
+            let can_submit =
+                target.is_some() && !track_ids.is_empty() && !is_resolving && !is_submitting;
+            if ui
+                .add_enabled(
+                    can_submit,
+                    egui::Button::new(format!("確認加入 {} 首曲目", track_ids.len())),
+                )
+                .clicked()
+            {
+                if let Some(target) = target {
+                    self.is_submitting.store(true, Ordering::SeqCst);
+                    actions.push(PlaylistBuilderAction::Submit { target, track_ids });
+                }
+            }
+            if is_submitting {
+                ui.spinner();
+            }
+        });
+
+        let message = self.message.lock().unwrap().clone();
+        if let Some(message) = message {
+            ui.label(message);
+        }
+
+        actions
+    }
+}
@@ -0,0 +1,383 @@
+// 標準庫導入
+use std::process::Stdio;
+use std::sync::RwLock;
+
+// 第三方庫導入
+use eframe::egui;
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+// 本地模組導入
+use crate::osu::Beatmapset;
+use crate::preview_compare::osu_preview_url;
+use crate::spotify::Track;
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "plugin_actions.json";
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("IO 錯誤: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("請求錯誤: {0}")]
+    RequestError(#[from] reqwest::Error),
+    #[error("程式結束代碼: {0}")]
+    ExitStatus(std::process::ExitStatus),
+    #[error("伺服器回應錯誤，狀態碼: {0}")]
+    StatusError(reqwest::StatusCode),
+}
+
+// 動作出現在哪一種項目的右鍵選單
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ActionTarget {
+    #[default]
+    Track,
+    Beatmapset,
+    Both,
+}
+
+impl ActionTarget {
+    pub const ALL: [ActionTarget; 3] = [
+        ActionTarget::Track,
+        ActionTarget::Beatmapset,
+        ActionTarget::Both,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActionTarget::Track => "Spotify 曲目",
+            ActionTarget::Beatmapset => "osu! 譜面",
+            ActionTarget::Both => "全部",
+        }
+    }
+
+    fn includes(&self, target: ActionTarget) -> bool {
+        *self == ActionTarget::Both || *self == target
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionKind {
+    // 執行外部程式，參數中的 {欄位} 會替換為項目資料，完整 JSON 寫入標準輸入
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    // 以 POST 將項目資料的 JSON 送到網址
+    Webhook {
+        url: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PluginAction {
+    // 右鍵選單顯示的名稱
+    pub name: String,
+    #[serde(default)]
+    pub target: ActionTarget,
+    #[serde(flatten)]
+    pub kind: ActionKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PluginOptions {
+    #[serde(default)]
+    pub actions: Vec<PluginAction>,
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<PluginOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> PluginOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn plugin_options() -> PluginOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_plugin_options(options: PluginOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存自訂動作失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 重新讀取設定檔，手動編輯 plugin_actions.json 後使用
+pub fn reload_plugin_options() {
+    *OPTIONS.write().unwrap() = load_options();
+}
+
+// 適用於指定項目的動作
+pub fn actions_for(target: ActionTarget) -> Vec<PluginAction> {
+    plugin_options()
+        .actions
+        .into_iter()
+        .filter(|action| action.target.includes(target))
+        .collect()
+}
+
+pub fn track_payload(track: &Track) -> Value {
+    json!({
+        "kind": "track",
+        "name": track.name,
+        "artists": track.artists.iter().map(|artist| artist.name.clone()).collect::<Vec<_>>(),
+        "artist": track.artists.iter().map(|artist| artist.name.as_str()).collect::<Vec<_>>().join(", "),
+        "album": track.album.name,
+        "url": track.external_urls.get("spotify"),
+        "preview_url": track.preview_url,
+        "duration_ms": track.duration_ms,
+    })
+}
+
+pub fn beatmapset_payload(beatmapset: &Beatmapset) -> Value {
+    json!({
+        "kind": "beatmapset",
+        "id": beatmapset.id,
+        "artist": beatmapset.artist,
+        "title": beatmapset.title,
+        "artist_unicode": beatmapset.artist_unicode,
+        "title_unicode": beatmapset.title_unicode,
+        "creator": beatmapset.creator,
+        "url": format!("https://osu.ppy.sh/beatmapsets/{}", beatmapset.id),
+        "preview_url": beatmapset.preview_url.as_deref().map(osu_preview_url),
+        "beatmaps": beatmapset.beatmaps.iter().map(|beatmap| json!({
+            "id": beatmap.id,
+            "version": beatmap.version,
+            "mode": beatmap.mode,
+            "difficulty_rating": beatmap.difficulty_rating,
+            "status": beatmap.status,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+// 參數中 {欄位} 對應的文字，項目資料中沒有此欄位或不是字串、數字時回傳 None
+fn placeholder_value(key: &str, payload: &Value) -> Option<String> {
+    if key == "json" {
+        return Some(payload.to_string());
+    }
+    match payload.get(key)? {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+// 將參數中的 {欄位} 替換為項目資料的字串或數字欄位，{json} 為完整資料。
+// 只掃描原始參數一次，替換進去的內容即使含有 {欄位} 也不會再被展開
+fn expand_arg(arg: &str, payload: &Value) -> String {
+    let mut expanded = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let replacement = after
+            .find('}')
+            .and_then(|end| placeholder_value(&after[..end], payload).map(|text| (end, text)));
+        match replacement {
+            Some((end, text)) => {
+                expanded.push_str(&text);
+                rest = &after[end + 1..];
+            }
+            None => {
+                expanded.push('{');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+pub async fn run_action(action: &PluginAction, payload: &Value) -> Result<(), PluginError> {
+    match &action.kind {
+        ActionKind::Command { program, args } => {
+            let mut child = Command::new(program)
+                .args(args.iter().map(|arg| expand_arg(arg, payload)))
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .spawn()?;
+            if let Some(mut stdin) = child.stdin.take() {
+                // 程式不讀取標準輸入時寫入會失敗，不視為錯誤
+                if let Err(e) = stdin.write_all(payload.to_string().as_bytes()).await {
+                    warn!("寫入 {} 的標準輸入失敗: {}", program, e);
+                }
+            }
+            let status = child.wait().await?;
+            if !status.success() {
+                return Err(PluginError::ExitStatus(status));
+            }
+        }
+        ActionKind::Webhook { url } => {
+            let response = Client::new().post(url).json(payload).send().await?;
+            if !response.status().is_success() {
+                return Err(PluginError::StatusError(response.status()));
+            }
+        }
+    }
+    Ok(())
+}
+
+// 在背景執行，失敗只記錄
+pub fn spawn_action(action: PluginAction, payload: Value) {
+    tokio::spawn(async move {
+        match run_action(&action, &payload).await {
+            Ok(()) => info!("已執行自訂動作: {}", action.name),
+            Err(e) => error!("自訂動作 {} 執行失敗: {:?}", action.name, e),
+        }
+    });
+}
+
+// 設定頁面中新增與移除自訂動作
+#[derive(Default)]
+pub struct PluginActionEditor {
+    name: String,
+    target: ActionTarget,
+    is_webhook: bool,
+    program: String,
+    // 以空白分隔，需要包含空白的參數請直接編輯設定檔
+    args: String,
+    url: String,
+}
+
+impl PluginActionEditor {
+    pub fn render(&mut self, ui: &mut egui::Ui) {
+        let mut options = plugin_options();
+        let mut removed = None;
+
+        if options.actions.is_empty() {
+            ui.label(egui::RichText::new("尚未新增自訂動作").weak());
+        }
+        for (index, action) in options.actions.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(&action.name).strong());
+                ui.label(egui::RichText::new(action.target.label()).small().weak());
+                let detail = match &action.kind {
+                    ActionKind::Command { program, args } => {
+                        format!("{} {}", program, args.join(" "))
+                    }
+                    ActionKind::Webhook { url } => format!("POST {}", url),
+                };
+                ui.label(egui::RichText::new(detail).small());
+                if ui.small_button("移除").clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed {
+            options.actions.remove(index);
+            set_plugin_options(options.clone());
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("名稱:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.name)
+                    .hint_text("在 foobar2000 開啟")
+                    .desired_width(150.0),
+            );
+            egui::ComboBox::from_id_source("plugin_action_target")
+                .selected_text(self.target.label())
+                .show_ui(ui, |ui| {
+                    for target in ActionTarget::ALL {
+                        ui.selectable_value(&mut self.target, target, target.label());
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.is_webhook, false, "執行程式");
+            ui.radio_value(&mut self.is_webhook, true, "發送 Webhook");
+        });
+        if self.is_webhook {
+            ui.horizontal(|ui| {
+                ui.label("網址:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.url)
+                        .hint_text("https://example.com/hook")
+                        .desired_width(250.0),
+                );
+            });
+        } else {
+            ui.horizontal(|ui| {
+                ui.label("程式:");
+                ui.add(egui::TextEdit::singleline(&mut self.program).desired_width(200.0));
+                if ui.button("選擇").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        self.program = path.to_string_lossy().to_string();
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("參數:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.args)
+                        .hint_text("/add {preview_url}")
+                        .desired_width(250.0),
+                );
+            });
+        }
+        ui.label(
+            egui::RichText::new(
+                "參數可使用 {url}、{name}、{artist}、{title}、{id}、{preview_url} 與 {json}，程式也會從標準輸入收到完整 JSON",
+            )
+            .small()
+            .weak(),
+        );
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.can_add(), egui::Button::new("新增"))
+                .clicked()
+            {
+                options.actions.push(self.build_action());
+                set_plugin_options(options);
+                *self = Self::default();
+            }
+            if ui
+                .button("重新載入設定檔")
+                .on_hover_text(OPTIONS_FILE)
+                .clicked()
+            {
+                reload_plugin_options();
+            }
+        });
+    }
+
+    fn can_add(&self) -> bool {
+        !self.name.trim().is_empty()
+            && if self.is_webhook {
+                !self.url.trim().is_empty()
+            } else {
+                !self.program.trim().is_empty()
+            }
+    }
+
+    fn build_action(&self) -> PluginAction {
+        let kind = if self.is_webhook {
+            ActionKind::Webhook {
+                url: self.url.trim().to_string(),
+            }
+        } else {
+            ActionKind::Command {
+                program: self.program.trim().to_string(),
+                args: self.args.split_whitespace().map(str::to_string).collect(),
+            }
+        };
+        PluginAction {
+            name: self.name.trim().to_string(),
+            target: self.target,
+            kind,
+        }
+    }
+}
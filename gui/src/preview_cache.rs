@@ -0,0 +1,147 @@
+// 標準庫導入
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{debug, error, info};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::cache::{CacheKind, CacheManager};
+use lib::{get_app_data_path, load_config, save_config, write_atomic};
+
+const OPTIONS_FILE: &str = "preview_cache.json";
+// 單個預覽音訊約 100~200KB
+pub const DEFAULT_MAX_SIZE_MB: u64 = 200;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PreviewCacheOptions {
+    // 預覽音訊快取的大小上限，超過時先刪除最久未播放的檔案
+    pub max_size_mb: u64,
+}
+
+impl Default for PreviewCacheOptions {
+    fn default() -> Self {
+        Self {
+            max_size_mb: DEFAULT_MAX_SIZE_MB,
+        }
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<PreviewCacheOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> PreviewCacheOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn preview_cache_options() -> PreviewCacheOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_preview_cache_options(options: PreviewCacheOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存預覽快取選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+    evict_previews();
+}
+
+// 檔名需符合 CacheKind::Previews 的格式，才能在快取管理中顯示與清除
+fn preview_path(key: &str) -> PathBuf {
+    get_app_data_path().join(format!("preview_{}.mp3", key))
+}
+
+// 已快取的預覽音訊檔案路徑，供匯出播放清單使用
+pub fn cached_preview_path(key: &str) -> Option<PathBuf> {
+    let path = preview_path(key);
+    match fs::metadata(&path) {
+        Ok(metadata) if metadata.len() > 0 => Some(path),
+        _ => None,
+    }
+}
+
+// 讀取快取的預覽音訊，並更新修改時間作為最近使用時間
+pub fn cached_preview(key: &str) -> Option<Vec<u8>> {
+    let path = preview_path(key);
+    let audio_bytes = fs::read(&path).ok().filter(|bytes| !bytes.is_empty())?;
+    if let Err(e) = File::options()
+        .write(true)
+        .open(&path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        debug!("無法更新預覽快取的使用時間 {:?}: {:?}", path, e);
+    }
+    debug!("使用快取的預覽音訊: {:?}", path);
+    Some(audio_bytes)
+}
+
+pub fn store_preview(key: &str, audio_bytes: &[u8]) {
+    let path = preview_path(key);
+    match write_atomic(&path, audio_bytes) {
+        Ok(_) => {
+            debug!("預覽音訊已快取: {:?}", path);
+            evict_previews();
+        }
+        Err(e) => error!("保存預覽音訊快取失敗: {:?}", e),
+    }
+}
+
+// 先讀取快取，沒有時從網址下載並寫入快取
+pub async fn load_preview(key: &str, url: &str) -> Result<Vec<u8>, reqwest::Error> {
+    if let Some(audio_bytes) = cached_preview(key) {
+        return Ok(audio_bytes);
+    }
+    info!("下載預覽音訊: {}", url);
+    let audio_bytes = Client::new()
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .to_vec();
+    store_preview(key, &audio_bytes);
+    Ok(audio_bytes)
+}
+
+// 超過大小上限時，依最近使用時間由舊到新刪除
+pub fn evict_previews() {
+    let max_bytes = preview_cache_options().max_size_mb * BYTES_PER_MB;
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = CacheManager::new()
+        .files(CacheKind::Previews)
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((path, metadata.len(), modified))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= max_bytes {
+        return;
+    }
+
+    files.sort_by_key(|(_, _, modified)| *modified);
+    let mut removed = 0;
+    for (path, size, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        match fs::remove_file(&path) {
+            Ok(_) => {
+                total -= size;
+                removed += 1;
+            }
+            Err(e) => error!("刪除預覽快取 {:?} 失敗: {:?}", path, e),
+        }
+    }
+    info!("預覽快取超過上限，已刪除 {} 個最久未使用的檔案", removed);
+}
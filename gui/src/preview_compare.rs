@@ -0,0 +1,214 @@
+// 標準庫導入
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use log::error;
+use rodio::{OutputStreamHandle, Sink};
+
+// 本地模組導入
+use crate::osu::Beatmapset;
+use crate::preview_cache::load_preview;
+use crate::preview_effects::{build_preview_sink, EqPreset};
+use crate::spotify::{parse_spotify_url, SpotifyUrlKind, Track};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompareSide {
+    Spotify,
+    Osu,
+}
+
+enum CompareState {
+    Loading,
+    Ready { spotify: Sink, osu: Sink },
+    Failed(String),
+    Closed,
+}
+
+pub enum CompareAction {
+    Close,
+}
+
+// 同一組候選的 Spotify 試聽與 osu! 預覽，兩者同時載入，切換時只暫停另一邊
+pub struct PreviewCompare {
+    pub track_index: usize,
+    pub beatmapset_id: i32,
+    side: CompareSide,
+    state: Arc<Mutex<CompareState>>,
+}
+
+// 比較用的兩段預覽，key 為預覽快取的鍵
+pub struct CompareSources {
+    pub track_index: usize,
+    pub spotify_key: String,
+    pub spotify_url: String,
+    pub beatmapset_id: i32,
+    pub osu_url: String,
+}
+
+// osu! API 回傳的預覽網址可能省略協定
+pub fn osu_preview_url(url: &str) -> String {
+    if url.starts_with("http") {
+        url.to_string()
+    } else {
+        format!("https:{}", url)
+    }
+}
+
+impl PreviewCompare {
+    // 曲目或譜面沒有預覽網址時回傳 None
+    pub fn start(
+        ctx: &egui::Context,
+        stream_handle: OutputStreamHandle,
+        track: &Track,
+        beatmapset: &Beatmapset,
+        volume: f32,
+        speed: f32,
+        eq: EqPreset,
+    ) -> Option<Self> {
+        let spotify_url = track.preview_url.clone()?;
+        let spotify_key = match track
+            .external_urls
+            .get("spotify")
+            .and_then(|url| parse_spotify_url(url))
+        {
+            Some(SpotifyUrlKind::Track(id)) => format!("spotify_{}", id),
+            _ => return None,
+        };
+        let osu_url = osu_preview_url(beatmapset.preview_url.as_deref()?);
+        let sources = CompareSources {
+            track_index: track.index,
+            spotify_key,
+            spotify_url,
+            beatmapset_id: beatmapset.id,
+            osu_url,
+        };
+        Some(Self::from_sources(
+            ctx,
+            stream_handle,
+            sources,
+            volume,
+            speed,
+            eq,
+        ))
+    }
+
+    pub fn from_sources(
+        ctx: &egui::Context,
+        stream_handle: OutputStreamHandle,
+        sources: CompareSources,
+        volume: f32,
+        speed: f32,
+        eq: EqPreset,
+    ) -> Self {
+        let CompareSources {
+            track_index,
+            spotify_key,
+            spotify_url,
+            beatmapset_id,
+            osu_url,
+        } = sources;
+
+        let state = Arc::new(Mutex::new(CompareState::Loading));
+        let state_clone = state.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            let osu_key = beatmapset_id.to_string();
+            let result = async {
+                let spotify_bytes = load_preview(&spotify_key, &spotify_url).await?;
+                let osu_bytes = load_preview(&osu_key, &osu_url).await?;
+                let spotify = build_preview_sink(spotify_bytes, &stream_handle, volume, speed, eq)?;
+                let osu = build_preview_sink(osu_bytes, &stream_handle, volume, speed, eq)?;
+                osu.pause();
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>(CompareState::Ready {
+                    spotify,
+                    osu,
+                })
+            }
+            .await;
+
+            let new_state = result.unwrap_or_else(|e| {
+                error!("載入 A/B 比較預覽失敗: {:?}", e);
+                CompareState::Failed(e.to_string())
+            });
+            let mut state = state_clone.lock().unwrap();
+            // 載入期間已關閉時直接丟棄
+            if matches!(*state, CompareState::Loading) {
+                *state = new_state;
+            }
+            ctx.request_repaint();
+        });
+
+        Self {
+            track_index,
+            beatmapset_id,
+            side: CompareSide::Spotify,
+            state,
+        }
+    }
+
+    fn switch_to(&mut self, side: CompareSide) {
+        self.side = side;
+        if let CompareState::Ready { spotify, osu } = &*self.state.lock().unwrap() {
+            match side {
+                CompareSide::Spotify => {
+                    osu.pause();
+                    spotify.play();
+                }
+                CompareSide::Osu => {
+                    spotify.pause();
+                    osu.play();
+                }
+            }
+        }
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        if let CompareState::Ready { spotify, osu } = &*self.state.lock().unwrap() {
+            spotify.set_volume(volume);
+            osu.set_volume(volume);
+        }
+    }
+
+    pub fn render(&mut self, ui: &mut egui::Ui) -> Option<CompareAction> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            ui.label("A/B 比較:");
+            let (loading, error) = match &*self.state.lock().unwrap() {
+                CompareState::Loading => (true, None),
+                CompareState::Ready { .. } => (false, None),
+                CompareState::Failed(e) => (false, Some(e.clone())),
+                CompareState::Closed => return,
+            };
+            if loading {
+                ui.add(egui::Spinner::new());
+                ui.label("載入預覽中...");
+            } else if let Some(e) = error {
+                ui.colored_label(egui::Color32::RED, format!("無法播放: {}", e));
+            } else {
+                let mut side = self.side;
+                ui.selectable_value(&mut side, CompareSide::Spotify, "A: Spotify");
+                ui.selectable_value(&mut side, CompareSide::Osu, "B: osu!");
+                if side != self.side {
+                    self.switch_to(side);
+                }
+            }
+            if ui.small_button("✖").on_hover_text("停止比較").clicked() {
+                action = Some(CompareAction::Close);
+            }
+        });
+        action
+    }
+}
+
+// 關閉比較時停止兩邊的播放，載入中的結果也不再使用
+impl Drop for PreviewCompare {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        if let CompareState::Ready { spotify, osu } = &*state {
+            spotify.stop();
+            osu.stop();
+        }
+        *state = CompareState::Closed;
+    }
+}
@@ -0,0 +1,131 @@
+// 標準庫導入
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+
+// 第三方庫導入
+use rodio::{Decoder, OutputStreamHandle, Sink, Source};
+
+// 預覽播放速度範圍，HT 為 0.75x、DT 為 1.5x
+pub const MIN_PREVIEW_SPEED: f32 = 0.75;
+pub const MAX_PREVIEW_SPEED: f32 = 1.5;
+pub const SPEED_PRESETS: [(&str, f32); 3] = [("HT", 0.75), ("1x", 1.0), ("DT", 1.5)];
+
+// 以濾波器組成的簡單等化器預設
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum EqPreset {
+    #[default]
+    Flat,
+    Bass,
+    Treble,
+    Vocal,
+}
+
+impl EqPreset {
+    pub const ALL: [EqPreset; 4] = [
+        EqPreset::Flat,
+        EqPreset::Bass,
+        EqPreset::Treble,
+        EqPreset::Vocal,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EqPreset::Flat => "原音",
+            EqPreset::Bass => "低音",
+            EqPreset::Treble => "高音",
+            EqPreset::Vocal => "人聲",
+        }
+    }
+}
+
+// 將解碼後的音訊套上等化器，回傳可直接加入 Sink 的來源
+fn apply_eq<S>(source: S, preset: EqPreset) -> Box<dyn Source<Item = f32> + Send>
+where
+    S: Source + Send + 'static,
+    S::Item: rodio::Sample,
+{
+    let source = source.convert_samples::<f32>();
+    match preset {
+        EqPreset::Flat => Box::new(source),
+        EqPreset::Bass => Box::new(source.low_pass(250)),
+        EqPreset::Treble => Box::new(source.high_pass(2000)),
+        // 保留人聲主要的頻率範圍
+        EqPreset::Vocal => Box::new(source.high_pass(300).low_pass(3400)),
+    }
+}
+
+// 解碼預覽音訊並建立套用音量、速度與等化器的 Sink
+pub fn build_preview_sink(
+    audio_bytes: Vec<u8>,
+    stream_handle: &OutputStreamHandle,
+    volume: f32,
+    speed: f32,
+    eq: EqPreset,
+) -> Result<Sink, Box<dyn std::error::Error + Send + Sync>> {
+    let sink = Sink::try_new(stream_handle)?;
+    let source = Decoder::new(Cursor::new(audio_bytes))?;
+    sink.set_volume(volume);
+    // 速度會同時改變音高，與 Nightcore 相同
+    sink.set_speed(speed);
+    sink.append(apply_eq(source, eq));
+    Ok(sink)
+}
+
+// 保留已下載的預覽音訊，跳轉時從指定位置重新建立來源
+#[derive(Clone)]
+pub struct BufferedPreview {
+    audio: Arc<[u8]>,
+    total_duration: Duration,
+    // 目前的 Sink 從音訊的哪個位置開始播放
+    offset: Duration,
+}
+
+impl BufferedPreview {
+    pub fn new(audio_bytes: Vec<u8>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let audio: Arc<[u8]> = audio_bytes.into();
+        let decoder = Decoder::new(Cursor::new(audio.clone()))?;
+        // MP3 通常沒有長度資訊，需要解碼一次計算樣本數
+        let total_duration = match decoder.total_duration() {
+            Some(duration) => duration,
+            None => {
+                let samples_per_second = decoder.sample_rate() as f64 * decoder.channels() as f64;
+                let samples = decoder.count();
+                Duration::from_secs_f64(samples as f64 / samples_per_second.max(1.0))
+            }
+        };
+        Ok(Self {
+            audio,
+            total_duration,
+            offset: Duration::ZERO,
+        })
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.total_duration
+    }
+
+    // Sink 回報的位置從跳轉點開始計算
+    pub fn elapsed(&self, sink: &Sink) -> Duration {
+        (self.offset + sink.get_pos()).min(self.total_duration)
+    }
+
+    // 從 offset 開始播放，rodio 的來源無法倒轉，因此每次跳轉都重新解碼
+    pub fn build_sink_at(
+        &mut self,
+        offset: Duration,
+        stream_handle: &OutputStreamHandle,
+        volume: f32,
+        speed: f32,
+        eq: EqPreset,
+    ) -> Result<Sink, Box<dyn std::error::Error + Send + Sync>> {
+        let offset = offset.min(self.total_duration);
+        let sink = Sink::try_new(stream_handle)?;
+        let source = Decoder::new(Cursor::new(self.audio.clone()))?.skip_duration(offset);
+        sink.set_volume(volume);
+        sink.set_speed(speed);
+        sink.append(apply_eq(source, eq));
+        self.offset = offset;
+        Ok(sink)
+    }
+}
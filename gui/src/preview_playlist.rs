@@ -0,0 +1,112 @@
+// 標準庫導入
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// 第三方庫導入
+use log::{error, info};
+
+// 本地模組導入
+use crate::preview_cache::{cached_preview_path, load_preview};
+
+// 要放進播放清單的一段預覽，key 為預覽快取的鍵
+#[derive(Clone, Debug)]
+pub struct PreviewSource {
+    pub key: String,
+    pub url: String,
+    pub title: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PlaylistFormat {
+    M3u,
+    Xspf,
+}
+
+impl PlaylistFormat {
+    // 依副檔名判斷格式，預設為 m3u
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("xspf") => PlaylistFormat::Xspf,
+            _ => PlaylistFormat::M3u,
+        }
+    }
+}
+
+// 先將尚未快取的預覽下載到快取，再以快取檔案產生播放清單，回傳寫入的曲目數
+pub async fn export_preview_playlist(
+    path: PathBuf,
+    title: String,
+    sources: Vec<PreviewSource>,
+) -> io::Result<usize> {
+    let mut entries = Vec::new();
+    for source in sources {
+        if let Err(e) = load_preview(&source.key, &source.url).await {
+            error!("下載預覽 {} 失敗，略過: {:?}", source.title, e);
+            continue;
+        }
+        match cached_preview_path(&source.key) {
+            Some(file) => entries.push((source.title, file)),
+            None => error!("找不到 {} 的預覽快取，略過", source.title),
+        }
+    }
+
+    let content = match PlaylistFormat::from_path(&path) {
+        PlaylistFormat::M3u => render_m3u(&entries),
+        PlaylistFormat::Xspf => render_xspf(&title, &entries),
+    };
+    fs::write(&path, content)?;
+    info!("已匯出 {} 首預覽到播放清單: {:?}", entries.len(), path);
+    Ok(entries.len())
+}
+
+fn render_m3u(entries: &[(String, PathBuf)]) -> String {
+    let mut output = String::from("#EXTM3U\n");
+    for (title, file) in entries {
+        // 預覽長度不固定，以 -1 表示未知
+        output.push_str(&format!("#EXTINF:-1,{}\n{}\n", title, file.display()));
+    }
+    output
+}
+
+fn render_xspf(title: &str, entries: &[(String, PathBuf)]) -> String {
+    let mut output = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n  <title>{}</title>\n  <trackList>\n",
+        escape_xml(title)
+    );
+    for (title, file) in entries {
+        output.push_str(&format!(
+            "    <track>\n      <location>{}</location>\n      <title>{}</title>\n    </track>\n",
+            escape_xml(&file_uri(file)),
+            escape_xml(title)
+        ));
+    }
+    output.push_str("  </trackList>\n</playlist>\n");
+    output
+}
+
+// xspf 的 location 必須是 URI
+fn file_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let encoded: String = path
+        .chars()
+        .map(|c| match c {
+            ' ' => "%20".to_string(),
+            '#' => "%23".to_string(),
+            '%' => "%25".to_string(),
+            c => c.to_string(),
+        })
+        .collect();
+    if encoded.starts_with('/') {
+        format!("file://{}", encoded)
+    } else {
+        format!("file:///{}", encoded)
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,116 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info};
+use osu_spotify_core::text::{clean_artist, clean_title};
+use osu_spotify_core::{search_candidates, SearchRequest};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::matcher::normalize;
+use crate::osu::{get_beatmapsets, Beatmapset, OsuError};
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "query_options.json";
+// 可選擇的 Spotify 搜尋地區，部分曲目只在特定地區上架
+pub const SEARCH_MARKETS: [(&str, &str); 8] = [
+    ("TW", "台灣"),
+    ("JP", "日本"),
+    ("KR", "韓國"),
+    ("HK", "香港"),
+    ("US", "美國"),
+    ("GB", "英國"),
+    ("TH", "泰國"),
+    ("DE", "德國"),
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct QueryOptions {
+    // 將歌手名稱中的假名轉為羅馬拼音後再多搜尋一次
+    pub romanize_kana: bool,
+    // Spotify 搜尋結果中同一首歌的不同專輯版本只顯示一筆
+    #[serde(default)]
+    pub dedup_results: bool,
+    // 隱藏在目前地區無法播放的 Spotify 曲目
+    #[serde(default)]
+    pub hide_unplayable: bool,
+    // Spotify 關鍵字搜尋的地區，未選擇時不指定地區
+    #[serde(default)]
+    pub search_markets: Vec<String>,
+    #[serde(default)]
+    pub search_all_markets: bool,
+}
+
+impl QueryOptions {
+    // 實際要搜尋的地區代碼
+    pub fn markets(&self) -> Vec<String> {
+        if self.search_all_markets {
+            SEARCH_MARKETS
+                .iter()
+                .map(|(code, _)| code.to_string())
+                .collect()
+        } else {
+            self.search_markets.clone()
+        }
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<QueryOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> QueryOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn query_options() -> QueryOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_query_options(options: QueryOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存搜尋選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 判斷是否為同一首歌的鍵，忽略 feat.、remaster 等後綴與大小寫、標點
+pub fn duplicate_key(artist: &str, title: &str) -> String {
+    format!(
+        "{}|{}",
+        normalize(&clean_artist(artist)),
+        normalize(&clean_title(title))
+    )
+}
+
+// 產生要嘗試的查詢字串：原始查詢、清理後的查詢，以及（啟用時）羅馬拼音版本
+pub fn query_variants(artist: &str, title: &str) -> Vec<String> {
+    osu_spotify_core::text::query_variants(artist, title, query_options().romanize_kana)
+}
+
+// 依序嘗試各種查詢並合併結果，找到可信的匹配後就不再繼續
+pub async fn search_beatmapsets_normalized(
+    client: &Client,
+    access_token: &str,
+    artist: &str,
+    title: &str,
+    debug_mode: bool,
+) -> Result<Vec<Beatmapset>, OsuError> {
+    let request =
+        SearchRequest::new(artist, title).with_romanize_kana(query_options().romanize_kana);
+    search_candidates(&request, |query, index| async move {
+        if index > 0 {
+            info!("以清理後的查詢重新搜尋 osu!: {}", query);
+        }
+        let result = get_beatmapsets(client, access_token, &query, debug_mode).await;
+        if let Err(e) = &result {
+            error!("osu! 搜尋失敗 ({}): {:?}", query, e);
+        }
+        result
+    })
+    .await
+}
@@ -0,0 +1,115 @@
+// 第三方庫導入
+use egui::Key;
+
+// 展開操作列的搜尋結果種類，決定可用的快速操作
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResultKind {
+    Track,
+    Beatmapset,
+}
+
+impl ResultKind {
+    pub const ALL: [ResultKind; 2] = [ResultKind::Track, ResultKind::Beatmapset];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResultKind::Track => "Spotify 曲目",
+            ResultKind::Beatmapset => "osu! 譜面",
+        }
+    }
+}
+
+// 操作列上的一個按鈕，button 為按鈕在操作列中的位置
+pub struct QuickAction {
+    pub button: usize,
+    pub key: Key,
+    pub label: &'static str,
+    // 依狀態切換的名稱，例如已收藏時為「取消收藏」
+    pub toggled_label: Option<&'static str>,
+}
+
+impl QuickAction {
+    pub fn label(&self, toggled: bool) -> &'static str {
+        match self.toggled_label {
+            Some(label) if toggled => label,
+            _ => self.label,
+        }
+    }
+}
+
+const fn action(button: usize, key: Key, label: &'static str) -> QuickAction {
+    QuickAction {
+        button,
+        key,
+        label,
+        toggled_label: None,
+    }
+}
+
+const TRACK_ACTIONS: [QuickAction; 5] = [
+    action(0, Key::S, "搜尋 osu! 譜面"),
+    action(1, Key::O, "在 Spotify 開啟"),
+    QuickAction {
+        button: 2,
+        key: Key::L,
+        label: "收藏",
+        toggled_label: Some("取消收藏"),
+    },
+    action(3, Key::M, "僅搜尋 osu!"),
+    action(4, Key::Escape, "收起"),
+];
+
+const BEATMAPSET_ACTIONS: [QuickAction; 5] = [
+    action(0, Key::P, "播放預覽"),
+    action(1, Key::O, "在 osu! 中開啟"),
+    QuickAction {
+        button: 2,
+        key: Key::D,
+        label: "下載",
+        toggled_label: Some("刪除"),
+    },
+    action(3, Key::F, "以此尋找"),
+    action(4, Key::Escape, "收起"),
+];
+
+// 開關快捷鍵說明的按鍵，以輸入的字元判斷，不受鍵盤配置影響
+pub const HELP_TEXT: &str = "?";
+
+pub fn quick_actions(kind: ResultKind) -> &'static [QuickAction] {
+    match kind {
+        ResultKind::Track => &TRACK_ACTIONS,
+        ResultKind::Beatmapset => &BEATMAPSET_ACTIONS,
+    }
+}
+
+pub fn action_label(kind: ResultKind, button: usize, toggled: bool) -> &'static str {
+    quick_actions(kind)
+        .iter()
+        .find(|action| action.button == button)
+        .map_or("", |action| action.label(toggled))
+}
+
+// 文字輸入框有焦點時不處理，避免打字時觸發操作
+pub fn pressed_action(ctx: &egui::Context, kind: ResultKind) -> Option<usize> {
+    if ctx.wants_keyboard_input() {
+        return None;
+    }
+    ctx.input_mut(|input| {
+        quick_actions(kind)
+            .iter()
+            .find(|action| input.consume_key(egui::Modifiers::NONE, action.key))
+            .map(|action| action.button)
+    })
+}
+
+pub fn help_pressed(ctx: &egui::Context) -> bool {
+    if ctx.wants_keyboard_input() {
+        return false;
+    }
+    ctx.input(|input| {
+        input
+            .events
+            .iter()
+            .any(|event| matches!(event, egui::Event::Text(text) if text == HELP_TEXT))
+    })
+}
@@ -0,0 +1,162 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::warn;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+// 剩餘額度低於此比例時提醒
+const NEAR_LIMIT_RATIO: f32 = 0.1;
+// 大於此值的 reset 視為 Unix 時間戳，否則視為剩餘秒數
+const RESET_EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+#[derive(Clone, Debug, Default)]
+pub struct RateLimitStatus {
+    pub limit: Option<u32>,
+    pub remaining: Option<u32>,
+    // 額度重置的時間點
+    pub reset_at: Option<Instant>,
+    // 收到 429 時伺服器要求等待到的時間點
+    pub retry_at: Option<Instant>,
+}
+
+impl RateLimitStatus {
+    pub fn used_ratio(&self) -> Option<f32> {
+        match (self.limit, self.remaining) {
+            (Some(limit), Some(remaining)) if limit > 0 => {
+                Some(1.0 - remaining.min(limit) as f32 / limit as f32)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        self.retry_at.map_or(false, |at| at > Instant::now())
+    }
+
+    pub fn is_near_limit(&self) -> bool {
+        self.is_throttled()
+            || self
+                .used_ratio()
+                .map_or(false, |ratio| ratio >= 1.0 - NEAR_LIMIT_RATIO)
+    }
+
+    fn summary(&self) -> String {
+        let now = Instant::now();
+        let mut text = match (self.remaining, self.limit) {
+            (Some(remaining), Some(limit)) => format!("剩餘 {}/{}", remaining, limit),
+            (Some(remaining), None) => format!("剩餘 {}", remaining),
+            _ => "未提供額度資訊".to_string(),
+        };
+        if let Some(reset_at) = self.reset_at.filter(|at| *at > now) {
+            text.push_str(&format!("，{} 秒後重置", (reset_at - now).as_secs()));
+        }
+        if let Some(retry_at) = self.retry_at.filter(|at| *at > now) {
+            text.push_str(&format!(
+                "，已被限速，{} 秒後重試",
+                (retry_at - now).as_secs()
+            ));
+        }
+        text
+    }
+}
+
+lazy_static! {
+    static ref STATUS: Mutex<HashMap<&'static str, RateLimitStatus>> = Mutex::new(HashMap::new());
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn reset_instant(value: u64) -> Instant {
+    let seconds = if value >= RESET_EPOCH_THRESHOLD {
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        value.saturating_sub(now)
+    } else {
+        value
+    };
+    Instant::now() + Duration::from_secs(seconds)
+}
+
+// 每個回應都會經過這裡，記錄伺服器回傳的速率限制標頭
+// osu! 回傳 X-RateLimit-Limit/Remaining，Spotify 只在 429 時回傳 Retry-After
+pub fn record_rate_limit(service: &'static str, status: StatusCode, headers: &HeaderMap) {
+    let limit = header_u64(headers, "x-ratelimit-limit");
+    let remaining = header_u64(headers, "x-ratelimit-remaining");
+    let reset = header_u64(headers, "x-ratelimit-reset");
+    let retry_after = header_u64(headers, RETRY_AFTER.as_str());
+    let throttled = status == StatusCode::TOO_MANY_REQUESTS;
+    if limit.is_none() && remaining.is_none() && reset.is_none() && !throttled {
+        return;
+    }
+
+    let mut statuses = STATUS.lock().unwrap();
+    let entry = statuses.entry(service).or_default();
+    let was_near_limit = entry.is_near_limit();
+    if limit.is_some() {
+        entry.limit = limit.map(|value| value as u32);
+    }
+    if remaining.is_some() {
+        entry.remaining = remaining.map(|value| value as u32);
+    }
+    if let Some(reset) = reset {
+        entry.reset_at = Some(reset_instant(reset));
+    }
+    if throttled {
+        entry.retry_at = Some(reset_instant(retry_after.unwrap_or(1)));
+    }
+
+    if !was_near_limit && entry.is_near_limit() {
+        warn!("{} API 額度即將用盡: {}", service, entry.summary());
+    }
+}
+
+pub fn rate_limit_statuses() -> Vec<(&'static str, RateLimitStatus)> {
+    let mut statuses: Vec<_> = STATUS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(service, status)| (*service, status.clone()))
+        .collect();
+    statuses.sort_by(|a, b| a.0.cmp(b.0));
+    statuses
+}
+
+// 頂部列的小型額度指示，接近上限時改用警告色，滑過顯示各服務的詳細資訊
+pub fn render_rate_limit_indicator(ui: &mut egui::Ui) {
+    let statuses = rate_limit_statuses();
+    if statuses.is_empty() {
+        return;
+    }
+    let near_limit = statuses.iter().any(|(_, status)| status.is_near_limit());
+    let peak_ratio = statuses
+        .iter()
+        .filter_map(|(_, status)| status.used_ratio())
+        .fold(0.0_f32, f32::max);
+
+    let text = format!("API {:.0}%", peak_ratio * 100.0);
+    let label = if near_limit {
+        egui::RichText::new(format!("⚠ {}", text)).color(ui.visuals().warn_fg_color)
+    } else {
+        egui::RichText::new(text).weak()
+    };
+    ui.label(label).on_hover_ui(|ui| {
+        ui.strong("API 額度使用狀況");
+        for (service, status) in &statuses {
+            let line = format!("{}: {}", service, status.summary());
+            if status.is_near_limit() {
+                ui.colored_label(ui.visuals().warn_fg_color, line);
+            } else {
+                ui.label(line);
+            }
+        }
+    });
+}
@@ -0,0 +1,154 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::osu::Beatmapset;
+use crate::spotify::Track;
+use lib::{load_config, save_config};
+
+const HISTORY_FILE: &str = "recently_viewed.json";
+// 保留的最近瀏覽筆數
+const MAX_ENTRIES: usize = 50;
+
+// 展開或開啟詳細資料的 Spotify 曲目或 osu! 譜面集
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ViewedItem {
+    SpotifyTrack {
+        url: String,
+        title: String,
+        artists: String,
+    },
+    Beatmapset {
+        beatmapset_id: i32,
+        title: String,
+        artist: String,
+        creator: String,
+    },
+}
+
+impl ViewedItem {
+    // 沒有 Spotify 網址的曲目無法重新開啟，不記錄
+    pub fn from_track(track: &Track) -> Option<Self> {
+        Some(ViewedItem::SpotifyTrack {
+            url: track.external_urls.get("spotify")?.clone(),
+            title: track.name.clone(),
+            artists: track
+                .artists
+                .iter()
+                .map(|artist| artist.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+    }
+
+    pub fn title(&self) -> &str {
+        match self {
+            ViewedItem::SpotifyTrack { title, .. } | ViewedItem::Beatmapset { title, .. } => title,
+        }
+    }
+
+    pub fn subtitle(&self) -> String {
+        match self {
+            ViewedItem::SpotifyTrack { artists, .. } => artists.clone(),
+            ViewedItem::Beatmapset {
+                artist, creator, ..
+            } => format!("{} · by {}", artist, creator),
+        }
+    }
+
+    // 以網址重新搜尋即可回到該項目
+    pub fn search_query(&self) -> String {
+        match self {
+            ViewedItem::SpotifyTrack { url, .. } => url.clone(),
+            ViewedItem::Beatmapset { beatmapset_id, .. } => {
+                format!("https://osu.ppy.sh/beatmapsets/{}", beatmapset_id)
+            }
+        }
+    }
+
+    fn is_same(&self, other: &ViewedItem) -> bool {
+        match (self, other) {
+            (ViewedItem::SpotifyTrack { url: a, .. }, ViewedItem::SpotifyTrack { url: b, .. }) => {
+                a == b
+            }
+            (
+                ViewedItem::Beatmapset {
+                    beatmapset_id: a, ..
+                },
+                ViewedItem::Beatmapset {
+                    beatmapset_id: b, ..
+                },
+            ) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl From<&Beatmapset> for ViewedItem {
+    fn from(beatmapset: &Beatmapset) -> Self {
+        ViewedItem::Beatmapset {
+            beatmapset_id: beatmapset.id,
+            title: beatmapset.title.clone(),
+            artist: beatmapset.artist.clone(),
+            creator: beatmapset.creator.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ViewedEntry {
+    pub item: ViewedItem,
+    pub viewed_at: DateTime<Local>,
+}
+
+lazy_static! {
+    static ref HISTORY: RwLock<Vec<ViewedEntry>> =
+        RwLock::new(load_config(HISTORY_FILE).unwrap_or_default());
+}
+
+fn save_history(history: &[ViewedEntry]) {
+    if let Err(e) = save_config(HISTORY_FILE, &history) {
+        error!("保存最近瀏覽紀錄失敗: {:?}", e);
+    }
+}
+
+// 同一個項目只保留最新的一筆，排在最前面
+pub fn record_view(item: ViewedItem) {
+    let mut history = HISTORY.write().unwrap();
+    if history.first().map_or(false, |entry| entry.item == item) {
+        return;
+    }
+    history.retain(|entry| !entry.item.is_same(&item));
+    history.insert(
+        0,
+        ViewedEntry {
+            item,
+            viewed_at: Local::now(),
+        },
+    );
+    history.truncate(MAX_ENTRIES);
+    save_history(&history);
+}
+
+// 由新到舊
+pub fn recently_viewed() -> Vec<ViewedEntry> {
+    HISTORY.read().unwrap().clone()
+}
+
+pub fn remove_viewed(item: &ViewedItem) {
+    let mut history = HISTORY.write().unwrap();
+    history.retain(|entry| !entry.item.is_same(item));
+    save_history(&history);
+}
+
+pub fn clear_recently_viewed() {
+    let mut history = HISTORY.write().unwrap();
+    history.clear();
+    save_history(&history);
+}
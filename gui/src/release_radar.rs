@@ -0,0 +1,524 @@
+// 標準庫導入
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use log::{error, info};
+use reqwest::Client;
+use rspotify::model::{SimplifiedAlbum, SimplifiedArtist};
+use rspotify::prelude::Id;
+use rspotify::AuthCodeSpotify;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::matcher::{rank_beatmapsets, ScoredBeatmapset};
+use crate::osu::get_osu_token;
+use crate::query_normalizer::search_beatmapsets_normalized;
+use crate::spotify::{get_artist_releases, get_followed_artists, get_release_tracks};
+use crate::DownloadStatus;
+use lib::{load_config, save_config};
+
+const STATE_FILE: &str = "release_radar.json";
+// 只列出最近幾天內發行的專輯與單曲
+const RELEASE_WINDOW_DAYS: i64 = 30;
+
+// 記錄上次打開頁面的時間，用來標示之後才發行的作品
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct RadarState {
+    last_visit: Option<DateTime<Local>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReleaseTrack {
+    pub name: String,
+    pub artists: String,
+    pub duration_ms: u64,
+    pub best_match: Option<ScoredBeatmapset>,
+}
+
+impl ReleaseTrack {
+    pub fn new(name: String, artists: &[SimplifiedArtist], duration: Duration) -> Self {
+        Self {
+            name,
+            artists: artists
+                .iter()
+                .map(|artist| artist.name.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+            duration_ms: duration.num_milliseconds().max(0) as u64,
+            best_match: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ReleaseTracksState {
+    NotChecked,
+    Checking,
+    Checked(Vec<ReleaseTrack>),
+    Failed(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Release {
+    pub album_id: String,
+    pub name: String,
+    pub artists: String,
+    // album 或 single
+    pub album_type: String,
+    pub release_date: NaiveDate,
+    pub url: Option<String>,
+    pub tracks: ReleaseTracksState,
+}
+
+// 發行日期精確度可能只到月或年，只到年的無法判斷是否為新作品
+fn parse_release_date(album: &SimplifiedAlbum) -> Option<NaiveDate> {
+    let date = album.release_date.as_deref()?;
+    match album.release_date_precision.as_deref() {
+        Some("month") => NaiveDate::parse_from_str(&format!("{}-01", date), "%Y-%m-%d").ok(),
+        Some("year") => None,
+        _ => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+    }
+}
+
+fn release_from_album(album: SimplifiedAlbum) -> Option<Release> {
+    let release_date = parse_release_date(&album)?;
+    Some(Release {
+        album_id: album.id.as_ref()?.id().to_string(),
+        artists: album
+            .artists
+            .iter()
+            .map(|artist| artist.name.clone())
+            .collect::<Vec<_>>()
+            .join(", "),
+        album_type: album.album_type.clone().unwrap_or_default(),
+        url: album.external_urls.get("spotify").cloned(),
+        name: album.name,
+        release_date,
+        tracks: ReleaseTracksState::NotChecked,
+    })
+}
+
+// 「新發行」側邊選單頁面，列出追蹤的歌手最近發行的作品
+pub struct ReleaseRadar {
+    pub show: bool,
+    releases: Arc<Mutex<Vec<Release>>>,
+    // 已讀取的歌手數與總數
+    progress: Arc<Mutex<(usize, usize)>>,
+    is_loading: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<String>>>,
+    // 本次打開前的上次造訪時間
+    previous_visit: Option<DateTime<Local>>,
+    only_new: bool,
+}
+
+impl ReleaseRadar {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            releases: Arc::new(Mutex::new(Vec::new())),
+            progress: Arc::new(Mutex::new((0, 0))),
+            is_loading: Arc::new(AtomicBool::new(false)),
+            error: Arc::new(Mutex::new(None)),
+            previous_visit: None,
+            only_new: false,
+        }
+    }
+
+    // 已配對的譜面集 ID，供呼叫端查詢下載狀態
+    pub fn matched_ids(&self) -> Vec<i32> {
+        self.releases
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|release| match &release.tracks {
+                ReleaseTracksState::Checked(tracks) => Some(tracks),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|track| track.best_match.as_ref())
+            .map(|scored| scored.beatmapset.id)
+            .collect()
+    }
+
+    // 打開頁面時記錄造訪時間，第一次打開時載入
+    pub fn open(&mut self, ctx: egui::Context, spotify: Option<AuthCodeSpotify>) {
+        self.show = true;
+        let state: RadarState = load_config(STATE_FILE).unwrap_or_default();
+        self.previous_visit = state.last_visit;
+        let state = RadarState {
+            last_visit: Some(Local::now()),
+        };
+        if let Err(e) = save_config(STATE_FILE, &state) {
+            error!("保存新發行造訪時間失敗: {:?}", e);
+        }
+        if self.releases.lock().unwrap().is_empty() {
+            self.refresh(ctx, spotify);
+        }
+    }
+
+    // 上次造訪之後才發行的作品
+    fn is_new(&self, release: &Release) -> bool {
+        self.previous_visit
+            .map_or(false, |visit| release.release_date >= visit.date_naive())
+    }
+
+    fn refresh(&mut self, ctx: egui::Context, spotify: Option<AuthCodeSpotify>) {
+        let spotify = match spotify {
+            Some(spotify) => spotify,
+            None => {
+                *self.error.lock().unwrap() = Some("請先登入 Spotify".to_string());
+                return;
+            }
+        };
+        if self.is_loading.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let releases = self.releases.clone();
+        let progress = self.progress.clone();
+        let is_loading = self.is_loading.clone();
+        let error = self.error.clone();
+        *error.lock().unwrap() = None;
+
+        tokio::spawn(async move {
+            let artists = match get_followed_artists(&spotify).await {
+                Ok(artists) => artists,
+                Err(e) => {
+                    error!("取得追蹤的歌手失敗: {:?}", e);
+                    // 舊的登入沒有 user-follow-read 權限，需要重新登入
+                    *error.lock().unwrap() =
+                        Some(format!("取得追蹤的歌手失敗，請嘗試重新登入 Spotify: {}", e));
+                    is_loading.store(false, Ordering::SeqCst);
+                    ctx.request_repaint();
+                    return;
+                }
+            };
+            *progress.lock().unwrap() = (0, artists.len());
+
+            let since = Local::now().date_naive() - Duration::days(RELEASE_WINDOW_DAYS);
+            let mut seen = HashSet::new();
+            let mut found = Vec::new();
+            for (index, artist) in artists.iter().enumerate() {
+                match get_artist_releases(&spotify, artist.id.id()).await {
+                    Ok(albums) => {
+                        for release in albums.into_iter().filter_map(release_from_album) {
+                            // 合作作品會出現在多位歌手底下
+                            if release.release_date >= since
+                                && seen.insert(release.album_id.clone())
+                            {
+                                found.push(release);
+                            }
+                        }
+                    }
+                    Err(e) => error!("取得 {} 的發行作品失敗: {:?}", artist.name, e),
+                }
+                *progress.lock().unwrap() = (index + 1, artists.len());
+                ctx.request_repaint();
+            }
+
+            found.sort_by(|a, b| b.release_date.cmp(&a.release_date));
+            info!(
+                "{} 位追蹤的歌手共有 {} 個新發行作品",
+                artists.len(),
+                found.len()
+            );
+            *releases.lock().unwrap() = found;
+            is_loading.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    // 讀取作品的曲目並為每首搜尋 osu! 譜面
+    fn check_release(
+        &self,
+        album_id: String,
+        ctx: egui::Context,
+        spotify: Option<AuthCodeSpotify>,
+        debug_mode: bool,
+    ) {
+        let spotify = match spotify {
+            Some(spotify) => spotify,
+            None => return,
+        };
+        let releases = self.releases.clone();
+        set_tracks_state(&releases, &album_id, ReleaseTracksState::Checking);
+
+        tokio::spawn(async move {
+            let state = match match_release_tracks(&spotify, &album_id, debug_mode).await {
+                Ok(tracks) => ReleaseTracksState::Checked(tracks),
+                Err(e) => {
+                    error!("檢查新發行 {} 的譜面失敗: {:?}", album_id, e);
+                    ReleaseTracksState::Failed(e.to_string())
+                }
+            };
+            set_tracks_state(&releases, &album_id, state);
+            ctx.request_repaint();
+        });
+    }
+
+    // 渲染頁面，回傳使用者要求下載的譜面集 ID
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        spotify_client: &Arc<Mutex<Option<AuthCodeSpotify>>>,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        debug_mode: bool,
+    ) -> Vec<i32> {
+        let mut download_requests = Vec::new();
+        let ctx = ui.ctx().clone();
+        let is_loading = self.is_loading.load(Ordering::SeqCst);
+        let spotify = || spotify_client.lock().unwrap().clone();
+
+        ui.horizontal(|ui| {
+            if ui.button("< 返回").clicked() {
+                self.show = false;
+            }
+            ui.heading("新發行");
+            if is_loading {
+                ui.spinner();
+            }
+        });
+        ui.add_space(10.0);
+
+        let error = self.error.lock().unwrap().clone();
+        if let Some(error) = error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+        if is_loading {
+            let (loaded, total) = *self.progress.lock().unwrap();
+            ui.label(format!("已讀取 {} / {} 位追蹤的歌手", loaded, total));
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!is_loading, egui::Button::new("重新整理"))
+                .clicked()
+            {
+                self.refresh(ctx.clone(), spotify());
+            }
+            ui.checkbox(&mut self.only_new, "只顯示上次造訪後的作品");
+        });
+        ui.label(
+            egui::RichText::new(match self.previous_visit {
+                Some(visit) => format!(
+                    "最近 {} 天內的作品，上次造訪: {}",
+                    RELEASE_WINDOW_DAYS,
+                    visit.format("%Y-%m-%d %H:%M")
+                ),
+                None => format!("最近 {} 天內的作品", RELEASE_WINDOW_DAYS),
+            })
+            .weak(),
+        );
+        ui.separator();
+
+        let releases: Vec<Release> = self
+            .releases
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|release| !self.only_new || self.is_new(release))
+            .cloned()
+            .collect();
+        let mut check_request = None;
+        egui::ScrollArea::vertical()
+            .id_source("release_radar")
+            .show(ui, |ui| {
+                if releases.is_empty() && !is_loading {
+                    ui.label("沒有新發行的作品");
+                }
+                for release in &releases {
+                    self.render_release(
+                        ui,
+                        release,
+                        download_statuses,
+                        &mut check_request,
+                        &mut download_requests,
+                    );
+                    ui.separator();
+                }
+            });
+        if let Some(album_id) = check_request {
+            self.check_release(album_id, ctx, spotify(), debug_mode);
+        }
+
+        download_requests
+    }
+
+    fn render_release(
+        &self,
+        ui: &mut egui::Ui,
+        release: &Release,
+        download_statuses: &HashMap<i32, DownloadStatus>,
+        check_request: &mut Option<String>,
+        download_requests: &mut Vec<i32>,
+    ) {
+        ui.horizontal_wrapped(|ui| {
+            if self.is_new(release) {
+                ui.label(
+                    egui::RichText::new("NEW")
+                        .strong()
+                        .color(ui.visuals().warn_fg_color),
+                )
+                .on_hover_text("上次造訪之後發行");
+            }
+            ui.label(egui::RichText::new(&release.name).strong());
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label(egui::RichText::new(&release.artists).weak());
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} · {}",
+                    release.album_type,
+                    release.release_date.format("%Y-%m-%d")
+                ))
+                .weak(),
+            );
+            if let Some(url) = &release.url {
+                if ui.small_button("開啟").clicked() {
+                    if let Err(e) = open::that(url) {
+                        error!("無法開啟 Spotify 頁面: {:?}", e);
+                    }
+                }
+            }
+        });
+
+        match &release.tracks {
+            ReleaseTracksState::NotChecked => {
+                if ui.small_button("檢查 osu! 譜面").clicked() {
+                    *check_request = Some(release.album_id.clone());
+                }
+            }
+            ReleaseTracksState::Checking => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("搜尋譜面中...");
+                });
+            }
+            ReleaseTracksState::Checked(tracks) => {
+                for track in tracks {
+                    render_track(ui, track, download_statuses, download_requests);
+                }
+            }
+            ReleaseTracksState::Failed(error) => {
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::RED, format!("檢查失敗: {}", error));
+                    if ui.small_button("重試").clicked() {
+                        *check_request = Some(release.album_id.clone());
+                    }
+                });
+            }
+        }
+    }
+}
+
+// 曲目與配對到的譜面，追蹤的歌手頁面也會使用
+pub fn render_track(
+    ui: &mut egui::Ui,
+    track: &ReleaseTrack,
+    download_statuses: &HashMap<i32, DownloadStatus>,
+    download_requests: &mut Vec<i32>,
+) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label(format!(
+            "{} ({}:{:02})",
+            track.name,
+            track.duration_ms / 60000,
+            track.duration_ms / 1000 % 60
+        ));
+        let scored = match &track.best_match {
+            Some(scored) => scored,
+            None => {
+                ui.label(egui::RichText::new("找不到譜面").weak());
+                return;
+            }
+        };
+        let beatmapset = &scored.beatmapset;
+        ui.label(format!(
+            "→ {} - {} [{:.0}%]",
+            beatmapset.artist,
+            beatmapset.title,
+            scored.score * 100.0
+        ));
+        let status = download_statuses
+            .get(&beatmapset.id)
+            .copied()
+            .unwrap_or(DownloadStatus::NotStarted);
+        match status {
+            DownloadStatus::NotStarted => {
+                if ui.small_button("下載").clicked() {
+                    download_requests.push(beatmapset.id);
+                }
+            }
+            DownloadStatus::Waiting => {
+                ui.label("等待中");
+            }
+            DownloadStatus::Downloading => {
+                ui.spinner();
+            }
+            DownloadStatus::Completed => {
+                ui.label("已下載");
+            }
+        }
+    });
+}
+
+fn set_tracks_state(releases: &Mutex<Vec<Release>>, album_id: &str, state: ReleaseTracksState) {
+    if let Some(release) = releases
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .find(|release| release.album_id == album_id)
+    {
+        release.tracks = state;
+    }
+}
+
+async fn match_release_tracks(
+    spotify: &AuthCodeSpotify,
+    album_id: &str,
+    debug_mode: bool,
+) -> anyhow::Result<Vec<ReleaseTrack>> {
+    let tracks = get_release_tracks(spotify, album_id)
+        .await?
+        .into_iter()
+        .map(|track| ReleaseTrack::new(track.name, &track.artists, track.duration))
+        .collect();
+    match_tracks(tracks, debug_mode).await
+}
+
+// 為每首曲目搜尋 osu! 譜面並填入分數最高的結果
+pub async fn match_tracks(
+    mut tracks: Vec<ReleaseTrack>,
+    debug_mode: bool,
+) -> anyhow::Result<Vec<ReleaseTrack>> {
+    let client = Client::new();
+    let osu_token = get_osu_token(&client, debug_mode).await?;
+
+    for track in &mut tracks {
+        track.best_match = match search_beatmapsets_normalized(
+            &client,
+            &osu_token,
+            &track.artists,
+            &track.name,
+            debug_mode,
+        )
+        .await
+        {
+            Ok(beatmapsets) => rank_beatmapsets(
+                &track.artists,
+                &track.name,
+                Some(track.duration_ms),
+                beatmapsets,
+            )
+            .into_iter()
+            .next(),
+            Err(e) => {
+                error!("搜尋 {} - {} 失敗: {:?}", track.artists, track.name, e);
+                None
+            }
+        };
+    }
+    Ok(tracks)
+}
@@ -0,0 +1,241 @@
+// 標準庫導入
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// 第三方庫導入
+use chrono::Local;
+
+// 本地模組導入
+use crate::matcher::ScoredBeatmapset;
+
+#[derive(Clone, Debug)]
+pub struct ReportTrack {
+    pub name: String,
+    pub artists: String,
+    pub album: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReportMatch {
+    pub beatmapset_id: i32,
+    pub artist: String,
+    pub title: String,
+    pub creator: String,
+    pub score: f32,
+}
+
+impl From<&ScoredBeatmapset> for ReportMatch {
+    fn from(scored: &ScoredBeatmapset) -> Self {
+        Self {
+            beatmapset_id: scored.beatmapset.id,
+            artist: scored.beatmapset.artist.clone(),
+            title: scored.beatmapset.title.clone(),
+            creator: scored.beatmapset.creator.clone(),
+            score: scored.score,
+        }
+    }
+}
+
+impl ReportMatch {
+    pub fn url(&self) -> String {
+        format!("https://osu.ppy.sh/beatmapsets/{}", self.beatmapset_id)
+    }
+}
+
+// 報告中的一列：原始查詢、對應的 Spotify 曲目與 osu! 譜面
+#[derive(Clone, Debug)]
+pub struct ReportEntry {
+    pub query: String,
+    pub track: Option<ReportTrack>,
+    pub matches: Vec<ReportMatch>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    // 依副檔名判斷格式，預設為 Markdown
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm") => {
+                ReportFormat::Html
+            }
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+pub fn export_report(path: &Path, title: &str, entries: &[ReportEntry]) -> io::Result<()> {
+    let content = match ReportFormat::from_path(path) {
+        ReportFormat::Markdown => render_markdown(title, entries),
+        ReportFormat::Html => render_html(title, entries),
+    };
+    fs::write(path, content)
+}
+
+fn matched_count(entries: &[ReportEntry]) -> usize {
+    entries.iter().filter(|e| !e.matches.is_empty()).count()
+}
+
+pub fn render_markdown(title: &str, entries: &[ReportEntry]) -> String {
+    let mut output = format!(
+        "# {}\n\n產生時間: {}  \n共 {} 首，{} 首找到譜面\n\n",
+        title,
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        entries.len(),
+        matched_count(entries)
+    );
+
+    for (index, entry) in entries.iter().enumerate() {
+        match &entry.track {
+            Some(track) => {
+                let name = match &track.url {
+                    Some(url) => format!("[{}]({})", escape_markdown(&track.name), url),
+                    None => escape_markdown(&track.name),
+                };
+                output.push_str(&format!(
+                    "## {}. {} - {}\n\n",
+                    index + 1,
+                    escape_markdown(&track.artists),
+                    name
+                ));
+                if let Some(album) = &track.album {
+                    output.push_str(&format!("專輯: {}\n\n", escape_markdown(album)));
+                }
+            }
+            None => {
+                output.push_str(&format!(
+                    "## {}. {}\n\n",
+                    index + 1,
+                    escape_markdown(&entry.query)
+                ));
+            }
+        }
+
+        if entry.matches.is_empty() {
+            output.push_str("_找不到譜面_\n\n");
+            continue;
+        }
+
+        output.push_str("| 譜面 | 作者 | 匹配度 |\n|---|---|---|\n");
+        for m in &entry.matches {
+            output.push_str(&format!(
+                "| [{} - {}]({}) | {} | {:.0}% |\n",
+                escape_markdown(&m.artist),
+                escape_markdown(&m.title),
+                m.url(),
+                escape_markdown(&m.creator),
+                m.score * 100.0
+            ));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+pub fn render_html(title: &str, entries: &[ReportEntry]) -> String {
+    let mut rows = String::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let track_cell = match &entry.track {
+            Some(track) => {
+                let name = match &track.url {
+                    Some(url) => format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape_html(url),
+                        escape_html(&track.name)
+                    ),
+                    None => escape_html(&track.name),
+                };
+                let album = track
+                    .album
+                    .as_ref()
+                    .map(|album| format!("<div class=\"album\">{}</div>", escape_html(album)))
+                    .unwrap_or_default();
+                format!(
+                    "<div class=\"track\">{}</div><div class=\"artist\">{}</div>{}",
+                    name,
+                    escape_html(&track.artists),
+                    album
+                )
+            }
+            None => format!("<div class=\"track\">{}</div>", escape_html(&entry.query)),
+        };
+
+        let matches_cell = if entry.matches.is_empty() {
+            "<span class=\"none\">找不到譜面</span>".to_string()
+        } else {
+            entry
+                .matches
+                .iter()
+                .map(|m| {
+                    format!(
+                        "<div><a href=\"{}\">{} - {}</a> <span class=\"creator\">by {}</span> <span class=\"score\">{:.0}%</span></div>",
+                        m.url(),
+                        escape_html(&m.artist),
+                        escape_html(&m.title),
+                        escape_html(&m.creator),
+                        m.score * 100.0
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("")
+        };
+
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            index + 1,
+            track_cell,
+            matches_cell
+        ));
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="zh-Hant">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; background: #121212; color: #eee; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border-bottom: 1px solid #333; padding: 8px; text-align: left; vertical-align: top; }}
+a {{ color: #1db954; }}
+.artist, .album, .creator {{ color: #aaa; font-size: 0.9em; }}
+.score {{ color: #ff66aa; font-weight: bold; }}
+.none {{ color: #888; font-style: italic; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>產生時間: {time}<br>共 {total} 首，{matched} 首找到譜面</p>
+<table>
+<tr><th>#</th><th>Spotify</th><th>osu! 譜面</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        time = Local::now().format("%Y-%m-%d %H:%M:%S"),
+        total = entries.len(),
+        matched = matched_count(entries),
+        rows = rows
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
@@ -0,0 +1,131 @@
+// 標準庫導入
+use std::sync::RwLock;
+
+// 第三方庫導入
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::match_memory::is_rejected;
+use crate::matcher::ScoredBeatmapset;
+use lib::{load_config, save_config};
+
+const QUEUE_FILE: &str = "review_queue.json";
+
+// 自動配對時的 Spotify 曲目
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReviewTrack {
+    pub spotify_track_id: Option<String>,
+    pub artist: String,
+    pub title: String,
+    pub duration_ms: Option<u64>,
+    pub preview_url: Option<String>,
+}
+
+impl ReviewTrack {
+    // 拒絕紀錄使用的鍵，沒有 Spotify 曲目 ID 時改用「演出者 - 曲名」
+    pub fn key(&self) -> String {
+        self.spotify_track_id
+            .clone()
+            .unwrap_or_else(|| format!("{} - {}", self.artist, self.title))
+    }
+}
+
+// 分數最高但未達門檻的譜面集
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReviewCandidate {
+    pub beatmapset_id: i32,
+    pub artist: String,
+    pub title: String,
+    pub creator: String,
+    pub preview_url: Option<String>,
+    // 最長難度的長度（秒）
+    pub length_secs: Option<i32>,
+}
+
+impl From<&ScoredBeatmapset> for ReviewCandidate {
+    fn from(scored: &ScoredBeatmapset) -> Self {
+        let beatmapset = &scored.beatmapset;
+        Self {
+            beatmapset_id: beatmapset.id,
+            artist: beatmapset.artist.clone(),
+            title: beatmapset.title.clone(),
+            creator: beatmapset.creator.clone(),
+            preview_url: beatmapset.preview_url.clone(),
+            length_secs: beatmapset
+                .beatmaps
+                .iter()
+                .map(|beatmap| beatmap.total_length)
+                .max(),
+        }
+    }
+}
+
+// 自動下載或同步模式中分數低於門檻的配對，等待使用者確認後才下載
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReviewItem {
+    pub track: ReviewTrack,
+    pub candidate: ReviewCandidate,
+    pub score: f32,
+    // 來源播放清單或「同步模式」
+    pub source: String,
+    pub added_at: DateTime<Local>,
+}
+
+lazy_static! {
+    static ref QUEUE: RwLock<Vec<ReviewItem>> = RwLock::new(load_queue());
+}
+
+fn load_queue() -> Vec<ReviewItem> {
+    load_config(QUEUE_FILE).unwrap_or_default()
+}
+
+fn save_queue(queue: &[ReviewItem]) {
+    if let Err(e) = save_config(QUEUE_FILE, &queue) {
+        error!("保存待確認配對失敗: {:?}", e);
+    }
+}
+
+pub fn review_items() -> Vec<ReviewItem> {
+    QUEUE.read().unwrap().clone()
+}
+
+pub fn review_count() -> usize {
+    QUEUE.read().unwrap().len()
+}
+
+// 同一首曲目與譜面集只保留一筆，已被拒絕的配對不再加入
+pub fn add_review_item(item: ReviewItem) {
+    if is_rejected(&item.track.key(), item.candidate.beatmapset_id) {
+        return;
+    }
+    let mut queue = QUEUE.write().unwrap();
+    if queue.iter().any(|existing| {
+        existing.candidate.beatmapset_id == item.candidate.beatmapset_id
+            && existing.track.artist == item.track.artist
+            && existing.track.title == item.track.title
+    }) {
+        return;
+    }
+    info!(
+        "加入待確認配對: {} - {} -> {} ({:.0}%)",
+        item.track.artist,
+        item.track.title,
+        item.candidate.beatmapset_id,
+        item.score * 100.0
+    );
+    queue.push(item);
+    save_queue(&queue);
+}
+
+pub fn remove_review_item(index: usize) -> Option<ReviewItem> {
+    let mut queue = QUEUE.write().unwrap();
+    if index >= queue.len() {
+        return None;
+    }
+    let item = queue.remove(index);
+    save_queue(&queue);
+    Some(item)
+}
@@ -0,0 +1,230 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// 第三方庫導入
+use chrono::{Local, NaiveDateTime, TimeZone};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+// 本地模組導入
+use crate::download_options::low_disk_space;
+use crate::DownloadStatus;
+use lib::{load_config, save_config};
+
+const SCHEDULE_FILE: &str = "scheduled_downloads.json";
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+pub const DEFAULT_IDLE_MINUTES: u32 = 10;
+
+// 稍後下載的觸發條件
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum ScheduleTrigger {
+    // 指定時間（Unix 時間戳，秒）
+    At { timestamp: i64 },
+    // 閒置（沒有搜尋）達指定分鐘數
+    Idle { minutes: u32 },
+}
+
+impl ScheduleTrigger {
+    pub fn describe(&self) -> String {
+        match self {
+            ScheduleTrigger::At { timestamp } => format!("於 {}", format_local_time(*timestamp)),
+            ScheduleTrigger::Idle { minutes } => format!("閒置 {} 分鐘後", minutes),
+        }
+    }
+
+    fn is_due(&self, idle_for: Duration) -> bool {
+        match self {
+            ScheduleTrigger::At { timestamp } => Local::now().timestamp() >= *timestamp,
+            ScheduleTrigger::Idle { minutes } => {
+                idle_for >= Duration::from_secs(*minutes as u64 * 60)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScheduledDownload {
+    pub beatmapset_id: i32,
+    pub label: String,
+    pub trigger: ScheduleTrigger,
+}
+
+// 稍後下載排程，排程到期時送入既有的下載隊列
+#[derive(Clone)]
+pub struct DownloadScheduler {
+    items: Arc<Mutex<Vec<ScheduledDownload>>>,
+    last_activity: Arc<Mutex<Instant>>,
+    // 到期時磁碟空間不足而未送出的譜面集，由介面移到下載管理的暫停列表
+    blocked: Arc<Mutex<Vec<i32>>>,
+}
+
+impl DownloadScheduler {
+    pub fn new() -> Self {
+        Self {
+            items: Arc::new(Mutex::new(load_schedule())),
+            last_activity: Arc::new(Mutex::new(Instant::now())),
+            blocked: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn items(&self) -> Vec<ScheduledDownload> {
+        self.items.lock().unwrap().clone()
+    }
+
+    pub fn contains(&self, beatmapset_id: i32) -> bool {
+        self.items
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|item| item.beatmapset_id == beatmapset_id)
+    }
+
+    pub fn add(&self, beatmapset_id: i32, label: String, trigger: ScheduleTrigger) {
+        {
+            let mut items = self.items.lock().unwrap();
+            items.retain(|item| item.beatmapset_id != beatmapset_id);
+            items.push(ScheduledDownload {
+                beatmapset_id,
+                label,
+                trigger,
+            });
+        }
+        info!("已排程稍後下載: {}", beatmapset_id);
+        self.save();
+    }
+
+    pub fn remove(&self, beatmapset_id: i32) {
+        self.items
+            .lock()
+            .unwrap()
+            .retain(|item| item.beatmapset_id != beatmapset_id);
+        self.save();
+    }
+
+    pub fn set_trigger(&self, beatmapset_id: i32, trigger: ScheduleTrigger) {
+        if let Some(item) = self
+            .items
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|item| item.beatmapset_id == beatmapset_id)
+        {
+            item.trigger = trigger;
+        }
+        self.save();
+    }
+
+    // 使用者有操作（例如搜尋）時呼叫，重設閒置計時
+    pub fn mark_activity(&self) {
+        *self.last_activity.lock().unwrap() = Instant::now();
+    }
+
+    pub fn idle_for(&self) -> Duration {
+        self.last_activity.lock().unwrap().elapsed()
+    }
+
+    pub fn take_blocked(&self) -> Vec<i32> {
+        std::mem::take(&mut *self.blocked.lock().unwrap())
+    }
+
+    fn take_due(&self) -> Vec<ScheduledDownload> {
+        let idle_for = self.idle_for();
+        let mut items = self.items.lock().unwrap();
+        let (due, pending): (Vec<_>, Vec<_>) = items
+            .drain(..)
+            .partition(|item| item.trigger.is_due(idle_for));
+        *items = pending;
+        due
+    }
+
+    fn save(&self) {
+        let items = self.items();
+        let result = save_config(SCHEDULE_FILE, &items);
+        if let Err(e) = result {
+            error!("保存下載排程失敗: {:?}", e);
+        }
+    }
+
+    // 啟動排程檢查任務
+    pub fn start(
+        &self,
+        download_directory: PathBuf,
+        download_queue_sender: mpsc::UnboundedSender<i32>,
+        download_statuses: Arc<Mutex<HashMap<i32, DownloadStatus>>>,
+        ctx: egui::Context,
+    ) {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                let due = scheduler.take_due();
+                if due.is_empty() {
+                    continue;
+                }
+                scheduler.save();
+
+                if let Some(available_mb) = low_disk_space(&download_directory) {
+                    warn!(
+                        "磁碟剩餘空間 {} MB 低於下限，暫停 {} 個排程下載",
+                        available_mb,
+                        due.len()
+                    );
+                    scheduler
+                        .blocked
+                        .lock()
+                        .unwrap()
+                        .extend(due.iter().map(|item| item.beatmapset_id));
+                    ctx.request_repaint();
+                    continue;
+                }
+
+                for item in due {
+                    info!(
+                        "排程到期，開始下載: {} ({})",
+                        item.label, item.beatmapset_id
+                    );
+                    download_statuses
+                        .lock()
+                        .unwrap()
+                        .insert(item.beatmapset_id, DownloadStatus::Waiting);
+                    if let Err(e) = download_queue_sender.send(item.beatmapset_id) {
+                        error!("無法將排程譜面加入下載隊列: {:?}", e);
+                        download_statuses
+                            .lock()
+                            .unwrap()
+                            .insert(item.beatmapset_id, DownloadStatus::NotStarted);
+                    }
+                }
+                ctx.request_repaint();
+            }
+        });
+    }
+}
+
+fn load_schedule() -> Vec<ScheduledDownload> {
+    load_config(SCHEDULE_FILE).unwrap_or_default()
+}
+
+// 將時間戳格式化為本地時間 "YYYY-MM-DD HH:MM"
+pub fn format_local_time(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format(TIME_FORMAT).to_string())
+        .unwrap_or_else(|| "時間無效".to_string())
+}
+
+// 解析使用者輸入的本地時間，回傳時間戳
+pub fn parse_local_time(input: &str) -> Option<i64> {
+    let naive = NaiveDateTime::parse_from_str(input.trim(), TIME_FORMAT).ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .single()
+        .map(|time| time.timestamp())
+}
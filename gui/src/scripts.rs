@@ -0,0 +1,547 @@
+// 標準庫導入
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// 第三方庫導入
+use log::{error, info};
+use reqwest::Client;
+use rhai::{Array, Dynamic, Engine, EvalAltResult};
+use serde_json::{json, Value};
+use tokio::runtime::Handle;
+
+// 本地模組導入
+use crate::lastfm::{get_top_tracks, LastFmConfig, TopPeriod};
+use crate::matcher::rank_beatmapsets;
+use crate::osu::{get_beatmapsets, get_osu_token, is_beatmap_downloaded};
+use crate::plugin_actions::{beatmapset_payload, track_payload};
+use crate::query_normalizer::search_beatmapsets_normalized;
+use crate::spotify::{
+    get_access_token, get_public_playlist_tracks, parse_spotify_url, SpotifyUrlKind,
+};
+use lib::get_app_data_path;
+
+const SCRIPTS_DIR: &str = "scripts";
+const SCRIPT_EXTENSION: &str = "rhai";
+// 輸出面板最多保留的行數
+const MAX_OUTPUT_LINES: usize = 500;
+const EXAMPLE_SCRIPT_NAME: &str = "lastfm_top_ranked.rhai";
+const EXAMPLE_SCRIPT: &str = r#"// 下載 Last.fm 最常聽的 100 首歌中已上架 (ranked) 的譜面
+let tracks = lastfm_top_tracks(100);
+let count = 0;
+for track in tracks {
+    let matches = match_track(track.artist, track.name);
+    if matches.is_empty() {
+        continue;
+    }
+    let best = matches[0];
+    if best.score < 0.8 || is_downloaded(best.id) {
+        continue;
+    }
+    if best.beatmaps.some(|beatmap| beatmap.status == "ranked") {
+        print(`下載 ${best.artist} - ${best.title}`);
+        download(best.id);
+        count += 1;
+    }
+}
+print(`共加入 ${count} 個下載`);
+"#;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn script_error(message: impl ToString) -> Box<EvalAltResult> {
+    message.to_string().into()
+}
+
+fn to_dynamic(value: Value) -> ScriptResult<Dynamic> {
+    rhai::serde::to_dynamic(value)
+}
+
+fn to_array(values: Vec<Value>) -> ScriptResult<Array> {
+    values.into_iter().map(to_dynamic).collect()
+}
+
+fn scripts_directory() -> PathBuf {
+    get_app_data_path().join(SCRIPTS_DIR)
+}
+
+// 腳本只能存取 scripts 資料夾內的檔案，拒絕絕對路徑與 ..
+fn sandboxed_path(name: &str) -> ScriptResult<PathBuf> {
+    let relative = Path::new(name);
+    let is_safe = !name.trim().is_empty()
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !is_safe {
+        return Err(script_error(format!(
+            "不允許存取 scripts 資料夾以外的路徑: {}",
+            name
+        )));
+    }
+    Ok(scripts_directory().join(relative))
+}
+
+// 依名稱排序的腳本檔名
+fn list_scripts() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(scripts_directory())
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == SCRIPT_EXTENSION)
+                })
+                .filter_map(|path| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+// 第一次開啟時建立範例腳本
+fn ensure_example_script() {
+    let directory = scripts_directory();
+    if directory.exists() {
+        return;
+    }
+    let result = fs::create_dir_all(&directory)
+        .and_then(|_| fs::write(directory.join(EXAMPLE_SCRIPT_NAME), EXAMPLE_SCRIPT));
+    if let Err(e) = result {
+        error!("建立範例腳本失敗: {:?}", e);
+    }
+}
+
+// 腳本執行期間共用的狀態
+#[derive(Clone)]
+struct ScriptContext {
+    handle: Handle,
+    client: Client,
+    osu_token: Rc<RefCell<Option<String>>>,
+    download_directory: PathBuf,
+    download_requests: Arc<Mutex<Vec<i32>>>,
+    debug_mode: bool,
+}
+
+impl ScriptContext {
+    // 第一次呼叫 osu! 相關函式時才取得 token
+    fn osu_token(&self) -> ScriptResult<String> {
+        if let Some(token) = self.osu_token.borrow().clone() {
+            return Ok(token);
+        }
+        let token = self
+            .handle
+            .block_on(get_osu_token(&self.client, self.debug_mode))
+            .map_err(script_error)?;
+        *self.osu_token.borrow_mut() = Some(token.clone());
+        Ok(token)
+    }
+
+    fn search(&self, query: &str) -> ScriptResult<Array> {
+        let token = self.osu_token()?;
+        let beatmapsets = self
+            .handle
+            .block_on(get_beatmapsets(
+                &self.client,
+                &token,
+                query,
+                self.debug_mode,
+            ))
+            .map_err(script_error)?;
+        to_array(beatmapsets.iter().map(beatmapset_payload).collect())
+    }
+
+    fn match_track(&self, artist: &str, title: &str) -> ScriptResult<Array> {
+        let token = self.osu_token()?;
+        let beatmapsets = self
+            .handle
+            .block_on(search_beatmapsets_normalized(
+                &self.client,
+                &token,
+                artist,
+                title,
+                self.debug_mode,
+            ))
+            .map_err(script_error)?;
+        let matches = rank_beatmapsets(artist, title, None, beatmapsets)
+            .into_iter()
+            .map(|scored| {
+                let mut payload = beatmapset_payload(&scored.beatmapset);
+                payload["score"] = json!(scored.score);
+                payload
+            })
+            .collect();
+        to_array(matches)
+    }
+
+    fn playlist_tracks(&self, url_or_id: &str) -> ScriptResult<Array> {
+        let playlist_id = match parse_spotify_url(url_or_id) {
+            Some(SpotifyUrlKind::Playlist(id)) => id,
+            Some(_) => return Err(script_error("不是 Spotify 播放清單網址")),
+            None => url_or_id.trim().to_string(),
+        };
+        let tracks = self
+            .handle
+            .block_on(async {
+                let token = get_access_token(&self.client, self.debug_mode).await?;
+                get_public_playlist_tracks(&self.client, &playlist_id, &token, self.debug_mode)
+                    .await
+            })
+            .map_err(script_error)?;
+        to_array(tracks.iter().map(track_payload).collect())
+    }
+
+    fn lastfm_top_tracks(&self, limit: i64) -> ScriptResult<Array> {
+        let config = LastFmConfig::load().ok_or_else(|| script_error("尚未設定 Last.fm"))?;
+        let limit = limit.clamp(1, 1000) as u32;
+        let tracks = self
+            .handle
+            .block_on(get_top_tracks(
+                &self.client,
+                &config,
+                TopPeriod::Overall,
+                limit,
+                self.debug_mode,
+            ))
+            .map_err(script_error)?;
+        to_array(
+            tracks
+                .into_iter()
+                .map(|track| {
+                    json!({
+                        "artist": track.artist,
+                        "name": track.name,
+                        "playcount": track.playcount,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn download(&self, beatmapset_id: i64) {
+        self.download_requests
+            .lock()
+            .unwrap()
+            .push(beatmapset_id as i32);
+    }
+
+    fn is_downloaded(&self, beatmapset_id: i64) -> bool {
+        is_beatmap_downloaded(&self.download_directory, beatmapset_id as i32)
+    }
+}
+
+fn build_engine(
+    context: ScriptContext,
+    output: Arc<Mutex<Vec<String>>>,
+    cancelled: Arc<AtomicBool>,
+    ctx: egui::Context,
+) -> Engine {
+    let mut engine = Engine::new();
+
+    let print_output = output.clone();
+    let print_ctx = ctx.clone();
+    engine.on_print(move |text| {
+        push_output(&print_output, text.to_string());
+        print_ctx.request_repaint();
+    });
+    engine.on_debug(move |text, _, position| {
+        push_output(&output, format!("[debug {}] {}", position, text));
+        ctx.request_repaint();
+    });
+    // 按下停止後在下一個運算中斷腳本
+    engine.on_progress(move |_| {
+        cancelled
+            .load(Ordering::SeqCst)
+            .then(|| Dynamic::from("已停止"))
+    });
+
+    let c = context.clone();
+    engine.register_fn("search", move |query: &str| c.search(query));
+    let c = context.clone();
+    engine.register_fn("match_track", move |artist: &str, title: &str| {
+        c.match_track(artist, title)
+    });
+    let c = context.clone();
+    engine.register_fn("playlist_tracks", move |url: &str| c.playlist_tracks(url));
+    let c = context.clone();
+    engine.register_fn("lastfm_top_tracks", move |limit: i64| {
+        c.lastfm_top_tracks(limit)
+    });
+    let c = context.clone();
+    engine.register_fn("download", move |beatmapset_id: i64| {
+        c.download(beatmapset_id)
+    });
+    let c = context;
+    engine.register_fn("is_downloaded", move |beatmapset_id: i64| {
+        c.is_downloaded(beatmapset_id)
+    });
+
+    engine.register_fn("read_file", |name: &str| -> ScriptResult<String> {
+        fs::read_to_string(sandboxed_path(name)?).map_err(script_error)
+    });
+    engine.register_fn("write_file", |name: &str, text: &str| -> ScriptResult<()> {
+        let path = sandboxed_path(name)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(script_error)?;
+        }
+        fs::write(path, text).map_err(script_error)
+    });
+    engine.register_fn("list_files", || -> Array {
+        fs::read_dir(scripts_directory())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| Dynamic::from(entry.file_name().to_string_lossy().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+
+    engine
+}
+
+fn push_output(output: &Mutex<Vec<String>>, line: String) {
+    let mut output = output.lock().unwrap();
+    output.push(line);
+    if output.len() > MAX_OUTPUT_LINES {
+        let overflow = output.len() - MAX_OUTPUT_LINES;
+        output.drain(..overflow);
+    }
+}
+
+// 腳本側邊選單頁面，可編輯、儲存並執行 scripts 資料夾中的 Rhai 腳本
+pub struct ScriptsPage {
+    pub show: bool,
+    scripts: Vec<String>,
+    selected: Option<String>,
+    source: String,
+    new_name: String,
+    output: Arc<Mutex<Vec<String>>>,
+    is_running: Arc<AtomicBool>,
+    cancelled: Arc<AtomicBool>,
+    download_requests: Arc<Mutex<Vec<i32>>>,
+}
+
+impl ScriptsPage {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            scripts: Vec::new(),
+            selected: None,
+            source: String::new(),
+            new_name: String::new(),
+            output: Arc::new(Mutex::new(Vec::new())),
+            is_running: Arc::new(AtomicBool::new(false)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            download_requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.show = true;
+        ensure_example_script();
+        self.scripts = list_scripts();
+        if self.selected.is_none() {
+            if let Some(name) = self.scripts.first().cloned() {
+                self.select(name);
+            }
+        }
+    }
+
+    fn select(&mut self, name: String) {
+        match fs::read_to_string(scripts_directory().join(&name)) {
+            Ok(source) => {
+                self.source = source;
+                self.selected = Some(name);
+            }
+            Err(e) => error!("讀取腳本 {} 失敗: {:?}", name, e),
+        }
+    }
+
+    fn save(&mut self) {
+        let name = match &self.selected {
+            Some(name) => name.clone(),
+            None => return,
+        };
+        match fs::write(scripts_directory().join(&name), &self.source) {
+            Ok(()) => info!("已儲存腳本: {}", name),
+            Err(e) => error!("儲存腳本 {} 失敗: {:?}", name, e),
+        }
+    }
+
+    fn create(&mut self) {
+        let name = self.new_name.trim().trim_end_matches(".rhai").to_string();
+        let file_name = format!("{}.{}", name, SCRIPT_EXTENSION);
+        let path = match sandboxed_path(&file_name) {
+            Ok(path) => path,
+            Err(e) => {
+                push_output(&self.output, e.to_string());
+                return;
+            }
+        };
+        if !path.exists() {
+            if let Err(e) = fs::write(&path, "") {
+                error!("建立腳本 {} 失敗: {:?}", file_name, e);
+                return;
+            }
+        }
+        self.new_name.clear();
+        self.scripts = list_scripts();
+        self.select(file_name);
+    }
+
+    fn run(&mut self, ctx: egui::Context, download_directory: PathBuf, debug_mode: bool) {
+        if self.is_running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.save();
+        self.cancelled.store(false, Ordering::SeqCst);
+        self.output.lock().unwrap().clear();
+
+        let source = self.source.clone();
+        let name = self.selected.clone().unwrap_or_default();
+        let output = self.output.clone();
+        let is_running = self.is_running.clone();
+        let cancelled = self.cancelled.clone();
+        let download_requests = self.download_requests.clone();
+        let handle = Handle::current();
+
+        // 腳本中的網路請求以 block_on 同步等待，在阻塞執行緒上執行避免卡住介面
+        tokio::task::spawn_blocking(move || {
+            let context = ScriptContext {
+                handle,
+                client: Client::new(),
+                osu_token: Rc::new(RefCell::new(None)),
+                download_directory,
+                download_requests,
+                debug_mode,
+            };
+            let engine = build_engine(context, output.clone(), cancelled, ctx.clone());
+            info!("執行腳本: {}", name);
+            let result = engine.run(&source);
+            let message = match result {
+                Ok(()) => "執行完成".to_string(),
+                Err(e) => match *e {
+                    EvalAltResult::ErrorTerminated(..) => "已停止".to_string(),
+                    e => format!("錯誤: {}", e),
+                },
+            };
+            push_output(&output, message);
+            is_running.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        download_directory: &Path,
+        debug_mode: bool,
+    ) -> Vec<i32> {
+        let is_running = self.is_running.load(Ordering::SeqCst);
+
+        ui.horizontal(|ui| {
+            if ui.button("< 返回").clicked() {
+                self.show = false;
+            }
+            ui.heading("腳本");
+            if is_running {
+                ui.spinner();
+            }
+        });
+        ui.add_space(10.0);
+
+        let mut selected = None;
+        ui.horizontal_wrapped(|ui| {
+            for name in &self.scripts {
+                let is_selected = self.selected.as_ref() == Some(name);
+                if ui.selectable_label(is_selected, name).clicked() && !is_selected {
+                    selected = Some(name.clone());
+                }
+            }
+        });
+        if let Some(name) = selected {
+            self.save();
+            self.select(name);
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_name)
+                    .hint_text("新腳本名稱")
+                    .desired_width(150.0),
+            );
+            if ui
+                .add_enabled(!self.new_name.trim().is_empty(), egui::Button::new("新增"))
+                .clicked()
+            {
+                self.create();
+            }
+            if ui.button("開啟資料夾").clicked() {
+                if let Err(e) = open::that(scripts_directory()) {
+                    error!("無法開啟腳本資料夾: {}", e);
+                }
+            }
+        });
+        ui.add_space(5.0);
+
+        if self.selected.is_some() {
+            egui::ScrollArea::vertical()
+                .id_source("script_editor")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.source)
+                            .code_editor()
+                            .desired_rows(15)
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!is_running, egui::Button::new("儲存"))
+                    .clicked()
+                {
+                    self.save();
+                }
+                if is_running {
+                    if ui.button("停止").clicked() {
+                        self.cancelled.store(true, Ordering::SeqCst);
+                    }
+                } else if ui.button("執行").clicked() {
+                    self.run(
+                        ui.ctx().clone(),
+                        download_directory.to_path_buf(),
+                        debug_mode,
+                    );
+                }
+            });
+        }
+        ui.label(
+            egui::RichText::new(
+                "可用函式: search、match_track、playlist_tracks、lastfm_top_tracks、download、is_downloaded、read_file、write_file、list_files；檔案只能存取腳本資料夾",
+            )
+            .small()
+            .weak(),
+        );
+
+        ui.separator();
+        let output = self.output.lock().unwrap().clone();
+        egui::ScrollArea::vertical()
+            .id_source("script_output")
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in output {
+                    ui.label(egui::RichText::new(line).monospace());
+                }
+            });
+
+        std::mem::take(&mut *self.download_requests.lock().unwrap())
+    }
+}
@@ -0,0 +1,174 @@
+// 標準庫導入
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// 第三方庫導入
+use log::warn;
+use reqwest::Client;
+use tokio::sync::Mutex as TokioMutex;
+use tokio::task::JoinHandle;
+
+// 本地模組導入
+use crate::spotify::{fetch_search_suggestions, get_access_token, SearchSuggestion};
+
+// 停止輸入多久後才查詢
+const DEBOUNCE: Duration = Duration::from_millis(300);
+const MIN_QUERY_CHARS: usize = 2;
+const SUGGESTION_LIMIT: u32 = 5;
+// Spotify 的 client credentials token 一小時後過期，提早更新
+const TOKEN_LIFETIME: Duration = Duration::from_secs(50 * 60);
+
+// 搜尋列下方的自動完成，與主要搜尋分開，只取得名稱
+pub struct SearchSuggestions {
+    // 最後一次輸入的時間，查詢送出後清除
+    edited_at: Option<Instant>,
+    query: String,
+    // 建議與其對應的查詢，查詢與搜尋列不同時不顯示
+    results: Arc<Mutex<(String, Vec<SearchSuggestion>)>>,
+    client: Client,
+    token: Arc<TokioMutex<Option<(String, Instant)>>>,
+    request: Option<JoinHandle<()>>,
+    dismissed: bool,
+    // 上一幀下拉選單的範圍，點擊選項時搜尋列會先失去焦點
+    popup_rect: Option<egui::Rect>,
+}
+
+impl SearchSuggestions {
+    pub fn new() -> Self {
+        Self {
+            edited_at: None,
+            query: String::new(),
+            results: Arc::new(Mutex::new((String::new(), Vec::new()))),
+            client: Client::new(),
+            token: Arc::new(TokioMutex::new(None)),
+            request: None,
+            dismissed: false,
+            popup_rect: None,
+        }
+    }
+
+    // 搜尋列內容變更時呼叫
+    pub fn on_edit(&mut self, query: &str) {
+        self.query = query.trim().to_string();
+        self.edited_at = Some(Instant::now());
+        self.dismissed = false;
+    }
+
+    // 送出搜尋或選擇建議後關閉下拉選單
+    pub fn dismiss(&mut self) {
+        self.dismissed = true;
+        self.edited_at = None;
+        if let Some(request) = self.request.take() {
+            request.abort();
+        }
+    }
+
+    // 輸入停止超過 DEBOUNCE 後才送出查詢，較舊的請求直接取消
+    pub fn poll(&mut self, ctx: &egui::Context, debug_mode: bool) {
+        let edited_at = match self.edited_at {
+            Some(edited_at) => edited_at,
+            None => return,
+        };
+        let elapsed = edited_at.elapsed();
+        if elapsed < DEBOUNCE {
+            ctx.request_repaint_after(DEBOUNCE - elapsed);
+            return;
+        }
+        self.edited_at = None;
+        if let Some(request) = self.request.take() {
+            request.abort();
+        }
+        // 網址不需要建議
+        if self.query.chars().count() < MIN_QUERY_CHARS || self.query.contains("://") {
+            *self.results.lock().unwrap() = (String::new(), Vec::new());
+            return;
+        }
+
+        let query = self.query.clone();
+        let client = self.client.clone();
+        let results = self.results.clone();
+        let token = self.token.clone();
+        let ctx = ctx.clone();
+        self.request = Some(tokio::spawn(async move {
+            let access_token = {
+                let mut token = token.lock().await;
+                match token.as_ref() {
+                    Some((access_token, fetched_at)) if fetched_at.elapsed() < TOKEN_LIFETIME => {
+                        access_token.clone()
+                    }
+                    _ => match get_access_token(&client, debug_mode).await {
+                        Ok(access_token) => {
+                            *token = Some((access_token.clone(), Instant::now()));
+                            access_token
+                        }
+                        Err(e) => {
+                            warn!("取得搜尋建議的 token 失敗: {:?}", e);
+                            return;
+                        }
+                    },
+                }
+            };
+            match fetch_search_suggestions(&client, &query, &access_token, SUGGESTION_LIMIT).await {
+                Ok(suggestions) => {
+                    *results.lock().unwrap() = (query, suggestions);
+                    ctx.request_repaint();
+                }
+                Err(e) => warn!("取得搜尋建議失敗: {:?}", e),
+            }
+        }));
+    }
+
+    // 在搜尋列下方顯示建議，選擇時回傳要填入的查詢
+    pub fn render(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        query: &str,
+    ) -> Option<String> {
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.dismissed = true;
+        }
+        let pointer_over_popup = match (self.popup_rect, ui.input(|i| i.pointer.hover_pos())) {
+            (Some(rect), Some(pos)) => rect.contains(pos),
+            _ => false,
+        };
+        if !response.has_focus() && !pointer_over_popup {
+            self.popup_rect = None;
+            return None;
+        }
+        let suggestions = {
+            let results = self.results.lock().unwrap();
+            if self.dismissed || results.0 != query.trim() || results.1.is_empty() {
+                self.popup_rect = None;
+                return None;
+            }
+            results.1.clone()
+        };
+
+        let mut selected = None;
+        let area = egui::Area::new(egui::Id::new("search_suggestions"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(response.rect.left_bottom())
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(response.rect.width());
+                    for suggestion in &suggestions {
+                        let text = match suggestion {
+                            SearchSuggestion::Track { name, artists } => {
+                                format!("🎵 {} - {}", name, artists)
+                            }
+                            SearchSuggestion::Artist(name) => format!("👤 {}", name),
+                        };
+                        if ui.add(egui::SelectableLabel::new(false, text)).clicked() {
+                            selected = Some(suggestion.query());
+                        }
+                    }
+                });
+            });
+        self.popup_rect = Some(area.response.rect);
+        if selected.is_some() {
+            self.dismiss();
+        }
+        selected
+    }
+}
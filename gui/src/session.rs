@@ -0,0 +1,165 @@
+// 標準庫導入
+use std::fs;
+use std::path::PathBuf;
+
+// 第三方庫導入
+use log::{error, info};
+use rspotify::AuthCodeSpotify;
+
+// 本地模組導入
+use crate::cache::{CacheKind, CacheManager};
+use lib::{get_app_data_path, read_login_info, save_login_info};
+
+const SPOTIFY_PLATFORM: &str = "spotify";
+// 頭像放在獨立的資料夾，快取管理才不會把應用程式資料夾中其他的 .jpg 當成頭像
+pub const AVATAR_DIR: &str = "avatars";
+// Spotify 沒有撤銷 token 的 API，只能由使用者在帳號頁面移除應用程式的存取權
+pub const SPOTIFY_APPS_URL: &str = "https://www.spotify.com/account/apps/";
+
+// 登出時如何處理本機資料
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LogoutMode {
+    // 只移除登入資訊，保留播放列表與喜歡的曲目快取
+    KeepLocalCache,
+    // 同時清除頭像與帳號相關的快取
+    ClearAll,
+}
+
+// 登出時需要清除的帳號資料，在重置介面狀態之前先取得
+pub struct SpotifySession {
+    pub client: Option<AuthCodeSpotify>,
+    pub user_name: Option<String>,
+}
+
+pub fn avatar_path(user_name: &str) -> PathBuf {
+    get_app_data_path()
+        .join(AVATAR_DIR)
+        .join(format!("{}.jpg", user_name))
+}
+
+// 舊版將頭像直接存放在應用程式資料夾，移到頭像資料夾
+pub fn migrate_legacy_avatar(user_name: &str) {
+    let legacy_path = get_app_data_path().join(format!("{}.jpg", user_name));
+    let path = avatar_path(user_name);
+    if !legacy_path.exists() || path.exists() {
+        return;
+    }
+    let result = fs::create_dir_all(get_app_data_path().join(AVATAR_DIR))
+        .and_then(|_| fs::rename(&legacy_path, &path));
+    match result {
+        Ok(()) => info!("已將頭像移到 {:?}", path),
+        Err(e) => error!("移動舊的頭像失敗: {}", e),
+    }
+}
+
+// 管理登出流程與登出對話框
+pub struct SessionManager {
+    show_logout_dialog: bool,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self {
+            show_logout_dialog: false,
+        }
+    }
+
+    pub fn request_logout(&mut self) {
+        self.show_logout_dialog = true;
+    }
+
+    // 使用者選擇登出方式時回傳
+    pub fn render_logout_dialog(&mut self, ctx: &egui::Context) -> Option<LogoutMode> {
+        if !self.show_logout_dialog {
+            return None;
+        }
+        let mut open = true;
+        let mut selected = None;
+        let mut cancelled = false;
+
+        egui::Window::new("登出 Spotify")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("登出會移除已保存的登入資訊，下次需要重新授權。");
+                ui.horizontal(|ui| {
+                    ui.label("若要同時撤銷本程式的存取權，請到");
+                    ui.hyperlink_to("Spotify 帳號頁面", SPOTIFY_APPS_URL);
+                    ui.label("移除。");
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("保留本地快取")
+                        .on_hover_text("保留播放列表與喜歡的曲目快取，重新登入後可以直接使用")
+                        .clicked()
+                    {
+                        selected = Some(LogoutMode::KeepLocalCache);
+                    }
+                    if ui
+                        .button(egui::RichText::new("完全清除").color(ui.visuals().error_fg_color))
+                        .on_hover_text("同時刪除頭像、播放列表與喜歡的曲目快取")
+                        .clicked()
+                    {
+                        selected = Some(LogoutMode::ClearAll);
+                    }
+                    if ui.button("取消").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+
+        if selected.is_some() || cancelled || !open {
+            self.show_logout_dialog = false;
+        }
+        selected
+    }
+
+    // 讓 token 失效並移除本機的登入資訊
+    pub fn end_spotify_session(
+        &self,
+        session: SpotifySession,
+        mode: LogoutMode,
+        cache_manager: &CacheManager,
+    ) {
+        // 清空共用的 token，仍持有客戶端複本的背景工作也無法再呼叫 API
+        if let Some(client) = session.client {
+            tokio::spawn(async move {
+                if let Ok(mut token) = client.token.lock().await {
+                    *token = None;
+                }
+            });
+        }
+
+        // 只移除 Spotify 的登入資訊，保留其他平台
+        match read_login_info() {
+            Ok(mut login_infos) => {
+                if login_infos.remove(SPOTIFY_PLATFORM).is_some() {
+                    if let Err(e) = save_login_info(&login_infos) {
+                        error!("更新 login_info.json 失敗: {}", e);
+                    }
+                }
+            }
+            Err(e) => error!("讀取 login_info.json 失敗: {}", e),
+        }
+
+        if mode == LogoutMode::ClearAll {
+            if let Some(user_name) = &session.user_name {
+                let path = avatar_path(user_name);
+                if path.exists() {
+                    if let Err(e) = fs::remove_file(&path) {
+                        error!("刪除使用者頭像失敗: {}", e);
+                    }
+                }
+            }
+            for kind in [CacheKind::PlaylistTracks, CacheKind::LikedTracks] {
+                if let Err(e) = cache_manager.clear(kind) {
+                    error!("清除{}失敗: {:?}", kind.label(), e);
+                }
+            }
+        }
+        info!("已登出 Spotify（{:?}）", mode);
+    }
+}
@@ -0,0 +1,174 @@
+// 標準庫導入
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+// 第三方庫導入
+use log::{error, info, warn};
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+// 本地模組導入
+use lib::{get_app_data_path, write_atomic};
+
+// 放在工作目錄的 API 金鑰設定，其餘設定都在應用數據目錄
+const CREDENTIALS_CONFIG: &str = "config.json";
+// 含有登入 token 或 API 金鑰的檔案，預設不匯出也不匯入
+const CREDENTIAL_FILES: [&str; 4] = [
+    CREDENTIALS_CONFIG,
+    "login_info.json",
+    "lastfm_config.json",
+    "api_server.json",
+];
+// 與這台電腦的下載資料夾綁定，換電腦後會重新建立
+const MACHINE_FILES: [&str; 1] = ["downloaded_map_index.json"];
+// 匯入的檔案先放在這裡，下次啟動、尚未讀取任何設定前才覆蓋
+const PENDING_DIR: &str = "pending_settings";
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("IO 錯誤: {0}")]
+    IoError(#[from] io::Error),
+    #[error("壓縮檔錯誤: {0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("壓縮檔中沒有可匯入的設定")]
+    Empty,
+}
+
+fn is_credential_file(file_name: &str) -> bool {
+    CREDENTIAL_FILES.contains(&file_name)
+}
+
+// 設定與資料檔；播放清單快取可以重新下載，不放進壓縮檔
+fn is_bundled_file(file_name: &str, include_credentials: bool) -> bool {
+    let is_data = file_name.ends_with(".json") || file_name.ends_with(".db");
+    is_data
+        && !file_name.ends_with("_cache.json")
+        && !MACHINE_FILES.contains(&file_name)
+        && (include_credentials || !is_credential_file(file_name))
+}
+
+fn pending_dir() -> PathBuf {
+    get_app_data_path().join(PENDING_DIR)
+}
+
+fn file_path(file_name: &str) -> PathBuf {
+    if file_name == CREDENTIALS_CONFIG {
+        PathBuf::from(CREDENTIALS_CONFIG)
+    } else {
+        get_app_data_path().join(file_name)
+    }
+}
+
+// 將設定、釘選、配對記憶、下載紀錄等打包成 zip，回傳打包的檔案數
+pub fn export_bundle(path: &Path, include_credentials: bool) -> Result<usize, BundleError> {
+    let mut file_names: Vec<String> = fs::read_dir(get_app_data_path())?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map_or(false, |kind| kind.is_file()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|file_name| is_bundled_file(file_name, include_credentials))
+        .collect();
+    if include_credentials && Path::new(CREDENTIALS_CONFIG).is_file() {
+        file_names.push(CREDENTIALS_CONFIG.to_string());
+    }
+    file_names.sort();
+
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for file_name in &file_names {
+        let content = fs::read(file_path(file_name))?;
+        zip.start_file(file_name.as_str(), options)?;
+        zip.write_all(&content)?;
+    }
+    zip.finish()?;
+    info!("已匯出 {} 個設定檔至 {:?}", file_names.len(), path);
+    Ok(file_names.len())
+}
+
+// 壓縮檔中會被匯入的檔名，只接受沒有子資料夾的檔案
+fn bundle_entries(
+    archive: &mut ZipArchive<File>,
+    include_credentials: bool,
+) -> Result<Vec<(usize, String)>, BundleError> {
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        let entry = archive.by_index(index)?;
+        let file_name = match entry.enclosed_name() {
+            Some(name) if name.components().count() == 1 => name.to_string_lossy().to_string(),
+            _ => {
+                warn!("略過壓縮檔中的項目: {}", entry.name());
+                continue;
+            }
+        };
+        if entry.is_file() && is_bundled_file(&file_name, include_credentials) {
+            entries.push((index, file_name));
+        }
+    }
+    Ok(entries)
+}
+
+// 匯入前列出會覆蓋的檔案，讓使用者確認
+pub fn bundle_contents(path: &Path, include_credentials: bool) -> Result<Vec<String>, BundleError> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let entries = bundle_entries(&mut archive, include_credentials)?;
+    if entries.is_empty() {
+        return Err(BundleError::Empty);
+    }
+    Ok(entries
+        .into_iter()
+        .map(|(_, file_name)| file_name)
+        .collect())
+}
+
+// 將壓縮檔中的檔案放到暫存資料夾，下次啟動時才覆蓋目前的設定。
+// 程式執行中直接覆蓋的話，記憶體中的設定會在結束或下次變更時寫回，匯入的內容就會遺失
+pub fn import_bundle(path: &Path, include_credentials: bool) -> Result<usize, BundleError> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let entries = bundle_entries(&mut archive, include_credentials)?;
+    if entries.is_empty() {
+        return Err(BundleError::Empty);
+    }
+    let pending_dir = pending_dir();
+    if pending_dir.exists() {
+        fs::remove_dir_all(&pending_dir)?;
+    }
+    fs::create_dir_all(&pending_dir)?;
+    for (index, file_name) in &entries {
+        let mut content = Vec::new();
+        archive.by_index(*index)?.read_to_end(&mut content)?;
+        write_atomic(&pending_dir.join(file_name), content)?;
+    }
+    info!(
+        "已從 {:?} 暫存 {} 個設定檔，下次啟動時套用",
+        path,
+        entries.len()
+    );
+    Ok(entries.len())
+}
+
+// 啟動時在讀取任何設定之前呼叫，套用上次匯入的設定
+pub fn apply_pending_import() {
+    let pending_dir = pending_dir();
+    let entries = match fs::read_dir(&pending_dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut applied = 0;
+    for entry in entries.flatten() {
+        let file_name = match entry.file_name().into_string() {
+            Ok(file_name) if is_bundled_file(&file_name, true) => file_name,
+            _ => continue,
+        };
+        let result = fs::read(entry.path())
+            .and_then(|content| write_atomic(&file_path(&file_name), content));
+        match result {
+            Ok(()) => applied += 1,
+            Err(e) => error!("套用匯入的設定 {} 失敗: {:?}", file_name, e),
+        }
+    }
+    if let Err(e) = fs::remove_dir_all(&pending_dir) {
+        warn!("無法刪除匯入暫存資料夾: {:?}", e);
+    }
+    info!("已套用 {} 個匯入的設定檔", applied);
+}
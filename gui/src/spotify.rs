@@ -1,5 +1,5 @@
 // 標準庫導入
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::fs::{self, OpenOptions};
 use std::future::Future;
@@ -17,12 +17,13 @@ use std::time::{Duration, Instant, SystemTime};
 use anyhow::{anyhow, Error, Result};
 use chrono::Local;
 use chrono::Utc;
+use futures::stream::{self, StreamExt, TryStreamExt};
 use lazy_static::lazy_static;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use regex::Regex;
 use reqwest::Client;
 use rspotify::{
-    clients::{OAuthClient,BaseClient}, model::{PlayableItem,TrackId,FullTrack,PlaylistId}, scopes, AuthCodeSpotify, ClientError, Credentials,
+    clients::{OAuthClient,BaseClient}, model::{AdditionalType,AlbumId,AlbumType,ArtistId,FullArtist,Id,Market,PlayableId,PlayableItem,SimplifiedAlbum,SimplifiedTrack,TrackId,FullTrack,PlaylistId}, scopes, AuthCodeSpotify, ClientError, Credentials,
     OAuth, Token,model::SimplifiedPlaylist,
 };
 use serde::{Deserialize, Serialize};
@@ -45,12 +46,19 @@ use winapi::{
 
 
 // 本地模組導入
+use crate::diagnostics::{send_traced, traced};
 use crate::{read_config, AuthManager, AuthPlatform};
 use lib::{LoginInfo, save_login_info, open_url_default_browser};
 
 // 常量定義
 const SPOTIFY_API_BASE_URL: &str = "https://api.spotify.com/v1";
 const SPOTIFY_AUTH_URL: &str = "https://accounts.spotify.com/api/token";
+// 應用程式 token 查詢熱門曲目與 Podcast 時使用的市場
+const DEFAULT_MARKET: &str = "TW";
+// 同時請求的分頁數，避免觸發 Spotify 的速率限制
+const PAGE_FETCH_CONCURRENCY: usize = 4;
+const PLAYLIST_PAGE_SIZE: u32 = 100;
+const LIKED_TRACKS_PAGE_SIZE: u32 = 50;
 
 // 靜態變量
 lazy_static! {
@@ -122,6 +130,8 @@ pub struct Album {
 #[derive(Deserialize, Clone)]
 pub struct Albums {
     pub items: Vec<Album>,
+    #[serde(default)]
+    pub total: u32,
 }
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Image {
@@ -164,19 +174,291 @@ pub struct Track {
     pub external_urls: HashMap<String, String>,
     pub album: Album,
     pub is_liked: Option<bool>,
+    // 30 秒試聽片段，部分曲目沒有提供
+    #[serde(default)]
+    pub preview_url: Option<String>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub explicit: bool,
+    // 查詢時未指定 market 才會回傳
+    #[serde(default)]
+    pub available_markets: Option<Vec<String>>,
+    // 查詢時指定 market 才會回傳
+    #[serde(default)]
+    pub is_playable: Option<bool>,
     #[serde(skip)]
     pub index: usize,
-    
+    // Podcast 單集以曲目形式顯示，不進行 osu! 配對
+    #[serde(skip)]
+    pub is_episode: bool,
+}
+
+impl Track {
+    // 無法判斷時回傳 None
+    pub fn playable_in(&self, market: &str) -> Option<bool> {
+        self.is_playable.or_else(|| {
+            self.available_markets
+                .as_ref()
+                .map(|markets| markets.iter().any(|m| m == market))
+        })
+    }
+
+    pub fn is_unplayable(&self) -> bool {
+        self.playable_in(DEFAULT_MARKET) == Some(false)
+    }
 }
+
 pub struct TrackWithCover {
     pub name: String,
     pub artists: Vec<Artist>,
     pub external_urls: HashMap<String, String>,
     pub album_name: String,
     pub cover_url: Option<String>,
+    pub preview_url: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub explicit: bool,
+    pub available_markets: Option<Vec<String>>,
+    pub is_playable: Option<bool>,
+    pub release_date: String,
+    pub is_episode: bool,
     pub index: usize,
 }
 
+impl TrackWithCover {
+    pub fn from_track(track: &Track, index: usize) -> Self {
+        Self {
+            name: track.name.clone(),
+            artists: track.artists.clone(),
+            external_urls: track.external_urls.clone(),
+            album_name: track.album.name.clone(),
+            cover_url: track.album.images.first().map(|img| img.url.clone()),
+            preview_url: track.preview_url.clone(),
+            duration_ms: track.duration_ms,
+            explicit: track.explicit,
+            available_markets: track.available_markets.clone(),
+            is_playable: track.is_playable,
+            release_date: track.album.release_date.clone(),
+            is_episode: false,
+            index,
+        }
+    }
+
+    // 以節目名稱代替專輯、發行者代替歌手顯示
+    pub fn from_episode(episode: Episode, index: usize) -> Self {
+        let show = episode.show.unwrap_or_default();
+        Self {
+            name: episode.name,
+            artists: vec![Artist {
+                name: show.publisher,
+            }],
+            external_urls: episode.external_urls,
+            album_name: show.name,
+            cover_url: episode.images.first().map(|img| img.url.clone()),
+            preview_url: None,
+            duration_ms: episode.duration_ms,
+            explicit: episode.explicit,
+            available_markets: None,
+            is_playable: episode.is_playable,
+            release_date: episode.release_date,
+            is_episode: true,
+            index,
+        }
+    }
+
+    // 轉為結果列表使用的 Track，喜歡狀態之後另外查詢
+    pub fn to_track(&self) -> Track {
+        Track {
+            name: self.name.clone(),
+            artists: self.artists.clone(),
+            album: Album {
+                name: self.album_name.clone(),
+                album_type: String::new(),
+                artists: Vec::new(),
+                external_urls: HashMap::new(),
+                images: self
+                    .cover_url
+                    .as_ref()
+                    .map(|url| {
+                        vec![Image {
+                            url: url.clone(),
+                            width: 0,
+                            height: 0,
+                        }]
+                    })
+                    .unwrap_or_default(),
+                id: String::new(),
+                release_date: self.release_date.clone(),
+                total_tracks: 0,
+            },
+            external_urls: self.external_urls.clone(),
+            index: self.index,
+            is_liked: None,
+            preview_url: self.preview_url.clone(),
+            duration_ms: self.duration_ms,
+            explicit: self.explicit,
+            available_markets: self.available_markets.clone(),
+            is_playable: self.is_playable,
+            is_episode: self.is_episode,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct AlbumTrack {
+    pub name: String,
+    pub artists: Vec<Artist>,
+}
+
+#[derive(Deserialize)]
+struct AlbumTracksPage {
+    items: Vec<AlbumTrack>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PlaylistTracksPage {
+    items: Vec<PlaylistTrackItem>,
+    next: Option<String>,
+    #[serde(default)]
+    total: u32,
+}
+
+// 播放清單項目可能是曲目、Podcast 單集或本機檔案，先保留原始 JSON
+#[derive(Deserialize)]
+struct PlaylistTrackItem {
+    track: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum UnavailableReason {
+    LocalFile,
+    Episode,
+    // 已從 Spotify 下架或無法解析
+    Removed,
+}
+
+impl UnavailableReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UnavailableReason::LocalFile => "本機檔案",
+            UnavailableReason::Episode => "Podcast 單集",
+            UnavailableReason::Removed => "已下架",
+        }
+    }
+}
+
+// 使用者播放清單的項目，無法取得的項目保留位置以便顯示，不參與配對
+// untagged 讓只有曲目的舊快取仍可讀取
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum PlaylistEntry {
+    Track(FullTrack),
+    Unavailable {
+        name: Option<String>,
+        reason: UnavailableReason,
+    },
+}
+
+impl PlaylistEntry {
+    fn from_value(value: Option<Value>) -> Self {
+        let value = match value {
+            Some(value) => value,
+            None => {
+                return PlaylistEntry::Unavailable {
+                    name: None,
+                    reason: UnavailableReason::Removed,
+                }
+            }
+        };
+        let name = value["name"]
+            .as_str()
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+        let reason = if value["is_local"] == true {
+            UnavailableReason::LocalFile
+        } else if value["type"] == "episode" {
+            UnavailableReason::Episode
+        } else {
+            match serde_json::from_value::<FullTrack>(value) {
+                Ok(track) if track.id.is_some() => return PlaylistEntry::Track(track),
+                Ok(_) => UnavailableReason::Removed,
+                Err(e) => {
+                    warn!("無法解析播放清單曲目: {:?}", e);
+                    UnavailableReason::Removed
+                }
+            }
+        };
+        PlaylistEntry::Unavailable { name, reason }
+    }
+
+    pub fn track(&self) -> Option<&FullTrack> {
+        match self {
+            PlaylistEntry::Track(track) => Some(track),
+            PlaylistEntry::Unavailable { .. } => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            PlaylistEntry::Track(track) => &track.name,
+            PlaylistEntry::Unavailable { name, .. } => name.as_deref().unwrap_or(""),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ArtistTopTracks {
+    tracks: Vec<Track>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct Show {
+    pub name: String,
+    pub publisher: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Episode {
+    pub name: String,
+    pub external_urls: HashMap<String, String>,
+    pub images: Vec<Image>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub release_date: String,
+    #[serde(default)]
+    pub explicit: bool,
+    #[serde(default)]
+    pub is_playable: Option<bool>,
+    // 節目頁面與搜尋結果中的單集不含節目資訊
+    #[serde(default)]
+    pub show: Option<Show>,
+}
+
+// 地區不提供的單集會以 null 回傳
+#[derive(Deserialize)]
+struct EpisodesPage {
+    items: Vec<Option<Episode>>,
+}
+
+#[derive(Deserialize)]
+struct ShowWithEpisodes {
+    name: String,
+    publisher: String,
+    episodes: EpisodesPage,
+}
+
+#[derive(Deserialize)]
+struct EpisodeSearchResult {
+    episodes: EpisodesPage,
+}
+
+#[derive(Deserialize)]
+struct SeveralEpisodes {
+    episodes: Vec<Option<Episode>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct TrackInfo {
     pub name: String,
@@ -197,20 +479,95 @@ pub enum SpotifyUrlStatus {
     NotSpotify,
 }
 
+// Spotify 網址指向的內容類型與 ID
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpotifyUrlKind {
+    Track(String),
+    Album(String),
+    Playlist(String),
+    Artist(String),
+    Episode(String),
+    Show(String),
+}
+
 #[derive(Debug, Clone)]
 pub struct CurrentlyPlaying {
     pub track_info: TrackInfo,
     pub spotify_url: Option<String>,
 }
 
+// 分享連結的短網址網域，需要先跟隨轉址才能取得 open.spotify.com 網址
+const SPOTIFY_SHORT_LINK_HOSTS: [&str; 2] = ["spotify.link", "spotify.app.link"];
+
+lazy_static! {
+    // 地區版網址，例如 https://open.spotify.com/intl-ja/track/...
+    static ref SPOTIFY_INTL_PATH: Regex =
+        Regex::new(r"^(https?://open\.spotify\.com/)intl-[a-zA-Z-]+/").unwrap();
+    // 短網址轉址頁面中的 open.spotify.com 網址
+    static ref SPOTIFY_OPEN_URL: Regex =
+        Regex::new(r#"https://open\.spotify\.com/[a-z]+/[a-zA-Z0-9]+[^"'\s<>]*"#).unwrap();
+}
+
+// 去掉網址前後空白與地區路徑，讓地區版網址與一般網址一致
+pub fn normalize_spotify_url(url: &str) -> String {
+    SPOTIFY_INTL_PATH.replace(url.trim(), "$1").into_owned()
+}
+
+pub fn is_spotify_short_link(url: &str) -> bool {
+    Url::parse(url.trim())
+        .ok()
+        .and_then(|parsed| {
+            parsed
+                .host_str()
+                .map(|host| SPOTIFY_SHORT_LINK_HOSTS.contains(&host))
+        })
+        .unwrap_or(false)
+}
+
+// 跟隨短網址的轉址取得實際的 open.spotify.com 網址
+// 先用 HEAD 請求，轉址停在 App 下載頁時再從頁面內容找出網址
+pub async fn resolve_spotify_short_link(
+    client: &Client,
+    url: &str,
+) -> Result<String, SpotifyError> {
+    let response = send_traced(
+        client.head(url.trim()),
+        "Spotify",
+        "resolve_spotify_short_link",
+    )
+    .await?;
+    let final_url = response.url().clone();
+    if final_url.host_str() == Some("open.spotify.com") {
+        info!("Spotify 短網址 {} 轉址至 {}", url, final_url);
+        return Ok(normalize_spotify_url(final_url.as_str()));
+    }
+
+    let body = send_traced(
+        client.get(url.trim()),
+        "Spotify",
+        "resolve_spotify_short_link",
+    )
+    .await?
+    .text()
+    .await?;
+    SPOTIFY_OPEN_URL
+        .find(&body)
+        .map(|found| {
+            info!("Spotify 短網址 {} 解析為 {}", url, found.as_str());
+            normalize_spotify_url(&found.as_str().replace("&amp;", "&"))
+        })
+        .ok_or_else(|| SpotifyError::ApiError(format!("無法解析 Spotify 短網址: {}", url)))
+}
+
 pub fn is_valid_spotify_url(url: &str) -> Result<SpotifyUrlStatus, SpotifyError> {
     lazy_static! {
         static ref SPOTIFY_URL_REGEX: Regex = Regex::new(
-            r"^https?://open\.spotify\.com/(track|album|playlist)/[a-zA-Z0-9]+(?:\?.*)?$"
+            r"^https?://open\.spotify\.com/(track|album|playlist|artist|episode|show)/[a-zA-Z0-9]+(?:\?.*)?$"
         )
         .unwrap();
     }
 
+    let url = &normalize_spotify_url(url);
     if let Ok(parsed_url) = url::Url::parse(url) {
         match parsed_url.domain() {
             Some("open.spotify.com") => {
@@ -221,7 +578,16 @@ pub fn is_valid_spotify_url(url: &str) -> Result<SpotifyUrlStatus, SpotifyError>
                 }
             }
             Some(_) => {
-                if url.contains("/track/") || url.contains("/album/") || url.contains("/playlist/")
+                if [
+                    "/track/",
+                    "/album/",
+                    "/playlist/",
+                    "/artist/",
+                    "/episode/",
+                    "/show/",
+                ]
+                .iter()
+                .any(|path| url.contains(path))
                 {
                     Ok(SpotifyUrlStatus::Invalid)
                 } else {
@@ -234,6 +600,28 @@ pub fn is_valid_spotify_url(url: &str) -> Result<SpotifyUrlStatus, SpotifyError>
         Ok(SpotifyUrlStatus::NotSpotify)
     }
 }
+
+// 解析 Spotify 網址的類型與 ID，不是有效的 Spotify 網址時回傳 None
+pub fn parse_spotify_url(url: &str) -> Option<SpotifyUrlKind> {
+    lazy_static! {
+        static ref SPOTIFY_URL_KIND_REGEX: Regex = Regex::new(
+            r"^https?://open\.spotify\.com/(track|album|playlist|artist|episode|show)/([a-zA-Z0-9]+)(?:\?.*)?$"
+        )
+        .unwrap();
+    }
+
+    let captures = SPOTIFY_URL_KIND_REGEX.captures(&normalize_spotify_url(url))?;
+    let id = captures[2].to_string();
+    match &captures[1] {
+        "track" => Some(SpotifyUrlKind::Track(id)),
+        "album" => Some(SpotifyUrlKind::Album(id)),
+        "playlist" => Some(SpotifyUrlKind::Playlist(id)),
+        "artist" => Some(SpotifyUrlKind::Artist(id)),
+        "episode" => Some(SpotifyUrlKind::Episode(id)),
+        "show" => Some(SpotifyUrlKind::Show(id)),
+        _ => None,
+    }
+}
 /*
 pub async fn search_album_by_url(
     client: &reqwest::Client,
@@ -261,14 +649,17 @@ pub async fn search_album_by_url(
     match album_id_result {
         Ok(album_id) => {
             let api_url = format!("https://api.spotify.com/v1/albums/{}", album_id);
-            let response = client
-                .get(&api_url)
-                .header(AUTHORIZATION, format!("Bearer {}", access_token))
-                .header(CONTENT_TYPE, "application/json")
-                .send()
-                .await?
-                .json::<Album>()
-                .await?;
+            let response = send_traced(
+                client
+                    .get(&api_url)
+                    .header(AUTHORIZATION, format!("Bearer {}", access_token))
+                    .header(CONTENT_TYPE, "application/json"),
+                "Spotify",
+                "search_album_by_url",
+            )
+            .await?
+            .json::<Album>()
+            .await?;
 
             Ok(response)
         }
@@ -280,32 +671,6 @@ pub async fn search_album_by_url(
     }
 }
 
-pub async fn search_album_by_name(
-    client: &reqwest::Client,
-    album_name: &str,
-    access_token: &str,
-    page: u32,
-    limit: u32,
-) -> Result<(Vec<Album>, u32), Box<dyn std::error::Error>> {
-    let offset = (page - 1) * limit;
-    let search_url = format!(
-        "https://api.spotify.com/v1/search?q={}&type=album&limit={}&offset={}",
-        album_name, limit, offset
-    );
-    let response = client
-        .get(&search_url)
-        .header("Authorization", format!("Bearer {}", access_token))
-        .send()
-        .await?;
-
-    let search_result: SearchResult = response.json().await?;
-    let total_pages =
-        (search_result.albums.clone().unwrap().items.len() as u32 + limit - 1) / limit;
-    let albums = search_result.albums.unwrap().items;
-    Ok((albums, total_pages))
-}
-
-
 pub fn print_track_infos(track_infos: Vec<Track>) {
     println!(" ");
     println!("------------------------");
@@ -332,12 +697,15 @@ pub async fn get_track_info(
     access_token: &str,
 ) -> Result<Track> {
     let url = format!("{}/tracks/{}", SPOTIFY_API_BASE_URL, track_id);
-    let response = client
-        .get(&url)
-        .header("Authorization", format!("Bearer {}", access_token))
-        .send()
-        .await
-        .map_err(Error::from)?;
+    let response = send_traced(
+        client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", access_token)),
+        "Spotify",
+        "get_track_info",
+    )
+    .await
+    .map_err(Error::from)?;
 
     let body = response.text().await.map_err(Error::from)?;
     let track: Track = serde_json::from_str(&body)?;
@@ -345,6 +713,332 @@ pub async fn get_track_info(
     Ok(track)
 }
 
+pub async fn search_album_by_name(
+    client: &Client,
+    query: &str,
+    token: &str,
+    limit: u32,
+    offset: u32,
+    debug_mode: bool,
+) -> Result<(Vec<Album>, u32), SpotifyError> {
+    let url = format!(
+        "{}/search?q={}&type=album&limit={}&offset={}",
+        SPOTIFY_API_BASE_URL,
+        urlencoding::encode(query),
+        limit,
+        offset
+    );
+
+    if debug_mode {
+        info!("Spotify 專輯搜尋 URL: {}", url);
+    }
+
+    let response = send_traced(
+        client.get(&url).bearer_auth(token),
+        "Spotify",
+        "search_album_by_name",
+    )
+    .await?;
+    if !response.status().is_success() {
+        return Err(SpotifyError::ApiError(format!(
+            "專輯搜尋失敗，狀態碼: {}",
+            response.status()
+        )));
+    }
+
+    let search_result: SearchResult = response.json().await?;
+    let (albums, total) = search_result
+        .albums
+        .map(|albums| (albums.items, albums.total))
+        .unwrap_or_default();
+    let total_pages = (total + limit - 1) / limit;
+
+    if debug_mode {
+        info!("找到 {} 張專輯", albums.len());
+    }
+
+    Ok((albums, total_pages))
+}
+
+// 取得專輯內所有曲目，自動處理分頁
+pub async fn get_album_tracks(
+    client: &Client,
+    album_id: &str,
+    token: &str,
+    debug_mode: bool,
+) -> Result<Vec<AlbumTrack>, SpotifyError> {
+    let mut tracks = Vec::new();
+    let mut next_url = Some(format!(
+        "{}/albums/{}/tracks?limit=50",
+        SPOTIFY_API_BASE_URL, album_id
+    ));
+
+    while let Some(url) = next_url {
+        if debug_mode {
+            debug!("獲取專輯曲目: {}", url);
+        }
+        let response = send_traced(
+            client.get(&url).bearer_auth(token),
+            "Spotify",
+            "get_album_tracks",
+        )
+        .await?;
+        if !response.status().is_success() {
+            return Err(SpotifyError::ApiError(format!(
+                "獲取專輯曲目失敗，狀態碼: {}",
+                response.status()
+            )));
+        }
+        let page: AlbumTracksPage = response.json().await?;
+        tracks.extend(page.items);
+        next_url = page.next;
+    }
+
+    Ok(tracks)
+}
+
+// 以 ID 取得單張專輯
+pub async fn get_album(
+    client: &Client,
+    album_id: &str,
+    token: &str,
+    debug_mode: bool,
+) -> Result<Album, SpotifyError> {
+    let url = format!("{}/albums/{}", SPOTIFY_API_BASE_URL, album_id);
+    if debug_mode {
+        debug!("獲取專輯: {}", url);
+    }
+    let response = send_traced(client.get(&url).bearer_auth(token), "Spotify", "get_album").await?;
+    if !response.status().is_success() {
+        return Err(SpotifyError::ApiError(format!(
+            "獲取專輯失敗，狀態碼: {}",
+            response.status()
+        )));
+    }
+    Ok(response.json().await?)
+}
+
+// 以應用程式 token 取得公開播放清單的曲目，不需要使用者登入
+// 本機檔案與 Podcast 單集會被略過
+pub async fn get_public_playlist_tracks(
+    client: &Client,
+    playlist_id: &str,
+    token: &str,
+    debug_mode: bool,
+) -> Result<Vec<Track>, SpotifyError> {
+    let mut tracks = Vec::new();
+    let mut next_url = Some(format!(
+        "{}/playlists/{}/tracks?limit=100",
+        SPOTIFY_API_BASE_URL, playlist_id
+    ));
+
+    while let Some(url) = next_url {
+        if debug_mode {
+            debug!("獲取播放清單曲目: {}", url);
+        }
+        let response = send_traced(
+            client.get(&url).bearer_auth(token),
+            "Spotify",
+            "get_public_playlist_tracks",
+        )
+        .await?;
+        if !response.status().is_success() {
+            return Err(SpotifyError::ApiError(format!(
+                "獲取播放清單曲目失敗，狀態碼: {}",
+                response.status()
+            )));
+        }
+        let page: PlaylistTracksPage = response.json().await?;
+        tracks.extend(
+            page.items
+                .into_iter()
+                .filter_map(|item| item.track)
+                .filter(|track| track["type"] == "track" && track["is_local"] != true)
+                .filter_map(|track| serde_json::from_value::<Track>(track).ok()),
+        );
+        next_url = page.next;
+    }
+
+    Ok(tracks)
+}
+
+pub async fn get_artist(
+    client: &Client,
+    artist_id: &str,
+    token: &str,
+) -> Result<Artist, SpotifyError> {
+    let url = format!("{}/artists/{}", SPOTIFY_API_BASE_URL, artist_id);
+    let response =
+        send_traced(client.get(&url).bearer_auth(token), "Spotify", "get_artist").await?;
+    if !response.status().is_success() {
+        return Err(SpotifyError::ApiError(format!(
+            "獲取歌手資訊失敗，狀態碼: {}",
+            response.status()
+        )));
+    }
+    Ok(response.json().await?)
+}
+
+// 歌手的熱門曲目，應用程式 token 無法使用 from_token，需指定市場
+pub async fn get_artist_top_tracks(
+    client: &Client,
+    artist_id: &str,
+    token: &str,
+    debug_mode: bool,
+) -> Result<Vec<Track>, SpotifyError> {
+    let url = format!(
+        "{}/artists/{}/top-tracks?market={}",
+        SPOTIFY_API_BASE_URL, artist_id, DEFAULT_MARKET
+    );
+    if debug_mode {
+        debug!("獲取歌手熱門曲目: {}", url);
+    }
+    let response = send_traced(
+        client.get(&url).bearer_auth(token),
+        "Spotify",
+        "get_artist_top_tracks",
+    )
+    .await?;
+    if !response.status().is_success() {
+        return Err(SpotifyError::ApiError(format!(
+            "獲取歌手熱門曲目失敗，狀態碼: {}",
+            response.status()
+        )));
+    }
+    let top_tracks: ArtistTopTracks = response.json().await?;
+    Ok(top_tracks.tracks)
+}
+
+pub async fn get_episode(
+    client: &Client,
+    episode_id: &str,
+    token: &str,
+) -> Result<Episode, SpotifyError> {
+    let url = format!(
+        "{}/episodes/{}?market={}",
+        SPOTIFY_API_BASE_URL, episode_id, DEFAULT_MARKET
+    );
+    let response = send_traced(
+        client.get(&url).bearer_auth(token),
+        "Spotify",
+        "get_episode",
+    )
+    .await?;
+    if !response.status().is_success() {
+        return Err(SpotifyError::ApiError(format!(
+            "獲取 Podcast 單集失敗，狀態碼: {}",
+            response.status()
+        )));
+    }
+    Ok(response.json().await?)
+}
+
+// 節目最新的單集，依發行日期由新到舊
+pub async fn get_show_episodes(
+    client: &Client,
+    show_id: &str,
+    token: &str,
+) -> Result<(Show, Vec<Episode>), SpotifyError> {
+    let url = format!(
+        "{}/shows/{}?market={}",
+        SPOTIFY_API_BASE_URL, show_id, DEFAULT_MARKET
+    );
+    let response = send_traced(
+        client.get(&url).bearer_auth(token),
+        "Spotify",
+        "get_show_episodes",
+    )
+    .await?;
+    if !response.status().is_success() {
+        return Err(SpotifyError::ApiError(format!(
+            "獲取 Podcast 節目失敗，狀態碼: {}",
+            response.status()
+        )));
+    }
+    let show_with_episodes: ShowWithEpisodes = response.json().await?;
+    let show = Show {
+        name: show_with_episodes.name,
+        publisher: show_with_episodes.publisher,
+    };
+    let episodes = show_with_episodes
+        .episodes
+        .items
+        .into_iter()
+        .flatten()
+        .map(|mut episode| {
+            episode.show = Some(show.clone());
+            episode
+        })
+        .collect();
+    Ok((show, episodes))
+}
+
+// 搜尋結果不含節目資訊，需再批次查詢單集補上節目名稱
+pub async fn search_episodes(
+    client: &Client,
+    query: &str,
+    token: &str,
+    limit: u32,
+    debug_mode: bool,
+) -> Result<Vec<Episode>, SpotifyError> {
+    let url = format!(
+        "{}/search?q={}&type=episode&market={}&limit={}",
+        SPOTIFY_API_BASE_URL, query, DEFAULT_MARKET, limit
+    );
+    if debug_mode {
+        debug!("搜尋 Podcast 單集: {}", url);
+    }
+    let response = send_traced(
+        client.get(&url).bearer_auth(token),
+        "Spotify",
+        "search_episodes",
+    )
+    .await?;
+    if !response.status().is_success() {
+        return Err(SpotifyError::ApiError(format!(
+            "搜尋 Podcast 單集失敗，狀態碼: {}",
+            response.status()
+        )));
+    }
+    let search_result: EpisodeSearchResult = response.json().await?;
+    let episodes: Vec<Episode> = search_result.episodes.items.into_iter().flatten().collect();
+    if episodes.is_empty() {
+        return Ok(episodes);
+    }
+
+    let ids = episodes
+        .iter()
+        .filter_map(
+            |episode| match parse_spotify_url(episode.external_urls.get("spotify")?) {
+                Some(SpotifyUrlKind::Episode(id)) => Some(id),
+                _ => None,
+            },
+        )
+        .collect::<Vec<_>>()
+        .join(",");
+    let url = format!(
+        "{}/episodes?ids={}&market={}",
+        SPOTIFY_API_BASE_URL, ids, DEFAULT_MARKET
+    );
+    let response = send_traced(
+        client.get(&url).bearer_auth(token),
+        "Spotify",
+        "search_episodes",
+    )
+    .await?;
+    if !response.status().is_success() {
+        // 取不到完整資訊時仍顯示搜尋結果，只是沒有節目名稱
+        warn!(
+            "獲取 Podcast 單集詳細資訊失敗，狀態碼: {}",
+            response.status()
+        );
+        return Ok(episodes);
+    }
+    let several: SeveralEpisodes = response.json().await?;
+    Ok(several.episodes.into_iter().flatten().collect())
+}
+
+// 回傳本頁曲目與 API 提供的總曲目數，offset 用於取得後續頁面
 pub async fn search_track(
     client: &Client,
     query: &str,
@@ -353,17 +1047,124 @@ pub async fn search_track(
     offset: u32,
     debug_mode: bool,
 ) -> Result<(Vec<TrackWithCover>, u32), SpotifyError> {
-    let url = format!(
+    search_track_in_market(client, query, token, limit, offset, None, debug_mode).await
+}
+
+// 在多個地區搜尋同一個關鍵字，依各地區的排名交錯合併並去除重複的曲目
+pub async fn search_track_in_markets(
+    client: &Client,
+    query: &str,
+    token: &str,
+    limit: u32,
+    offset: u32,
+    markets: &[String],
+    debug_mode: bool,
+) -> Result<(Vec<TrackWithCover>, u32), SpotifyError> {
+    if markets.len() <= 1 {
+        let market = markets.first().map(String::as_str);
+        return search_track_in_market(client, query, token, limit, offset, market, debug_mode)
+            .await;
+    }
+
+    let pages: Vec<_> = stream::iter(markets)
+        .map(|market| {
+            search_track_in_market(
+                client,
+                query,
+                token,
+                limit,
+                offset,
+                Some(market.as_str()),
+                debug_mode,
+            )
+        })
+        .buffered(PAGE_FETCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    // 部分地區失敗時仍使用其他地區的結果
+    let mut total = 0;
+    let mut market_tracks = Vec::new();
+    let mut last_error = None;
+    for (market, page) in markets.iter().zip(pages) {
+        match page {
+            Ok((tracks, market_total)) => {
+                total = total.max(market_total);
+                market_tracks.push(tracks.into_iter());
+            }
+            Err(e) => {
+                warn!("在地區 {} 搜尋 Spotify 失敗: {:?}", market, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    if market_tracks.is_empty() {
+        return Err(last_error
+            .unwrap_or_else(|| SpotifyError::ApiError("搜索結果中沒有找到曲目".to_string())));
+    }
+
+    // 不同地區的同一首歌可能對應到不同的曲目 ID，以名稱、歌手與專輯判斷
+    let mut seen = HashSet::new();
+    let mut merged = Vec::new();
+    loop {
+        let mut exhausted = true;
+        for tracks in market_tracks.iter_mut() {
+            if let Some(track) = tracks.next() {
+                exhausted = false;
+                let artists = track
+                    .artists
+                    .iter()
+                    .map(|artist| artist.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if seen.insert((track.name.clone(), artists, track.album_name.clone())) {
+                    merged.push(track);
+                }
+            }
+        }
+        if exhausted {
+            break;
+        }
+    }
+    // 每頁最多有 limit × 地區數 首，以此保留索引給之後的分頁
+    let base = offset as usize * markets.len();
+    for (position, track) in merged.iter_mut().enumerate() {
+        track.index = base + position;
+    }
+    if debug_mode {
+        info!(
+            "在 {} 個地區找到 {} 首不重複的曲目",
+            markets.len(),
+            merged.len()
+        );
+    }
+    Ok((merged, total))
+}
+
+async fn search_track_in_market(
+    client: &Client,
+    query: &str,
+    token: &str,
+    limit: u32,
+    offset: u32,
+    market: Option<&str>,
+    debug_mode: bool,
+) -> Result<(Vec<TrackWithCover>, u32), SpotifyError> {
+    let mut url = format!(
         "{}/search?q={}&type=track&limit={}&offset={}",
         SPOTIFY_API_BASE_URL, query, limit, offset
     );
+    if let Some(market) = market {
+        url.push_str(&format!("&market={}", market));
+    }
 
-    let response = client
-        .get(&url)
-        .bearer_auth(token)
-        .send()
-        .await
-        .map_err(|e| SpotifyError::RequestError(e))?;
+    let response = send_traced(
+        client.get(&url).bearer_auth(token),
+        "Spotify",
+        "search_track",
+    )
+    .await
+    .map_err(|e| SpotifyError::RequestError(e))?;
 
     if debug_mode {
         info!("Spotify API 請求詳情:");
@@ -426,6 +1227,13 @@ pub async fn search_track(
                         external_urls: track.external_urls,
                         album_name: track.album.name,
                         cover_url,
+                        preview_url: track.preview_url,
+                        duration_ms: track.duration_ms,
+                        explicit: track.explicit,
+                        available_markets: track.available_markets,
+                        is_playable: track.is_playable,
+                        release_date: track.album.release_date,
+                        is_episode: false,
                         index: index + (offset as usize),
                     }
                 })
@@ -435,13 +1243,101 @@ pub async fn search_track(
                 info!("成功處理 {} 首曲目", track_infos.len());
             }
 
-            Ok((track_infos, total_pages))
+            Ok((track_infos, total_tracks))
         }
         None => Err(SpotifyError::ApiError("搜索結果中沒有找到曲目".to_string())),
     }
 }
 
 
+// 搜尋建議只需要名稱，不解析完整的曲目資料
+#[derive(Deserialize)]
+struct SuggestionResult {
+    tracks: Option<SuggestionItems<SuggestionTrack>>,
+    artists: Option<SuggestionItems<Artist>>,
+}
+
+#[derive(Deserialize)]
+struct SuggestionItems<T> {
+    items: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct SuggestionTrack {
+    name: String,
+    artists: Vec<Artist>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchSuggestion {
+    Track { name: String, artists: String },
+    Artist(String),
+}
+
+impl SearchSuggestion {
+    // 選擇建議後填入搜尋列的查詢
+    pub fn query(&self) -> String {
+        match self {
+            SearchSuggestion::Track { name, artists } => format!("{} {}", artists, name),
+            SearchSuggestion::Artist(name) => name.clone(),
+        }
+    }
+}
+
+// 輸入時的自動完成，歌手在前、曲目在後
+pub async fn fetch_search_suggestions(
+    client: &Client,
+    query: &str,
+    token: &str,
+    limit: u32,
+) -> Result<Vec<SearchSuggestion>, SpotifyError> {
+    let limit_param = limit.to_string();
+    let request = client
+        .get(format!("{}/search", SPOTIFY_API_BASE_URL))
+        .query(&[
+            ("q", query),
+            ("type", "artist,track"),
+            ("limit", limit_param.as_str()),
+        ])
+        .bearer_auth(token);
+    let response = send_traced(request, "Spotify", "search_suggestions")
+        .await
+        .map_err(SpotifyError::RequestError)?;
+    if !response.status().is_success() {
+        return Err(SpotifyError::ApiError(format!(
+            "搜尋建議請求失敗: {}",
+            response.status()
+        )));
+    }
+    let result: SuggestionResult = response.json().await?;
+
+    let mut suggestions: Vec<SearchSuggestion> = result
+        .artists
+        .map(|artists| artists.items)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|artist| SearchSuggestion::Artist(artist.name))
+        .collect();
+    suggestions.extend(
+        result
+            .tracks
+            .map(|tracks| tracks.items)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|track| SearchSuggestion::Track {
+                name: track.name,
+                artists: track
+                    .artists
+                    .iter()
+                    .map(|artist| artist.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }),
+    );
+    suggestions.dedup();
+    Ok(suggestions)
+}
+
 pub async fn get_access_token(
     client: &reqwest::Client,
     debug_mode: bool,
@@ -463,7 +1359,9 @@ pub async fn get_access_token(
         .header("Content-Type", "application/x-www-form-urlencoded")
         .body(body);
 
-    let response = request.send().await.map_err(SpotifyError::RequestError)?;
+    let response = send_traced(request, "Spotify", "get_access_token")
+        .await
+        .map_err(SpotifyError::RequestError)?;
 
     if response.status().is_success() {
         let auth_response: AuthResponse = response.json().await?; // 這裡直接使用 ?
@@ -596,7 +1494,13 @@ pub async fn update_current_playing(
     currently_playing: Arc<Mutex<Option<CurrentlyPlaying>>>,
     debug_mode: bool,
 ) -> Result<Option<CurrentlyPlaying>> {
-    match spotify.current_user_playing_item().await {
+    match traced(
+        "Spotify",
+        "current_user_playing_item",
+        spotify.current_user_playing_item(),
+    )
+    .await
+    {
         Ok(Some(playing_context)) => {
             if let Some(PlayableItem::Track(track)) = playing_context.item {
                 let artists = track
@@ -700,7 +1604,7 @@ pub fn authorize_spotify(
         let client_id = config["spotify"]["client_id"]
             .as_str()
             .ok_or_else(|| SpotifyError::ConfigError("Missing Spotify client ID".to_string()))?;
-        let scope = "user-read-currently-playing user-read-private user-read-email user-library-read user-library-modify";
+        let scope = "user-read-currently-playing user-read-private user-read-email user-library-read user-library-modify playlist-modify-public playlist-modify-private user-read-playback-state user-modify-playback-state user-follow-read user-follow-modify";
 
         // 檢查是否已有監聽器，如果沒有則創建新的
         let bound_port = {
@@ -883,18 +1787,21 @@ async fn process_authorization_callback(
 
     match timeout(
         Duration::from_secs(30),
-        client
-            .post(token_url)
-            .basic_auth(
-                config["spotify"]["client_id"].as_str().ok_or_else(|| {
-                    SpotifyError::ConfigError("Missing Spotify client ID".to_string())
-                })?,
-                Some(config["spotify"]["client_secret"].as_str().ok_or_else(|| {
-                    SpotifyError::ConfigError("Missing Spotify client secret".to_string())
-                })?),
-            )
-            .form(&params)
-            .send(),
+        send_traced(
+            client
+                .post(token_url)
+                .basic_auth(
+                    config["spotify"]["client_id"].as_str().ok_or_else(|| {
+                        SpotifyError::ConfigError("Missing Spotify client ID".to_string())
+                    })?,
+                    Some(config["spotify"]["client_secret"].as_str().ok_or_else(|| {
+                        SpotifyError::ConfigError("Missing Spotify client secret".to_string())
+                    })?),
+                )
+                .form(&params),
+            "Spotify",
+            "process_authorization_callback",
+        ),
     )
     .await
     {
@@ -919,7 +1826,11 @@ async fn process_authorization_callback(
                         scopes: scopes!(
                             "user-read-currently-playing",
                             "user-read-private",
-                            "user-read-email"
+                            "user-read-email",
+                            "user-read-playback-state",
+                            "user-modify-playback-state",
+                            "user-follow-read",
+                            "user-follow-modify"
                         ),
                         ..Default::default()
                     };
@@ -931,8 +1842,7 @@ async fn process_authorization_callback(
                         rspotify::Config::default(),
                     );
 
-                    let user = new_spotify
-                        .current_user()
+                    let user = traced("Spotify", "current_user", new_spotify.current_user())
                         .await
                         .map_err(|e| SpotifyError::ApiError(format!("無法獲取用戶信息: {}", e)))?;
 
@@ -1031,31 +1941,6 @@ async fn accept_connection(
     }
 }
 
-pub fn load_spotify_icon(ctx: &egui::Context) -> Option<egui::TextureHandle> {
-    let icon_bytes = include_bytes!("assets/spotify_icon_black.png");
-
-    match image::load_from_memory(icon_bytes) {
-        Ok(image) => {
-            let size = [image.width() as _, image.height() as _];
-            let image_buffer = image.to_rgba8();
-            let pixels = image_buffer.as_flat_samples();
-            let image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
-
-            let texture_options = egui::TextureOptions {
-                magnification: egui::TextureFilter::Linear,
-                minification: egui::TextureFilter::Linear,
-                wrap_mode: egui::TextureWrapMode::ClampToEdge,
-            };
-
-            Some(ctx.load_texture("spotify_icon", image, texture_options))
-        }
-        Err(e) => {
-            error!("無法載入 Spotify 圖標：{:?}", e);
-            None
-        }
-    }
-}
-
 pub async fn add_track_to_liked(
     spotify: &AuthCodeSpotify, 
     track_id: &str
@@ -1063,9 +1948,13 @@ pub async fn add_track_to_liked(
     let track_id = TrackId::from_id(track_id)
         .map_err(|e| SpotifyError::ApiError(format!("無效的曲目 ID: {}", e)))?;
     
-    spotify.current_user_saved_tracks_add(vec![track_id])
-        .await
-        .map_err(|e| SpotifyError::ApiError(format!("無法將曲目添加到 Liked Songs: {}", e)))?;
+    traced(
+        "Spotify",
+        "current_user_saved_tracks_add",
+        spotify.current_user_saved_tracks_add(vec![track_id]),
+    )
+    .await
+    .map_err(|e| SpotifyError::ApiError(format!("無法將曲目添加到 Liked Songs: {}", e)))?;
     
     Ok(())
 }
@@ -1076,12 +1965,90 @@ pub async fn remove_track_from_liked(
     let track_id = TrackId::from_id(track_id)
         .map_err(|e| SpotifyError::ApiError(format!("無效的曲目 ID: {}", e)))?;
     
-    spotify.current_user_saved_tracks_delete(vec![track_id])
-        .await
-        .map_err(|e| SpotifyError::ApiError(format!("無法從 Liked Songs 中移除曲目: {}", e)))?;
+    traced(
+        "Spotify",
+        "current_user_saved_tracks_delete",
+        spotify.current_user_saved_tracks_delete(vec![track_id]),
+    )
+    .await
+    .map_err(|e| SpotifyError::ApiError(format!("無法從 Liked Songs 中移除曲目: {}", e)))?;
     
     Ok(())
 }
+// 切換使用者目前裝置的播放 / 暫停，控制播放需要 Premium 帳號
+pub async fn toggle_spotify_playback(spotify: &AuthCodeSpotify) -> Result<(), SpotifyError> {
+    let playback = traced(
+        "Spotify",
+        "current_playback",
+        spotify.current_playback(None, None::<&[AdditionalType]>),
+    )
+    .await
+    .map_err(|e| SpotifyError::ApiError(format!("無法獲取 Spotify 播放狀態: {}", e)))?;
+    let result = match playback {
+        Some(playback) if playback.is_playing => {
+            traced("Spotify", "pause_playback", spotify.pause_playback(None)).await
+        }
+        Some(_) => {
+            traced(
+                "Spotify",
+                "resume_playback",
+                spotify.resume_playback(None, None),
+            )
+            .await
+        }
+        None => {
+            return Err(SpotifyError::ApiError(
+                "沒有正在使用的 Spotify 裝置".to_string(),
+            ))
+        }
+    };
+    result.map_err(|e| SpotifyError::ApiError(format!("無法切換 Spotify 播放: {}", e)))
+}
+
+// forward 為 false 時回到上一首
+pub async fn skip_spotify_track(
+    spotify: &AuthCodeSpotify,
+    forward: bool,
+) -> Result<(), SpotifyError> {
+    let result = if forward {
+        traced("Spotify", "next_track", spotify.next_track(None)).await
+    } else {
+        traced("Spotify", "previous_track", spotify.previous_track(None)).await
+    };
+    result.map_err(|e| SpotifyError::ApiError(format!("無法切換 Spotify 曲目: {}", e)))
+}
+
+// 收藏曲目端點每次最多接受的曲目數
+pub const SAVED_TRACKS_CHUNK_SIZE: usize = 50;
+
+// 一次加入或移除一批收藏，呼叫端需自行切成 SAVED_TRACKS_CHUNK_SIZE 以內
+pub async fn set_tracks_liked(
+    spotify: &AuthCodeSpotify,
+    track_ids: &[String],
+    liked: bool,
+) -> Result<(), SpotifyError> {
+    let track_ids = track_ids
+        .iter()
+        .map(|id| TrackId::from_id(id.as_str()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| SpotifyError::ApiError(format!("無效的曲目 ID: {}", e)))?;
+    let result = if liked {
+        traced(
+            "Spotify",
+            "current_user_saved_tracks_add",
+            spotify.current_user_saved_tracks_add(track_ids),
+        )
+        .await
+    } else {
+        traced(
+            "Spotify",
+            "current_user_saved_tracks_delete",
+            spotify.current_user_saved_tracks_delete(track_ids),
+        )
+        .await
+    };
+    result.map_err(|e| SpotifyError::ApiError(format!("批次更新 Liked Songs 失敗: {}", e)))
+}
 pub async fn get_user_playlists(spotify_client: Arc<Mutex<Option<AuthCodeSpotify>>>) -> Result<Vec<SimplifiedPlaylist>> {
     // 鎖定 Mutex，取得 Spotify 客戶端的克隆，然後立即釋放 MutexGuard
     let spotify_ref = {
@@ -1094,7 +2061,12 @@ pub async fn get_user_playlists(spotify_client: Arc<Mutex<Option<AuthCodeSpotify
         let mut offset = 0;
         loop {
             // 在這裡執行異步操作，不再持有 MutexGuard
-            let current_user_playlists = spotify.current_user_playlists_manual(Some(50), Some(offset)).await?;
+            let current_user_playlists = traced(
+                "Spotify",
+                "current_user_playlists_manual",
+                spotify.current_user_playlists_manual(Some(50), Some(offset)),
+            )
+            .await?;
             if current_user_playlists.items.is_empty() {
                 break;
             }
@@ -1106,47 +2078,246 @@ pub async fn get_user_playlists(spotify_client: Arc<Mutex<Option<AuthCodeSpotify
         Err(anyhow!("Spotify 客戶端未初始化"))
     }
 }
-pub async fn get_playlist_tracks(
+// 建立新的播放清單並回傳播放清單 ID
+pub async fn create_playlist(
     spotify_client: Arc<Mutex<Option<AuthCodeSpotify>>>,
-    playlist_id: String,
-) -> Result<Vec<FullTrack>> {
+    name: String,
+    description: Option<String>,
+) -> Result<String> {
     let spotify_ref = {
         let spotify = spotify_client.lock().unwrap();
         spotify.as_ref().cloned()
     };
 
     if let Some(spotify) = spotify_ref {
-        let mut tracks = Vec::new();
-        let mut offset = 0;
+        let user = traced("Spotify", "current_user", spotify.current_user()).await?;
+        let playlist = traced(
+            "Spotify",
+            "user_playlist_create",
+            spotify.user_playlist_create(user.id, &name, Some(false), None, description.as_deref()),
+        )
+        .await?;
+        info!("已建立播放清單: {} ({})", playlist.name, playlist.id.id());
+        Ok(playlist.id.id().to_string())
+    } else {
+        Err(anyhow!("Spotify 客戶端未初始化"))
+    }
+}
+// 將曲目加入播放清單，API 每次最多 100 首
+pub async fn add_tracks_to_playlist(
+    spotify_client: Arc<Mutex<Option<AuthCodeSpotify>>>,
+    playlist_id: String,
+    track_ids: Vec<String>,
+) -> Result<usize> {
+    let spotify_ref = {
+        let spotify = spotify_client.lock().unwrap();
+        spotify.as_ref().cloned()
+    };
 
+    if let Some(spotify) = spotify_ref {
         let playlist_id = PlaylistId::from_id(&playlist_id)?;
-
-        loop {
-            let playlist_items = spotify
-                .playlist_items_manual(
+        let track_ids = track_ids
+            .iter()
+            .map(|id| TrackId::from_id(id.as_str()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for chunk in track_ids.chunks(100) {
+            traced(
+                "Spotify",
+                "playlist_add_items",
+                spotify.playlist_add_items(
                     playlist_id.clone(),
+                    chunk.iter().map(|id| PlayableId::Track(id.clone())),
                     None,
-                    None,
-                    Some(100),
-                    Some(offset),
-                )
-                .await?;
+                ),
+            )
+            .await?;
+        }
+        Ok(track_ids.len())
+    } else {
+        Err(anyhow!("Spotify 客戶端未初始化"))
+    }
+}
+// 先取得第一頁得知總數，其餘分頁以有限的並行數同時取得，依 offset 順序合併
+async fn fetch_pages_concurrently<T, F, Fut>(page_size: u32, fetch_page: F) -> Result<Vec<T>>
+where
+    F: Fn(u32) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, u32)>>,
+{
+    let (mut items, total) = fetch_page(0).await?;
+    let offsets: Vec<u32> = (page_size..total).step_by(page_size as usize).collect();
+    let pages: Vec<(Vec<T>, u32)> = stream::iter(offsets)
+        .map(&fetch_page)
+        .buffered(PAGE_FETCH_CONCURRENCY)
+        .try_collect()
+        .await?;
+    for (page, _) in pages {
+        items.extend(page);
+    }
+    Ok(items)
+}
 
-            if playlist_items.items.is_empty() {
-                break;
-            }
+// 以原始 JSON 逐項解析，本機檔案或已下架的曲目不會讓整頁讀取失敗
+pub async fn get_playlist_tracks(
+    spotify_client: Arc<Mutex<Option<AuthCodeSpotify>>>,
+    playlist_id: String,
+) -> Result<Vec<PlaylistEntry>> {
+    let spotify_ref = {
+        let spotify = spotify_client.lock().unwrap();
+        spotify.as_ref().cloned()
+    };
 
-            for item in playlist_items.items {
-                if let Some(PlayableItem::Track(track)) = item.track {
-                    tracks.push(track);
-                }
-            }
+    if let Some(spotify) = spotify_ref {
+        let playlist_id = PlaylistId::from_id(&playlist_id)?;
+        let url = format!("playlists/{}/tracks", playlist_id.id());
+        let (spotify, url) = (&spotify, &url);
+
+        fetch_pages_concurrently(PLAYLIST_PAGE_SIZE, |offset| async move {
+            let limit_param = PLAYLIST_PAGE_SIZE.to_string();
+            let offset_param = offset.to_string();
+            let params = HashMap::from([
+                ("limit", limit_param.as_str()),
+                ("offset", offset_param.as_str()),
+            ]);
+            let response = traced(
+                "Spotify",
+                "playlist_items_manual",
+                spotify.api_get(url, &params),
+            )
+            .await?;
+            let page: PlaylistTracksPage = serde_json::from_str(&response)?;
+            let entries = page
+                .items
+                .into_iter()
+                .map(|item| PlaylistEntry::from_value(item.track))
+                .collect();
+            Ok((entries, page.total))
+        })
+        .await
+    } else {
+        Err(anyhow!("Spotify 客戶端未初始化"))
+    }
+}
 
-            offset += 100;
+pub async fn get_liked_tracks(spotify: &AuthCodeSpotify) -> Result<Vec<FullTrack>> {
+    fetch_pages_concurrently(LIKED_TRACKS_PAGE_SIZE, |offset| async move {
+        let page = traced(
+            "Spotify",
+            "current_user_saved_tracks_manual",
+            spotify.current_user_saved_tracks_manual(
+                None,
+                Some(LIKED_TRACKS_PAGE_SIZE),
+                Some(offset),
+            ),
+        )
+        .await?;
+        let tracks = page
+            .items
+            .into_iter()
+            .map(|saved_track| saved_track.track)
+            .collect();
+        Ok((tracks, page.total))
+    })
+    .await
+}
+// 使用者追蹤的歌手，需要 user-follow-read 權限，以 cursor 分頁
+pub async fn get_followed_artists(spotify: &AuthCodeSpotify) -> Result<Vec<FullArtist>> {
+    let mut artists = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let page = traced(
+            "Spotify",
+            "current_user_followed_artists",
+            spotify.current_user_followed_artists(after.as_deref(), Some(50)),
+        )
+        .await?;
+        artists.extend(page.items);
+        after = page.cursors.and_then(|cursors| cursors.after);
+        if page.next.is_none() || after.is_none() {
+            break;
         }
-
-        Ok(tracks)
+    }
+    Ok(artists)
+}
+// 歌手的專輯與單曲，API 在各類型內依發行日期由新到舊排列，只取第一頁
+pub async fn get_artist_releases(
+    spotify: &AuthCodeSpotify,
+    artist_id: &str,
+) -> Result<Vec<SimplifiedAlbum>> {
+    let artist_id = ArtistId::from_id(artist_id)?;
+    let page = traced(
+        "Spotify",
+        "artist_albums_manual",
+        spotify.artist_albums_manual(
+            artist_id,
+            [AlbumType::Album, AlbumType::Single],
+            None,
+            Some(20),
+            Some(0),
+        ),
+    )
+    .await?;
+    Ok(page.items)
+}
+// 取得專輯內所有曲目，使用者登入的版本
+pub async fn get_release_tracks(
+    spotify: &AuthCodeSpotify,
+    album_id: &str,
+) -> Result<Vec<SimplifiedTrack>> {
+    let album_id = AlbumId::from_id(album_id)?;
+    let mut tracks = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = traced(
+            "Spotify",
+            "album_track_manual",
+            spotify.album_track_manual(album_id.clone(), None, Some(50), Some(offset)),
+        )
+        .await?;
+        let count = page.items.len() as u32;
+        tracks.extend(page.items);
+        if page.next.is_none() || count == 0 {
+            break;
+        }
+        offset += count;
+    }
+    Ok(tracks)
+}
+// 追蹤或取消追蹤歌手，需要 user-follow-modify 權限
+pub async fn set_artist_followed(
+    spotify: &AuthCodeSpotify,
+    artist_id: &str,
+    follow: bool,
+) -> Result<()> {
+    let artist_id = ArtistId::from_id(artist_id)?;
+    if follow {
+        traced(
+            "Spotify",
+            "user_follow_artists",
+            spotify.user_follow_artists([artist_id]),
+        )
+        .await?;
     } else {
-        Err(anyhow!("Spotify 客戶端未初始化"))
+        traced(
+            "Spotify",
+            "user_unfollow_artists",
+            spotify.user_unfollow_artists([artist_id]),
+        )
+        .await?;
     }
+    Ok(())
+}
+// 歌手的熱門曲目，使用登入帳號所在的市場
+pub async fn get_artist_top_tracks_for_user(
+    spotify: &AuthCodeSpotify,
+    artist_id: &str,
+) -> Result<Vec<FullTrack>> {
+    let artist_id = ArtistId::from_id(artist_id)?;
+    let tracks = traced(
+        "Spotify",
+        "artist_top_tracks",
+        spotify.artist_top_tracks(artist_id, Some(Market::FromToken)),
+    )
+    .await?;
+    Ok(tracks)
 }
@@ -0,0 +1,247 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// 第三方庫導入
+use chrono::{DateTime, Local};
+use log::{error, info};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+// 本地模組導入
+use crate::download_history::DownloadRecord;
+use crate::match_memory::ConfirmedMatch;
+use crate::storage::{Storage, StorageError};
+
+// 索引 i 的語句將資料庫從版本 i 升級到 i + 1，只能在尾端新增
+const MIGRATIONS: [&str; 1] = ["
+    CREATE TABLE download_history (
+        beatmapset_id INTEGER PRIMARY KEY,
+        file_name TEXT NOT NULL,
+        downloaded_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_download_history_downloaded_at ON download_history (downloaded_at);
+    CREATE TABLE match_memory (
+        track_id TEXT PRIMARY KEY,
+        beatmapset_id INTEGER NOT NULL,
+        track TEXT NOT NULL,
+        beatmapset TEXT NOT NULL,
+        confirmed_at TEXT NOT NULL
+    );
+    CREATE INDEX idx_match_memory_beatmapset_id ON match_memory (beatmapset_id);
+    CREATE TABLE caches (
+        name TEXT PRIMARY KEY,
+        content BLOB NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+"];
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn write_history(transaction: &Transaction, records: &[DownloadRecord]) -> rusqlite::Result<()> {
+    transaction.execute("DELETE FROM download_history", [])?;
+    let mut statement = transaction.prepare(
+        "INSERT OR REPLACE INTO download_history (beatmapset_id, file_name, downloaded_at)
+         VALUES (?1, ?2, ?3)",
+    )?;
+    for record in records {
+        statement.execute(params![
+            record.beatmapset_id,
+            record.file_name,
+            record.downloaded_at as i64
+        ])?;
+    }
+    Ok(())
+}
+
+fn write_match_memory(
+    transaction: &Transaction,
+    memory: &HashMap<String, ConfirmedMatch>,
+) -> rusqlite::Result<()> {
+    transaction.execute("DELETE FROM match_memory", [])?;
+    let mut statement = transaction.prepare(
+        "INSERT INTO match_memory (track_id, beatmapset_id, track, beatmapset, confirmed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (track_id, confirmed) in memory {
+        statement.execute(params![
+            track_id,
+            confirmed.beatmapset_id,
+            confirmed.track,
+            confirmed.beatmapset,
+            confirmed.confirmed_at.to_rfc3339()
+        ])?;
+    }
+    Ok(())
+}
+
+pub struct SqliteStorage {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    // legacy 為第一次建立資料庫時匯入資料的來源
+    pub fn open(path: &Path, legacy: &dyn Storage) -> Result<Self, StorageError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut connection = Connection::open(path)?;
+        let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let transaction = connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            // 與建立資料表在同一個交易中匯入，匯入失敗時下次啟動會重新建立並匯入
+            if index == 0 {
+                write_history(&transaction, &legacy.load_history()?)?;
+                write_match_memory(&transaction, &legacy.load_match_memory()?)?;
+                info!("已將既有的下載紀錄與配對記憶匯入 SQLite 資料庫");
+            }
+            transaction.pragma_update(None, "user_version", (index + 1) as i64)?;
+            transaction.commit()?;
+            info!("SQLite 資料庫已升級到版本 {}", index + 1);
+        }
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load_history(&self) -> Result<Vec<DownloadRecord>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT beatmapset_id, file_name, downloaded_at FROM download_history
+             ORDER BY downloaded_at",
+        )?;
+        let records = statement
+            .query_map([], |row| {
+                Ok(DownloadRecord {
+                    beatmapset_id: row.get(0)?,
+                    file_name: row.get(1)?,
+                    downloaded_at: row.get::<_, i64>(2)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    fn save_history(&self, records: &[DownloadRecord]) -> Result<(), StorageError> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        write_history(&transaction, records)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn load_match_memory(&self) -> Result<HashMap<String, ConfirmedMatch>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT track_id, beatmapset_id, track, beatmapset, confirmed_at FROM match_memory",
+        )?;
+        let memory = statement
+            .query_map([], |row| {
+                let confirmed_at: String = row.get(4)?;
+                let confirmed_at = DateTime::parse_from_rfc3339(&confirmed_at)
+                    .map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(4, Type::Text, Box::new(e))
+                    })?
+                    .with_timezone(&Local);
+                Ok((
+                    row.get::<_, String>(0)?,
+                    ConfirmedMatch {
+                        beatmapset_id: row.get(1)?,
+                        track: row.get(2)?,
+                        beatmapset: row.get(3)?,
+                        confirmed_at,
+                    },
+                ))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(memory)
+    }
+
+    fn save_match_memory(
+        &self,
+        memory: &HashMap<String, ConfirmedMatch>,
+    ) -> Result<(), StorageError> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        write_match_memory(&transaction, memory)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn open_cache(&self, name: &str) -> Result<Option<(Box<dyn Read + Send>, u64)>, StorageError> {
+        let content: Option<Vec<u8>> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT content FROM caches WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(content.map(|content| {
+            let size = content.len() as u64;
+            (Box::new(Cursor::new(content)) as Box<dyn Read + Send>, size)
+        }))
+    }
+
+    fn save_cache(&self, name: &str, content: &[u8]) -> Result<(), StorageError> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO caches (name, content, updated_at) VALUES (?1, ?2, ?3)",
+            params![name, content, now_secs()],
+        )?;
+        Ok(())
+    }
+
+    fn cache_modified(&self, name: &str) -> Option<SystemTime> {
+        let updated_at: i64 = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT updated_at FROM caches WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .ok()?;
+        Some(UNIX_EPOCH + Duration::from_secs(updated_at.max(0) as u64))
+    }
+
+    fn caches(&self) -> Vec<(String, u64)> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = match connection.prepare("SELECT name, length(content) FROM caches") {
+            Ok(statement) => statement,
+            Err(e) => {
+                error!("讀取 SQLite 快取列表失敗: {:?}", e);
+                return Vec::new();
+            }
+        };
+        let caches = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>());
+        caches.unwrap_or_default()
+    }
+
+    fn remove_cache(&self, name: &str) -> Result<(), StorageError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM caches WHERE name = ?1", params![name])?;
+        Ok(())
+    }
+}
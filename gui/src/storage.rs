@@ -0,0 +1,210 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// 本地模組導入
+use crate::download_history::DownloadRecord;
+use crate::match_memory::ConfirmedMatch;
+#[cfg(feature = "sqlite")]
+use crate::sqlite_storage::SqliteStorage;
+use lib::{get_app_data_path, load_config, save_config, write_atomic};
+
+const OPTIONS_FILE: &str = "storage_options.json";
+const HISTORY_FILE: &str = "download_history.json";
+const MEMORY_FILE: &str = "match_memory.json";
+#[cfg(feature = "sqlite")]
+const DATABASE_FILE: &str = "storage.db";
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("IO 錯誤: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON 錯誤: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite 錯誤: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum StorageBackend {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+impl StorageBackend {
+    pub const ALL: [StorageBackend; 2] = [StorageBackend::Json, StorageBackend::Sqlite];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StorageBackend::Json => "JSON 檔案",
+            StorageBackend::Sqlite => "SQLite 資料庫",
+        }
+    }
+
+    // SQLite 需要以 sqlite 功能編譯
+    pub fn available(&self) -> bool {
+        match self {
+            StorageBackend::Json => true,
+            StorageBackend::Sqlite => cfg!(feature = "sqlite"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct StorageOptions {
+    // 重新啟動後生效
+    pub backend: StorageBackend,
+}
+
+// 播放列表快取、下載紀錄與配對記憶的儲存方式
+pub trait Storage: Send + Sync {
+    fn load_history(&self) -> Result<Vec<DownloadRecord>, StorageError>;
+    fn save_history(&self, records: &[DownloadRecord]) -> Result<(), StorageError>;
+    fn load_match_memory(&self) -> Result<HashMap<String, ConfirmedMatch>, StorageError>;
+    fn save_match_memory(
+        &self,
+        memory: &HashMap<String, ConfirmedMatch>,
+    ) -> Result<(), StorageError>;
+
+    // 曲目快取以原本的檔名作為鍵，內容為 JSON，回傳讀取器與總位元組數
+    fn open_cache(&self, name: &str) -> Result<Option<(Box<dyn Read + Send>, u64)>, StorageError>;
+    fn save_cache(&self, name: &str, content: &[u8]) -> Result<(), StorageError>;
+    fn cache_modified(&self, name: &str) -> Option<SystemTime>;
+    // 所有曲目快取的名稱與大小
+    fn caches(&self) -> Vec<(String, u64)>;
+    fn remove_cache(&self, name: &str) -> Result<(), StorageError>;
+}
+
+// 預設的儲存方式，每種資料各一個 JSON 檔案
+pub struct JsonStorage;
+
+fn is_track_cache(file_name: &str) -> bool {
+    file_name == "liked_tracks_cache.json"
+        || (file_name.starts_with("playlist_") && file_name.ends_with("_cache.json"))
+}
+
+impl Storage for JsonStorage {
+    fn load_history(&self) -> Result<Vec<DownloadRecord>, StorageError> {
+        Ok(load_config(HISTORY_FILE).unwrap_or_default())
+    }
+
+    fn save_history(&self, records: &[DownloadRecord]) -> Result<(), StorageError> {
+        Ok(save_config(HISTORY_FILE, records)?)
+    }
+
+    fn load_match_memory(&self) -> Result<HashMap<String, ConfirmedMatch>, StorageError> {
+        Ok(load_config(MEMORY_FILE).unwrap_or_default())
+    }
+
+    fn save_match_memory(
+        &self,
+        memory: &HashMap<String, ConfirmedMatch>,
+    ) -> Result<(), StorageError> {
+        Ok(save_config(MEMORY_FILE, memory)?)
+    }
+
+    fn open_cache(&self, name: &str) -> Result<Option<(Box<dyn Read + Send>, u64)>, StorageError> {
+        let file = match File::open(get_app_data_path().join(name)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let size = file.metadata()?.len();
+        Ok(Some((Box::new(file), size)))
+    }
+
+    fn save_cache(&self, name: &str, content: &[u8]) -> Result<(), StorageError> {
+        Ok(write_atomic(&get_app_data_path().join(name), content)?)
+    }
+
+    fn cache_modified(&self, name: &str) -> Option<SystemTime> {
+        fs::metadata(get_app_data_path().join(name))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn caches(&self) -> Vec<(String, u64)> {
+        let entries = match fs::read_dir(get_app_data_path()) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_str()?.to_string();
+                let metadata = entry.metadata().ok()?;
+                (metadata.is_file() && is_track_cache(&name)).then(|| (name, metadata.len()))
+            })
+            .collect()
+    }
+
+    fn remove_cache(&self, name: &str) -> Result<(), StorageError> {
+        Ok(fs::remove_file(get_app_data_path().join(name))?)
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<StorageOptions> = RwLock::new(load_options());
+    // 啟動時依選項決定，執行期間不會切換
+    static ref STORAGE: Box<dyn Storage> = open_storage(storage_options().backend);
+}
+
+fn load_options() -> StorageOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn storage_options() -> StorageOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_storage_options(options: StorageOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存儲存選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 目前使用中的儲存方式
+pub fn storage() -> &'static dyn Storage {
+    STORAGE.as_ref()
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite() -> Option<Box<dyn Storage>> {
+    // 第一次建立資料庫時會匯入既有的 JSON 紀錄
+    match SqliteStorage::open(&get_app_data_path().join(DATABASE_FILE), &JsonStorage) {
+        Ok(storage) => Some(Box::new(storage)),
+        Err(e) => {
+            warn!("無法開啟 SQLite 資料庫，改用 JSON 檔案: {:?}", e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn open_sqlite() -> Option<Box<dyn Storage>> {
+    warn!("此版本未啟用 sqlite 功能，改用 JSON 檔案");
+    None
+}
+
+fn open_storage(backend: StorageBackend) -> Box<dyn Storage> {
+    if backend == StorageBackend::Sqlite {
+        if let Some(storage) = open_sqlite() {
+            info!("使用 SQLite 資料庫儲存快取與紀錄");
+            return storage;
+        }
+    }
+    Box::new(JsonStorage)
+}
@@ -0,0 +1,344 @@
+// 標準庫導入
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// 第三方庫導入
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use log::{error, info, warn};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::download_options::low_disk_space;
+use crate::match_memory::{confirmed_beatmapset, is_rejected, spotify_track_id};
+use crate::matcher::{match_options, rank_beatmapsets};
+use crate::notify::{notify_batch_completed, BatchReport};
+use crate::osu::{download_beatmap, get_osu_token, is_beatmap_downloaded};
+use crate::query_normalizer::search_beatmapsets_normalized;
+use crate::review_queue::{add_review_item, ReviewCandidate, ReviewItem, ReviewTrack};
+use crate::spotify::{
+    get_access_token, get_public_playlist_tracks, parse_spotify_url, SpotifyUrlKind, Track,
+};
+use lib::{get_app_data_path, load_config, save_config};
+
+const CONFIG_FILE: &str = "sync_config.json";
+const STATE_FILE: &str = "sync_state.json";
+const DEFAULT_INTERVAL_MINUTES: u64 = 60;
+
+// 同步設定，第一次執行時會產生範本讓使用者填寫
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SyncConfig {
+    // 播放清單網址或 ID，只支援公開播放清單
+    pub playlists: Vec<String>,
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: u64,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            playlists: Vec::new(),
+            interval_minutes: DEFAULT_INTERVAL_MINUTES,
+        }
+    }
+}
+
+fn default_interval_minutes() -> u64 {
+    DEFAULT_INTERVAL_MINUTES
+}
+
+// 每個播放清單已處理過的曲目，下次只處理新加入的曲目
+#[derive(Serialize, Deserialize, Default)]
+struct SyncState {
+    seen_tracks: HashMap<String, HashSet<String>>,
+}
+
+#[derive(Default)]
+struct SyncSummary {
+    playlists: usize,
+    new_tracks: usize,
+    matched: usize,
+    downloaded: usize,
+    already_downloaded: usize,
+    needs_review: usize,
+    failures: Vec<String>,
+}
+
+impl SyncSummary {
+    fn report(&self) -> BatchReport {
+        BatchReport {
+            title: "播放清單同步完成".to_string(),
+            succeeded: self.downloaded,
+            failures: self.failures.clone(),
+            fields: vec![
+                ("播放清單".to_string(), self.playlists.to_string()),
+                ("新曲目".to_string(), self.new_tracks.to_string()),
+                ("匹配".to_string(), self.matched.to_string()),
+                ("已存在".to_string(), self.already_downloaded.to_string()),
+                ("需要確認".to_string(), self.needs_review.to_string()),
+            ],
+        }
+    }
+}
+
+fn playlist_id(entry: &str) -> String {
+    match parse_spotify_url(entry) {
+        Some(SpotifyUrlKind::Playlist(id)) => id,
+        _ => entry.trim().to_string(),
+    }
+}
+
+// 以 Spotify 網址辨識曲目，沒有網址時（極少數）改用歌手與歌名
+fn track_key(track: &Track) -> String {
+    track
+        .external_urls
+        .get("spotify")
+        .cloned()
+        .unwrap_or_else(|| format!("{} - {}", artist_names(track), track.name))
+}
+
+fn artist_names(track: &Track) -> String {
+    track
+        .artists
+        .iter()
+        .map(|artist| artist.name.clone())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// 不開啟圖形介面，定期同步設定的播放清單
+pub async fn run_sync_daemon(download_directory: PathBuf, debug_mode: bool) -> Result<()> {
+    let config = match load_config::<SyncConfig>(CONFIG_FILE) {
+        Some(config) if !config.playlists.is_empty() => config,
+        _ => {
+            save_config(CONFIG_FILE, &SyncConfig::default())?;
+            let path = get_app_data_path().join(CONFIG_FILE);
+            println!("請在 {:?} 中設定要同步的播放清單後重新執行", path);
+            return Err(anyhow!("尚未設定要同步的播放清單: {:?}", path));
+        }
+    };
+    let interval = Duration::from_secs(config.interval_minutes.max(1) * 60);
+    info!(
+        "同步模式啟動：{} 個播放清單，每 {} 分鐘檢查一次",
+        config.playlists.len(),
+        config.interval_minutes
+    );
+    println!("同步模式啟動，下載目錄: {:?}", download_directory);
+
+    let client = Client::new();
+    loop {
+        match sync_once(&client, &config, &download_directory, debug_mode).await {
+            Ok(summary) => {
+                let message = format!(
+                    "同步完成：{} 個播放清單，{} 首新曲目，匹配 {} 首，下載 {} 個，已存在 {} 個，需要確認 {} 首，失敗 {} 個",
+                    summary.playlists,
+                    summary.new_tracks,
+                    summary.matched,
+                    summary.downloaded,
+                    summary.already_downloaded,
+                    summary.needs_review,
+                    summary.failures.len()
+                );
+                info!("{}", message);
+                println!("{}", message);
+                // 沒有新曲目也沒有錯誤時不發送，避免每次檢查都通知
+                if summary.new_tracks > 0 || !summary.failures.is_empty() {
+                    notify_batch_completed(summary.report()).await;
+                }
+            }
+            Err(e) => {
+                error!("同步失敗: {:?}", e);
+                println!("同步失敗: {}", e);
+                notify_batch_completed(BatchReport {
+                    title: "播放清單同步失敗".to_string(),
+                    succeeded: 0,
+                    failures: vec![e.to_string()],
+                    fields: Vec::new(),
+                })
+                .await;
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn sync_once(
+    client: &Client,
+    config: &SyncConfig,
+    download_directory: &Path,
+    debug_mode: bool,
+) -> Result<SyncSummary> {
+    let spotify_token = get_access_token(client, debug_mode)
+        .await
+        .map_err(|e| anyhow!("無法取得 Spotify token: {}", e))?;
+    let osu_token = get_osu_token(client, debug_mode)
+        .await
+        .map_err(|e| anyhow!("無法取得 osu! token: {}", e))?;
+
+    let mut state: SyncState = load_config(STATE_FILE).unwrap_or_default();
+    let mut summary = SyncSummary::default();
+    let min_score = match_options().auto_min_score;
+
+    for entry in &config.playlists {
+        let playlist_id = playlist_id(entry);
+        let tracks = match get_public_playlist_tracks(
+            client,
+            &playlist_id,
+            &spotify_token,
+            debug_mode,
+        )
+        .await
+        {
+            Ok(tracks) => tracks,
+            Err(e) => {
+                error!("讀取播放清單 {} 失敗: {:?}", playlist_id, e);
+                summary
+                    .failures
+                    .push(format!("讀取播放清單 {} 失敗: {}", playlist_id, e));
+                continue;
+            }
+        };
+        summary.playlists += 1;
+
+        let seen = state.seen_tracks.entry(playlist_id.clone()).or_default();
+        let new_tracks: Vec<&Track> = tracks
+            .iter()
+            .filter(|track| !seen.contains(&track_key(track)))
+            .collect();
+        info!(
+            "播放清單 {}：共 {} 首，新曲目 {} 首",
+            playlist_id,
+            tracks.len(),
+            new_tracks.len()
+        );
+        summary.new_tracks += new_tracks.len();
+
+        for track in new_tracks {
+            let artist = artist_names(track);
+            // 使用者確認過的配對直接使用，不重新搜尋與評分
+            let remembered = track
+                .external_urls
+                .get("spotify")
+                .and_then(|url| spotify_track_id(url))
+                .and_then(|track_id| confirmed_beatmapset(&track_id));
+            let beatmapset_id = match remembered {
+                Some(beatmapset_id) => {
+                    info!(
+                        "{} - {} 使用已確認的譜面 {}",
+                        artist, track.name, beatmapset_id
+                    );
+                    beatmapset_id
+                }
+                None => {
+                    let beatmapsets = match search_beatmapsets_normalized(
+                        client,
+                        &osu_token,
+                        &artist,
+                        &track.name,
+                        debug_mode,
+                    )
+                    .await
+                    {
+                        Ok(beatmapsets) => beatmapsets,
+                        Err(e) => {
+                            // 搜尋失敗的曲目不標記為已處理，下次再試
+                            error!("搜尋 {} - {} 失敗: {:?}", artist, track.name, e);
+                            summary
+                                .failures
+                                .push(format!("搜尋 {} - {} 失敗", artist, track.name));
+                            continue;
+                        }
+                    };
+
+                    let review_track = ReviewTrack {
+                        spotify_track_id: track
+                            .external_urls
+                            .get("spotify")
+                            .and_then(|url| spotify_track_id(url)),
+                        artist: artist.clone(),
+                        title: track.name.clone(),
+                        duration_ms: track.duration_ms,
+                        preview_url: track.preview_url.clone(),
+                    };
+                    let rejected_key = review_track.key();
+                    // 使用者拒絕過的譜面不再列入候選
+                    match rank_beatmapsets(&artist, &track.name, track.duration_ms, beatmapsets)
+                        .into_iter()
+                        .find(|scored| !is_rejected(&rejected_key, scored.beatmapset.id))
+                    {
+                        Some(best) if best.score >= min_score => {
+                            info!(
+                                "{} - {} 匹配到譜面 {} ({:.0}%)",
+                                artist,
+                                track.name,
+                                best.beatmapset.id,
+                                best.score * 100.0
+                            );
+                            best.beatmapset.id
+                        }
+                        Some(best) => {
+                            // 低於門檻的配對不下載，加入待確認列表讓使用者在圖形介面中確認
+                            info!(
+                                "{} - {} 最佳譜面 {} 只有 {:.0}%，加入待確認",
+                                artist,
+                                track.name,
+                                best.beatmapset.id,
+                                best.score * 100.0
+                            );
+                            add_review_item(ReviewItem {
+                                track: review_track,
+                                candidate: ReviewCandidate::from(&best),
+                                score: best.score,
+                                source: "同步模式".to_string(),
+                                added_at: Local::now(),
+                            });
+                            summary.needs_review += 1;
+                            seen.insert(track_key(track));
+                            continue;
+                        }
+                        None => {
+                            info!("{} - {} 找不到譜面", artist, track.name);
+                            seen.insert(track_key(track));
+                            continue;
+                        }
+                    }
+                }
+            };
+            summary.matched += 1;
+
+            if is_beatmap_downloaded(download_directory, beatmapset_id) {
+                summary.already_downloaded += 1;
+                seen.insert(track_key(track));
+                continue;
+            }
+            if let Some(available_mb) = low_disk_space(download_directory) {
+                warn!("磁碟剩餘空間 {} MB 不足，停止本次同步的下載", available_mb);
+                save_config(STATE_FILE, &state)?;
+                return Ok(summary);
+            }
+
+            match download_beatmap(beatmapset_id, download_directory, |_| {}).await {
+                Ok(_) => {
+                    info!(
+                        "已下載 {} - {} 的譜面 {}",
+                        artist, track.name, beatmapset_id
+                    );
+                    summary.downloaded += 1;
+                    seen.insert(track_key(track));
+                }
+                Err(e) => {
+                    error!("下載譜面 {} 失敗: {:?}", beatmapset_id, e);
+                    summary.failures.push(format!(
+                        "下載 {} - {} (#{}) 失敗",
+                        artist, track.name, beatmapset_id
+                    ));
+                }
+            }
+        }
+    }
+
+    save_config(STATE_FILE, &state)?;
+    Ok(summary)
+}
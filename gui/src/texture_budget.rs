@@ -0,0 +1,168 @@
+// 標準庫導入
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::RwLock;
+
+// 第三方庫導入
+use egui::TextureHandle;
+use lazy_static::lazy_static;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "texture_budget_options.json";
+pub const DEFAULT_TEXTURE_BUDGET_MB: u32 = 256;
+pub const TEXTURE_BUDGET_MB_RANGE: (u32, u32) = (64, 2048);
+
+fn default_budget_mb() -> u32 {
+    DEFAULT_TEXTURE_BUDGET_MB
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TextureBudgetOptions {
+    // 封面紋理可使用的記憶體上限，超過時淘汰最久沒顯示的封面
+    #[serde(default = "default_budget_mb")]
+    pub budget_mb: u32,
+}
+
+impl Default for TextureBudgetOptions {
+    fn default() -> Self {
+        Self {
+            budget_mb: DEFAULT_TEXTURE_BUDGET_MB,
+        }
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<TextureBudgetOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> TextureBudgetOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn texture_budget_options() -> TextureBudgetOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_texture_budget_options(options: TextureBudgetOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存紋理記憶體選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+// 估計紋理佔用的記憶體：RGBA 每像素 4 位元組，不含 mipmap 與驅動額外配置
+pub fn texture_bytes(texture: &TextureHandle) -> usize {
+    let [width, height] = texture.size();
+    width * height * 4
+}
+
+struct BudgetEntry {
+    bytes: usize,
+    last_visible_frame: u64,
+}
+
+// 記錄每張封面的大小與最後一次顯示的幀，超出上限時依 LRU 決定要淘汰的封面
+// 紋理本身仍存放在各自的快取中，這裡只負責記帳
+pub struct TextureBudget<K> {
+    entries: HashMap<K, BudgetEntry>,
+    used_bytes: usize,
+    frame: u64,
+}
+
+impl<K: Hash + Eq + Clone> TextureBudget<K> {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            used_bytes: 0,
+            frame: 0,
+        }
+    }
+
+    // 每一幀開始時呼叫
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    // 新載入的封面視為本幀可見，避免還沒畫出來就被淘汰
+    pub fn insert(&mut self, key: K, bytes: usize) {
+        let frame = self.frame;
+        if let Some(old) = self.entries.insert(
+            key,
+            BudgetEntry {
+                bytes,
+                last_visible_frame: frame,
+            },
+        ) {
+            self.used_bytes -= old.bytes;
+        }
+        self.used_bytes += bytes;
+    }
+
+    // 封面畫在畫面上時呼叫
+    pub fn touch(&mut self, key: &K) {
+        let frame = self.frame;
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.last_visible_frame = frame;
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&K) -> bool) {
+        let used_bytes = &mut self.used_bytes;
+        self.entries.retain(|key, entry| {
+            let kept = keep(key);
+            if !kept {
+                *used_bytes -= entry.bytes;
+            }
+            kept
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.used_bytes = 0;
+    }
+
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+
+    // 超過上限時從最久沒顯示的封面開始移除，回傳被淘汰的鍵讓呼叫端釋放紋理
+    // 本幀仍可見的封面不會被淘汰，因此使用量可能暫時超過上限
+    pub fn evict(&mut self, limit_bytes: usize) -> Vec<K> {
+        if self.used_bytes <= limit_bytes {
+            return Vec::new();
+        }
+        let frame = self.frame;
+        let mut candidates: Vec<(K, u64)> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.last_visible_frame < frame)
+            .map(|(key, entry)| (key.clone(), entry.last_visible_frame))
+            .collect();
+        candidates.sort_by_key(|(_, last_visible_frame)| *last_visible_frame);
+
+        let mut evicted = Vec::new();
+        for (key, _) in candidates {
+            if self.used_bytes <= limit_bytes {
+                break;
+            }
+            if let Some(entry) = self.entries.remove(&key) {
+                self.used_bytes -= entry.bytes;
+                evicted.push(key);
+            }
+        }
+        if !evicted.is_empty() {
+            debug!(
+                "淘汰 {} 張封面紋理，目前使用 {} 位元組",
+                evicted.len(),
+                self.used_bytes
+            );
+        }
+        evicted
+    }
+}
@@ -0,0 +1,137 @@
+// 標準庫導入
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+// 第三方庫導入
+use anyhow::Result;
+use image::DynamicImage;
+use lazy_static::lazy_static;
+use reqwest::Client;
+use tokio::sync::{Notify, Semaphore};
+
+// 畫面外預先載入的距離（像素），讓捲動時封面能提早開始下載
+pub const PREFETCH_MARGIN: f32 = 200.0;
+// 列表中的封面以 100px 高顯示，保留兩倍大小給高 DPI 螢幕
+pub const THUMBNAIL_SIZE: u32 = 200;
+// osu! 封面、Spotify 封面與播放清單封面共用的同時下載數上限
+pub const COVER_FETCH_CONCURRENCY: usize = 6;
+
+lazy_static! {
+    static ref COVER_FETCH_PERMITS: Semaphore = Semaphore::new(COVER_FETCH_CONCURRENCY);
+}
+
+// 下載並解碼封面圖片，所有封面下載共用同一組併發上限
+pub async fn fetch_cover_image(
+    client: &Client,
+    url: &str,
+    timeout: Duration,
+) -> Result<DynamicImage> {
+    let bytes = {
+        let _permit = COVER_FETCH_PERMITS.acquire().await?;
+        tokio::time::timeout(timeout, client.get(url).send())
+            .await??
+            .error_for_status()?
+            .bytes()
+            .await?
+    };
+    Ok(image::load_from_memory(&bytes)?)
+}
+
+// 上傳到 GPU 前將圖片縮小到指定高度，寬度依比例縮放，較小的圖片保持原樣
+pub fn downscale_image(image: DynamicImage, max_height: u32) -> DynamicImage {
+    if image.height() <= max_height {
+        return image;
+    }
+    let width = (image.width() as u64 * max_height as u64 / image.height() as u64).max(1) as u32;
+    image.thumbnail(width, max_height)
+}
+
+struct QueuedTexture {
+    index: usize,
+    last_seen_frame: u64,
+}
+
+// 封面紋理的載入佇列
+// 每一幀由 UI 重新登記可見列的封面，只有最近一幀仍在畫面內的請求會被載入，
+// 捲出畫面的請求會直接取消
+pub struct TextureLoadQueue {
+    entries: HashMap<String, QueuedTexture>,
+    // 已取出、正在下載的網址，避免 UI 每幀重新登記造成重複下載
+    in_flight: HashSet<String>,
+    frame: u64,
+    notify: Arc<Notify>,
+}
+
+impl TextureLoadQueue {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            in_flight: HashSet::new(),
+            frame: 0,
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    // 有新的請求時會被喚醒，讓載入迴圈不必輪詢
+    pub fn notifier(&self) -> Arc<Notify> {
+        self.notify.clone()
+    }
+
+    // 每一幀開始時呼叫，之後登記的請求屬於新的一幀
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    // 登記封面請求，visible 為 false 時取消尚未開始的載入
+    pub fn request(&mut self, index: usize, url: &str, visible: bool) {
+        if !visible {
+            self.entries.remove(url);
+            return;
+        }
+        if self.in_flight.contains(url) {
+            return;
+        }
+        let frame = self.frame;
+        match self.entries.get_mut(url) {
+            Some(entry) => {
+                entry.index = index;
+                entry.last_seen_frame = frame;
+            }
+            None => {
+                self.entries.insert(
+                    url.to_string(),
+                    QueuedTexture {
+                        index,
+                        last_seen_frame: frame,
+                    },
+                );
+                self.notify.notify_one();
+            }
+        }
+    }
+
+    // 取出下一個要載入的網址：先移除上一幀之後沒有再出現的請求，再依列表順序取最前面的
+    pub fn pop(&mut self) -> Option<String> {
+        let frame = self.frame;
+        self.entries
+            .retain(|_, entry| entry.last_seen_frame + 1 >= frame);
+        let url = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.index)
+            .map(|(url, _)| url.clone())?;
+        self.entries.remove(&url);
+        self.in_flight.insert(url.clone());
+        Some(url)
+    }
+
+    // 下載結束（成功或失敗）後呼叫
+    pub fn finish(&mut self, url: &str) {
+        self.in_flight.remove(url);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
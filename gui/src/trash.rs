@@ -0,0 +1,278 @@
+// 標準庫導入
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::download_history::record_download;
+use crate::download_options::unique_path;
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "trash_options.json";
+const TRASH_FILE: &str = "trash.json";
+// 下載目錄下的子資料夾，名稱不以數字開頭，不會出現在已下載列表
+const TRASH_DIR_NAME: &str = ".trash";
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+// 刪除後可以復原的時間
+pub const UNDO_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrashOptions {
+    // 關閉時直接刪除檔案
+    pub enabled: bool,
+    // 超過天數的項目在啟動時永久刪除，0 表示不自動清除
+    pub retention_days: u64,
+}
+
+impl Default for TrashOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: 30,
+        }
+    }
+}
+
+// 資源回收筒中的一個 .osz 檔或資料夾
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrashedItem {
+    pub file_name: String,
+    // 在回收筒資料夾中的名稱，同名檔案會加上編號
+    pub trashed_name: String,
+    pub download_directory: PathBuf,
+    pub beatmapset_id: Option<i32>,
+    // Unix 時間（秒）
+    pub deleted_at: u64,
+}
+
+impl TrashedItem {
+    fn trashed_path(&self) -> PathBuf {
+        trash_dir(&self.download_directory).join(&self.trashed_name)
+    }
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<TrashOptions> =
+        RwLock::new(load_config(OPTIONS_FILE).unwrap_or_default());
+    static ref TRASH: RwLock<Vec<TrashedItem>> =
+        RwLock::new(load_config(TRASH_FILE).unwrap_or_default());
+}
+
+pub fn trash_options() -> TrashOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_trash_options(options: TrashOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存資源回收筒選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+fn save_trash(items: &[TrashedItem]) {
+    if let Err(e) = save_config(TRASH_FILE, &items) {
+        error!("保存資源回收筒清單失敗: {:?}", e);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn trash_dir(download_directory: &Path) -> PathBuf {
+    download_directory.join(TRASH_DIR_NAME)
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+fn leading_id(name: &str) -> Option<i32> {
+    name.split_whitespace().next()?.parse().ok()
+}
+
+// 啟用資源回收筒時移到回收筒並回傳項目，否則直接刪除
+pub fn remove_entry(download_directory: &Path, file_name: &str) -> io::Result<Option<TrashedItem>> {
+    let path = download_directory.join(file_name);
+    if !trash_options().enabled {
+        remove_path(&path)?;
+        return Ok(None);
+    }
+
+    let dir = trash_dir(download_directory);
+    fs::create_dir_all(&dir)?;
+    let trashed_path = unique_path(&dir, file_name);
+    fs::rename(&path, &trashed_path)?;
+
+    let item = TrashedItem {
+        file_name: file_name.to_string(),
+        trashed_name: trashed_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file_name.to_string()),
+        download_directory: download_directory.to_path_buf(),
+        beatmapset_id: leading_id(file_name),
+        deleted_at: now_secs(),
+    };
+    let mut trash = TRASH.write().unwrap();
+    trash.push(item.clone());
+    save_trash(&trash);
+    Ok(Some(item))
+}
+
+// 依刪除時間由新到舊排序
+pub fn trash_items() -> Vec<TrashedItem> {
+    let mut items = TRASH.read().unwrap().clone();
+    items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    items
+}
+
+pub fn trash_size() -> u64 {
+    fn entry_size(path: &Path) -> u64 {
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => fs::read_dir(path)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|entry| entry_size(&entry.path()))
+                        .sum()
+                })
+                .unwrap_or_default(),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        }
+    }
+    TRASH
+        .read()
+        .unwrap()
+        .iter()
+        .map(|item| entry_size(&item.trashed_path()))
+        .sum()
+}
+
+// 移回原本的下載目錄，原位置已有同名檔案時加上編號，回傳成功復原的數量
+pub fn restore_items(items: &[TrashedItem]) -> usize {
+    let mut trash = TRASH.write().unwrap();
+    let mut restored = 0;
+    for item in items {
+        let target = unique_path(&item.download_directory, &item.file_name);
+        match fs::rename(item.trashed_path(), &target) {
+            Ok(()) => {
+                trash.retain(|trashed| trashed != item);
+                if let Some(beatmapset_id) = item.beatmapset_id {
+                    let file_name = target
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| item.file_name.clone());
+                    record_download(beatmapset_id, &file_name);
+                }
+                restored += 1;
+            }
+            Err(e) => error!("復原 {} 失敗: {:?}", item.file_name, e),
+        }
+    }
+    save_trash(&trash);
+    info!("已從資源回收筒復原 {} / {} 個圖譜", restored, items.len());
+    restored
+}
+
+// 永久刪除，檔案已不存在時也從清單移除
+pub fn purge_items(items: &[TrashedItem]) {
+    let mut trash = TRASH.write().unwrap();
+    for item in items {
+        let path = item.trashed_path();
+        if path.exists() {
+            if let Err(e) = remove_path(&path) {
+                error!("永久刪除 {:?} 失敗: {:?}", path, e);
+                continue;
+            }
+        }
+        trash.retain(|trashed| trashed != item);
+    }
+    save_trash(&trash);
+}
+
+pub fn empty_trash() {
+    purge_items(&trash_items());
+}
+
+// 啟動時清除超過保留天數的項目
+pub fn purge_expired_trash() {
+    let retention_days = trash_options().retention_days;
+    if retention_days == 0 {
+        return;
+    }
+    let now = now_secs();
+    let expired: Vec<TrashedItem> = trash_items()
+        .into_iter()
+        .filter(|item| now.saturating_sub(item.deleted_at) / SECS_PER_DAY >= retention_days)
+        .collect();
+    if !expired.is_empty() {
+        info!(
+            "清除 {} 個超過 {} 天的回收筒項目",
+            expired.len(),
+            retention_days
+        );
+        purge_items(&expired);
+    }
+}
+
+// 刪除後在畫面下方顯示的「復原」提示
+pub struct UndoToast {
+    items: Vec<TrashedItem>,
+    shown_at: Instant,
+}
+
+impl UndoToast {
+    pub fn new(items: Vec<TrashedItem>) -> Self {
+        Self {
+            items,
+            shown_at: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.shown_at.elapsed() >= UNDO_TIMEOUT
+    }
+
+    // 按下復原時回傳 true
+    pub fn render(&self, ctx: &egui::Context) -> bool {
+        let mut undo = false;
+        let message = match self.items.as_slice() {
+            [item] => format!("已將 {} 移到資源回收筒", item.file_name),
+            items => format!("已將 {} 個圖譜移到資源回收筒", items.len()),
+        };
+        egui::Area::new(egui::Id::new("undo_toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(message);
+                        undo = ui.button("復原").clicked();
+                    });
+                });
+            });
+        // 時間到時需要重繪才能關閉提示
+        ctx.request_repaint_after(UNDO_TIMEOUT.saturating_sub(self.shown_at.elapsed()));
+        undo
+    }
+
+    pub fn items(&self) -> &[TrashedItem] {
+        &self.items
+    }
+}
@@ -0,0 +1,160 @@
+// 標準庫導入
+use std::path::{Path, PathBuf};
+
+// 第三方庫導入
+use log::{debug, info};
+use reqwest::Client;
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::fs;
+
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const RELEASES_API_URL: &str =
+    "https://api.github.com/repos/smalljellyfish/Graduation_Topics/releases/latest";
+
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    #[error("請求錯誤: {0}")]
+    RequestError(#[from] reqwest::Error),
+    #[error("GitHub API 錯誤: {0}")]
+    ApiError(String),
+    #[error("IO 錯誤: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("找不到適用的安裝檔")]
+    NoAsset,
+    #[error("安裝檔名稱無效: {0}")]
+    InvalidAssetName(String),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ReleaseInfo {
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub body: Option<String>,
+    pub html_url: String,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+impl ReleaseInfo {
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches(['v', 'V'])
+    }
+
+    // 優先選擇安裝程式，其次是執行檔或壓縮檔
+    pub fn preferred_asset(&self) -> Option<&ReleaseAsset> {
+        ["msi", "exe", "zip"].iter().find_map(|ext| {
+            self.assets
+                .iter()
+                .find(|asset| asset.name.to_lowercase().ends_with(&format!(".{}", ext)))
+        })
+    }
+}
+
+// 應用程式更新下載狀態
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateDownloadStatus {
+    Idle,
+    Downloading,
+    Completed(PathBuf),
+    Failed(String),
+}
+
+fn parse_version(version: &str) -> Vec<u32> {
+    version
+        .trim_start_matches(['v', 'V'])
+        .split(|c| c == '.' || c == '-')
+        .map_while(|part| part.parse::<u32>().ok())
+        .collect()
+}
+
+pub fn is_newer_version(latest: &str, current: &str) -> bool {
+    let mut latest = parse_version(latest);
+    let mut current = parse_version(current);
+    let len = latest.len().max(current.len());
+    latest.resize(len, 0);
+    current.resize(len, 0);
+    latest > current
+}
+
+// 檢查 GitHub Releases 是否有比目前版本更新的發布，沒有則回傳 None
+pub async fn check_latest_release(
+    client: &Client,
+    debug_mode: bool,
+) -> Result<Option<ReleaseInfo>, UpdateError> {
+    if debug_mode {
+        debug!("檢查應用程式更新，目前版本: {}", CURRENT_VERSION);
+    }
+
+    let response = client
+        .get(RELEASES_API_URL)
+        .header("User-Agent", format!("SongSearch/{}", CURRENT_VERSION))
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(UpdateError::ApiError(format!(
+            "狀態碼: {}",
+            response.status()
+        )));
+    }
+
+    let release: ReleaseInfo = response.json().await?;
+    if debug_mode {
+        debug!("最新發布版本: {}", release.tag_name);
+    }
+
+    if is_newer_version(release.version(), CURRENT_VERSION) {
+        info!(
+            "發現新版本: {} (目前 {})",
+            release.version(),
+            CURRENT_VERSION
+        );
+        Ok(Some(release))
+    } else {
+        Ok(None)
+    }
+}
+
+// 下載發布的安裝檔到指定目錄
+pub async fn download_release(
+    client: &Client,
+    release: &ReleaseInfo,
+    download_directory: &Path,
+) -> Result<PathBuf, UpdateError> {
+    let asset = release.preferred_asset().ok_or(UpdateError::NoAsset)?;
+    // 名稱來自 GitHub 回應，只取檔名部分，避免寫到下載目錄之外
+    let file_name = match Path::new(&asset.name).file_name() {
+        Some(file_name) => file_name.to_owned(),
+        None => return Err(UpdateError::InvalidAssetName(asset.name.clone())),
+    };
+    info!("開始下載更新檔案: {}", asset.name);
+
+    let response = client
+        .get(&asset.browser_download_url)
+        .header("User-Agent", format!("SongSearch/{}", CURRENT_VERSION))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(UpdateError::ApiError(format!(
+            "下載失敗，狀態碼: {}",
+            response.status()
+        )));
+    }
+
+    let content = response.bytes().await?;
+    fs::create_dir_all(download_directory).await?;
+    let file_path = download_directory.join(file_name);
+    fs::write(&file_path, &content).await?;
+
+    info!("更新檔案已下載至: {:?}", file_path);
+    Ok(file_path)
+}
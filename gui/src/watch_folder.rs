@@ -0,0 +1,224 @@
+// 標準庫導入
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// 本地模組導入
+use crate::download_history::record_download;
+use crate::download_options::unique_path;
+use crate::osu::is_beatmap_downloaded;
+use lib::{load_config, save_config};
+
+const OPTIONS_FILE: &str = "watch_folder.json";
+// 瀏覽器寫入檔案需要時間，檔案大小連續兩次相同才視為下載完成
+const STABLE_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_STABLE_CHECKS: u32 = 120;
+
+#[derive(Error, Debug)]
+pub enum WatchFolderError {
+    #[error("IO 錯誤: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("監看資料夾錯誤: {0}")]
+    WatchError(#[from] notify::Error),
+    #[error("檔案未在時間內寫入完成")]
+    NotStable,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ImportMode {
+    #[default]
+    Move,
+    Copy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WatchFolderOptions {
+    pub enabled: bool,
+    // 要監看的資料夾，例如瀏覽器的下載資料夾
+    pub folder: Option<PathBuf>,
+    // 匯入的目的地，未設定時使用圖譜下載目錄
+    pub target_folder: Option<PathBuf>,
+    pub mode: ImportMode,
+}
+
+lazy_static! {
+    static ref OPTIONS: RwLock<WatchFolderOptions> = RwLock::new(load_options());
+}
+
+fn load_options() -> WatchFolderOptions {
+    load_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn watch_folder_options() -> WatchFolderOptions {
+    OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_watch_folder_options(options: WatchFolderOptions) {
+    let result = save_config(OPTIONS_FILE, &options);
+    if let Err(e) = result {
+        error!("保存監看資料夾選項失敗: {:?}", e);
+    }
+    *OPTIONS.write().unwrap() = options;
+}
+
+fn is_osz(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension.eq_ignore_ascii_case("osz"))
+}
+
+fn leading_id(file_name: &str) -> Option<i32> {
+    file_name.split_whitespace().next()?.parse().ok()
+}
+
+// 等待檔案大小不再變化
+fn wait_until_stable(path: &Path) -> Result<(), WatchFolderError> {
+    let mut last_size = None;
+    for _ in 0..MAX_STABLE_CHECKS {
+        let size = fs::metadata(path)?.len();
+        if size > 0 && last_size == Some(size) {
+            return Ok(());
+        }
+        last_size = Some(size);
+        thread::sleep(STABLE_CHECK_INTERVAL);
+    }
+    Err(WatchFolderError::NotStable)
+}
+
+// 將 .osz 檔搬移或複製到目的地並加入下載紀錄，已下載過的譜面會略過
+// 回傳匯入後的檔名
+pub fn import_osz(
+    source: &Path,
+    target_directory: &Path,
+    mode: ImportMode,
+) -> Result<Option<String>, WatchFolderError> {
+    let file_name = match source.file_name().and_then(|name| name.to_str()) {
+        Some(file_name) => file_name.to_string(),
+        None => return Ok(None),
+    };
+    let beatmapset_id = leading_id(&file_name);
+    if let Some(beatmapset_id) = beatmapset_id {
+        if is_beatmap_downloaded(target_directory, beatmapset_id) {
+            info!("譜面 {} 已存在，略過匯入 {:?}", beatmapset_id, source);
+            return Ok(None);
+        }
+    }
+
+    // 複製模式下來源檔仍在，避免之後的事件重複匯入同一個檔案
+    if mode == ImportMode::Copy && target_directory.join(&file_name).exists() {
+        return Ok(None);
+    }
+
+    fs::create_dir_all(target_directory)?;
+    let destination = unique_path(target_directory, &file_name);
+    match mode {
+        ImportMode::Copy => {
+            fs::copy(source, &destination)?;
+        }
+        ImportMode::Move => {
+            // 跨磁碟時無法直接 rename，改為複製後刪除
+            if fs::rename(source, &destination).is_err() {
+                fs::copy(source, &destination)?;
+                fs::remove_file(source)?;
+            }
+        }
+    }
+
+    let imported_name = destination
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or(file_name);
+    match beatmapset_id {
+        Some(beatmapset_id) => record_download(beatmapset_id, &imported_name),
+        None => warn!("無法從檔名 {} 取得譜面 ID，未加入下載紀錄", imported_name),
+    }
+    info!("已從監看資料夾匯入 {:?} -> {:?}", source, destination);
+    Ok(Some(imported_name))
+}
+
+// 持有 watcher，drop 時停止監看
+pub struct WatchFolder {
+    // 監看中的 watcher 與資料夾
+    watcher: Option<(RecommendedWatcher, PathBuf)>,
+}
+
+impl WatchFolder {
+    pub fn new() -> Self {
+        Self { watcher: None }
+    }
+
+    pub fn watching(&self) -> Option<&Path> {
+        self.watcher.as_ref().map(|(_, folder)| folder.as_path())
+    }
+
+    // 依目前的選項重新開始監看，選項或下載目錄變更後呼叫
+    pub fn restart(
+        &mut self,
+        ctx: &egui::Context,
+        download_directory: &Path,
+    ) -> Result<(), WatchFolderError> {
+        self.watcher = None;
+
+        let options = watch_folder_options();
+        let folder = match (options.enabled, options.folder.clone()) {
+            (true, Some(folder)) => folder,
+            _ => return Ok(()),
+        };
+        let target_directory = options
+            .target_folder
+            .clone()
+            .unwrap_or_else(|| download_directory.to_path_buf());
+        if folder == target_directory {
+            warn!("監看資料夾與匯入目的地相同，不啟動監看");
+            return Ok(());
+        }
+
+        // 同一個檔案會觸發多個事件，處理中的檔案不重複處理
+        let in_progress: Arc<Mutex<HashSet<PathBuf>>> = Arc::new(Mutex::new(HashSet::new()));
+        let ctx = ctx.clone();
+        let mode = options.mode;
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            let event = match result {
+                Ok(event) => event,
+                Err(e) => {
+                    error!("監看資料夾事件錯誤: {:?}", e);
+                    return;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                return;
+            }
+            for path in event.paths.into_iter().filter(|path| is_osz(path)) {
+                if !path.is_file() || !in_progress.lock().unwrap().insert(path.clone()) {
+                    continue;
+                }
+                let in_progress = in_progress.clone();
+                let target_directory = target_directory.clone();
+                let ctx = ctx.clone();
+                thread::spawn(move || {
+                    let result = wait_until_stable(&path)
+                        .and_then(|_| import_osz(&path, &target_directory, mode));
+                    if let Err(e) = result {
+                        error!("匯入 {:?} 失敗: {:?}", path, e);
+                    }
+                    in_progress.lock().unwrap().remove(&path);
+                    ctx.request_repaint();
+                });
+            }
+        })?;
+        watcher.watch(&folder, RecursiveMode::NonRecursive)?;
+
+        info!("開始監看資料夾: {:?}", folder);
+        self.watcher = Some((watcher, folder));
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+// 以歌手與歌名搜尋 osu! 譜面，列出匹配分數最高的結果，加上 --download 時下載最可信的譜面
+//
+// OSU_CLIENT_ID=... OSU_CLIENT_SECRET=... cargo run -p osu_spotify_core --example match_track -- "YOASOBI" "夜に駆ける" --download
+
+// 標準庫導入
+use std::env;
+use std::process;
+
+// 第三方庫導入
+use osu_spotify_core::{DownloadJob, OsuClient, SearchRequest, VersionPreference};
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (artist, title) = match (args.first(), args.get(1)) {
+        (Some(artist), Some(title)) => (artist.clone(), title.clone()),
+        _ => {
+            eprintln!("用法: match_track <歌手> <歌名> [--download]");
+            process::exit(2);
+        }
+    };
+    let download = args.iter().any(|arg| arg == "--download");
+
+    let (client_id, client_secret) =
+        match (env::var("OSU_CLIENT_ID"), env::var("OSU_CLIENT_SECRET")) {
+            (Ok(id), Ok(secret)) => (id, secret),
+            _ => {
+                eprintln!("請設定 OSU_CLIENT_ID 與 OSU_CLIENT_SECRET");
+                process::exit(2);
+            }
+        };
+
+    let osu = match OsuClient::authorize(&client_id, &client_secret).await {
+        Ok(osu) => osu,
+        Err(e) => {
+            eprintln!("osu! 授權失敗: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let request = SearchRequest::new(artist, title)
+        .with_version_preference(VersionPreference::FullVersion)
+        .with_romanize_kana(true);
+    let matches = match osu.search_and_match(&request).await {
+        Ok(matches) => matches,
+        Err(e) => {
+            eprintln!("搜尋失敗: {}", e);
+            process::exit(1);
+        }
+    };
+
+    for result in matches.iter().take(5) {
+        let beatmapset = &result.candidate;
+        println!(
+            "{:>3.0}%  {} - {} (by {})  {}",
+            result.score * 100.0,
+            beatmapset.artist,
+            beatmapset.title,
+            beatmapset.creator,
+            beatmapset.url()
+        );
+    }
+
+    if !download {
+        return;
+    }
+    match matches.first().filter(|best| best.is_confident()) {
+        Some(best) => match DownloadJob::new(best.candidate.id, "downloads").run().await {
+            Ok(path) => println!("已下載到 {}", path.display()),
+            Err(e) => eprintln!("下載失敗: {}", e),
+        },
+        None => println!("沒有足夠可信的譜面，略過下載"),
+    }
+}
@@ -0,0 +1,277 @@
+//! 從鏡像站下載譜面集，以及下載時使用的檔名處理。
+
+// 標準庫導入
+use std::path::{Path, PathBuf};
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{Client, RequestBuilder, Response};
+
+// 本地模組導入
+use crate::error::CoreError;
+
+/// 預設的譜面鏡像站，`{id}` 會替換為譜面集 ID。
+pub const DEFAULT_MIRROR: &str = "https://api.nerinyan.moe/d/{id}";
+/// 預設的檔名範本，可使用 `{id}`、`{artist}`、`{title}`。
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{id} {artist} - {title}.osz";
+// Windows 檔名長度上限為 255，保留空間給重複檔名的編號
+const MAX_FILENAME_LENGTH: usize = 200;
+
+lazy_static! {
+    // 鏡像站的檔名格式：123456 Artist - Title.osz
+    static ref MIRROR_FILENAME: Regex = Regex::new(r"^\d+\s+(.+?)\s+-\s+(.+?)\.osz$").unwrap();
+}
+
+/// 鏡像站回傳的檔名中可得到的譜面資訊。
+#[derive(Clone, Debug, PartialEq)]
+pub struct BeatmapsetNames {
+    pub artist: String,
+    pub title: String,
+}
+
+/// 下載一個譜面集 (.osz) 到指定資料夾。
+///
+/// 只需要下載時使用 [`run`](DownloadJob::run)；需要自訂請求標頭、記錄回應或回報進度時，
+/// 以 [`request`](DownloadJob::request) 建立請求後將回應交給 [`save`](DownloadJob::save)。
+#[derive(Clone, Debug, PartialEq)]
+pub struct DownloadJob {
+    pub beatmapset_id: i32,
+    pub directory: PathBuf,
+    /// 鏡像站網址範本，見 [`DEFAULT_MIRROR`]。
+    pub mirror: String,
+    /// 檔名範本，見 [`DEFAULT_FILENAME_TEMPLATE`]。
+    pub filename_template: String,
+}
+
+impl DownloadJob {
+    pub fn new(beatmapset_id: i32, directory: impl Into<PathBuf>) -> Self {
+        Self {
+            beatmapset_id,
+            directory: directory.into(),
+            mirror: DEFAULT_MIRROR.to_string(),
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+        }
+    }
+
+    pub fn with_mirror(mut self, mirror: impl Into<String>) -> Self {
+        self.mirror = mirror.into();
+        self
+    }
+
+    pub fn with_filename_template(mut self, template: impl Into<String>) -> Self {
+        self.filename_template = template.into();
+        self
+    }
+
+    pub fn url(&self) -> String {
+        self.mirror.replace("{id}", &self.beatmapset_id.to_string())
+    }
+
+    /// 建立下載請求，呼叫端可以再加上自己的標頭。
+    pub fn request(&self, client: &Client) -> RequestBuilder {
+        client
+            .get(self.url())
+            .header("Accept", "application/x-osu-beatmap-archive")
+    }
+
+    /// 存檔使用的檔名：鏡像站的檔名能解析出歌手與歌名時套用檔名範本，否則使用清理後的鏡像站檔名，
+    /// 沒有時為 `{id}.osz`。
+    pub fn file_name(&self, mirror_filename: Option<&str>) -> String {
+        let mirror_filename = match mirror_filename {
+            Some(name) => name.to_string(),
+            None => format!("{}.osz", self.beatmapset_id),
+        };
+        match parse_mirror_filename(&mirror_filename) {
+            Some(names) => format_filename(&self.filename_template, self.beatmapset_id, &names),
+            None => sanitize_filename(&mirror_filename),
+        }
+    }
+
+    /// 接收回應內容並寫入檔案，回傳檔案路徑。
+    ///
+    /// 每收到一段內容就以目前已接收的位元組數呼叫 `on_progress`；同名檔案已存在時加上 (2)、(3) 等編號。
+    pub async fn save(
+        &self,
+        mut response: Response,
+        mut on_progress: impl FnMut(u64),
+    ) -> Result<PathBuf, CoreError> {
+        if !response.status().is_success() {
+            return Err(CoreError::ApiError(format!(
+                "下載譜面 {} 失敗，狀態碼: {}",
+                self.beatmapset_id,
+                response.status()
+            )));
+        }
+
+        let mirror_filename = response
+            .headers()
+            .get("content-disposition")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split("filename=\"").nth(1))
+            .and_then(|value| value.strip_suffix('"'))
+            .map(str::to_string);
+        let filename = self.file_name(mirror_filename.as_deref());
+
+        let mut content =
+            Vec::with_capacity(response.content_length().unwrap_or_default() as usize);
+        while let Some(chunk) = response.chunk().await? {
+            content.extend_from_slice(&chunk);
+            on_progress(content.len() as u64);
+        }
+
+        tokio::fs::create_dir_all(&self.directory).await?;
+        let path = unique_path(&self.directory, &filename);
+        tokio::fs::write(&path, &content).await?;
+        Ok(path)
+    }
+
+    /// 下載並寫入檔案，回傳檔案路徑。
+    pub async fn run(&self) -> Result<PathBuf, CoreError> {
+        let client = Client::builder()
+            .redirect(reqwest::redirect::Policy::limited(10))
+            .build()?;
+        let response = self.request(&client).send().await?;
+        self.save(response, |_| {}).await
+    }
+}
+
+/// 從鏡像站的檔名取出歌手與歌名，格式不符時回傳 None。
+pub fn parse_mirror_filename(filename: &str) -> Option<BeatmapsetNames> {
+    let captures = MIRROR_FILENAME.captures(filename)?;
+    Some(BeatmapsetNames {
+        artist: captures[1].to_string(),
+        title: captures[2].to_string(),
+    })
+}
+
+/// 依範本產生檔名。
+///
+/// 是否已下載依檔名中的譜面 ID 判斷，範本沒有 `{id}` 時會自動加在開頭。
+pub fn format_filename(template: &str, beatmapset_id: i32, names: &BeatmapsetNames) -> String {
+    let template = if template.contains("{id}") {
+        template.to_string()
+    } else {
+        format!("{{id}} {}", template)
+    };
+    let name = template
+        .replace("{id}", &beatmapset_id.to_string())
+        .replace("{artist}", &names.artist)
+        .replace("{title}", &names.title);
+    let name = name.trim_end_matches(".osz").trim_end_matches(" - ");
+
+    let mut stem = sanitize_filename(name);
+    if stem.chars().count() > MAX_FILENAME_LENGTH {
+        stem = stem.chars().take(MAX_FILENAME_LENGTH).collect::<String>();
+        stem = stem.trim_end_matches(['.', ' ']).to_string();
+    }
+    if stem.is_empty() {
+        stem = beatmapset_id.to_string();
+    }
+    format!("{}.osz", stem)
+}
+
+/// 將 Windows 不允許的字元替換為底線，並移除結尾的句點與空白。
+pub fn sanitize_filename(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    sanitized
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', ' '])
+        .to_string()
+}
+
+/// 檔案已存在時在檔名後加上 (2)、(3) 等編號。
+pub fn unique_path(directory: &Path, filename: &str) -> PathBuf {
+    let path = directory.join(filename);
+    if !path.exists() {
+        return path;
+    }
+    let stem = filename.trim_end_matches(".osz");
+    (2..)
+        .map(|n| directory.join(format!("{} ({}).osz", stem, n)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 標準庫導入
+    use std::fs;
+    use std::process;
+
+    #[test]
+    fn sanitize_filename_replaces_reserved_characters() {
+        assert_eq!(sanitize_filename("a:b/c?*.osz"), "a_b_c__.osz");
+        assert_eq!(sanitize_filename("line\nbreak"), "line_break");
+    }
+
+    #[test]
+    fn sanitize_filename_collapses_whitespace_and_trims_trailing_dots() {
+        assert_eq!(
+            sanitize_filename("  Artist   -  Title . "),
+            "Artist - Title"
+        );
+    }
+
+    #[test]
+    fn format_filename_applies_template() {
+        let names = BeatmapsetNames {
+            artist: "YOASOBI".to_string(),
+            title: "Idol?".to_string(),
+        };
+        assert_eq!(
+            format_filename("{artist} - {title} [{id}]", 1, &names),
+            "YOASOBI - Idol_ [1].osz"
+        );
+        assert_eq!(format_filename("{title}", 1, &names), "1 Idol_.osz");
+    }
+
+    #[test]
+    fn file_name_uses_template_when_mirror_name_parses() {
+        let job = DownloadJob::new(123, "downloads").with_filename_template("{title} ({id})");
+        assert_eq!(
+            job.file_name(Some("123 YOASOBI - Idol.osz")),
+            "Idol (123).osz"
+        );
+        assert_eq!(job.file_name(Some("weird:name.osz")), "weird_name.osz");
+        assert_eq!(job.file_name(None), "123.osz");
+        assert_eq!(
+            DownloadJob::new(123, "downloads").file_name(Some("123 YOASOBI - Idol.osz")),
+            "123 YOASOBI - Idol.osz"
+        );
+    }
+
+    #[test]
+    fn unique_path_appends_number_when_file_exists() {
+        let directory = std::env::temp_dir().join(format!("osu_spotify_core_{}", process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        assert_eq!(
+            unique_path(&directory, "1 Song.osz"),
+            directory.join("1 Song.osz")
+        );
+
+        fs::write(directory.join("1 Song.osz"), b"").unwrap();
+        assert_eq!(
+            unique_path(&directory, "1 Song.osz"),
+            directory.join("1 Song (2).osz")
+        );
+
+        fs::write(directory.join("1 Song (2).osz"), b"").unwrap();
+        assert_eq!(
+            unique_path(&directory, "1 Song.osz"),
+            directory.join("1 Song (3).osz")
+        );
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
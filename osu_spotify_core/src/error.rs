@@ -0,0 +1,15 @@
+// 第三方庫導入
+use thiserror::Error;
+
+/// 搜尋、授權與下載時可能發生的錯誤。
+#[derive(Error, Debug)]
+pub enum CoreError {
+    #[error("請求錯誤: {0}")]
+    RequestError(#[from] reqwest::Error),
+    #[error("JSON 解析錯誤: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("IO 錯誤: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("osu! API 錯誤: {0}")]
+    ApiError(String),
+}
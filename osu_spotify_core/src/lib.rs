@@ -0,0 +1,51 @@
+//! Spotify 曲目與 osu! 譜面的配對、搜尋與下載。
+//!
+//! 圖形介面使用的配對、搜尋與下載邏輯都在這個 crate 中，機器人或腳本可以直接使用：
+//!
+//! - [`SearchRequest`]：要尋找譜面的曲目（歌手、歌名與長度）
+//! - [`MatchResult`]：候選譜面與 0.0 ~ 1.0 的匹配分數
+//! - [`OsuClient`]：以 osu! API 搜尋並配對譜面集
+//! - [`search_and_match`]：搭配自己的 osu! API 客戶端與實作 [`Candidate`] 的型別搜尋並配對
+//! - [`DownloadJob`]：從鏡像站下載譜面集到指定資料夾
+//!
+//! 公開的型別與函式遵循語意化版本，只在主版本號變更時修改。
+//!
+//! # 範例
+//!
+//! ```no_run
+//! use osu_spotify_core::{DownloadJob, OsuClient, SearchRequest};
+//!
+//! # async fn run() -> Result<(), osu_spotify_core::CoreError> {
+//! let osu = OsuClient::authorize("client_id", "client_secret").await?;
+//! let request = SearchRequest::new("YOASOBI", "夜に駆ける").with_duration_ms(261_000);
+//! let matches = osu.search_and_match(&request).await?;
+//! if let Some(best) = matches.first().filter(|best| best.is_confident()) {
+//!     let path = DownloadJob::new(best.candidate.id, "downloads")
+//!         .with_filename_template("{artist} - {title} ({id})")
+//!         .run()
+//!         .await?;
+//!     println!("已下載 {}", path.display());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 更完整的用法見 `examples/match_track.rs`。
+
+mod download;
+mod error;
+pub mod matching;
+mod osu;
+mod search;
+pub mod text;
+
+pub use download::{
+    format_filename, parse_mirror_filename, sanitize_filename, unique_path, BeatmapsetNames,
+    DownloadJob, DEFAULT_FILENAME_TEMPLATE, DEFAULT_MIRROR,
+};
+pub use error::CoreError;
+pub use matching::{
+    rank, Candidate, MatchResult, SearchRequest, VersionPreference, CONFIDENT_MATCH_SCORE,
+};
+pub use osu::{OsuBeatmap, OsuBeatmapset, OsuClient};
+pub use search::{search_and_match, search_candidates};
@@ -0,0 +1,272 @@
+//! 曲目與譜面集的匹配分數與排序。
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// 本地模組導入
+use crate::text::title_similarity;
+
+/// 分數達到此值即視為可信的匹配。
+pub const CONFIDENT_MATCH_SCORE: f32 = 0.8;
+/// 長度相差超過此秒數時，譜面可能是 TV Size 或剪輯版本。
+pub const DURATION_MISMATCH_SECS: i64 = 15;
+// 符合版本偏好的譜面在排序時加減的分數
+const VERSION_PREFERENCE_WEIGHT: f32 = 0.1;
+
+lazy_static! {
+    // TV Size、Short Ver.、Cut Ver.、Game Size 以及日文標示
+    static ref CUT_VERSION: Regex = Regex::new(
+        r"(?i)(tv\s*(size|ver|edit)|short\s*(ver|size|edit)|cut\s*(ver|edit)|game\s*(size|ver)|tvサイズ|ショート\s*ver)"
+    )
+    .unwrap();
+}
+
+/// 同一首歌有完整版與 TV Size 譜面時優先排在前面的版本。
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VersionPreference {
+    #[default]
+    NoPreference,
+    FullVersion,
+    TvSize,
+}
+
+/// 要尋找譜面的曲目。
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchRequest {
+    pub artist: String,
+    pub title: String,
+    /// Spotify 曲目長度，用來辨識剪輯版本；沒有時只以標題判斷。
+    pub duration_ms: Option<u64>,
+    pub version_preference: VersionPreference,
+    /// 歌手名稱含假名時，額外以羅馬拼音搜尋一次。
+    pub romanize_kana: bool,
+}
+
+impl SearchRequest {
+    pub fn new(artist: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            artist: artist.into(),
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_duration_ms(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = Some(duration_ms);
+        self
+    }
+
+    pub fn with_version_preference(mut self, preference: VersionPreference) -> Self {
+        self.version_preference = preference;
+        self
+    }
+
+    pub fn with_romanize_kana(mut self, romanize_kana: bool) -> Self {
+        self.romanize_kana = romanize_kana;
+        self
+    }
+}
+
+/// 可以參與配對的譜面集。
+///
+/// [`OsuBeatmapset`](crate::OsuBeatmapset) 已實作此 trait，使用自己的 osu! API 型別時也可以實作後交給 [`rank`]。
+pub trait Candidate {
+    /// 譜面集 ID，合併多個查詢的結果時用來去除重複。
+    fn id(&self) -> i32;
+    /// (歌手, 歌名) 的所有寫法，通常為羅馬拼音與原文。
+    fn name_variants(&self) -> Vec<(String, String)>;
+    /// 歌曲長度（秒），沒有難度資料時回傳 None。
+    fn length_secs(&self) -> Option<i32>;
+}
+
+/// 一個候選譜面集與它的匹配分數。
+#[derive(Clone, Debug)]
+pub struct MatchResult<C> {
+    pub candidate: C,
+    /// 0.0 ~ 1.0，不含版本偏好的調整。
+    pub score: f32,
+}
+
+impl<C> MatchResult<C> {
+    /// 分數是否達到 [`CONFIDENT_MATCH_SCORE`]。
+    pub fn is_confident(&self) -> bool {
+        self.score >= CONFIDENT_MATCH_SCORE
+    }
+}
+
+/// 計算兩組歌手與歌名的匹配分數，歌名權重較高。
+pub fn score_track(artist: &str, title: &str, other_artist: &str, other_title: &str) -> f32 {
+    let title_score = title_similarity(title, other_title);
+    if artist.trim().is_empty() {
+        return title_score;
+    }
+    let artist_score = title_similarity(artist, other_artist);
+    title_score * 0.6 + artist_score * 0.4
+}
+
+/// 與多種寫法（羅馬拼音、原文）比較，取最高分。
+pub fn score_names(artist: &str, title: &str, names: &[(String, String)]) -> f32 {
+    names
+        .iter()
+        .map(|(other_artist, other_title)| score_track(artist, title, other_artist, other_title))
+        .fold(0.0, f32::max)
+}
+
+/// 計算曲目與候選譜面集的匹配分數。
+pub fn score_candidate(artist: &str, title: &str, candidate: &impl Candidate) -> f32 {
+    score_names(artist, title, &candidate.name_variants())
+}
+
+/// 長度相差超過 [`DURATION_MISMATCH_SECS`] 時回傳 (曲目秒數, 譜面秒數)，任一方沒有長度時回傳 None。
+pub fn duration_difference(
+    duration_ms: Option<u64>,
+    candidate: &impl Candidate,
+) -> Option<(i64, i64)> {
+    let track_secs = (duration_ms? / 1000) as i64;
+    let beatmap_secs = candidate.length_secs()? as i64;
+    ((track_secs - beatmap_secs).abs() > DURATION_MISMATCH_SECS)
+        .then_some((track_secs, beatmap_secs))
+}
+
+/// 從標題或長度判斷譜面是否為 TV Size 等剪輯版本，譜面明顯較曲目短時也視為剪輯版本。
+pub fn is_cut_version(candidate: &impl Candidate, duration_ms: Option<u64>) -> bool {
+    candidate
+        .name_variants()
+        .iter()
+        .any(|(_, title)| CUT_VERSION.is_match(title))
+        || duration_difference(duration_ms, candidate)
+            .is_some_and(|(track_secs, beatmap_secs)| beatmap_secs < track_secs)
+}
+
+// 依版本偏好調整排序用的分數，不影響回傳的匹配分數
+fn preference_adjustment(request: &SearchRequest, candidate: &impl Candidate) -> f32 {
+    match request.version_preference {
+        VersionPreference::NoPreference => 0.0,
+        VersionPreference::FullVersion if is_cut_version(candidate, request.duration_ms) => {
+            -VERSION_PREFERENCE_WEIGHT
+        }
+        VersionPreference::TvSize if is_cut_version(candidate, request.duration_ms) => {
+            VERSION_PREFERENCE_WEIGHT
+        }
+        _ => 0.0,
+    }
+}
+
+/// 依匹配分數由高到低排序，並套用完整版 / TV Size 的偏好。
+pub fn rank<C: Candidate>(request: &SearchRequest, candidates: Vec<C>) -> Vec<MatchResult<C>> {
+    let mut scored: Vec<(f32, MatchResult<C>)> = candidates
+        .into_iter()
+        .map(|candidate| {
+            let score = score_candidate(&request.artist, &request.title, &candidate);
+            let rank_score = score + preference_adjustment(request, &candidate);
+            (rank_score, MatchResult { candidate, score })
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestBeatmapset {
+        id: i32,
+        artist: &'static str,
+        title: &'static str,
+        length_secs: Option<i32>,
+    }
+
+    impl Candidate for TestBeatmapset {
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn name_variants(&self) -> Vec<(String, String)> {
+            vec![(self.artist.to_string(), self.title.to_string())]
+        }
+
+        fn length_secs(&self) -> Option<i32> {
+            self.length_secs
+        }
+    }
+
+    fn beatmapset(title: &'static str, length_secs: i32) -> TestBeatmapset {
+        TestBeatmapset {
+            id: length_secs,
+            artist: "YOASOBI",
+            title,
+            length_secs: Some(length_secs),
+        }
+    }
+
+    #[test]
+    fn score_track_matches_romanized_title() {
+        let score = score_track("YOASOBI", "よるにかける", "YOASOBI", "Yoru ni Kakeru");
+        assert!(score >= CONFIDENT_MATCH_SCORE, "score = {}", score);
+    }
+
+    #[test]
+    fn score_track_without_artist_uses_title_only() {
+        assert_eq!(score_track("", "Idol", "Someone", "Idol"), 1.0);
+    }
+
+    #[test]
+    fn score_track_rejects_unrelated_track() {
+        assert_eq!(score_track("Artist", "Song", "Other", "Different"), 0.0);
+    }
+
+    #[test]
+    fn cut_version_detected_by_title_or_duration() {
+        assert!(is_cut_version(
+            &beatmapset("夜に駆ける (TV Size)", 261),
+            None
+        ));
+        assert!(is_cut_version(&beatmapset("夜に駆ける", 89), Some(261_000)));
+        assert!(!is_cut_version(
+            &beatmapset("夜に駆ける", 261),
+            Some(261_000)
+        ));
+    }
+
+    #[test]
+    fn rank_sorts_by_score() {
+        let request = SearchRequest::new("YOASOBI", "夜に駆ける");
+        let results = rank(
+            &request,
+            vec![beatmapset("群青", 250), beatmapset("夜に駆ける", 261)],
+        );
+        assert_eq!(results[0].candidate.title, "夜に駆ける");
+        assert!(results[0].is_confident());
+        assert!(!results[1].is_confident());
+    }
+
+    #[test]
+    fn rank_applies_version_preference() {
+        let candidates = || {
+            vec![
+                beatmapset("夜に駆ける (TV Size)", 89),
+                beatmapset("夜に駆ける", 261),
+            ]
+        };
+        let request = SearchRequest::new("YOASOBI", "夜に駆ける").with_duration_ms(261_000);
+
+        let full = rank(
+            &request
+                .clone()
+                .with_version_preference(VersionPreference::FullVersion),
+            candidates(),
+        );
+        assert_eq!(full[0].candidate.title, "夜に駆ける");
+
+        let tv_size = rank(
+            &request.with_version_preference(VersionPreference::TvSize),
+            candidates(),
+        );
+        assert_eq!(tv_size[0].candidate.title, "夜に駆ける (TV Size)");
+        // 偏好只影響排序，不改變回傳的分數
+        assert!(tv_size[0].score < tv_size[1].score);
+    }
+}
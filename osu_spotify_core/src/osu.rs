@@ -0,0 +1,153 @@
+// 第三方庫導入
+use reqwest::Client;
+use serde::Deserialize;
+
+// 本地模組導入
+use crate::error::CoreError;
+use crate::matching::{Candidate, MatchResult, SearchRequest};
+use crate::search::search_and_match;
+
+const TOKEN_URL: &str = "https://osu.ppy.sh/oauth/token";
+const SEARCH_URL: &str = "https://osu.ppy.sh/api/v2/beatmapsets/search";
+
+/// osu! API 回傳的難度。
+#[derive(Debug, Deserialize, Clone)]
+pub struct OsuBeatmap {
+    pub id: i32,
+    pub version: String,
+    pub mode: String,
+    pub difficulty_rating: f32,
+    /// 長度（秒）。
+    pub total_length: i32,
+}
+
+/// osu! API 回傳的譜面集，只保留配對需要的欄位。
+#[derive(Debug, Deserialize, Clone)]
+pub struct OsuBeatmapset {
+    pub id: i32,
+    pub artist: String,
+    pub title: String,
+    /// 原文（日文、韓文等）歌手與歌名，`artist` / `title` 通常是羅馬拼音。
+    #[serde(default)]
+    pub artist_unicode: Option<String>,
+    #[serde(default)]
+    pub title_unicode: Option<String>,
+    pub creator: String,
+    pub preview_url: Option<String>,
+    #[serde(default)]
+    pub beatmaps: Vec<OsuBeatmap>,
+}
+
+impl OsuBeatmapset {
+    pub fn url(&self) -> String {
+        format!("https://osu.ppy.sh/beatmapsets/{}", self.id)
+    }
+}
+
+impl Candidate for OsuBeatmapset {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name_variants(&self) -> Vec<(String, String)> {
+        let mut variants = vec![(self.artist.clone(), self.title.clone())];
+        let unicode = (
+            self.artist_unicode
+                .clone()
+                .filter(|artist| !artist.trim().is_empty())
+                .unwrap_or_else(|| self.artist.clone()),
+            self.title_unicode
+                .clone()
+                .filter(|title| !title.trim().is_empty())
+                .unwrap_or_else(|| self.title.clone()),
+        );
+        if unicode != variants[0] {
+            variants.push(unicode);
+        }
+        variants
+    }
+
+    fn length_secs(&self) -> Option<i32> {
+        self.beatmaps
+            .iter()
+            .map(|beatmap| beatmap.total_length)
+            .max()
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    beatmapsets: Vec<OsuBeatmapset>,
+}
+
+/// 以 client credentials 授權的 osu! API v2 客戶端。
+#[derive(Clone, Debug)]
+pub struct OsuClient {
+    client: Client,
+    access_token: String,
+}
+
+impl OsuClient {
+    /// 使用 osu! OAuth 應用程式的 client ID 與 secret 取得 token。
+    pub async fn authorize(client_id: &str, client_secret: &str) -> Result<Self, CoreError> {
+        let client = Client::new();
+        let params = [
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("grant_type", "client_credentials"),
+            ("scope", "public"),
+        ];
+        let response = client.post(TOKEN_URL).form(&params).send().await?;
+        if !response.status().is_success() {
+            return Err(CoreError::ApiError(format!(
+                "取得 token 失敗，狀態碼: {}",
+                response.status()
+            )));
+        }
+        let token: TokenResponse = response.json().await?;
+        Ok(Self::with_token(client, token.access_token))
+    }
+
+    /// 使用已取得的 token，例如與其他程式共用的 token。
+    pub fn with_token(client: Client, access_token: String) -> Self {
+        Self {
+            client,
+            access_token,
+        }
+    }
+
+    /// 以單一查詢字串搜尋譜面集，回傳第一頁結果。
+    pub async fn search(&self, query: &str) -> Result<Vec<OsuBeatmapset>, CoreError> {
+        let response = self
+            .client
+            .get(SEARCH_URL)
+            .query(&[("query", query)])
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(CoreError::ApiError(format!(
+                "搜尋失敗，狀態碼: {}",
+                response.status()
+            )));
+        }
+        let text = response.text().await?;
+        let search: SearchResponse = serde_json::from_str(&text)?;
+        Ok(search.beatmapsets)
+    }
+
+    /// 以 [`search_and_match`](crate::search_and_match) 搜尋並依匹配分數排序。
+    ///
+    /// 部分查詢失敗時仍回傳其他查詢的結果，全部失敗才回傳錯誤。
+    pub async fn search_and_match(
+        &self,
+        request: &SearchRequest,
+    ) -> Result<Vec<MatchResult<OsuBeatmapset>>, CoreError> {
+        search_and_match(request, |query, _| async move { self.search(&query).await }).await
+    }
+}
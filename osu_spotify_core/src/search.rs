@@ -0,0 +1,146 @@
+//! 以多種查詢字串搜尋並配對譜面集，不限定使用哪一種 osu! API 客戶端。
+
+// 標準庫導入
+use std::collections::HashSet;
+use std::future::Future;
+
+// 本地模組導入
+use crate::matching::{
+    rank, score_candidate, Candidate, MatchResult, SearchRequest, CONFIDENT_MATCH_SCORE,
+};
+use crate::text::query_variants;
+
+/// 依序以原始、清理後與（`romanize_kana` 啟用時）羅馬拼音的查詢呼叫 `search`，
+/// 依譜面集 ID 合併結果，找到可信的匹配後就不再繼續。
+///
+/// `search` 收到查詢字串與它是第幾個查詢（從 0 開始）。部分查詢失敗時仍回傳其他查詢的結果，
+/// 全部失敗才回傳最後一個錯誤。回傳的結果未排序，需要排序時使用 [`search_and_match`]。
+pub async fn search_candidates<C, E, F, Fut>(
+    request: &SearchRequest,
+    mut search: F,
+) -> Result<Vec<C>, E>
+where
+    C: Candidate,
+    F: FnMut(String, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<C>, E>>,
+{
+    let mut results: Vec<C> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut last_error = None;
+
+    let queries = query_variants(&request.artist, &request.title, request.romanize_kana);
+    for (index, query) in queries.into_iter().enumerate() {
+        match search(query, index).await {
+            Ok(candidates) => results.extend(
+                candidates
+                    .into_iter()
+                    .filter(|candidate| seen.insert(candidate.id())),
+            ),
+            Err(e) => {
+                last_error = Some(e);
+                continue;
+            }
+        }
+        if results.iter().any(|candidate| {
+            score_candidate(&request.artist, &request.title, candidate) >= CONFIDENT_MATCH_SCORE
+        }) {
+            break;
+        }
+    }
+
+    match last_error {
+        Some(e) if results.is_empty() => Err(e),
+        _ => Ok(results),
+    }
+}
+
+/// [`search_candidates`] 後依匹配分數與版本偏好排序。
+pub async fn search_and_match<C, E, F, Fut>(
+    request: &SearchRequest,
+    search: F,
+) -> Result<Vec<MatchResult<C>>, E>
+where
+    C: Candidate,
+    F: FnMut(String, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<C>, E>>,
+{
+    let candidates = search_candidates(request, search).await?;
+    Ok(rank(request, candidates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestBeatmapset {
+        id: i32,
+        title: &'static str,
+    }
+
+    impl Candidate for TestBeatmapset {
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn name_variants(&self) -> Vec<(String, String)> {
+            vec![("YOASOBI".to_string(), self.title.to_string())]
+        }
+
+        fn length_secs(&self) -> Option<i32> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_after_confident_match_and_deduplicates() {
+        let request = SearchRequest::new("YOASOBI", "夜に駆ける (TV Size)");
+        let mut queries = Vec::new();
+        let results = search_candidates(&request, |query, index| {
+            queries.push((index, query));
+            async move {
+                Ok::<_, ()>(vec![
+                    TestBeatmapset {
+                        id: 1,
+                        title: "夜に駆ける",
+                    },
+                    TestBeatmapset {
+                        id: 1,
+                        title: "夜に駆ける",
+                    },
+                ])
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            queries,
+            vec![(0, "YOASOBI 夜に駆ける (TV Size)".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_error_only_when_every_query_fails() {
+        let request = SearchRequest::new("YOASOBI", "夜に駆ける (TV Size)");
+        let failed = search_candidates(&request, |_, _| async {
+            Err::<Vec<TestBeatmapset>, _>("失敗")
+        })
+        .await;
+        assert_eq!(failed.err(), Some("失敗"));
+
+        let partial = search_and_match(&request, |_, index| async move {
+            if index == 0 {
+                Err("失敗")
+            } else {
+                Ok(vec![TestBeatmapset {
+                    id: 2,
+                    title: "夜に駆ける",
+                }])
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(partial.len(), 1);
+        assert!(partial[0].is_confident());
+    }
+}
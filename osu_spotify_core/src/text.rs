@@ -0,0 +1,295 @@
+//! 名稱正規化、相似度與查詢字串清理。
+
+// 標準庫導入
+use std::collections::HashSet;
+
+// 第三方庫導入
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    // 依序套用的清理規則
+    static ref TITLE_NOISE: Vec<Regex> = [
+        // (feat. X)、[ft. X]、(with X)
+        r"(?i)\s*[\(\[（](feat\.?|ft\.?|featuring|with)\s[^\)\]）]*[\)\]）]",
+        // 結尾的 feat. X
+        r"(?i)\s+(feat\.?|ft\.?|featuring)\s.*$",
+        // - Remastered 2011、- 2011 Remaster、(Remastered Version)
+        r"(?i)\s*-\s*(\d{4}\s+)?(digital\s+)?re-?master(ed)?(\s+\d{4})?(\s+version)?\s*$",
+        r"(?i)\s*[\(\[](\d{4}\s+)?(digital\s+)?re-?master(ed)?(\s+\d{4})?(\s+version)?[\)\]]",
+        // (TV Size)、TV ver.
+        r"(?i)\s*[\(\[]?\s*tv\s*(size|ver\.?|version|edit)\s*[\)\]]?",
+        // - Radio Edit、- Single Version、- From "XXX"
+        r#"(?i)\s*-\s*(radio edit|single version|album version|original mix|from\s+["“].*)\s*$"#,
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).unwrap())
+    .collect();
+}
+
+/// 轉小寫並將標點符號轉為空白，方便比較。
+pub fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn tokens(text: &str) -> HashSet<String> {
+    normalize(text)
+        .split_whitespace()
+        .map(|token| token.to_string())
+        .collect()
+}
+
+/// 以詞彙為單位的 Dice 係數，回傳 0.0 ~ 1.0。
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let a_tokens = tokens(a);
+    let b_tokens = tokens(b);
+    if a_tokens.is_empty() || b_tokens.is_empty() {
+        return 0.0;
+    }
+    let common = a_tokens.intersection(&b_tokens).count();
+    (2 * common) as f32 / (a_tokens.len() + b_tokens.len()) as f32
+}
+
+// 將假名轉為羅馬拼音並移除空白，讓 "Yoru ni Kakeru" 與 "よるにかける" 可以互相比較
+fn transliterate(text: &str) -> String {
+    kana_to_romaji(&normalize(text))
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+/// 比較兩個名稱，相同、包含或假名轉寫後相同時給予高分。
+pub fn title_similarity(a: &str, b: &str) -> f32 {
+    let (a_norm, b_norm) = (normalize(a), normalize(b));
+    if a_norm.is_empty() || b_norm.is_empty() {
+        return 0.0;
+    }
+    if a_norm == b_norm {
+        return 1.0;
+    }
+    // 譜面標題常帶有 (TV Size) 等後綴，包含關係也視為高度相似
+    if a_norm.contains(&b_norm) || b_norm.contains(&a_norm) {
+        return similarity(a, b).max(0.9);
+    }
+    let (a_romaji, b_romaji) = (transliterate(a), transliterate(b));
+    if !a_romaji.is_empty() && a_romaji == b_romaji {
+        return 0.95;
+    }
+    similarity(a, b)
+}
+
+/// 將連續的空白合併為一個。
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 移除 feat.、remaster、TV Size 等常讓 osu! 搜尋失敗的後綴。
+pub fn clean_title(title: &str) -> String {
+    let cleaned = TITLE_NOISE.iter().fold(title.to_string(), |text, regex| {
+        regex.replace_all(&text, "").into_owned()
+    });
+    let cleaned = collapse_whitespace(&cleaned);
+    if cleaned.is_empty() {
+        collapse_whitespace(title)
+    } else {
+        cleaned
+    }
+}
+
+/// 多位歌手時只保留第一位。
+pub fn clean_artist(artist: &str) -> String {
+    let first = artist.split([',', '、', '&']).next().unwrap_or(artist);
+    collapse_whitespace(first)
+}
+
+/// 產生要嘗試的 osu! 查詢字串：原始查詢、清理後的查詢，以及（`romanize_kana` 啟用時）羅馬拼音版本。
+pub fn query_variants(artist: &str, title: &str, romanize_kana: bool) -> Vec<String> {
+    let raw = collapse_whitespace(&format!("{} {}", artist, title));
+    let cleaned_artist = clean_artist(artist);
+    let cleaned_title = clean_title(title);
+    let cleaned = collapse_whitespace(&format!("{} {}", cleaned_artist, cleaned_title));
+
+    let mut variants = vec![raw, cleaned];
+    if romanize_kana && contains_kana(&cleaned_artist) {
+        variants.push(collapse_whitespace(&format!(
+            "{} {}",
+            kana_to_romaji(&cleaned_artist),
+            cleaned_title
+        )));
+    }
+
+    let mut seen = HashSet::new();
+    variants
+        .into_iter()
+        .filter(|variant| !variant.is_empty() && seen.insert(variant.to_lowercase()))
+        .collect()
+}
+
+/// 是否包含平假名或片假名。
+pub fn contains_kana(text: &str) -> bool {
+    text.chars()
+        .any(|c| ('\u{3041}'..='\u{3096}').contains(&c) || ('\u{30A1}'..='\u{30FA}').contains(&c))
+}
+
+fn katakana_to_hiragana(c: char) -> char {
+    if ('\u{30A1}'..='\u{30F6}').contains(&c) {
+        char::from_u32(c as u32 - 0x60).unwrap_or(c)
+    } else {
+        c
+    }
+}
+
+// 平假名與對應的羅馬拼音，依相同順序排列
+const KANA: &str = concat!(
+    "あいうえおかきくけこ",
+    "さしすせそたちつてと",
+    "なにぬねのはひふへほ",
+    "まみむめもやゆよらり",
+    "るれろわをんがぎぐげ",
+    "ござじずぜぞだぢづで",
+    "どばびぶべぼぱぴぷぺ",
+    "ぽぁぃぅぇぉゃゅょゔ",
+);
+const ROMAJI: &str = concat!(
+    "a i u e o ka ki ku ke ko ",
+    "sa shi su se so ta chi tsu te to ",
+    "na ni nu ne no ha hi fu he ho ",
+    "ma mi mu me mo ya yu yo ra ri ",
+    "ru re ro wa o n ga gi gu ge ",
+    "go za ji zu ze zo da ji zu de ",
+    "do ba bi bu be bo pa pi pu pe ",
+    "po a i u e o ya yu yo vu",
+);
+
+fn hiragana_romaji(c: char) -> Option<&'static str> {
+    let index = KANA.chars().position(|kana| kana == c)?;
+    ROMAJI.split_whitespace().nth(index)
+}
+
+// 拗音，例如 きゃ -> kya、しゅ -> shu
+fn youon_romaji(base: char, small: char) -> Option<String> {
+    let vowel = match small {
+        'ゃ' => "a",
+        'ゅ' => "u",
+        'ょ' => "o",
+        _ => return None,
+    };
+    let stem = match hiragana_romaji(base)? {
+        "shi" => "sh".to_string(),
+        "chi" => "ch".to_string(),
+        "ji" => "j".to_string(),
+        romaji if romaji.len() == 2 && romaji.ends_with('i') => format!("{}y", &romaji[..1]),
+        _ => return None,
+    };
+    Some(format!("{}{}", stem, vowel))
+}
+
+/// 將平假名與片假名轉為平文式羅馬拼音，漢字等其他字元保持不變。
+pub fn kana_to_romaji(text: &str) -> String {
+    let chars: Vec<char> = text.chars().map(katakana_to_hiragana).collect();
+    let mut output = String::new();
+    let mut double_next = false;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        // 促音：重複下一個子音
+        if c == 'っ' {
+            double_next = true;
+            index += 1;
+            continue;
+        }
+        // 長音：重複前一個母音
+        if c == 'ー' {
+            if let Some(last) = output.chars().last().filter(|l| "aeiou".contains(*l)) {
+                output.push(last);
+            }
+            index += 1;
+            continue;
+        }
+
+        let next = chars.get(index + 1).copied();
+        let (romaji, consumed) = match next.and_then(|small| youon_romaji(c, small)) {
+            Some(romaji) => (Some(romaji), 2),
+            None => (hiragana_romaji(c).map(str::to_string), 1),
+        };
+        match romaji {
+            Some(romaji) => {
+                if double_next {
+                    if let Some(first) = romaji.chars().next() {
+                        output.push(first);
+                    }
+                }
+                output.push_str(&romaji);
+            }
+            None => output.push(c),
+        }
+        double_next = false;
+        index += consumed;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_lowercases_and_strips_punctuation() {
+        assert_eq!(normalize("  Hello,   World! "), "hello world");
+        assert_eq!(normalize("夜に駆ける (TV Size)"), "夜に駆ける tv size");
+    }
+
+    #[test]
+    fn kana_to_romaji_handles_youon_sokuon_and_long_vowels() {
+        assert_eq!(kana_to_romaji("よるにかける"), "yorunikakeru");
+        assert_eq!(kana_to_romaji("きゃ"), "kya");
+        assert_eq!(kana_to_romaji("がっこう"), "gakkou");
+        assert_eq!(kana_to_romaji("カード"), "kaado");
+        assert_eq!(kana_to_romaji("夜に"), "夜ni");
+    }
+
+    #[test]
+    fn title_similarity_scores() {
+        assert_eq!(title_similarity("夜に駆ける", "夜に駆ける"), 1.0);
+        assert_eq!(title_similarity("Yoru ni Kakeru", "よるにかける"), 0.95);
+        assert!(title_similarity("夜に駆ける (TV Size)", "夜に駆ける") >= 0.9);
+        assert_eq!(title_similarity("", "夜に駆ける"), 0.0);
+    }
+
+    #[test]
+    fn clean_title_removes_noise() {
+        assert_eq!(clean_title("Song (feat. Someone)"), "Song");
+        assert_eq!(clean_title("Song - Remastered 2011"), "Song");
+        assert_eq!(clean_title("Song (TV Size)"), "Song");
+        assert_eq!(clean_title("Song - Radio Edit"), "Song");
+        // 清理後為空時保留原始標題
+        assert_eq!(clean_title("(TV Size)"), "(TV Size)");
+    }
+
+    #[test]
+    fn clean_artist_keeps_first_artist() {
+        assert_eq!(clean_artist("Artist A, Artist B"), "Artist A");
+        assert_eq!(clean_artist("Artist A & Artist B"), "Artist A");
+        assert_eq!(clean_artist("歌手A、歌手B"), "歌手A");
+    }
+
+    #[test]
+    fn query_variants_deduplicates_and_romanizes() {
+        assert_eq!(
+            query_variants("YOASOBI", "夜に駆ける (TV Size)", false),
+            vec!["YOASOBI 夜に駆ける (TV Size)", "YOASOBI 夜に駆ける"]
+        );
+        assert_eq!(query_variants("Artist", "Song", false), vec!["Artist Song"]);
+        assert_eq!(
+            query_variants("ヨアソビ", "Idol", true),
+            vec!["ヨアソビ Idol", "yoasobi Idol"]
+        );
+    }
+}